@@ -4,6 +4,7 @@ use crate::node::SharedNodeManager;
 use crate::state::AppState;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use synapsenet_agent::{ApprovalQueue, ApprovalRequest, ApprovalStatus};
 use tauri::State;
 use tokio::sync::Mutex;
 use uuid::Uuid;
@@ -231,6 +232,46 @@ pub async fn get_today_earnings(
     Ok(state.today_earnings())
 }
 
+// ============================================================================
+// Tool Approval Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn list_approvals(
+    status: Option<String>,
+    approvals: State<'_, Arc<ApprovalQueue>>,
+) -> Result<Vec<ApprovalRequest>, String> {
+    let status = match status {
+        Some(name) => Some(
+            ApprovalStatus::parse(&name).ok_or_else(|| format!("Unknown status: {}", name))?,
+        ),
+        None => None,
+    };
+    approvals.list(status).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn approve_action(
+    approval_id: String,
+    approvals: State<'_, Arc<ApprovalQueue>>,
+) -> Result<ApprovalRequest, String> {
+    let id = Uuid::parse_str(&approval_id).map_err(|_| format!("Invalid approval id: {}", approval_id))?;
+    approvals.approve(&id, "desktop").map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reject_action(
+    approval_id: String,
+    reason: String,
+    approvals: State<'_, Arc<ApprovalQueue>>,
+) -> Result<ApprovalRequest, String> {
+    if reason.trim().is_empty() {
+        return Err("A reason is required".to_string());
+    }
+    let id = Uuid::parse_str(&approval_id).map_err(|_| format!("Invalid approval id: {}", approval_id))?;
+    approvals.reject(&id, "desktop", &reason).map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // Data Export Commands
 // ============================================================================
@@ -11,12 +11,24 @@ use commands::*;
 use events::start_event_emitter;
 use node::create_node_manager;
 use state::AppState;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::Manager;
 use tokio::sync::Mutex;
 
+/// Node data directory: `SYNAPSENET_DATA_DIR`, or `~/.synapsenet`
+fn data_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = std::env::var_os("SYNAPSENET_DATA_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".synapsenet"))
+        .ok_or_else(|| "Set SYNAPSENET_DATA_DIR to the node's data directory".to_string())
+}
+
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
@@ -27,12 +39,20 @@ async fn main() {
     // Create app state
     let app_state = Arc::new(Mutex::new(AppState::new()));
     let node_manager = create_node_manager();
+    // Shared with `syn approvals` and the REST API of a node on the same data dir
+    let data_dir = data_dir()?;
+    std::fs::create_dir_all(&data_dir)?;
+    let store = synapsenet_storage::Store::new(&data_dir.join("synapsenet.db").to_string_lossy())?;
+    let approvals = Arc::new(synapsenet_agent::ApprovalQueue::new(Arc::new(
+        std::sync::Mutex::new(store),
+    )));
 
     let app_state_for_setup = app_state.clone();
     
     tauri::Builder::default()
         .manage(app_state)
         .manage(node_manager)
+        .manage(approvals)
         .invoke_handler(tauri::generate_handler![
             start_node,
             stop_node,
@@ -44,6 +64,9 @@ async fn main() {
             get_balance,
             get_rewards,
             get_today_earnings,
+            list_approvals,
+            approve_action,
+            reject_action,
             export_data
         ])
         .setup(move |app| {
@@ -62,6 +85,7 @@ async fn main() {
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .run(tauri::generate_context!())?;
+
+    Ok(())
 }
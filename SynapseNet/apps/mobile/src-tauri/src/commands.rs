@@ -111,12 +111,13 @@ pub async fn syn_wallet(state: State<'_, MobileAppState>) -> Result<serde_json::
 #[tauri::command]
 pub async fn syn_export(
     path: String,
+    recovery_phrase: String,
     state: State<'_, MobileAppState>,
 ) -> Result<(), String> {
     tracing::info!("Exporting to: {}", path);
     
     let capsule = state.capsule.read().await;
-    capsule.export_encrypted(&path).map_err(|e| e.to_string())?;
+    capsule.export_encrypted(&path, &recovery_phrase).map_err(|e| e.to_string())?;
     
     Ok(())
 }
@@ -0,0 +1,152 @@
+//! PoE batch leaves, Merkle proofs and signed batch bytes
+//!
+//! Shared by the node-side batch aggregator and the on-chain contract so
+//! both hash leaves, walk proofs and sign batches the same way. Trees pair hashes with
+//! SHA-256 level by level; an odd node at the end of a level is paired
+//! with itself.
+
+use sha2::{Digest, Sha256};
+
+pub type Hash = [u8; 32];
+
+/// Fixed-point scale of committed weights (1.0 == `WEIGHT_SCALE`)
+pub const WEIGHT_SCALE: u64 = 1_000_000;
+
+/// Domain separator for batch signatures
+const BATCH_SIGN_DOMAIN: &[u8] = b"synapsenet/poe-batch/v1";
+
+/// Bytes signers sign to approve an epoch root
+///
+/// Binding the chain and epoch keeps a signature from being replayed
+/// for another epoch or on another chain.
+pub fn batch_sign_bytes(chain_id: &str, epoch: u64, root: &[u8], total_weight: u128) -> Vec<u8> {
+    let mut data =
+        Vec::with_capacity(BATCH_SIGN_DOMAIN.len() + chain_id.len() + 25 + root.len());
+    data.extend_from_slice(BATCH_SIGN_DOMAIN);
+    data.extend_from_slice(chain_id.as_bytes());
+    data.push(0);
+    data.extend_from_slice(&epoch.to_be_bytes());
+    data.extend_from_slice(root);
+    data.extend_from_slice(&total_weight.to_be_bytes());
+    data
+}
+
+/// Hash of one batch item
+///
+/// `scores` is the little-endian encoding of the novelty, coherence and
+/// reuse scores. The contract never interprets it, it only needs the bytes
+/// to rebuild the leaf.
+pub fn leaf_hash(node: &str, goal: &[u8; 16], scores: &[u8; 12], weight: u64) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(node.as_bytes());
+    hasher.update(goal);
+    hasher.update(scores);
+    hasher.update(weight.to_le_bytes());
+    hasher.finalize().into()
+}
+
+pub fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn next_level(hashes: &[Hash]) -> Vec<Hash> {
+    hashes
+        .chunks(2)
+        .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
+
+/// Root over `leaves`, all zeroes for an empty tree
+pub fn merkle_root(leaves: &[Hash]) -> Hash {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+
+    let mut hashes = leaves.to_vec();
+    while hashes.len() > 1 {
+        hashes = next_level(&hashes);
+    }
+    hashes[0]
+}
+
+/// Sibling hashes from leaf `index` up to the root
+pub fn merkle_proof(leaves: &[Hash], index: usize) -> Option<Vec<Hash>> {
+    if index >= leaves.len() {
+        return None;
+    }
+
+    let mut proof = Vec::new();
+    let mut hashes = leaves.to_vec();
+    let mut index = index;
+    while hashes.len() > 1 {
+        let sibling = if index.is_multiple_of(2) {
+            hashes.get(index + 1).unwrap_or(&hashes[index])
+        } else {
+            &hashes[index - 1]
+        };
+        proof.push(*sibling);
+        hashes = next_level(&hashes);
+        index /= 2;
+    }
+    Some(proof)
+}
+
+/// Check that `leaf` sits at `index` of the tree with `root`
+pub fn verify_proof(leaf: &Hash, index: u64, proof: &[Hash], root: &Hash) -> bool {
+    // Any index bits left over would mean the proof is too short
+    if proof.len() < 64 && index >> proof.len() != 0 {
+        return false;
+    }
+
+    let mut hash = *leaf;
+    let mut index = index;
+    for sibling in proof {
+        hash = if index.is_multiple_of(2) {
+            hash_pair(&hash, sibling)
+        } else {
+            hash_pair(sibling, &hash)
+        };
+        index /= 2;
+    }
+    hash == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: usize) -> Vec<Hash> {
+        (0..n)
+            .map(|i| leaf_hash(&format!("node{}", i), &[i as u8; 16], &[0u8; 12], 1))
+            .collect()
+    }
+
+    #[test]
+    fn test_proofs_for_every_leaf() {
+        for n in 1..=9 {
+            let leaves = leaves(n);
+            let root = merkle_root(&leaves);
+
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = merkle_proof(&leaves, i).unwrap();
+                assert!(verify_proof(leaf, i as u64, &proof, &root), "n={} i={}", n, i);
+            }
+            assert!(merkle_proof(&leaves, n).is_none());
+        }
+    }
+
+    #[test]
+    fn test_tampered_leaf_rejected() {
+        let leaves = leaves(5);
+        let root = merkle_root(&leaves);
+        let proof = merkle_proof(&leaves, 2).unwrap();
+
+        let forged = leaf_hash("node2", &[2u8; 16], &[0u8; 12], 2);
+        assert!(!verify_proof(&forged, 2, &proof, &root));
+        assert!(!verify_proof(&leaves[2], 3, &proof, &root));
+        assert!(!verify_proof(&leaves[2], 2, &proof[1..], &root));
+    }
+}
@@ -1,33 +1,55 @@
 //! PoE contract implementation
 
+use std::collections::HashSet;
+
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult, Storage, Uint128, Uint64, WasmMsg,
 };
+use cw20::Cw20ExecuteMsg;
+use sha2::{Digest, Sha256};
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, EpochAcc, Stats, ACCRUAL, CONFIG, EPOCHS, STATS};
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SignerInfo, SignersResponse};
+use crate::state::{
+    Config, EpochAcc, SigScheme, Signer, Stats, ACCRUAL, CLAIMS, CONFIG, EPOCHS, SIGNERS, STATS,
+};
+
+pub use poe_merkle::batch_sign_bytes;
 
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
+
     let config = Config {
+        admin,
+        token: deps.api.addr_validate(&msg.token)?,
         epoch_secs: msg.epoch_secs,
         min_signers: msg.min_signers,
         reward_per_epoch: msg.reward_per_epoch,
     };
-    
+
+    for signer in &msg.signers {
+        save_signer(deps.storage, signer)?;
+    }
+    check_threshold(deps.storage, config.min_signers)?;
+
     CONFIG.save(deps.storage, &config)?;
     STATS.save(deps.storage, &Stats::default())?;
-    
+
     Ok(Response::new()
         .add_attribute("action", "instantiate")
         .add_attribute("epoch_secs", msg.epoch_secs.to_string())
-        .add_attribute("min_signers", msg.min_signers.to_string()))
+        .add_attribute("min_signers", msg.min_signers.to_string())
+        .add_attribute("signers", msg.signers.len().to_string()))
 }
 
 #[entry_point]
@@ -41,27 +63,39 @@ pub fn execute(
         ExecuteMsg::SubmitBatch {
             epoch,
             root,
-            participants,
+            total_weight,
             signers,
             sigs,
-        } => execute_submit_batch(deps, env, epoch, root, participants, signers, sigs),
-        ExecuteMsg::ClaimReward { node } => execute_claim_reward(deps, info, node),
+        } => execute_submit_batch(deps, env, epoch, root, total_weight, signers, sigs),
+        ExecuteMsg::ClaimReward {
+            epoch,
+            node,
+            goal,
+            scores,
+            weight,
+            index,
+            proof,
+        } => execute_claim_reward(deps, epoch, node, goal, scores, weight, index, proof),
         ExecuteMsg::UpdateConfig {
             epoch_secs,
             min_signers,
             reward_per_epoch,
         } => execute_update_config(deps, info, epoch_secs, min_signers, reward_per_epoch),
+        ExecuteMsg::UpdateSigners { add, remove } => {
+            execute_update_signers(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdateAdmin { admin } => execute_update_admin(deps, info, admin),
     }
 }
 
 fn execute_submit_batch(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     epoch: u64,
     root: Binary,
-    participants: Vec<String>,
+    total_weight: Uint128,
     signers: Vec<String>,
-    _sigs: Vec<Binary>,
+    sigs: Vec<Binary>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     
@@ -78,74 +112,191 @@ fn execute_submit_batch(
         });
     }
     
-    // TODO: Verify signatures
-    
-    // Store epoch
+    if total_weight.is_zero() {
+        return Err(ContractError::ZeroWeight {});
+    }
+    if root.len() != 32 {
+        return Err(ContractError::InvalidLeaf {
+            reason: "root must be 32 bytes".to_string(),
+        });
+    }
+
+    verify_batch_signatures(deps.as_ref(), &env, epoch, &root, total_weight, &signers, &sigs)?;
+
+    // Rewards are paid out as leaves are claimed
     let epoch_acc = EpochAcc {
         root: root.to_vec(),
-        total_weight: cosmwasm_std::Decimal::one(),
+        total_weight,
+        reward: config.reward_per_epoch,
+        claimed: Uint128::zero(),
         posted: true,
     };
     EPOCHS.save(deps.storage, epoch, &epoch_acc)?;
     
-    // Distribute rewards
-    let reward_per_participant = config.reward_per_epoch.checked_div(Uint128::from(participants.len() as u128))?;
-    
-    for participant in &participants {
-        let current = ACCRUAL.may_load(deps.storage, participant)?.unwrap_or_default();
-        ACCRUAL.save(deps.storage, participant, &(current + reward_per_participant))?;
-    }
-    
     // Update stats
     let mut stats = STATS.load(deps.storage)?;
     stats.total_epochs += 1;
-    stats.total_rewards += config.reward_per_epoch;
-    stats.total_participants += participants.len() as u64;
     STATS.save(deps.storage, &stats)?;
     
     Ok(Response::new()
         .add_attribute("action", "submit_batch")
         .add_attribute("epoch", epoch.to_string())
-        .add_attribute("participants", participants.len().to_string()))
+        .add_attribute("total_weight", total_weight.to_string()))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_claim_reward(
     deps: DepsMut,
-    _info: MessageInfo,
+    epoch: u64,
     node: String,
+    goal: Binary,
+    scores: Binary,
+    weight: Uint64,
+    index: u64,
+    proof: Vec<Binary>,
 ) -> Result<Response, ContractError> {
-    let amount = ACCRUAL.may_load(deps.storage, &node)?.unwrap_or_default();
-    
+    let config = CONFIG.load(deps.storage)?;
+    let mut epoch_acc = EPOCHS
+        .may_load(deps.storage, epoch)?
+        .ok_or(ContractError::EpochNotFound { epoch })?;
+    let recipient = deps.api.addr_validate(&node)?;
+
+    let goal: [u8; 16] = goal.as_slice().try_into().map_err(|_| ContractError::InvalidLeaf {
+        reason: "goal must be 16 bytes".to_string(),
+    })?;
+    let scores: [u8; 12] = scores.as_slice().try_into().map_err(|_| ContractError::InvalidLeaf {
+        reason: "scores must be 12 bytes".to_string(),
+    })?;
+    let proof = proof
+        .iter()
+        .map(|hash| hash.as_slice().try_into())
+        .collect::<Result<Vec<poe_merkle::Hash>, _>>()
+        .map_err(|_| ContractError::InvalidLeaf {
+            reason: "proof hashes must be 32 bytes".to_string(),
+        })?;
+    let root: poe_merkle::Hash = epoch_acc
+        .root
+        .as_slice()
+        .try_into()
+        .map_err(|_| ContractError::InvalidProof { epoch })?;
+
+    let leaf = poe_merkle::leaf_hash(&node, &goal, &scores, weight.u64());
+    if !poe_merkle::verify_proof(&leaf, index, &proof, &root) {
+        return Err(ContractError::InvalidProof { epoch });
+    }
+    if CLAIMS.has(deps.storage, (epoch, &leaf)) {
+        return Err(ContractError::AlreadyClaimed { epoch });
+    }
+
+    let amount = epoch_acc
+        .reward
+        .multiply_ratio(weight.u64(), epoch_acc.total_weight);
     if amount.is_zero() {
         return Err(ContractError::NoRewardsToClaim {});
     }
-    
-    // Clear accrual
-    ACCRUAL.save(deps.storage, &node, &Uint128::zero())?;
-    
-    // TODO: Mint/transfer NGT tokens
-    
+    // A root committing to more weight than its total can't overdraw the epoch
+    epoch_acc.claimed += amount;
+    if epoch_acc.claimed > epoch_acc.reward {
+        return Err(ContractError::EpochRewardExceeded { epoch });
+    }
+
+    CLAIMS.save(deps.storage, (epoch, &leaf), &true)?;
+    EPOCHS.save(deps.storage, epoch, &epoch_acc)?;
+    let paid = ACCRUAL.may_load(deps.storage, &node)?.unwrap_or_default();
+    ACCRUAL.save(deps.storage, &node, &(paid + amount))?;
+
+    let mut stats = STATS.load(deps.storage)?;
+    stats.total_rewards += amount;
+    stats.total_participants += 1;
+    STATS.save(deps.storage, &stats)?;
+
+    let mint = WasmMsg::Execute {
+        contract_addr: config.token.to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Mint {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    };
+
     Ok(Response::new()
+        .add_message(mint)
         .add_attribute("action", "claim_reward")
+        .add_attribute("epoch", epoch.to_string())
         .add_attribute("node", node)
         .add_attribute("amount", amount.to_string()))
 }
 
+/// Check that every signer is registered, appears once and signed the root
+fn verify_batch_signatures(
+    deps: Deps,
+    env: &Env,
+    epoch: u64,
+    root: &Binary,
+    total_weight: Uint128,
+    signers: &[String],
+    sigs: &[Binary],
+) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if signers.len() != sigs.len() {
+        return Err(ContractError::SignatureCountMismatch {
+            signers: signers.len() as u32,
+            sigs: sigs.len() as u32,
+        });
+    }
+
+    let message = batch_sign_bytes(&env.block.chain_id, epoch, root, total_weight.u128());
+    let message_hash = Sha256::digest(&message);
+    let mut seen = HashSet::new();
+
+    for (id, sig) in signers.iter().zip(sigs) {
+        if !seen.insert(id.as_str()) {
+            return Err(ContractError::DuplicateSigner { signer: id.clone() });
+        }
+
+        let signer = SIGNERS
+            .may_load(deps.storage, id)?
+            .ok_or_else(|| ContractError::UnknownSigner { signer: id.clone() })?;
+
+        let valid = match signer.scheme {
+            SigScheme::Ed25519 => deps.api.ed25519_verify(&message, sig, &signer.pubkey),
+            SigScheme::Secp256k1 => {
+                deps.api
+                    .secp256k1_verify(&message_hash, sig, &signer.pubkey)
+            }
+        }
+        .unwrap_or(false);
+
+        if !valid {
+            return Err(ContractError::InvalidSignature { signer: id.clone() });
+        }
+    }
+
+    if seen.len() < config.min_signers as usize {
+        return Err(ContractError::InsufficientSigners {
+            required: config.min_signers,
+            provided: seen.len() as u32,
+        });
+    }
+
+    Ok(())
+}
+
 fn execute_update_config(
     deps: DepsMut,
-    _info: MessageInfo,
+    info: MessageInfo,
     epoch_secs: Option<u64>,
     min_signers: Option<u32>,
     reward_per_epoch: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    // TODO: Add admin check
-    
-    let mut config = CONFIG.load(deps.storage)?;
-    
+    let mut config = load_config_as_admin(deps.storage, &info)?;
+
     if let Some(secs) = epoch_secs {
         config.epoch_secs = secs;
     }
     if let Some(signers) = min_signers {
+        check_threshold(deps.storage, signers)?;
         config.min_signers = signers;
     }
     if let Some(reward) = reward_per_epoch {
@@ -157,6 +308,84 @@ fn execute_update_config(
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
+fn execute_update_signers(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<SignerInfo>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = load_config_as_admin(deps.storage, &info)?;
+
+    for id in &remove {
+        SIGNERS.remove(deps.storage, id);
+    }
+    for signer in &add {
+        save_signer(deps.storage, signer)?;
+    }
+    check_threshold(deps.storage, config.min_signers)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_signers")
+        .add_attribute("added", add.len().to_string())
+        .add_attribute("removed", remove.len().to_string()))
+}
+
+fn execute_update_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: String,
+) -> Result<Response, ContractError> {
+    let mut config = load_config_as_admin(deps.storage, &info)?;
+    config.admin = deps.api.addr_validate(&admin)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_admin")
+        .add_attribute("admin", admin))
+}
+
+fn load_config_as_admin(storage: &dyn Storage, info: &MessageInfo) -> Result<Config, ContractError> {
+    let config = CONFIG.load(storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(config)
+}
+
+fn save_signer(storage: &mut dyn Storage, signer: &SignerInfo) -> Result<(), ContractError> {
+    let valid_len = match signer.scheme {
+        SigScheme::Ed25519 => signer.pubkey.len() == 32,
+        SigScheme::Secp256k1 => matches!(signer.pubkey.len(), 33 | 65),
+    };
+    if !valid_len {
+        return Err(ContractError::InvalidPublicKey {
+            signer: signer.id.clone(),
+        });
+    }
+
+    SIGNERS.save(
+        storage,
+        &signer.id,
+        &Signer {
+            pubkey: signer.pubkey.clone(),
+            scheme: signer.scheme,
+        },
+    )?;
+    Ok(())
+}
+
+/// A quorum must exist and be reachable by the registered signers
+fn check_threshold(storage: &dyn Storage, min_signers: u32) -> Result<(), ContractError> {
+    let signers = SIGNERS.keys(storage, None, None, Order::Ascending).count() as u32;
+    if min_signers == 0 || min_signers > signers {
+        return Err(ContractError::InvalidThreshold {
+            min_signers,
+            signers,
+        });
+    }
+    Ok(())
+}
+
 #[entry_point]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -164,6 +393,10 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetEpoch { epoch } => to_json_binary(&query_epoch(deps, epoch)?),
         QueryMsg::GetAccrual { node } => to_json_binary(&query_accrual(deps, node)?),
         QueryMsg::GetStats {} => to_json_binary(&query_stats(deps)?),
+        QueryMsg::GetSigners {} => to_json_binary(&query_signers(deps)?),
+        QueryMsg::IsClaimed { epoch, leaf } => {
+            to_json_binary(&CLAIMS.has(deps.storage, (epoch, leaf.as_slice())))
+        }
     }
 }
 
@@ -182,3 +415,18 @@ fn query_accrual(deps: Deps, node: String) -> StdResult<Uint128> {
 fn query_stats(deps: Deps) -> StdResult<Stats> {
     STATS.load(deps.storage)
 }
+
+fn query_signers(deps: Deps) -> StdResult<SignersResponse> {
+    let signers = SIGNERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (id, signer) = item?;
+            Ok(SignerInfo {
+                id,
+                pubkey: signer.pubkey,
+                scheme: signer.scheme,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(SignersResponse { signers })
+}
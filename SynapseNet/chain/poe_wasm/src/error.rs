@@ -20,6 +20,39 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
-    #[error("Invalid signature")]
-    InvalidSignature {},
+    #[error("Invalid signature from signer {signer}")]
+    InvalidSignature { signer: String },
+
+    #[error("Unknown signer {signer}")]
+    UnknownSigner { signer: String },
+
+    #[error("Duplicate signer {signer}")]
+    DuplicateSigner { signer: String },
+
+    #[error("Got {signers} signers but {sigs} signatures")]
+    SignatureCountMismatch { signers: u32, sigs: u32 },
+
+    #[error("Invalid public key for signer {signer}")]
+    InvalidPublicKey { signer: String },
+
+    #[error("min_signers {min_signers} must be between 1 and the signer count {signers}")]
+    InvalidThreshold { min_signers: u32, signers: u32 },
+
+    #[error("Batch total weight must be positive")]
+    ZeroWeight {},
+
+    #[error("Epoch {epoch} not found")]
+    EpochNotFound { epoch: u64 },
+
+    #[error("Invalid leaf: {reason}")]
+    InvalidLeaf { reason: String },
+
+    #[error("Merkle proof does not match epoch {epoch} root")]
+    InvalidProof { epoch: u64 },
+
+    #[error("Leaf already claimed for epoch {epoch}")]
+    AlreadyClaimed { epoch: u64 },
+
+    #[error("Claim exceeds the reward of epoch {epoch}")]
+    EpochRewardExceeded { epoch: u64 },
 }
@@ -1,32 +1,73 @@
 //! PoE contract messages
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Uint128};
+use cosmwasm_std::{Binary, Uint128, Uint64};
+
+use crate::state::SigScheme;
 
 #[cw_serde]
 pub struct InstantiateMsg {
+    /// Defaults to the instantiating address
+    pub admin: Option<String>,
+    /// cw20 token contract; this contract must be set as its minter
+    pub token: String,
     pub epoch_secs: u64,
     pub min_signers: u32,
     pub reward_per_epoch: Uint128,
+    pub signers: Vec<SignerInfo>,
+}
+
+/// Batch signer registration
+#[cw_serde]
+pub struct SignerInfo {
+    pub id: String,
+    pub pubkey: Binary,
+    pub scheme: SigScheme,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
+    /// Post an epoch root. `sigs[i]` is the signature of registered signer
+    /// `signers[i]` over `batch_sign_bytes(chain_id, epoch, root, total_weight)`.
     SubmitBatch {
         epoch: u64,
         root: Binary,
-        participants: Vec<String>,
+        /// Sum of the leaf weights under `root`
+        total_weight: Uint128,
         signers: Vec<String>,
         sigs: Vec<Binary>,
     },
+    /// Claim the share of one batch leaf. Anyone may relay a claim, the
+    /// reward is always minted to `node`.
     ClaimReward {
+        epoch: u64,
+        /// Payout address committed in the leaf
         node: String,
+        /// 16-byte goal id
+        goal: Binary,
+        /// 12-byte score encoding
+        scores: Binary,
+        /// Committed weight in `poe_merkle::WEIGHT_SCALE` units
+        weight: Uint64,
+        /// Leaf position in the batch
+        index: u64,
+        /// Sibling hashes from the leaf up to the root
+        proof: Vec<Binary>,
     },
     UpdateConfig {
         epoch_secs: Option<u64>,
         min_signers: Option<u32>,
         reward_per_epoch: Option<Uint128>,
     },
+    /// Rotate the signer set (admin only)
+    UpdateSigners {
+        add: Vec<SignerInfo>,
+        remove: Vec<String>,
+    },
+    /// Hand over admin rights (admin only)
+    UpdateAdmin {
+        admin: String,
+    },
 }
 
 #[cw_serde]
@@ -38,15 +79,24 @@ pub enum QueryMsg {
     #[returns(EpochResponse)]
     GetEpoch { epoch: u64 },
     
+    /// Total reward paid to a node
     #[returns(AccrualResponse)]
     GetAccrual { node: String },
     
     #[returns(StatsResponse)]
     GetStats {},
+
+    #[returns(SignersResponse)]
+    GetSigners {},
+
+    #[returns(bool)]
+    IsClaimed { epoch: u64, leaf: Binary },
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
+    pub admin: String,
+    pub token: String,
     pub epoch_secs: u64,
     pub min_signers: u32,
     pub reward_per_epoch: Uint128,
@@ -72,3 +122,8 @@ pub struct StatsResponse {
     pub total_rewards: Uint128,
     pub total_participants: u64,
 }
+
+#[cw_serde]
+pub struct SignersResponse {
+    pub signers: Vec<SignerInfo>,
+}
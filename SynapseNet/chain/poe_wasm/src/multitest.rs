@@ -0,0 +1,473 @@
+//! Contract tests against cw-multi-test
+
+use cosmwasm_std::{Addr, Binary, Uint128, Uint64};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use ed25519_dalek::{Signer as _, SigningKey};
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use sha2::{Digest, Sha256};
+
+use crate::contract::{batch_sign_bytes, execute, instantiate, query};
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, SignerInfo, SignersResponse, StatsResponse,
+};
+use crate::state::SigScheme;
+use crate::ContractError;
+
+const ADMIN: &str = "admin";
+const ROOT: &[u8] = &[0xab; 32];
+const TOTAL_WEIGHT: u128 = 2_000_000;
+
+fn ed25519_key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn ed25519_signer(id: &str, key: &SigningKey) -> SignerInfo {
+    SignerInfo {
+        id: id.to_string(),
+        pubkey: Binary::from(key.verifying_key().to_bytes().to_vec()),
+        scheme: SigScheme::Ed25519,
+    }
+}
+
+fn instantiate_token(app: &mut App) -> Addr {
+    let code_id = app.store_code(Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    )));
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(ADMIN),
+        &cw20_base::msg::InstantiateMsg {
+            name: "Neural Grain Token".to_string(),
+            symbol: "NGT".to_string(),
+            decimals: 6,
+            initial_balances: vec![],
+            mint: Some(MinterResponse {
+                minter: ADMIN.to_string(),
+                cap: None,
+            }),
+            marketing: None,
+        },
+        &[],
+        "ngt",
+        None,
+    )
+    .unwrap()
+}
+
+/// Contract with a cw20 token it is allowed to mint
+fn setup_with_token(signers: Vec<SignerInfo>, min_signers: u32) -> (App, Addr, Addr) {
+    let mut app = App::default();
+    let token = instantiate_token(&mut app);
+    let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    let contract = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(ADMIN),
+            &InstantiateMsg {
+                admin: None,
+                token: token.to_string(),
+                epoch_secs: 3600,
+                min_signers,
+                reward_per_epoch: Uint128::new(1000),
+                signers,
+            },
+            &[],
+            "poe",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        token.clone(),
+        &Cw20ExecuteMsg::UpdateMinter {
+            new_minter: Some(contract.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+    (app, contract, token)
+}
+
+fn setup(signers: Vec<SignerInfo>, min_signers: u32) -> (App, Addr) {
+    let (app, contract, _) = setup_with_token(signers, min_signers);
+    (app, contract)
+}
+
+fn sign_root(app: &App, key: &SigningKey, epoch: u64, root: &[u8], total_weight: u128) -> Binary {
+    let message = batch_sign_bytes(&app.block_info().chain_id, epoch, root, total_weight);
+    Binary::from(key.sign(&message).to_bytes().to_vec())
+}
+
+fn sign(app: &App, key: &SigningKey, epoch: u64) -> Binary {
+    sign_root(app, key, epoch, ROOT, TOTAL_WEIGHT)
+}
+
+fn submit_root(
+    app: &mut App,
+    contract: &Addr,
+    epoch: u64,
+    root: &[u8],
+    total_weight: u128,
+    signers: &[&str],
+    sigs: Vec<Binary>,
+) -> Result<(), ContractError> {
+    app.execute_contract(
+        Addr::unchecked("relayer"),
+        contract.clone(),
+        &ExecuteMsg::SubmitBatch {
+            epoch,
+            root: Binary::from(root),
+            total_weight: Uint128::new(total_weight),
+            signers: signers.iter().map(|s| s.to_string()).collect(),
+            sigs,
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|e| e.downcast().unwrap())
+}
+
+fn submit(
+    app: &mut App,
+    contract: &Addr,
+    epoch: u64,
+    signers: &[&str],
+    sigs: Vec<Binary>,
+) -> Result<(), ContractError> {
+    submit_root(app, contract, epoch, ROOT, TOTAL_WEIGHT, signers, sigs)
+}
+
+/// Batch item as committed by the node aggregator
+struct Item {
+    node: &'static str,
+    goal: [u8; 16],
+    weight: u64,
+}
+
+impl Item {
+    fn leaf(&self) -> poe_merkle::Hash {
+        poe_merkle::leaf_hash(self.node, &self.goal, &[0u8; 12], self.weight)
+    }
+}
+
+fn claim_msg(epoch: u64, items: &[Item], index: usize) -> ExecuteMsg {
+    let leaves: Vec<_> = items.iter().map(Item::leaf).collect();
+    let item = &items[index];
+    ExecuteMsg::ClaimReward {
+        epoch,
+        node: item.node.to_string(),
+        goal: Binary::from(item.goal.to_vec()),
+        scores: Binary::from(vec![0u8; 12]),
+        weight: Uint64::new(item.weight),
+        index: index as u64,
+        proof: poe_merkle::merkle_proof(&leaves, index)
+            .unwrap()
+            .into_iter()
+            .map(|hash| Binary::from(hash.to_vec()))
+            .collect(),
+    }
+}
+
+fn claim(app: &mut App, contract: &Addr, msg: &ExecuteMsg) -> Result<(), ContractError> {
+    app.execute_contract(Addr::unchecked("relayer"), contract.clone(), msg, &[])
+        .map(|_| ())
+        .map_err(|e| e.downcast().unwrap())
+}
+
+fn balance(app: &App, token: &Addr, address: &str) -> u128 {
+    let response: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    response.balance.u128()
+}
+
+#[test]
+fn test_submit_batch_with_valid_signatures() {
+    let (k1, k2) = (ed25519_key(1), ed25519_key(2));
+    let (mut app, contract) = setup(vec![ed25519_signer("s1", &k1), ed25519_signer("s2", &k2)], 2);
+
+    let sigs = vec![sign(&app, &k1, 1), sign(&app, &k2, 1)];
+    submit(&mut app, &contract, 1, &["s1", "s2"], sigs).unwrap();
+
+    let stats: StatsResponse = app
+        .wrap()
+        .query_wasm_smart(&contract, &QueryMsg::GetStats {})
+        .unwrap();
+    assert_eq!(stats.total_epochs, 1);
+    assert_eq!(stats.total_participants, 0);
+}
+
+#[test]
+fn test_weighted_claims_mint_tokens() {
+    let key = ed25519_key(1);
+    let (mut app, contract, token) = setup_with_token(vec![ed25519_signer("s1", &key)], 1);
+
+    let items = [
+        Item { node: "node1", goal: [1; 16], weight: 3_000_000 },
+        Item { node: "node2", goal: [2; 16], weight: 500_000 },
+        Item { node: "node3", goal: [3; 16], weight: 500_000 },
+    ];
+    let leaves: Vec<_> = items.iter().map(Item::leaf).collect();
+    let root = poe_merkle::merkle_root(&leaves);
+    let total = 4_000_000;
+
+    let sigs = vec![sign_root(&app, &key, 1, &root, total)];
+    submit_root(&mut app, &contract, 1, &root, total, &["s1"], sigs).unwrap();
+
+    for index in 0..items.len() {
+        claim(&mut app, &contract, &claim_msg(1, &items, index)).unwrap();
+    }
+    assert_eq!(balance(&app, &token, "node1"), 750);
+    assert_eq!(balance(&app, &token, "node2"), 125);
+    assert_eq!(balance(&app, &token, "node3"), 125);
+
+    let stats: StatsResponse = app
+        .wrap()
+        .query_wasm_smart(&contract, &QueryMsg::GetStats {})
+        .unwrap();
+    assert_eq!(stats.total_rewards, Uint128::new(1000));
+    assert_eq!(stats.total_participants, 3);
+
+    let claimed: bool = app
+        .wrap()
+        .query_wasm_smart(
+            &contract,
+            &QueryMsg::IsClaimed {
+                epoch: 1,
+                leaf: Binary::from(leaves[0].to_vec()),
+            },
+        )
+        .unwrap();
+    assert!(claimed);
+}
+
+#[test]
+fn test_invalid_and_repeated_claims_rejected() {
+    let key = ed25519_key(1);
+    let (mut app, contract, token) = setup_with_token(vec![ed25519_signer("s1", &key)], 1);
+
+    let items = [
+        Item { node: "node1", goal: [1; 16], weight: 1_000_000 },
+        Item { node: "node2", goal: [2; 16], weight: 1_000_000 },
+    ];
+    let leaves: Vec<_> = items.iter().map(Item::leaf).collect();
+    let root = poe_merkle::merkle_root(&leaves);
+
+    let sigs = vec![sign_root(&app, &key, 1, &root, TOTAL_WEIGHT)];
+    submit_root(&mut app, &contract, 1, &root, TOTAL_WEIGHT, &["s1"], sigs).unwrap();
+
+    let err = claim(&mut app, &contract, &claim_msg(2, &items, 0)).unwrap_err();
+    assert!(matches!(err, ContractError::EpochNotFound { epoch: 2 }));
+
+    // Inflating the weight breaks the proof
+    let mut inflated = claim_msg(1, &items, 0);
+    if let ExecuteMsg::ClaimReward { weight, .. } = &mut inflated {
+        *weight = Uint64::new(2_000_000);
+    }
+    let err = claim(&mut app, &contract, &inflated).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidProof { epoch: 1 }));
+
+    // The payout address is part of the leaf
+    let mut redirected = claim_msg(1, &items, 0);
+    if let ExecuteMsg::ClaimReward { node, .. } = &mut redirected {
+        *node = "mallory".to_string();
+    }
+    assert!(claim(&mut app, &contract, &redirected).is_err());
+
+    claim(&mut app, &contract, &claim_msg(1, &items, 0)).unwrap();
+    let err = claim(&mut app, &contract, &claim_msg(1, &items, 0)).unwrap_err();
+    assert!(matches!(err, ContractError::AlreadyClaimed { epoch: 1 }));
+    assert_eq!(balance(&app, &token, "node1"), 500);
+    assert_eq!(balance(&app, &token, "mallory"), 0);
+}
+
+#[test]
+fn test_forged_and_replayed_signatures_rejected() {
+    let (k1, k2) = (ed25519_key(1), ed25519_key(2));
+    let (mut app, contract) = setup(vec![ed25519_signer("s1", &k1), ed25519_signer("s2", &k2)], 2);
+
+    // s2's slot signed by a key that isn't registered
+    let forged = vec![sign(&app, &k1, 1), sign(&app, &ed25519_key(9), 1)];
+    let err = submit(&mut app, &contract, 1, &["s1", "s2"], forged).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSignature { signer } if signer == "s2"));
+
+    // Signatures for another epoch don't carry over
+    let replayed = vec![sign(&app, &k1, 2), sign(&app, &k2, 2)];
+    let err = submit(&mut app, &contract, 1, &["s1", "s2"], replayed).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSignature { .. }));
+}
+
+#[test]
+fn test_duplicate_and_unknown_signers_rejected() {
+    let (k1, k2) = (ed25519_key(1), ed25519_key(2));
+    let (mut app, contract) = setup(vec![ed25519_signer("s1", &k1), ed25519_signer("s2", &k2)], 2);
+
+    let sig = sign(&app, &k1, 1);
+    let err = submit(&mut app, &contract, 1, &["s1", "s1"], vec![sig.clone(), sig.clone()])
+        .unwrap_err();
+    assert!(matches!(err, ContractError::DuplicateSigner { signer } if signer == "s1"));
+
+    let err = submit(&mut app, &contract, 1, &["s1", "s3"], vec![sig.clone(), sig.clone()])
+        .unwrap_err();
+    assert!(matches!(err, ContractError::UnknownSigner { signer } if signer == "s3"));
+
+    let err = submit(&mut app, &contract, 1, &["s1", "s2"], vec![sig]).unwrap_err();
+    assert!(matches!(err, ContractError::SignatureCountMismatch { signers: 2, sigs: 1 }));
+}
+
+#[test]
+fn test_admin_only_updates() {
+    let k1 = ed25519_key(1);
+    let (mut app, contract) = setup(vec![ed25519_signer("s1", &k1)], 1);
+
+    let update = ExecuteMsg::UpdateConfig {
+        epoch_secs: None,
+        min_signers: Some(1),
+        reward_per_epoch: None,
+    };
+    let err: ContractError = app
+        .execute_contract(Addr::unchecked("mallory"), contract.clone(), &update, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    // Threshold can't exceed the signer set
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(ADMIN),
+            contract.clone(),
+            &ExecuteMsg::UpdateConfig {
+                epoch_secs: None,
+                min_signers: Some(2),
+                reward_per_epoch: None,
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::InvalidThreshold { min_signers: 2, signers: 1 }));
+
+    let rotate = ExecuteMsg::UpdateSigners {
+        add: vec![ed25519_signer("s2", &ed25519_key(2))],
+        remove: vec![],
+    };
+    assert!(app
+        .execute_contract(Addr::unchecked("mallory"), contract.clone(), &rotate, &[])
+        .is_err());
+
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        contract.clone(),
+        &ExecuteMsg::UpdateAdmin {
+            admin: "newadmin".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    assert!(app
+        .execute_contract(Addr::unchecked(ADMIN), contract.clone(), &update, &[])
+        .is_err());
+    app.execute_contract(Addr::unchecked("newadmin"), contract, &update, &[])
+        .unwrap();
+}
+
+#[test]
+fn test_signer_rotation() {
+    let (old, new) = (ed25519_key(1), ed25519_key(2));
+    let (mut app, contract) = setup(vec![ed25519_signer("s1", &old)], 1);
+
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        contract.clone(),
+        &ExecuteMsg::UpdateSigners {
+            add: vec![ed25519_signer("s1", &new)],
+            remove: vec![],
+        },
+        &[],
+    )
+    .unwrap();
+
+    let signers: SignersResponse = app
+        .wrap()
+        .query_wasm_smart(&contract, &QueryMsg::GetSigners {})
+        .unwrap();
+    assert_eq!(signers.signers, vec![ed25519_signer("s1", &new)]);
+
+    let stale = vec![sign(&app, &old, 1)];
+    assert!(submit(&mut app, &contract, 1, &["s1"], stale).is_err());
+    let fresh = vec![sign(&app, &new, 1)];
+    submit(&mut app, &contract, 1, &["s1"], fresh).unwrap();
+
+    // Removing the last signer would leave the threshold unreachable
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(ADMIN),
+            contract,
+            &ExecuteMsg::UpdateSigners {
+                add: vec![],
+                remove: vec!["s1".to_string()],
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::InvalidThreshold { .. }));
+}
+
+#[test]
+fn test_secp256k1_signer() {
+    let key = k256::ecdsa::SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+    let signer = SignerInfo {
+        id: "cosmos".to_string(),
+        pubkey: Binary::from(key.verifying_key().to_encoded_point(true).as_bytes().to_vec()),
+        scheme: SigScheme::Secp256k1,
+    };
+    let (mut app, contract) = setup(vec![signer], 1);
+
+    let message = batch_sign_bytes(&app.block_info().chain_id, 1, ROOT, TOTAL_WEIGHT);
+    let hash = Sha256::digest(&message);
+    let sig: k256::ecdsa::Signature = key.sign_prehash(&hash).unwrap();
+    let sig = Binary::from(sig.to_bytes().to_vec());
+
+    submit(&mut app, &contract, 1, &["cosmos"], vec![sig]).unwrap();
+}
+
+#[test]
+fn test_instantiate_rejects_bad_keys_and_threshold() {
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    let mut msg = InstantiateMsg {
+        admin: None,
+        token: "token".to_string(),
+        epoch_secs: 3600,
+        min_signers: 1,
+        reward_per_epoch: Uint128::new(1000),
+        signers: vec![SignerInfo {
+            id: "s1".to_string(),
+            pubkey: Binary::from(vec![1u8; 31]),
+            scheme: SigScheme::Ed25519,
+        }],
+    };
+    assert!(app
+        .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "poe", None)
+        .is_err());
+
+    msg.signers = vec![ed25519_signer("s1", &ed25519_key(1))];
+    msg.min_signers = 0;
+    assert!(app
+        .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "poe", None)
+        .is_err());
+}
@@ -1,12 +1,16 @@
 //! PoE contract state
 
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Addr, Binary, Uint128};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
+    /// Address allowed to change config and the signer set
+    pub admin: Addr,
+    /// cw20 token minted to claimants; this contract must be its minter
+    pub token: Addr,
     pub epoch_secs: u64,
     pub min_signers: u32,
     pub reward_per_epoch: Uint128,
@@ -15,19 +19,45 @@ pub struct Config {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct EpochAcc {
     pub root: Vec<u8>,
-    pub total_weight: Decimal,
+    /// Sum of committed leaf weights
+    pub total_weight: Uint128,
+    /// Reward split across the epoch's leaves
+    pub reward: Uint128,
+    /// Reward paid out so far
+    pub claimed: Uint128,
     pub posted: bool,
 }
 
+/// Signature scheme of a registered signer
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SigScheme {
+    Ed25519,
+    Secp256k1,
+}
+
+/// Key of a registered batch signer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Signer {
+    pub pubkey: Binary,
+    pub scheme: SigScheme,
+}
+
 pub const CONFIG: Item<Config> = Item::new("config");
+pub const SIGNERS: Map<&str, Signer> = Map::new("signers");
 pub const EPOCHS: Map<u64, EpochAcc> = Map::new("epochs");
+/// Total reward paid to each node
 pub const ACCRUAL: Map<&str, Uint128> = Map::new("accrual");
+/// Claimed leaves by (epoch, leaf hash)
+pub const CLAIMS: Map<(u64, &[u8]), bool> = Map::new("claims");
 pub const STATS: Item<Stats> = Item::new("stats");
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Stats {
     pub total_epochs: u64,
+    /// Reward paid out through claims
     pub total_rewards: Uint128,
+    /// Number of claimed leaves
     pub total_participants: u64,
 }
 
@@ -1,10 +1,14 @@
 //! Agent Core - Main action execution loop
 
+use crate::approval::{ApprovalError, ApprovalQueue, ApprovalStatus, GoalCheckpoint};
+use crate::dag_executor::DagExecutor;
 use crate::sandbox::{Sandbox, SandboxConfig};
-use crate::tool_api::{ExecutionContext, Tool, ToolError, ToolInput, ToolOutput};
+use crate::tool_api::{ToolError, ToolInput};
 use crate::tool_registry::ToolRegistry;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Duration;
+use synapsenet_governance::{PolicyClass, PolicyEngine, PolicyInput, Route};
 use uuid::Uuid;
 
 /// Action trace for a goal
@@ -15,6 +19,9 @@ pub struct ActionTrace {
     pub total_execution_time_ms: u64,
     pub success: bool,
     pub final_result: Option<serde_json::Value>,
+    /// Set when the goal is suspended on this approval request
+    #[serde(default)]
+    pub awaiting_approval: Option<Uuid>,
 }
 
 /// Single action log entry
@@ -36,6 +43,8 @@ pub struct ActionLog {
 pub struct AgentCore {
     tool_registry: Arc<ToolRegistry>,
     sandbox: Sandbox,
+    approvals: Arc<ApprovalQueue>,
+    policy: Option<Arc<PolicyEngine>>,
 }
 
 impl AgentCore {
@@ -44,6 +53,8 @@ impl AgentCore {
         Self {
             tool_registry,
             sandbox: Sandbox::new(sandbox_config),
+            approvals: Arc::new(ApprovalQueue::in_memory()),
+            policy: None,
         }
     }
 
@@ -52,18 +63,71 @@ impl AgentCore {
         Self::new(tool_registry, SandboxConfig::default())
     }
 
+    /// Use `approvals` for tools whose policy requires approval
+    pub fn with_approvals(mut self, approvals: Arc<ApprovalQueue>) -> Self {
+        self.approvals = approvals;
+        self
+    }
+
+    /// Screen tool calls with `policy`
+    ///
+    /// Calls it classifies as curated go through the approval queue and
+    /// blocked ones fail with `ToolError::PolicyDenied`.
+    pub fn with_policy(mut self, policy: Arc<PolicyEngine>) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Check tool and content policy; `Ok(true)` means approval is needed
+    pub(crate) fn admit(&self, tool_name: &str, input: &ToolInput) -> Result<bool, ToolError> {
+        self.tool_registry.check_policy(tool_name, &input.context.user_id)?;
+        let mut needs_approval = self.tool_registry.requires_approval(tool_name);
+
+        if let Some(policy) = &self.policy {
+            let mut strings = Vec::new();
+            collect_strings(&input.params, &mut strings);
+            let text = strings.join("\n");
+            let tags = [format!("tool:{}", tool_name)];
+            let classification =
+                policy.evaluate(Route::Tool, &PolicyInput::new(&text).with_tags(&tags));
+            match classification.class {
+                PolicyClass::Blocked => {
+                    return Err(ToolError::PolicyDenied(classification.explanation))
+                }
+                PolicyClass::Curated => needs_approval = true,
+                PolicyClass::Ok | PolicyClass::AnalysisOnly => {}
+            }
+        }
+
+        Ok(needs_approval)
+    }
+
     /// Execute a single action
+    ///
+    /// Tools that require approval, or calls the content policy holds for
+    /// review, are not run: the proposed input is filed with the approval
+    /// queue and `ToolError::ApprovalRequired` is returned.
     pub async fn execute_action(
         &self,
         tool_name: &str,
         input: ToolInput,
     ) -> Result<ActionLog, ToolError> {
+        if self.admit(tool_name, &input)? {
+            let request = self
+                .approvals
+                .request(tool_name, input, GoalCheckpoint::default())
+                .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+            return Err(ToolError::ApprovalRequired(request.id));
+        }
+
+        self.run_tool(tool_name, input).await
+    }
+
+    /// Run a tool whose policy has already been checked
+    pub(crate) async fn run_tool(&self, tool_name: &str, input: ToolInput) -> Result<ActionLog, ToolError> {
         let start = std::time::Instant::now();
         let action_id = Uuid::new_v4();
 
-        // Check policy
-        self.tool_registry.check_policy(tool_name, &input.context.user_id)?;
-
         // Get tool
         let tool = self.tool_registry.get(tool_name)
             .ok_or(ToolError::NotFound)?;
@@ -72,8 +136,9 @@ impl AgentCore {
         tool.validate_input(&input)?;
 
         // Execute in sandbox
-        let result = self.sandbox.execute_async(|| async {
-            tool.execute(input.clone()).await
+        let task_input = input.clone();
+        let result = self.sandbox.execute_async(move || async move {
+            tool.execute(task_input).await
         }).await;
 
         let execution_time_ms = start.elapsed().as_millis() as u64;
@@ -121,39 +186,173 @@ impl AgentCore {
     }
 
     /// Execute multiple actions for a goal
+    ///
+    /// Stops at the first action that requires approval; the trace then
+    /// carries `awaiting_approval` and `resume_goal` continues from there.
     pub async fn execute_goal(&self, goal_id: Uuid, actions: Vec<(String, ToolInput)>) -> ActionTrace {
+        let checkpoint = GoalCheckpoint {
+            remaining: actions,
+            ..GoalCheckpoint::default()
+        };
+        self.run_goal(goal_id, checkpoint, None).await
+    }
+
+    /// Continue a goal suspended on `approval_id` once it has been decided
+    ///
+    /// An approved action runs with its proposed input, followed by the rest
+    /// of the goal. A rejected or expired one fails the goal.
+    pub async fn resume_goal(&self, approval_id: &Uuid) -> Result<ActionTrace, ApprovalError> {
+        if self.approvals.get(approval_id)?.checkpoint.graph.is_some() {
+            return Err(ApprovalError::TaskGraph(*approval_id));
+        }
+        let request = self.approvals.claim(approval_id)?;
+
+        if request.status == ApprovalStatus::Approved {
+            let approved = (request.tool_name, request.input);
+            return Ok(self.run_goal(request.goal_id, request.checkpoint, Some(approved)).await);
+        }
+
+        let reason = request.reason.unwrap_or_else(|| request.status.as_str().to_string());
+        let mut actions = request.checkpoint.completed;
+        actions.push(failed_log(
+            request.goal_id,
+            request.tool_name,
+            &ToolError::ApprovalDenied(reason),
+        ));
+        self.tool_registry.end_goal(&request.goal_id);
+
+        Ok(ActionTrace {
+            goal_id: request.goal_id,
+            final_result: None,
+            actions,
+            total_execution_time_ms: request.checkpoint.elapsed_ms,
+            success: false,
+            awaiting_approval: None,
+        })
+    }
+
+    /// Wait for a decision on `approval_id`, then resume its goal
+    pub async fn resume_when_decided(
+        &self,
+        approval_id: &Uuid,
+        poll: Duration,
+    ) -> Result<ActionTrace, ApprovalError> {
+        self.approvals.wait(approval_id, poll).await?;
+        self.resume_goal(approval_id).await
+    }
+
+    /// Resume the goals of decided approvals nobody has acted on yet
+    ///
+    /// Overdue requests are expired first, so their goals fail with the
+    /// timeout. Requests another process claims in the meantime are skipped,
+    /// and task graphs are left to `DagExecutor::resume_decided`.
+    pub async fn resume_decided(&self) -> Result<Vec<ActionTrace>, ApprovalError> {
+        self.approvals.expire_overdue()?;
+
+        let mut traces = Vec::new();
+        for request in self.approvals.list(None)? {
+            if request.status == ApprovalStatus::Pending
+                || request.resumed_at_ms.is_some()
+                || request.checkpoint.graph.is_some()
+            {
+                continue;
+            }
+            match self.resume_goal(&request.id).await {
+                Ok(trace) => {
+                    tracing::info!(
+                        "Resumed goal {} after approval {} ({})",
+                        trace.goal_id,
+                        request.id,
+                        if trace.success { "succeeded" } else { "failed" }
+                    );
+                    traces.push(trace);
+                }
+                Err(ApprovalError::AlreadyResumed(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(traces)
+    }
+
+    /// Resume decided goals and task graphs every `interval`, forever
+    pub async fn run_approvals(self: Arc<Self>, interval: Duration) {
+        let graphs = DagExecutor::new(self.clone());
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.resume_decided().await {
+                tracing::warn!("Resuming approved goals failed: {}", e);
+            }
+            if let Err(e) = graphs.resume_decided().await {
+                tracing::warn!("Resuming approved task graphs failed: {}", e);
+            }
+        }
+    }
+
+    async fn run_goal(
+        &self,
+        goal_id: Uuid,
+        checkpoint: GoalCheckpoint,
+        mut approved: Option<(String, ToolInput)>,
+    ) -> ActionTrace {
         let start = std::time::Instant::now();
-        let mut action_logs = Vec::new();
-        let mut final_result = None;
+        let mut action_logs = checkpoint.completed;
+        let mut final_result = action_logs.last().and_then(|log| log.output.clone());
         let mut overall_success = true;
+        let mut remaining = checkpoint.remaining.into_iter();
+
+        loop {
+            let (tool_name, input, is_approved) = match approved.take() {
+                Some((tool_name, input)) => (tool_name, input, true),
+                None => match remaining.next() {
+                    Some((tool_name, input)) => (tool_name, input, false),
+                    None => break,
+                },
+            };
 
-        for (tool_name, input) in actions {
-            match self.execute_action(&tool_name, input).await {
+            let result = match self.admit(&tool_name, &input) {
+                Ok(true) if !is_approved => {
+                    // Suspend: the goal resumes from here once a person decides
+                    let elapsed_ms = checkpoint.elapsed_ms + start.elapsed().as_millis() as u64;
+                    let checkpoint = GoalCheckpoint {
+                        completed: action_logs.clone(),
+                        remaining: remaining.by_ref().collect(),
+                        elapsed_ms,
+                        graph: None,
+                    };
+                    match self.approvals.request(&tool_name, input, checkpoint) {
+                        Ok(request) => {
+                            return ActionTrace {
+                                goal_id,
+                                actions: action_logs,
+                                total_execution_time_ms: elapsed_ms,
+                                success: false,
+                                final_result,
+                                awaiting_approval: Some(request.id),
+                            };
+                        }
+                        Err(e) => Err(ToolError::ExecutionFailed(e.to_string())),
+                    }
+                }
+                Ok(_) => self.run_tool(&tool_name, input).await,
+                Err(e) => Err(e),
+            };
+
+            match result {
                 Ok(log) => {
                     final_result = log.output.clone();
                     action_logs.push(log);
                 }
                 Err(e) => {
                     overall_success = false;
-                    let error_log = ActionLog {
-                        id: Uuid::new_v4(),
-                        goal_id,
-                        episode_id: None,
-                        tool_name,
-                        input: serde_json::Value::Null,
-                        output: None,
-                        error: Some(e.to_string()),
-                        timestamp: chrono::Utc::now().timestamp(),
-                        execution_time_ms: 0,
-                        success: false,
-                    };
-                    action_logs.push(error_log);
+                    action_logs.push(failed_log(goal_id, tool_name, &e));
                     break;
                 }
             }
         }
 
-        let total_execution_time_ms = start.elapsed().as_millis() as u64;
+        let total_execution_time_ms = checkpoint.elapsed_ms + start.elapsed().as_millis() as u64;
+        self.tool_registry.end_goal(&goal_id);
 
         ActionTrace {
             goal_id,
@@ -161,6 +360,7 @@ impl AgentCore {
             total_execution_time_ms,
             success: overall_success,
             final_result,
+            awaiting_approval: None,
         }
     }
 
@@ -173,12 +373,44 @@ impl AgentCore {
     pub fn sandbox(&self) -> &Sandbox {
         &self.sandbox
     }
+
+    /// Get approval queue
+    pub fn approvals(&self) -> &Arc<ApprovalQueue> {
+        &self.approvals
+    }
+}
+
+/// Every string value in `value`, depth first
+fn collect_strings<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(s) => out.push(s),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+/// Log entry for an action that did not run to completion
+fn failed_log(goal_id: Uuid, tool_name: String, error: &ToolError) -> ActionLog {
+    ActionLog {
+        id: Uuid::new_v4(),
+        goal_id,
+        episode_id: None,
+        tool_name,
+        input: serde_json::Value::Null,
+        output: None,
+        error: Some(error.to_string()),
+        timestamp: chrono::Utc::now().timestamp(),
+        execution_time_ms: 0,
+        success: false,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tool_api::*;
+    use crate::tool_registry::ToolPolicy;
     use async_trait::async_trait;
     use std::collections::HashMap;
 
@@ -287,4 +519,227 @@ mod tests {
         assert!(trace.success);
         assert_eq!(trace.actions.len(), 1);
     }
+
+    fn goal_actions(goal_id: Uuid) -> Vec<(String, ToolInput)> {
+        let context = ExecutionContext {
+            goal_id,
+            episode_id: None,
+            user_id: "test_user".to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            metadata: HashMap::new(),
+        };
+        let input = |step: u32| ToolInput {
+            params: serde_json::json!({ "step": step }),
+            context: context.clone(),
+        };
+        vec![
+            ("test_tool".to_string(), input(1)),
+            ("gated_tool".to_string(), input(2)),
+            ("test_tool".to_string(), input(3)),
+        ]
+    }
+
+    struct GatedTool;
+
+    #[async_trait]
+    impl Tool for GatedTool {
+        fn name(&self) -> &'static str {
+            "gated_tool"
+        }
+
+        fn description(&self) -> &'static str {
+            "Tool that needs approval"
+        }
+
+        fn schema(&self) -> ToolSchema {
+            TestTool.schema()
+        }
+
+        async fn execute(&self, input: ToolInput) -> Result<ToolOutput, ToolError> {
+            let mut output = TestTool.execute(input.clone()).await?;
+            output.result = input.params;
+            Ok(output)
+        }
+    }
+
+    fn gated_agent() -> AgentCore {
+        let registry = Arc::new(ToolRegistry::new());
+        registry.register(Box::new(TestTool)).unwrap();
+        registry.register(Box::new(GatedTool)).unwrap();
+        registry
+            .update_policy("gated_tool", ToolPolicy { requires_approval: true, ..ToolPolicy::default() })
+            .unwrap();
+        AgentCore::with_defaults(registry)
+    }
+
+    #[tokio::test]
+    async fn test_goal_suspends_and_resumes_on_approval() {
+        let agent = gated_agent();
+        let goal_id = Uuid::new_v4();
+
+        let trace = agent.execute_goal(goal_id, goal_actions(goal_id)).await;
+        let approval_id = trace.awaiting_approval.expect("goal should suspend");
+        assert!(!trace.success);
+        assert_eq!(trace.actions.len(), 1);
+
+        let request = agent.approvals().get(&approval_id).unwrap();
+        assert_eq!(request.tool_name, "gated_tool");
+        assert_eq!(request.input.params["step"], 2);
+        assert_eq!(request.checkpoint.remaining.len(), 1);
+
+        assert!(matches!(agent.resume_goal(&approval_id).await, Err(ApprovalError::Pending(_))));
+
+        agent.approvals().approve(&approval_id, "reviewer").unwrap();
+        let trace = agent.resume_goal(&approval_id).await.unwrap();
+        assert!(trace.success);
+        assert!(trace.awaiting_approval.is_none());
+        assert_eq!(trace.actions.len(), 3);
+        assert_eq!(trace.actions[1].output, Some(serde_json::json!({ "step": 2 })));
+    }
+
+    #[tokio::test]
+    async fn test_goal_fails_on_rejection() {
+        let agent = gated_agent();
+        let goal_id = Uuid::new_v4();
+
+        let trace = agent.execute_goal(goal_id, goal_actions(goal_id)).await;
+        let approval_id = trace.awaiting_approval.unwrap();
+
+        agent.approvals().reject(&approval_id, "reviewer", "not today").unwrap();
+        let trace = agent.resume_goal(&approval_id).await.unwrap();
+        assert!(!trace.success);
+        assert_eq!(trace.actions.len(), 2);
+        assert_eq!(trace.actions[1].error.as_deref(), Some("Approval denied: not today"));
+    }
+
+    /// Records the goals it is told have finished
+    struct EndedGoals(Arc<std::sync::Mutex<Vec<Uuid>>>);
+
+    #[async_trait]
+    impl Tool for EndedGoals {
+        fn name(&self) -> &'static str {
+            "ended_goals"
+        }
+
+        fn description(&self) -> &'static str {
+            "Tool that tracks finished goals"
+        }
+
+        fn schema(&self) -> ToolSchema {
+            TestTool.schema()
+        }
+
+        async fn execute(&self, input: ToolInput) -> Result<ToolOutput, ToolError> {
+            TestTool.execute(input).await
+        }
+
+        fn end_goal(&self, goal_id: &Uuid) {
+            self.0.lock().unwrap().push(*goal_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_finished_goals_are_ended() {
+        let agent = gated_agent();
+        let ended = Arc::new(std::sync::Mutex::new(Vec::new()));
+        agent.registry().register(Box::new(EndedGoals(ended.clone()))).unwrap();
+
+        // A suspended goal is not over yet
+        let approved = Uuid::new_v4();
+        let trace = agent.execute_goal(approved, goal_actions(approved)).await;
+        let approval_id = trace.awaiting_approval.unwrap();
+        assert!(ended.lock().unwrap().is_empty());
+        agent.approvals().approve(&approval_id, "reviewer").unwrap();
+        agent.resume_goal(&approval_id).await.unwrap();
+        assert_eq!(*ended.lock().unwrap(), vec![approved]);
+
+        let rejected = Uuid::new_v4();
+        let trace = agent.execute_goal(rejected, goal_actions(rejected)).await;
+        let approval_id = trace.awaiting_approval.unwrap();
+        agent.approvals().reject(&approval_id, "reviewer", "no").unwrap();
+        agent.resume_goal(&approval_id).await.unwrap();
+        assert_eq!(*ended.lock().unwrap(), vec![approved, rejected]);
+    }
+
+    #[tokio::test]
+    async fn test_resume_decided_runs_once() {
+        let agent = gated_agent();
+        let goal_id = Uuid::new_v4();
+
+        let trace = agent.execute_goal(goal_id, goal_actions(goal_id)).await;
+        let approval_id = trace.awaiting_approval.unwrap();
+        assert!(agent.resume_decided().await.unwrap().is_empty());
+
+        agent.approvals().approve(&approval_id, "reviewer").unwrap();
+        let traces = agent.resume_decided().await.unwrap();
+        assert_eq!(traces.len(), 1);
+        assert!(traces[0].success);
+        assert_eq!(traces[0].actions.len(), 3);
+        assert!(agent.resume_decided().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_action_requires_approval() {
+        let agent = gated_agent()
+            .with_approvals(Arc::new(ApprovalQueue::in_memory().with_timeout(Duration::from_millis(50))));
+        let input = goal_actions(Uuid::new_v4()).remove(1).1;
+
+        let Err(ToolError::ApprovalRequired(approval_id)) = agent.execute_action("gated_tool", input).await else {
+            panic!("gated tool ran without approval");
+        };
+
+        // Nobody decides: the request times out and is rejected
+        let trace = agent
+            .resume_when_decided(&approval_id, Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert!(!trace.success);
+        assert_eq!(trace.actions[0].error.as_deref(), Some("Approval denied: approval timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_content_policy_gates_tool_calls() {
+        let policy = synapsenet_governance::Policy::from_toml_str(
+            r#"
+            [[rules]]
+            id = "destructive"
+            type = "keywords"
+            keywords = ["rm -rf"]
+            class = "blocked"
+            routes = ["tool"]
+
+            [[rules]]
+            id = "publish"
+            type = "keywords"
+            keywords = ["publish"]
+            class = "curated"
+            "#,
+        )
+        .unwrap();
+        let agent = gated_agent().with_policy(Arc::new(PolicyEngine::new(policy)));
+        let goal_id = Uuid::new_v4();
+        let (_, mut input) = goal_actions(goal_id).remove(0);
+
+        input.params = serde_json::json!({ "commands": ["ls", "rm -rf /"] });
+        let Err(ToolError::PolicyDenied(explanation)) =
+            agent.execute_action("test_tool", input.clone()).await
+        else {
+            panic!("blocked call ran");
+        };
+        assert!(explanation.contains("destructive"));
+
+        // Curated calls wait for approval like gated tools
+        input.params = serde_json::json!({ "note": "publish the report" });
+        let trace = agent
+            .execute_goal(goal_id, vec![("test_tool".to_string(), input.clone())])
+            .await;
+        let approval_id = trace.awaiting_approval.expect("curated call should suspend");
+
+        agent.approvals().approve(&approval_id, "reviewer").unwrap();
+        let trace = agent.resume_goal(&approval_id).await.unwrap();
+        assert!(trace.success);
+
+        input.params = serde_json::json!({ "note": "summarise the report" });
+        assert!(agent.execute_action("test_tool", input).await.is_ok());
+    }
 }
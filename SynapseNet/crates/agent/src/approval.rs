@@ -0,0 +1,549 @@
+//! Approval Queue - Human sign-off for tools whose policy requires it
+//!
+//! `AgentCore` files an `ApprovalRequest` with the proposed input instead of
+//! running a gated tool. A person approves or rejects it (REST, CLI or the
+//! desktop app), requests left undecided past their deadline are rejected
+//! automatically, and the goal is resumed from the saved checkpoint.
+//!
+//! Queues opened on the node's `Store` share requests with every process
+//! using the same database; each decision and resumption is a conditional
+//! update, so it happens once even when processes race.
+
+use crate::agent_core::ActionLog;
+use crate::dag_executor::GraphCheckpoint;
+use crate::tool_api::ToolInput;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use synapsenet_storage::Store;
+use uuid::Uuid;
+
+/// Default time a request waits for a decision
+pub const DEFAULT_APPROVAL_TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// Decision state of an approval request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+    /// Nobody decided before the deadline; treated as a rejection
+    Expired,
+}
+
+impl ApprovalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+            Self::Expired => "expired",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(Self::Pending),
+            "approved" => Some(Self::Approved),
+            "rejected" => Some(Self::Rejected),
+            "expired" => Some(Self::Expired),
+            _ => None,
+        }
+    }
+}
+
+/// Progress of a goal suspended on an approval
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GoalCheckpoint {
+    /// Actions already executed
+    pub completed: Vec<ActionLog>,
+    /// Actions queued after the one awaiting approval
+    pub remaining: Vec<(String, ToolInput)>,
+    pub elapsed_ms: u64,
+    /// Set when the goal runs as a task graph instead of a list of actions
+    #[serde(default)]
+    pub graph: Option<GraphCheckpoint>,
+}
+
+/// A gated action waiting for (or past) a human decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub id: Uuid,
+    pub goal_id: Uuid,
+    pub tool_name: String,
+    /// Proposed input, executed unchanged once approved
+    pub input: ToolInput,
+    pub status: ApprovalStatus,
+    pub requested_at_ms: i64,
+    pub expires_at_ms: i64,
+    pub decided_at_ms: Option<i64>,
+    pub decided_by: Option<String>,
+    pub reason: Option<String>,
+    /// Set once the decision has been acted on
+    pub resumed_at_ms: Option<i64>,
+    pub checkpoint: GoalCheckpoint,
+}
+
+impl ApprovalRequest {
+    /// Mark the request expired if its deadline has passed
+    fn expire_if_due(&mut self, now_ms: i64) -> bool {
+        if self.status != ApprovalStatus::Pending || now_ms < self.expires_at_ms {
+            return false;
+        }
+        self.status = ApprovalStatus::Expired;
+        self.decided_at_ms = Some(self.expires_at_ms);
+        self.reason = Some("approval timed out".to_string());
+        true
+    }
+}
+
+/// Approval queue errors
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApprovalError {
+    NotFound(Uuid),
+    /// No decision has been made yet
+    Pending(Uuid),
+    AlreadyDecided(ApprovalStatus),
+    AlreadyResumed(Uuid),
+    /// Filed by a task graph; `DagExecutor::resume` continues it
+    TaskGraph(Uuid),
+    /// Filed by a linear goal; `AgentCore::resume_goal` continues it
+    NotTaskGraph(Uuid),
+    Storage(String),
+}
+
+impl std::fmt::Display for ApprovalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(id) => write!(f, "Approval not found: {}", id),
+            Self::Pending(id) => write!(f, "Approval still pending: {}", id),
+            Self::AlreadyDecided(status) => write!(f, "Approval already {}", status.as_str()),
+            Self::AlreadyResumed(id) => write!(f, "Approval already acted on: {}", id),
+            Self::TaskGraph(id) => write!(f, "Approval belongs to a task graph: {}", id),
+            Self::NotTaskGraph(id) => write!(f, "Approval does not belong to a task graph: {}", id),
+            Self::Storage(msg) => write!(f, "Approval storage error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ApprovalError {}
+
+enum Backend {
+    Memory(Mutex<HashMap<Uuid, ApprovalRequest>>),
+    /// The node's store, shared by every process using the data directory
+    Store(Arc<Mutex<Store>>),
+}
+
+impl Backend {
+    fn insert(&self, request: &ApprovalRequest) -> Result<(), ApprovalError> {
+        match self {
+            Backend::Memory(map) => {
+                map.lock().unwrap().insert(request.id, request.clone());
+                Ok(())
+            }
+            Backend::Store(store) => store
+                .lock()
+                .unwrap()
+                .insert_approval(
+                    &request.id,
+                    request.status.as_str(),
+                    request.requested_at_ms,
+                    &serde_json::to_string(request).map_err(storage)?,
+                )
+                .map_err(storage),
+        }
+    }
+
+    fn load(&self, id: &Uuid) -> Result<Option<ApprovalRequest>, ApprovalError> {
+        match self {
+            Backend::Memory(map) => Ok(map.lock().unwrap().get(id).cloned()),
+            Backend::Store(store) => match store.lock().unwrap().get_approval(id) {
+                Ok(Some(record)) => serde_json::from_str(&record).map(Some).map_err(storage),
+                Ok(None) => Ok(None),
+                Err(e) => Err(storage(e)),
+            },
+        }
+    }
+
+    fn load_all(&self) -> Result<Vec<ApprovalRequest>, ApprovalError> {
+        match self {
+            Backend::Memory(map) => Ok(map.lock().unwrap().values().cloned().collect()),
+            Backend::Store(store) => {
+                let records = store.lock().unwrap().list_approvals().map_err(storage)?;
+                let mut requests = Vec::new();
+                for record in records {
+                    match serde_json::from_str(&record) {
+                        Ok(request) => requests.push(request),
+                        Err(e) => tracing::warn!("Skipping unreadable approval: {}", e),
+                    }
+                }
+                Ok(requests)
+            }
+        }
+    }
+
+    /// Save `request` if it is still in status `from` and not resumed;
+    /// false if another writer got there first
+    fn update(
+        &self,
+        from: ApprovalStatus,
+        request: &ApprovalRequest,
+    ) -> Result<bool, ApprovalError> {
+        match self {
+            Backend::Memory(map) => {
+                let mut map = map.lock().unwrap();
+                let unchanged = map.get(&request.id).is_some_and(|current| {
+                    current.status == from && current.resumed_at_ms.is_none()
+                });
+                if unchanged {
+                    map.insert(request.id, request.clone());
+                }
+                Ok(unchanged)
+            }
+            Backend::Store(store) => store
+                .lock()
+                .unwrap()
+                .update_approval(
+                    &request.id,
+                    from.as_str(),
+                    request.status.as_str(),
+                    request.resumed_at_ms.is_some(),
+                    &serde_json::to_string(request).map_err(storage)?,
+                )
+                .map_err(storage),
+        }
+    }
+}
+
+/// Persistent queue of approval requests
+pub struct ApprovalQueue {
+    backend: Backend,
+    timeout: Duration,
+}
+
+impl ApprovalQueue {
+    /// Queue kept in memory only
+    pub fn in_memory() -> Self {
+        Self {
+            backend: Backend::Memory(Mutex::new(HashMap::new())),
+            timeout: DEFAULT_APPROVAL_TIMEOUT,
+        }
+    }
+
+    /// Queue persisted in the node's store
+    pub fn new(store: Arc<Mutex<Store>>) -> Self {
+        Self {
+            backend: Backend::Store(store),
+            timeout: DEFAULT_APPROVAL_TIMEOUT,
+        }
+    }
+
+    /// Set how long new requests wait before they are auto-rejected
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// File a new request for `tool_name` with the proposed input
+    pub fn request(
+        &self,
+        tool_name: &str,
+        input: ToolInput,
+        checkpoint: GoalCheckpoint,
+    ) -> Result<ApprovalRequest, ApprovalError> {
+        let now = now_ms();
+        let request = ApprovalRequest {
+            id: Uuid::new_v4(),
+            goal_id: input.context.goal_id,
+            tool_name: tool_name.to_string(),
+            input,
+            status: ApprovalStatus::Pending,
+            requested_at_ms: now,
+            expires_at_ms: now.saturating_add(self.timeout.as_millis() as i64),
+            decided_at_ms: None,
+            decided_by: None,
+            reason: None,
+            resumed_at_ms: None,
+            checkpoint,
+        };
+
+        self.backend.insert(&request)?;
+        tracing::info!("Tool '{}' awaiting approval {}", tool_name, request.id);
+        Ok(request)
+    }
+
+    /// Get a request, expiring it if overdue
+    pub fn get(&self, id: &Uuid) -> Result<ApprovalRequest, ApprovalError> {
+        let request = self.backend.load(id)?.ok_or(ApprovalError::NotFound(*id))?;
+        self.refresh(request)
+    }
+
+    /// List requests, oldest first, optionally filtered by status
+    pub fn list(
+        &self,
+        status: Option<ApprovalStatus>,
+    ) -> Result<Vec<ApprovalRequest>, ApprovalError> {
+        let mut requests = Vec::new();
+        for request in self.backend.load_all()? {
+            let request = self.refresh(request)?;
+            if status.is_none_or(|s| s == request.status) {
+                requests.push(request);
+            }
+        }
+        requests.sort_by_key(|r| r.requested_at_ms);
+        Ok(requests)
+    }
+
+    /// Approve a pending request
+    pub fn approve(&self, id: &Uuid, decided_by: &str) -> Result<ApprovalRequest, ApprovalError> {
+        self.decide(id, ApprovalStatus::Approved, decided_by, None)
+    }
+
+    /// Reject a pending request with a reason
+    pub fn reject(
+        &self,
+        id: &Uuid,
+        decided_by: &str,
+        reason: &str,
+    ) -> Result<ApprovalRequest, ApprovalError> {
+        self.decide(
+            id,
+            ApprovalStatus::Rejected,
+            decided_by,
+            Some(reason.to_string()),
+        )
+    }
+
+    /// Auto-reject every overdue request, returning their ids
+    pub fn expire_overdue(&self) -> Result<Vec<Uuid>, ApprovalError> {
+        let now = now_ms();
+
+        let mut expired = Vec::new();
+        for mut request in self.backend.load_all()? {
+            if request.expire_if_due(now)
+                && self.backend.update(ApprovalStatus::Pending, &request)?
+            {
+                expired.push(request.id);
+            }
+        }
+        Ok(expired)
+    }
+
+    /// Wait until a request is decided or expires
+    pub async fn wait(&self, id: &Uuid, poll: Duration) -> Result<ApprovalRequest, ApprovalError> {
+        loop {
+            let request = self.get(id)?;
+            if request.status != ApprovalStatus::Pending {
+                return Ok(request);
+            }
+            let until_expiry = (request.expires_at_ms - now_ms()).max(1) as u64;
+            tokio::time::sleep(poll.min(Duration::from_millis(until_expiry))).await;
+        }
+    }
+
+    /// Claim a decided request so its goal is resumed exactly once
+    pub fn claim(&self, id: &Uuid) -> Result<ApprovalRequest, ApprovalError> {
+        let mut request = self.get(id)?;
+
+        if request.status == ApprovalStatus::Pending {
+            return Err(ApprovalError::Pending(*id));
+        }
+        if request.resumed_at_ms.is_some() {
+            return Err(ApprovalError::AlreadyResumed(*id));
+        }
+
+        // A decided request only changes by being claimed
+        request.resumed_at_ms = Some(now_ms());
+        if !self.backend.update(request.status, &request)? {
+            return Err(ApprovalError::AlreadyResumed(*id));
+        }
+        Ok(request)
+    }
+
+    /// Persist the expiry of an overdue request
+    fn refresh(&self, mut request: ApprovalRequest) -> Result<ApprovalRequest, ApprovalError> {
+        if request.expire_if_due(now_ms())
+            && !self.backend.update(ApprovalStatus::Pending, &request)?
+        {
+            // Decided elsewhere in the meantime
+            return self
+                .backend
+                .load(&request.id)?
+                .ok_or(ApprovalError::NotFound(request.id));
+        }
+        Ok(request)
+    }
+
+    fn decide(
+        &self,
+        id: &Uuid,
+        status: ApprovalStatus,
+        decided_by: &str,
+        reason: Option<String>,
+    ) -> Result<ApprovalRequest, ApprovalError> {
+        let mut request = self.get(id)?;
+
+        if request.status != ApprovalStatus::Pending {
+            return Err(ApprovalError::AlreadyDecided(request.status));
+        }
+
+        request.status = status;
+        request.decided_at_ms = Some(now_ms());
+        request.decided_by = Some(decided_by.to_string());
+        request.reason = reason;
+        if !self.backend.update(ApprovalStatus::Pending, &request)? {
+            return Err(ApprovalError::AlreadyDecided(self.get(id)?.status));
+        }
+
+        tracing::info!(
+            "Approval {} for '{}' {} by {}",
+            id,
+            request.tool_name,
+            status.as_str(),
+            decided_by
+        );
+        Ok(request)
+    }
+}
+
+impl Default for ApprovalQueue {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn storage(e: impl std::fmt::Display) -> ApprovalError {
+    ApprovalError::Storage(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_api::ExecutionContext;
+
+    fn input() -> ToolInput {
+        ToolInput {
+            params: serde_json::json!({"path": "notes.txt"}),
+            context: ExecutionContext {
+                goal_id: Uuid::new_v4(),
+                episode_id: None,
+                user_id: "test_user".to_string(),
+                timestamp: chrono::Utc::now().timestamp(),
+                metadata: HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_approve_and_reject() {
+        let queue = ApprovalQueue::in_memory();
+
+        let first = queue
+            .request("file_ops", input(), GoalCheckpoint::default())
+            .unwrap();
+        let second = queue
+            .request("file_ops", input(), GoalCheckpoint::default())
+            .unwrap();
+        assert_eq!(queue.list(Some(ApprovalStatus::Pending)).unwrap().len(), 2);
+
+        let approved = queue.approve(&first.id, "alice").unwrap();
+        assert_eq!(approved.status, ApprovalStatus::Approved);
+        assert_eq!(approved.decided_by.as_deref(), Some("alice"));
+
+        let rejected = queue
+            .reject(&second.id, "bob", "writes outside the project")
+            .unwrap();
+        assert_eq!(rejected.status, ApprovalStatus::Rejected);
+        assert_eq!(
+            rejected.reason.as_deref(),
+            Some("writes outside the project")
+        );
+
+        assert_eq!(
+            queue.approve(&second.id, "alice").unwrap_err(),
+            ApprovalError::AlreadyDecided(ApprovalStatus::Rejected)
+        );
+        assert!(queue
+            .list(Some(ApprovalStatus::Pending))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_timeout_auto_rejects() {
+        let queue = ApprovalQueue::in_memory().with_timeout(Duration::ZERO);
+        let request = queue
+            .request("code_exec", input(), GoalCheckpoint::default())
+            .unwrap();
+
+        let expired = queue.get(&request.id).unwrap();
+        assert_eq!(expired.status, ApprovalStatus::Expired);
+        assert_eq!(
+            queue.approve(&request.id, "alice").unwrap_err(),
+            ApprovalError::AlreadyDecided(ApprovalStatus::Expired)
+        );
+    }
+
+    #[test]
+    fn test_claim_once() {
+        let queue = ApprovalQueue::in_memory();
+        let request = queue
+            .request("file_ops", input(), GoalCheckpoint::default())
+            .unwrap();
+
+        assert_eq!(
+            queue.claim(&request.id).unwrap_err(),
+            ApprovalError::Pending(request.id)
+        );
+        queue.approve(&request.id, "alice").unwrap();
+        assert!(queue.claim(&request.id).is_ok());
+        assert_eq!(
+            queue.claim(&request.id).unwrap_err(),
+            ApprovalError::AlreadyResumed(request.id)
+        );
+    }
+
+    #[test]
+    fn test_persisted_across_queues() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("synapsenet.db");
+        let open = || {
+            let store = Store::new(&db.to_string_lossy()).unwrap();
+            ApprovalQueue::new(Arc::new(Mutex::new(store)))
+        };
+
+        let node = open();
+        let request = node
+            .request("file_ops", input(), GoalCheckpoint::default())
+            .unwrap();
+
+        // A second process (e.g. the CLI) decides what the first one filed
+        let cli = open();
+        let pending = cli.list(Some(ApprovalStatus::Pending)).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].input.params["path"], "notes.txt");
+
+        cli.reject(&request.id, "cli", "not needed").unwrap();
+        assert_eq!(
+            node.approve(&request.id, "desktop").unwrap_err(),
+            ApprovalError::AlreadyDecided(ApprovalStatus::Rejected)
+        );
+
+        // Only one process gets to resume the goal
+        assert!(node.claim(&request.id).is_ok());
+        assert_eq!(
+            cli.claim(&request.id).unwrap_err(),
+            ApprovalError::AlreadyResumed(request.id)
+        );
+        assert_eq!(
+            open().get(&request.id).unwrap().status,
+            ApprovalStatus::Rejected
+        );
+    }
+}
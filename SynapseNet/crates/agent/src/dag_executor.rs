@@ -0,0 +1,924 @@
+//! DAG executor - Runs planner task graphs as concurrent tool actions
+//!
+//! A `TaskNode` becomes a tool action through its metadata:
+//!
+//! ```json
+//! {
+//!   "tool": "web_fetch",
+//!   "params": { "max_bytes": 65536 },
+//!   "inputs": { "url": "<parent node id>/result/0" }
+//! }
+//! ```
+//!
+//! `inputs` copies a parent's output (optionally narrowed by a JSON pointer)
+//! into a named parameter; every child also receives its parents' outputs
+//! under `parent_outputs`. Nodes without a `tool` (the root and pure
+//! decomposition steps) complete without running anything.
+
+use crate::agent_core::{ActionLog, AgentCore};
+use crate::approval::{ApprovalError, ApprovalStatus, GoalCheckpoint};
+use crate::tool_api::{ExecutionContext, ToolError, ToolInput};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use synapsenet_ai::planner::{TaskGraph, TaskNode, TaskStatus};
+use synapsenet_core::{
+    retry_with_backoff, Episode, EpisodeAction, EpisodeTrace, EpisodeV2, RetryConfig,
+    SynapseNetError,
+};
+use tokio::task::JoinSet;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Default number of nodes run at the same time
+pub const DEFAULT_MAX_PARALLELISM: usize = 4;
+
+/// Result of running a task graph
+#[derive(Debug, Clone)]
+pub struct DagRun {
+    /// The graph with final node statuses
+    pub graph: TaskGraph,
+    /// One episode per tool node run, every attempt recorded as an action
+    pub trace: EpisodeTrace,
+    /// Output of each completed tool node
+    pub outputs: HashMap<Uuid, Value>,
+    /// Node errors, after retries
+    pub errors: HashMap<Uuid, String>,
+    /// Whether every node completed
+    pub success: bool,
+    /// Approval the graph is suspended on; `DagExecutor::resume` continues it
+    pub awaiting_approval: Option<Uuid>,
+}
+
+/// Task graph progress saved with the approval it is suspended on
+///
+/// The node awaiting the decision is `InProgress`; its descendants stay
+/// `Pending` until the graph is resumed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphCheckpoint {
+    pub graph: TaskGraph,
+    pub context: ExecutionContext,
+    pub outputs: HashMap<Uuid, Value>,
+    pub errors: HashMap<Uuid, String>,
+    /// Node whose action awaits the decision
+    pub node_id: Uuid,
+    /// Episodes numbered so far
+    pub step: u32,
+}
+
+/// Executes `TaskGraph` nodes as tool actions
+pub struct DagExecutor {
+    agent: Arc<AgentCore>,
+    max_parallelism: usize,
+    retry: RetryConfig,
+}
+
+impl DagExecutor {
+    /// Create executor with default parallelism and retry policy
+    pub fn new(agent: Arc<AgentCore>) -> Self {
+        Self {
+            agent,
+            max_parallelism: DEFAULT_MAX_PARALLELISM,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Run at most `max` nodes at the same time
+    pub fn with_max_parallelism(mut self, max: usize) -> Self {
+        self.max_parallelism = max.max(1);
+        self
+    }
+
+    /// Retry policy for transient tool failures
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Run every node of `graph` whose dependencies complete
+    ///
+    /// Ready nodes run concurrently. A node that still fails after retries
+    /// is marked `Failed` and its descendants `Skipped`; independent
+    /// branches keep running. A node that needs approval is held: once
+    /// nothing else can run, the graph is saved with its approval request
+    /// and the run returns with `awaiting_approval` set.
+    pub async fn execute(
+        &self,
+        graph: TaskGraph,
+        context: ExecutionContext,
+    ) -> Result<DagRun, String> {
+        graph.validate()?;
+
+        let state = RunState {
+            trace: EpisodeTrace::new(context.goal_id),
+            graph,
+            context,
+            outputs: HashMap::new(),
+            errors: HashMap::new(),
+            step: 0,
+        };
+        self.drive(state, None).await
+    }
+
+    /// Continue a graph suspended on `approval_id` once it has been decided
+    ///
+    /// An approved node runs with its proposed input and the graph carries
+    /// on from there; a rejected or expired one fails like any other node.
+    pub async fn resume(&self, approval_id: &Uuid) -> Result<DagRun, ApprovalError> {
+        let approvals = self.agent.approvals();
+        if approvals.get(approval_id)?.checkpoint.graph.is_none() {
+            return Err(ApprovalError::NotTaskGraph(*approval_id));
+        }
+
+        let request = approvals.claim(approval_id)?;
+        let Some(checkpoint) = request.checkpoint.graph else {
+            return Err(ApprovalError::NotTaskGraph(*approval_id));
+        };
+        let node_id = checkpoint.node_id;
+        let mut state = RunState {
+            trace: EpisodeTrace::new(checkpoint.context.goal_id),
+            graph: checkpoint.graph,
+            context: checkpoint.context,
+            outputs: checkpoint.outputs,
+            errors: checkpoint.errors,
+            step: checkpoint.step,
+        };
+
+        let approved = if request.status == ApprovalStatus::Approved {
+            Some((node_id, request.tool_name, request.input))
+        } else {
+            let reason = request
+                .reason
+                .unwrap_or_else(|| request.status.as_str().to_string());
+            let error = ToolError::ApprovalDenied(reason);
+            fail_node(&mut state.graph, &mut state.errors, node_id, error.to_string());
+            None
+        };
+
+        self.drive(state, approved)
+            .await
+            .map_err(|e| ApprovalError::Storage(format!("Invalid saved graph: {}", e)))
+    }
+
+    /// Resume the graphs of decided approvals nobody has acted on yet
+    ///
+    /// Requests filed by linear goals are left to `AgentCore::resume_decided`.
+    pub async fn resume_decided(&self) -> Result<Vec<DagRun>, ApprovalError> {
+        let approvals = self.agent.approvals();
+        approvals.expire_overdue()?;
+
+        let mut runs = Vec::new();
+        for request in approvals.list(None)? {
+            if request.status == ApprovalStatus::Pending
+                || request.resumed_at_ms.is_some()
+                || request.checkpoint.graph.is_none()
+            {
+                continue;
+            }
+            match self.resume(&request.id).await {
+                Ok(run) => {
+                    info!(
+                        "Resumed task graph {} after approval {} ({})",
+                        request.goal_id,
+                        request.id,
+                        match (run.success, run.awaiting_approval) {
+                            (true, _) => "succeeded",
+                            (false, Some(_)) => "awaiting approval",
+                            (false, None) => "failed",
+                        }
+                    );
+                    runs.push(run);
+                }
+                Err(ApprovalError::AlreadyResumed(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(runs)
+    }
+
+    /// Schedule ready nodes until nothing more can run
+    async fn drive(
+        &self,
+        mut state: RunState,
+        approved: Option<(Uuid, String, ToolInput)>,
+    ) -> Result<DagRun, String> {
+        let order = state.graph.topological_sort()?;
+        let mut running: JoinSet<NodeResult> = JoinSet::new();
+        let mut held: Vec<(Uuid, String, ToolInput)> = Vec::new();
+
+        if let Some((node_id, tool_name, input)) = approved {
+            self.spawn(&mut running, &mut state, node_id, tool_name, input);
+        }
+
+        loop {
+            // Schedule ready nodes in plan order
+            for node_id in &order {
+                if running.len() >= self.max_parallelism {
+                    break;
+                }
+                let Some(node) = state.graph.get_node(node_id) else {
+                    continue;
+                };
+                if !node.is_ready(&state.graph) {
+                    continue;
+                }
+
+                let Some(tool_name) = node.metadata.get("tool").and_then(Value::as_str) else {
+                    // Nothing to run; unblocks children right away
+                    if let Some(node) = state.graph.get_node_mut(node_id) {
+                        node.status = TaskStatus::Completed;
+                    }
+                    continue;
+                };
+                let tool_name = tool_name.to_string();
+
+                let input = match build_params(node, &state.outputs) {
+                    Ok(params) => ToolInput {
+                        params,
+                        context: state.context.clone(),
+                    },
+                    Err(e) => {
+                        fail_node(&mut state.graph, &mut state.errors, *node_id, e.to_string());
+                        continue;
+                    }
+                };
+
+                match self.agent.admit(&tool_name, &input) {
+                    Ok(false) => {
+                        self.spawn(&mut running, &mut state, *node_id, tool_name, input);
+                    }
+                    Ok(true) => {
+                        if let Some(node) = state.graph.get_node_mut(node_id) {
+                            node.status = TaskStatus::InProgress;
+                        }
+                        held.push((*node_id, tool_name, input));
+                    }
+                    Err(e) => {
+                        fail_node(&mut state.graph, &mut state.errors, *node_id, e.to_string());
+                    }
+                }
+            }
+
+            let Some(joined) = running.join_next().await else {
+                break;
+            };
+            let finished = joined.map_err(|e| format!("Node task panicked: {}", e))?;
+
+            state.trace.add_episode(finished.episode);
+            match finished.result {
+                Ok(output) => {
+                    if let Some(node) = state.graph.get_node_mut(&finished.node_id) {
+                        node.status = TaskStatus::Completed;
+                    }
+                    state.outputs.insert(finished.node_id, output);
+                }
+                Err(e) => {
+                    warn!("Node {} failed: {}", finished.node_id, e);
+                    let node_id = finished.node_id;
+                    fail_node(&mut state.graph, &mut state.errors, node_id, e.to_string());
+                }
+            }
+        }
+
+        let awaiting_approval = self.suspend(&mut state, held);
+
+        // Dependencies that never completed (e.g. unknown ids) leave nodes pending
+        if awaiting_approval.is_none() {
+            for node in state.graph.nodes.values_mut() {
+                if node.status == TaskStatus::Pending {
+                    node.status = TaskStatus::Skipped;
+                }
+            }
+            // The goal is over; tools can drop what they kept for it
+            self.agent.registry().end_goal(&state.context.goal_id);
+        }
+
+        let success = state
+            .graph
+            .nodes
+            .values()
+            .all(|n| n.status == TaskStatus::Completed);
+
+        Ok(DagRun {
+            graph: state.graph,
+            trace: state.trace,
+            outputs: state.outputs,
+            errors: state.errors,
+            success,
+            awaiting_approval,
+        })
+    }
+
+    /// Start `node_id` as its own episode
+    fn spawn(
+        &self,
+        running: &mut JoinSet<NodeResult>,
+        state: &mut RunState,
+        node_id: Uuid,
+        tool_name: String,
+        mut input: ToolInput,
+    ) {
+        let Some(node) = state.graph.get_node_mut(&node_id) else {
+            return;
+        };
+        node.status = TaskStatus::InProgress;
+
+        state.step += 1;
+        let mut episode = Episode::new(state.context.goal_id, state.step, node.task.clone());
+        episode.metadata = serde_json::json!({ "node_id": node_id });
+        input.context.episode_id = Some(episode.id);
+
+        debug!("Running node {} with tool '{}'", node_id, tool_name);
+        running.spawn(run_node(
+            self.agent.clone(),
+            self.retry.clone(),
+            node_id,
+            tool_name,
+            input,
+            episode,
+        ));
+    }
+
+    /// File an approval request for the first held node, saving the graph with it
+    ///
+    /// Requests are filed one at a time so each carries the latest graph;
+    /// other held nodes go back to `Pending` and are checked again on resume.
+    fn suspend(&self, state: &mut RunState, held: Vec<(Uuid, String, ToolInput)>) -> Option<Uuid> {
+        let mut held = held.into_iter();
+        let (node_id, tool_name, input) = held.next()?;
+        let others: Vec<Uuid> = held.map(|(id, _, _)| id).collect();
+        for id in &others {
+            if let Some(node) = state.graph.get_node_mut(id) {
+                node.status = TaskStatus::Pending;
+            }
+        }
+
+        let checkpoint = GoalCheckpoint {
+            graph: Some(GraphCheckpoint {
+                graph: state.graph.clone(),
+                context: state.context.clone(),
+                outputs: state.outputs.clone(),
+                errors: state.errors.clone(),
+                node_id,
+                step: state.step,
+            }),
+            ..GoalCheckpoint::default()
+        };
+        match self.agent.approvals().request(&tool_name, input, checkpoint) {
+            Ok(request) => Some(request.id),
+            Err(e) => {
+                warn!("Filing approval for node {} failed: {}", node_id, e);
+                for id in std::iter::once(node_id).chain(others) {
+                    fail_node(&mut state.graph, &mut state.errors, id, e.to_string());
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Progress of one execution of a graph
+struct RunState {
+    graph: TaskGraph,
+    context: ExecutionContext,
+    trace: EpisodeTrace,
+    outputs: HashMap<Uuid, Value>,
+    errors: HashMap<Uuid, String>,
+    step: u32,
+}
+
+struct NodeResult {
+    node_id: Uuid,
+    episode: EpisodeV2,
+    result: Result<Value, ToolError>,
+}
+
+/// Run one node's admitted tool, retrying transient failures
+async fn run_node(
+    agent: Arc<AgentCore>,
+    retry: RetryConfig,
+    node_id: Uuid,
+    tool_name: String,
+    input: ToolInput,
+    episode: Episode,
+) -> NodeResult {
+    let attempts: Mutex<Vec<EpisodeAction>> = Mutex::new(Vec::new());
+    let last_error: Mutex<Option<ToolError>> = Mutex::new(None);
+
+    let outcome = retry_with_backoff(
+        || {
+            let agent = agent.clone();
+            let input = input.clone();
+            let (tool_name, attempts, last_error) = (&tool_name, &attempts, &last_error);
+            async move {
+                let start = Instant::now();
+                let params = input.params.clone();
+                match agent.run_tool(tool_name, input).await {
+                    Ok(log) => {
+                        let output = log.output.clone().unwrap_or(Value::Null);
+                        attempts.lock().unwrap().push(episode_action(log));
+                        Ok(Ok(output))
+                    }
+                    Err(e) => {
+                        attempts.lock().unwrap().push(EpisodeAction {
+                            id: Uuid::new_v4(),
+                            tool_name: tool_name.clone(),
+                            input: params,
+                            output: None,
+                            error: Some(e.to_string()),
+                            timestamp: chrono::Utc::now().timestamp(),
+                            execution_time_ms: start.elapsed().as_millis() as u64,
+                            success: false,
+                        });
+                        if is_transient(&e) {
+                            let message = e.to_string();
+                            *last_error.lock().unwrap() = Some(e);
+                            Err(SynapseNetError::Internal(message))
+                        } else {
+                            Ok(Err(e))
+                        }
+                    }
+                }
+            }
+        },
+        &retry,
+        &tool_name,
+    )
+    .await;
+
+    let result = match outcome {
+        Ok(result) => result,
+        Err(e) => Err(last_error
+            .into_inner()
+            .unwrap()
+            .unwrap_or_else(|| ToolError::ExecutionFailed(e.to_string()))),
+    };
+
+    let mut episode = EpisodeV2::from_episode(episode);
+    for action in attempts.into_inner().unwrap() {
+        episode.add_action(action);
+    }
+
+    NodeResult {
+        node_id,
+        episode,
+        result,
+    }
+}
+
+/// Failures worth another attempt
+///
+/// Only timeouts and network errors: a tool that failed while executing may
+/// already have had side effects, so running it again is not safe.
+fn is_transient(error: &ToolError) -> bool {
+    matches!(error, ToolError::Timeout | ToolError::NetworkError(_))
+}
+
+fn episode_action(log: ActionLog) -> EpisodeAction {
+    EpisodeAction {
+        id: log.id,
+        tool_name: log.tool_name,
+        input: log.input,
+        output: log.output,
+        error: log.error,
+        timestamp: log.timestamp,
+        execution_time_ms: log.execution_time_ms,
+        success: log.success,
+    }
+}
+
+/// Node params with parent outputs wired in
+fn build_params(node: &TaskNode, outputs: &HashMap<Uuid, Value>) -> Result<Value, ToolError> {
+    let mut params = node
+        .metadata
+        .get("params")
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}));
+    let Some(fields) = params.as_object_mut() else {
+        return Err(ToolError::InvalidInput(
+            "Node params must be a JSON object".to_string(),
+        ));
+    };
+
+    if let Some(inputs) = node.metadata.get("inputs").and_then(Value::as_object) {
+        for (name, source) in inputs {
+            let source = source.as_str().ok_or_else(|| {
+                ToolError::InvalidInput(format!("Input '{}' must be a string", name))
+            })?;
+            let (parent, pointer) = match source.split_once('/') {
+                Some((parent, rest)) => (parent, format!("/{}", rest)),
+                None => (source, String::new()),
+            };
+            let parent_id = Uuid::parse_str(parent).map_err(|_| {
+                ToolError::InvalidInput(format!("Input '{}' names unknown node {}", name, parent))
+            })?;
+            if !node.dependencies.contains(&parent_id) {
+                return Err(ToolError::InvalidInput(format!(
+                    "Input '{}' reads node {} which is not a dependency",
+                    name, parent_id
+                )));
+            }
+            let value = outputs
+                .get(&parent_id)
+                .and_then(|output| output.pointer(&pointer))
+                .ok_or_else(|| {
+                    ToolError::InvalidInput(format!("Input '{}' not found at {}", name, source))
+                })?;
+            fields.insert(name.clone(), value.clone());
+        }
+    }
+
+    let parent_outputs: serde_json::Map<String, Value> = node
+        .dependencies
+        .iter()
+        .filter_map(|id| {
+            outputs
+                .get(id)
+                .map(|output| (id.to_string(), output.clone()))
+        })
+        .collect();
+    if !parent_outputs.is_empty() {
+        fields
+            .entry("parent_outputs")
+            .or_insert(Value::Object(parent_outputs));
+    }
+
+    Ok(params)
+}
+
+/// Mark `node_id` failed and every node downstream of it skipped
+fn fail_node(
+    graph: &mut TaskGraph,
+    errors: &mut HashMap<Uuid, String>,
+    node_id: Uuid,
+    error: String,
+) {
+    if let Some(node) = graph.get_node_mut(&node_id) {
+        node.status = TaskStatus::Failed;
+    }
+    errors.insert(node_id, error);
+
+    let mut queue: VecDeque<Uuid> = graph
+        .edges
+        .get(&node_id)
+        .cloned()
+        .unwrap_or_default()
+        .into();
+    while let Some(child_id) = queue.pop_front() {
+        if let Some(child) = graph.get_node_mut(&child_id) {
+            if child.status == TaskStatus::Pending {
+                child.status = TaskStatus::Skipped;
+                queue.extend(graph.edges.get(&child_id).cloned().unwrap_or_default());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_api::*;
+    use crate::tool_registry::{ToolPolicy, ToolRegistry};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use synapsenet_ai::planner::NodeType;
+
+    fn output(result: Value) -> ToolOutput {
+        ToolOutput {
+            result,
+            metadata: ToolMetadata {
+                execution_time_ms: 1,
+                resources_used: ResourceUsage {
+                    cpu_ms: 0,
+                    memory_mb: 0,
+                    network_bytes: 0,
+                },
+                success: true,
+                error: None,
+            },
+        }
+    }
+
+    fn schema(name: &str) -> ToolSchema {
+        ToolSchema {
+            name: name.to_string(),
+            description: "Test".to_string(),
+            version: "1.0.0".to_string(),
+            parameters: vec![],
+            returns: ToolReturn {
+                return_type: "object".to_string(),
+                description: "Result".to_string(),
+            },
+        }
+    }
+
+    /// Echoes its params after a short sleep, tracking peak concurrency
+    struct EchoTool {
+        active: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn description(&self) -> &'static str {
+            "Echo params"
+        }
+
+        fn schema(&self) -> ToolSchema {
+            schema("echo")
+        }
+
+        async fn execute(&self, input: ToolInput) -> Result<ToolOutput, ToolError> {
+            let now = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            Ok(output(input.params))
+        }
+    }
+
+    /// Fails until it has been called `failures` times
+    struct FlakyTool {
+        calls: Arc<AtomicUsize>,
+        failures: usize,
+        /// Fail with a network error rather than an execution failure
+        transient: bool,
+    }
+
+    #[async_trait]
+    impl Tool for FlakyTool {
+        fn name(&self) -> &'static str {
+            "flaky"
+        }
+
+        fn description(&self) -> &'static str {
+            "Fails a few times"
+        }
+
+        fn schema(&self) -> ToolSchema {
+            schema("flaky")
+        }
+
+        async fn execute(&self, _input: ToolInput) -> Result<ToolOutput, ToolError> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(match self.transient {
+                    true => ToolError::NetworkError("connection reset".to_string()),
+                    false => ToolError::ExecutionFailed("disk full".to_string()),
+                });
+            }
+            Ok(output(serde_json::json!({ "value": 42 })))
+        }
+    }
+
+    fn context() -> ExecutionContext {
+        ExecutionContext {
+            goal_id: Uuid::new_v4(),
+            episode_id: None,
+            user_id: "test_user".to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn tool_node(task: &str, metadata: Value) -> TaskNode {
+        let mut node = TaskNode::new(task, NodeType::Leaf);
+        node.metadata = metadata;
+        node
+    }
+
+    fn executor(flaky_failures: usize) -> (DagExecutor, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        executor_with(flaky_failures, true)
+    }
+
+    fn executor_with(
+        flaky_failures: usize,
+        transient: bool,
+    ) -> (DagExecutor, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let peak = Arc::new(AtomicUsize::new(0));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let registry = Arc::new(ToolRegistry::new());
+        registry
+            .register(Box::new(EchoTool {
+                active: Arc::new(AtomicUsize::new(0)),
+                peak: peak.clone(),
+            }))
+            .unwrap();
+        registry
+            .register(Box::new(FlakyTool {
+                calls: calls.clone(),
+                failures: flaky_failures,
+                transient,
+            }))
+            .unwrap();
+
+        let retry = RetryConfig {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            multiplier: 2.0,
+        };
+        let executor =
+            DagExecutor::new(Arc::new(AgentCore::with_defaults(registry))).with_retry(retry);
+        (executor, peak, calls)
+    }
+
+    #[tokio::test]
+    async fn test_runs_independent_nodes_in_parallel() {
+        let (executor, peak, _) = executor(0);
+        let executor = executor.with_max_parallelism(2);
+
+        let mut graph = TaskGraph::new("fan out");
+        let root = graph.root;
+        for i in 0..4 {
+            let id = graph.add_node(tool_node(
+                &format!("branch {}", i),
+                serde_json::json!({ "tool": "echo", "params": { "branch": i } }),
+            ));
+            graph.add_edge(root, id);
+        }
+
+        let run = executor.execute(graph, context()).await.unwrap();
+
+        assert!(run.success);
+        assert_eq!(run.outputs.len(), 4);
+        assert_eq!(run.trace.episodes.len(), 4);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_passes_parent_outputs_to_children() {
+        let (executor, _, _) = executor(1);
+
+        let mut graph = TaskGraph::new("chain");
+        let root = graph.root;
+        let source = graph.add_node(tool_node("fetch", serde_json::json!({ "tool": "flaky" })));
+        let sink = graph.add_node(tool_node(
+            "use",
+            serde_json::json!({
+                "tool": "echo",
+                "inputs": { "answer": format!("{}/value", source) }
+            }),
+        ));
+        graph.add_edge(root, source);
+        graph.add_edge(source, sink);
+
+        let run = executor.execute(graph, context()).await.unwrap();
+
+        assert!(run.success);
+        let result = &run.outputs[&sink];
+        assert_eq!(result["answer"], 42);
+        assert_eq!(result["parent_outputs"][source.to_string()]["value"], 42);
+
+        // The retried node records both attempts in its episode
+        let fetch = run
+            .trace
+            .episodes
+            .iter()
+            .find(|e| e.base.query == "fetch")
+            .unwrap();
+        assert_eq!(fetch.actions.len(), 2);
+        assert!(!fetch.actions[0].success);
+        assert!(fetch.actions[1].success);
+    }
+
+    #[tokio::test]
+    async fn test_failure_skips_descendants_only() {
+        let (executor, _, calls) = executor(usize::MAX);
+
+        let mut graph = TaskGraph::new("partial");
+        let root = graph.root;
+        let failing = graph.add_node(tool_node("fails", serde_json::json!({ "tool": "flaky" })));
+        let blocked = graph.add_node(tool_node("blocked", serde_json::json!({ "tool": "echo" })));
+        let independent = graph.add_node(tool_node(
+            "independent",
+            serde_json::json!({ "tool": "echo" }),
+        ));
+        graph.add_edge(root, failing);
+        graph.add_edge(failing, blocked);
+        graph.add_edge(root, independent);
+
+        let run = executor.execute(graph, context()).await.unwrap();
+
+        assert!(!run.success);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(run.graph.nodes[&failing].status, TaskStatus::Failed);
+        assert_eq!(run.graph.nodes[&blocked].status, TaskStatus::Skipped);
+        assert_eq!(run.graph.nodes[&independent].status, TaskStatus::Completed);
+        assert!(run.errors[&failing].contains("connection reset"));
+    }
+
+    #[tokio::test]
+    async fn test_execution_failures_are_not_retried() {
+        let (executor, _, calls) = executor_with(usize::MAX, false);
+
+        let mut graph = TaskGraph::new("side effects");
+        let root = graph.root;
+        let failing = graph.add_node(tool_node("write", serde_json::json!({ "tool": "flaky" })));
+        graph.add_edge(root, failing);
+
+        let run = executor.execute(graph, context()).await.unwrap();
+
+        assert!(!run.success);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(run.errors[&failing].contains("disk full"));
+    }
+
+    /// Graph `fetch -> gated echo -> after`, with echo requiring approval
+    fn gated_graph(executor: &DagExecutor) -> (TaskGraph, Uuid, Uuid, Uuid) {
+        executor
+            .agent
+            .registry()
+            .update_policy(
+                "echo",
+                ToolPolicy {
+                    requires_approval: true,
+                    ..ToolPolicy::default()
+                },
+            )
+            .unwrap();
+
+        let mut graph = TaskGraph::new("gated");
+        let root = graph.root;
+        let fetch = graph.add_node(tool_node("fetch", serde_json::json!({ "tool": "flaky" })));
+        let gated = graph.add_node(tool_node(
+            "publish",
+            serde_json::json!({
+                "tool": "echo",
+                "inputs": { "answer": format!("{}/value", fetch) }
+            }),
+        ));
+        let after = graph.add_node(tool_node("after", serde_json::json!({ "tool": "flaky" })));
+        graph.add_edge(root, fetch);
+        graph.add_edge(fetch, gated);
+        graph.add_edge(gated, after);
+        (graph, fetch, gated, after)
+    }
+
+    #[tokio::test]
+    async fn test_suspends_on_approval_and_resumes() {
+        let (executor, _, calls) = executor(0);
+        let (graph, fetch, gated, after) = gated_graph(&executor);
+
+        let run = executor.execute(graph, context()).await.unwrap();
+        let approval_id = run.awaiting_approval.expect("graph should suspend");
+        assert!(!run.success);
+        assert_eq!(run.graph.nodes[&fetch].status, TaskStatus::Completed);
+        assert_eq!(run.graph.nodes[&gated].status, TaskStatus::InProgress);
+        assert_eq!(run.graph.nodes[&after].status, TaskStatus::Pending);
+
+        let approvals = executor.agent.approvals();
+        let request = approvals.get(&approval_id).unwrap();
+        assert_eq!(request.input.params["answer"], 42);
+        assert!(matches!(
+            executor.agent.resume_goal(&approval_id).await,
+            Err(ApprovalError::TaskGraph(_))
+        ));
+        assert!(matches!(
+            executor.resume(&approval_id).await,
+            Err(ApprovalError::Pending(_))
+        ));
+
+        approvals.approve(&approval_id, "reviewer").unwrap();
+        let run = executor.resume(&approval_id).await.unwrap();
+        assert!(run.success);
+        assert!(run.awaiting_approval.is_none());
+        assert_eq!(run.outputs[&gated]["answer"], 42);
+        assert_eq!(run.graph.nodes[&after].status, TaskStatus::Completed);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        assert!(matches!(
+            executor.resume(&approval_id).await,
+            Err(ApprovalError::AlreadyResumed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejection_fails_the_held_node() {
+        let (executor, _, _) = executor(0);
+        let (graph, _, gated, after) = gated_graph(&executor);
+
+        let run = executor.execute(graph, context()).await.unwrap();
+        let approval_id = run.awaiting_approval.unwrap();
+        assert!(executor.resume_decided().await.unwrap().is_empty());
+
+        executor
+            .agent
+            .approvals()
+            .reject(&approval_id, "reviewer", "not today")
+            .unwrap();
+        let runs = executor.resume_decided().await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert!(!runs[0].success);
+        assert_eq!(runs[0].graph.nodes[&gated].status, TaskStatus::Failed);
+        assert_eq!(runs[0].graph.nodes[&after].status, TaskStatus::Skipped);
+        assert!(runs[0].errors[&gated].contains("not today"));
+        assert!(executor.resume_decided().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,365 @@
+//! Isolation - Landlock, seccomp and rlimit confinement for tool processes
+//!
+//! Everything that allocates (opening rule paths, building the seccomp
+//! program) happens in `Confinement::prepare` before the fork; `apply` only
+//! issues syscalls so it is safe to call from `pre_exec`.
+
+use std::ffi::CString;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use crate::sandbox::SandboxConfig;
+
+// Landlock filesystem rights (ABI 1-5)
+const ACCESS_FS_EXECUTE: u64 = 1 << 0;
+const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+const ACCESS_FS_REFER: u64 = 1 << 13;
+const ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+const ACCESS_FS_IOCTL_DEV: u64 = 1 << 15;
+const ACCESS_FS_ABI1: u64 = (1 << 13) - 1;
+const ACCESS_FILE: u64 = ACCESS_FS_EXECUTE
+    | ACCESS_FS_WRITE_FILE
+    | ACCESS_FS_READ_FILE
+    | ACCESS_FS_TRUNCATE
+    | ACCESS_FS_IOCTL_DEV;
+
+// Landlock scopes (ABI 6)
+const SCOPE_ABSTRACT_UNIX_SOCKET: u64 = 1 << 0;
+const SCOPE_SIGNAL: u64 = 1 << 1;
+
+const CREATE_RULESET_VERSION: u32 = 1 << 0;
+const RULE_PATH_BENEATH: u32 = 1;
+
+// seccomp return actions
+const RET_KILL_PROCESS: u32 = 0x8000_0000;
+const RET_ERRNO: u32 = 0x0005_0000;
+const RET_ALLOW: u32 = 0x7fff_0000;
+
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xc000_003e;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xc000_00b7;
+
+/// Device files tool processes may read and write
+const DEVICES: &[&str] = &["/dev/null", "/dev/zero", "/dev/random", "/dev/urandom"];
+
+/// Syscalls that could leave the confinement or reach into the kernel
+const DENIED_SYSCALLS: &[libc::c_long] = &[
+    libc::SYS_ptrace,
+    libc::SYS_mount,
+    libc::SYS_umount2,
+    libc::SYS_pivot_root,
+    libc::SYS_chroot,
+    libc::SYS_unshare,
+    libc::SYS_setns,
+    libc::SYS_setsid,
+    libc::SYS_setpgid,
+    libc::SYS_bpf,
+    libc::SYS_perf_event_open,
+    libc::SYS_userfaultfd,
+    libc::SYS_io_uring_setup,
+    libc::SYS_keyctl,
+    libc::SYS_add_key,
+    libc::SYS_request_key,
+    libc::SYS_init_module,
+    libc::SYS_finit_module,
+    libc::SYS_delete_module,
+    libc::SYS_kexec_load,
+    libc::SYS_reboot,
+    libc::SYS_swapon,
+    libc::SYS_swapoff,
+];
+
+/// Socket families tool processes may open, and only with network access
+///
+/// Every other family is refused: Landlock does not cover `connect()` to a
+/// Unix socket path, so `AF_UNIX` would reach the node's key agent.
+const NETWORK_FAMILIES: &[libc::c_int] = &[libc::AF_INET, libc::AF_INET6];
+
+#[repr(C)]
+struct RulesetAttr {
+    handled_access_fs: u64,
+    handled_access_net: u64,
+    scoped: u64,
+}
+
+#[repr(C, packed)]
+struct PathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: i32,
+}
+
+/// Landlock ABI version of the running kernel, `None` if unsupported
+pub(crate) fn landlock_abi() -> Option<i64> {
+    // SAFETY: querying the ABI version takes no attribute pointer
+    let abi = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            std::ptr::null::<RulesetAttr>(),
+            0usize,
+            CREATE_RULESET_VERSION,
+        )
+    };
+    (abi >= 1).then_some(abi)
+}
+
+/// Prepared confinement for one tool process
+pub(crate) struct Confinement {
+    ruleset: OwnedFd,
+    filter: Vec<libc::sock_filter>,
+    cpu_secs: libc::rlim_t,
+    data_bytes: libc::rlim_t,
+    file_bytes: libc::rlim_t,
+}
+
+impl Confinement {
+    /// Build the Landlock ruleset, seccomp program and rlimits for `config`
+    ///
+    /// `work_dir` is writable; `read_only` paths may be read and executed.
+    pub(crate) fn prepare(
+        config: &SandboxConfig,
+        work_dir: &Path,
+        read_only: &[PathBuf],
+    ) -> io::Result<Self> {
+        let abi = landlock_abi().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Unsupported, "Landlock is not available")
+        })?;
+
+        let mut handled_fs = ACCESS_FS_ABI1;
+        if abi >= 2 {
+            handled_fs |= ACCESS_FS_REFER;
+        }
+        if abi >= 3 {
+            handled_fs |= ACCESS_FS_TRUNCATE;
+        }
+        if abi >= 5 {
+            handled_fs |= ACCESS_FS_IOCTL_DEV;
+        }
+
+        let attr = RulesetAttr {
+            handled_access_fs: handled_fs,
+            handled_access_net: 0,
+            scoped: if abi >= 6 {
+                SCOPE_ABSTRACT_UNIX_SOCKET | SCOPE_SIGNAL
+            } else {
+                0
+            },
+        };
+        let attr_size = if abi >= 6 {
+            std::mem::size_of::<RulesetAttr>()
+        } else {
+            std::mem::size_of::<u64>()
+        };
+
+        // SAFETY: attr is a valid ruleset attribute of at least attr_size bytes
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                &attr as *const RulesetAttr,
+                attr_size,
+                0u32,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the kernel just returned this descriptor to us
+        let ruleset = unsafe { OwnedFd::from_raw_fd(fd as i32) };
+
+        let read_access = ACCESS_FS_EXECUTE | ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+        for path in read_only {
+            allow_path(&ruleset, path, read_access & handled_fs)?;
+        }
+        for device in DEVICES {
+            allow_path(
+                &ruleset,
+                Path::new(device),
+                (ACCESS_FS_READ_FILE | ACCESS_FS_WRITE_FILE) & handled_fs,
+            )?;
+        }
+        allow_path(&ruleset, work_dir, handled_fs)?;
+
+        let mb = |value: u64| value.saturating_mul(1024 * 1024);
+
+        Ok(Self {
+            ruleset,
+            filter: seccomp_filter(config.network_allowed),
+            cpu_secs: config.cpu_limit_ms.div_ceil(1000).max(1),
+            data_bytes: mb(config.memory_limit_mb),
+            file_bytes: mb(config.max_file_size_mb),
+        })
+    }
+
+    /// Confine the calling process; runs in the child between fork and exec
+    pub(crate) fn apply(&self) -> io::Result<()> {
+        // The resource parameter type differs between libcs
+        macro_rules! set_limit {
+            ($resource:expr, $soft:expr, $hard:expr) => {
+                check(libc::setrlimit(
+                    $resource,
+                    &libc::rlimit {
+                        rlim_cur: $soft,
+                        rlim_max: $hard,
+                    },
+                ))
+            };
+        }
+
+        // SAFETY: plain syscalls on memory owned by self, no allocation
+        unsafe {
+            // Own process group so a timeout can kill every descendant
+            check(libc::setpgid(0, 0))?;
+
+            // SIGXCPU at the soft CPU limit, SIGKILL one second later
+            set_limit!(libc::RLIMIT_CPU, self.cpu_secs, self.cpu_secs + 1)?;
+            set_limit!(libc::RLIMIT_DATA, self.data_bytes, self.data_bytes)?;
+            set_limit!(libc::RLIMIT_FSIZE, self.file_bytes, self.file_bytes)?;
+            set_limit!(libc::RLIMIT_CORE, 0, 0)?;
+
+            check(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0))?;
+
+            if libc::syscall(
+                libc::SYS_landlock_restrict_self,
+                self.ruleset.as_raw_fd(),
+                0u32,
+            ) < 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+
+            let program = libc::sock_fprog {
+                len: self.filter.len() as u16,
+                filter: self.filter.as_ptr() as *mut libc::sock_filter,
+            };
+            check(libc::prctl(
+                libc::PR_SET_SECCOMP,
+                libc::SECCOMP_MODE_FILTER,
+                &program as *const libc::sock_fprog,
+                0,
+                0,
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+fn check(result: libc::c_int) -> io::Result<()> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Add a path-beneath rule; paths missing on this system are skipped
+fn allow_path(ruleset: &OwnedFd, path: &Path, access: u64) -> io::Result<()> {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return Ok(());
+    };
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    // SAFETY: c_path is a valid NUL-terminated string
+    let fd = unsafe { libc::open(c_path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: open just returned this descriptor
+    let parent = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    // Directory-only rights are rejected on file rules
+    let allowed_access = if metadata.is_dir() {
+        access
+    } else {
+        access & ACCESS_FILE
+    };
+    let rule = PathBeneathAttr {
+        allowed_access,
+        parent_fd: parent.as_raw_fd(),
+    };
+
+    // SAFETY: rule is a valid path-beneath attribute for this call
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_add_rule,
+            ruleset.as_raw_fd(),
+            RULE_PATH_BENEATH,
+            &rule as *const PathBeneathAttr,
+            0u32,
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Classic BPF program denying `DENIED_SYSCALLS` and every socket outside
+/// `NETWORK_FAMILIES`, those too without network access
+fn seccomp_filter(network_allowed: bool) -> Vec<libc::sock_filter> {
+    const NR: u32 = 0;
+    const ARCH: u32 = 4;
+    const ARG0: u32 = 16;
+
+    let load = |offset| bpf_stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, offset);
+    let deny = bpf_stmt(libc::BPF_RET | libc::BPF_K, RET_ERRNO | libc::EPERM as u32);
+    let jeq =
+        |value: u32, jt, jf| bpf_jump(libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K, value, jt, jf);
+
+    let mut filter = vec![
+        load(ARCH),
+        jeq(AUDIT_ARCH, 1, 0),
+        bpf_stmt(libc::BPF_RET | libc::BPF_K, RET_KILL_PROCESS),
+        load(NR),
+    ];
+
+    // x32 syscalls share the x86_64 audit arch
+    #[cfg(target_arch = "x86_64")]
+    filter.extend([
+        bpf_jump(
+            libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K,
+            0x4000_0000,
+            0,
+            1,
+        ),
+        bpf_stmt(libc::BPF_RET | libc::BPF_K, RET_KILL_PROCESS),
+    ]);
+
+    for &nr in DENIED_SYSCALLS {
+        filter.extend([jeq(nr as u32, 0, 1), deny]);
+    }
+
+    // Allowed families jump past the refusal to the final allow
+    let families: &[libc::c_int] = if network_allowed { NETWORK_FAMILIES } else { &[] };
+    let socket_checks = 2 + families.len() as u8;
+    filter.extend([jeq(libc::SYS_socket as u32, 0, socket_checks), load(ARG0)]);
+    for (i, &family) in families.iter().enumerate() {
+        filter.push(jeq(family as u32, (families.len() - i) as u8, 0));
+    }
+    filter.push(bpf_stmt(libc::BPF_RET | libc::BPF_K, RET_ERRNO | libc::EACCES as u32));
+
+    filter.push(bpf_stmt(libc::BPF_RET | libc::BPF_K, RET_ALLOW));
+    filter
+}
+
+fn bpf_stmt(code: u32, k: u32) -> libc::sock_filter {
+    libc::sock_filter {
+        code: code as u16,
+        jt: 0,
+        jf: 0,
+        k,
+    }
+}
+
+fn bpf_jump(code: u32, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+    libc::sock_filter {
+        code: code as u16,
+        jt,
+        jf,
+        k,
+    }
+}
@@ -1,14 +1,31 @@
 //! Agent crate - Tool execution and action management
 
 pub mod agent_core;
+pub mod approval;
+pub mod dag_executor;
+#[cfg(target_os = "linux")]
+mod isolation;
 pub mod sandbox;
 pub mod tool_api;
 pub mod tool_registry;
+pub mod wasm_tools;
 
 pub use agent_core::{ActionLog, ActionTrace, AgentCore};
-pub use sandbox::{Sandbox, SandboxBuilder, SandboxConfig, SandboxError, SandboxLimits};
+pub use approval::{
+    ApprovalError, ApprovalQueue, ApprovalRequest, ApprovalStatus, GoalCheckpoint,
+    DEFAULT_APPROVAL_TIMEOUT,
+};
+pub use dag_executor::{DagExecutor, DagRun, GraphCheckpoint, DEFAULT_MAX_PARALLELISM};
+pub use sandbox::{
+    CommandOutput, Sandbox, SandboxBuilder, SandboxCommand, SandboxConfig, SandboxError,
+    SandboxLimits, SandboxResult,
+};
 pub use tool_api::{
     ExecutionContext, Tool, ToolError, ToolInfo, ToolInput, ToolMetadata, ToolOutput,
     ToolParameter, ToolReturn, ToolSchema, ResourceUsage,
 };
 pub use tool_registry::{RateLimit, ToolPolicy, ToolRegistry};
+pub use wasm_tools::{
+    GrainQuery, HttpAccess, HttpClient, PluginCapabilities, PluginCapability, PluginManifest,
+    WasmTool, WasmToolHost,
+};
@@ -1,7 +1,14 @@
 //! Sandbox - Isolated execution environment with resource limits
+//!
+//! In-process closures only get a timeout. Tool processes started with
+//! `Sandbox::run_command` are confined by the OS on Linux: Landlock limits
+//! the filesystem to `file_access_path` plus read-only system paths, seccomp
+//! blocks all sockets but IP ones, and those too unless `network_allowed`,
+//! along with namespace/kernel syscalls, and rlimits cap CPU, memory and file
+//! size.
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
 
@@ -13,6 +20,9 @@ pub struct SandboxConfig {
     pub network_allowed: bool,
     pub file_access_path: PathBuf,
     pub max_file_size_mb: u64,
+    /// Paths tool processes may read and execute besides `file_access_path`
+    #[serde(default = "default_read_only_paths")]
+    pub read_only_paths: Vec<PathBuf>,
 }
 
 impl Default for SandboxConfig {
@@ -23,16 +33,41 @@ impl Default for SandboxConfig {
             network_allowed: false,
             file_access_path: PathBuf::from("./capsule/work"),
             max_file_size_mb: 10,
+            read_only_paths: default_read_only_paths(),
         }
     }
 }
 
+/// System libraries and interpreters, without the rest of `/etc`
+fn default_read_only_paths() -> Vec<PathBuf> {
+    [
+        "/usr",
+        "/lib",
+        "/lib64",
+        "/lib32",
+        "/bin",
+        "/sbin",
+        "/etc/alternatives",
+        "/etc/ld.so.cache",
+        "/etc/ld.so.conf",
+        "/etc/ld.so.conf.d",
+        "/etc/localtime",
+        "/etc/ssl",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect()
+}
+
 /// Resource limits for execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SandboxLimits {
     pub cpu_ms: u64,
     pub mem_mb: u64,
     pub max_steps: u32,
+    /// Wall-clock time for one call, blocking host calls included
+    #[serde(default = "default_wall_ms")]
+    pub wall_ms: u64,
 }
 
 impl Default for SandboxLimits {
@@ -41,18 +76,88 @@ impl Default for SandboxLimits {
             cpu_ms: 200,
             mem_mb: 64,
             max_steps: 100,
+            wall_ms: default_wall_ms(),
         }
     }
 }
 
+fn default_wall_ms() -> u64 {
+    5_000
+}
+
 /// Sandbox execution result
 #[derive(Debug)]
 pub struct SandboxResult<T> {
     pub value: T,
     pub execution_time_ms: u64,
+    /// User + system CPU time; only measured for `run_command`
+    pub cpu_time_ms: u64,
+    /// Peak resident memory; only measured for `run_command`
     pub memory_used_mb: u64,
 }
 
+/// Process to run under OS isolation
+#[derive(Debug, Clone)]
+pub struct SandboxCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    /// Wall-clock limit; the process group is killed when it expires
+    pub timeout: Duration,
+    /// Bytes kept from each of stdout and stderr
+    pub max_output_bytes: usize,
+}
+
+impl SandboxCommand {
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            env: Vec::new(),
+            timeout: Duration::from_secs(10),
+            max_output_bytes: 1024 * 1024,
+        }
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn max_output_bytes(mut self, limit: usize) -> Self {
+        self.max_output_bytes = limit;
+        self
+    }
+}
+
+/// Output of a sandboxed process
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    /// Exit code, `None` if the process was killed by a signal
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// Output went past `max_output_bytes` and was cut
+    pub truncated: bool,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
 /// Sandbox errors
 #[derive(Debug, Clone)]
 pub enum SandboxError {
@@ -79,6 +184,21 @@ impl std::fmt::Display for SandboxError {
 
 impl std::error::Error for SandboxError {}
 
+impl From<SandboxError> for crate::tool_api::ToolError {
+    fn from(error: SandboxError) -> Self {
+        match error {
+            SandboxError::Timeout => Self::Timeout,
+            SandboxError::MemoryLimitExceeded | SandboxError::CpuLimitExceeded => {
+                Self::ResourceLimitExceeded
+            }
+            SandboxError::FileAccessDenied(_) | SandboxError::NetworkAccessDenied => {
+                Self::SandboxViolation(error.to_string())
+            }
+            SandboxError::ExecutionFailed(msg) => Self::ExecutionFailed(msg),
+        }
+    }
+}
+
 /// Sandbox for isolated execution
 pub struct Sandbox {
     config: SandboxConfig,
@@ -112,13 +232,13 @@ impl Sandbox {
 
         let execution_time_ms = start.elapsed().as_millis() as u64;
 
-        // TODO: Actual memory tracking
-        let memory_used_mb = 0;
-
+        // Closures share the node's process, so there is nothing separate
+        // to account; run_command measures tool processes
         Ok(SandboxResult {
             value: result,
             execution_time_ms,
-            memory_used_mb,
+            cpu_time_ms: 0,
+            memory_used_mb: 0,
         })
     }
 
@@ -137,15 +257,144 @@ impl Sandbox {
             .map_err(|_| SandboxError::Timeout)?;
 
         let execution_time_ms = start.elapsed().as_millis() as u64;
-        let memory_used_mb = 0;
 
         Ok(SandboxResult {
             value: result,
             execution_time_ms,
+            cpu_time_ms: 0,
+            memory_used_mb: 0,
+        })
+    }
+
+    /// Whether `run_command` can confine processes on this system
+    pub fn isolation_available() -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            crate::isolation::landlock_abi().is_some()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
+    }
+
+    /// Run a process confined to this sandbox
+    ///
+    /// The process runs in `file_access_path` with a cleared environment.
+    /// Without OS support for isolation this fails instead of running the
+    /// process unconfined.
+    #[cfg(target_os = "linux")]
+    pub async fn run_command(
+        &self,
+        command: SandboxCommand,
+    ) -> Result<SandboxResult<CommandOutput>, SandboxError> {
+        use std::os::unix::process::CommandExt;
+        use std::process::{Command, Stdio};
+
+        let start = Instant::now();
+
+        std::fs::create_dir_all(&self.config.file_access_path)
+            .map_err(|e| SandboxError::FileAccessDenied(e.to_string()))?;
+        let work_dir = self
+            .config
+            .file_access_path
+            .canonicalize()
+            .map_err(|e| SandboxError::FileAccessDenied(e.to_string()))?;
+
+        let search_path = std::env::var_os("PATH").unwrap_or_default();
+        let program = resolve_program(&command.program, &search_path).ok_or_else(|| {
+            SandboxError::ExecutionFailed(format!("Program not found: {}", command.program))
+        })?;
+
+        // The program's own install prefix, e.g. /usr or ~/.pyenv
+        let mut read_only = self.config.read_only_paths.clone();
+        read_only.extend(install_prefix(&program));
+
+        let confinement =
+            crate::isolation::Confinement::prepare(&self.config, &work_dir, &read_only)
+                .map_err(|e| SandboxError::ExecutionFailed(format!("Isolation failed: {}", e)))?;
+
+        let mut process = Command::new(&program);
+        process
+            .args(&command.args)
+            .env_clear()
+            .env("PATH", &search_path)
+            .env("HOME", &work_dir)
+            .env("TMPDIR", &work_dir)
+            .env("LANG", "C.UTF-8")
+            .envs(command.env.iter().map(|(k, v)| (k, v)))
+            .current_dir(&work_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // SAFETY: apply only issues syscalls on memory prepared before fork
+        unsafe {
+            process.pre_exec(move || confinement.apply());
+        }
+
+        let child = process
+            .spawn()
+            .map_err(|e| SandboxError::ExecutionFailed(format!("Failed to spawn process: {}", e)))?;
+        let pid = child.id() as libc::pid_t;
+
+        let limit = command.max_output_bytes;
+        let mut waiter = tokio::task::spawn_blocking(move || wait_for_child(child, limit));
+
+        let finished = match timeout(command.timeout, &mut waiter).await {
+            Ok(finished) => finished,
+            Err(_) => {
+                // SAFETY: the child is its own process group leader
+                unsafe {
+                    libc::kill(-pid, libc::SIGKILL);
+                }
+                let _ = waiter.await;
+                return Err(SandboxError::Timeout);
+            }
+        };
+        let finished = finished
+            .map_err(|e| SandboxError::ExecutionFailed(e.to_string()))?
+            .map_err(|e| SandboxError::ExecutionFailed(e.to_string()))?;
+
+        let cpu_time_ms = timeval_ms(finished.usage.ru_utime) + timeval_ms(finished.usage.ru_stime);
+        let memory_used_mb = (finished.usage.ru_maxrss as u64).div_ceil(1024);
+
+        let status = finished.status;
+        let signal = libc::WIFSIGNALED(status).then(|| libc::WTERMSIG(status));
+        if matches!(signal, Some(libc::SIGXCPU))
+            || (signal == Some(libc::SIGKILL) && cpu_time_ms >= self.config.cpu_limit_ms)
+        {
+            return Err(SandboxError::CpuLimitExceeded);
+        }
+
+        Ok(SandboxResult {
+            value: CommandOutput {
+                exit_code: libc::WIFEXITED(status).then(|| libc::WEXITSTATUS(status)),
+                signal,
+                stdout: String::from_utf8_lossy(&finished.stdout.bytes).to_string(),
+                stderr: String::from_utf8_lossy(&finished.stderr.bytes).to_string(),
+                truncated: finished.stdout.truncated || finished.stderr.truncated,
+            },
+            execution_time_ms: start.elapsed().as_millis() as u64,
+            cpu_time_ms,
             memory_used_mb,
         })
     }
 
+    /// Run a process confined to this sandbox
+    ///
+    /// OS isolation is only implemented on Linux; elsewhere this refuses to
+    /// run the process unconfined.
+    #[cfg(not(target_os = "linux"))]
+    pub async fn run_command(
+        &self,
+        command: SandboxCommand,
+    ) -> Result<SandboxResult<CommandOutput>, SandboxError> {
+        Err(SandboxError::ExecutionFailed(format!(
+            "Process isolation is not supported on this platform: {}",
+            command.program
+        )))
+    }
+
     /// Check if file access is allowed
     pub fn check_file_access(&self, path: &PathBuf) -> Result<(), SandboxError> {
         let canonical_base = self.config.file_access_path.canonicalize()
@@ -177,6 +426,109 @@ impl Sandbox {
     }
 }
 
+/// Captured stream of a sandboxed process
+#[cfg(target_os = "linux")]
+struct Captured {
+    bytes: Vec<u8>,
+    truncated: bool,
+}
+
+/// Exit status and resource usage of a reaped process
+#[cfg(target_os = "linux")]
+struct Finished {
+    status: libc::c_int,
+    usage: libc::rusage,
+    stdout: Captured,
+    stderr: Captured,
+}
+
+/// Drain the child's output, kill its process group and reap it with its
+/// resource usage
+#[cfg(target_os = "linux")]
+fn wait_for_child(mut child: std::process::Child, limit: usize) -> std::io::Result<Finished> {
+    let stdout = child.stdout.take().map(|pipe| capture(pipe, limit));
+    let stderr = child.stderr.take().map(|pipe| capture(pipe, limit));
+
+    let pid = child.id() as libc::pid_t;
+    let mut status = 0;
+    // SAFETY: plain data the kernel fills in
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+
+    // SAFETY: waiting on our own child, which std has not reaped. The
+    // leader stays a zombie until wait4, so its process group id cannot be
+    // reused while we kill whatever it left behind.
+    unsafe {
+        let options = libc::WEXITED | libc::WNOWAIT;
+        if libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, options) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        libc::kill(-pid, libc::SIGKILL);
+        if libc::wait4(pid, &mut status, 0, &mut usage) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+
+    let join = |handle: Option<std::thread::JoinHandle<Captured>>| {
+        handle.and_then(|h| h.join().ok()).unwrap_or(Captured {
+            bytes: Vec::new(),
+            truncated: false,
+        })
+    };
+
+    Ok(Finished {
+        status,
+        usage,
+        stdout: join(stdout),
+        stderr: join(stderr),
+    })
+}
+
+/// Read up to `limit` bytes, then discard the rest so the child never blocks
+#[cfg(target_os = "linux")]
+fn capture<R: std::io::Read + Send + 'static>(
+    pipe: R,
+    limit: usize,
+) -> std::thread::JoinHandle<Captured> {
+    std::thread::spawn(move || {
+        use std::io::Read;
+
+        let mut bytes = Vec::new();
+        let mut pipe = pipe;
+        let _ = (&mut pipe).take(limit as u64).read_to_end(&mut bytes);
+        let rest = std::io::copy(&mut pipe, &mut std::io::sink()).unwrap_or(0);
+        Captured {
+            bytes,
+            truncated: rest > 0,
+        }
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn timeval_ms(time: libc::timeval) -> u64 {
+    time.tv_sec as u64 * 1000 + time.tv_usec as u64 / 1000
+}
+
+/// Find `program` on `search_path` unless it is already a path
+fn resolve_program(program: &str, search_path: &std::ffi::OsStr) -> Option<PathBuf> {
+    if program.contains('/') {
+        return Path::new(program).canonicalize().ok();
+    }
+    std::env::split_paths(search_path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Install prefix of a program, e.g. `/usr` for `/usr/bin/python3`
+fn install_prefix(program: &Path) -> Option<PathBuf> {
+    let program = program.canonicalize().ok()?;
+    let dir = program.parent()?;
+    match dir.file_name()?.to_str()? {
+        "bin" | "sbin" | "shims" => dir.parent().map(Path::to_path_buf),
+        _ => Some(dir.to_path_buf()),
+    }
+}
+
 /// Sandbox builder for custom configuration
 pub struct SandboxBuilder {
     config: SandboxConfig,
@@ -209,6 +561,11 @@ impl SandboxBuilder {
         self
     }
 
+    pub fn read_only_path(mut self, path: PathBuf) -> Self {
+        self.config.read_only_paths.push(path);
+        self
+    }
+
     pub fn build(self) -> Sandbox {
         Sandbox::new(self.config)
     }
@@ -284,6 +641,212 @@ mod tests {
         assert!(sandbox.check_network_access().is_ok());
     }
 
+    fn isolated_sandbox(work: &Path) -> Sandbox {
+        SandboxBuilder::new()
+            .cpu_limit_ms(1000)
+            .file_access_path(work.to_path_buf())
+            .build()
+    }
+
+    fn shell(script: &str) -> SandboxCommand {
+        SandboxCommand::new("sh").arg("-c").arg(script)
+    }
+
+    fn python(code: &str) -> SandboxCommand {
+        SandboxCommand::new("python3").arg("-c").arg(code)
+    }
+
+    /// Fail instead of passing vacuously on a kernel without Landlock
+    fn require_isolation() {
+        assert!(
+            Sandbox::isolation_available(),
+            "Landlock is unavailable; isolation tests need Linux 5.13+ with Landlock enabled"
+        );
+    }
+
+    fn require_python() {
+        assert!(
+            resolve_program("python3", &std::env::var_os("PATH").unwrap_or_default()).is_some(),
+            "python3 must be on PATH for this test"
+        );
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(target_os = "linux"), ignore = "process isolation needs Linux")]
+    async fn test_command_confined_to_work_dir() {
+        require_isolation();
+        let work = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("secret.txt");
+        std::fs::write(&secret, "secret").unwrap();
+        let sandbox = isolated_sandbox(work.path());
+
+        let result = sandbox.run_command(shell("echo hi > out.txt && cat out.txt")).await.unwrap();
+        assert!(result.value.success());
+        assert_eq!(result.value.stdout.trim(), "hi");
+        assert!(work.path().join("out.txt").exists());
+
+        // Reading, writing and listing outside the work dir all fail
+        let read = sandbox.run_command(shell(&format!("cat {}", secret.display()))).await.unwrap();
+        assert!(!read.value.success());
+        assert!(!read.value.stdout.contains("secret"));
+
+        let escape = outside.path().join("escape.txt");
+        let write = sandbox
+            .run_command(shell(&format!("echo x > {}", escape.display())))
+            .await
+            .unwrap();
+        assert!(!write.value.success());
+        assert!(!escape.exists());
+
+        let list = sandbox.run_command(shell("ls /root /home /etc/passwd")).await.unwrap();
+        assert!(!list.value.success());
+
+        // Symlinks do not help either
+        let link = sandbox
+            .run_command(shell(&format!("ln -s {} link && cat link", secret.display())))
+            .await
+            .unwrap();
+        assert!(!link.value.stdout.contains("secret"));
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(target_os = "linux"), ignore = "process isolation needs Linux")]
+    async fn test_command_escape_syscalls_denied() {
+        require_isolation();
+        let work = tempfile::tempdir().unwrap();
+        let sandbox = isolated_sandbox(work.path());
+
+        for script in ["unshare -r true", "unshare -n true", "setsid true"] {
+            let result = sandbox.run_command(shell(script)).await.unwrap();
+            assert!(!result.value.success(), "{} should fail", script);
+        }
+
+        // Environment of the node does not leak in
+        let env = sandbox.run_command(SandboxCommand::new("env")).await.unwrap();
+        assert!(!env.value.stdout.contains("CARGO"));
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(target_os = "linux"), ignore = "process isolation needs Linux")]
+    async fn test_command_network_denied() {
+        require_isolation();
+        require_python();
+        let work = tempfile::tempdir().unwrap();
+        let code = "import socket; socket.socket(socket.AF_INET); print('open')";
+
+        let denied = isolated_sandbox(work.path()).run_command(python(code)).await.unwrap();
+        assert!(!denied.value.success());
+        assert!(denied.value.stderr.contains("PermissionError"));
+
+        let allowed = SandboxBuilder::new()
+            .cpu_limit_ms(1000)
+            .allow_network(true)
+            .file_access_path(work.path().to_path_buf())
+            .build()
+            .run_command(python(code))
+            .await
+            .unwrap();
+        assert!(allowed.value.success());
+        assert_eq!(allowed.value.stdout.trim(), "open");
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(target_os = "linux"), ignore = "process isolation needs Linux")]
+    async fn test_command_unix_sockets_denied() {
+        require_isolation();
+        require_python();
+        let work = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+
+        // Stands in for the key agent's socket outside the work dir
+        let path = outside.path().join("agent.sock");
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        let server = std::thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let _ = std::io::Write::write_all(&mut stream, b"NODE-SECRET");
+            }
+        });
+        let code = format!(
+            "import socket; s = socket.socket(socket.AF_UNIX); s.connect({:?}); print(s.recv(64))",
+            path.display().to_string()
+        );
+
+        for network in [false, true] {
+            let result = SandboxBuilder::new()
+                .cpu_limit_ms(1000)
+                .allow_network(network)
+                .file_access_path(work.path().to_path_buf())
+                .build()
+                .run_command(python(&code))
+                .await
+                .unwrap();
+            assert!(!result.value.success());
+            assert!(!result.value.stdout.contains("NODE-SECRET"));
+            assert!(result.value.stderr.contains("PermissionError"));
+        }
+
+        let netlink = "import socket; socket.socket(socket.AF_NETLINK, socket.SOCK_RAW)";
+        let result = isolated_sandbox(work.path()).run_command(python(netlink)).await.unwrap();
+        assert!(!result.value.success());
+
+        // Release the listener nobody reached
+        std::os::unix::net::UnixStream::connect(&path).unwrap();
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(target_os = "linux"), ignore = "process isolation needs Linux")]
+    async fn test_command_resource_limits() {
+        require_isolation();
+        let work = tempfile::tempdir().unwrap();
+
+        // Wall-clock timeout kills the whole process group
+        let result = isolated_sandbox(work.path())
+            .run_command(shell("sleep 30 & sleep 30").timeout(Duration::from_millis(200)))
+            .await;
+        assert!(matches!(result, Err(SandboxError::Timeout)));
+
+        // CPU time is limited independently of the wall clock
+        let result = isolated_sandbox(work.path())
+            .run_command(shell("while :; do :; done").timeout(Duration::from_secs(10)))
+            .await;
+        assert!(matches!(result, Err(SandboxError::CpuLimitExceeded)));
+
+        // Files beyond max_file_size_mb cannot be written
+        let result = isolated_sandbox(work.path())
+            .run_command(shell("head -c 20000000 /dev/zero > big.bin"))
+            .await
+            .unwrap();
+        assert!(!result.value.success());
+        assert!(std::fs::metadata(work.path().join("big.bin")).unwrap().len() <= 10 * 1024 * 1024);
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(target_os = "linux"), ignore = "process isolation needs Linux")]
+    async fn test_command_memory_accounting() {
+        require_isolation();
+        require_python();
+        let work = tempfile::tempdir().unwrap();
+        let sandbox = isolated_sandbox(work.path());
+
+        let result = sandbox
+            .run_command(python("x = bytearray(32 * 1024 * 1024); print(len(x))"))
+            .await
+            .unwrap();
+        assert!(result.value.success());
+        assert!(result.memory_used_mb >= 32);
+        assert!(result.cpu_time_ms > 0);
+
+        // Past memory_limit_mb allocation fails inside the process
+        let result = sandbox
+            .run_command(python("x = bytearray(256 * 1024 * 1024)"))
+            .await
+            .unwrap();
+        assert!(!result.value.success());
+        assert!(result.value.stderr.contains("MemoryError"));
+    }
+
     #[test]
     fn test_sandbox_builder() {
         let sandbox = SandboxBuilder::new()
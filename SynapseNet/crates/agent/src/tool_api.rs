@@ -87,13 +87,13 @@ pub struct ToolInfo {
 #[async_trait]
 pub trait Tool: Send + Sync {
     /// Tool name (unique identifier)
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
     
     /// Human-readable description
-    fn description(&self) -> &'static str;
+    fn description(&self) -> &str;
     
     /// Tool version
-    fn version(&self) -> &'static str {
+    fn version(&self) -> &str {
         "1.0.0"
     }
     
@@ -111,6 +111,9 @@ pub trait Tool: Send + Sync {
         }
         Ok(())
     }
+
+    /// Release anything kept for `goal_id` once the goal has finished
+    fn end_goal(&self, _goal_id: &Uuid) {}
 }
 
 /// Tool execution errors
@@ -124,6 +127,12 @@ pub enum ToolError {
     NotFound,
     NetworkError(String),
     SandboxViolation(String),
+    /// The tool's policy requires approval; the action waits on this request
+    ApprovalRequired(Uuid),
+    /// A person rejected the action or its approval timed out
+    ApprovalDenied(String),
+    /// The content policy blocks this call; carries the explanation
+    PolicyDenied(String),
 }
 
 impl std::fmt::Display for ToolError {
@@ -137,6 +146,9 @@ impl std::fmt::Display for ToolError {
             Self::NotFound => write!(f, "Tool not found"),
             Self::NetworkError(msg) => write!(f, "Network error: {}", msg),
             Self::SandboxViolation(msg) => write!(f, "Sandbox violation: {}", msg),
+            Self::ApprovalRequired(id) => write!(f, "Awaiting approval: {}", id),
+            Self::ApprovalDenied(reason) => write!(f, "Approval denied: {}", reason),
+            Self::PolicyDenied(explanation) => write!(f, "Denied by policy: {}", explanation),
         }
     }
 }
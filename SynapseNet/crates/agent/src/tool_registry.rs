@@ -4,6 +4,7 @@ use crate::tool_api::{Tool, ToolError, ToolInfo};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use uuid::Uuid;
 
 /// Tool policy configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,7 +44,7 @@ impl Default for RateLimit {
 
 /// Tool registry for managing available tools
 pub struct ToolRegistry {
-    tools: Arc<RwLock<HashMap<String, Box<dyn Tool>>>>,
+    tools: Arc<RwLock<HashMap<String, Arc<dyn Tool>>>>,
     policies: Arc<RwLock<HashMap<String, ToolPolicy>>>,
     usage_stats: Arc<RwLock<HashMap<String, UsageStats>>>,
 }
@@ -77,7 +78,7 @@ impl ToolRegistry {
             return Err(format!("Tool '{}' already registered", name));
         }
         
-        tools.insert(name.clone(), tool);
+        tools.insert(name.clone(), Arc::from(tool));
         
         // Initialize default policy
         let mut policies = self.policies.write().unwrap();
@@ -91,9 +92,9 @@ impl ToolRegistry {
     }
 
     /// Get a tool by name
-    pub fn get(&self, name: &str) -> Option<Arc<Box<dyn Tool>>> {
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
         let tools = self.tools.read().unwrap();
-        tools.get(name).map(|t| Arc::new(t.clone()))
+        tools.get(name).cloned()
     }
 
     /// List all registered tools
@@ -139,6 +140,12 @@ impl ToolRegistry {
         Ok(())
     }
 
+    /// Whether calls to the tool must be approved by a person first
+    pub fn requires_approval(&self, name: &str) -> bool {
+        let policies = self.policies.read().unwrap();
+        policies.get(name).is_some_and(|p| p.requires_approval)
+    }
+
     /// Update tool policy
     pub fn update_policy(&self, name: &str, policy: ToolPolicy) -> Result<(), String> {
         let mut policies = self.policies.write().unwrap();
@@ -177,7 +184,7 @@ impl ToolRegistry {
     pub fn record_usage(&self, name: &str, success: bool) {
         let mut stats = self.usage_stats.write().unwrap();
         
-        let stat = stats.entry(name.to_string()).or_insert_with(UsageStats::default);
+        let stat = stats.entry(name.to_string()).or_default();
         
         stat.total_calls += 1;
         if success {
@@ -220,6 +227,13 @@ impl ToolRegistry {
         let stats = self.usage_stats.read().unwrap();
         stats.get(name).map(|s| (s.total_calls, s.successful_calls, s.failed_calls))
     }
+
+    /// Tell every tool that `goal_id` has finished
+    pub fn end_goal(&self, goal_id: &Uuid) {
+        for tool in self.tools.read().unwrap().values() {
+            tool.end_goal(goal_id);
+        }
+    }
 }
 
 impl Default for ToolRegistry {
@@ -0,0 +1,974 @@
+//! WASM Tools - Plugin tools loaded from WebAssembly modules
+//!
+//! A plugin is a core WASM module exporting:
+//!
+//! - `memory`
+//! - `alloc(len: i32) -> i32`, used by the host to pass data in
+//! - `manifest() -> i64`, a packed pointer to the JSON `PluginManifest`
+//! - `run(ptr: i32, len: i32) -> i64`, called with the JSON `ToolInput`;
+//!   returns a packed pointer to `{"result": ...}` or `{"error": "..."}`
+//!
+//! Packed pointers are `ptr << 32 | len`. Host functions live in the
+//! `synapse` import module and are only linked when the plugin declares the
+//! capability in its manifest and the host grants it. Fuel, memory, the
+//! number of host calls and the wall-clock time per run come from
+//! `SandboxLimits`.
+
+use crate::sandbox::SandboxLimits;
+use crate::tool_api::{
+    ResourceUsage, Tool, ToolError, ToolInput, ToolMetadata, ToolOutput, ToolSchema,
+};
+use crate::tool_registry::ToolRegistry;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use wasmtime::{
+    Caller, Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap,
+};
+
+/// Import module holding the host functions
+const HOST_MODULE: &str = "synapse";
+
+/// Fuel granted per millisecond of `SandboxLimits::cpu_ms`
+const FUEL_PER_MS: u64 = 100_000;
+
+/// Interval between engine epochs, the resolution of `SandboxLimits::wall_ms`
+const EPOCH_TICK_MS: u64 = 10;
+
+/// Host capabilities a plugin may ask for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginCapability {
+    /// `file_read` / `file_write` under the host's plugin file root
+    File,
+    /// `http_get` to allowlisted hosts
+    Http,
+    /// `grain_query` against the local grain store
+    GrainQuery,
+}
+
+impl PluginCapability {
+    /// Capability required by a host function, `None` for unknown functions
+    fn for_import(name: &str) -> Option<Option<Self>> {
+        match name {
+            "log" => Some(None),
+            "file_read" | "file_write" => Some(Some(Self::File)),
+            "http_get" => Some(Some(Self::Http)),
+            "grain_query" => Some(Some(Self::GrainQuery)),
+            _ => None,
+        }
+    }
+}
+
+/// Manifest exported by a plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub schema: ToolSchema,
+    #[serde(default)]
+    pub capabilities: Vec<PluginCapability>,
+}
+
+/// Blocking HTTP client behind the `http` capability
+pub trait HttpClient: Send + Sync {
+    fn get(&self, url: &str) -> Result<Vec<u8>, String>;
+}
+
+/// Grain search behind the `grain_query` capability
+pub trait GrainQuery: Send + Sync {
+    /// Answer a JSON request such as `{"query": "...", "k": 5}`
+    fn query(&self, request: &serde_json::Value) -> Result<serde_json::Value, String>;
+}
+
+/// HTTP access granted to plugins
+#[derive(Clone)]
+pub struct HttpAccess {
+    /// Hosts (and their subdomains) plugins may fetch from
+    pub allowed_hosts: Vec<String>,
+    pub client: Arc<dyn HttpClient>,
+}
+
+/// Capabilities the host grants to plugins; `None` withholds one
+#[derive(Clone, Default)]
+pub struct PluginCapabilities {
+    pub file_root: Option<PathBuf>,
+    pub http: Option<HttpAccess>,
+    pub grains: Option<Arc<dyn GrainQuery>>,
+}
+
+impl PluginCapabilities {
+    fn grants(&self, capability: PluginCapability) -> bool {
+        match capability {
+            PluginCapability::File => self.file_root.is_some(),
+            PluginCapability::Http => self.http.is_some(),
+            PluginCapability::GrainQuery => self.grains.is_some(),
+        }
+    }
+}
+
+/// Loads plugin modules and registers them as tools
+pub struct WasmToolHost {
+    engine: Engine,
+    linker: Arc<Linker<HostState>>,
+    limits: SandboxLimits,
+    capabilities: Arc<PluginCapabilities>,
+}
+
+impl WasmToolHost {
+    /// Create a host enforcing `limits` on every plugin call
+    pub fn new(limits: SandboxLimits, capabilities: PluginCapabilities) -> Result<Self, String> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        config.epoch_interruption(true);
+        let engine = Engine::new(&config).map_err(|e| e.to_string())?;
+        let linker = host_linker(&engine).map_err(|e| e.to_string())?;
+
+        // Epochs count wall-clock time; the ticker stops once the engine,
+        // and every plugin compiled by it, is dropped
+        let ticking = engine.weak();
+        std::thread::Builder::new()
+            .name("wasm-epoch".to_string())
+            .spawn(move || {
+                while let Some(engine) = ticking.upgrade() {
+                    engine.increment_epoch();
+                    drop(engine);
+                    std::thread::sleep(Duration::from_millis(EPOCH_TICK_MS));
+                }
+            })
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            engine,
+            linker: Arc::new(linker),
+            limits,
+            capabilities: Arc::new(capabilities),
+        })
+    }
+
+    /// Load a plugin from WASM bytes
+    pub fn load(&self, wasm: &[u8]) -> Result<WasmTool, String> {
+        let module = Module::new(&self.engine, wasm).map_err(|e| e.to_string())?;
+
+        // Only known host functions, and only ones the host grants
+        let mut imported = Vec::new();
+        for import in module.imports() {
+            let capability = (import.module() == HOST_MODULE)
+                .then(|| PluginCapability::for_import(import.name()))
+                .flatten()
+                .ok_or_else(|| format!("Unknown import {}.{}", import.module(), import.name()))?;
+            if let Some(capability) = capability {
+                if !self.capabilities.grants(capability) {
+                    return Err(format!(
+                        "{} needs the {:?} capability, which is not granted",
+                        import.name(),
+                        capability
+                    ));
+                }
+                imported.push(capability);
+            }
+        }
+
+        let plugin = Arc::new(Plugin {
+            engine: self.engine.clone(),
+            linker: self.linker.clone(),
+            module,
+            limits: self.limits.clone(),
+            capabilities: self.capabilities.clone(),
+        });
+
+        let (bytes, _) = plugin.call("manifest", None).map_err(|e| e.to_string())?;
+        let manifest: PluginManifest = serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Invalid plugin manifest: {}", e))?;
+
+        // Imports must match what the manifest admits to
+        if let Some(capability) = imported
+            .iter()
+            .find(|capability| !manifest.capabilities.contains(capability))
+        {
+            return Err(format!(
+                "Plugin {} imports {:?} functions without declaring the capability",
+                manifest.schema.name, capability
+            ));
+        }
+
+        Ok(WasmTool { manifest, plugin })
+    }
+
+    /// Load a plugin from a `.wasm` file
+    pub fn load_file(&self, path: &Path) -> Result<WasmTool, String> {
+        let wasm = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        self.load(&wasm)
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Load every `.wasm` plugin in `dir`, skipping ones that fail to load
+    pub fn load_dir(&self, dir: &Path) -> Result<Vec<WasmTool>, String> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map_err(|e| format!("{}: {}", dir.display(), e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+            .collect();
+        paths.sort();
+
+        let mut tools = Vec::new();
+        for path in paths {
+            match self.load_file(&path) {
+                Ok(tool) => tools.push(tool),
+                Err(e) => warn!("Skipping plugin {}", e),
+            }
+        }
+        Ok(tools)
+    }
+
+    /// Load the plugins in `dir` and register them, returning how many
+    /// were registered
+    pub fn register_dir(&self, dir: &Path, registry: &ToolRegistry) -> Result<usize, String> {
+        let mut registered = 0;
+        for tool in self.load_dir(dir)? {
+            let name = tool.name().to_string();
+            match registry.register(Box::new(tool)) {
+                Ok(()) => {
+                    info!("Registered plugin tool {}", name);
+                    registered += 1;
+                }
+                Err(e) => warn!("Skipping plugin: {}", e),
+            }
+        }
+        Ok(registered)
+    }
+}
+
+/// Tool backed by a WASM plugin
+pub struct WasmTool {
+    manifest: PluginManifest,
+    plugin: Arc<Plugin>,
+}
+
+impl WasmTool {
+    /// Manifest the plugin exported
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+}
+
+#[async_trait]
+impl Tool for WasmTool {
+    fn name(&self) -> &str {
+        &self.manifest.schema.name
+    }
+
+    fn description(&self) -> &str {
+        &self.manifest.schema.description
+    }
+
+    fn version(&self) -> &str {
+        &self.manifest.schema.version
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.manifest.schema.clone()
+    }
+
+    async fn execute(&self, input: ToolInput) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+        let payload =
+            serde_json::to_vec(&input).map_err(|e| ToolError::InvalidInput(e.to_string()))?;
+
+        // A plugin stuck in a host call is abandoned at the deadline; its
+        // thread traps at the next epoch check once the call returns
+        let plugin = self.plugin.clone();
+        let wall = Duration::from_millis(self.plugin.limits.wall_ms);
+        let (bytes, usage) = tokio::time::timeout(
+            wall,
+            tokio::task::spawn_blocking(move || plugin.call("run", Some(&payload))),
+        )
+        .await
+        .map_err(|_| ToolError::Timeout)?
+        .map_err(|e| ToolError::ExecutionFailed(e.to_string()))??;
+
+        let output: PluginOutput = serde_json::from_slice(&bytes)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid plugin output: {}", e)))?;
+        let result = match output {
+            PluginOutput::Result(result) => result,
+            PluginOutput::Error(error) => return Err(ToolError::ExecutionFailed(error)),
+        };
+
+        Ok(ToolOutput {
+            result,
+            metadata: ToolMetadata {
+                execution_time_ms: start.elapsed().as_millis() as u64,
+                resources_used: usage,
+                success: true,
+                error: None,
+            },
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PluginOutput {
+    Result(serde_json::Value),
+    Error(String),
+}
+
+/// Compiled plugin shared by all calls of a tool
+struct Plugin {
+    engine: Engine,
+    linker: Arc<Linker<HostState>>,
+    module: Module,
+    limits: SandboxLimits,
+    capabilities: Arc<PluginCapabilities>,
+}
+
+/// Per-call store data
+struct HostState {
+    limits: StoreLimits,
+    capabilities: Arc<PluginCapabilities>,
+    steps_left: u32,
+    network_bytes: u64,
+}
+
+/// A plugin used up its `max_steps` host calls
+#[derive(Debug)]
+struct StepLimitExceeded;
+
+impl std::fmt::Display for StepLimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Host call limit exceeded")
+    }
+}
+
+impl std::error::Error for StepLimitExceeded {}
+
+impl Plugin {
+    /// Instantiate the module in a fresh store and call an export
+    ///
+    /// `run` receives `input`; `manifest` takes no arguments.
+    fn call(
+        &self,
+        export: &str,
+        input: Option<&[u8]>,
+    ) -> Result<(Vec<u8>, ResourceUsage), ToolError> {
+        const MB: usize = 1024 * 1024;
+
+        let state = HostState {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(self.limits.mem_mb as usize * MB)
+                .instances(1)
+                .memories(1)
+                .tables(1)
+                .build(),
+            capabilities: self.capabilities.clone(),
+            steps_left: self.limits.max_steps,
+            network_bytes: 0,
+        };
+        let fuel = self.limits.cpu_ms.saturating_mul(FUEL_PER_MS);
+
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(fuel).map_err(trap_error)?;
+        store.set_epoch_deadline(self.limits.wall_ms.div_ceil(EPOCH_TICK_MS).max(1));
+
+        let instance = self
+            .linker
+            .instantiate(&mut store, &self.module)
+            .map_err(trap_error)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| ToolError::ExecutionFailed("Plugin exports no memory".to_string()))?;
+
+        let packed = match input {
+            Some(input) => {
+                let ptr = write_guest(&mut store, &instance, input)?;
+                instance
+                    .get_typed_func::<(i32, i32), i64>(&mut store, export)
+                    .map_err(trap_error)?
+                    .call(&mut store, (ptr, input.len() as i32))
+                    .map_err(trap_error)?
+            }
+            None => instance
+                .get_typed_func::<(), i64>(&mut store, export)
+                .map_err(trap_error)?
+                .call(&mut store, ())
+                .map_err(trap_error)?,
+        };
+
+        let (ptr, len) = unpack(packed)
+            .ok_or_else(|| ToolError::ExecutionFailed(format!("{} returned an error", export)))?;
+        let bytes = memory
+            .data(&store)
+            .get(ptr..ptr + len)
+            .ok_or_else(|| ToolError::ExecutionFailed("Plugin output out of bounds".to_string()))?
+            .to_vec();
+
+        let usage = ResourceUsage {
+            cpu_ms: (fuel - store.get_fuel().unwrap_or(0)) / FUEL_PER_MS,
+            memory_mb: memory.data_size(&store).div_ceil(MB) as u64,
+            network_bytes: store.data().network_bytes,
+        };
+        Ok((bytes, usage))
+    }
+}
+
+fn trap_error(error: wasmtime::Error) -> ToolError {
+    if matches!(error.downcast_ref::<Trap>(), Some(Trap::Interrupt)) {
+        ToolError::Timeout
+    } else if matches!(error.downcast_ref::<Trap>(), Some(Trap::OutOfFuel))
+        || error.downcast_ref::<StepLimitExceeded>().is_some()
+    {
+        ToolError::ResourceLimitExceeded
+    } else {
+        ToolError::ExecutionFailed(error.to_string())
+    }
+}
+
+fn pack(ptr: i32, len: usize) -> i64 {
+    ((ptr as u32 as i64) << 32) | len as u32 as i64
+}
+
+fn unpack(packed: i64) -> Option<(usize, usize)> {
+    (packed >= 0).then_some(((packed >> 32) as usize, (packed & 0xffff_ffff) as usize))
+}
+
+/// Copy `bytes` into guest memory allocated with the plugin's `alloc`
+fn write_guest(
+    store: &mut Store<HostState>,
+    instance: &wasmtime::Instance,
+    bytes: &[u8],
+) -> Result<i32, ToolError> {
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&mut *store, "alloc")
+        .map_err(trap_error)?;
+    let ptr = alloc
+        .call(&mut *store, bytes.len() as i32)
+        .map_err(trap_error)?;
+    instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| ToolError::ExecutionFailed("Plugin exports no memory".to_string()))?
+        .write(&mut *store, ptr as usize, bytes)
+        .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+    Ok(ptr)
+}
+
+/// Guest bytes at `ptr..ptr + len`
+fn read_bytes(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Option<Vec<u8>> {
+    let memory = caller.get_export("memory")?.into_memory()?;
+    memory
+        .data(&*caller)
+        .get(ptr as usize..(ptr as usize).checked_add(len as usize)?)
+        .map(<[u8]>::to_vec)
+}
+
+fn read_str(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Option<String> {
+    String::from_utf8(read_bytes(caller, ptr, len)?).ok()
+}
+
+/// Hand `bytes` to the guest, returning a packed pointer or -1
+fn return_bytes(caller: &mut Caller<'_, HostState>, bytes: &[u8]) -> wasmtime::Result<i64> {
+    let Some(alloc) = caller.get_export("alloc").and_then(|e| e.into_func()) else {
+        return Ok(-1);
+    };
+    let ptr = alloc
+        .typed::<i32, i32>(&*caller)?
+        .call(&mut *caller, bytes.len() as i32)?;
+    let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory()) else {
+        return Ok(-1);
+    };
+    if memory.write(&mut *caller, ptr as usize, bytes).is_err() {
+        return Ok(-1);
+    }
+    Ok(pack(ptr, bytes.len()))
+}
+
+/// Count a host call against `max_steps`
+fn take_step(caller: &mut Caller<'_, HostState>) -> wasmtime::Result<()> {
+    let state = caller.data_mut();
+    if state.steps_left == 0 {
+        return Err(StepLimitExceeded.into());
+    }
+    state.steps_left -= 1;
+    Ok(())
+}
+
+/// Resolve a plugin-supplied path under the file root, refusing escapes
+fn scoped_path(root: &Path, relative: &str) -> Option<PathBuf> {
+    let relative = Path::new(relative);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return None;
+    }
+
+    // Symlinks inside the root must not lead out of it
+    let root = root.canonicalize().ok()?;
+    let path = root.join(relative);
+    let resolved = match path.canonicalize() {
+        Ok(resolved) => resolved,
+        Err(_) => path.parent()?.canonicalize().ok()?.join(path.file_name()?),
+    };
+    resolved.starts_with(&root).then_some(resolved)
+}
+
+fn host_allowed(url: &str, allowed_hosts: &[String]) -> bool {
+    let Ok(url) = url::Url::parse(url) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    matches!(url.scheme(), "http" | "https")
+        && allowed_hosts
+            .iter()
+            .any(|allowed| host == allowed || host.ends_with(&format!(".{}", allowed)))
+}
+
+fn host_linker(engine: &Engine) -> wasmtime::Result<Linker<HostState>> {
+    let mut linker = Linker::new(engine);
+
+    linker.func_wrap(
+        HOST_MODULE,
+        "log",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> wasmtime::Result<()> {
+            take_step(&mut caller)?;
+            if let Some(message) = read_str(&mut caller, ptr, len) {
+                info!("plugin: {}", message);
+            }
+            Ok(())
+        },
+    )?;
+
+    linker.func_wrap(
+        HOST_MODULE,
+        "file_read",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> wasmtime::Result<i64> {
+            take_step(&mut caller)?;
+            let Some(root) = caller.data().capabilities.file_root.clone() else {
+                return Ok(-1);
+            };
+            let contents = read_str(&mut caller, ptr, len)
+                .and_then(|relative| scoped_path(&root, &relative))
+                .and_then(|path| std::fs::read(path).ok());
+            match contents {
+                Some(contents) => return_bytes(&mut caller, &contents),
+                None => Ok(-1),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        HOST_MODULE,
+        "file_write",
+        |mut caller: Caller<'_, HostState>,
+         path_ptr: i32,
+         path_len: i32,
+         data_ptr: i32,
+         data_len: i32|
+         -> wasmtime::Result<i32> {
+            take_step(&mut caller)?;
+            let Some(root) = caller.data().capabilities.file_root.clone() else {
+                return Ok(-1);
+            };
+            let path = read_str(&mut caller, path_ptr, path_len)
+                .and_then(|relative| scoped_path(&root, &relative));
+            let data = read_bytes(&mut caller, data_ptr, data_len);
+            let written = match (path, data) {
+                (Some(path), Some(data)) => std::fs::write(path, data).is_ok(),
+                _ => false,
+            };
+            Ok(if written { 0 } else { -1 })
+        },
+    )?;
+
+    linker.func_wrap(
+        HOST_MODULE,
+        "http_get",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> wasmtime::Result<i64> {
+            take_step(&mut caller)?;
+            let Some(http) = caller.data().capabilities.http.clone() else {
+                return Ok(-1);
+            };
+            let Some(url) = read_str(&mut caller, ptr, len) else {
+                return Ok(-1);
+            };
+            if !host_allowed(&url, &http.allowed_hosts) {
+                warn!("Plugin fetch of {} refused", url);
+                return Ok(-1);
+            }
+            match http.client.get(&url) {
+                Ok(body) => {
+                    caller.data_mut().network_bytes += body.len() as u64;
+                    return_bytes(&mut caller, &body)
+                }
+                Err(_) => Ok(-1),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        HOST_MODULE,
+        "grain_query",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> wasmtime::Result<i64> {
+            take_step(&mut caller)?;
+            let Some(grains) = caller.data().capabilities.grains.clone() else {
+                return Ok(-1);
+            };
+            let response = read_bytes(&mut caller, ptr, len)
+                .and_then(|request| serde_json::from_slice(&request).ok())
+                .and_then(|request| grains.query(&request).ok())
+                .and_then(|response| serde_json::to_vec(&response).ok());
+            match response {
+                Some(response) => return_bytes(&mut caller, &response),
+                None => Ok(-1),
+            }
+        },
+    )?;
+
+    Ok(linker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_api::ExecutionContext;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    const OUTPUT_AT: usize = 2048;
+    const ARG_AT: usize = 3072;
+
+    /// Plugin with a bump allocator, the given imports and `run` body
+    ///
+    /// `run` can use `$output` (packed pointer to `output`) and reads
+    /// `arg` at `ARG_AT`.
+    fn plugin(
+        name: &str,
+        capabilities: &str,
+        imports: &str,
+        output: &str,
+        arg: &str,
+        run: &str,
+    ) -> Vec<u8> {
+        let manifest = format!(
+            r#"{{"schema":{{"name":"{}","description":"Test plugin","version":"0.1.0","parameters":[],"returns":{{"return_type":"object","description":"Result"}}}},"capabilities":[{}]}}"#,
+            name, capabilities
+        );
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let wat = format!(
+            r#"(module
+                {imports}
+                (memory (export "memory") 1)
+                (global $heap (mut i32) (i32.const 4096))
+                (data (i32.const 0) "{manifest}")
+                (data (i32.const {OUTPUT_AT}) "{output}")
+                (data (i32.const {ARG_AT}) "{arg}")
+                (func (export "alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $heap))
+                    (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+                    (if (i32.gt_u (global.get $heap) (i32.mul (memory.size) (i32.const 65536)))
+                        (then (drop (memory.grow (i32.add (i32.div_u (local.get $len) (i32.const 65536)) (i32.const 1))))))
+                    (local.get $ptr))
+                (func (export "manifest") (result i64) (i64.const {manifest_len}))
+                (func (export "run") (param $ptr i32) (param $len i32) (result i64)
+                    (local $output i64)
+                    (local.set $output (i64.or (i64.shl (i64.const {OUTPUT_AT}) (i64.const 32)) (i64.const {output_len})))
+                    {run}))"#,
+            manifest = escape(&manifest),
+            manifest_len = manifest.len(),
+            output = escape(output),
+            output_len = output.len(),
+            arg = escape(arg),
+        );
+        wat::parse_str(wat).unwrap()
+    }
+
+    fn input() -> ToolInput {
+        ToolInput {
+            params: serde_json::json!({}),
+            context: ExecutionContext {
+                goal_id: Uuid::new_v4(),
+                episode_id: None,
+                user_id: "test".to_string(),
+                timestamp: chrono::Utc::now().timestamp(),
+                metadata: HashMap::new(),
+            },
+        }
+    }
+
+    fn host(capabilities: PluginCapabilities) -> WasmToolHost {
+        WasmToolHost::new(SandboxLimits::default(), capabilities).unwrap()
+    }
+
+    /// Run body calling a host function on `arg` and returning its result
+    fn call_with_arg(function: &str, arg: &str) -> String {
+        format!(
+            "(local.set $output (call ${} (i32.const {}) (i32.const {}))) (local.get $output)",
+            function,
+            ARG_AT,
+            arg.len()
+        )
+    }
+
+    #[tokio::test]
+    async fn test_plugin_registered_and_executed() {
+        let dir = tempfile::tempdir().unwrap();
+        let pong = plugin(
+            "pong",
+            "",
+            "",
+            r#"{"result":"pong"}"#,
+            "",
+            "(local.get $output)",
+        );
+        std::fs::write(dir.path().join("pong.wasm"), pong).unwrap();
+        std::fs::write(dir.path().join("broken.wasm"), b"not wasm").unwrap();
+
+        let registry = ToolRegistry::new();
+        let registered = host(PluginCapabilities::default())
+            .register_dir(dir.path(), &registry)
+            .unwrap();
+        assert_eq!(registered, 1);
+
+        let tool = registry.get("pong").unwrap();
+        assert_eq!(tool.version(), "0.1.0");
+        assert_eq!(tool.schema().returns.return_type, "object");
+
+        let output = tool.execute(input()).await.unwrap();
+        assert_eq!(output.result, "pong");
+        assert!(output.metadata.success);
+    }
+
+    #[tokio::test]
+    async fn test_plugin_limits_enforced() {
+        let host = host(PluginCapabilities::default());
+
+        let spin = plugin(
+            "spin",
+            "",
+            "",
+            "",
+            "",
+            "(loop $spin (br $spin)) (local.get $output)",
+        );
+        let result = host.load(&spin).unwrap().execute(input()).await;
+        assert!(matches!(result, Err(ToolError::ResourceLimitExceeded)));
+
+        // 2000 pages is far past the 64 MB default
+        let greedy = plugin(
+            "greedy",
+            "",
+            "",
+            r#"{"error":"out of memory"}"#,
+            "",
+            r#"(drop (memory.grow (i32.const 2000))) (local.get $output)"#,
+        );
+        let tool = host.load(&greedy).unwrap();
+        assert!(matches!(
+            tool.execute(input()).await,
+            Err(ToolError::ExecutionFailed(_))
+        ));
+
+        // Every host call counts against max_steps
+        let chatty = plugin(
+            "chatty",
+            "",
+            r#"(import "synapse" "log" (func $log (param i32 i32)))"#,
+            "",
+            "hi",
+            &format!("(loop $again (call $log (i32.const {}) (i32.const 2)) (br $again)) (local.get $output)", ARG_AT),
+        );
+        let result = host.load(&chatty).unwrap().execute(input()).await;
+        assert!(matches!(result, Err(ToolError::ResourceLimitExceeded)));
+    }
+
+    #[tokio::test]
+    async fn test_plugin_wall_clock_enforced() {
+        struct Slow;
+        impl HttpClient for Slow {
+            fn get(&self, _url: &str) -> Result<Vec<u8>, String> {
+                std::thread::sleep(Duration::from_millis(300));
+                Ok(Vec::new())
+            }
+        }
+
+        let limits = SandboxLimits {
+            wall_ms: 100,
+            ..Default::default()
+        };
+        let host = WasmToolHost::new(
+            limits,
+            PluginCapabilities {
+                http: Some(HttpAccess {
+                    allowed_hosts: vec!["example.org".to_string()],
+                    client: Arc::new(Slow),
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Host calls burn no fuel, so only the deadline stops this loop
+        let url = "https://example.org/";
+        let poller = plugin(
+            "poll",
+            "\"http\"",
+            r#"(import "synapse" "http_get" (func $http_get (param i32 i32) (result i64)))"#,
+            "",
+            url,
+            &format!(
+                "(loop $again (drop (call $http_get (i32.const {}) (i32.const {}))) (br $again)) \
+                 (local.get $output)",
+                ARG_AT,
+                url.len()
+            ),
+        );
+
+        let started = std::time::Instant::now();
+        let result = host.load(&poller).unwrap().execute(input()).await;
+        assert!(matches!(result, Err(ToolError::Timeout)));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_plugin_capabilities_checked_at_load() {
+        let import =
+            r#"(import "synapse" "http_get" (func $http_get (param i32 i32) (result i64)))"#;
+        let fetcher =
+            |capabilities| plugin("fetch", capabilities, import, "", "", "(local.get $output)");
+
+        // Not granted by the host
+        let error = host(PluginCapabilities::default())
+            .load(&fetcher("\"http\""))
+            .err()
+            .unwrap();
+        assert!(error.contains("not granted"));
+
+        struct NoHttp;
+        impl HttpClient for NoHttp {
+            fn get(&self, _url: &str) -> Result<Vec<u8>, String> {
+                Err("offline".to_string())
+            }
+        }
+        let granted = host(PluginCapabilities {
+            http: Some(HttpAccess {
+                allowed_hosts: vec!["example.org".to_string()],
+                client: Arc::new(NoHttp),
+            }),
+            ..Default::default()
+        });
+
+        // Granted but not declared in the manifest
+        let error = granted.load(&fetcher("")).err().unwrap();
+        assert!(error.contains("without declaring"));
+        assert!(granted.load(&fetcher("\"http\"")).is_ok());
+
+        // Imports outside the host module
+        let wasi = plugin(
+            "wasi",
+            "",
+            r#"(import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32)))"#,
+            "",
+            "",
+            "(local.get $output)",
+        );
+        assert!(granted
+            .load(&wasi)
+            .err()
+            .unwrap()
+            .contains("Unknown import"));
+
+        assert!(host_allowed(
+            "https://api.example.org/x",
+            &["example.org".to_string()]
+        ));
+        assert!(!host_allowed(
+            "https://example.org.evil.com/",
+            &["example.org".to_string()]
+        ));
+        assert!(!host_allowed(
+            "file:///etc/passwd",
+            &["example.org".to_string()]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plugin_file_access_scoped() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("note.json"), r#"{"result":"from file"}"#).unwrap();
+        let host = host(PluginCapabilities {
+            file_root: Some(root.path().to_path_buf()),
+            ..Default::default()
+        });
+        let import =
+            r#"(import "synapse" "file_read" (func $file_read (param i32 i32) (result i64)))"#;
+        let failed = r#"{"error":"read failed"}"#;
+        let reader = |path: &str| {
+            let run = format!(
+                "(local.set $output (call $file_read (i32.const {}) (i32.const {}))) \
+                 (if (result i64) (i64.lt_s (local.get $output) (i64.const 0)) \
+                     (then (i64.or (i64.shl (i64.const {}) (i64.const 32)) (i64.const {}))) \
+                     (else (local.get $output)))",
+                ARG_AT,
+                path.len(),
+                OUTPUT_AT,
+                failed.len()
+            );
+            plugin("reader", "\"file\"", import, failed, path, &run)
+        };
+
+        let output = host
+            .load(&reader("note.json"))
+            .unwrap()
+            .execute(input())
+            .await
+            .unwrap();
+        assert_eq!(output.result, "from file");
+
+        for escape in ["../secret.json", "/etc/passwd"] {
+            let result = host.load(&reader(escape)).unwrap().execute(input()).await;
+            assert!(matches!(result, Err(ToolError::ExecutionFailed(msg)) if msg == "read failed"));
+        }
+
+        std::os::unix::fs::symlink("/etc/passwd", root.path().join("link")).unwrap();
+        let result = host.load(&reader("link")).unwrap().execute(input()).await;
+        assert!(matches!(result, Err(ToolError::ExecutionFailed(msg)) if msg == "read failed"));
+    }
+
+    #[tokio::test]
+    async fn test_plugin_grain_query() {
+        struct Grains;
+        impl GrainQuery for Grains {
+            fn query(&self, request: &serde_json::Value) -> Result<serde_json::Value, String> {
+                Ok(serde_json::json!({ "result": [request["query"], "second"] }))
+            }
+        }
+
+        let host = host(PluginCapabilities {
+            grains: Some(Arc::new(Grains)),
+            ..Default::default()
+        });
+        let import =
+            r#"(import "synapse" "grain_query" (func $grain_query (param i32 i32) (result i64)))"#;
+        let arg = r#"{"query":"rust","k":2}"#;
+        let searcher = plugin(
+            "search",
+            "\"grain_query\"",
+            import,
+            "",
+            arg,
+            &call_with_arg("grain_query", arg),
+        );
+
+        let output = host
+            .load(&searcher)
+            .unwrap()
+            .execute(input())
+            .await
+            .unwrap();
+        assert_eq!(output.result, serde_json::json!(["rust", "second"]));
+    }
+}
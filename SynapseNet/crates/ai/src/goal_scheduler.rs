@@ -0,0 +1,313 @@
+//! Goal scheduler - durable execution of reasoning goals
+//!
+//! Goals, their task graphs and every completed episode are written to the
+//! store as they progress, so a restarted node picks each unfinished goal
+//! up again from the last completed task node instead of starting over.
+
+use crate::planner::{TaskGraph, TaskStatus};
+use crate::reasoner::Reasoner;
+use anyhow::{anyhow, Result};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use synapsenet_core::{Goal, GoalStatus, Priority};
+use synapsenet_storage::Store;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Outcome of running a goal
+#[derive(Debug, Clone)]
+pub struct GoalOutcome {
+    pub goal_id: Uuid,
+    pub status: GoalStatus,
+    pub answer: Option<String>,
+    pub steps_executed: usize,
+}
+
+/// Persistent goal scheduler
+pub struct GoalScheduler {
+    store: Arc<Mutex<Store>>,
+    reasoner: Reasoner,
+}
+
+impl GoalScheduler {
+    /// Create scheduler over a shared store
+    pub fn new(store: Arc<Mutex<Store>>, reasoner: Reasoner) -> Self {
+        Self { store, reasoner }
+    }
+
+    /// Persist a new pending goal
+    pub fn submit(
+        &self,
+        text: impl Into<String>,
+        created_by: impl Into<String>,
+        priority: Priority,
+    ) -> Result<Goal> {
+        let mut goal = Goal::new(text, created_by);
+        goal.priority = priority;
+        self.store()?.upsert_goal(&goal)?;
+        info!("Goal submitted: {}", goal.id);
+        Ok(goal)
+    }
+
+    /// Cancel a goal; a running goal stops before its next task
+    pub fn cancel(&self, goal_id: &Uuid) -> Result<bool> {
+        cancel_goal(&self.store, goal_id)
+    }
+
+    /// Reset a goal so it is planned and executed again from scratch
+    pub fn reset(&self, goal_id: &Uuid) -> Result<bool> {
+        reset_goal(&self.store, goal_id)
+    }
+
+    /// Run every unfinished goal in priority order
+    pub async fn resume_all(&mut self) -> Result<Vec<GoalOutcome>> {
+        let goals = self.store()?.get_unfinished_goals()?;
+        if !goals.is_empty() {
+            info!("Resuming {} unfinished goals", goals.len());
+        }
+
+        let mut outcomes = Vec::new();
+        for goal in goals {
+            outcomes.push(self.run_goal(&goal.id).await?);
+        }
+        Ok(outcomes)
+    }
+
+    /// Poll the store for unfinished goals until the task is dropped
+    pub async fn run(mut self, poll_interval: Duration) {
+        loop {
+            if let Err(e) = self.resume_all().await {
+                warn!("Goal scheduler error: {}", e);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Run a goal to completion, resuming from persisted progress
+    pub async fn run_goal(&mut self, goal_id: &Uuid) -> Result<GoalOutcome> {
+        let mut goal = self
+            .store()?
+            .get_goal(goal_id)?
+            .ok_or_else(|| anyhow!("Goal not found: {}", goal_id))?;
+
+        if goal.is_terminal() {
+            return Ok(outcome(&goal, 0));
+        }
+
+        // Load the saved plan or create one
+        let saved_plan = self.store()?.get_plan(goal_id)?;
+        let mut plan: TaskGraph = match saved_plan {
+            Some(json) => serde_json::from_str(&json)?,
+            None => {
+                self.set_status(&mut goal, GoalStatus::Planning)?;
+                let plan = self.reasoner.plan(&goal.text).await.map_err(|e| anyhow!(e))?;
+                self.save_plan(goal_id, &plan)?;
+                plan
+            }
+        };
+
+        // Tasks interrupted by a shutdown are executed again
+        for node in plan.nodes.values_mut() {
+            if node.status == TaskStatus::InProgress {
+                node.status = TaskStatus::Pending;
+            }
+        }
+
+        self.set_status(&mut goal, GoalStatus::Reasoning)?;
+
+        let mut episodes = self.store()?.get_episodes(goal_id)?;
+        let mut steps_executed = 0;
+
+        while let Some(node_id) = next_ready_node(&plan)? {
+            // Honour cancellation between tasks
+            if let Some(current) = self.store()?.get_goal(goal_id)? {
+                if current.status == GoalStatus::Cancelled {
+                    info!("Goal {} cancelled", goal_id);
+                    return Ok(outcome(&current, steps_executed));
+                }
+            }
+
+            let task = plan.nodes[&node_id].task.clone();
+            set_node_status(&mut plan, &node_id, TaskStatus::InProgress);
+            self.save_plan(goal_id, &plan)?;
+
+            match self
+                .reasoner
+                .execute_task(*goal_id, episodes.len() as u32, &task)
+                .await
+            {
+                Ok(episode) => {
+                    self.store()?.insert_episode(&episode)?;
+                    episodes.push(episode);
+                    set_node_status(&mut plan, &node_id, TaskStatus::Completed);
+                    self.save_plan(goal_id, &plan)?;
+                    steps_executed += 1;
+                }
+                Err(e) => {
+                    warn!("Goal {} failed at task '{}': {}", goal_id, task, e);
+                    set_node_status(&mut plan, &node_id, TaskStatus::Failed);
+                    self.save_plan(goal_id, &plan)?;
+                    goal.metadata["error"] = serde_json::json!(e);
+                    self.set_status(&mut goal, GoalStatus::Failed)?;
+                    return Ok(outcome(&goal, steps_executed));
+                }
+            }
+        }
+
+        self.set_status(&mut goal, GoalStatus::Reflecting)?;
+        let (answer, confidence) = self
+            .reasoner
+            .conclude(&goal.text, &episodes)
+            .await
+            .map_err(|e| anyhow!(e))?;
+
+        goal.metadata["answer"] = serde_json::json!(answer);
+        goal.metadata["confidence"] = serde_json::json!(confidence);
+        self.set_status(&mut goal, GoalStatus::Completed)?;
+        info!("Goal {} completed ({} steps)", goal_id, steps_executed);
+
+        Ok(outcome(&goal, steps_executed))
+    }
+
+    fn store(&self) -> Result<std::sync::MutexGuard<'_, Store>> {
+        self.store
+            .lock()
+            .map_err(|_| anyhow!("Store lock poisoned"))
+    }
+
+    fn set_status(&self, goal: &mut Goal, status: GoalStatus) -> Result<()> {
+        goal.set_status(status);
+        self.store()?.upsert_goal(goal)
+    }
+
+    fn save_plan(&self, goal_id: &Uuid, plan: &TaskGraph) -> Result<()> {
+        let json = serde_json::to_string(plan)?;
+        self.store()?.save_plan(goal_id, &json)
+    }
+}
+
+/// Cancel a goal in the store (shared with API handlers)
+pub fn cancel_goal(store: &Mutex<Store>, goal_id: &Uuid) -> Result<bool> {
+    let store = store.lock().map_err(|_| anyhow!("Store lock poisoned"))?;
+    match store.get_goal(goal_id)? {
+        Some(goal) if !goal.is_terminal() => {
+            store.update_goal_status(goal_id, GoalStatus::Cancelled)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Clear a goal's progress and mark it pending again (shared with API handlers)
+pub fn reset_goal(store: &Mutex<Store>, goal_id: &Uuid) -> Result<bool> {
+    let store = store.lock().map_err(|_| anyhow!("Store lock poisoned"))?;
+    let Some(mut goal) = store.get_goal(goal_id)? else {
+        return Ok(false);
+    };
+
+    store.clear_goal_progress(goal_id)?;
+    goal.metadata = serde_json::json!({});
+    goal.set_status(GoalStatus::Pending);
+    store.upsert_goal(&goal)?;
+    Ok(true)
+}
+
+/// First ready node in execution order
+fn next_ready_node(plan: &TaskGraph) -> Result<Option<Uuid>> {
+    let order = plan.topological_sort().map_err(|e| anyhow!(e))?;
+    Ok(order
+        .into_iter()
+        .find(|id| plan.nodes[id].is_ready(plan)))
+}
+
+fn set_node_status(plan: &mut TaskGraph, node_id: &Uuid, status: TaskStatus) {
+    if let Some(node) = plan.get_node_mut(node_id) {
+        node.status = status;
+    }
+}
+
+fn outcome(goal: &Goal, steps_executed: usize) -> GoalOutcome {
+    GoalOutcome {
+        goal_id: goal.id,
+        status: goal.status,
+        answer: goal.metadata["answer"].as_str().map(str::to_string),
+        steps_executed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reasoner::ReasonerConfig;
+
+    fn scheduler() -> GoalScheduler {
+        let store = Arc::new(Mutex::new(Store::new(":memory:").unwrap()));
+        GoalScheduler::new(store, Reasoner::new(ReasonerConfig::default()))
+    }
+
+    #[tokio::test]
+    async fn test_run_goal_persists_progress() {
+        let mut scheduler = scheduler();
+        let goal = scheduler.submit("How does PoE work?", "test", Priority::Normal).unwrap();
+
+        let result = scheduler.run_goal(&goal.id).await.unwrap();
+        assert_eq!(result.status, GoalStatus::Completed);
+        assert!(result.answer.is_some());
+
+        let store = scheduler.store().unwrap();
+        let plan: TaskGraph =
+            serde_json::from_str(&store.get_plan(&goal.id).unwrap().unwrap()).unwrap();
+        assert_eq!(plan.completion_percentage(), 100.0);
+        assert_eq!(store.get_episodes(&goal.id).unwrap().len(), plan.nodes.len());
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_last_completed_node() {
+        let mut scheduler = scheduler();
+        let goal = scheduler.submit("How does PoE work?", "test", Priority::High).unwrap();
+
+        // Simulate a crash after the first two tasks
+        let mut plan = scheduler.reasoner.plan(&goal.text).await.unwrap();
+        let order = plan.topological_sort().unwrap();
+        for (step, id) in order.iter().take(2).enumerate() {
+            let task = plan.nodes[id].task.clone();
+            let episode = scheduler
+                .reasoner
+                .execute_task(goal.id, step as u32, &task)
+                .await
+                .unwrap();
+            scheduler.store().unwrap().insert_episode(&episode).unwrap();
+            set_node_status(&mut plan, id, TaskStatus::Completed);
+        }
+        set_node_status(&mut plan, &order[2], TaskStatus::InProgress);
+        scheduler.save_plan(&goal.id, &plan).unwrap();
+        scheduler
+            .store()
+            .unwrap()
+            .update_goal_status(&goal.id, GoalStatus::Reasoning)
+            .unwrap();
+
+        let outcomes = scheduler.resume_all().await.unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].status, GoalStatus::Completed);
+        assert_eq!(outcomes[0].steps_executed, order.len() - 2);
+
+        let episodes = scheduler.store().unwrap().get_episodes(&goal.id).unwrap();
+        let steps: Vec<u32> = episodes.iter().map(|e| e.step).collect();
+        assert_eq!(steps, (0..order.len() as u32).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_rerun() {
+        let mut scheduler = scheduler();
+        let goal = scheduler.submit("Explain grains", "test", Priority::Normal).unwrap();
+
+        assert!(scheduler.cancel(&goal.id).unwrap());
+        let result = scheduler.run_goal(&goal.id).await.unwrap();
+        assert_eq!(result.status, GoalStatus::Cancelled);
+        assert_eq!(result.steps_executed, 0);
+
+        assert!(scheduler.reset(&goal.id).unwrap());
+        let result = scheduler.run_goal(&goal.id).await.unwrap();
+        assert_eq!(result.status, GoalStatus::Completed);
+    }
+}
@@ -1,12 +1,18 @@
-// SynapseNet AI - Embeddings and consequence analysis
+// SynapseNet AI - Embeddings, planning and consequence analysis
 
 pub mod batch;
 pub mod consequence;
 pub mod embed;
+pub mod goal_scheduler;
 pub mod gpu_providers;
+pub mod llm;
+pub mod memory_chain;
 pub mod model_manager;
 pub mod multi_model;
 pub mod onnx_embed;
+pub mod planner;
+pub mod reasoner;
+pub mod reflection;
 
 pub use batch::{
     BatchConfig, BatchProcessor, BatchProgress, BatchResult, DocumentParser, MarkdownParser,
@@ -14,7 +20,11 @@ pub use batch::{
 };
 pub use consequence::ConsequenceAnalyzer;
 pub use embed::EmbeddingModel;
+pub use goal_scheduler::{GoalOutcome, GoalScheduler};
 pub use gpu_providers::GpuProvider;
+pub use llm::{GenerationRequest, LlmBackend, LocalLlm, TextGenerator};
 pub use model_manager::{ModelInfo as ModelManagerInfo, ModelManager, ALL_MINILM_L6_V2};
 pub use multi_model::{ModelInfo, ModelSize, MultiModelManager};
 pub use onnx_embed::OnnxEmbedding;
+pub use planner::{Planner, TaskGraph, TaskNode, TaskStatus};
+pub use reasoner::{Reasoner, ReasonerConfig, ReasoningResult};
@@ -0,0 +1,342 @@
+//! Local LLM backends for planning and synthesis
+//!
+//! Talks to a text generation server running on the node (llama.cpp
+//! `server`, Ollama, vLLM, LM Studio, ...) over HTTP. Nothing leaves the
+//! machine unless the endpoint is pointed elsewhere.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use synapsenet_core::LlmConfig;
+use tracing::debug;
+
+/// Text generation request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationRequest {
+    /// Optional system instruction
+    pub system: Option<String>,
+    /// User prompt
+    pub prompt: String,
+    /// Maximum tokens to generate (backend default if None)
+    pub max_tokens: Option<u32>,
+    /// Sampling temperature (backend default if None)
+    pub temperature: Option<f32>,
+    /// Stop sequences
+    pub stop: Vec<String>,
+}
+
+impl GenerationRequest {
+    /// Create a request from a prompt
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            system: None,
+            prompt: prompt.into(),
+            max_tokens: None,
+            temperature: None,
+            stop: Vec::new(),
+        }
+    }
+
+    /// Set system instruction
+    pub fn with_system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// Set maximum tokens
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+}
+
+/// Text generator interface
+#[async_trait]
+pub trait TextGenerator: Send + Sync {
+    /// Generate a completion for the request
+    async fn generate(&self, request: &GenerationRequest) -> Result<String>;
+
+    /// Backend name (for logging and traces)
+    fn name(&self) -> &str;
+}
+
+/// Wire protocol spoken by the local server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmBackend {
+    /// OpenAI-compatible `/v1/chat/completions`
+    OpenAi,
+    /// llama.cpp native `/completion`
+    LlamaCpp,
+}
+
+impl LlmBackend {
+    /// Parse backend name from config
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "openai" => Ok(Self::OpenAi),
+            "llamacpp" | "llama.cpp" => Ok(Self::LlamaCpp),
+            other => Err(anyhow!("Unknown LLM backend: {}", other)),
+        }
+    }
+}
+
+/// HTTP client for a local text generation server
+pub struct LocalLlm {
+    client: reqwest::Client,
+    backend: LlmBackend,
+    endpoint: String,
+    model: String,
+    max_tokens: u32,
+    temperature: f32,
+}
+
+impl LocalLlm {
+    /// Create client from configuration
+    pub fn new(config: &LlmConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()?;
+
+        Ok(Self {
+            client,
+            backend: LlmBackend::parse(&config.backend)?,
+            endpoint: config.endpoint.trim_end_matches('/').to_string(),
+            model: config.model.clone(),
+            max_tokens: config.max_tokens,
+            temperature: config.temperature,
+        })
+    }
+
+    /// Create client from configuration if the LLM is enabled
+    pub fn from_config(config: &LlmConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        Self::new(config).map(Some)
+    }
+
+    /// Get backend type
+    pub fn backend(&self) -> LlmBackend {
+        self.backend
+    }
+
+    async fn generate_openai(&self, request: &GenerationRequest) -> Result<String> {
+        let mut messages = Vec::new();
+        if let Some(system) = &request.system {
+            messages.push(serde_json::json!({"role": "system", "content": system}));
+        }
+        messages.push(serde_json::json!({"role": "user", "content": request.prompt}));
+
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "messages": messages,
+            "max_tokens": request.max_tokens.unwrap_or(self.max_tokens),
+            "temperature": request.temperature.unwrap_or(self.temperature),
+            "stream": false,
+        });
+        if !request.stop.is_empty() {
+            body["stop"] = serde_json::json!(request.stop);
+        }
+
+        let url = format!("{}/v1/chat/completions", self.endpoint);
+        let response: serde_json::Value = self
+            .client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        response["choices"][0]["message"]["content"]
+            .as_str()
+            .map(|s| s.trim().to_string())
+            .ok_or_else(|| anyhow!("Malformed chat completion response"))
+    }
+
+    async fn generate_llamacpp(&self, request: &GenerationRequest) -> Result<String> {
+        let prompt = match &request.system {
+            Some(system) => format!("{}\n\n{}", system, request.prompt),
+            None => request.prompt.clone(),
+        };
+
+        let body = serde_json::json!({
+            "prompt": prompt,
+            "n_predict": request.max_tokens.unwrap_or(self.max_tokens),
+            "temperature": request.temperature.unwrap_or(self.temperature),
+            "stop": request.stop,
+            "stream": false,
+        });
+
+        let url = format!("{}/completion", self.endpoint);
+        let response: serde_json::Value = self
+            .client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        response["content"]
+            .as_str()
+            .map(|s| s.trim().to_string())
+            .ok_or_else(|| anyhow!("Malformed completion response"))
+    }
+}
+
+#[async_trait]
+impl TextGenerator for LocalLlm {
+    async fn generate(&self, request: &GenerationRequest) -> Result<String> {
+        debug!("LLM request ({:?}): {} chars", self.backend, request.prompt.len());
+
+        match self.backend {
+            LlmBackend::OpenAi => self.generate_openai(request).await,
+            LlmBackend::LlamaCpp => self.generate_llamacpp(request).await,
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.model
+    }
+}
+
+/// Parse a numbered or bulleted list from model output
+pub fn parse_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .trim_start_matches(['.', ')', '-', '*', ':'])
+                .trim()
+                .to_string()
+        })
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Extract `[n]` citation markers from model output (1-based, deduplicated)
+pub fn parse_citations(text: &str) -> Vec<usize> {
+    let mut citations = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('[') {
+        rest = &rest[start + 1..];
+        if let Some(end) = rest.find(']') {
+            if let Ok(n) = rest[..end].trim().parse::<usize>() {
+                if n > 0 && !citations.contains(&n) {
+                    citations.push(n);
+                }
+            }
+            rest = &rest[end + 1..];
+        } else {
+            break;
+        }
+    }
+
+    citations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve a single canned JSON response and return the request body
+    async fn stub_server(body: serde_json::Value) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 64 * 1024];
+            let mut read = 0;
+            loop {
+                let n = socket.read(&mut buf[read..]).await.unwrap();
+                read += n;
+                let text = String::from_utf8_lossy(&buf[..read]).to_string();
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let len = text[..header_end]
+                        .lines()
+                        .find_map(|l| {
+                            let l = l.to_lowercase();
+                            l.strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if read >= header_end + 4 + len || n == 0 {
+                        break;
+                    }
+                }
+            }
+
+            let payload = body.to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                payload.len(),
+                payload
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&buf[..read]).to_string()
+        });
+
+        (format!("http://{}", addr), handle)
+    }
+
+    fn config(backend: &str, endpoint: String) -> LlmConfig {
+        LlmConfig {
+            enabled: true,
+            backend: backend.to_string(),
+            endpoint,
+            ..LlmConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openai_backend() {
+        let (endpoint, handle) = stub_server(serde_json::json!({
+            "choices": [{"message": {"role": "assistant", "content": " Hello [1] "}}]
+        }))
+        .await;
+
+        let llm = LocalLlm::new(&config("openai", endpoint)).unwrap();
+        let request = GenerationRequest::new("Say hello").with_system("Be brief");
+        let output = llm.generate(&request).await.unwrap();
+        assert_eq!(output, "Hello [1]");
+
+        let raw = handle.await.unwrap();
+        assert!(raw.starts_with("POST /v1/chat/completions"));
+        assert!(raw.contains("Be brief"));
+    }
+
+    #[tokio::test]
+    async fn test_llamacpp_backend() {
+        let (endpoint, handle) =
+            stub_server(serde_json::json!({"content": "1. First\n2. Second"})).await;
+
+        let llm = LocalLlm::new(&config("llamacpp", endpoint)).unwrap();
+        let output = llm.generate(&GenerationRequest::new("List")).await.unwrap();
+        assert_eq!(parse_list(&output), vec!["First", "Second"]);
+
+        let raw = handle.await.unwrap();
+        assert!(raw.starts_with("POST /completion"));
+        assert!(raw.contains("n_predict"));
+    }
+
+    #[test]
+    fn test_disabled_config() {
+        let llm = LocalLlm::from_config(&LlmConfig::default()).unwrap();
+        assert!(llm.is_none());
+    }
+
+    #[test]
+    fn test_parse_citations() {
+        let text = "PoE rewards novelty [1] and reuse [3][1]. See [x] and [].";
+        assert_eq!(parse_citations(text), vec![1, 3]);
+    }
+}
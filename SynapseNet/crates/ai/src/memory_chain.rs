@@ -3,7 +3,7 @@
 //! Provides unified access to episodes and grains from both
 //! local storage and P2P network for reasoning context.
 
-use crate::episodes::{Episode, MemoryChain as EpisodeChain, RetrievedGrain};
+use synapsenet_core::episodes::{Episode, MemoryChain as EpisodeChain, RetrievedGrain};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
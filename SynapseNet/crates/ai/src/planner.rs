@@ -3,8 +3,11 @@
 //! Decomposes high-level goals into executable task graphs using
 //! Hierarchical Task Network (HTN) style planning.
 
+use crate::llm::{parse_list, GenerationRequest, TextGenerator};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tracing::warn;
 use uuid::Uuid;
 
 /// A node in the task graph
@@ -124,7 +127,7 @@ impl TaskGraph {
 
     /// Add an edge (parent -> child)
     pub fn add_edge(&mut self, parent: Uuid, child: Uuid) {
-        self.edges.entry(parent).or_insert_with(Vec::new).push(child);
+        self.edges.entry(parent).or_default().push(child);
         
         // Add dependency to child
         if let Some(child_node) = self.nodes.get_mut(&child) {
@@ -182,7 +185,7 @@ impl TaskGraph {
             in_degree.insert(*node_id, 0);
         }
         
-        for (_, children) in &self.edges {
+        for children in self.edges.values() {
             for child_id in children {
                 *in_degree.get_mut(child_id).unwrap() += 1;
             }
@@ -270,6 +273,8 @@ pub struct Planner {
     max_depth: usize,
     /// Maximum nodes per graph
     max_nodes: usize,
+    /// Optional LLM used for goal decomposition
+    generator: Option<Arc<dyn TextGenerator>>,
 }
 
 impl Planner {
@@ -278,6 +283,7 @@ impl Planner {
         Self {
             max_depth: 4,
             max_nodes: 32,
+            generator: None,
         }
     }
 
@@ -293,39 +299,86 @@ impl Planner {
         self
     }
 
-    /// Decompose a goal into a task graph
+    /// Use an LLM for goal decomposition
+    pub fn with_generator(mut self, generator: Arc<dyn TextGenerator>) -> Self {
+        self.generator = Some(generator);
+        self
+    }
+
+    /// Decompose a goal into a task graph (heuristic decomposition)
     pub fn plan(&self, goal: &str) -> Result<TaskGraph, String> {
-        let mut graph = TaskGraph::new(goal);
-        
-        // Simple decomposition strategy
-        // TODO: Implement more sophisticated HTN planning
         let sub_tasks = self.decompose_goal(goal)?;
-        
-        for (i, sub_task) in sub_tasks.iter().enumerate() {
+        self.build_graph(goal, sub_tasks)
+    }
+
+    /// Decompose a goal into a task graph, using the LLM if configured
+    ///
+    /// Falls back to heuristic decomposition if the generator fails or
+    /// returns nothing usable.
+    pub async fn plan_async(&self, goal: &str) -> Result<TaskGraph, String> {
+        let Some(generator) = &self.generator else {
+            return self.plan(goal);
+        };
+
+        match self.decompose_goal_llm(generator.as_ref(), goal).await {
+            Ok(sub_tasks) if !sub_tasks.is_empty() => self.build_graph(goal, sub_tasks),
+            Ok(_) => {
+                warn!("LLM returned no sub-tasks, using heuristic plan");
+                self.plan(goal)
+            }
+            Err(e) => {
+                warn!("LLM planning failed ({}), using heuristic plan", e);
+                self.plan(goal)
+            }
+        }
+    }
+
+    /// Build a sequential task graph under the goal root
+    fn build_graph(&self, goal: &str, sub_tasks: Vec<String>) -> Result<TaskGraph, String> {
+        let mut graph = TaskGraph::new(goal);
+        let mut prev_id = graph.root;
+
+        for sub_task in sub_tasks {
             let mut node = TaskNode::new(sub_task, NodeType::SubTask);
             node.complexity = 0.3;
-            
+
             // Sequential dependencies
-            if i > 0 {
-                let prev_id = graph.nodes.keys().nth(i).copied().unwrap();
-                node.add_dependency(prev_id);
-            } else {
-                node.add_dependency(graph.root);
-            }
-            
             let node_id = graph.add_node(node);
-            graph.add_edge(graph.root, node_id);
+            graph.add_edge(prev_id, node_id);
+            prev_id = node_id;
         }
 
         graph.validate()?;
         Ok(graph)
     }
 
-    /// Simple goal decomposition (placeholder)
+    /// Ask the LLM for an ordered list of sub-tasks
+    async fn decompose_goal_llm(
+        &self,
+        generator: &dyn TextGenerator,
+        goal: &str,
+    ) -> Result<Vec<String>, String> {
+        let request = GenerationRequest::new(format!(
+            "Goal: {}\n\nBreak this goal into at most {} short, concrete research steps \
+             that can each be answered from a knowledge base. \
+             Reply with a numbered list, one step per line, and nothing else.",
+            goal,
+            self.max_nodes.min(self.max_depth * 2)
+        ))
+        .with_system("You are a planning assistant for a semantic memory network.");
+
+        let output = generator
+            .generate(&request)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut sub_tasks = parse_list(&output);
+        sub_tasks.truncate(self.max_nodes.saturating_sub(1));
+        Ok(sub_tasks)
+    }
+
+    /// Simple heuristic goal decomposition
     fn decompose_goal(&self, goal: &str) -> Result<Vec<String>, String> {
-        // TODO: Use LLM or rule-based decomposition
-        // For now, simple heuristic decomposition
-        
         let sub_tasks = vec![
             format!("Understand: {}", goal),
             format!("Gather information about: {}", goal),
@@ -393,6 +446,11 @@ mod tests {
         let child_id = child.id;
         graph.add_node(child);
         
+        let child_node = graph.get_node(&child_id).unwrap();
+        assert!(!child_node.dependencies_met(&graph));
+
+        let root = graph.root;
+        graph.get_node_mut(&root).unwrap().status = TaskStatus::Completed;
         let child_node = graph.get_node(&child_id).unwrap();
         assert!(child_node.dependencies_met(&graph));
     }
@@ -443,4 +501,49 @@ mod tests {
         // 1 of 2 nodes completed = 50%
         assert_eq!(graph.completion_percentage(), 50.0);
     }
+
+    struct FixedGenerator(&'static str);
+
+    #[async_trait::async_trait]
+    impl TextGenerator for FixedGenerator {
+        async fn generate(&self, _request: &GenerationRequest) -> anyhow::Result<String> {
+            Ok(self.0.to_string())
+        }
+
+        fn name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_planner_with_generator() {
+        let generator = Arc::new(FixedGenerator(
+            "1. Define proof of emergence\n2. Find how novelty is scored\n3. Summarize rewards",
+        ));
+        let planner = Planner::new().with_generator(generator);
+        let graph = planner.plan_async("How does PoE work?").await.unwrap();
+
+        assert_eq!(graph.nodes.len(), 4);
+        let order = graph.topological_sort().unwrap();
+        let tasks: Vec<&str> = order
+            .iter()
+            .filter(|id| **id != graph.root)
+            .map(|id| graph.get_node(id).unwrap().task.as_str())
+            .collect();
+        assert_eq!(
+            tasks,
+            vec![
+                "Define proof of emergence",
+                "Find how novelty is scored",
+                "Summarize rewards"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_planner_generator_fallback() {
+        let planner = Planner::new().with_generator(Arc::new(FixedGenerator("")));
+        let graph = planner.plan_async("How does PoE work?").await.unwrap();
+        assert_eq!(graph.nodes.len(), 5);
+    }
 }
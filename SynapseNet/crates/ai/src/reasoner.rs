@@ -1,10 +1,15 @@
 //! Main reasoning loop - Goal → Plan → Think → Reflect → Learn
 
-use crate::episodes::Episode;
+use synapsenet_core::episodes::Episode;
+use crate::llm::{parse_citations, GenerationRequest, LocalLlm, TextGenerator};
 use crate::memory_chain::{MemoryChainManager, MemoryConfig};
-use crate::planner::{Planner, TaskGraph, TaskStatus};
+use crate::planner::{Planner, TaskGraph};
 use crate::reflection::Reflector;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use synapsenet_core::episodes::RetrievedGrain;
+use synapsenet_core::LlmConfig;
+use tracing::warn;
 use uuid::Uuid;
 
 /// Reasoning configuration
@@ -43,6 +48,9 @@ pub struct TraceStep {
     pub step: u32,
     pub task: String,
     pub sources: Vec<String>,
+    /// Grain IDs cited in the synthesis
+    #[serde(default)]
+    pub citations: Vec<String>,
     pub synthesis: String,
     pub confidence: f64,
 }
@@ -53,8 +61,14 @@ pub struct Reasoner {
     planner: Planner,
     memory: MemoryChainManager,
     reflector: Reflector,
+    generator: Option<Arc<dyn TextGenerator>>,
 }
 
+/// Instruction for grounded synthesis
+const SYNTHESIS_SYSTEM_PROMPT: &str = "You answer questions using only the numbered sources \
+provided. Cite every claim with the source number in square brackets, e.g. [2]. \
+If the sources do not contain the answer, say so.";
+
 impl Reasoner {
     /// Create new reasoner
     pub fn new(config: ReasonerConfig) -> Self {
@@ -62,14 +76,34 @@ impl Reasoner {
             planner: Planner::new(),
             memory: MemoryChainManager::new(config.memory_config.clone()),
             reflector: Reflector::new(),
+            generator: None,
+            config,
+        }
+    }
+
+    /// Create reasoner that plans and synthesises with an LLM
+    pub fn with_generator(config: ReasonerConfig, generator: Arc<dyn TextGenerator>) -> Self {
+        Self {
+            planner: Planner::new().with_generator(generator.clone()),
+            memory: MemoryChainManager::new(config.memory_config.clone()),
+            reflector: Reflector::new(),
+            generator: Some(generator),
             config,
         }
     }
 
+    /// Create reasoner from LLM configuration (heuristic mode if disabled)
+    pub fn from_llm_config(config: ReasonerConfig, llm: &LlmConfig) -> anyhow::Result<Self> {
+        Ok(match LocalLlm::from_config(llm)? {
+            Some(generator) => Self::with_generator(config, Arc::new(generator)),
+            None => Self::new(config),
+        })
+    }
+
     /// Execute reasoning cycle for a goal
     pub async fn reason(&mut self, goal_id: Uuid, goal_text: &str) -> Result<ReasoningResult, String> {
         // 1. Plan: decompose goal into tasks
-        let plan = self.planner.plan(goal_text)?;
+        let plan = self.plan(goal_text).await?;
         
         // 2. Think: execute tasks
         let mut episodes = Vec::new();
@@ -89,6 +123,7 @@ impl Reasoner {
                 step: step as u32,
                 task: task_node.task.clone(),
                 sources: episode.retrieved_grains.iter().map(|g| g.grain_id.clone()).collect(),
+                citations: citations_of(&episode),
                 synthesis: episode.synthesis.clone(),
                 confidence: episode.confidence,
             };
@@ -97,14 +132,8 @@ impl Reasoner {
             episodes.push(episode);
         }
         
-        // 3. Reflect: verify consistency
-        if self.config.enable_reflection {
-            self.reflector.reflect(&episodes)?;
-        }
-        
-        // 4. Synthesize final answer
-        let answer = self.synthesize_answer(&episodes);
-        let confidence = self.calculate_confidence(&episodes);
+        // 3-4. Reflect and synthesize final answer
+        let (answer, confidence) = self.conclude(goal_text, &episodes).await?;
         
         Ok(ReasoningResult {
             goal_id,
@@ -115,8 +144,24 @@ impl Reasoner {
         })
     }
 
+    /// Decompose a goal into a task graph
+    pub async fn plan(&self, goal_text: &str) -> Result<TaskGraph, String> {
+        self.planner.plan_async(goal_text).await
+    }
+
+    /// Reflect on completed episodes and synthesize the final answer
+    pub async fn conclude(&self, goal_text: &str, episodes: &[Episode]) -> Result<(String, f64), String> {
+        if self.config.enable_reflection {
+            self.reflector.reflect(episodes)?;
+        }
+
+        let answer = self.synthesize_answer(goal_text, episodes).await;
+        let confidence = self.calculate_confidence(episodes);
+        Ok((answer, confidence))
+    }
+
     /// Execute single task
-    async fn execute_task(&mut self, goal_id: Uuid, step: u32, task: &str) -> Result<Episode, String> {
+    pub async fn execute_task(&mut self, goal_id: Uuid, step: u32, task: &str) -> Result<Episode, String> {
         let mut episode = Episode::new(goal_id, step, task);
         
         // Generate query embedding (mock)
@@ -129,9 +174,21 @@ impl Reasoner {
             episode.add_grain(grain);
         }
         
-        // Synthesize (mock)
-        let synthesis = format!("Answer to: {}", task);
-        episode.set_synthesis(synthesis, 0.8);
+        // Synthesize
+        match self.grounded_synthesis(task, &episode.retrieved_grains).await {
+            Some((synthesis, cited)) => {
+                let confidence = citation_confidence(&episode.retrieved_grains, &cited);
+                episode.set_synthesis(synthesis, confidence);
+                episode.metadata = serde_json::json!({
+                    "generator": self.generator.as_ref().map(|g| g.name().to_string()),
+                    "citations": cited,
+                });
+            }
+            None => {
+                let synthesis = format!("Answer to: {}", task);
+                episode.set_synthesis(synthesis, 0.8);
+            }
+        }
         
         // Store episode
         self.memory.add_episode(episode.clone());
@@ -139,11 +196,77 @@ impl Reasoner {
         Ok(episode)
     }
 
+    /// Ask the LLM to answer `question` from the given grains
+    ///
+    /// Returns the synthesis and the IDs of the grains it cites, or `None`
+    /// when no generator is configured or generation failed.
+    async fn grounded_synthesis(
+        &self,
+        question: &str,
+        grains: &[RetrievedGrain],
+    ) -> Option<(String, Vec<String>)> {
+        let generator = self.generator.as_ref()?;
+
+        let sources = if grains.is_empty() {
+            "(no sources retrieved)".to_string()
+        } else {
+            grains
+                .iter()
+                .enumerate()
+                .map(|(i, g)| {
+                    format!(
+                        "[{}] grain {} ({}): {}",
+                        i + 1,
+                        g.grain_id,
+                        g.source,
+                        g.snippet.as_deref().unwrap_or("(no text available)")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let request = GenerationRequest::new(format!(
+            "Sources:\n{}\n\nQuestion: {}\n\nAnswer:",
+            sources, question
+        ))
+        .with_system(SYNTHESIS_SYSTEM_PROMPT);
+
+        match generator.generate(&request).await {
+            Ok(text) => {
+                let cited = parse_citations(&text)
+                    .into_iter()
+                    .filter_map(|n| grains.get(n - 1))
+                    .map(|g| g.grain_id.clone())
+                    .collect();
+                Some((text, cited))
+            }
+            Err(e) => {
+                warn!("LLM synthesis failed: {}", e);
+                None
+            }
+        }
+    }
+
     /// Synthesize final answer from episodes
-    fn synthesize_answer(&self, episodes: &[Episode]) -> String {
+    async fn synthesize_answer(&self, goal_text: &str, episodes: &[Episode]) -> String {
         if episodes.is_empty() {
             return "No answer generated".to_string();
         }
+
+        if self.generator.is_some() {
+            // Ground the final answer in every grain seen during the cycle
+            let mut grains: Vec<RetrievedGrain> = Vec::new();
+            for grain in episodes.iter().flat_map(|e| &e.retrieved_grains) {
+                if !grains.iter().any(|g| g.grain_id == grain.grain_id) {
+                    grains.push(grain.clone());
+                }
+            }
+
+            if let Some((answer, _)) = self.grounded_synthesis(goal_text, &grains).await {
+                return answer;
+            }
+        }
         
         // Combine episode syntheses
         episodes.iter()
@@ -163,6 +286,35 @@ impl Reasoner {
     }
 }
 
+/// Grain IDs cited by an episode's synthesis
+fn citations_of(episode: &Episode) -> Vec<String> {
+    episode.metadata["citations"]
+        .as_array()
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|id| id.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Confidence from the relevance of cited grains
+///
+/// Uncited answers are treated as ungrounded.
+fn citation_confidence(grains: &[RetrievedGrain], cited: &[String]) -> f64 {
+    let scores: Vec<f64> = grains
+        .iter()
+        .filter(|g| cited.contains(&g.grain_id))
+        .map(|g| g.score)
+        .collect();
+
+    if scores.is_empty() {
+        return 0.3;
+    }
+
+    scores.iter().sum::<f64>() / scores.len() as f64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,4 +338,43 @@ mod tests {
         assert_eq!(result.goal_id, goal_id);
         assert!(!result.answer.is_empty());
     }
+
+    struct EchoGenerator;
+
+    #[async_trait::async_trait]
+    impl TextGenerator for EchoGenerator {
+        async fn generate(&self, request: &GenerationRequest) -> anyhow::Result<String> {
+            if request.prompt.contains("numbered list") {
+                Ok("1. Look up emergence".to_string())
+            } else {
+                Ok("Emergence is rewarded [1].".to_string())
+            }
+        }
+
+        fn name(&self) -> &str {
+            "echo"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reasoning_with_generator() {
+        let config = ReasonerConfig::default();
+        let mut reasoner = Reasoner::with_generator(config, Arc::new(EchoGenerator));
+
+        let result = reasoner.reason(Uuid::new_v4(), "What is PoE?").await.unwrap();
+
+        assert_eq!(result.answer, "Emergence is rewarded [1].");
+        assert!(result.trace.iter().any(|s| s.task == "Look up emergence"));
+    }
+
+    #[test]
+    fn test_citation_confidence() {
+        let grains = vec![
+            RetrievedGrain::new("a", 0.9, "local"),
+            RetrievedGrain::new("b", 0.5, "local"),
+        ];
+
+        assert_eq!(citation_confidence(&grains, &["a".to_string()]), 0.9);
+        assert_eq!(citation_confidence(&grains, &[]), 0.3);
+    }
 }
@@ -1,6 +1,6 @@
 //! Reflection - consistency checking and verification
 
-use crate::episodes::Episode;
+use synapsenet_core::episodes::Episode;
 use serde::{Deserialize, Serialize};
 
 /// Reflection result
@@ -0,0 +1,173 @@
+//! REST API v2 for tool approvals
+//!
+//! Agent actions whose tool policy requires approval wait in the node's
+//! `ApprovalQueue`; these endpoints list them and record the decision.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use synapsenet_agent::{ApprovalError, ApprovalStatus};
+
+use crate::rest::{ApiError, ApiState};
+
+/// Approval list query parameters
+#[derive(Debug, Deserialize)]
+pub struct ApprovalListQuery {
+    pub status: Option<String>,
+}
+
+/// Approve request
+#[derive(Debug, Default, Deserialize)]
+pub struct ApproveRequest {
+    pub by: Option<String>,
+}
+
+/// Reject request
+#[derive(Debug, Deserialize)]
+pub struct RejectRequest {
+    pub reason: String,
+    pub by: Option<String>,
+}
+
+/// Create approvals API router (nested under /v2)
+pub fn create_approval_router() -> Router<Arc<ApiState>> {
+    Router::new()
+        .route("/approvals", get(list_approvals))
+        .route("/approvals/:id", get(get_approval))
+        .route("/approvals/:id/approve", post(approve))
+        .route("/approvals/:id/reject", post(reject))
+}
+
+/// List approval requests
+async fn list_approvals(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ApprovalListQuery>,
+) -> Result<Response, ApiError> {
+    info!("GET /v2/approvals");
+
+    let status = match query.status.as_deref() {
+        Some(name) => match ApprovalStatus::parse(name) {
+            Some(status) => Some(status),
+            None => {
+                return Ok(error_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("Unknown status: {}", name),
+                ))
+            }
+        },
+        None => None,
+    };
+
+    Ok(Json(state.approvals.list(status)?).into_response())
+}
+
+/// Get an approval request with its proposed input
+async fn get_approval(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    info!("GET /v2/approvals/{}", id);
+
+    let Ok(approval_id) = Uuid::parse_str(&id) else {
+        return Ok(error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid approval id: {}", id),
+        ));
+    };
+
+    decision_response(state.approvals.get(&approval_id))
+}
+
+/// Approve a pending action
+async fn approve(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<String>,
+    request: Option<Json<ApproveRequest>>,
+) -> Result<Response, ApiError> {
+    info!("POST /v2/approvals/{}/approve", id);
+
+    let Ok(approval_id) = Uuid::parse_str(&id) else {
+        return Ok(error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid approval id: {}", id),
+        ));
+    };
+    let by = request
+        .and_then(|Json(r)| r.by)
+        .unwrap_or_else(|| "api".to_string());
+
+    decision_response(state.approvals.approve(&approval_id, &by))
+}
+
+/// Reject a pending action with a reason
+async fn reject(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<String>,
+    Json(request): Json<RejectRequest>,
+) -> Result<Response, ApiError> {
+    info!("POST /v2/approvals/{}/reject", id);
+
+    let Ok(approval_id) = Uuid::parse_str(&id) else {
+        return Ok(error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid approval id: {}", id),
+        ));
+    };
+    if request.reason.trim().is_empty() {
+        return Ok(error_response(
+            StatusCode::BAD_REQUEST,
+            "A reason is required".to_string(),
+        ));
+    }
+    let by = request.by.unwrap_or_else(|| "api".to_string());
+
+    decision_response(state.approvals.reject(&approval_id, &by, &request.reason))
+}
+
+/// Map queue errors callers can act on to 404/409
+fn decision_response(
+    result: Result<synapsenet_agent::ApprovalRequest, ApprovalError>,
+) -> Result<Response, ApiError> {
+    match result {
+        Ok(request) => Ok(Json(request).into_response()),
+        Err(e @ ApprovalError::NotFound(_)) => {
+            Ok(error_response(StatusCode::NOT_FOUND, e.to_string()))
+        }
+        Err(e @ ApprovalError::AlreadyDecided(_)) => {
+            Ok(error_response(StatusCode::CONFLICT, e.to_string()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decision_status_codes() {
+        let id = Uuid::new_v4();
+
+        let response = decision_response(Err(ApprovalError::NotFound(id))).unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response =
+            decision_response(Err(ApprovalError::AlreadyDecided(ApprovalStatus::Expired))).unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        assert!(decision_response(Err(ApprovalError::Storage("disk full".to_string()))).is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! REST API v2 for curator review
+//!
+//! Queries the content policy sends to curation wait in the node's
+//! `CuratorQueue`. Curators act with decisions signed by their registered
+//! keys; requesters poll `release` for the outcome with the release token
+//! `/query` returned. Held results and requesters never appear in the task
+//! views, and results only in an approved release.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use synapsenet_core::{CuratorAction, CuratorDecision, SignedRecord};
+use synapsenet_storage::{CuratorAuditEntry, CuratorTask, CuratorTaskStatus, DecisionOutcome};
+
+use crate::rest::{ApiError, ApiState};
+
+const DEFAULT_LIMIT: usize = 100;
+
+/// Task list query parameters
+#[derive(Debug, Deserialize)]
+pub struct TaskListQuery {
+    pub status: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Release query parameters
+#[derive(Debug, Deserialize)]
+pub struct ReleaseQuery {
+    /// Release token returned with the held query
+    pub token: Option<String>,
+}
+
+/// Audit query parameters
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub task_id: Option<Uuid>,
+    pub limit: Option<usize>,
+}
+
+/// Signed curator decision; see `CuratorDecision::signing_bytes`
+#[derive(Debug, Deserialize)]
+pub struct DecisionRequest {
+    /// Curator public key (hex)
+    pub curator: String,
+    /// claim, approve or reject
+    pub action: String,
+    pub note: Option<String>,
+    pub ts_unix_ms: i64,
+    /// Signature (hex)
+    pub sig: String,
+}
+
+/// Review task without its held outcome or requester
+#[derive(Debug, Serialize)]
+pub struct TaskView {
+    pub id: Uuid,
+    pub route: String,
+    pub subject: String,
+    pub classification: serde_json::Value,
+    pub status: String,
+    pub curator: Option<String>,
+    pub note: Option<String>,
+    pub created_at: i64,
+    pub decided_at: Option<i64>,
+}
+
+impl From<CuratorTask> for TaskView {
+    fn from(task: CuratorTask) -> Self {
+        Self {
+            id: task.id,
+            route: task.route,
+            subject: task.subject,
+            classification: task.classification,
+            status: task.status.as_str().to_string(),
+            curator: task.curator.map(hex::encode),
+            note: task.note,
+            created_at: task.created_at,
+            decided_at: task.decided_at,
+        }
+    }
+}
+
+/// Audit log entry
+#[derive(Debug, Serialize)]
+pub struct AuditView {
+    pub id: i64,
+    pub task_id: Uuid,
+    pub curator: String,
+    pub action: CuratorAction,
+    pub note: Option<String>,
+    pub ts_unix_ms: i64,
+    pub sig: String,
+    pub recorded_at: i64,
+}
+
+impl From<CuratorAuditEntry> for AuditView {
+    fn from(entry: CuratorAuditEntry) -> Self {
+        Self {
+            id: entry.id,
+            task_id: entry.task_id,
+            curator: hex::encode(entry.curator),
+            action: entry.action,
+            note: entry.note,
+            ts_unix_ms: entry.ts_unix_ms,
+            sig: hex::encode(entry.sig),
+            recorded_at: entry.recorded_at,
+        }
+    }
+}
+
+/// Registered curator
+#[derive(Debug, Serialize)]
+pub struct CuratorView {
+    pub public_key: String,
+    pub name: Option<String>,
+}
+
+/// Create curation API router (nested under /v2)
+pub fn create_curation_router() -> Router<Arc<ApiState>> {
+    Router::new()
+        .route("/curation/tasks", get(list_tasks))
+        .route("/curation/tasks/:id", get(get_task))
+        .route("/curation/tasks/:id/decision", post(decide))
+        .route("/curation/tasks/:id/release", get(release))
+        .route("/curation/curators", get(list_curators))
+        .route("/curation/audit", get(audit))
+}
+
+/// List review tasks, oldest first
+async fn list_tasks(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<TaskListQuery>,
+) -> Result<Response, ApiError> {
+    info!("GET /v2/curation/tasks");
+
+    let status = match query.status.as_deref() {
+        Some(name) => match CuratorTaskStatus::parse(name) {
+            Some(status) => Some(status),
+            None => {
+                return Ok(error_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("Unknown status: {}", name),
+                ))
+            }
+        },
+        None => None,
+    };
+
+    let tasks = state
+        .curation
+        .list(status, query.limit.unwrap_or(DEFAULT_LIMIT))?;
+    Ok(Json(tasks.into_iter().map(TaskView::from).collect::<Vec<_>>()).into_response())
+}
+
+/// Get a review task
+async fn get_task(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    info!("GET /v2/curation/tasks/{}", id);
+
+    let Ok(task_id) = Uuid::parse_str(&id) else {
+        return Ok(invalid_task_id(&id));
+    };
+
+    match state.curation.get(&task_id)? {
+        Some(task) => Ok(Json(TaskView::from(task)).into_response()),
+        None => Ok(task_not_found(&task_id)),
+    }
+}
+
+/// Apply a signed claim, approval or rejection
+async fn decide(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<String>,
+    Json(request): Json<DecisionRequest>,
+) -> Result<Response, ApiError> {
+    info!(
+        "POST /v2/curation/tasks/{}/decision: {}",
+        id, request.action
+    );
+
+    let Ok(task_id) = Uuid::parse_str(&id) else {
+        return Ok(invalid_task_id(&id));
+    };
+    let decision = match parse_decision(task_id, request) {
+        Ok(decision) => decision,
+        Err(message) => return Ok(error_response(StatusCode::BAD_REQUEST, message)),
+    };
+    if !decision.verify()? {
+        return Ok(error_response(
+            StatusCode::UNAUTHORIZED,
+            "Invalid curator signature".to_string(),
+        ));
+    }
+
+    decision_response(&task_id, state.curation.decide(&decision)?)
+}
+
+/// The outcome of a task, for the holder of its release token
+///
+/// A wrong or missing token gets the same 404 as an unknown task.
+async fn release(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<String>,
+    Query(query): Query<ReleaseQuery>,
+) -> Result<Response, ApiError> {
+    info!("GET /v2/curation/tasks/{}/release", id);
+
+    let Ok(task_id) = Uuid::parse_str(&id) else {
+        return Ok(invalid_task_id(&id));
+    };
+
+    let token = query.token.unwrap_or_default();
+    match state.curation.release(&task_id, &token)? {
+        Some(release) => Ok(Json(release).into_response()),
+        None => Ok(task_not_found(&task_id)),
+    }
+}
+
+/// List registered curator keys
+async fn list_curators(State(state): State<Arc<ApiState>>) -> Result<Response, ApiError> {
+    info!("GET /v2/curation/curators");
+
+    let curators: Vec<CuratorView> = state
+        .curation
+        .curators()?
+        .into_iter()
+        .map(|(key, name)| CuratorView {
+            public_key: hex::encode(key),
+            name,
+        })
+        .collect();
+    Ok(Json(curators).into_response())
+}
+
+/// Applied curator decisions, oldest first
+async fn audit(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Response, ApiError> {
+    info!("GET /v2/curation/audit");
+
+    let entries = state
+        .curation
+        .audit(query.task_id.as_ref(), query.limit.unwrap_or(DEFAULT_LIMIT))?;
+    Ok(Json(entries.into_iter().map(AuditView::from).collect::<Vec<_>>()).into_response())
+}
+
+fn parse_decision(task_id: Uuid, request: DecisionRequest) -> Result<CuratorDecision, String> {
+    let action = CuratorAction::parse(&request.action)
+        .ok_or_else(|| format!("Unknown action: {}", request.action))?;
+    let note = request.note.as_deref().unwrap_or_default();
+    if action == CuratorAction::Reject && note.trim().is_empty() {
+        return Err("A note is required to reject".to_string());
+    }
+    let curator = hex::decode(&request.curator)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| "Curator must be a 32-byte hex public key".to_string())?;
+    let sig = hex::decode(&request.sig).map_err(|_| "Signature must be hex".to_string())?;
+
+    Ok(CuratorDecision {
+        task_id,
+        curator,
+        action,
+        note: request.note,
+        ts_unix_ms: request.ts_unix_ms,
+        sig,
+    })
+}
+
+/// Map decision outcomes callers can act on to 403/404/409
+fn decision_response(task_id: &Uuid, outcome: DecisionOutcome) -> Result<Response, ApiError> {
+    match outcome {
+        DecisionOutcome::Applied(task) => Ok(Json(TaskView::from(*task)).into_response()),
+        DecisionOutcome::NotFound => Ok(task_not_found(task_id)),
+        DecisionOutcome::NotCurator => Ok(error_response(
+            StatusCode::FORBIDDEN,
+            "Key is not a registered curator".to_string(),
+        )),
+        DecisionOutcome::Conflict(message) => Ok(error_response(StatusCode::CONFLICT, message)),
+    }
+}
+
+fn invalid_task_id(id: &str) -> Response {
+    error_response(StatusCode::BAD_REQUEST, format!("Invalid task id: {}", id))
+}
+
+fn task_not_found(id: &Uuid) -> Response {
+    error_response(StatusCode::NOT_FOUND, format!("Task not found: {}", id))
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+
+    fn request(decision: &CuratorDecision) -> DecisionRequest {
+        DecisionRequest {
+            curator: hex::encode(decision.curator),
+            action: decision.action.as_str().to_string(),
+            note: decision.note.clone(),
+            ts_unix_ms: decision.ts_unix_ms,
+            sig: hex::encode(&decision.sig),
+        }
+    }
+
+    #[test]
+    fn test_parse_signed_decision() {
+        let key = SigningKey::from_bytes(&[3u8; 32]);
+        let task_id = Uuid::new_v4();
+
+        let decision = CuratorDecision::new(
+            task_id,
+            CuratorAction::Approve,
+            Some("ok".to_string()),
+            &key,
+        );
+        let parsed = parse_decision(task_id, request(&decision)).unwrap();
+        assert_eq!(parsed, decision);
+        assert!(parsed.verify().unwrap());
+
+        // Signed for another task
+        let parsed = parse_decision(Uuid::new_v4(), request(&decision)).unwrap();
+        assert!(!parsed.verify().unwrap());
+
+        let reject = CuratorDecision::new(task_id, CuratorAction::Reject, None, &key);
+        assert!(parse_decision(task_id, request(&reject)).is_err());
+    }
+
+    #[test]
+    fn test_decision_status_codes() {
+        let id = Uuid::new_v4();
+
+        let response = decision_response(&id, DecisionOutcome::NotFound).unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = decision_response(&id, DecisionOutcome::NotCurator).unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = decision_response(
+            &id,
+            DecisionOutcome::Conflict("Task already approved".into()),
+        )
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+}
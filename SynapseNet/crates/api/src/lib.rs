@@ -1,11 +1,17 @@
 // SynapseNet API - RPC and REST interfaces
 
+pub mod approval_api;
+pub mod curation_api;
+pub mod mcp;
 pub mod metrics;
+pub mod moderation_api;
+pub mod reason_api;
 pub mod rest;
 pub mod rpc;
 pub mod v2;
 
+pub use mcp::{create_mcp_router, McpHttpAccess, McpServer};
 pub use metrics::create_metrics_router;
-pub use rest::{create_router, ApiState};
+pub use rest::{create_router, ApiState, LOCAL_PEER};
 pub use v2::create_v2_router;
 pub use rpc::RpcServer;
@@ -0,0 +1,1025 @@
+//! Model Context Protocol server
+//!
+//! Lets MCP clients (AI assistants) use this node: every enabled tool in the
+//! agent's `ToolRegistry` is offered as an MCP tool, with its `ToolSchema`
+//! translated to JSON Schema, and every public grain is readable as a
+//! `synapse://grain/<id>` resource. Calls go through `AgentCore`, so tool
+//! policies and approvals apply as they do for goals.
+//!
+//! Two transports share one `McpServer`: newline-delimited JSON-RPC over
+//! stdio (`serve`) and JSON over HTTP POST (`create_mcp_router`). Each MCP
+//! session runs as one agent goal. Over HTTP, only sessions issued by
+//! `initialize` are accepted, at most `MAX_HTTP_SESSIONS` at a time and
+//! until idle for `SESSION_IDLE_TIMEOUT`; browser origins other than loopback
+//! are refused, and `McpHttpAccess` can require a bearer token.
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use synapsenet_agent::{AgentCore, ExecutionContext, ToolInput, ToolSchema};
+use synapsenet_core::Grain;
+use synapsenet_storage::Store;
+use synapsenet_tools_official::GrainMemory;
+
+/// Newest protocol revision this server speaks
+pub const MCP_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Revisions accepted from clients, newest first
+const SUPPORTED_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// HTTP header carrying the session id
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// HTTP sessions open at once
+const MAX_HTTP_SESSIONS: usize = 64;
+
+/// HTTP sessions without a request for this long are ended
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Grain resources returned per `resources/list` page
+const RESOURCE_PAGE_SIZE: usize = 100;
+
+const GRAIN_URI_PREFIX: &str = "synapse://grain/";
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+/// MCP: the requested resource does not exist
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// MCP server over the node's agent tools and grain store
+pub struct McpServer {
+    agent: Arc<AgentCore>,
+    store: Arc<Mutex<Store>>,
+    memory: Option<Arc<GrainMemory>>,
+    user_id: String,
+}
+
+/// Access rules for the MCP HTTP transport
+#[derive(Debug, Clone, Default)]
+pub struct McpHttpAccess {
+    /// Token clients must send as `Authorization: Bearer <token>`
+    pub bearer_token: Option<String>,
+    /// Browser origins allowed besides loopback ones
+    pub allowed_origins: Vec<String>,
+}
+
+/// State of the MCP HTTP router
+struct HttpState {
+    server: Arc<McpServer>,
+    access: McpHttpAccess,
+    sessions: Mutex<Sessions>,
+}
+
+/// HTTP sessions issued by `initialize`, not deleted or expired yet
+struct Sessions {
+    last_active: HashMap<Uuid, Instant>,
+    capacity: usize,
+    idle_timeout: Duration,
+}
+
+impl Sessions {
+    fn new(capacity: usize, idle_timeout: Duration) -> Self {
+        Self {
+            last_active: HashMap::new(),
+            capacity,
+            idle_timeout,
+        }
+    }
+
+    /// Drop sessions idle past the timeout, returning them to be ended
+    fn expire(&mut self, now: Instant) -> Vec<Uuid> {
+        let idle_timeout = self.idle_timeout;
+        let expired: Vec<Uuid> = self
+            .last_active
+            .iter()
+            .filter(|(_, &active)| now.duration_since(active) >= idle_timeout)
+            .map(|(&session, _)| session)
+            .collect();
+        for session in &expired {
+            self.last_active.remove(session);
+        }
+        expired
+    }
+
+    /// Issue a session; `None` while every slot is taken
+    fn open(&mut self, now: Instant) -> Option<Uuid> {
+        if self.last_active.len() >= self.capacity {
+            return None;
+        }
+        let session = Uuid::new_v4();
+        self.last_active.insert(session, now);
+        Some(session)
+    }
+
+    /// Record a request on a session; false if it is unknown
+    fn touch(&mut self, session: &Uuid, now: Instant) -> bool {
+        match self.last_active.get_mut(session) {
+            Some(active) => {
+                *active = now;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn close(&mut self, session: &Uuid) -> bool {
+        self.last_active.remove(session).is_some()
+    }
+}
+
+/// JSON-RPC error raised while handling a request
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl McpServer {
+    /// Serve the tools registered with `agent` and the grains in `store`
+    pub fn new(agent: Arc<AgentCore>, store: Arc<Mutex<Store>>) -> Self {
+        Self {
+            agent,
+            store,
+            memory: None,
+            user_id: "mcp".to_string(),
+        }
+    }
+
+    /// Offer the grain tools of `memory`
+    ///
+    /// Registers `grain_search`, `grain_get`, `grain_add` and `grain_link`
+    /// with the agent's registry; their quotas apply per session.
+    pub fn with_grain_memory(mut self, memory: Arc<GrainMemory>) -> Result<Self, String> {
+        memory.register(self.agent.registry())?;
+        self.memory = Some(memory);
+        Ok(self)
+    }
+
+    /// User id that tool policies see for MCP calls (default `mcp`)
+    pub fn with_user_id(mut self, user_id: &str) -> Self {
+        self.user_id = user_id.to_string();
+        self
+    }
+
+    /// Serve one session of newline-delimited JSON-RPC until `reader` closes
+    pub async fn serve<R, W>(&self, reader: R, mut writer: W) -> anyhow::Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let session = Uuid::new_v4();
+        let mut lines = reader.lines();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle(session, message).await,
+                Err(e) => Some(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, format!("Parse error: {}", e)),
+                )),
+            };
+
+            if let Some(response) = response {
+                let mut bytes = serde_json::to_vec(&response)?;
+                bytes.push(b'\n');
+                writer.write_all(&bytes).await?;
+                writer.flush().await?;
+            }
+        }
+
+        self.end_session(&session);
+        Ok(())
+    }
+
+    /// Handle one JSON-RPC message; notifications get no response
+    pub async fn handle(&self, session: Uuid, message: Value) -> Option<Value> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // This server sends no requests, so client responses are dropped
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            let id = message.get("id").cloned().unwrap_or(Value::Null);
+            return Some(error_response(
+                id,
+                RpcError::new(INVALID_REQUEST, "Invalid request"),
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let Some(id) = message.get("id").cloned() else {
+            debug!("MCP notification: {}", method);
+            return None;
+        };
+
+        debug!("MCP request: {}", method);
+        let result = match method {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools()),
+            "tools/call" => self.call_tool(session, &params).await,
+            "resources/list" => self.list_resources(&params),
+            "resources/read" => self.read_resource(&params),
+            "resources/templates/list" => Ok(json!({
+                "resourceTemplates": [{
+                    "uriTemplate": format!("{}{{grain_id}}", GRAIN_URI_PREFIX),
+                    "name": "grain",
+                    "description": "A grain in SynapseNet memory, by hex id",
+                    "mimeType": "application/json",
+                }]
+            })),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => error_response(id, e),
+        })
+    }
+
+    /// Release what a finished session holds
+    pub fn end_session(&self, session: &Uuid) {
+        if let Some(memory) = &self.memory {
+            memory.forget_goal(session);
+        }
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params
+            .get("protocolVersion")
+            .and_then(Value::as_str)
+            .unwrap_or(MCP_PROTOCOL_VERSION);
+        let version = SUPPORTED_VERSIONS
+            .iter()
+            .find(|v| **v == requested)
+            .copied()
+            .unwrap_or(MCP_PROTOCOL_VERSION);
+
+        info!("MCP session initialized (protocol {})", version);
+
+        json!({
+            "protocolVersion": version,
+            "capabilities": {
+                "tools": { "listChanged": false },
+                "resources": { "subscribe": false, "listChanged": false },
+            },
+            "serverInfo": {
+                "name": "synapsenet",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    fn list_tools(&self) -> Value {
+        let registry = self.agent.registry();
+        let mut infos: Vec<_> = registry.list().into_iter().filter(|t| t.enabled).collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let tools: Vec<Value> = infos
+            .into_iter()
+            .filter_map(|info| {
+                let tool = registry.get(&info.name)?;
+                let schema = tool.schema();
+                Some(json!({
+                    "name": info.name,
+                    "description": info.description,
+                    "inputSchema": input_schema(&schema),
+                }))
+            })
+            .collect();
+
+        json!({ "tools": tools })
+    }
+
+    async fn call_tool(&self, session: Uuid, params: &Value) -> Result<Value, RpcError> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing tool name"))?;
+        if self.agent.registry().get(name).is_none() {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("Unknown tool: {}", name),
+            ));
+        }
+        let arguments = match params.get("arguments") {
+            Some(Value::Null) | None => json!({}),
+            Some(arguments) => arguments.clone(),
+        };
+
+        let input = ToolInput {
+            params: arguments,
+            context: ExecutionContext {
+                goal_id: session,
+                episode_id: None,
+                user_id: self.user_id.clone(),
+                timestamp: chrono::Utc::now().timestamp(),
+                metadata: HashMap::from([("transport".to_string(), "mcp".to_string())]),
+            },
+        };
+
+        // Tool failures are results the model should see, not protocol errors
+        Ok(match self.agent.execute_action(name, input).await {
+            Ok(log) => {
+                let output = log.output.unwrap_or(Value::Null);
+                let text = match &output {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                let mut result = json!({
+                    "content": [{ "type": "text", "text": text }],
+                    "isError": false,
+                });
+                if output.is_object() {
+                    result["structuredContent"] = output;
+                }
+                result
+            }
+            Err(e) => {
+                warn!("MCP call to '{}' failed: {}", name, e);
+                json!({
+                    "content": [{ "type": "text", "text": e.to_string() }],
+                    "isError": true,
+                })
+            }
+        })
+    }
+
+    fn list_resources(&self, params: &Value) -> Result<Value, RpcError> {
+        let offset = match params.get("cursor").and_then(Value::as_str) {
+            Some(cursor) => cursor
+                .parse::<usize>()
+                .map_err(|_| RpcError::new(INVALID_PARAMS, "Invalid cursor"))?,
+            None => 0,
+        };
+
+        let store = self.store.lock().unwrap();
+        let hidden = store.quarantined_grain_ids().map_err(internal)?;
+        let mut grains: Vec<Grain> = store
+            .get_all_grains()
+            .map_err(internal)?
+            .into_iter()
+            .filter(|g| !hidden.contains(&g.id))
+            .collect();
+        grains.sort_by_key(|g| std::cmp::Reverse(g.meta.ts_unix_ms));
+
+        let resources: Vec<Value> = grains
+            .iter()
+            .skip(offset)
+            .take(RESOURCE_PAGE_SIZE)
+            .map(|grain| {
+                let mut resource = json!({
+                    "uri": grain_uri(grain),
+                    "name": resource_name(grain),
+                    "mimeType": "application/json",
+                });
+                if let Some(title) = &grain.meta.title {
+                    resource["title"] = json!(title);
+                }
+                resource
+            })
+            .collect();
+
+        let mut result = json!({ "resources": resources });
+        if offset + RESOURCE_PAGE_SIZE < grains.len() {
+            result["nextCursor"] = json!((offset + RESOURCE_PAGE_SIZE).to_string());
+        }
+        Ok(result)
+    }
+
+    fn read_resource(&self, params: &Value) -> Result<Value, RpcError> {
+        let uri = params
+            .get("uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing uri"))?;
+        let not_found =
+            || RpcError::new(RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri));
+
+        let grain_id: [u8; 32] = uri
+            .strip_prefix(GRAIN_URI_PREFIX)
+            .and_then(|id| hex::decode(id).ok())
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(not_found)?;
+
+        let store = self.store.lock().unwrap();
+        if store
+            .quarantined_grain_ids()
+            .map_err(internal)?
+            .contains(&grain_id)
+        {
+            return Err(not_found());
+        }
+        let grain = store
+            .get_grain(&grain_id)
+            .map_err(internal)?
+            .ok_or_else(not_found)?;
+
+        let body = json!({
+            "grain_id": hex::encode(grain.id),
+            "title": grain.meta.title,
+            "text": grain.meta.summary,
+            "tags": grain.meta.tags,
+            "mime": grain.meta.mime,
+            "lang": grain.meta.lang,
+            "author": hex::encode(&grain.meta.author_pk),
+            "created_at_ms": grain.meta.ts_unix_ms,
+        });
+
+        Ok(json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "application/json",
+                "text": body.to_string(),
+            }]
+        }))
+    }
+}
+
+/// JSON Schema for a tool's parameters
+pub fn input_schema(schema: &ToolSchema) -> Value {
+    let mut properties = serde_json::Map::new();
+    let mut required = Vec::new();
+
+    for param in &schema.parameters {
+        let mut property = json!({ "description": param.description });
+        if let Some(json_type) = json_type(&param.param_type) {
+            property["type"] = json!(json_type);
+        }
+        if let Some(default) = &param.default {
+            property["default"] = default.clone();
+        }
+        properties.insert(param.name.clone(), property);
+        if param.required {
+            required.push(param.name.clone());
+        }
+    }
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/// JSON Schema type for a `ToolParameter::param_type`; unknown types stay untyped
+fn json_type(param_type: &str) -> Option<&'static str> {
+    match param_type.to_ascii_lowercase().as_str() {
+        "string" | "str" => Some("string"),
+        "number" | "float" | "f64" => Some("number"),
+        "integer" | "int" | "u64" | "usize" | "i64" => Some("integer"),
+        "boolean" | "bool" => Some("boolean"),
+        "array" | "list" => Some("array"),
+        "object" | "map" => Some("object"),
+        _ => None,
+    }
+}
+
+fn grain_uri(grain: &Grain) -> String {
+    format!("{}{}", GRAIN_URI_PREFIX, hex::encode(grain.id))
+}
+
+fn resource_name(grain: &Grain) -> String {
+    match (&grain.meta.title, &grain.meta.summary) {
+        (Some(title), _) => title.clone(),
+        (None, Some(summary)) => summary.chars().take(60).collect(),
+        (None, None) => hex::encode(grain.id),
+    }
+}
+
+fn internal(e: impl std::fmt::Display) -> RpcError {
+    RpcError::new(INTERNAL_ERROR, e.to_string())
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// Create the MCP HTTP router (`POST /mcp`, `DELETE /mcp`)
+///
+/// `initialize` starts a session whose id comes back in the `Mcp-Session-Id`
+/// header; later requests send it back, and unknown, deleted or expired
+/// sessions get `404 Not Found`. While `MAX_HTTP_SESSIONS` are open,
+/// `initialize` gets `503 Service Unavailable`. Notifications are answered
+/// with `202 Accepted`.
+pub fn create_mcp_router(server: Arc<McpServer>, access: McpHttpAccess) -> Router {
+    mcp_router(
+        server,
+        access,
+        Sessions::new(MAX_HTTP_SESSIONS, SESSION_IDLE_TIMEOUT),
+    )
+}
+
+fn mcp_router(server: Arc<McpServer>, access: McpHttpAccess, sessions: Sessions) -> Router {
+    let state = HttpState {
+        server,
+        access,
+        sessions: Mutex::new(sessions),
+    };
+
+    Router::new()
+        .route("/mcp", post(http_post).delete(http_delete))
+        .with_state(Arc::new(state))
+}
+
+async fn http_post(
+    State(state): State<Arc<HttpState>>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    if let Err(response) = check_access(&state.access, &headers) {
+        return response;
+    }
+
+    let message: Value = match serde_json::from_str(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            let error = RpcError::new(PARSE_ERROR, format!("Parse error: {}", e));
+            return http_error(StatusCode::BAD_REQUEST, error);
+        }
+    };
+
+    let initializing = message.get("method").and_then(Value::as_str) == Some("initialize");
+    let now = Instant::now();
+    let (session, expired) = {
+        let mut sessions = state.sessions.lock().unwrap();
+        let expired = sessions.expire(now);
+        let session = if initializing {
+            sessions.open(now)
+        } else {
+            session_id(&headers).filter(|session| sessions.touch(session, now))
+        };
+        (session, expired)
+    };
+    for session in &expired {
+        state.server.end_session(session);
+    }
+
+    let session = match session {
+        Some(session) => session,
+        None if initializing => {
+            let error = RpcError::new(INTERNAL_ERROR, "Too many open MCP sessions");
+            return http_error(StatusCode::SERVICE_UNAVAILABLE, error);
+        }
+        None if session_id(&headers).is_none() => {
+            let error = RpcError::new(INVALID_REQUEST, "Missing or invalid Mcp-Session-Id");
+            return http_error(StatusCode::BAD_REQUEST, error);
+        }
+        None => return unknown_session(),
+    };
+
+    let response = state.server.handle(session, message).await;
+
+    // A session is only issued along with a successful initialize
+    let issued = initializing
+        && response
+            .as_ref()
+            .is_some_and(|response| response.get("result").is_some());
+    if initializing && !issued {
+        state.sessions.lock().unwrap().close(&session);
+        state.server.end_session(&session);
+    }
+
+    let Some(response) = response else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+
+    let mut response = Json(response).into_response();
+    if issued {
+        if let Ok(value) = HeaderValue::from_str(&session.to_string()) {
+            response.headers_mut().insert(SESSION_HEADER, value);
+        }
+    }
+    response
+}
+
+async fn http_delete(State(state): State<Arc<HttpState>>, headers: HeaderMap) -> Response {
+    if let Err(response) = check_access(&state.access, &headers) {
+        return response;
+    }
+
+    let Some(session) = session_id(&headers) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    if !state.sessions.lock().unwrap().close(&session) {
+        return unknown_session();
+    }
+
+    state.server.end_session(&session);
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// Refuse foreign browser origins and requests without the bearer token
+#[allow(clippy::result_large_err)]
+fn check_access(access: &McpHttpAccess, headers: &HeaderMap) -> Result<(), Response> {
+    if let Some(origin) = headers.get(header::ORIGIN) {
+        let allowed = origin.to_str().is_ok_and(|origin| {
+            is_loopback_origin(origin) || access.allowed_origins.iter().any(|o| o == origin)
+        });
+        if !allowed {
+            let error = RpcError::new(INVALID_REQUEST, "Origin not allowed");
+            return Err(http_error(StatusCode::FORBIDDEN, error));
+        }
+    }
+
+    if let Some(ref token) = access.bearer_token {
+        let presented = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if !presented.is_some_and(|presented| constant_time_eq(presented, token)) {
+            let error = RpcError::new(INVALID_REQUEST, "Missing or invalid bearer token");
+            return Err(http_error(StatusCode::UNAUTHORIZED, error));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether an `Origin` is a page served from this machine
+fn is_loopback_origin(origin: &str) -> bool {
+    let Some(authority) = origin.split_once("://").map(|(_, rest)| rest) else {
+        return false;
+    };
+    let host = match authority.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+fn unknown_session() -> Response {
+    let error = RpcError::new(INVALID_REQUEST, "Unknown or expired Mcp-Session-Id");
+    http_error(StatusCode::NOT_FOUND, error)
+}
+
+fn http_error(status: StatusCode, error: RpcError) -> Response {
+    (status, Json(error_response(Value::Null, error))).into_response()
+}
+
+fn session_id(headers: &HeaderMap) -> Option<Uuid> {
+    headers
+        .get(SESSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use synapsenet_agent::ToolRegistry;
+    use synapsenet_ai::embed::DummyEmbedding;
+    use synapsenet_core::{CryptoBackend, UnifiedSigningKey};
+    use synapsenet_storage::HnswIndex;
+    use tokio::io::{AsyncReadExt, BufReader};
+    use tokio::sync::RwLock;
+
+    fn server() -> McpServer {
+        let store = Arc::new(Mutex::new(Store::new(":memory:").unwrap()));
+        let memory = Arc::new(GrainMemory::new(
+            store.clone(),
+            Arc::new(RwLock::new(HnswIndex::new(1000, 16))),
+            Arc::new(DummyEmbedding::new(16)),
+            Arc::new(UnifiedSigningKey::generate(CryptoBackend::Classical)),
+        ));
+        let agent = Arc::new(AgentCore::with_defaults(Arc::new(ToolRegistry::new())));
+        McpServer::new(agent, store)
+            .with_grain_memory(memory)
+            .unwrap()
+    }
+
+    /// Send `requests` over a stdio-like pipe and collect one line per response
+    async fn exchange(server: &McpServer, requests: &[Value]) -> Vec<Value> {
+        let (client, server_end) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server_end);
+        let (mut client_read, mut client_write) = tokio::io::split(client);
+
+        let mut input = String::new();
+        for request in requests {
+            input.push_str(&request.to_string());
+            input.push('\n');
+        }
+        client_write.write_all(input.as_bytes()).await.unwrap();
+        client_write.shutdown().await.unwrap();
+        drop(client_write);
+
+        server
+            .serve(BufReader::new(server_read), server_write)
+            .await
+            .unwrap();
+
+        let mut output = String::new();
+        client_read.read_to_string(&mut output).await.unwrap();
+        output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    #[tokio::test]
+    async fn test_stdio_session() {
+        let server = server();
+        let responses = exchange(
+            &server,
+            &[
+                request(1, "initialize", json!({ "protocolVersion": "2025-03-26" })),
+                json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+                request(2, "tools/list", json!({})),
+                request(
+                    3,
+                    "tools/call",
+                    json!({ "name": "grain_add", "arguments": { "text": "MCP clients share memory" } }),
+                ),
+                request(
+                    4,
+                    "tools/call",
+                    json!({ "name": "grain_search", "arguments": { "query": "MCP clients share memory" } }),
+                ),
+                request(5, "resources/list", json!({})),
+                request(6, "no/such_method", json!({})),
+            ],
+        )
+        .await;
+
+        // The notification gets no response
+        assert_eq!(responses.len(), 6);
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2025-03-26");
+        assert!(responses[0]["result"]["capabilities"]["tools"].is_object());
+
+        let tools = responses[1]["result"]["tools"].as_array().unwrap();
+        let add = tools.iter().find(|t| t["name"] == "grain_add").unwrap();
+        assert_eq!(add["inputSchema"]["type"], "object");
+        assert_eq!(add["inputSchema"]["properties"]["text"]["type"], "string");
+        assert!(add["inputSchema"]["required"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("text")));
+
+        assert_eq!(responses[2]["result"]["isError"], false);
+        let grain_id = responses[2]["result"]["structuredContent"]["grain_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            responses[3]["result"]["structuredContent"]["results"][0]["grain_id"],
+            grain_id
+        );
+
+        let uri = format!("{}{}", GRAIN_URI_PREFIX, grain_id);
+        assert_eq!(responses[4]["result"]["resources"][0]["uri"], uri);
+        assert_eq!(responses[5]["error"]["code"], METHOD_NOT_FOUND);
+
+        let read = server
+            .handle(
+                Uuid::new_v4(),
+                request(7, "resources/read", json!({ "uri": uri })),
+            )
+            .await
+            .unwrap();
+        let body: Value =
+            serde_json::from_str(read["result"]["contents"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["text"], "MCP clients share memory");
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let server = server();
+        let responses = exchange(
+            &server,
+            &[
+                json!("not a request"),
+                request(1, "tools/call", json!({ "name": "missing_tool" })),
+                request(
+                    2,
+                    "tools/call",
+                    json!({ "name": "grain_get", "arguments": { "grain_id": "00" } }),
+                ),
+                request(3, "resources/read", json!({ "uri": "synapse://grain/00" })),
+            ],
+        )
+        .await;
+
+        assert_eq!(responses[0]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(responses[1]["error"]["code"], INVALID_PARAMS);
+        // Tool failures are reported in the result for the model to see
+        assert_eq!(responses[2]["result"]["isError"], true);
+        assert_eq!(responses[3]["error"]["code"], RESOURCE_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_http_session_header() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let app = create_mcp_router(Arc::new(server()), McpHttpAccess::default());
+        let post = |body: Value, session: Option<&str>| {
+            let mut builder = Request::post("/mcp").header("content-type", "application/json");
+            if let Some(session) = session {
+                builder = builder.header(SESSION_HEADER, session);
+            }
+            builder.body(Body::from(body.to_string())).unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(post(request(1, "initialize", json!({})), None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let session = response.headers()[SESSION_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let response = app
+            .clone()
+            .oneshot(post(request(2, "tools/list", json!({})), None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        let response = app
+            .clone()
+            .oneshot(post(notification, Some(&session)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let response = app
+            .clone()
+            .oneshot(post(request(3, "tools/list", json!({})), Some(&session)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Sessions must have been issued by initialize
+        let forged = Uuid::new_v4().to_string();
+        let response = app
+            .clone()
+            .oneshot(post(request(4, "tools/list", json!({})), Some(&forged)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let delete = Request::delete("/mcp")
+            .header(SESSION_HEADER, session.as_str())
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(delete).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = app
+            .oneshot(post(request(5, "tools/list", json!({})), Some(&session)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_http_sessions_bounded() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let sessions = Sessions::new(1, SESSION_IDLE_TIMEOUT);
+        let app = mcp_router(Arc::new(server()), McpHttpAccess::default(), sessions);
+        let initialize = |body: Value| {
+            Request::post("/mcp")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        // An initialize without a result issues no session
+        let notification = json!({ "jsonrpc": "2.0", "method": "initialize" });
+        let response = app.clone().oneshot(initialize(notification)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert!(response.headers().get(SESSION_HEADER).is_none());
+
+        let response = app
+            .clone()
+            .oneshot(initialize(request(1, "initialize", json!({}))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let session = response.headers()[SESSION_HEADER].clone();
+
+        let response = app
+            .clone()
+            .oneshot(initialize(request(2, "initialize", json!({}))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let delete = Request::delete("/mcp")
+            .header(SESSION_HEADER, session)
+            .body(Body::empty())
+            .unwrap();
+        app.clone().oneshot(delete).await.unwrap();
+        let response = app
+            .oneshot(initialize(request(3, "initialize", json!({}))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_idle_sessions_expire() {
+        let mut sessions = Sessions::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+        let idle = sessions.open(start).unwrap();
+        let active = sessions.open(start).unwrap();
+        assert!(sessions.open(start).is_none());
+
+        let later = start + Duration::from_secs(45);
+        assert!(sessions.touch(&active, later));
+        assert_eq!(sessions.expire(start + Duration::from_secs(90)), vec![idle]);
+        assert!(!sessions.touch(&idle, later));
+        assert!(sessions.open(later).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_http_origin_and_token() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let access = McpHttpAccess {
+            bearer_token: Some("s3cret".to_string()),
+            allowed_origins: vec!["https://app.example".to_string()],
+        };
+        let app = create_mcp_router(Arc::new(server()), access);
+        let initialize = |origin: Option<&str>, token: Option<&str>| {
+            let mut builder = Request::post("/mcp").header("content-type", "application/json");
+            if let Some(origin) = origin {
+                builder = builder.header("origin", origin);
+            }
+            if let Some(token) = token {
+                builder = builder.header("authorization", format!("Bearer {}", token));
+            }
+            builder
+                .body(Body::from(request(1, "initialize", json!({})).to_string()))
+                .unwrap()
+        };
+
+        for (origin, token, status) in [
+            (None, None, StatusCode::UNAUTHORIZED),
+            (None, Some("wrong"), StatusCode::UNAUTHORIZED),
+            (None, Some("s3cret"), StatusCode::OK),
+            (Some("http://localhost:3000"), Some("s3cret"), StatusCode::OK),
+            (Some("https://app.example"), Some("s3cret"), StatusCode::OK),
+            (Some("https://evil.example"), Some("s3cret"), StatusCode::FORBIDDEN),
+            (Some("http://localhost.evil.example"), Some("s3cret"), StatusCode::FORBIDDEN),
+        ] {
+            let response = app.clone().oneshot(initialize(origin, token)).await.unwrap();
+            assert_eq!(response.status(), status, "{:?} {:?}", origin, token);
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! REST API v2 for community moderation
+//!
+//! Flags, unflags and appeals are signed by their authors and submitted
+//! here to be recorded and gossiped. The node's `Moderator` weighs them and
+//! quarantines grains locally. Releasing a grain or upholding its
+//! quarantine is left to the operator's CLI (`syn moderation`), since these
+//! routes carry no operator credentials.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
+
+use synapsenet_core::{FlagAction, GrainFlag, SignedRecord};
+use synapsenet_storage::{FlagOutcome, GrainModeration, ModerationStatus};
+
+use crate::rest::{ApiError, ApiState};
+
+const DEFAULT_LIMIT: usize = 100;
+
+/// Moderation list query parameters
+#[derive(Debug, Deserialize)]
+pub struct ModerationListQuery {
+    pub status: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Signed flag message; fields as in `GrainFlag`, byte fields in hex
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FlagRequest {
+    pub grain_id: String,
+    pub signer: String,
+    /// flag:<reason>, unflag or appeal
+    pub action: String,
+    pub note: Option<String>,
+    pub ts_unix_ms: i64,
+    pub sig: String,
+}
+
+/// This node's verdict on a grain
+#[derive(Debug, Serialize)]
+pub struct ModerationView {
+    pub grain_id: String,
+    pub status: String,
+    pub flag_weight: f64,
+    pub cleared_at: Option<i64>,
+    pub appeal_dismissed_at: Option<i64>,
+    pub updated_at: i64,
+}
+
+impl From<GrainModeration> for ModerationView {
+    fn from(moderation: GrainModeration) -> Self {
+        Self {
+            grain_id: hex::encode(moderation.grain_id),
+            status: moderation.status.as_str().to_string(),
+            flag_weight: moderation.flag_weight,
+            cleared_at: moderation.cleared_at,
+            appeal_dismissed_at: moderation.appeal_dismissed_at,
+            updated_at: moderation.updated_at,
+        }
+    }
+}
+
+/// Verdict with the flag messages behind it
+#[derive(Debug, Serialize)]
+pub struct GrainModerationResponse {
+    pub moderation: Option<ModerationView>,
+    pub flags: Vec<FlagRequest>,
+}
+
+/// Create moderation API router (nested under /v2)
+pub fn create_moderation_router() -> Router<Arc<ApiState>> {
+    Router::new()
+        .route("/moderation/grains", get(list_grains))
+        .route("/moderation/grains/:id", get(get_grain))
+        .route("/moderation/flags", post(submit_flag))
+}
+
+/// List moderated grains, most recently updated first
+async fn list_grains(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ModerationListQuery>,
+) -> Result<Response, ApiError> {
+    info!("GET /v2/moderation/grains");
+
+    let status = match query.status.as_deref() {
+        Some(name) => match ModerationStatus::parse(name) {
+            Some(status) => Some(status),
+            None => {
+                return Ok(error_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("Unknown status: {}", name),
+                ))
+            }
+        },
+        None => None,
+    };
+
+    let grains = state
+        .moderation
+        .list(status, query.limit.unwrap_or(DEFAULT_LIMIT))?;
+    Ok(Json(
+        grains
+            .into_iter()
+            .map(ModerationView::from)
+            .collect::<Vec<_>>(),
+    )
+    .into_response())
+}
+
+/// Get a grain's verdict and flags
+async fn get_grain(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    info!("GET /v2/moderation/grains/{}", id);
+
+    let Some(grain_id) = parse_id(&id) else {
+        return Ok(invalid_grain_id(&id));
+    };
+
+    Ok(Json(GrainModerationResponse {
+        moderation: state
+            .moderation
+            .status(&grain_id)?
+            .map(ModerationView::from),
+        flags: state
+            .moderation
+            .flags(&grain_id)?
+            .iter()
+            .map(flag_request)
+            .collect(),
+    })
+    .into_response())
+}
+
+/// Record a signed flag, unflag or appeal and queue it for gossip
+async fn submit_flag(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<FlagRequest>,
+) -> Result<Response, ApiError> {
+    info!(
+        "POST /v2/moderation/flags: {} {}",
+        request.action, request.grain_id
+    );
+
+    let flag = match parse_flag(request) {
+        Ok(flag) => flag,
+        Err(message) => return Ok(error_response(StatusCode::BAD_REQUEST, message)),
+    };
+    if !flag.verify()? {
+        return Ok(error_response(
+            StatusCode::UNAUTHORIZED,
+            "Invalid flag signature".to_string(),
+        ));
+    }
+
+    match state.moderation.submit(&flag)? {
+        FlagOutcome::Recorded => {
+            let moderation = state.moderation.status(&flag.grain_id)?;
+            Ok(Json(moderation.map(ModerationView::from)).into_response())
+        }
+        FlagOutcome::Duplicate => Ok(error_response(
+            StatusCode::CONFLICT,
+            "Flag already recorded".to_string(),
+        )),
+        FlagOutcome::Rejected(message) => Ok(error_response(StatusCode::FORBIDDEN, message)),
+    }
+}
+
+fn parse_flag(request: FlagRequest) -> Result<GrainFlag, String> {
+    let grain_id =
+        parse_id(&request.grain_id).ok_or_else(|| "Grain ID must be 32-byte hex".to_string())?;
+    let signer = parse_id(&request.signer)
+        .ok_or_else(|| "Signer must be a 32-byte hex public key".to_string())?;
+    let action = FlagAction::parse(&request.action)
+        .ok_or_else(|| format!("Unknown action: {}", request.action))?;
+    let sig = hex::decode(&request.sig).map_err(|_| "Signature must be hex".to_string())?;
+
+    Ok(GrainFlag {
+        grain_id,
+        signer,
+        action,
+        note: request.note,
+        ts_unix_ms: request.ts_unix_ms,
+        sig,
+    })
+}
+
+fn flag_request(flag: &GrainFlag) -> FlagRequest {
+    FlagRequest {
+        grain_id: hex::encode(flag.grain_id),
+        signer: hex::encode(flag.signer),
+        action: flag.action.as_str().to_string(),
+        note: flag.note.clone(),
+        ts_unix_ms: flag.ts_unix_ms,
+        sig: hex::encode(&flag.sig),
+    }
+}
+
+fn parse_id(id: &str) -> Option<[u8; 32]> {
+    hex::decode(id).ok().and_then(|bytes| bytes.try_into().ok())
+}
+
+fn invalid_grain_id(id: &str) -> Response {
+    error_response(StatusCode::BAD_REQUEST, format!("Invalid grain id: {}", id))
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use synapsenet_core::FlagReason;
+
+    #[test]
+    fn test_parse_signed_flag() {
+        let key = SigningKey::from_bytes(&[5u8; 32]);
+        let flag = GrainFlag::new(
+            [1u8; 32],
+            FlagAction::Flag(FlagReason::Harmful),
+            Some("doxxing".to_string()),
+            &key,
+        );
+
+        let parsed = parse_flag(flag_request(&flag)).unwrap();
+        assert_eq!(parsed, flag);
+        assert!(parsed.verify().unwrap());
+
+        let mut request = flag_request(&flag);
+        request.action = "flag:boring".to_string();
+        assert!(parse_flag(request).is_err());
+
+        let mut request = flag_request(&flag);
+        request.grain_id = "abcd".to_string();
+        assert!(parse_flag(request).is_err());
+    }
+}
@@ -1,9 +1,26 @@
-//! REST API v2 for reasoning system
-
-use axum::{extract::Path, http::StatusCode, response::Json, routing::*, Router};
+//! REST API v2 for reasoning goals
+//!
+//! Goals are persisted in the store; a `GoalScheduler` running alongside
+//! the server picks up pending goals and records their plans and episodes.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
 use uuid::Uuid;
 
+use synapsenet_ai::goal_scheduler::{cancel_goal, reset_goal};
+use synapsenet_ai::TaskGraph;
+use synapsenet_core::{EpisodeV2, Goal, GoalStatus, Priority};
+
+use crate::rest::{ApiError, ApiState};
+
 /// Goal creation request
 #[derive(Debug, Deserialize)]
 pub struct CreateGoalRequest {
@@ -11,157 +28,198 @@ pub struct CreateGoalRequest {
     pub priority: Option<u8>,
 }
 
+/// Goal list query parameters
+#[derive(Debug, Deserialize)]
+pub struct GoalListQuery {
+    pub status: Option<String>,
+    pub limit: Option<usize>,
+}
+
 /// Goal response
 #[derive(Debug, Serialize)]
 pub struct GoalResponse {
     pub id: String,
     pub text: String,
     pub status: String,
+    pub priority: i32,
+    pub created_by: String,
     pub created_at: i64,
+    pub updated_at: i64,
+    pub answer: Option<String>,
 }
 
-/// Reasoning request
-#[derive(Debug, Deserialize)]
-pub struct ReasoningRequest {
-    pub goal_id: Option<String>,
-    pub query: Option<String>,
-    pub max_steps: Option<usize>,
-}
-
-/// Reasoning response
+/// Detailed goal response with plan and trace
 #[derive(Debug, Serialize)]
-pub struct ReasoningResponse {
-    pub goal_id: String,
-    pub answer: String,
-    pub confidence: f64,
-    pub steps: usize,
+pub struct GoalDetailResponse {
+    #[serde(flatten)]
+    pub goal: GoalResponse,
+    pub completion_percentage: f64,
+    pub plan: Option<TaskGraph>,
+    pub episodes: Vec<EpisodeV2>,
 }
 
-/// Trace response
-#[derive(Debug, Serialize)]
-pub struct TraceResponse {
-    pub goal_id: String,
-    pub steps: Vec<TraceStepResponse>,
+impl From<&Goal> for GoalResponse {
+    fn from(goal: &Goal) -> Self {
+        Self {
+            id: goal.id.to_string(),
+            text: goal.text.clone(),
+            status: goal.status.as_str().to_string(),
+            priority: goal.priority as i32,
+            created_by: goal.created_by.clone(),
+            created_at: goal.created_at,
+            updated_at: goal.updated_at,
+            answer: goal.metadata["answer"].as_str().map(str::to_string),
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-pub struct TraceStepResponse {
-    pub step: u32,
-    pub task: String,
-    pub sources: Vec<String>,
-    pub synthesis: String,
-    pub confidence: f64,
+/// Create reasoning API router (nested under /v2)
+pub fn create_reason_router() -> Router<Arc<ApiState>> {
+    Router::new()
+        .route("/goals", get(list_goals).post(create_goal))
+        .route("/goals/:id", get(get_goal))
+        .route("/goals/:id/cancel", post(cancel))
+        .route("/goals/:id/rerun", post(rerun))
 }
 
-/// Stats response
-#[derive(Debug, Serialize)]
-pub struct StatsResponse {
-    pub total_goals: u64,
-    pub active_goals: u64,
-    pub total_episodes: u64,
-    pub avg_confidence: f64,
-    pub p2p_insights_used: u64,
-}
+/// List goals
+async fn list_goals(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<GoalListQuery>,
+) -> Result<Response, ApiError> {
+    info!("GET /v2/goals");
+
+    let status = match query.status.as_deref() {
+        Some(name) => match GoalStatus::parse(name) {
+            Some(status) => Some(status),
+            None => return Ok(bad_request(format!("Unknown status: {}", name))),
+        },
+        None => None,
+    };
 
-/// Create reasoning API router
-pub fn create_router() -> Router {
-    Router::new()
-        .route("/v2/goals", post(create_goal))
-        .route("/v2/goals/:id", get(get_goal))
-        .route("/v2/reason", post(start_reasoning))
-        .route("/v2/trace/:goal_id", get(get_trace))
-        .route("/v2/episodes", get(get_episodes))
-        .route("/v2/stats", get(get_stats))
+    let store = state.store.lock().unwrap();
+    let goals = store.list_goals(status, query.limit.unwrap_or(50))?;
+    let goals: Vec<GoalResponse> = goals.iter().map(GoalResponse::from).collect();
+
+    Ok(Json(goals).into_response())
 }
 
-/// Create new goal
+/// Create new goal (executed by the scheduler)
 async fn create_goal(
+    State(state): State<Arc<ApiState>>,
     Json(request): Json<CreateGoalRequest>,
-) -> Result<Json<GoalResponse>, StatusCode> {
-    let goal_id = Uuid::new_v4();
-    
-    Ok(Json(GoalResponse {
-        id: goal_id.to_string(),
-        text: request.text,
-        status: "pending".to_string(),
-        created_at: chrono::Utc::now().timestamp(),
-    }))
+) -> Result<Json<GoalResponse>, ApiError> {
+    info!("POST /v2/goals: {}", request.text);
+
+    let mut goal = Goal::new(request.text, "api");
+    goal.priority = Priority::from_i32(request.priority.unwrap_or(1) as i32);
+
+    state.store.lock().unwrap().upsert_goal(&goal)?;
+
+    Ok(Json(GoalResponse::from(&goal)))
 }
 
-/// Get goal by ID
-async fn get_goal(Path(id): Path<String>) -> Result<Json<GoalResponse>, StatusCode> {
-    Ok(Json(GoalResponse {
-        id,
-        text: "Example goal".to_string(),
-        status: "completed".to_string(),
-        created_at: chrono::Utc::now().timestamp(),
-    }))
+/// Get goal with its plan and episodes
+async fn get_goal(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    info!("GET /v2/goals/{}", id);
+
+    let Ok(goal_id) = Uuid::parse_str(&id) else {
+        return Ok(bad_request(format!("Invalid goal id: {}", id)));
+    };
+
+    let store = state.store.lock().unwrap();
+    let Some(goal) = store.get_goal(&goal_id)? else {
+        return Ok(not_found(&id));
+    };
+
+    let plan: Option<TaskGraph> = store
+        .get_plan(&goal_id)?
+        .map(|json| serde_json::from_str(&json))
+        .transpose()?;
+    let episodes = store.get_episodes_v2(&goal_id)?;
+
+    Ok(Json(GoalDetailResponse {
+        goal: GoalResponse::from(&goal),
+        completion_percentage: plan
+            .as_ref()
+            .map(|p| p.completion_percentage())
+            .unwrap_or(0.0),
+        plan,
+        episodes,
+    })
+    .into_response())
 }
 
-/// Start reasoning
-async fn start_reasoning(
-    Json(request): Json<ReasoningRequest>,
-) -> Result<Json<ReasoningResponse>, StatusCode> {
-    let goal_id = request.goal_id.unwrap_or_else(|| Uuid::new_v4().to_string());
-    
-    Ok(Json(ReasoningResponse {
-        goal_id,
-        answer: "Reasoning result".to_string(),
-        confidence: 0.85,
-        steps: 4,
-    }))
+/// Cancel a goal
+async fn cancel(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    info!("POST /v2/goals/{}/cancel", id);
+
+    let Ok(goal_id) = Uuid::parse_str(&id) else {
+        return Ok(bad_request(format!("Invalid goal id: {}", id)));
+    };
+
+    if cancel_goal(&state.store, &goal_id)? {
+        Ok(Json(serde_json::json!({"id": id, "status": "cancelled"})).into_response())
+    } else {
+        Ok((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "Goal not found or already finished"})),
+        )
+            .into_response())
+    }
 }
 
-/// Get reasoning trace
-async fn get_trace(Path(goal_id): Path<String>) -> Result<Json<TraceResponse>, StatusCode> {
-    let steps = vec![
-        TraceStepResponse {
-            step: 1,
-            task: "Understand goal".to_string(),
-            sources: vec!["grain_123".to_string()],
-            synthesis: "Goal understood".to_string(),
-            confidence: 0.9,
-        },
-    ];
-    
-    Ok(Json(TraceResponse { goal_id, steps }))
+/// Reset a goal so the scheduler runs it again
+async fn rerun(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    info!("POST /v2/goals/{}/rerun", id);
+
+    let Ok(goal_id) = Uuid::parse_str(&id) else {
+        return Ok(bad_request(format!("Invalid goal id: {}", id)));
+    };
+
+    if reset_goal(&state.store, &goal_id)? {
+        Ok(Json(serde_json::json!({"id": id, "status": "pending"})).into_response())
+    } else {
+        Ok(not_found(&id))
+    }
 }
 
-/// Get episodes
-async fn get_episodes() -> Result<Json<Vec<serde_json::Value>>, StatusCode> {
-    Ok(Json(vec![]))
+fn bad_request(message: String) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": message })),
+    )
+        .into_response()
 }
 
-/// Get reasoning stats
-async fn get_stats() -> Result<Json<StatsResponse>, StatusCode> {
-    Ok(Json(StatsResponse {
-        total_goals: 42,
-        active_goals: 3,
-        total_episodes: 156,
-        avg_confidence: 0.78,
-        p2p_insights_used: 23,
-    }))
+fn not_found(id: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "error": format!("Goal not found: {}", id) })),
+    )
+        .into_response()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum_test::TestServer;
-
-    #[tokio::test]
-    async fn test_create_goal() {
-        let app = create_router();
-        let server = TestServer::new(app).unwrap();
-        
-        let request = CreateGoalRequest {
-            text: "Test goal".to_string(),
-            priority: Some(1),
-        };
-        
-        let response = server.post("/v2/goals")
-            .json(&request)
-            .await;
-        
-        response.assert_status_ok();
+
+    #[test]
+    fn test_goal_response() {
+        let mut goal = Goal::new("Test goal", "api");
+        goal.metadata["answer"] = serde_json::json!("42");
+
+        let response = GoalResponse::from(&goal);
+        assert_eq!(response.status, "pending");
+        assert_eq!(response.answer.as_deref(), Some("42"));
     }
 }
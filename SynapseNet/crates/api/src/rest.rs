@@ -14,8 +14,14 @@ use tokio::sync::RwLock;
 use tower_http::cors::CorsLayer;
 use tracing::{error, info};
 
+use synapsenet_agent::ApprovalQueue;
 use synapsenet_ai::{EmbeddingModel, OnnxEmbedding};
 use synapsenet_core::{Grain, GrainMeta, SigningKeyTrait, UnifiedSigningKey};
+use synapsenet_economy::{AccessType, PoEv2Engine};
+use synapsenet_governance::{
+    Classification, CuratorQueue, Moderator, PolicyClass, PolicyEngine, PolicyInput,
+    PolicyViolation, Route, SubmittedTask,
+};
 use synapsenet_storage::{HnswIndex, Store};
 
 /// API Server state
@@ -24,6 +30,42 @@ pub struct ApiState {
     pub embedding: Arc<OnnxEmbedding>,
     pub signing_key: Arc<UnifiedSigningKey>,
     pub index: Arc<RwLock<HnswIndex<'static>>>,
+    /// PoE v2 scoring over `store` and `index`
+    pub poe: Arc<PoEv2Engine>,
+    /// Agent actions waiting for a human decision
+    pub approvals: Arc<ApprovalQueue>,
+    /// Content policy for queries and ingestion
+    pub policy: Arc<PolicyEngine>,
+    /// Queries held for curator review
+    pub curation: Arc<CuratorQueue>,
+    /// Grain flags and local quarantine
+    pub moderation: Arc<Moderator>,
+}
+
+/// Access events from this node's own queries are attributed to this peer
+pub const LOCAL_PEER: &str = "local";
+
+/// Store, index and score a new grain
+///
+/// Grains the ingest policy holds for review or blocks are rejected with a
+/// `PolicyViolation`.
+pub(crate) async fn ingest_grain(state: &ApiState, grain: &Grain) -> anyhow::Result<()> {
+    let start = std::time::Instant::now();
+
+    let classification = state.policy.evaluate_grain(grain);
+    if !classification.is_allowed() {
+        return Err(PolicyViolation(classification).into());
+    }
+
+    if let Some(score) = state.poe.ingest(grain).await? {
+        crate::metrics::record_poe_v2_score(
+            score.novelty as f64,
+            score.coherence as f64,
+            score.reuse as f64,
+            start.elapsed().as_secs_f64(),
+        );
+    }
+    Ok(())
 }
 
 /// API Error type
@@ -32,6 +74,18 @@ pub struct ApiError(anyhow::Error);
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
+        if let Some(PolicyViolation(classification)) = self.0.downcast_ref::<PolicyViolation>() {
+            info!("Refused by policy: {}", classification.explanation);
+            return (
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({
+                    "error": self.0.to_string(),
+                    "policy": classification,
+                })),
+            )
+                .into_response();
+        }
+
         error!("API error: {}", self.0);
         (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -85,10 +139,12 @@ pub struct AddResponse {
 pub struct QueryRequest {
     pub text: String,
     pub k: Option<usize>,
+    /// Who is asking, recorded with the task if the query is held for review
+    pub requester: Option<String>,
 }
 
 /// Query result
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct QueryResult {
     pub grain_id: String,
     pub similarity: f32,
@@ -100,6 +156,20 @@ pub struct QueryResult {
 pub struct QueryResponse {
     pub results: Vec<QueryResult>,
     pub query_time_ms: u64,
+    /// Set when the query matched a policy rule but may proceed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<Classification>,
+}
+
+/// Query held for curator review
+#[derive(Debug, Serialize)]
+pub struct HeldQueryResponse {
+    /// Poll `GET /v2/curation/tasks/:id/release?token=` for the outcome
+    pub task_id: String,
+    /// Only this token releases the outcome; it is not shown again
+    pub release_token: String,
+    pub status: String,
+    pub policy: Classification,
 }
 
 /// Stats response
@@ -201,17 +271,8 @@ async fn add_grain(
     let grain = Grain::new_with_unified_key(vec, meta, &state.signing_key)?;
     let grain_id = hex::encode(&grain.id);
 
-    // Store grain
-    {
-        let mut store = state.store.lock().unwrap();
-        store.insert_grain(&grain)?;
-    }
-
-    // Add to index
-    {
-        let mut index = state.index.write().await;
-        index.add(&grain)?;
-    }
+    // Store, index and score grain
+    ingest_grain(&state, &grain).await?;
 
     let embedding_time_ms = start.elapsed().as_millis() as u64;
 
@@ -224,10 +285,14 @@ async fn add_grain(
 }
 
 /// Query grains
+///
+/// Blocked queries are refused. Queries the policy sends to curation are
+/// answered now but held: the response is `202` with a task to poll, and the
+/// results are released once a curator approves.
 async fn query(
     State(state): State<Arc<ApiState>>,
     Json(req): Json<QueryRequest>,
-) -> Result<Json<QueryResponse>, ApiError> {
+) -> Result<Response, ApiError> {
     info!("POST /query: {}", req.text);
 
     let start = std::time::Instant::now();
@@ -236,34 +301,79 @@ async fn query(
     // Generate query embedding
     let query_vec = state.embedding.embed(&req.text)?;
 
-    // Search index
-    let results = {
+    // Enforce query policy before touching the index
+    let classification = state
+        .policy
+        .evaluate(Route::Query, &PolicyInput::new(&req.text).with_embedding(&query_vec));
+    if classification.class == PolicyClass::Blocked {
+        return Err(PolicyViolation(classification).into());
+    }
+    let held = classification.class == PolicyClass::Curated;
+
+    // Search index, leaving out quarantined grains
+    let quarantined = state.store.lock().unwrap().quarantined_grain_ids()?;
+    let results: Vec<_> = {
         let index = state.index.read().await;
-        index.search(&query_vec, k)?
-    };
+        index.search(&query_vec, k + quarantined.len())?
+    }
+    .into_iter()
+    .filter(|r| !quarantined.contains(&r.grain_id))
+    .take(k)
+    .collect();
+
+    // Results count as reuse of the returned grains, once someone sees them
+    if !held {
+        let grain_ids: Vec<[u8; 32]> = results.iter().map(|r| r.grain_id).collect();
+        state.poe.record_access(&grain_ids, LOCAL_PEER, AccessType::Search)?;
+    }
 
     // Get grain details
-    let store = state.store.lock().unwrap();
     let mut query_results = Vec::new();
-
-    for result in results {
-        if let Some(grain) = store.get_grain(&result.grain_id)? {
-            query_results.push(QueryResult {
-                grain_id: hex::encode(&result.grain_id),
-                similarity: result.similarity,
-                title: grain.meta.title,
-            });
+    {
+        let store = state.store.lock().unwrap();
+        for result in results {
+            if let Some(grain) = store.get_grain(&result.grain_id)? {
+                query_results.push(QueryResult {
+                    grain_id: hex::encode(&result.grain_id),
+                    similarity: result.similarity,
+                    title: grain.meta.title,
+                });
+            }
         }
     }
 
     let query_time_ms = start.elapsed().as_millis() as u64;
 
+    if held {
+        let SubmittedTask {
+            task,
+            release_token,
+        } = state.curation.submit(
+            Route::Query,
+            &req.text,
+            req.requester.as_deref(),
+            &classification,
+            Some(serde_json::to_value(&query_results)?),
+        )?;
+        info!("⏳ Query held for review: task {}", task.id);
+
+        let response = HeldQueryResponse {
+            task_id: task.id.to_string(),
+            release_token,
+            status: task.status.as_str().to_string(),
+            policy: classification,
+        };
+        return Ok((StatusCode::ACCEPTED, Json(response)).into_response());
+    }
+
     info!("✓ Query complete: {} results ({}ms)", query_results.len(), query_time_ms);
 
     Ok(Json(QueryResponse {
         results: query_results,
         query_time_ms,
-    }))
+        policy: (!classification.matches.is_empty()).then_some(classification),
+    })
+    .into_response())
 }
 
 /// Get stats
@@ -11,6 +11,7 @@ use tracing::{debug, info};
 
 use synapsenet_ai::EmbeddingModel;
 use synapsenet_core::SigningKeyTrait;
+use synapsenet_storage::{LedgerAudit, LedgerEntry, LedgerEntryKind, LedgerQuery, PoeScoreRecord};
 use crate::rest::{ApiError, ApiState};
 
 /// Create v2 API router
@@ -23,6 +24,13 @@ pub fn create_v2_router() -> Router<Arc<ApiState>> {
         .route("/poe/scores/:grain_id", get(get_grain_poe_score))
         .route("/network/peers", get(get_network_peers))
         .route("/network/clusters", get(get_peer_clusters))
+        .route("/ledger/entries", get(get_ledger_entries))
+        .route("/ledger/balances", get(get_ledger_balances))
+        .route("/ledger/audit", get(audit_ledger))
+        .merge(crate::reason_api::create_reason_router())
+        .merge(crate::approval_api::create_approval_router())
+        .merge(crate::curation_api::create_curation_router())
+        .merge(crate::moderation_api::create_moderation_router())
 }
 
 // ===== Models Endpoints =====
@@ -175,17 +183,8 @@ async fn process_batch_item(item: &BatchItem, state: &ApiState) -> anyhow::Resul
     let grain = synapsenet_core::Grain::new_with_unified_key(vec, meta, &state.signing_key)?;
     let grain_id = hex::encode(&grain.id);
 
-    // Store grain
-    {
-        let mut store = state.store.lock().unwrap();
-        store.insert_grain(&grain)?;
-    }
-
-    // Add to index
-    {
-        let mut index = state.index.write().await;
-        index.add(&grain)?;
-    }
+    // Store, index and score grain
+    crate::rest::ingest_grain(state, &grain).await?;
 
     Ok(grain_id)
 }
@@ -200,10 +199,26 @@ pub struct PoEScoreInfo {
     pub coherence: f32,
     pub reuse: f32,
     pub total: f32,
-    pub ngt_reward: f32,
+    pub ngt_reward: f64,
+    pub ngt_credited: f64,
     pub calculated_at: i64,
 }
 
+impl From<PoeScoreRecord> for PoEScoreInfo {
+    fn from(record: PoeScoreRecord) -> Self {
+        Self {
+            grain_id: hex::encode(record.grain_id),
+            novelty: record.novelty,
+            coherence: record.coherence,
+            reuse: record.reuse,
+            total: record.total,
+            ngt_reward: record.ngt_reward,
+            ngt_credited: record.credited,
+            calculated_at: record.calculated_at,
+        }
+    }
+}
+
 /// Query parameters for PoE scores
 #[derive(Debug, Deserialize)]
 pub struct PoEScoresQuery {
@@ -211,31 +226,115 @@ pub struct PoEScoresQuery {
     pub min_score: Option<f32>,
 }
 
-/// Get PoE scores for all grains
+/// Get PoE scores, best first
 async fn get_poe_scores(
-    State(_state): State<Arc<ApiState>>,
+    State(state): State<Arc<ApiState>>,
     Query(query): Query<PoEScoresQuery>,
 ) -> Result<Json<Vec<PoEScoreInfo>>, ApiError> {
     info!("GET /v2/poe/scores - Getting PoE scores");
 
-    // TODO: Implement actual PoE score retrieval from storage
-    // For now, return empty list as placeholder
-    let _limit = query.limit.unwrap_or(100);
-    let _min_score = query.min_score.unwrap_or(0.0);
+    let limit = query.limit.unwrap_or(100);
+    let min_score = query.min_score.unwrap_or(0.0);
 
-    debug!("PoE scores endpoint - implementation pending");
-    Ok(Json(Vec::new()))
+    let store = state.store.lock().unwrap();
+    let scores = store
+        .list_poe_scores(limit, min_score)?
+        .into_iter()
+        .map(PoEScoreInfo::from)
+        .collect();
+
+    Ok(Json(scores))
 }
 
 /// Get PoE score for specific grain
 async fn get_grain_poe_score(
-    State(_state): State<Arc<ApiState>>,
+    State(state): State<Arc<ApiState>>,
     Path(grain_id): Path<String>,
 ) -> Result<Json<PoEScoreInfo>, ApiError> {
     info!("GET /v2/poe/scores/{} - Getting grain PoE score", grain_id);
 
-    // TODO: Implement actual PoE score retrieval
-    Err(anyhow::anyhow!("PoE scores not yet implemented").into())
+    let id: [u8; 32] = hex::decode(&grain_id)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("Invalid grain id: {}", grain_id))?;
+
+    let store = state.store.lock().unwrap();
+    let record = store
+        .get_poe_score(&id)?
+        .ok_or_else(|| anyhow::anyhow!("No PoE score for grain {}", grain_id))?;
+
+    Ok(Json(record.into()))
+}
+
+// ===== Ledger Endpoints =====
+
+/// Ledger entry query parameters
+#[derive(Debug, Deserialize)]
+pub struct LedgerEntriesQuery {
+    /// Account name, e.g. `node:<hex pk>` or `system:issuance`
+    pub account: Option<String>,
+    /// Entry kind: mint, reward, transfer, penalty, burn
+    pub kind: Option<String>,
+    /// Only entries at or after this unix timestamp (ms)
+    pub since: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// Account balance
+#[derive(Debug, Serialize)]
+pub struct AccountBalance {
+    pub account: String,
+    pub balance: f64,
+}
+
+/// Query ledger entries for auditing
+async fn get_ledger_entries(
+    State(state): State<Arc<ApiState>>,
+    Query(params): Query<LedgerEntriesQuery>,
+) -> Result<Json<Vec<LedgerEntry>>, ApiError> {
+    info!("GET /v2/ledger/entries - Querying ledger");
+
+    let kind = match params.kind.as_deref() {
+        Some(name) => Some(
+            LedgerEntryKind::parse(name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown ledger entry kind: {}", name))?,
+        ),
+        None => None,
+    };
+
+    let store = state.store.lock().unwrap();
+    let entries = store.query_ledger(&LedgerQuery {
+        account: params.account,
+        kind,
+        since_ms: params.since,
+        limit: params.limit.unwrap_or(100).min(1000),
+    })?;
+
+    Ok(Json(entries))
+}
+
+/// Get balances of all node accounts
+async fn get_ledger_balances(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<Vec<AccountBalance>>, ApiError> {
+    info!("GET /v2/ledger/balances - Getting node balances");
+
+    let store = state.store.lock().unwrap();
+    let balances = store
+        .get_account_balances("node:")?
+        .into_iter()
+        .map(|(account, balance)| AccountBalance { account, balance })
+        .collect();
+
+    Ok(Json(balances))
+}
+
+/// Check ledger consistency
+async fn audit_ledger(State(state): State<Arc<ApiState>>) -> Result<Json<LedgerAudit>, ApiError> {
+    info!("GET /v2/ledger/audit - Auditing ledger");
+
+    let store = state.store.lock().unwrap();
+    Ok(Json(store.audit_ledger()?))
 }
 
 // ===== Network Endpoints =====
@@ -2,12 +2,27 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use ed25519_dalek::SigningKey;
 use rand::{rngs::OsRng, RngCore};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use synapsenet_ai::{EmbeddingModel, OnnxEmbedding};
-use synapsenet_core::{CryptoBackend, Grain, GrainMeta};
-use synapsenet_storage::{HnswIndex, Store};
-use tracing::{info, Level};
+use synapsenet_agent::ApprovalQueue;
+use synapsenet_ai::{EmbeddingModel, GoalScheduler, OnnxEmbedding, Reasoner, ReasonerConfig};
+use synapsenet_api::{McpHttpAccess, LOCAL_PEER};
+use synapsenet_core::keystore::{self, EncryptedKey, KdfParams, KeyFile};
+use synapsenet_core::poe::Credit;
+use synapsenet_core::{
+    CryptoBackend, Grain, GrainMeta, GroupKey, GroupKeyGrant, KeySuccession, Link, PrivateGrain,
+    RecipientKey, RecipientSecret,
+};
+use synapsenet_economy::{AccessType, NgtLedger, PoEv2Engine};
+use synapsenet_governance::{CuratorQueue, Moderator, Policy, PolicyEngine, PolicyViolation};
+use synapsenet_storage::{
+    HnswIndex, PrivateGrainOutcome, Store, SuccessionOutcome, TransferOutcome,
+};
+use tracing::{info, warn, Level};
+use zeroize::Zeroizing;
+
+/// Environment variable holding the key password for non-interactive use
+const KEY_PASSWORD_ENV: &str = "SYNAPSENET_KEY_PASSWORD";
 
 #[derive(Parser)]
 #[command(name = "syn")]
@@ -28,7 +43,11 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize local node (keys, DB, index)
-    Init,
+    Init {
+        /// Store the node key unencrypted
+        #[arg(long)]
+        no_password: bool,
+    },
 
     /// Add text or file as grain
     Add {
@@ -49,7 +68,7 @@ enum Commands {
     /// Show peers and P2P status
     Peers,
 
-    /// Export grains to Parquet
+    /// Export grains, links and credits to Parquet with a signed manifest
     Export {
         /// Output directory
         #[arg(short, long, default_value = "export")]
@@ -61,6 +80,14 @@ enum Commands {
         /// Input directory
         #[arg(short, long, default_value = "export")]
         input: PathBuf,
+
+        /// Accept exports without a signed manifest
+        #[arg(long)]
+        allow_unsigned: bool,
+
+        /// Only accept exports signed by this node key (hex)
+        #[arg(long)]
+        signer: Option<String>,
     },
 
     /// Generate default configuration file
@@ -80,53 +107,460 @@ enum Commands {
         addr: String,
     },
 
+    /// Serve memory and tools to MCP clients (stdio by default)
+    Mcp {
+        /// Serve MCP over HTTP on this address (or port, on 127.0.0.1) instead of stdio
+        #[arg(long)]
+        http: Option<String>,
+
+        /// Bearer token HTTP clients must send; required off loopback
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Browser origin allowed besides loopback ones (repeatable)
+        #[arg(long = "allow-origin")]
+        allow_origins: Vec<String>,
+    },
+
     /// Migrate database from v0.3 to v0.4
     Migrate {
         /// Database path (optional, defaults to data_dir/grains.db)
         #[arg(short, long)]
         db_path: Option<PathBuf>,
     },
+
+    /// Manage persisted reasoning goals
+    Goals {
+        #[command(subcommand)]
+        command: GoalCommands,
+    },
+
+    /// Review agent actions waiting for approval
+    Approvals {
+        #[command(subcommand)]
+        command: ApprovalCommands,
+    },
+
+    /// Review queries held by the content policy
+    Curation {
+        #[command(subcommand)]
+        command: CurationCommands,
+    },
+
+    /// Flag grains and review local quarantine
+    Moderation {
+        #[command(subcommand)]
+        command: ModerationCommands,
+    },
+
+    /// Send NGT and inspect balances
+    Wallet {
+        #[command(subcommand)]
+        command: WalletCommands,
+    },
+
+    /// Back up, restore and encrypt the node key
+    Key {
+        #[command(subcommand)]
+        command: KeyCommands,
+    },
+
+    /// Share grains only with the members of a group
+    Private {
+        #[command(subcommand)]
+        command: PrivateCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum GoalCommands {
+    /// List goals, newest first
+    List {
+        /// Only show goals with this status (pending, reasoning, completed, ...)
+        #[arg(short, long)]
+        status: Option<String>,
+
+        /// Maximum number of goals
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Show a goal with its plan and episode trace
+    Show {
+        /// Goal ID
+        id: String,
+    },
+
+    /// Cancel a pending or running goal
+    Cancel {
+        /// Goal ID
+        id: String,
+    },
+
+    /// Discard a goal's progress and run it again
+    Rerun {
+        /// Goal ID
+        id: String,
+    },
+
+    /// Run a task graph whose nodes name tools, suspending on approvals
+    Run {
+        /// Task graph JSON file
+        plan: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum ApprovalCommands {
+    /// List approval requests, oldest first
+    List {
+        /// Only show requests with this status (pending, approved, rejected, expired)
+        #[arg(short, long)]
+        status: Option<String>,
+    },
+
+    /// Show a request with its proposed input
+    Show {
+        /// Approval ID
+        id: String,
+    },
+
+    /// Let a pending action run
+    Approve {
+        /// Approval ID
+        id: String,
+    },
+
+    /// Refuse a pending action
+    Reject {
+        /// Approval ID
+        id: String,
+
+        /// Why the action was refused
+        #[arg(short, long)]
+        reason: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CurationCommands {
+    /// List review tasks, oldest first
+    List {
+        /// Only show tasks with this status (pending, in_review, approved, rejected)
+        #[arg(short, long)]
+        status: Option<String>,
+    },
+
+    /// Show a task with its policy classification
+    Show {
+        /// Task ID
+        id: String,
+    },
+
+    /// Take a task so other curators leave it alone
+    Claim {
+        /// Task ID
+        id: String,
+    },
+
+    /// Release a task's held outcome to its requester
+    Approve {
+        /// Task ID
+        id: String,
+
+        /// Note for the requester and the audit log
+        #[arg(short, long)]
+        note: Option<String>,
+    },
+
+    /// Withhold a task's outcome
+    Reject {
+        /// Task ID
+        id: String,
+
+        /// Why the outcome was withheld
+        #[arg(short, long)]
+        note: String,
+    },
+
+    /// Show applied curator decisions
+    Audit {
+        /// Only decisions on this task
+        #[arg(long)]
+        task: Option<String>,
+    },
+
+    /// Manage the keys allowed to curate
+    Curators {
+        #[command(subcommand)]
+        command: CuratorCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum CuratorCommands {
+    /// List curator keys
+    List,
+
+    /// Allow a key to curate
+    Add {
+        /// Curator public key (hex, defaults to this node's key)
+        public_key: Option<String>,
+
+        /// Name shown in listings
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Stop a key from curating
+    Remove {
+        /// Curator public key (hex)
+        public_key: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ModerationCommands {
+    /// Flag a grain with this node's key
+    Flag {
+        /// Grain ID (hex)
+        grain_id: String,
+
+        /// Reason: spam, harmful, illegal or other
+        #[arg(short, long, default_value = "spam")]
+        reason: String,
+
+        /// Explanation sent with the flag
+        #[arg(short, long)]
+        note: Option<String>,
+    },
+
+    /// Withdraw this node's flag on a grain
+    Unflag {
+        /// Grain ID (hex)
+        grain_id: String,
+    },
+
+    /// Appeal the quarantine of a grain this node authored
+    Appeal {
+        /// Grain ID (hex)
+        grain_id: String,
+
+        /// Why the grain should be shown again
+        #[arg(short, long)]
+        note: String,
+    },
+
+    /// List moderated grains
+    List {
+        /// Only show grains with this status (flagged, quarantined, appealed, cleared)
+        #[arg(short, long)]
+        status: Option<String>,
+    },
+
+    /// Show a grain's moderation status and flags
+    Show {
+        /// Grain ID (hex)
+        grain_id: String,
+    },
+
+    /// Show a quarantined grain again
+    Release {
+        /// Grain ID (hex)
+        grain_id: String,
+    },
+
+    /// Dismiss an appeal and keep the grain quarantined
+    Uphold {
+        /// Grain ID (hex)
+        grain_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum WalletCommands {
+    /// Sign an NGT transfer and queue it for broadcast
+    Send {
+        /// Recipient node public key (hex)
+        to: String,
+
+        /// NGT amount
+        amount: f64,
+
+        /// Optional note for the recipient
+        #[arg(short, long)]
+        memo: Option<String>,
+    },
+
+    /// Show NGT balance
+    Balance {
+        /// Node public key (hex), defaults to this node
+        node: Option<String>,
+    },
+
+    /// Show ledger history and signed transfers
+    History {
+        /// Maximum number of entries
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeyCommands {
+    /// Print the recovery phrase of the node key
+    Backup,
+
+    /// Recreate the node key from a recovery phrase
+    Restore {
+        /// Recovery phrase (prompted for if omitted)
+        #[arg(long)]
+        phrase: Option<String>,
+
+        /// Replace an existing node key
+        #[arg(long)]
+        force: bool,
+
+        /// Store the node key unencrypted
+        #[arg(long)]
+        no_password: bool,
+    },
+
+    /// Encrypt a plaintext node key or change its password
+    Encrypt,
+
+    /// Replace the node key, signing a succession from the old one
+    Rotate {
+        /// Revoke the old key: its grains from now on are rejected
+        #[arg(long)]
+        revoke: bool,
+
+        /// Store the new node key unencrypted
+        #[arg(long)]
+        no_password: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PrivateCommands {
+    /// Print the recipient key others add this node to groups with
+    Key,
+
+    /// Create a group and queue its key for the members
+    Create {
+        /// Local group name
+        name: String,
+
+        /// Member recipient key (x25519:<hex> or kyber:<hex>), repeatable
+        #[arg(short, long = "member")]
+        members: Vec<String>,
+    },
+
+    /// Queue the key of an existing group for more members
+    Invite {
+        /// Group name
+        group: String,
+
+        /// Member recipient key, repeatable
+        #[arg(short, long = "member", required = true)]
+        members: Vec<String>,
+    },
+
+    /// Add text or file as a grain only the group can read
+    Add {
+        /// Group name
+        group: String,
+
+        /// Text content or file path
+        input: String,
+    },
+
+    /// Search the grains of a group this node belongs to
+    Query {
+        /// Group name
+        group: String,
+
+        /// Question
+        question: String,
+
+        /// Number of results
+        #[arg(short, long, default_value = "5")]
+        k: usize,
+    },
+
+    /// List groups this node belongs to
+    Groups,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt().with_max_level(Level::INFO).init();
-
     let cli = Cli::parse();
 
+    // Initialize tracing; stdout carries the protocol when serving MCP over stdio
+    if matches!(cli.command, Commands::Mcp { http: None, .. }) {
+        tracing_subscriber::fmt()
+            .with_max_level(Level::INFO)
+            .with_writer(std::io::stderr)
+            .init();
+    } else {
+        tracing_subscriber::fmt().with_max_level(Level::INFO).init();
+    }
+
     match cli.command {
-        Commands::Init => init_node(&cli.data_dir).await,
-        Commands::Add { input } => add_grain(&cli.data_dir, &input).await,
-        Commands::Query { question, k } => query_grains(&cli.data_dir, &question, k).await,
+        Commands::Init { no_password } => init_node(&cli.data_dir, no_password).await,
+        Commands::Add { input } => add_grain(&cli.data_dir, &cli.config, &input).await,
+        Commands::Query { question, k } => {
+            query_grains(&cli.data_dir, &cli.config, &question, k).await
+        }
         Commands::Peers => show_peers(&cli.data_dir).await,
         Commands::Export { output } => export_grains(&cli.data_dir, &output).await,
-        Commands::Import { input } => import_grains(&cli.data_dir, &input).await,
+        Commands::Import {
+            input,
+            allow_unsigned,
+            signer,
+        } => import_grains(&cli.data_dir, &input, allow_unsigned, signer.as_deref()).await,
         Commands::Config { output } => generate_config(&output).await,
         Commands::Stats => show_stats(&cli.data_dir).await,
-        Commands::Serve { addr } => serve_api(&cli.data_dir, &addr).await,
+        Commands::Serve { addr } => serve_api(&cli.data_dir, &cli.config, &addr).await,
+        Commands::Mcp {
+            http,
+            token,
+            allow_origins,
+        } => {
+            let access = McpHttpAccess {
+                bearer_token: token,
+                allowed_origins: allow_origins,
+            };
+            serve_mcp(&cli.data_dir, http.as_deref(), access).await
+        }
         Commands::Migrate { db_path } => migrate_database(&cli.data_dir, db_path).await,
+        Commands::Goals { command } => manage_goals(&cli.data_dir, &cli.config, command).await,
+        Commands::Approvals { command } => manage_approvals(&cli.data_dir, command),
+        Commands::Curation { command } => manage_curation(&cli.data_dir, command),
+        Commands::Moderation { command } => {
+            manage_moderation(&cli.data_dir, &cli.config, command)
+        }
+        Commands::Wallet { command } => manage_wallet(&cli.data_dir, command).await,
+        Commands::Key { command } => manage_key(&cli.data_dir, command),
+        Commands::Private { command } => manage_private(&cli.data_dir, command).await,
     }
 }
 
-async fn init_node(data_dir: &PathBuf) -> Result<()> {
+async fn init_node(data_dir: &PathBuf, no_password: bool) -> Result<()> {
     info!("Initializing SynapseNet node at {:?}", data_dir);
 
     // Create data directory
     std::fs::create_dir_all(data_dir)?;
 
     // Generate keypair
-    let mut secret_bytes = [0u8; 32];
-    OsRng.fill_bytes(&mut secret_bytes);
+    let mut secret_bytes = Zeroizing::new([0u8; 32]);
+    OsRng.fill_bytes(secret_bytes.as_mut());
     let signing_key = SigningKey::from_bytes(&secret_bytes);
     let public_key = signing_key.verifying_key();
 
     // Save keys
-    let key_path = data_dir.join("node.key");
-    std::fs::write(&key_path, signing_key.to_bytes())?;
-
-    let pub_path = data_dir.join("node.pub");
-    std::fs::write(&pub_path, public_key.to_bytes())?;
+    let password = if no_password { None } else { Some(new_password()?) };
+    save_node_key(data_dir, &signing_key, password.as_deref().map(String::as_str))?;
 
     // Initialize database
     let db_path = data_dir.join("synapsenet.db");
@@ -135,17 +569,227 @@ async fn init_node(data_dir: &PathBuf) -> Result<()> {
     info!("✓ Node initialized");
     info!("  Public key: {}", hex::encode(public_key.to_bytes()));
     info!("  Data dir: {:?}", data_dir);
+    info!("  Run 'syn key backup' to write down the recovery phrase");
 
     Ok(())
 }
 
-async fn add_grain(data_dir: &PathBuf, input: &str) -> Result<()> {
-    info!("Adding grain: {}", input);
+fn agent_socket(data_dir: &Path) -> PathBuf {
+    data_dir.join("agent").join("agent.sock")
+}
+
+/// Key password from the environment or an interactive prompt
+fn read_password(prompt: &str) -> Result<Zeroizing<String>> {
+    if let Ok(password) = std::env::var(KEY_PASSWORD_ENV) {
+        return Ok(Zeroizing::new(password));
+    }
+    Ok(Zeroizing::new(rpassword::prompt_password(prompt)?))
+}
+
+/// Ask for a new key password, confirming interactive input
+fn new_password() -> Result<Zeroizing<String>> {
+    if let Ok(password) = std::env::var(KEY_PASSWORD_ENV) {
+        return Ok(Zeroizing::new(password));
+    }
+
+    let password = Zeroizing::new(rpassword::prompt_password("New key password: ")?);
+    let confirm = Zeroizing::new(rpassword::prompt_password("Confirm password: ")?);
+    if password != confirm {
+        return Err(anyhow::anyhow!("Passwords do not match"));
+    }
+    Ok(password)
+}
+
+/// Write `node.key` (encrypted when a password is given) and `node.pub`
+fn save_node_key(data_dir: &Path, signing_key: &SigningKey, password: Option<&str>) -> Result<()> {
+    let public_key = signing_key.verifying_key().to_bytes();
+    let key_file = match password {
+        Some(password) => KeyFile::Encrypted(EncryptedKey::seal(
+            &signing_key.to_bytes(),
+            &public_key,
+            password,
+            KdfParams::default(),
+        )?),
+        None => KeyFile::Plaintext(Zeroizing::new(signing_key.to_bytes().to_vec())),
+    };
+
+    key_file.write(&data_dir.join("node.key"))?;
+    std::fs::write(data_dir.join("node.pub"), public_key)?;
+    Ok(())
+}
 
-    // Load signing key
+/// Unlock the node secret, preferring a running key agent over a password
+fn read_node_secret(data_dir: &Path) -> Result<(KeyFile, Zeroizing<Vec<u8>>)> {
     let key_path = data_dir.join("node.key");
-    let key_bytes = std::fs::read(&key_path)?;
-    let signing_key = SigningKey::from_bytes(&key_bytes.try_into().unwrap());
+    if !key_path.exists() {
+        return Err(anyhow::anyhow!("Node not initialized. Run 'syn init' first."));
+    }
+
+    let key_file = KeyFile::read(&key_path)?;
+    let encrypted = match &key_file {
+        KeyFile::Plaintext(secret) => {
+            warn!("node.key is not encrypted; run 'syn key encrypt' to protect it");
+            let secret = secret.clone();
+            return Ok((key_file, secret));
+        }
+        KeyFile::Encrypted(encrypted) => encrypted,
+    };
+
+    #[cfg(unix)]
+    if let Some(secret) = keystore::request_key(&agent_socket(data_dir))? {
+        let matches = <[u8; 32]>::try_from(secret.as_slice())
+            .map(|bytes| SigningKey::from_bytes(&bytes).verifying_key().to_bytes().to_vec())
+            .ok()
+            == Some(encrypted.public_key_bytes()?);
+        if matches {
+            return Ok((key_file, secret));
+        }
+        warn!("Key agent holds a different key, ignoring it");
+    }
+
+    let password = read_password("Key password: ")?;
+    let secret = encrypted.open(&password)?;
+    Ok((key_file, secret))
+}
+
+fn load_signing_key(data_dir: &Path) -> Result<SigningKey> {
+    let (_, secret) = read_node_secret(data_dir)?;
+    let bytes: Zeroizing<[u8; 32]> = Zeroizing::new(
+        secret
+            .as_slice()
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Invalid node key"))?,
+    );
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+fn manage_key(data_dir: &Path, command: KeyCommands) -> Result<()> {
+    match command {
+        KeyCommands::Backup => {
+            let signing_key = load_signing_key(data_dir)?;
+            let phrase = keystore::recovery_phrase(&signing_key.to_bytes())?;
+
+            println!("\n🔑 Recovery phrase for {}", hex::encode(signing_key.verifying_key().to_bytes()));
+            println!("========================================");
+            println!("{}", phrase.as_str());
+            println!("\nAnyone with these words controls this node's key and NGT.");
+        }
+        KeyCommands::Restore {
+            phrase,
+            force,
+            no_password,
+        } => {
+            if data_dir.join("node.key").exists() && !force {
+                return Err(anyhow::anyhow!(
+                    "A node key already exists in {:?}; pass --force to replace it",
+                    data_dir
+                ));
+            }
+
+            let phrase = match phrase {
+                Some(phrase) => Zeroizing::new(phrase),
+                None => Zeroizing::new(rpassword::prompt_password("Recovery phrase: ")?),
+            };
+            let secret = keystore::secret_from_phrase(&phrase)?;
+            let bytes: Zeroizing<[u8; 32]> = Zeroizing::new(
+                secret
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Invalid recovery phrase"))?,
+            );
+            let signing_key = SigningKey::from_bytes(&bytes);
+
+            std::fs::create_dir_all(data_dir)?;
+            let password = if no_password { None } else { Some(new_password()?) };
+            save_node_key(data_dir, &signing_key, password.as_deref().map(String::as_str))?;
+
+            println!("✓ Restored node key {}", hex::encode(signing_key.verifying_key().to_bytes()));
+        }
+        KeyCommands::Encrypt => {
+            let signing_key = load_signing_key(data_dir)?;
+            let password = new_password()?;
+            save_node_key(data_dir, &signing_key, Some(&password))?;
+            println!("✓ Node key encrypted");
+        }
+        KeyCommands::Rotate {
+            revoke,
+            no_password,
+        } => {
+            let old_key = load_signing_key(data_dir)?;
+            let mut secret_bytes = Zeroizing::new([0u8; 32]);
+            OsRng.fill_bytes(secret_bytes.as_mut());
+            let new_key = SigningKey::from_bytes(&secret_bytes);
+
+            let now_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_millis() as i64;
+            let revoked_from = revoke.then_some(now_ms);
+            let succession = KeySuccession::new(&old_key, &new_key, revoked_from)?;
+
+            // Record the succession before replacing the key; the node
+            // broadcasts it from the store outbox
+            let store = Store::new(&data_dir.join("synapsenet.db").to_string_lossy())?;
+            let identity = match store.apply_key_succession(&succession, true)? {
+                SuccessionOutcome::Applied { identity } => identity,
+                SuccessionOutcome::Duplicate => {
+                    return Err(anyhow::anyhow!("Key succession already recorded"))
+                }
+                SuccessionOutcome::Rejected(reason) => {
+                    return Err(anyhow::anyhow!("Key rotation rejected: {}", reason))
+                }
+            };
+
+            let password = if no_password { None } else { Some(new_password()?) };
+            save_node_key(data_dir, &new_key, password.as_deref().map(String::as_str))?;
+
+            println!("✓ Node key rotated");
+            println!("  Identity: {}", hex::encode(identity));
+            println!("  Old key:  {}", hex::encode(succession.old_pk));
+            println!("  New key:  {}", hex::encode(succession.new_pk));
+            if revoke {
+                println!("  Old key revoked; grains it signs from now on are rejected");
+            }
+            println!("\nRun 'syn key backup' to write down the new recovery phrase");
+        }
+    }
+
+    Ok(())
+}
+
+async fn add_grain(data_dir: &PathBuf, config_path: &Path, input: &str) -> Result<()> {
+    info!("Adding grain: {}", input);
+
+    let signing_key = load_signing_key(data_dir)?;
+    let embedding = Arc::new(OnnxEmbedding::new(data_dir.clone()).await?);
+    let grain = embed_grain(&embedding, &signing_key, input)?;
+
+    let classification = load_policy(data_dir, embedding)?.evaluate_grain(&grain);
+    if !classification.is_allowed() {
+        return Err(PolicyViolation(classification).into());
+    }
+
+    // Store, index and score grain
+    let db_path = data_dir.join("synapsenet.db");
+    let store = Store::new(db_path.to_str().unwrap())?;
+    let engine = load_poe_engine(store, config_path)?;
+    let score = engine.ingest(&grain).await?;
+
+    info!("✓ Grain added: {}", hex::encode(grain.id));
+    if let Some(score) = score {
+        info!(
+            "  PoE score: {:.3} (novelty {:.2}, coherence {:.2}) → {:.4} NGT",
+            score.total,
+            score.novelty,
+            score.coherence,
+            engine.calculate_ngt_reward(&score)
+        );
+    }
+
+    Ok(())
+}
+
+/// Embed text or a file and sign it as a grain
+fn embed_grain(embedding: &OnnxEmbedding, signing_key: &SigningKey, input: &str) -> Result<Grain> {
     let author_pk = signing_key.verifying_key().to_bytes().to_vec();
 
     // Read input (file or text)
@@ -155,8 +799,6 @@ async fn add_grain(data_dir: &PathBuf, input: &str) -> Result<()> {
         input.to_string()
     };
 
-    // Generate embedding using ONNX model
-    let embedding = OnnxEmbedding::new(data_dir.clone()).await?;
     let vec = embedding.embed(&content)?;
 
     // Create metadata
@@ -175,26 +817,23 @@ async fn add_grain(data_dir: &PathBuf, input: &str) -> Result<()> {
         embedding_dimensions: Some(vec.len()),
     };
 
-    // Create grain
-    let grain = Grain::new(vec, meta, &signing_key)?;
-
-    // Store grain
-    let db_path = data_dir.join("synapsenet.db");
-    let store = Store::new(db_path.to_str().unwrap())?;
-    store.insert_grain(&grain)?;
-
-    info!("✓ Grain added: {}", hex::encode(grain.id));
-
-    Ok(())
+    Grain::new(vec, meta, signing_key)
 }
 
-async fn query_grains(data_dir: &PathBuf, question: &str, k: usize) -> Result<()> {
+async fn query_grains(data_dir: &PathBuf, config_path: &Path, question: &str, k: usize) -> Result<()> {
     info!("Querying: {}", question);
 
     // Load grains from DB
     let db_path = data_dir.join("synapsenet.db");
     let store = Store::new(db_path.to_str().unwrap())?;
-    let grains = store.get_all_grains()?;
+
+    // Quarantined grains stay stored but never show up in results
+    let quarantined = store.quarantined_grain_ids()?;
+    let grains: Vec<Grain> = store
+        .get_all_grains()?
+        .into_iter()
+        .filter(|grain| !quarantined.contains(&grain.id))
+        .collect();
 
     if grains.is_empty() {
         info!("No grains in local memory. Use 'syn add' first.");
@@ -216,6 +855,13 @@ async fn query_grains(data_dir: &PathBuf, question: &str, k: usize) -> Result<()
     // Search
     let results = index.search(&query_vec, k)?;
 
+    // Results count as reuse of the returned grains
+    if load_config(config_path)?.economy.track_access {
+        for result in &results {
+            store.record_grain_access(&result.grain_id, LOCAL_PEER, AccessType::Search.as_str())?;
+        }
+    }
+
     info!("Found {} results:", results.len());
     for (i, result) in results.iter().enumerate() {
         let grain = store.get_grain(&result.grain_id)?.unwrap();
@@ -264,7 +910,20 @@ async fn export_grains(data_dir: &PathBuf, output: &PathBuf) -> Result<()> {
     pb.set_message("Loading grains from database...");
     pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    let grains = store.get_all_grains()?;
+    // Private grains never leave their group: the store leaves them out of
+    // the grains, and links and credits touching them are dropped here
+    let private_ids = store.private_grain_ids()?;
+    let grains: Vec<Grain> = store.get_all_grains()?;
+    let links: Vec<Link> = store
+        .get_all_links()?
+        .into_iter()
+        .filter(|link| !private_ids.contains(&link.from) && !private_ids.contains(&link.to))
+        .collect();
+    let credits: Vec<Credit> = store
+        .get_all_credits()?
+        .into_iter()
+        .filter(|credit| !private_ids.contains(&credit.grain_id))
+        .collect();
     pb.finish_with_message(format!("✓ Loaded {} grains", grains.len()));
 
     if grains.is_empty() {
@@ -272,6 +931,9 @@ async fn export_grains(data_dir: &PathBuf, output: &PathBuf) -> Result<()> {
         return Ok(());
     }
 
+    // The manifest is signed with the node key
+    let signing_key = load_signing_key(data_dir)?;
+
     // Export to Parquet
     let exporter = ParquetExporter::new(output.to_string_lossy().to_string());
 
@@ -285,12 +947,14 @@ async fn export_grains(data_dir: &PathBuf, output: &PathBuf) -> Result<()> {
             .progress_chars("#>-"),
     );
 
-    let stats = exporter.export(grains)?;
+    let stats = exporter.export(grains, links, credits, &signing_key)?;
     pb.finish_with_message("✓ Export complete");
 
     // Display statistics
     println!("\n📊 Export Statistics:");
     println!("  Total grains:   {}", stats.total_grains);
+    println!("  Links:          {}", stats.total_links);
+    println!("  Credits:        {}", stats.total_credits);
     println!("  Files created:  {}", stats.files_created);
     println!(
         "  Bytes written:  {} ({:.2} MB)",
@@ -302,14 +966,28 @@ async fn export_grains(data_dir: &PathBuf, output: &PathBuf) -> Result<()> {
     Ok(())
 }
 
-async fn import_grains(data_dir: &PathBuf, input: &PathBuf) -> Result<()> {
+async fn import_grains(
+    data_dir: &PathBuf,
+    input: &PathBuf,
+    allow_unsigned: bool,
+    signer: Option<&str>,
+) -> Result<()> {
     use indicatif::{ProgressBar, ProgressStyle};
     use synapsenet_storage::ParquetImporter;
 
     info!("Importing grains from Parquet: {:?}", input);
 
     // Import from Parquet
-    let importer = ParquetImporter::new(input.to_string_lossy().to_string());
+    let mut importer = ParquetImporter::new(input.to_string_lossy().to_string());
+    if allow_unsigned {
+        importer = importer.allow_unsigned();
+    }
+    if let Some(signer) = signer {
+        let node_pk: [u8; 32] = hex::decode(signer)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Signer key must be 32 bytes"))?;
+        importer = importer.expect_signer(node_pk);
+    }
 
     let pb = ProgressBar::new_spinner();
     pb.set_style(
@@ -320,7 +998,8 @@ async fn import_grains(data_dir: &PathBuf, input: &PathBuf) -> Result<()> {
     pb.set_message("Importing grains...");
     pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    let stats = importer.import()?;
+    let store = Store::new(&data_dir.join("synapsenet.db").to_string_lossy())?;
+    let stats = importer.import(&store)?;
     pb.finish_with_message("✓ Import complete");
 
     // Display statistics
@@ -329,32 +1008,21 @@ async fn import_grains(data_dir: &PathBuf, input: &PathBuf) -> Result<()> {
     println!("  Imported:       {}", stats.imported);
     println!("  Skipped:        {}", stats.skipped);
     println!("  Invalid:        {}", stats.invalid);
+    println!("  Links:          {}", stats.links);
+    println!("  Credits:        {}", stats.credits);
+    match stats.signer {
+        Some(signer) => println!("  Signed by:      {}", hex::encode(signer)),
+        None => println!("  Signed by:      (unsigned export)"),
+    }
 
-    if stats.imported > 0 {
-        // Note about storage integration
-        println!("\n📝 Note: Grains verified successfully.");
-        println!("   Storage integration: Import validates grains.");
-        println!("   To store grains, use 'syn add' command.");
-        
-        // Rebuild HNSW index from existing grains
-        println!("\n🔄 Rebuilding HNSW index from database...");
-
-        let db_path = data_dir.join("synapsenet.db");
-        if db_path.exists() {
-            let store = Store::new(&db_path.to_string_lossy())?;
-            let grains = store.get_all_grains()?;
-
-            if !grains.is_empty() {
-                use synapsenet_storage::HnswIndex;
-                let mut index = HnswIndex::new(grains.len(), 384);
-                index.rebuild(&grains)?;
-                println!("✓ Index rebuilt with {} grains", index.len());
-            } else {
-                println!("  No grains in database yet");
-            }
-        }
+    if stats.signer.is_some() && signer.is_none() {
+        println!("\n⚠️  The manifest only proves the export is intact: any node can sign");
+        println!("   its own export. Pass --signer <node key> to accept only a trusted node.");
+    }
 
-        println!("\n✅ Import validation complete!");
+    if stats.imported > 0 {
+        // The HNSW index is built from the database when a node starts
+        println!("\n✅ {} grains stored; running nodes index them on restart", stats.imported);
     }
 
     Ok(())
@@ -393,6 +1061,19 @@ mod hex {
             .map(|b| format!("{:02x}", b))
             .collect()
     }
+
+    pub fn decode(s: &str) -> anyhow::Result<Vec<u8>> {
+        if !s.len().is_multiple_of(2) {
+            return Err(anyhow::anyhow!("Odd-length hex string"));
+        }
+        (0..s.len())
+            .step_by(2)
+            .map(|i| {
+                u8::from_str_radix(&s[i..i + 2], 16)
+                    .map_err(|_| anyhow::anyhow!("Invalid hex string: {}", s))
+            })
+            .collect()
+    }
 }
 
 async fn generate_config(output: &PathBuf) -> Result<()> {
@@ -467,68 +1148,87 @@ async fn show_stats(data_dir: &PathBuf) -> Result<()> {
 }
 
 
-async fn serve_api(data_dir: &PathBuf, addr: &str) -> Result<()> {
+async fn serve_api(data_dir: &PathBuf, config_path: &Path, addr: &str) -> Result<()> {
     use std::sync::Arc;
     use tokio::sync::RwLock;
     use synapsenet_api::{create_router, create_metrics_router, ApiState};
     
     info!("Starting REST API server on {}", addr);
     
-    // Load signing key
-    let key_path = data_dir.join("node.key");
-    if !key_path.exists() {
-        return Err(anyhow::anyhow!("Node not initialized. Run 'syn init' first."));
-    }
-    
-    let key_bytes = std::fs::read(&key_path)?;
-    let signing_key = match key_bytes.len() {
-        32 => {
-            // Classical ed25519
-            #[cfg(feature = "classical-crypto")]
-            {
-                use ed25519_dalek::SigningKey;
-                let sk = SigningKey::from_bytes(&key_bytes.try_into().unwrap());
-                synapsenet_core::UnifiedSigningKey::Classical(
-                    synapsenet_core::crypto::classical::ClassicalSigningKey::new(sk)
-                )
-            }
-            #[cfg(not(feature = "classical-crypto"))]
-            {
-                return Err(anyhow::anyhow!("Classical crypto not enabled"));
+    // Unlock the key once; local commands borrow it from the agent
+    let (key_file, key_bytes) = read_node_secret(data_dir)?;
+    #[cfg(unix)]
+    let _agent = if key_file.is_encrypted() {
+        match keystore::KeyAgent::spawn(&agent_socket(data_dir), key_bytes.clone()) {
+            Ok(agent) => Some(agent),
+            Err(e) => {
+                warn!("Key agent not started: {}", e);
+                None
             }
         }
-        _ => {
-            return Err(anyhow::anyhow!("Unsupported key format"));
-        }
+    } else {
+        None
     };
+    #[cfg(not(unix))]
+    let _ = key_file;
+
+    let signing_key = unified_signing_key(&key_bytes)?;
+
+    // Flags signed with this node's ed25519 key quarantine right away
+    let local_pk = <[u8; 32]>::try_from(&key_bytes[..])
+        .map(|secret| SigningKey::from_bytes(&secret).verifying_key().to_bytes())
+        .unwrap_or_default();
     
     // Open database
     let db_path = data_dir.join("synapsenet.db");
     let store = Store::new(&db_path.to_string_lossy())?;
     
     // Load grains and build index
-    let grains = store.get_all_grains()?;
-    let mut index = if grains.is_empty() {
-        HnswIndex::new(1000, 384)
-    } else {
-        let mut idx = HnswIndex::new(grains.len(), 384);
-        for grain in &grains {
-            idx.add(grain)?;
-        }
-        idx
-    };
-    
-    info!("Loaded {} grains", grains.len());
+    let index = load_index(&store)?;
     
     // Create embedding model
-    let embedding = OnnxEmbedding::new(data_dir.clone()).await?;
+    let embedding = Arc::new(OnnxEmbedding::new(data_dir.clone()).await?);
+    let policy = Arc::new(load_policy(data_dir, embedding.clone())?);
     
+    let store = Arc::new(Mutex::new(store));
+    let index = Arc::new(tokio::sync::RwLock::new(index));
+
+    // Score ingested grains and rescore reused ones in the background
+    let config = load_config(config_path)?;
+    let poe = Arc::new(PoEv2Engine::new(store.clone(), index.clone(), config.economy.clone())?);
+    tokio::spawn(poe.clone().run());
+
+    // Resume unfinished goals and pick up new ones in the background
+    let scheduler = GoalScheduler::new(store.clone(), load_reasoner(config_path)?);
+    tokio::spawn(scheduler.run(std::time::Duration::from_secs(5)));
+
+    let moderation = Arc::new(Moderator::new(
+        store.clone(),
+        config.moderation.clone(),
+        local_pk,
+    ));
+    if config.p2p.enabled {
+        let recipient = <[u8; 32]>::try_from(&key_bytes[..])
+            .ok()
+            .map(|secret| RecipientSecret::from_node_secret(&secret));
+        let ingest = GossipIngest {
+            poe: poe.clone(),
+            policy: policy.clone(),
+        };
+        spawn_p2p(&config, store.clone(), ingest, moderation.clone(), recipient).await?;
+    }
+
     // Create API state
     let state = Arc::new(ApiState {
-        store: Arc::new(Mutex::new(store)),
-        embedding: Arc::new(embedding),
+        store: store.clone(),
+        embedding,
         signing_key: Arc::new(signing_key),
-        index: Arc::new(tokio::sync::RwLock::new(index)),
+        index,
+        poe,
+        approvals: Arc::new(ApprovalQueue::new(store.clone())),
+        policy,
+        curation: Arc::new(CuratorQueue::new(store.clone())),
+        moderation,
     });
     
     // Create routers
@@ -551,6 +1251,8 @@ async fn serve_api(data_dir: &PathBuf, addr: &str) -> Result<()> {
     println!("  POST /query");
     println!("  GET  /stats");
     println!("  GET  /peers");
+    println!("  GET  /v2/goals");
+    println!("  POST /v2/goals");
     println!("  GET  /metrics");
     println!("\nPress Ctrl+C to stop\n");
     
@@ -559,6 +1261,313 @@ async fn serve_api(data_dir: &PathBuf, addr: &str) -> Result<()> {
     Ok(())
 }
 
+/// Grains received over gossip waiting for the ingest policy and scoring
+const GOSSIP_GRAIN_QUEUE: usize = 1024;
+
+/// Where `spawn_p2p` sends grains received from peers
+struct GossipIngest {
+    poe: Arc<PoEv2Engine>,
+    policy: Arc<PolicyEngine>,
+}
+
+/// Join the gossip network and relay signed records between it and the store
+///
+/// Received records are checked by the swarm, then recorded like local
+/// ones. Records this node creates stay pending in the store until the
+/// swarm has published them.
+async fn spawn_p2p(
+    config: &synapsenet_core::Config,
+    store: Arc<Mutex<Store>>,
+    ingest: GossipIngest,
+    moderation: Arc<Moderator>,
+    recipient: Option<RecipientSecret>,
+) -> Result<()> {
+    use synapsenet_p2p::{P2pConfig, SynapseSwarm};
+
+    let bootstrap_peers = config
+        .p2p
+        .bootstrap_peers
+        .iter()
+        .map(|addr| addr.parse())
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut swarm = SynapseSwarm::new(P2pConfig {
+        port: config.p2p.port,
+        enable_mdns: config.p2p.mdns_enabled,
+        bootstrap_peers,
+        hybrid_policy: config.crypto.hybrid_policy,
+    })
+    .await?;
+
+    // Keys revoked before this start must stay revoked
+    for (pk, from) in store.lock().unwrap().revoked_keys()? {
+        swarm.revoke_key(pk, from);
+    }
+
+    // Grains from peers must pass the ingest policy before they are stored
+    let (grains, mut received) = tokio::sync::mpsc::channel::<Grain>(GOSSIP_GRAIN_QUEUE);
+    swarm.set_grain_callback(move |grain| {
+        grains
+            .try_send(grain)
+            .map_err(|e| anyhow::anyhow!("Grain ingest queue: {}", e))
+    });
+    tokio::spawn(async move {
+        while let Some(grain) = received.recv().await {
+            let classification = ingest.policy.evaluate_grain(&grain);
+            if !classification.is_allowed() {
+                info!(
+                    "Refused gossiped grain {}: {}",
+                    hex::encode(grain.id),
+                    classification.explanation
+                );
+                continue;
+            }
+            if let Err(e) = ingest.poe.ingest(&grain).await {
+                warn!("Failed to ingest gossiped grain {}: {}", hex::encode(grain.id), e);
+            }
+        }
+    });
+
+    // Through the ledger for its amount and sender checks; a rejected
+    // transfer stays unseen so a later copy is tried again once funds arrive
+    let ledger = Mutex::new(NgtLedger::with_store(store.clone(), Default::default()));
+    relay(
+        &mut swarm,
+        &store,
+        Store::pending_transfers,
+        Store::mark_transfer_broadcast,
+        move |transfer| match ledger.lock().unwrap().apply_transfer(transfer)? {
+            TransferOutcome::Rejected(reason) => {
+                Err(anyhow::anyhow!("Transfer rejected: {}", reason))
+            }
+            _ => Ok(()),
+        },
+    );
+    let db = store.clone();
+    relay(
+        &mut swarm,
+        &store,
+        Store::pending_successions,
+        Store::mark_succession_broadcast,
+        move |succession| {
+            db.lock()
+                .unwrap()
+                .apply_key_succession(succession, false)
+                .map(drop)
+        },
+    );
+    let recipient = recipient.map(Arc::new);
+    let db = store.clone();
+    let secret = recipient.clone();
+    relay(
+        &mut swarm,
+        &store,
+        Store::pending_private_grains,
+        Store::mark_private_grain_broadcast,
+        move |grain| match secret {
+            Some(ref secret) => db
+                .lock()
+                .unwrap()
+                .apply_private_grain(grain, secret, false)
+                .map(drop),
+            None => Ok(()),
+        },
+    );
+    let db = store.clone();
+    relay(
+        &mut swarm,
+        &store,
+        Store::pending_group_grants,
+        Store::mark_group_grant_broadcast,
+        move |grant| match recipient {
+            Some(ref secret) => db
+                .lock()
+                .unwrap()
+                .apply_group_grant(grant, secret, false)
+                .map(drop),
+            None => Ok(()),
+        },
+    );
+    relay(
+        &mut swarm,
+        &store,
+        Store::pending_grain_flags,
+        Store::mark_grain_flag_broadcast,
+        move |flag| moderation.receive(flag).map(drop),
+    );
+
+    info!("Joining P2P network on port {}", config.p2p.port);
+    tokio::spawn(async move {
+        if let Err(e) = swarm.run().await {
+            warn!("P2P swarm stopped: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+/// Wire one signed record type between the swarm and the store
+fn relay<T, F>(
+    swarm: &mut synapsenet_p2p::SynapseSwarm,
+    store: &Arc<Mutex<Store>>,
+    pending: fn(&Store) -> Result<Vec<T>>,
+    mark_sent: fn(&Store, &[u8; 32]) -> Result<()>,
+    receive: F,
+) where
+    T: synapsenet_p2p::GossipRecord,
+    F: Fn(&T) -> Result<()> + Send + Sync + 'static,
+{
+    let outbox = store.clone();
+    let sent = store.clone();
+    let channel = swarm.channel::<T>();
+    channel.set_callback(move |record| receive(&record));
+    channel.set_outbox(
+        move || pending(&outbox.lock().unwrap()),
+        move |record: &T| mark_sent(&sent.lock().unwrap(), &record.record_id()),
+    );
+}
+
+/// Node signing key from its secret bytes
+fn unified_signing_key(key_bytes: &[u8]) -> Result<synapsenet_core::UnifiedSigningKey> {
+    match key_bytes.len() {
+        32 => {
+            // Classical ed25519
+            #[cfg(feature = "classical-crypto")]
+            {
+                use ed25519_dalek::SigningKey;
+                let sk = SigningKey::from_bytes(&key_bytes.try_into().unwrap());
+                Ok(synapsenet_core::UnifiedSigningKey::Classical(
+                    synapsenet_core::crypto::classical::ClassicalSigningKey::new(sk)
+                ))
+            }
+            #[cfg(not(feature = "classical-crypto"))]
+            {
+                Err(anyhow::anyhow!("Classical crypto not enabled"))
+            }
+        }
+        _ => Err(anyhow::anyhow!("Unsupported key format")),
+    }
+}
+
+/// Build the HNSW index over every stored grain
+fn load_index(store: &Store) -> Result<HnswIndex<'static>> {
+    let grains = store.get_all_grains()?;
+    let capacity = if grains.is_empty() { 1000 } else { grains.len() };
+    let mut index = HnswIndex::new(capacity, 384);
+    for grain in &grains {
+        index.add(grain)?;
+    }
+
+    info!("Loaded {} grains", grains.len());
+    Ok(index)
+}
+
+/// Content policy from `policy.toml` in the data directory, or the default
+fn load_policy(data_dir: &Path, embedding: Arc<OnnxEmbedding>) -> Result<PolicyEngine> {
+    let path = data_dir.join("policy.toml");
+    let policy = if path.exists() {
+        info!("Loading policy from {}", path.display());
+        Policy::load(&path)?
+    } else {
+        Policy::default()
+    };
+    PolicyEngine::new(policy).with_embedding_model(embedding)
+}
+
+/// Agent running math and installed plugins under the node's approvals and policy
+fn node_agent(
+    data_dir: &Path,
+    store: Arc<Mutex<Store>>,
+    policy: Arc<PolicyEngine>,
+) -> Result<synapsenet_agent::AgentCore> {
+    use synapsenet_agent::{AgentCore, PluginCapabilities, SandboxLimits, ToolRegistry, WasmToolHost};
+    use synapsenet_tools_official::MathEvalTool;
+
+    let registry = Arc::new(ToolRegistry::new());
+    registry
+        .register(Box::new(MathEvalTool::new()))
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let plugins_dir = data_dir.join("plugins");
+    if plugins_dir.is_dir() {
+        WasmToolHost::new(SandboxLimits::default(), PluginCapabilities::default())
+            .and_then(|host| host.register_dir(&plugins_dir, &registry))
+            .map_err(|e| anyhow::anyhow!(e))?;
+    }
+
+    Ok(AgentCore::with_defaults(registry)
+        .with_approvals(Arc::new(ApprovalQueue::new(store)))
+        .with_policy(policy))
+}
+
+async fn serve_mcp(data_dir: &Path, http: Option<&str>, access: McpHttpAccess) -> Result<()> {
+    use synapsenet_api::{create_mcp_router, McpServer};
+
+    // A bare port binds loopback; anything reachable from elsewhere needs a token
+    let http = http.map(|addr| match addr.parse::<u16>() {
+        Ok(port) => format!("127.0.0.1:{}", port),
+        Err(_) => addr.to_string(),
+    });
+    if let Some(ref addr) = http {
+        let loopback = tokio::net::lookup_host(addr.as_str())
+            .await?
+            .all(|a| a.ip().is_loopback());
+        if !loopback && access.bearer_token.is_none() {
+            anyhow::bail!("--token is required to serve MCP on non-loopback address {}", addr);
+        }
+    }
+    use synapsenet_tools_official::{GrainMemory, GrainQuota};
+
+    let (_, key_bytes) = read_node_secret(data_dir)?;
+    let signing_key = unified_signing_key(&key_bytes)?;
+
+    let db_path = data_dir.join("synapsenet.db");
+    let store = Store::new(&db_path.to_string_lossy())?;
+    let index = load_index(&store)?;
+    let embedding = Arc::new(OnnxEmbedding::new(data_dir.to_path_buf()).await?);
+    let policy = Arc::new(load_policy(data_dir, embedding.clone())?);
+
+    let store = Arc::new(Mutex::new(store));
+    let agent = Arc::new(node_agent(data_dir, store.clone(), policy.clone())?);
+
+    // A goal writes no more to memory than its sandbox allows on disk
+    let memory = Arc::new(
+        GrainMemory::new(
+            store.clone(),
+            Arc::new(tokio::sync::RwLock::new(index)),
+            embedding,
+            Arc::new(signing_key),
+        )
+        .with_quota(GrainQuota::from_sandbox(agent.sandbox().config()))
+        .with_policy(policy),
+    );
+
+    // Approved calls run once decided, wherever the decision was made
+    tokio::spawn(agent.clone().run_approvals(std::time::Duration::from_secs(5)));
+
+    let server = McpServer::new(agent, store)
+        .with_grain_memory(memory)
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    match http {
+        Some(addr) => {
+            let listener = tokio::net::TcpListener::bind(&addr).await?;
+
+            println!("\n🔌 SynapseNet MCP Server");
+            println!("================================");
+            println!("Endpoint: http://{}/mcp", addr);
+            println!("\nPress Ctrl+C to stop\n");
+
+            axum::serve(listener, create_mcp_router(Arc::new(server), access)).await?;
+        }
+        None => {
+            info!("Serving MCP over stdio");
+            let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+            server.serve(stdin, tokio::io::stdout()).await?;
+        }
+    }
+
+    Ok(())
+}
+
 async fn migrate_database(data_dir: &PathBuf, db_path: Option<PathBuf>) -> Result<()> {
     use synapsenet_storage::{migrate_v03_to_v04, needs_migration};
 
@@ -598,3 +1607,742 @@ async fn migrate_database(data_dir: &PathBuf, db_path: Option<PathBuf>) -> Resul
 
     Ok(())
 }
+
+/// Build the reasoner from the `[ai.llm]` section of the config file (if any)
+fn load_config(config_path: &Path) -> Result<synapsenet_core::Config> {
+    if config_path.exists() {
+        synapsenet_core::Config::load(config_path)
+    } else {
+        Ok(synapsenet_core::Config::default())
+    }
+}
+
+fn load_reasoner(config_path: &Path) -> Result<Reasoner> {
+    let config = load_config(config_path)?;
+    Reasoner::from_llm_config(ReasonerConfig::default(), &config.ai.llm)
+}
+
+/// PoE engine over the store, with an index of all stored grains
+fn load_poe_engine(store: Store, config_path: &Path) -> Result<PoEv2Engine> {
+    let grains = store.get_all_grains()?;
+    let capacity = if grains.is_empty() { 1000 } else { grains.len() };
+    let mut index = HnswIndex::new(capacity, 384);
+    for grain in &grains {
+        index.add(grain)?;
+    }
+
+    PoEv2Engine::new(
+        Arc::new(Mutex::new(store)),
+        Arc::new(tokio::sync::RwLock::new(index)),
+        load_config(config_path)?.economy,
+    )
+}
+
+async fn manage_goals(data_dir: &Path, config_path: &Path, command: GoalCommands) -> Result<()> {
+    use synapsenet_ai::TaskGraph;
+    use synapsenet_core::GoalStatus;
+
+    let db_path = data_dir.join("synapsenet.db");
+    let store = Arc::new(Mutex::new(Store::new(&db_path.to_string_lossy())?));
+
+    let parse_id = |id: &str| {
+        uuid::Uuid::parse_str(id).map_err(|_| anyhow::anyhow!("Invalid goal id: {}", id))
+    };
+
+    match command {
+        GoalCommands::List { status, limit } => {
+            let status = match status {
+                Some(name) => Some(
+                    GoalStatus::parse(&name)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown status: {}", name))?,
+                ),
+                None => None,
+            };
+
+            let goals = store.lock().unwrap().list_goals(status, limit)?;
+            if goals.is_empty() {
+                println!("No goals found");
+                return Ok(());
+            }
+
+            println!("\n🎯 Goals ({})", goals.len());
+            println!("========================================");
+            for goal in goals {
+                println!("{}  {:<10}  {}", goal.id, goal.status.as_str(), goal.text);
+            }
+        }
+        GoalCommands::Show { id } => {
+            let goal_id = parse_id(&id)?;
+            let store = store.lock().unwrap();
+            let goal = store
+                .get_goal(&goal_id)?
+                .ok_or_else(|| anyhow::anyhow!("Goal not found: {}", id))?;
+
+            println!("\n🎯 Goal {}", goal.id);
+            println!("========================================");
+            println!("Text:       {}", goal.text);
+            println!("Status:     {}", goal.status.as_str());
+            println!("Priority:   {:?}", goal.priority);
+            println!("Created by: {}", goal.created_by);
+
+            if let Some(json) = store.get_plan(&goal_id)? {
+                let plan: TaskGraph = serde_json::from_str(&json)?;
+                println!("\n📋 Plan ({:.0}% complete)", plan.completion_percentage());
+                for node_id in plan.topological_sort().map_err(|e| anyhow::anyhow!(e))? {
+                    let node = &plan.nodes[&node_id];
+                    println!("  [{:?}] {}", node.status, node.task);
+                }
+            }
+
+            let episodes = store.get_episodes_v2(&goal_id)?;
+            if !episodes.is_empty() {
+                println!("\n🧠 Episodes");
+                for episode in &episodes {
+                    println!(
+                        "  Step {}: {} (confidence {:.2})",
+                        episode.base.step, episode.base.query, episode.base.confidence
+                    );
+                    for action in &episode.actions {
+                        let mark = if action.success { "✅" } else { "❌" };
+                        println!(
+                            "    └─ {} {} {}ms",
+                            action.tool_name, mark, action.execution_time_ms
+                        );
+                    }
+                }
+            }
+
+            if let Some(answer) = goal.metadata["answer"].as_str() {
+                println!("\n💡 Answer:\n{}", answer);
+            }
+            if let Some(error) = goal.metadata["error"].as_str() {
+                println!("\n❌ Error: {}", error);
+            }
+        }
+        GoalCommands::Cancel { id } => {
+            let goal_id = parse_id(&id)?;
+            if synapsenet_ai::goal_scheduler::cancel_goal(&store, &goal_id)? {
+                println!("✓ Goal cancelled: {}", id);
+            } else {
+                println!("⚠️  Goal not found or already finished: {}", id);
+            }
+        }
+        GoalCommands::Rerun { id } => {
+            let goal_id = parse_id(&id)?;
+            let mut scheduler = GoalScheduler::new(store, load_reasoner(config_path)?);
+            if !scheduler.reset(&goal_id)? {
+                return Err(anyhow::anyhow!("Goal not found: {}", id));
+            }
+
+            println!("🔄 Re-running goal {}...", id);
+            let outcome = scheduler.run_goal(&goal_id).await?;
+            println!(
+                "✓ Goal {} ({} steps)",
+                outcome.status.as_str(),
+                outcome.steps_executed
+            );
+            if let Some(answer) = outcome.answer {
+                println!("\n💡 Answer:\n{}", answer);
+            }
+        }
+        GoalCommands::Run { plan } => {
+            use synapsenet_agent::{DagExecutor, ExecutionContext};
+
+            let graph: TaskGraph = serde_json::from_str(&std::fs::read_to_string(&plan)?)?;
+            let embedding = Arc::new(OnnxEmbedding::new(data_dir.to_path_buf()).await?);
+            let policy = Arc::new(load_policy(data_dir, embedding)?);
+            let agent = Arc::new(node_agent(data_dir, store, policy)?);
+
+            let context = ExecutionContext {
+                goal_id: uuid::Uuid::new_v4(),
+                episode_id: None,
+                user_id: LOCAL_PEER.to_string(),
+                timestamp: chrono::Utc::now().timestamp(),
+                metadata: std::collections::HashMap::new(),
+            };
+            let run = DagExecutor::new(agent)
+                .execute(graph, context)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+
+            println!("\n📋 Plan");
+            println!("========================================");
+            for node_id in run.graph.topological_sort().map_err(|e| anyhow::anyhow!(e))? {
+                let node = &run.graph.nodes[&node_id];
+                println!("  [{:?}] {}", node.status, node.task);
+                if let Some(error) = run.errors.get(&node_id) {
+                    println!("      {}", error);
+                }
+            }
+
+            match run.awaiting_approval {
+                Some(id) => {
+                    println!("\n⏸  Waiting for approval {}", id);
+                    println!("   Decide with `syn approvals approve|reject {}`;", id);
+                    println!("   a running `syn mcp` resumes the plan once it is decided");
+                }
+                None if run.success => println!("\n✓ Plan completed"),
+                None => println!("\n✗ Plan failed"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn manage_private(data_dir: &Path, command: PrivateCommands) -> Result<()> {
+    use synapsenet_core::grain::cosine_similarity;
+
+    let signing_key = load_signing_key(data_dir)?;
+    let secret = RecipientSecret::from_node_secret(&signing_key.to_bytes());
+    let store = Store::new(&data_dir.join("synapsenet.db").to_string_lossy())?;
+
+    let parse_members = |members: &[String]| -> Result<Vec<RecipientKey>> {
+        members.iter().map(|member| member.parse()).collect()
+    };
+    let group_key = |name: &str| -> Result<GroupKey> {
+        store
+            .find_group_key(name, &secret)?
+            .ok_or_else(|| anyhow::anyhow!("Unknown group: {}", name))
+    };
+
+    match command {
+        PrivateCommands::Key => {
+            for key in secret.public_keys() {
+                println!("{}", key);
+            }
+        }
+        PrivateCommands::Create { name, members } => {
+            if store.find_group_key(&name, &secret)?.is_some() {
+                return Err(anyhow::anyhow!("Group {} already exists", name));
+            }
+            let members = parse_members(&members)?;
+
+            let key = GroupKey::generate();
+            store.insert_group_key(&name, &key, &secret)?;
+            println!("✓ Created group {} ({})", name, hex::encode(key.group_id()));
+
+            if !members.is_empty() {
+                let grant = GroupKeyGrant::new(&key, &members, &signing_key)?;
+                store.apply_group_grant(&grant, &secret, true)?;
+                println!("  Key queued for {} member(s)", members.len());
+            }
+        }
+        PrivateCommands::Invite { group, members } => {
+            let members = parse_members(&members)?;
+            let grant = GroupKeyGrant::new(&group_key(&group)?, &members, &signing_key)?;
+            store.apply_group_grant(&grant, &secret, true)?;
+            println!("✓ Key of {} queued for {} member(s)", group, members.len());
+        }
+        PrivateCommands::Add { group, input } => {
+            let key = group_key(&group)?;
+            let embedding = OnnxEmbedding::new(data_dir.to_path_buf()).await?;
+            let grain = embed_grain(&embedding, &signing_key, &input)?;
+            let private = PrivateGrain::seal(&grain, &key, &signing_key)?;
+
+            match store.apply_private_grain(&private, &secret, true)? {
+                PrivateGrainOutcome::Opened { grain_id } => {
+                    println!("✓ Private grain added to {}: {}", group, hex::encode(grain_id));
+                    println!("  Queued for broadcast; only members can read it");
+                }
+                other => return Err(anyhow::anyhow!("Unexpected outcome: {:?}", other)),
+            }
+        }
+        PrivateCommands::Query { group, question, k } => {
+            // Group grains are few and kept out of the shared index
+            let grains = store.get_private_grains(&group_key(&group)?.group_id())?;
+            let embedding = OnnxEmbedding::new(data_dir.to_path_buf()).await?;
+            let query_vec = embedding.embed(&question)?;
+
+            let mut results: Vec<(f32, &Grain)> = grains
+                .iter()
+                .map(|grain| (cosine_similarity(&query_vec, &grain.vec), grain))
+                .collect();
+            results.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+            info!("Found {} results:", results.len().min(k));
+            for (i, (similarity, grain)) in results.iter().take(k).enumerate() {
+                println!("\n{}. Similarity: {:.3}", i + 1, similarity);
+                println!("   ID: {}", hex::encode(grain.id));
+                if let Some(title) = &grain.meta.title {
+                    println!("   Title: {}", title);
+                }
+            }
+        }
+        PrivateCommands::Groups => {
+            let groups = store.list_groups()?;
+            if groups.is_empty() {
+                println!("Not a member of any group");
+            }
+            for (name, group_id) in groups {
+                println!("{}  {}", hex::encode(group_id), name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn manage_approvals(data_dir: &Path, command: ApprovalCommands) -> Result<()> {
+    use synapsenet_agent::{ApprovalRequest, ApprovalStatus};
+
+    let store = Store::new(&data_dir.join("synapsenet.db").to_string_lossy())?;
+    let queue = ApprovalQueue::new(Arc::new(Mutex::new(store)));
+
+    let parse_id = |id: &str| {
+        uuid::Uuid::parse_str(id).map_err(|_| anyhow::anyhow!("Invalid approval id: {}", id))
+    };
+    let print_decision = |request: &ApprovalRequest| {
+        println!("✓ {} {}: {}", request.tool_name, request.status.as_str(), request.id);
+        if let Some(reason) = &request.reason {
+            println!("  Reason: {}", reason);
+        }
+    };
+
+    match command {
+        ApprovalCommands::List { status } => {
+            let status = match status {
+                Some(name) => Some(
+                    ApprovalStatus::parse(&name)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown status: {}", name))?,
+                ),
+                None => None,
+            };
+
+            let requests = queue.list(status)?;
+            if requests.is_empty() {
+                println!("No approval requests found");
+                return Ok(());
+            }
+
+            println!("\n🛂 Approvals ({})", requests.len());
+            println!("========================================");
+            for request in requests {
+                println!(
+                    "{}  {:<8}  {}  goal {}",
+                    request.id,
+                    request.status.as_str(),
+                    request.tool_name,
+                    request.goal_id
+                );
+            }
+        }
+        ApprovalCommands::Show { id } => {
+            let request = queue.get(&parse_id(&id)?)?;
+
+            println!("\n🛂 Approval {}", request.id);
+            println!("========================================");
+            println!("Tool:      {}", request.tool_name);
+            println!("Goal:      {}", request.goal_id);
+            println!("Status:    {}", request.status.as_str());
+            if request.status == ApprovalStatus::Pending {
+                let now_ms = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)?
+                    .as_millis() as i64;
+                println!("Expires in: {}s", (request.expires_at_ms - now_ms).max(0) / 1000);
+            }
+            if let Some(by) = &request.decided_by {
+                println!("Decided by: {}", by);
+            }
+            if let Some(reason) = &request.reason {
+                println!("Reason:    {}", reason);
+            }
+            println!("\nProposed input:");
+            println!("{}", serde_json::to_string_pretty(&request.input.params)?);
+        }
+        ApprovalCommands::Approve { id } => {
+            print_decision(&queue.approve(&parse_id(&id)?, "cli")?);
+        }
+        ApprovalCommands::Reject { id, reason } => {
+            print_decision(&queue.reject(&parse_id(&id)?, "cli", &reason)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn manage_curation(data_dir: &Path, command: CurationCommands) -> Result<()> {
+    use synapsenet_core::{CuratorAction, CuratorDecision};
+    use synapsenet_governance::CuratorQueue;
+    use synapsenet_storage::{CuratorTaskStatus, DecisionOutcome};
+
+    let db_path = data_dir.join("synapsenet.db");
+    let store = Arc::new(Mutex::new(Store::new(&db_path.to_string_lossy())?));
+    let queue = CuratorQueue::new(store);
+
+    let parse_id = |id: &str| {
+        uuid::Uuid::parse_str(id).map_err(|_| anyhow::anyhow!("Invalid task id: {}", id))
+    };
+    let parse_pk = |s: &str| -> Result<[u8; 32]> {
+        hex::decode(s)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Public key must be 32 bytes: {}", s))
+    };
+    // Decisions are signed with the node key, which must be a curator
+    let decide = |id: &str, action: CuratorAction, note: Option<String>| -> Result<()> {
+        let signing_key = load_signing_key(data_dir)?;
+        let decision = CuratorDecision::new(parse_id(id)?, action, note, &signing_key);
+        match queue.decide(&decision)? {
+            DecisionOutcome::Applied(task) => {
+                println!("✓ Task {}: {}", task.status.as_str(), task.id);
+                if let Some(note) = &task.note {
+                    println!("  Note: {}", note);
+                }
+                Ok(())
+            }
+            DecisionOutcome::NotFound => Err(anyhow::anyhow!("Task not found: {}", id)),
+            DecisionOutcome::NotCurator => Err(anyhow::anyhow!(
+                "This node's key is not a curator (add it with `curation curators add`)"
+            )),
+            DecisionOutcome::Conflict(reason) => Err(anyhow::anyhow!(reason)),
+        }
+    };
+
+    match command {
+        CurationCommands::List { status } => {
+            let status = match status {
+                Some(name) => Some(
+                    CuratorTaskStatus::parse(&name)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown status: {}", name))?,
+                ),
+                None => None,
+            };
+
+            let tasks = queue.list(status, 100)?;
+            if tasks.is_empty() {
+                println!("No curation tasks found");
+                return Ok(());
+            }
+
+            println!("\n🧑‍⚖️ Curation tasks ({})", tasks.len());
+            println!("========================================");
+            for task in tasks {
+                println!(
+                    "{}  {:<9}  {:<6}  {}",
+                    task.id,
+                    task.status.as_str(),
+                    task.route,
+                    task.subject.chars().take(60).collect::<String>()
+                );
+            }
+        }
+        CurationCommands::Show { id } => {
+            let task = queue
+                .get(&parse_id(&id)?)?
+                .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+
+            println!("\n🧑‍⚖️ Curation task {}", task.id);
+            println!("========================================");
+            println!("Route:     {}", task.route);
+            println!("Status:    {}", task.status.as_str());
+            if let Some(requester) = &task.requester {
+                println!("Requester: {}", requester);
+            }
+            if let Some(curator) = &task.curator {
+                println!("Curator:   {}", hex::encode(curator));
+            }
+            if let Some(note) = &task.note {
+                println!("Note:      {}", note);
+            }
+            println!("\nSubject:\n{}", task.subject);
+            if let Some(explanation) = task.classification["explanation"].as_str() {
+                println!("\nPolicy: {}", explanation);
+            }
+        }
+        CurationCommands::Claim { id } => decide(&id, CuratorAction::Claim, None)?,
+        CurationCommands::Approve { id, note } => decide(&id, CuratorAction::Approve, note)?,
+        CurationCommands::Reject { id, note } => {
+            if note.trim().is_empty() {
+                return Err(anyhow::anyhow!("A note is required to reject"));
+            }
+            decide(&id, CuratorAction::Reject, Some(note))?
+        }
+        CurationCommands::Audit { task } => {
+            let task_id = task.as_deref().map(parse_id).transpose()?;
+            let entries = queue.audit(task_id.as_ref(), 100)?;
+            if entries.is_empty() {
+                println!("No curator decisions recorded");
+                return Ok(());
+            }
+
+            println!("\n📜 Curator decisions ({})", entries.len());
+            println!("========================================");
+            for entry in entries {
+                println!(
+                    "{}  {}  {:<7}  {}  {}",
+                    chrono::DateTime::from_timestamp_millis(entry.ts_unix_ms)
+                        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_default(),
+                    entry.task_id,
+                    entry.action.as_str(),
+                    &hex::encode(entry.curator)[..16],
+                    entry.note.unwrap_or_default()
+                );
+            }
+        }
+        CurationCommands::Curators { command } => match command {
+            CuratorCommands::List => {
+                let curators = queue.curators()?;
+                if curators.is_empty() {
+                    println!("No curators registered");
+                    return Ok(());
+                }
+                for (key, name) in curators {
+                    println!("{}  {}", hex::encode(key), name.unwrap_or_default());
+                }
+            }
+            CuratorCommands::Add { public_key, name } => {
+                let key = match public_key {
+                    Some(pk) => parse_pk(&pk)?,
+                    None => load_signing_key(data_dir)?.verifying_key().to_bytes(),
+                };
+                if queue.add_curator(&key, name.as_deref())? {
+                    println!("✓ Curator added: {}", hex::encode(key));
+                } else {
+                    println!("Already a curator: {}", hex::encode(key));
+                }
+            }
+            CuratorCommands::Remove { public_key } => {
+                if queue.remove_curator(&parse_pk(&public_key)?)? {
+                    println!("✓ Curator removed: {}", public_key);
+                } else {
+                    println!("Not a curator: {}", public_key);
+                }
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn manage_moderation(
+    data_dir: &Path,
+    config_path: &Path,
+    command: ModerationCommands,
+) -> Result<()> {
+    use synapsenet_core::{FlagAction, FlagReason, GrainFlag};
+    use synapsenet_storage::{FlagOutcome, ModerationStatus};
+
+    let db_path = data_dir.join("synapsenet.db");
+    let store = Arc::new(Mutex::new(Store::new(&db_path.to_string_lossy())?));
+    let parse_id = |id: &str| -> Result<[u8; 32]> {
+        hex::decode(id)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Grain ID must be 32 bytes: {}", id))
+    };
+
+    // The node's own flags are weighed as local decisions
+    let moderator = |local_pk: [u8; 32]| -> Result<Moderator> {
+        Ok(Moderator::new(store.clone(), load_config(config_path)?.moderation, local_pk))
+    };
+    let node_pk =
+        || -> Result<[u8; 32]> { Ok(load_signing_key(data_dir)?.verifying_key().to_bytes()) };
+
+    // Flags are signed with the node key and queued for gossip
+    let submit = |grain_id: &str, action: FlagAction, note: Option<String>| -> Result<()> {
+        let signing_key = load_signing_key(data_dir)?;
+        let moderator = moderator(signing_key.verifying_key().to_bytes())?;
+        let flag = GrainFlag::new(parse_id(grain_id)?, action, note, &signing_key);
+
+        match moderator.submit(&flag)? {
+            FlagOutcome::Recorded => {
+                println!("✓ Recorded {} on {}", action.as_str(), grain_id);
+                if let Some(state) = moderator.status(&flag.grain_id)? {
+                    println!(
+                        "  Status: {} (flag weight {:.2})",
+                        state.status.as_str(),
+                        state.flag_weight
+                    );
+                }
+                Ok(())
+            }
+            FlagOutcome::Duplicate => {
+                println!("Already recorded");
+                Ok(())
+            }
+            FlagOutcome::Rejected(reason) => Err(anyhow::anyhow!(reason)),
+        }
+    };
+
+    match command {
+        ModerationCommands::Flag {
+            grain_id,
+            reason,
+            note,
+        } => {
+            let reason = FlagReason::parse(&reason)
+                .ok_or_else(|| anyhow::anyhow!("Unknown reason: {}", reason))?;
+            submit(&grain_id, FlagAction::Flag(reason), note)?
+        }
+        ModerationCommands::Unflag { grain_id } => submit(&grain_id, FlagAction::Unflag, None)?,
+        ModerationCommands::Appeal { grain_id, note } => {
+            submit(&grain_id, FlagAction::Appeal, Some(note))?
+        }
+        ModerationCommands::List { status } => {
+            let status = match status {
+                Some(name) => Some(
+                    ModerationStatus::parse(&name)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown status: {}", name))?,
+                ),
+                None => None,
+            };
+
+            let grains = store.lock().unwrap().list_grain_moderation(status, 100)?;
+            if grains.is_empty() {
+                println!("No moderated grains");
+                return Ok(());
+            }
+
+            println!("\n🚩 Moderated grains ({})", grains.len());
+            println!("========================================");
+            for state in grains {
+                println!(
+                    "{}  {:<11}  {:.2}",
+                    hex::encode(state.grain_id),
+                    state.status.as_str(),
+                    state.flag_weight
+                );
+            }
+        }
+        ModerationCommands::Show { grain_id } => {
+            let id = parse_id(&grain_id)?;
+            let (state, flags) = {
+                let store = store.lock().unwrap();
+                (store.get_grain_moderation(&id)?, store.grain_flags(&id)?)
+            };
+
+            println!("\n🚩 Grain {}", grain_id);
+            println!("========================================");
+            match state {
+                Some(state) => {
+                    println!("Status:      {}", state.status.as_str());
+                    println!("Flag weight: {:.2}", state.flag_weight);
+                }
+                None => println!("Status:      not moderated"),
+            }
+            for flag in flags {
+                println!(
+                    "  {}  {:<12}  {}  {}",
+                    chrono::DateTime::from_timestamp_millis(flag.ts_unix_ms)
+                        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_default(),
+                    flag.action.as_str(),
+                    &hex::encode(flag.signer)[..16],
+                    flag.note.unwrap_or_default()
+                );
+            }
+        }
+        ModerationCommands::Release { grain_id } => {
+            match moderator(node_pk()?)?.release(&parse_id(&grain_id)?)? {
+                Some(state) => println!("✓ Grain {}: {}", grain_id, state.status.as_str()),
+                None => println!("Grain not moderated: {}", grain_id),
+            }
+        }
+        ModerationCommands::Uphold { grain_id } => {
+            match moderator(node_pk()?)?.uphold(&parse_id(&grain_id)?)? {
+                Some(state) => println!("✓ Grain {}: {}", grain_id, state.status.as_str()),
+                None => println!("Grain not moderated: {}", grain_id),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn manage_wallet(data_dir: &Path, command: WalletCommands) -> Result<()> {
+    let signing_key = load_signing_key(data_dir)?;
+    let node_pk = signing_key.verifying_key().to_bytes();
+
+    let db_path = data_dir.join("synapsenet.db");
+    let store = Arc::new(Mutex::new(Store::new(&db_path.to_string_lossy())?));
+    let mut ledger = NgtLedger::with_store(store, Default::default());
+
+    let parse_pk = |s: &str| -> Result<[u8; 32]> {
+        hex::decode(s)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Public key must be 32 bytes: {}", s))
+    };
+
+    match command {
+        WalletCommands::Send { to, amount, memo } => {
+            let (transfer, outcome) = ledger.send(&signing_key, parse_pk(&to)?, amount, memo)?;
+            match outcome {
+                TransferOutcome::Applied { .. } => {
+                    println!("✓ Sent {:.6} NGT to {}", amount, to);
+                    println!("  Transfer: {}", hex::encode(transfer.id()));
+                    println!("  Nonce:    {}", transfer.nonce);
+                    println!("  Queued for broadcast to peers");
+                }
+                TransferOutcome::Rejected(reason) => {
+                    return Err(anyhow::anyhow!("Transfer rejected: {}", reason));
+                }
+                other => return Err(anyhow::anyhow!("Unexpected outcome: {:?}", other)),
+            }
+        }
+        WalletCommands::Balance { node } => {
+            let pk = match node {
+                Some(hex_pk) => parse_pk(&hex_pk)?,
+                None => node_pk,
+            };
+            println!("{}: {:.6} NGT", hex::encode(pk), ledger.balance(&pk)?);
+        }
+        WalletCommands::History { limit } => {
+            println!("\n💰 Balance: {:.6} NGT", ledger.balance(&node_pk)?);
+
+            let entries = ledger.history(&node_pk, limit)?;
+            println!("\n📒 Ledger ({})", entries.len());
+            println!("========================================");
+            let account = format!("node:{}", hex::encode(node_pk));
+            for entry in entries {
+                let delta: f64 = entry
+                    .postings
+                    .iter()
+                    .filter(|p| p.account == account)
+                    .map(|p| p.amount)
+                    .sum();
+                println!(
+                    "#{:<6} {:<9} {:>+14.6}  {}",
+                    entry.id,
+                    entry.kind.as_str(),
+                    delta,
+                    entry.memo
+                );
+            }
+
+            let transfers = ledger.transfers(&node_pk, limit)?;
+            if !transfers.is_empty() {
+                println!("\n🔁 Signed transfers ({})", transfers.len());
+                for record in transfers {
+                    let t = &record.transfer;
+                    let (direction, peer) = if t.from == node_pk {
+                        ("→", t.to)
+                    } else {
+                        ("←", t.from)
+                    };
+                    println!(
+                        "  {} {} {:.6} NGT (nonce {}) {}",
+                        direction,
+                        hex::encode(&peer[..8]),
+                        t.amount,
+                        t.nonce,
+                        t.memo.as_deref().unwrap_or("")
+                    );
+                }
+            }
+
+            let conflicts = ledger.conflicts(limit)?;
+            if !conflicts.is_empty() {
+                println!("\n⚠️  Double-spend attempts ({})", conflicts.len());
+                for conflict in conflicts {
+                    println!(
+                        "  {} reused nonce {}",
+                        hex::encode(&conflict.from[..8]),
+                        conflict.nonce
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
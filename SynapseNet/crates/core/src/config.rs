@@ -1,7 +1,10 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
+use crate::crypto::HybridPolicy;
+
 /// SynapseNet configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -28,6 +31,14 @@ pub struct Config {
     /// UI configuration (NEW in v0.4)
     #[serde(default)]
     pub ui: UiConfig,
+
+    /// Signature verification configuration
+    #[serde(default)]
+    pub crypto: CryptoConfig,
+
+    /// Community moderation configuration
+    #[serde(default)]
+    pub moderation: ModerationConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,6 +86,56 @@ pub struct AiConfig {
     /// Enable multi-model support (NEW in v0.4)
     #[serde(default)]
     pub multi_model_enabled: bool,
+
+    /// Local LLM used for planning and synthesis
+    #[serde(default)]
+    pub llm: LlmConfig,
+}
+
+/// Local text generation backend configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmConfig {
+    /// Use the LLM for planning and synthesis (falls back to heuristics when off)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Server API flavour: openai, llamacpp
+    #[serde(default = "default_llm_backend")]
+    pub backend: String,
+
+    /// Base URL of the local server
+    #[serde(default = "default_llm_endpoint")]
+    pub endpoint: String,
+
+    /// Model name sent to OpenAI-compatible servers
+    #[serde(default = "default_llm_model")]
+    pub model: String,
+
+    /// Maximum tokens to generate per request
+    #[serde(default = "default_llm_max_tokens")]
+    pub max_tokens: u32,
+
+    /// Sampling temperature
+    #[serde(default = "default_llm_temperature")]
+    pub temperature: f32,
+
+    /// Request timeout (seconds)
+    #[serde(default = "default_llm_timeout")]
+    pub timeout_secs: u64,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: default_llm_backend(),
+            endpoint: default_llm_endpoint(),
+            model: default_llm_model(),
+            max_tokens: default_llm_max_tokens(),
+            temperature: default_llm_temperature(),
+            timeout_secs: default_llm_timeout(),
+        }
+    }
 }
 
 /// Configuration for a single model (NEW in v0.4)
@@ -193,6 +254,10 @@ pub struct EconomyConfig {
     /// Access event retention period (days)
     #[serde(default = "default_access_retention")]
     pub access_retention_days: u32,
+
+    /// Interval between reuse rescoring passes (seconds)
+    #[serde(default = "default_rescore_interval")]
+    pub rescore_interval_secs: u64,
 }
 
 impl Default for EconomyConfig {
@@ -205,6 +270,43 @@ impl Default for EconomyConfig {
             min_novelty_threshold: 0.1,
             track_access: true,
             access_retention_days: 90,
+            rescore_interval_secs: 300,
+        }
+    }
+}
+
+/// Signature verification configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CryptoConfig {
+    /// Which halves of a hybrid signature must validate: both, either
+    #[serde(default)]
+    pub hybrid_policy: HybridPolicy,
+}
+
+/// Community moderation configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationConfig {
+    /// Flag weight at which a grain is quarantined
+    #[serde(default = "default_quarantine_threshold")]
+    pub quarantine_threshold: f64,
+
+    /// Trust in flags from nodes not listed in `trust`, scaled by their
+    /// stake; nodes without NGT balance weigh nothing
+    #[serde(default = "default_flag_trust")]
+    pub default_trust: f64,
+
+    /// Trust in flags per node public key (hex), counted even without
+    /// stake; 0 ignores a node's flags
+    #[serde(default)]
+    pub trust: HashMap<String, f64>,
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        Self {
+            quarantine_threshold: 3.0,
+            default_trust: 1.0,
+            trust: HashMap::new(),
         }
     }
 }
@@ -292,6 +394,42 @@ fn default_access_retention() -> u32 {
     90
 }
 
+fn default_rescore_interval() -> u64 {
+    300
+}
+
+fn default_quarantine_threshold() -> f64 {
+    3.0
+}
+
+fn default_flag_trust() -> f64 {
+    1.0
+}
+
+fn default_llm_backend() -> String {
+    "openai".to_string()
+}
+
+fn default_llm_endpoint() -> String {
+    "http://127.0.0.1:8080".to_string()
+}
+
+fn default_llm_model() -> String {
+    "local".to_string()
+}
+
+fn default_llm_max_tokens() -> u32 {
+    512
+}
+
+fn default_llm_temperature() -> f32 {
+    0.2
+}
+
+fn default_llm_timeout() -> u64 {
+    60
+}
+
 fn default_theme() -> String {
     "auto".to_string()
 }
@@ -329,6 +467,7 @@ impl Default for Config {
                 provider: "cpu".to_string(), // cpu, coreml, directml, cuda
                 additional_models: vec![],
                 multi_model_enabled: false,
+                llm: LlmConfig::default(),
             },
             storage: StorageConfig {
                 db_file: "synapsenet.db".to_string(),
@@ -338,6 +477,8 @@ impl Default for Config {
             },
             economy: EconomyConfig::default(),
             ui: UiConfig::default(),
+            crypto: CryptoConfig::default(),
+            moderation: ModerationConfig::default(),
         }
     }
 }
@@ -430,6 +571,11 @@ impl Config {
             anyhow::bail!("Embedding dimension must be > 0");
         }
 
+        // Validate LLM backend
+        if !["openai", "llamacpp"].contains(&self.ai.llm.backend.as_str()) {
+            anyhow::bail!("AI llm.backend must be 'openai' or 'llamacpp'");
+        }
+
         // Validate HNSW parameters
         if self.storage.hnsw_max_elements == 0 {
             anyhow::bail!("HNSW max_elements must be > 0");
@@ -471,6 +617,17 @@ impl Config {
             anyhow::bail!("Economy min_novelty_threshold must be between 0.0 and 1.0");
         }
 
+        // Validate moderation config
+        if self.moderation.quarantine_threshold <= 0.0 {
+            anyhow::bail!("Moderation quarantine_threshold must be > 0");
+        }
+
+        if self.moderation.default_trust < 0.0
+            || self.moderation.trust.values().any(|trust| *trust < 0.0)
+        {
+            anyhow::bail!("Moderation trust values must be >= 0");
+        }
+
         // Validate UI config
         if !["light", "dark", "auto"].contains(&self.ui.theme.as_str()) {
             anyhow::bail!("UI theme must be 'light', 'dark', or 'auto'");
@@ -1,5 +1,5 @@
 // SynapseNet Crypto Abstraction Layer
-// Supports both classical (ed25519) and post-quantum (Dilithium) signatures
+// Supports classical (ed25519), post-quantum (Dilithium) and hybrid signatures
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -11,6 +11,20 @@ pub enum CryptoBackend {
     Classical,
     /// Post-quantum Dilithium signatures
     PostQuantum,
+    /// ed25519 and Dilithium signatures over the same message
+    Hybrid,
+}
+
+/// Which halves of a hybrid signature must validate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HybridPolicy {
+    /// Both the ed25519 and the Dilithium signature
+    #[default]
+    Both,
+    /// Either signature; lets nodes without PQC support accept hybrid
+    /// content while a network migrates
+    Either,
 }
 
 /// Unified signing key interface
@@ -124,7 +138,7 @@ pub mod classical {
 pub mod pqc {
     use super::*;
     use pqcrypto_dilithium::dilithium5;
-    use pqcrypto_traits::sign::{PublicKey, SecretKey, SignedMessage};
+    use pqcrypto_traits::sign::{DetachedSignature, PublicKey, SecretKey, SignedMessage};
     
     pub struct PqcSigningKey {
         inner: dilithium5::SecretKey,
@@ -158,6 +172,11 @@ pub mod pqc {
         pub fn to_bytes(&self) -> Vec<u8> {
             self.inner.as_bytes().to_vec()
         }
+        
+        /// Signature without the message attached
+        pub fn sign_detached(&self, message: &[u8]) -> Vec<u8> {
+            dilithium5::detached_sign(message, &self.inner).as_bytes().to_vec()
+        }
     }
     
     impl SigningKeyTrait for PqcSigningKey {
@@ -185,6 +204,13 @@ pub mod pqc {
                 .map_err(|_| anyhow::anyhow!("Invalid public key"))?;
             Ok(Self { inner: pk })
         }
+        
+        /// Verify a signature made with `sign_detached`
+        pub fn verify_detached(&self, message: &[u8], signature: &[u8]) -> Result<bool> {
+            let sig = dilithium5::DetachedSignature::from_bytes(signature)
+                .map_err(|_| anyhow::anyhow!("Invalid detached signature"))?;
+            Ok(dilithium5::verify_detached_signature(&sig, message, &self.inner).is_ok())
+        }
     }
     
     impl VerifyingKeyTrait for PqcVerifyingKey {
@@ -208,17 +234,150 @@ pub mod pqc {
     }
 }
 
+// ============================================================================
+// Hybrid Crypto (ed25519 + Dilithium)
+// ============================================================================
+
+/// Composite keys and signatures
+///
+/// Public key: `ed25519_pk (32) || dilithium5_pk`
+/// Signature:  `ed25519_sig (64) || dilithium5_detached_sig`
+///
+/// The classical half comes first at a fixed length, so nodes built without
+/// PQC support can still check it.
+#[cfg(feature = "classical-crypto")]
+pub mod hybrid {
+    use super::*;
+    
+    pub const CLASSICAL_PK_LEN: usize = 32;
+    pub const CLASSICAL_SIG_LEN: usize = 64;
+    
+    #[cfg(feature = "pqc-dilithium")]
+    pub struct HybridSigningKey {
+        classical: classical::ClassicalSigningKey,
+        pqc: pqc::PqcSigningKey,
+    }
+    
+    #[cfg(feature = "pqc-dilithium")]
+    impl HybridSigningKey {
+        pub fn new(classical: classical::ClassicalSigningKey, pqc: pqc::PqcSigningKey) -> Self {
+            Self { classical, pqc }
+        }
+        
+        pub fn generate() -> Self {
+            Self::new(
+                classical::ClassicalSigningKey::generate(),
+                pqc::PqcSigningKey::generate(),
+            )
+        }
+    }
+    
+    #[cfg(feature = "pqc-dilithium")]
+    impl SigningKeyTrait for HybridSigningKey {
+        fn sign(&self, message: &[u8]) -> Vec<u8> {
+            let mut signature = self.classical.sign(message);
+            signature.extend(self.pqc.sign_detached(message));
+            signature
+        }
+        
+        fn public_key(&self) -> Vec<u8> {
+            let mut public_key = self.classical.public_key();
+            public_key.extend(self.pqc.public_key());
+            public_key
+        }
+        
+        fn backend(&self) -> CryptoBackend {
+            CryptoBackend::Hybrid
+        }
+    }
+    
+    pub struct HybridVerifyingKey {
+        classical: classical::ClassicalVerifyingKey,
+        #[cfg(feature = "pqc-dilithium")]
+        pqc: pqc::PqcVerifyingKey,
+        pqc_bytes: Vec<u8>,
+        policy: HybridPolicy,
+    }
+    
+    impl HybridVerifyingKey {
+        pub fn from_bytes(bytes: &[u8], policy: HybridPolicy) -> Result<Self> {
+            if bytes.len() <= CLASSICAL_PK_LEN {
+                return Err(anyhow::anyhow!("Invalid hybrid public key length"));
+            }
+            let (classical_bytes, pqc_bytes) = bytes.split_at(CLASSICAL_PK_LEN);
+            
+            Ok(Self {
+                classical: classical::ClassicalVerifyingKey::from_bytes(
+                    classical_bytes.try_into().expect("split at 32"),
+                )?,
+                #[cfg(feature = "pqc-dilithium")]
+                pqc: pqc::PqcVerifyingKey::from_bytes(pqc_bytes)?,
+                pqc_bytes: pqc_bytes.to_vec(),
+                policy,
+            })
+        }
+        
+        pub fn policy(&self) -> HybridPolicy {
+            self.policy
+        }
+        
+        /// Check the Dilithium half; `None` when PQC support is not built in
+        #[allow(unused_variables)]
+        fn verify_pqc(&self, message: &[u8], signature: &[u8]) -> Result<Option<bool>> {
+            #[cfg(feature = "pqc-dilithium")]
+            {
+                Ok(Some(self.pqc.verify_detached(message, signature)?))
+            }
+            #[cfg(not(feature = "pqc-dilithium"))]
+            {
+                Ok(None)
+            }
+        }
+    }
+    
+    impl VerifyingKeyTrait for HybridVerifyingKey {
+        fn verify(&self, message: &[u8], signature: &[u8]) -> Result<bool> {
+            if signature.len() <= CLASSICAL_SIG_LEN {
+                return Err(anyhow::anyhow!("Invalid hybrid signature length"));
+            }
+            let (classical_sig, pqc_sig) = signature.split_at(CLASSICAL_SIG_LEN);
+            
+            let classical_ok = self.classical.verify(message, classical_sig)?;
+            match (self.policy, self.verify_pqc(message, pqc_sig)?) {
+                (HybridPolicy::Both, Some(pqc_ok)) => Ok(classical_ok && pqc_ok),
+                (HybridPolicy::Both, None) => Err(anyhow::anyhow!(
+                    "PQC not enabled; hybrid policy requires both signatures"
+                )),
+                (HybridPolicy::Either, pqc_ok) => Ok(classical_ok || pqc_ok == Some(true)),
+            }
+        }
+        
+        fn to_bytes(&self) -> Vec<u8> {
+            let mut bytes = self.classical.to_bytes();
+            bytes.extend_from_slice(&self.pqc_bytes);
+            bytes
+        }
+        
+        fn backend(&self) -> CryptoBackend {
+            CryptoBackend::Hybrid
+        }
+    }
+}
+
 // ============================================================================
 // Unified Crypto Interface
 // ============================================================================
 
-/// Unified signing key that works with both classical and PQC
+/// Unified signing key that works with classical, PQC and hybrid keys
 pub enum UnifiedSigningKey {
     #[cfg(feature = "classical-crypto")]
     Classical(classical::ClassicalSigningKey),
     
     #[cfg(feature = "pqc-dilithium")]
     PostQuantum(pqc::PqcSigningKey),
+    
+    #[cfg(all(feature = "classical-crypto", feature = "pqc-dilithium"))]
+    Hybrid(hybrid::HybridSigningKey),
 }
 
 impl UnifiedSigningKey {
@@ -246,6 +405,17 @@ impl UnifiedSigningKey {
                     panic!("PQC not enabled. Enable with --features pqc-dilithium")
                 }
             }
+            
+            CryptoBackend::Hybrid => {
+                #[cfg(all(feature = "classical-crypto", feature = "pqc-dilithium"))]
+                {
+                    Self::Hybrid(hybrid::HybridSigningKey::generate())
+                }
+                #[cfg(not(all(feature = "classical-crypto", feature = "pqc-dilithium")))]
+                {
+                    panic!("Hybrid crypto needs both classical-crypto and pqc-dilithium")
+                }
+            }
         }
     }
     
@@ -270,6 +440,9 @@ impl SigningKeyTrait for UnifiedSigningKey {
             
             #[cfg(feature = "pqc-dilithium")]
             Self::PostQuantum(key) => key.sign(message),
+            
+            #[cfg(all(feature = "classical-crypto", feature = "pqc-dilithium"))]
+            Self::Hybrid(key) => key.sign(message),
         }
     }
     
@@ -280,6 +453,9 @@ impl SigningKeyTrait for UnifiedSigningKey {
             
             #[cfg(feature = "pqc-dilithium")]
             Self::PostQuantum(key) => key.public_key(),
+            
+            #[cfg(all(feature = "classical-crypto", feature = "pqc-dilithium"))]
+            Self::Hybrid(key) => key.public_key(),
         }
     }
     
@@ -290,6 +466,9 @@ impl SigningKeyTrait for UnifiedSigningKey {
             
             #[cfg(feature = "pqc-dilithium")]
             Self::PostQuantum(key) => key.backend(),
+            
+            #[cfg(all(feature = "classical-crypto", feature = "pqc-dilithium"))]
+            Self::Hybrid(key) => key.backend(),
         }
     }
 }
@@ -301,11 +480,23 @@ pub enum UnifiedVerifyingKey {
     
     #[cfg(feature = "pqc-dilithium")]
     PostQuantum(pqc::PqcVerifyingKey),
+    
+    #[cfg(feature = "classical-crypto")]
+    Hybrid(hybrid::HybridVerifyingKey),
 }
 
 impl UnifiedVerifyingKey {
     /// Create from bytes with specified backend
     pub fn from_bytes(bytes: &[u8], backend: CryptoBackend) -> Result<Self> {
+        Self::from_bytes_with_policy(bytes, backend, HybridPolicy::default())
+    }
+    
+    /// Create from bytes, checking hybrid signatures under `policy`
+    pub fn from_bytes_with_policy(
+        bytes: &[u8],
+        backend: CryptoBackend,
+        policy: HybridPolicy,
+    ) -> Result<Self> {
         match backend {
             CryptoBackend::Classical => {
                 #[cfg(feature = "classical-crypto")]
@@ -330,6 +521,18 @@ impl UnifiedVerifyingKey {
                     Err(anyhow::anyhow!("PQC not enabled"))
                 }
             }
+            
+            CryptoBackend::Hybrid => {
+                #[cfg(feature = "classical-crypto")]
+                {
+                    Ok(Self::Hybrid(hybrid::HybridVerifyingKey::from_bytes(bytes, policy)?))
+                }
+                #[cfg(not(feature = "classical-crypto"))]
+                {
+                    let _ = policy;
+                    Err(anyhow::anyhow!("Classical crypto not enabled"))
+                }
+            }
         }
     }
 }
@@ -342,6 +545,9 @@ impl VerifyingKeyTrait for UnifiedVerifyingKey {
             
             #[cfg(feature = "pqc-dilithium")]
             Self::PostQuantum(key) => key.verify(message, signature),
+            
+            #[cfg(feature = "classical-crypto")]
+            Self::Hybrid(key) => key.verify(message, signature),
         }
     }
     
@@ -352,6 +558,9 @@ impl VerifyingKeyTrait for UnifiedVerifyingKey {
             
             #[cfg(feature = "pqc-dilithium")]
             Self::PostQuantum(key) => key.to_bytes(),
+            
+            #[cfg(feature = "classical-crypto")]
+            Self::Hybrid(key) => key.to_bytes(),
         }
     }
     
@@ -362,6 +571,9 @@ impl VerifyingKeyTrait for UnifiedVerifyingKey {
             
             #[cfg(feature = "pqc-dilithium")]
             Self::PostQuantum(key) => key.backend(),
+            
+            #[cfg(feature = "classical-crypto")]
+            Self::Hybrid(key) => key.backend(),
         }
     }
 }
@@ -395,4 +607,24 @@ mod tests {
         let verifying_key = UnifiedVerifyingKey::from_bytes(&public_key_bytes, CryptoBackend::PostQuantum).unwrap();
         assert!(verifying_key.verify(message, &signature).unwrap());
     }
+    
+    #[test]
+    #[cfg(all(feature = "classical-crypto", feature = "pqc-dilithium"))]
+    fn test_hybrid_compact_layout() {
+        use pqcrypto_dilithium::dilithium5;
+        
+        let key = UnifiedSigningKey::generate(CryptoBackend::Hybrid);
+        let message = b"Hello, hybrid SynapseNet!";
+        let signature = key.sign(message);
+        let public_key = key.public_key();
+        
+        // Detached Dilithium signature, no copy of the message
+        assert_eq!(signature.len(), 64 + dilithium5::signature_bytes());
+        assert_eq!(public_key.len(), 32 + dilithium5::public_key_bytes());
+        
+        let verifying_key = UnifiedVerifyingKey::from_bytes(&public_key, CryptoBackend::Hybrid).unwrap();
+        assert_eq!(verifying_key.to_bytes(), public_key);
+        assert!(verifying_key.verify(message, &signature).unwrap());
+        assert!(!verifying_key.verify(b"tampered", &signature).unwrap());
+    }
 }
@@ -0,0 +1,104 @@
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::signed::{sign_record, SignedRecord};
+
+/// What a curator does with a review task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CuratorAction {
+    /// Take the task so other curators leave it alone
+    Claim,
+    /// Release the held outcome to the requester
+    Approve,
+    /// Withhold the outcome
+    Reject,
+}
+
+impl CuratorAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Claim => "claim",
+            Self::Approve => "approve",
+            Self::Reject => "reject",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "claim" => Some(Self::Claim),
+            "approve" => Some(Self::Approve),
+            "reject" => Some(Self::Reject),
+            _ => None,
+        }
+    }
+}
+
+/// Curator decision on a review task, signed by the curator's key
+///
+/// The signature makes every entry in the curation audit log attributable
+/// to a registered curator key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CuratorDecision {
+    pub task_id: Uuid,
+    /// Curator public key (ed25519)
+    pub curator: [u8; 32],
+    pub action: CuratorAction,
+    /// Note for the requester and the audit log
+    pub note: Option<String>,
+    /// Unix timestamp (milliseconds)
+    pub ts_unix_ms: i64,
+    /// Curator signature over all fields above
+    pub sig: Vec<u8>,
+}
+
+impl CuratorDecision {
+    /// Create and sign a decision by the key's owner
+    pub fn new(
+        task_id: Uuid,
+        action: CuratorAction,
+        note: Option<String>,
+        signing_key: &SigningKey,
+    ) -> Self {
+        let mut decision = CuratorDecision {
+            task_id,
+            curator: signing_key.verifying_key().to_bytes(),
+            action,
+            note,
+            ts_unix_ms: chrono::Utc::now().timestamp_millis(),
+            sig: Vec::new(),
+        };
+
+        decision.sig = sign_record(&decision, signing_key);
+        decision
+    }
+}
+
+impl SignedRecord for CuratorDecision {
+    const DOMAIN: &'static [u8] = b"synapsenet/curator-decision/v2";
+
+    fn subject(&self) -> &[u8] {
+        self.task_id.as_bytes()
+    }
+
+    fn signer(&self) -> &[u8; 32] {
+        &self.curator
+    }
+
+    fn action(&self) -> &str {
+        self.action.as_str()
+    }
+
+    fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    fn ts_unix_ms(&self) -> i64 {
+        self.ts_unix_ms
+    }
+
+    fn sig(&self) -> &[u8] {
+        &self.sig
+    }
+}
@@ -267,7 +267,7 @@ mod tests {
         episode.add_grain(RetrievedGrain::new("g3", 0.7, "local"));
         
         assert_eq!(episode.retrieved_grains.len(), 3);
-        assert_eq!(episode.avg_grain_score(), 0.8);
+        assert!((episode.avg_grain_score() - 0.8).abs() < 1e-9);
         assert_eq!(episode.p2p_grain_count(), 1);
     }
 
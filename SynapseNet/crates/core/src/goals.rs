@@ -21,8 +21,39 @@ pub enum GoalStatus {
     Reflecting,
     /// Goal successfully completed
     Completed,
-    /// Goal failed or was cancelled
+    /// Goal failed
     Failed,
+    /// Goal was cancelled by a user
+    Cancelled,
+}
+
+impl GoalStatus {
+    /// Status name as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalStatus::Pending => "pending",
+            GoalStatus::Planning => "planning",
+            GoalStatus::Reasoning => "reasoning",
+            GoalStatus::Reflecting => "reflecting",
+            GoalStatus::Completed => "completed",
+            GoalStatus::Failed => "failed",
+            GoalStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// Parse status name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "pending" => Some(GoalStatus::Pending),
+            "planning" => Some(GoalStatus::Planning),
+            "reasoning" => Some(GoalStatus::Reasoning),
+            "reflecting" => Some(GoalStatus::Reflecting),
+            "completed" => Some(GoalStatus::Completed),
+            "failed" => Some(GoalStatus::Failed),
+            "cancelled" => Some(GoalStatus::Cancelled),
+            _ => None,
+        }
+    }
 }
 
 /// Priority level for goal processing
@@ -34,6 +65,18 @@ pub enum Priority {
     Critical = 3,
 }
 
+impl Priority {
+    /// Convert from stored integer value (unknown values map to Normal)
+    pub fn from_i32(value: i32) -> Self {
+        match value {
+            0 => Priority::Low,
+            2 => Priority::High,
+            3 => Priority::Critical,
+            _ => Priority::Normal,
+        }
+    }
+}
+
 /// A goal represents a high-level objective for the reasoning system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Goal {
@@ -92,9 +135,12 @@ impl Goal {
         self.updated_at = chrono::Utc::now().timestamp();
     }
 
-    /// Check if goal is terminal (completed or failed)
+    /// Check if goal is terminal (completed, failed or cancelled)
     pub fn is_terminal(&self) -> bool {
-        matches!(self.status, GoalStatus::Completed | GoalStatus::Failed)
+        matches!(
+            self.status,
+            GoalStatus::Completed | GoalStatus::Failed | GoalStatus::Cancelled
+        )
     }
 
     /// Check if goal is active (being processed)
@@ -218,7 +264,7 @@ mod tests {
         goal.set_status(GoalStatus::Planning);
         
         assert_eq!(goal.status, GoalStatus::Planning);
-        assert!(goal.updated_at > initial_time);
+        assert!(goal.updated_at >= initial_time);
     }
 
     #[test]
@@ -275,10 +321,30 @@ mod tests {
         failed.status = GoalStatus::Failed;
         assert!(failed.is_terminal());
         
+        let mut cancelled = Goal::new("Cancelled", "user");
+        cancelled.status = GoalStatus::Cancelled;
+        assert!(cancelled.is_terminal());
+        
         let pending = Goal::new("Pending", "user");
         assert!(!pending.is_terminal());
     }
 
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            GoalStatus::Pending,
+            GoalStatus::Planning,
+            GoalStatus::Reasoning,
+            GoalStatus::Reflecting,
+            GoalStatus::Completed,
+            GoalStatus::Failed,
+            GoalStatus::Cancelled,
+        ] {
+            assert_eq!(GoalStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(Priority::from_i32(Priority::High as i32), Priority::High);
+    }
+
     #[test]
     fn test_active_goals() {
         let mut planning = Goal::new("Planning", "user");
@@ -2,7 +2,10 @@
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
 
-use crate::crypto::{CryptoBackend, SigningKeyTrait, UnifiedSigningKey, UnifiedVerifyingKey, VerifyingKeyTrait};
+use crate::crypto::{
+    CryptoBackend, HybridPolicy, SigningKeyTrait, UnifiedSigningKey, UnifiedVerifyingKey,
+    VerifyingKeyTrait,
+};
 
 /// Grain - atomic unit of semantic knowledge
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,9 +110,16 @@ impl Grain {
         })
     }
 
-    /// Verify grain signature (classical ed25519)
+    /// Verify grain signature
+    ///
+    /// PQC and hybrid grains are checked through the unified keys, with
+    /// hybrid signatures requiring both halves.
     #[cfg(feature = "classical-crypto")]
     pub fn verify(&self) -> Result<bool, anyhow::Error> {
+        if self.meta.crypto_backend != CryptoBackend::Classical {
+            return self.verify_with_policy(HybridPolicy::default());
+        }
+
         let pk_bytes: [u8; 32] = self.meta.author_pk.as_slice().try_into()
             .map_err(|_| anyhow::anyhow!("Invalid public key length for ed25519"))?;
         let verifying_key = VerifyingKey::from_bytes(&pk_bytes)?;
@@ -129,6 +139,16 @@ impl Grain {
         verifying_key.verify(&self.id, &self.sig)
     }
 
+    /// Verify grain signature, checking hybrid signatures under `policy`
+    pub fn verify_with_policy(&self, policy: HybridPolicy) -> Result<bool, anyhow::Error> {
+        let verifying_key = UnifiedVerifyingKey::from_bytes_with_policy(
+            &self.meta.author_pk,
+            self.meta.crypto_backend,
+            policy,
+        )?;
+        verifying_key.verify(&self.id, &self.sig)
+    }
+
     /// Compute cosine similarity with another grain
     pub fn cosine_similarity(&self, other: &Grain) -> f32 {
         cosine_similarity(&self.vec, &other.vec)
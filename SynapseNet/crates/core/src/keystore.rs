@@ -0,0 +1,434 @@
+//! Node key storage
+//!
+//! Key files are either the legacy raw secret or a JSON envelope holding the
+//! secret encrypted with AES-256-GCM under an Argon2id password key. The
+//! public key is kept in the clear so a node can be identified without
+//! unlocking it, and is bound to the ciphertext as associated data.
+//!
+//! Secrets can be backed up as a 24-word BIP-39 phrase and restored from it.
+//! On Unix a [`KeyAgent`] lets a running daemon share its unlocked key with
+//! local commands so the password is entered once at startup.
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, Context, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// Current encrypted key file version
+pub const KEY_FILE_VERSION: u32 = 1;
+
+const KDF_ARGON2ID: &str = "argon2id";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Argon2id cost parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB
+    pub m_cost: u32,
+    /// Number of passes
+    pub t_cost: u32,
+    /// Degree of parallelism
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        // OWASP recommended minimum for Argon2id
+        Self {
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    pub(crate) fn derive_key(&self, password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(|e| anyhow!("Invalid KDF parameters: {}", e))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), salt, key.as_mut())
+            .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
+        Ok(key)
+    }
+}
+
+/// Password-encrypted secret key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedKey {
+    pub version: u32,
+    pub kdf: String,
+    pub kdf_params: KdfParams,
+    /// Hex-encoded fields
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+    pub public_key: String,
+}
+
+impl EncryptedKey {
+    /// Encrypt `secret` under `password`
+    pub fn seal(secret: &[u8], public_key: &[u8], password: &str, params: KdfParams) -> Result<Self> {
+        if password.is_empty() {
+            return Err(anyhow!("Key password must not be empty"));
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let key = params.derive_key(password, &salt)?;
+        let cipher = Aes256Gcm::new_from_slice(key.as_ref()).expect("32-byte key");
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: secret,
+                    aad: public_key,
+                },
+            )
+            .map_err(|_| anyhow!("Key encryption failed"))?;
+
+        Ok(Self {
+            version: KEY_FILE_VERSION,
+            kdf: KDF_ARGON2ID.to_string(),
+            kdf_params: params,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+            public_key: hex::encode(public_key),
+        })
+    }
+
+    /// Decrypt the secret; fails on a wrong password or tampered file
+    pub fn open(&self, password: &str) -> Result<Zeroizing<Vec<u8>>> {
+        if self.version != KEY_FILE_VERSION || self.kdf != KDF_ARGON2ID {
+            return Err(anyhow!(
+                "Unsupported key file (version {}, kdf {})",
+                self.version,
+                self.kdf
+            ));
+        }
+
+        let salt = hex::decode(&self.salt).context("Invalid key file salt")?;
+        let nonce = hex::decode(&self.nonce).context("Invalid key file nonce")?;
+        let ciphertext = hex::decode(&self.ciphertext).context("Invalid key file ciphertext")?;
+        let public_key = self.public_key_bytes()?;
+        if nonce.len() != NONCE_LEN {
+            return Err(anyhow!("Invalid key file nonce"));
+        }
+
+        let key = self.kdf_params.derive_key(password, &salt)?;
+        let cipher = Aes256Gcm::new_from_slice(key.as_ref()).expect("32-byte key");
+        cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &public_key,
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| anyhow!("Wrong key password or corrupted key file"))
+    }
+
+    pub fn public_key_bytes(&self) -> Result<Vec<u8>> {
+        hex::decode(&self.public_key).context("Invalid key file public key")
+    }
+}
+
+/// Contents of a node key file
+#[derive(Debug, Clone)]
+pub enum KeyFile {
+    /// Legacy unencrypted secret
+    Plaintext(Zeroizing<Vec<u8>>),
+    Encrypted(EncryptedKey),
+}
+
+impl KeyFile {
+    pub fn read(path: &Path) -> Result<Self> {
+        let data = Zeroizing::new(
+            std::fs::read(path).with_context(|| format!("Failed to read key file {:?}", path))?,
+        );
+
+        if data.first() == Some(&b'{') {
+            let key = serde_json::from_slice(&data)
+                .with_context(|| format!("Invalid encrypted key file {:?}", path))?;
+            return Ok(KeyFile::Encrypted(key));
+        }
+        Ok(KeyFile::Plaintext(data))
+    }
+
+    /// Write the key file readable only by the owner
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let data = match self {
+            KeyFile::Plaintext(secret) => Zeroizing::new(secret.to_vec()),
+            KeyFile::Encrypted(key) => Zeroizing::new(serde_json::to_vec_pretty(key)?),
+        };
+
+        let tmp = path.with_extension("tmp");
+        write_private(&tmp, &data)?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Failed to write key file {:?}", path))?;
+        Ok(())
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, KeyFile::Encrypted(_))
+    }
+
+    /// Secret key bytes; `password` is only needed for encrypted files
+    pub fn unlock(&self, password: Option<&str>) -> Result<Zeroizing<Vec<u8>>> {
+        match self {
+            KeyFile::Plaintext(secret) => Ok(secret.clone()),
+            KeyFile::Encrypted(key) => {
+                let password = password.ok_or_else(|| anyhow!("Key file is encrypted"))?;
+                key.open(password)
+            }
+        }
+    }
+}
+
+fn write_private(path: &Path, data: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to create {:?}", path))?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// 24-word BIP-39 phrase encoding a 32-byte secret
+pub fn recovery_phrase(secret: &[u8]) -> Result<Zeroizing<String>> {
+    if secret.len() != 32 {
+        return Err(anyhow!(
+            "Recovery phrases cover 32-byte keys, got {} bytes",
+            secret.len()
+        ));
+    }
+    let mnemonic = bip39::Mnemonic::from_entropy(secret)
+        .map_err(|e| anyhow!("Failed to encode recovery phrase: {}", e))?;
+    Ok(Zeroizing::new(mnemonic.to_string()))
+}
+
+/// Secret encoded by a recovery phrase, checking its checksum
+pub fn secret_from_phrase(phrase: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let normalized = phrase
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mnemonic = bip39::Mnemonic::parse_normalized(&normalized)
+        .map_err(|e| anyhow!("Invalid recovery phrase: {}", e))?;
+
+    let secret = Zeroizing::new(mnemonic.to_entropy());
+    if secret.len() != 32 {
+        return Err(anyhow!("Recovery phrase must have 24 words"));
+    }
+    Ok(secret)
+}
+
+#[cfg(unix)]
+pub use agent::{request_key, KeyAgent};
+
+#[cfg(unix)]
+mod agent {
+    use anyhow::{anyhow, Context, Result};
+    use std::io::{Read, Write};
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tracing::{debug, info, warn};
+    use zeroize::Zeroizing;
+
+    /// Serves an unlocked secret to processes of the same user
+    ///
+    /// The socket lives in a directory only the owner can enter. The agent
+    /// stops and removes its socket when dropped.
+    pub struct KeyAgent {
+        socket_path: PathBuf,
+        stop: Arc<AtomicBool>,
+    }
+
+    impl KeyAgent {
+        pub fn spawn(socket_path: &Path, secret: Zeroizing<Vec<u8>>) -> Result<Self> {
+            let dir = socket_path
+                .parent()
+                .ok_or_else(|| anyhow!("Invalid agent socket path {:?}", socket_path))?;
+            std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(dir)
+                .with_context(|| format!("Failed to create agent directory {:?}", dir))?;
+            std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+
+            // A stale socket from a crashed daemon would block bind
+            if socket_path.exists() {
+                if UnixStream::connect(socket_path).is_ok() {
+                    return Err(anyhow!("A key agent is already running at {:?}", socket_path));
+                }
+                std::fs::remove_file(socket_path)?;
+            }
+
+            let listener = UnixListener::bind(socket_path)
+                .with_context(|| format!("Failed to bind agent socket {:?}", socket_path))?;
+            std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+
+            let stop = Arc::new(AtomicBool::new(false));
+            let stop_flag = stop.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop_flag.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    match stream {
+                        Ok(mut stream) => {
+                            if let Err(e) = stream.write_all(&secret) {
+                                debug!("Key agent client went away: {}", e);
+                            }
+                        }
+                        Err(e) => warn!("Key agent accept failed: {}", e),
+                    }
+                }
+            });
+
+            info!("Key agent listening on {:?}", socket_path);
+            Ok(Self {
+                socket_path: socket_path.to_path_buf(),
+                stop,
+            })
+        }
+    }
+
+    impl Drop for KeyAgent {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::SeqCst);
+            // Wake the accept loop so it sees the stop flag
+            let _ = UnixStream::connect(&self.socket_path);
+            let _ = std::fs::remove_file(&self.socket_path);
+        }
+    }
+
+    /// Secret held by a running agent, `None` if no agent is listening
+    pub fn request_key(socket_path: &Path) -> Result<Option<Zeroizing<Vec<u8>>>> {
+        let mut stream = match UnixStream::connect(socket_path) {
+            Ok(stream) => stream,
+            Err(_) => return Ok(None),
+        };
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+        let mut secret = Zeroizing::new(Vec::new());
+        stream
+            .read_to_end(&mut secret)
+            .context("Failed to read key from agent")?;
+        if secret.is_empty() {
+            return Err(anyhow!("Key agent returned no key"));
+        }
+        Ok(Some(secret))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fast_params() -> KdfParams {
+        KdfParams {
+            m_cost: 64,
+            t_cost: 1,
+            p_cost: 1,
+        }
+    }
+
+    #[test]
+    fn test_encrypted_key_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.key");
+        let secret = [7u8; 32];
+
+        let sealed = EncryptedKey::seal(&secret, &[1u8; 32], "hunter2", fast_params()).unwrap();
+        KeyFile::Encrypted(sealed).write(&path).unwrap();
+
+        let file = KeyFile::read(&path).unwrap();
+        assert!(file.is_encrypted());
+        assert_eq!(file.unlock(Some("hunter2")).unwrap().as_slice(), &secret);
+        assert!(file.unlock(Some("wrong")).is_err());
+        assert!(file.unlock(None).is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_tampered_public_key_rejected() {
+        let mut sealed = EncryptedKey::seal(&[7u8; 32], &[1u8; 32], "pw", fast_params()).unwrap();
+        sealed.public_key = hex::encode([2u8; 32]);
+        assert!(sealed.open("pw").is_err());
+    }
+
+    #[test]
+    fn test_legacy_plaintext_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.key");
+        std::fs::write(&path, [9u8; 32]).unwrap();
+
+        let file = KeyFile::read(&path).unwrap();
+        assert!(!file.is_encrypted());
+        assert_eq!(file.unlock(None).unwrap().as_slice(), &[9u8; 32]);
+    }
+
+    #[test]
+    fn test_recovery_phrase_roundtrip() {
+        let secret: Vec<u8> = (0..32).collect();
+        let phrase = recovery_phrase(&secret).unwrap();
+        assert_eq!(phrase.split_whitespace().count(), 24);
+
+        let restored = secret_from_phrase(&phrase.to_uppercase()).unwrap();
+        assert_eq!(restored.as_slice(), secret.as_slice());
+
+        // Swapping two words breaks the checksum
+        let mut words: Vec<_> = phrase.split_whitespace().collect();
+        words.swap(0, 1);
+        assert!(secret_from_phrase(&words.join(" ")).is_err());
+        assert!(recovery_phrase(&[0u8; 16]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_key_agent() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("agent").join("agent.sock");
+        assert!(request_key(&socket).unwrap().is_none());
+
+        let agent = KeyAgent::spawn(&socket, Zeroizing::new(vec![3u8; 32])).unwrap();
+        assert!(KeyAgent::spawn(&socket, Zeroizing::new(vec![4u8; 32])).is_err());
+        assert_eq!(request_key(&socket).unwrap().unwrap().as_slice(), &[3u8; 32]);
+
+        drop(agent);
+        assert!(request_key(&socket).unwrap().is_none());
+    }
+}
@@ -2,35 +2,56 @@
 
 pub mod config;
 pub mod crypto;
+pub mod curation;
+pub mod episodes;
+pub mod episodes_v2;
 pub mod error;
+pub mod goals;
 pub mod grain;
 pub mod graph;
+pub mod keystore;
 pub mod link;
 pub mod logging;
 pub mod metrics;
+pub mod moderation;
 pub mod poe;
+pub mod private;
 pub mod recovery;
+pub mod signed;
+pub mod succession;
+pub mod transfer;
 
-#[cfg(any(target_os = "ios", target_os = "android"))]
+#[cfg(feature = "capsule")]
 pub mod mobile;
 
-pub use config::{Config, ModelConfig};
+pub use config::{Config, CryptoConfig, EconomyConfig, LlmConfig, ModelConfig, ModerationConfig};
 pub use crypto::{
-    CryptoBackend, SigningKeyTrait, UnifiedSigningKey, UnifiedVerifyingKey, VerifyingKeyTrait,
+    CryptoBackend, HybridPolicy, SigningKeyTrait, UnifiedSigningKey, UnifiedVerifyingKey,
+    VerifyingKeyTrait,
 };
+pub use curation::{CuratorAction, CuratorDecision};
 pub use error::{
     BatchError, EmbeddingError, ErrorContext, NetworkError, StorageError, SynapseNetError,
     WithContext,
 };
+pub use episodes::{Episode, RetrievedGrain};
+pub use episodes_v2::{EpisodeAction, EpisodeTrace, EpisodeV2};
+pub use goals::{Goal, GoalQueue, GoalStatus, Priority};
 pub use grain::{Grain, GrainMeta};
 pub use graph::Graph;
+pub use keystore::{EncryptedKey, KdfParams, KeyFile};
 pub use link::Link;
 pub use logging::{
     init_logging, LogConfig, LogLevel, LogRotationConfig, LogRotationManager, PerformanceLogger,
 };
 pub use metrics::{MetricsTimer, NodeMetrics};
+pub use moderation::{FlagAction, FlagReason, GrainFlag};
 pub use poe::ProofOfEmergence;
+pub use private::{GroupKey, GroupKeyGrant, PrivateGrain, RecipientKey, RecipientSecret};
 pub use recovery::{
     retry_with_backoff, CircuitBreaker, CircuitState, GpuFallbackStrategy, ModelFallbackConfig,
     RetryConfig,
 };
+pub use signed::SignedRecord;
+pub use succession::KeySuccession;
+pub use transfer::NgtTransfer;
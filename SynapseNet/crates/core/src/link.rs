@@ -1,6 +1,8 @@
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
 
+use crate::crypto::{SigningKeyTrait, UnifiedSigningKey};
+
 /// Semantic link between grains
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Link {
@@ -45,6 +47,33 @@ impl Link {
         })
     }
 
+    /// Create new link signed with a unified (classical, PQC or hybrid) key
+    pub fn new_with_unified_key(
+        from: [u8; 32],
+        to: [u8; 32],
+        weight: f32,
+        rationale: Option<String>,
+        signing_key: &UnifiedSigningKey,
+    ) -> Result<Self, anyhow::Error> {
+        let weight = weight.clamp(0.0, 1.0);
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&from);
+        data.extend_from_slice(&to);
+        data.extend_from_slice(&weight.to_le_bytes());
+        if let Some(ref r) = rationale {
+            data.extend_from_slice(r.as_bytes());
+        }
+
+        Ok(Link {
+            from,
+            to,
+            weight,
+            rationale,
+            sig: signing_key.sign(&data),
+        })
+    }
+
     /// Verify link signature
     pub fn verify(&self, author_pk: &[u8; 32]) -> Result<bool, anyhow::Error> {
         let verifying_key = VerifyingKey::from_bytes(author_pk)?;
@@ -58,7 +58,7 @@ impl HardwareCapabilities {
             supports_coreml: true,
             supports_nnapi: false,
             ram_mb: 4096, // Placeholder
-            cpu_cores: num_cpus::get(),
+            cpu_cores: std::thread::available_parallelism().map_or(1, |n| n.get()),
             device_model: "iPhone".to_string(), // TODO: Get actual model
             os_version: "iOS 14+".to_string(), // TODO: Get actual version
         }
@@ -73,7 +73,7 @@ impl HardwareCapabilities {
             supports_coreml: false,
             supports_nnapi: true, // Android 8.1+
             ram_mb: 4096, // Placeholder
-            cpu_cores: num_cpus::get(),
+            cpu_cores: std::thread::available_parallelism().map_or(1, |n| n.get()),
             device_model: "Android Device".to_string(), // TODO: Get actual model
             os_version: "Android 8+".to_string(), // TODO: Get actual version
         }
@@ -106,7 +106,7 @@ impl Default for HardwareCapabilities {
             supports_coreml: false,
             supports_nnapi: false,
             ram_mb: 2048,
-            cpu_cores: num_cpus::get(),
+            cpu_cores: std::thread::available_parallelism().map_or(1, |n| n.get()),
             device_model: "Unknown".to_string(),
             os_version: "Unknown".to_string(),
         }
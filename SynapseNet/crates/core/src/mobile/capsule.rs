@@ -2,39 +2,55 @@
 
 use super::encryption::{Encryptor, EncryptedBlob};
 use super::keystore::KeyStore;
-use crate::grain::Grain;
-use anyhow::{anyhow, Result};
+use crate::grain::{cosine_similarity, Grain};
+use crate::keystore::{secret_from_phrase, KdfParams};
+use anyhow::{anyhow, Context, Result};
+use rand::{rngs::OsRng, RngCore};
 use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
 const KEY_ID: &str = "synapsenet-capsule-key";
 
+/// Header of exported capsule files
+const BACKUP_MAGIC: &[u8; 8] = b"SYNCAPS1";
+
+/// Exported capsule, encrypted under a key derived from the recovery phrase
+#[derive(Serialize, Deserialize)]
+struct CapsuleBackup {
+    kdf_params: KdfParams,
+    salt: [u8; 16],
+    nonce: [u8; 12],
+    ciphertext: Vec<u8>,
+}
+
 /// Encrypted memory capsule
 pub struct MemoryCapsule {
     db: Connection,
     encryptor: Encryptor,
     keystore: Box<dyn KeyStore>,
+    /// Decrypted embeddings for `search_local`, never written to disk
+    index: HashMap<[u8; 32], Vec<f32>>,
 }
 
 impl MemoryCapsule {
     /// Create a new memory capsule
-    pub fn new<P: AsRef<Path>>(db_path: P, mut keystore: Box<dyn KeyStore>) -> Result<Self> {
-        // Try to retrieve existing key
-        let key = match keystore.retrieve_key(KEY_ID) {
-            Ok(key_bytes) => {
-                if key_bytes.len() != 32 {
-                    return Err(anyhow!("Invalid key length"));
-                }
-                let mut key = [0u8; 32];
-                key.copy_from_slice(&key_bytes);
-                key
-            }
-            Err(_) => {
-                // Generate new key
-                let key = Encryptor::generate_key()?;
-                keystore.store_key(KEY_ID, &key)?;
-                key
+    pub fn new<P: AsRef<Path>>(db_path: P, keystore: Box<dyn KeyStore>) -> Result<Self> {
+        // Only generate a key for a fresh capsule; a failing unlock must not
+        // replace the key of existing data
+        let key = if keystore.key_exists(KEY_ID) {
+            let key_bytes = keystore.retrieve_key(KEY_ID)?;
+            if key_bytes.len() != 32 {
+                return Err(anyhow!("Invalid key length"));
             }
+            let mut key = [0u8; 32];
+            key.copy_from_slice(&key_bytes);
+            key
+        } else {
+            let key = Encryptor::generate_key()?;
+            keystore.store_key(KEY_ID, &key)?;
+            key
         };
         
         let encryptor = Encryptor::new(key);
@@ -43,15 +59,22 @@ impl MemoryCapsule {
         // Initialize database schema
         Self::init_schema(&db)?;
         
-        Ok(Self {
+        let mut capsule = Self {
             db,
             encryptor,
             keystore,
-        })
+            index: HashMap::new(),
+        };
+        capsule.rebuild_index()?;
+        
+        Ok(capsule)
     }
     
     /// Initialize database schema
     fn init_schema(db: &Connection) -> Result<()> {
+        // Zero deleted content instead of leaving it in free pages
+        db.pragma_update(None, "secure_delete", true)?;
+        
         db.execute(
             "CREATE TABLE IF NOT EXISTS encrypted_grains (
                 id BLOB PRIMARY KEY,
@@ -89,6 +112,7 @@ impl MemoryCapsule {
             ],
         )?;
         
+        self.index.insert(grain.id, grain.vec.clone());
         Ok(())
     }
     
@@ -165,41 +189,107 @@ impl MemoryCapsule {
             "DELETE FROM encrypted_grains WHERE id = ?1",
             params![&id[..]],
         )?;
+        self.index.remove(id);
         Ok(())
     }
     
-    /// Search local grains (placeholder - needs HNSW index)
-    pub fn search_local(&self, _query: &[f32], _k: usize) -> Result<Vec<Grain>> {
-        // TODO: Implement local search with HNSW index
-        // For now, return all grains
-        self.get_all_grains()
+    /// Decrypt all grains and rebuild the in-memory search index
+    pub fn rebuild_index(&mut self) -> Result<()> {
+        self.index = self
+            .get_all_grains()?
+            .into_iter()
+            .map(|grain| (grain.id, grain.vec))
+            .collect();
+        Ok(())
     }
     
-    /// Export encrypted capsule
-    pub fn export_encrypted<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        // TODO: Implement export
-        // 1. Create backup file
-        // 2. Include encrypted grains
-        // 3. Include metadata
-        tracing::info!("Exporting capsule to: {:?}", path.as_ref());
-        Ok(())
+    /// Search local grains by cosine similarity
+    pub fn search_local(&self, query: &[f32], k: usize) -> Result<Vec<Grain>> {
+        let mut scored: Vec<(&[u8; 32], f32)> = self
+            .index
+            .iter()
+            .map(|(id, vec)| (id, cosine_similarity(query, vec)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        
+        let mut grains = Vec::with_capacity(k.min(scored.len()));
+        for (id, _) in scored.into_iter().take(k) {
+            if let Some(grain) = self.get_grain(id)? {
+                grains.push(grain);
+            }
+        }
+        Ok(grains)
     }
     
-    /// Import encrypted capsule
-    pub fn import_encrypted<P: AsRef<Path>>(&mut self, path: P, _recovery_phrase: &str) -> Result<()> {
-        // TODO: Implement import
-        // 1. Read backup file
-        // 2. Verify integrity
-        // 3. Decrypt with recovery phrase
-        // 4. Import grains
-        tracing::info!("Importing capsule from: {:?}", path.as_ref());
+    /// Export all grains to `path`, encrypted under `recovery_phrase`
+    pub fn export_encrypted<P: AsRef<Path>>(&self, path: P, recovery_phrase: &str) -> Result<()> {
+        let grains = self.get_all_grains()?;
+        
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let kdf_params = KdfParams::default();
+        let key = kdf_params.derive_key(&normalize_phrase(recovery_phrase)?, &salt)?;
+        let encrypted = Encryptor::new(*key).encrypt(&bincode::serialize(&grains)?)?;
+        
+        let backup = CapsuleBackup {
+            kdf_params,
+            salt,
+            nonce: encrypted.nonce,
+            ciphertext: encrypted.ciphertext,
+        };
+        let mut data = BACKUP_MAGIC.to_vec();
+        data.extend(bincode::serialize(&backup)?);
+        std::fs::write(path.as_ref(), data)
+            .with_context(|| format!("Failed to write capsule export {:?}", path.as_ref()))?;
+        
+        tracing::info!("Exported {} grains to {:?}", grains.len(), path.as_ref());
         Ok(())
     }
     
+    /// Import grains exported with the same recovery phrase
+    ///
+    /// Nothing is imported unless the file decrypts and every grain
+    /// signature verifies. Returns the number of grains imported.
+    pub fn import_encrypted<P: AsRef<Path>>(&mut self, path: P, recovery_phrase: &str) -> Result<usize> {
+        let data = std::fs::read(path.as_ref())
+            .with_context(|| format!("Failed to read capsule export {:?}", path.as_ref()))?;
+        let body = data
+            .strip_prefix(BACKUP_MAGIC.as_slice())
+            .ok_or_else(|| anyhow!("Not a SynapseNet capsule export"))?;
+        let backup: CapsuleBackup = bincode::deserialize(body)?;
+        
+        let key = backup
+            .kdf_params
+            .derive_key(&normalize_phrase(recovery_phrase)?, &backup.salt)?;
+        let plaintext = Encryptor::new(*key)
+            .decrypt(&EncryptedBlob {
+                ciphertext: backup.ciphertext,
+                nonce: backup.nonce,
+            })
+            .map_err(|_| anyhow!("Wrong recovery phrase or corrupted capsule export"))?;
+        let grains: Vec<Grain> = bincode::deserialize(&plaintext)?;
+        
+        for grain in &grains {
+            if !grain.verify_with_backend(grain.meta.crypto_backend)? {
+                return Err(anyhow!(
+                    "Capsule export contains a grain with an invalid signature: {}",
+                    hex::encode(grain.id)
+                ));
+            }
+        }
+        for grain in &grains {
+            self.insert_grain(grain)?;
+        }
+        
+        tracing::info!("Imported {} grains from {:?}", grains.len(), path.as_ref());
+        Ok(grains.len())
+    }
+    
     /// Securely delete all data
     pub fn secure_delete(&mut self) -> Result<()> {
         // Delete all grains
         self.db.execute("DELETE FROM encrypted_grains", [])?;
+        self.index.clear();
         
         // Delete encryption key
         self.keystore.delete_key(KEY_ID)?;
@@ -209,18 +299,37 @@ impl MemoryCapsule {
     }
 }
 
+/// Recovery phrases match regardless of case and spacing
+///
+/// The phrase must be a valid BIP-39 recovery phrase, so a mistyped word is
+/// caught before it seals a backup nobody can open.
+fn normalize_phrase(phrase: &str) -> Result<String> {
+    secret_from_phrase(phrase)?;
+    let words: Vec<String> = phrase.split_whitespace().map(str::to_lowercase).collect();
+    Ok(words.join(" "))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::crypto::UnifiedSigningKey;
+    use crate::crypto::{SigningKeyTrait, UnifiedSigningKey};
     use crate::grain::GrainMeta;
+    use crate::mobile::keystore::{FileKeyStore, MockKeyStore};
     use crate::CryptoBackend;
     use tempfile::TempDir;
     
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                          abandon abandon abandon abandon abandon abandon abandon abandon \
+                          abandon abandon abandon abandon abandon abandon abandon art";
+    
     fn create_test_grain() -> Grain {
-        let vec = vec![0.1f32; 384];
+        grain_with_vec(vec![0.1f32; 384])
+    }
+    
+    fn grain_with_vec(vec: Vec<f32>) -> Grain {
+        let signing_key = UnifiedSigningKey::generate(CryptoBackend::Classical);
         let meta = GrainMeta {
-            author_pk: [0u8; 32],
+            author_pk: signing_key.public_key(),
             crypto_backend: CryptoBackend::Classical,
             ts_unix_ms: chrono::Utc::now().timestamp_millis(),
             tags: vec!["test".to_string()],
@@ -229,11 +338,10 @@ mod tests {
             title: Some("Test Grain".to_string()),
             summary: None,
             embedding_model: Some("test-model".to_string()),
-            embedding_dimensions: Some(384),
+            embedding_dimensions: Some(vec.len()),
         };
         
-        let signing_key = UnifiedSigningKey::generate_classical();
-        Grain::new(vec, meta, &signing_key).unwrap()
+        Grain::new_with_unified_key(vec, meta, &signing_key).unwrap()
     }
     
     #[test]
@@ -302,4 +410,98 @@ mod tests {
         capsule.delete_grain(&grain_id).unwrap();
         assert_eq!(capsule.count_grains().unwrap(), 0);
     }
+    
+    #[test]
+    fn test_search_local_ranks_by_similarity() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut capsule =
+            MemoryCapsule::new(temp_dir.path().join("test.db"), Box::new(MockKeyStore::new())).unwrap();
+        
+        let x = grain_with_vec(vec![1.0, 0.0, 0.0]);
+        let y = grain_with_vec(vec![0.0, 1.0, 0.0]);
+        let xy = grain_with_vec(vec![0.7, 0.7, 0.0]);
+        for grain in [&x, &y, &xy] {
+            capsule.insert_grain(grain).unwrap();
+        }
+        
+        let results = capsule.search_local(&[1.0, 0.1, 0.0], 2).unwrap();
+        let ids: Vec<_> = results.iter().map(|g| g.id).collect();
+        assert_eq!(ids, vec![x.id, xy.id]);
+        
+        capsule.delete_grain(&x.id).unwrap();
+        assert_eq!(capsule.search_local(&[1.0, 0.1, 0.0], 1).unwrap()[0].id, xy.id);
+    }
+    
+    #[test]
+    fn test_index_rebuilt_after_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let keys_dir = temp_dir.path().join("keys");
+        let grain = grain_with_vec(vec![0.0, 0.0, 1.0]);
+        
+        {
+            let keystore = FileKeyStore::new(&keys_dir).unwrap().with_password("pw");
+            let mut capsule = MemoryCapsule::new(&db_path, Box::new(keystore)).unwrap();
+            capsule.insert_grain(&grain).unwrap();
+        }
+        
+        // A wrong password must not replace the existing key
+        let wrong = FileKeyStore::new(&keys_dir).unwrap().with_password("nope");
+        assert!(MemoryCapsule::new(&db_path, Box::new(wrong)).is_err());
+        
+        let keystore = FileKeyStore::new(&keys_dir).unwrap().with_password("pw");
+        let capsule = MemoryCapsule::new(&db_path, Box::new(keystore)).unwrap();
+        let results = capsule.search_local(&[0.0, 0.0, 1.0], 5).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, grain.id);
+    }
+    
+    #[test]
+    fn test_export_import_with_recovery_phrase() {
+        let temp_dir = TempDir::new().unwrap();
+        let export_path = temp_dir.path().join("capsule.syncaps");
+        
+        let mut source =
+            MemoryCapsule::new(temp_dir.path().join("a.db"), Box::new(MockKeyStore::new())).unwrap();
+        let grain = grain_with_vec(vec![0.5, 0.5, 0.0]);
+        source.insert_grain(&grain).unwrap();
+        source.insert_grain(&create_test_grain()).unwrap();
+
+        // A mistyped phrase fails its checksum instead of sealing the export
+        let typo = PHRASE.replace(" art", " arm");
+        assert!(source.export_encrypted(&export_path, &typo).is_err());
+        assert!(!export_path.exists());
+        source.export_encrypted(&export_path, PHRASE).unwrap();
+        
+        let exported = std::fs::read(&export_path).unwrap();
+        assert!(exported.starts_with(BACKUP_MAGIC));
+        
+        let mut target =
+            MemoryCapsule::new(temp_dir.path().join("b.db"), Box::new(MockKeyStore::new())).unwrap();
+        let other = crate::keystore::recovery_phrase(&[1u8; 32]).unwrap();
+        assert!(target.import_encrypted(&export_path, &other).is_err());
+        assert!(target.import_encrypted(&export_path, "wrong phrase").is_err());
+        assert_eq!(target.count_grains().unwrap(), 0);
+        
+        // Case and spacing of the phrase do not matter
+        let phrase = format!("  {}", PHRASE.to_uppercase());
+        assert_eq!(target.import_encrypted(&export_path, &phrase).unwrap(), 2);
+        assert_eq!(target.count_grains().unwrap(), 2);
+        assert_eq!(target.search_local(&[1.0, 1.0, 0.0], 1).unwrap()[0].id, grain.id);
+    }
+    
+    #[test]
+    fn test_secure_delete_removes_key_and_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let keys_dir = temp_dir.path().join("keys");
+        let keystore = FileKeyStore::new(&keys_dir).unwrap();
+        let mut capsule = MemoryCapsule::new(temp_dir.path().join("test.db"), Box::new(keystore)).unwrap();
+        
+        capsule.insert_grain(&create_test_grain()).unwrap();
+        capsule.secure_delete().unwrap();
+        
+        assert_eq!(capsule.count_grains().unwrap(), 0);
+        assert!(capsule.search_local(&[0.1; 384], 5).unwrap().is_empty());
+        assert!(!FileKeyStore::new(&keys_dir).unwrap().key_exists(KEY_ID));
+    }
 }
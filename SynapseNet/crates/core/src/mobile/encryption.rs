@@ -81,13 +81,12 @@ impl Encryptor {
     /// Derive key from password using HKDF
     pub fn derive_key_from_password(password: &str, salt: &[u8]) -> Result<[u8; KEY_LEN]> {
         use ring::hkdf;
-        use ring::hmac;
         
         let salt = hkdf::Salt::new(hkdf::HKDF_SHA256, salt);
         let prk = salt.extract(password.as_bytes());
         
-        let info = b"synapsenet-mobile-capsule";
-        let okm = prk.expand(&[info], &AES_256_GCM)
+        let info: &[&[u8]] = &[b"synapsenet-mobile-capsule"];
+        let okm = prk.expand(info, &AES_256_GCM)
             .map_err(|_| anyhow!("Key derivation failed"))?;
         
         let mut key = [0u8; KEY_LEN];
@@ -1,6 +1,9 @@
 //! Secure key storage abstraction for mobile platforms
 
-use anyhow::Result;
+use crate::keystore::{EncryptedKey, KdfParams, KeyFile};
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
 
 /// Trait for platform-specific secure key storage
 pub trait KeyStore: Send + Sync {
@@ -148,10 +151,102 @@ pub fn create_keystore() -> Box<dyn KeyStore> {
     }
 }
 
+/// File-based keystore for desktop and server nodes
+///
+/// Each key is written to `<dir>/<key_id>.key` with owner-only permissions,
+/// encrypted with the node key file format when a password is set.
+pub struct FileKeyStore {
+    dir: PathBuf,
+    password: Option<Zeroizing<String>>,
+    biometric_required: bool,
+}
+
+impl FileKeyStore {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            password: None,
+            biometric_required: false,
+        })
+    }
+
+    /// Encrypt stored keys under `password`
+    pub fn with_password(mut self, password: &str) -> Self {
+        self.password = Some(Zeroizing::new(password.to_string()));
+        self
+    }
+
+    fn key_path(&self, key_id: &str) -> Result<PathBuf> {
+        let valid = !key_id.is_empty()
+            && key_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(anyhow!("Invalid key id: {}", key_id));
+        }
+        Ok(self.dir.join(format!("{}.key", key_id)))
+    }
+}
+
+impl KeyStore for FileKeyStore {
+    fn store_key(&self, key_id: &str, key: &[u8]) -> Result<()> {
+        let key_file = match &self.password {
+            // The key id is bound as associated data so files cannot be swapped
+            Some(password) => KeyFile::Encrypted(EncryptedKey::seal(
+                key,
+                key_id.as_bytes(),
+                password,
+                KdfParams::default(),
+            )?),
+            None => KeyFile::Plaintext(Zeroizing::new(key.to_vec())),
+        };
+        key_file.write(&self.key_path(key_id)?)
+    }
+
+    fn retrieve_key(&self, key_id: &str) -> Result<Vec<u8>> {
+        let path = self.key_path(key_id)?;
+        if !path.exists() {
+            return Err(anyhow!("Key not found: {}", key_id));
+        }
+        let key_file = KeyFile::read(&path)?;
+        if let KeyFile::Encrypted(encrypted) = &key_file {
+            if encrypted.public_key_bytes()? != key_id.as_bytes() {
+                return Err(anyhow!("Key file does not belong to {}", key_id));
+            }
+        }
+        let key = key_file.unlock(self.password.as_deref().map(String::as_str))?;
+        Ok(key.to_vec())
+    }
+
+    fn delete_key(&self, key_id: &str) -> Result<()> {
+        let path = self.key_path(key_id)?;
+        if path.exists() {
+            // Overwrite before unlinking so the key does not linger on disk
+            let len = std::fs::metadata(&path)?.len() as usize;
+            std::fs::write(&path, vec![0u8; len])?;
+            std::fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
+    fn key_exists(&self, key_id: &str) -> bool {
+        self.key_path(key_id).map(|p| p.exists()).unwrap_or(false)
+    }
+
+    fn requires_biometric(&self) -> bool {
+        self.biometric_required
+    }
+
+    fn set_biometric_required(&mut self, required: bool) {
+        self.biometric_required = required;
+    }
+}
+
 /// Mock keystore for testing
 #[cfg(not(any(target_os = "ios", target_os = "android")))]
 pub struct MockKeyStore {
-    keys: std::collections::HashMap<String, Vec<u8>>,
+    keys: std::sync::Mutex<std::collections::HashMap<String, Vec<u8>>>,
     biometric_required: bool,
 }
 
@@ -159,32 +254,45 @@ pub struct MockKeyStore {
 impl MockKeyStore {
     pub fn new() -> Self {
         Self {
-            keys: std::collections::HashMap::new(),
+            keys: std::sync::Mutex::new(std::collections::HashMap::new()),
             biometric_required: false,
         }
     }
 }
 
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+impl Default for MockKeyStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(not(any(target_os = "ios", target_os = "android")))]
 impl KeyStore for MockKeyStore {
     fn store_key(&self, key_id: &str, key: &[u8]) -> Result<()> {
-        // Note: This is not thread-safe, just for testing
         tracing::info!("Storing key in mock keystore: {}", key_id);
+        self.keys.lock().unwrap().insert(key_id.to_string(), key.to_vec());
         Ok(())
     }
     
     fn retrieve_key(&self, key_id: &str) -> Result<Vec<u8>> {
         tracing::info!("Retrieving key from mock keystore: {}", key_id);
-        Ok(vec![0u8; 32])
+        self.keys
+            .lock()
+            .unwrap()
+            .get(key_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Key not found: {}", key_id))
     }
     
     fn delete_key(&self, key_id: &str) -> Result<()> {
         tracing::info!("Deleting key from mock keystore: {}", key_id);
+        self.keys.lock().unwrap().remove(key_id);
         Ok(())
     }
     
-    fn key_exists(&self, _key_id: &str) -> bool {
-        false
+    fn key_exists(&self, key_id: &str) -> bool {
+        self.keys.lock().unwrap().contains_key(key_id)
     }
     
     fn requires_biometric(&self) -> bool {
@@ -214,4 +322,37 @@ mod tests {
         keystore.set_biometric_required(true);
         assert!(keystore.requires_biometric());
     }
+    
+    #[test]
+    fn test_mock_keystore_roundtrip() {
+        let keystore = MockKeyStore::new();
+        assert!(keystore.retrieve_key("capsule").is_err());
+        
+        keystore.store_key("capsule", &[7u8; 32]).unwrap();
+        assert!(keystore.key_exists("capsule"));
+        assert_eq!(keystore.retrieve_key("capsule").unwrap(), vec![7u8; 32]);
+        
+        keystore.delete_key("capsule").unwrap();
+        assert!(!keystore.key_exists("capsule"));
+    }
+    
+    #[test]
+    fn test_file_keystore() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = FileKeyStore::new(dir.path()).unwrap().with_password("pw");
+        
+        keystore.store_key("capsule", &[9u8; 32]).unwrap();
+        assert!(keystore.key_exists("capsule"));
+        assert_eq!(keystore.retrieve_key("capsule").unwrap(), vec![9u8; 32]);
+        
+        // Wrong password and mismatched key ids are rejected
+        let wrong = FileKeyStore::new(dir.path()).unwrap().with_password("other");
+        assert!(wrong.retrieve_key("capsule").is_err());
+        std::fs::copy(dir.path().join("capsule.key"), dir.path().join("other.key")).unwrap();
+        assert!(keystore.retrieve_key("other").is_err());
+        assert!(keystore.store_key("../escape", &[0u8; 32]).is_err());
+        
+        keystore.delete_key("capsule").unwrap();
+        assert!(!keystore.key_exists("capsule"));
+    }
 }
@@ -5,6 +5,10 @@
 //! - Battery and thermal monitoring
 //! - Platform-specific integrations
 //! - Encrypted storage
+//!
+//! Enabled with the `capsule` feature. The encrypted [`MemoryCapsule`] works
+//! on every platform; desktop and server nodes keep its key in a
+//! [`FileKeyStore`].
 
 #[cfg(target_os = "ios")]
 pub mod ios;
@@ -21,7 +25,7 @@ pub mod capsule;
 
 pub use capabilities::HardwareCapabilities;
 pub use battery::{BatteryMonitor, ThermalState, MemoryPressure};
-pub use keystore::KeyStore;
+pub use keystore::{FileKeyStore, KeyStore};
 pub use encryption::{Encryptor, EncryptedBlob};
 pub use recovery::RecoveryPhrase;
 pub use capsule::MemoryCapsule;
@@ -106,11 +106,6 @@ impl RecoveryPhrase {
         &self.words
     }
     
-    /// Convert to string (space-separated)
-    pub fn to_string(&self) -> String {
-        self.words.join(" ")
-    }
-    
     /// Parse from string
     pub fn from_string(s: &str) -> Result<Self> {
         let words: Vec<String> = s.split_whitespace()
@@ -161,7 +156,8 @@ impl RecoveryPhrase {
 
 impl std::fmt::Display for RecoveryPhrase {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        // Space-separated words
+        write!(f, "{}", self.words.join(" "))
     }
 }
 
@@ -0,0 +1,169 @@
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+
+use crate::signed::{sign_record, SignedRecord};
+
+/// Why a grain was flagged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagReason {
+    Spam,
+    Harmful,
+    Illegal,
+    Other,
+}
+
+impl FlagReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Spam => "spam",
+            Self::Harmful => "harmful",
+            Self::Illegal => "illegal",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "spam" => Some(Self::Spam),
+            "harmful" => Some(Self::Harmful),
+            "illegal" => Some(Self::Illegal),
+            "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
+
+/// What a moderation message does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagAction {
+    /// Report the grain
+    Flag(FlagReason),
+    /// Withdraw the signer's earlier flag
+    Unflag,
+    /// The grain's author contests its quarantine
+    Appeal,
+}
+
+impl FlagAction {
+    /// Stable name, also covered by the signature
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Flag(FlagReason::Spam) => "flag:spam",
+            Self::Flag(FlagReason::Harmful) => "flag:harmful",
+            Self::Flag(FlagReason::Illegal) => "flag:illegal",
+            Self::Flag(FlagReason::Other) => "flag:other",
+            Self::Unflag => "unflag",
+            Self::Appeal => "appeal",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "unflag" => Some(Self::Unflag),
+            "appeal" => Some(Self::Appeal),
+            _ => s
+                .strip_prefix("flag:")
+                .and_then(FlagReason::parse)
+                .map(Self::Flag),
+        }
+    }
+}
+
+/// Signed flag, unflag or appeal on a grain
+///
+/// Flags are gossiped so every node can aggregate them; each node decides
+/// for itself whether a grain is quarantined.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrainFlag {
+    pub grain_id: [u8; 32],
+    /// Signer public key (ed25519)
+    pub signer: [u8; 32],
+    pub action: FlagAction,
+    /// Optional explanation
+    pub note: Option<String>,
+    /// Unix timestamp (milliseconds)
+    pub ts_unix_ms: i64,
+    /// Signer signature over all fields above
+    pub sig: Vec<u8>,
+}
+
+impl GrainFlag {
+    /// Create and sign a moderation message by the key's owner
+    pub fn new(
+        grain_id: [u8; 32],
+        action: FlagAction,
+        note: Option<String>,
+        signing_key: &SigningKey,
+    ) -> Self {
+        let mut flag = GrainFlag {
+            grain_id,
+            signer: signing_key.verifying_key().to_bytes(),
+            action,
+            note,
+            ts_unix_ms: chrono::Utc::now().timestamp_millis(),
+            sig: Vec::new(),
+        };
+
+        flag.sig = sign_record(&flag, signing_key);
+        flag
+    }
+
+    /// Message ID: blake3 of the signed fields
+    pub fn id(&self) -> [u8; 32] {
+        *blake3::hash(&self.signing_bytes()).as_bytes()
+    }
+}
+
+impl SignedRecord for GrainFlag {
+    const DOMAIN: &'static [u8] = b"synapsenet/grain-flag/v2";
+
+    fn subject(&self) -> &[u8] {
+        &self.grain_id
+    }
+
+    fn signer(&self) -> &[u8; 32] {
+        &self.signer
+    }
+
+    fn action(&self) -> &str {
+        self.action.as_str()
+    }
+
+    fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    fn ts_unix_ms(&self) -> i64 {
+        self.ts_unix_ms
+    }
+
+    fn sig(&self) -> &[u8] {
+        &self.sig
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_id_covers_action() {
+        let flagger = SigningKey::from_bytes(&[3u8; 32]);
+        let flag = GrainFlag::new([9u8; 32], FlagAction::Flag(FlagReason::Spam), None, &flagger);
+
+        let mut tampered = flag.clone();
+        tampered.action = FlagAction::Flag(FlagReason::Illegal);
+        assert!(!tampered.verify().unwrap());
+        assert_ne!(tampered.id(), flag.id());
+
+        for action in [
+            FlagAction::Flag(FlagReason::Harmful),
+            FlagAction::Unflag,
+            FlagAction::Appeal,
+        ] {
+            assert_eq!(FlagAction::parse(action.as_str()), Some(action));
+        }
+    }
+}
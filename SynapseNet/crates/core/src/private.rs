@@ -0,0 +1,657 @@
+//! Private grains shared with selected peers
+//!
+//! Members of a group share a random 256-bit group key. The key reaches each
+//! member in a signed [`GroupKeyGrant`], wrapped to the member's X25519 key or,
+//! with the `pqc-kyber` feature, to a Kyber1024 key (the KEM used by the P2P
+//! Kyber handshake). A [`PrivateGrain`] carries a whole grain, vector and
+//! metadata included, encrypted under the group key. Peers outside the group
+//! relay the ciphertext but only learn the group id and the author.
+//!
+//! Group keys a node holds are stored sealed under a key derived from its
+//! node secret, so they are as safe at rest as the keystore itself.
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, Result};
+use ed25519_dalek::{Signer, SigningKey};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use x25519_dalek::{EphemeralSecret, PublicKey as X25519PublicKey, StaticSecret};
+use zeroize::Zeroizing;
+
+#[cfg(feature = "pqc-kyber")]
+use pqcrypto_kyber::kyber1024;
+#[cfg(feature = "pqc-kyber")]
+use pqcrypto_traits::kem::{Ciphertext, PublicKey, SecretKey, SharedSecret};
+
+use crate::grain::Grain;
+use crate::signed::{extend_with_len, verify_signature};
+
+const NONCE_LEN: usize = 12;
+const GROUP_ID_CONTEXT: &str = "synapsenet/private-grain/group-id/v1";
+const X25519_SECRET_CONTEXT: &str = "synapsenet/private-grain/x25519-secret/v1";
+const X25519_WRAP_CONTEXT: &str = "synapsenet/private-grain/x25519-wrap/v1";
+const GROUP_KEY_STORAGE_CONTEXT: &str = "synapsenet/private-grain/group-key-storage/v1";
+#[cfg(feature = "pqc-kyber")]
+const KYBER_WRAP_CONTEXT: &str = "synapsenet/private-grain/kyber-wrap/v1";
+
+/// Public key a group key can be wrapped to
+///
+/// Written as `x25519:<hex>` or `kyber:<hex>` so it can be shared out of band.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecipientKey {
+    X25519([u8; 32]),
+    /// Kyber1024 public key
+    Kyber(Vec<u8>),
+}
+
+impl fmt::Display for RecipientKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipientKey::X25519(pk) => write!(f, "x25519:{}", hex::encode(pk)),
+            RecipientKey::Kyber(pk) => write!(f, "kyber:{}", hex::encode(pk)),
+        }
+    }
+}
+
+impl FromStr for RecipientKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (kind, key) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Recipient key must look like x25519:<hex> or kyber:<hex>"))?;
+        let bytes = hex::decode(key.trim())?;
+        match kind {
+            "x25519" => Ok(RecipientKey::X25519(
+                bytes
+                    .try_into()
+                    .map_err(|_| anyhow!("X25519 key must be 32 bytes"))?,
+            )),
+            "kyber" => Ok(RecipientKey::Kyber(bytes)),
+            other => Err(anyhow!("Unknown recipient key type: {}", other)),
+        }
+    }
+}
+
+/// Secret half of a node's recipient keys
+pub struct RecipientSecret {
+    x25519: StaticSecret,
+    /// Seals group keys at rest
+    storage_key: Zeroizing<[u8; 32]>,
+    #[cfg(feature = "pqc-kyber")]
+    kyber: Option<(kyber1024::PublicKey, kyber1024::SecretKey)>,
+}
+
+impl RecipientSecret {
+    /// Derive the X25519 key from the node secret key, so nothing new has to
+    /// be stored or backed up
+    pub fn from_node_secret(secret: &[u8; 32]) -> Self {
+        let derived = Zeroizing::new(blake3::derive_key(X25519_SECRET_CONTEXT, secret));
+        Self {
+            x25519: StaticSecret::from(*derived),
+            storage_key: Zeroizing::new(blake3::derive_key(GROUP_KEY_STORAGE_CONTEXT, secret)),
+            #[cfg(feature = "pqc-kyber")]
+            kyber: None,
+        }
+    }
+
+    /// Also accept keys wrapped to a Kyber1024 keypair (e.g. a `KyberKem`)
+    #[cfg(feature = "pqc-kyber")]
+    pub fn with_kyber(mut self, public_key: &[u8], secret_key: &[u8]) -> Result<Self> {
+        let pk = kyber1024::PublicKey::from_bytes(public_key)
+            .map_err(|_| anyhow!("Invalid Kyber public key"))?;
+        let sk = kyber1024::SecretKey::from_bytes(secret_key)
+            .map_err(|_| anyhow!("Invalid Kyber secret key"))?;
+        self.kyber = Some((pk, sk));
+        Ok(self)
+    }
+
+    /// Public keys other members wrap group keys to
+    pub fn public_keys(&self) -> Vec<RecipientKey> {
+        #[allow(unused_mut)]
+        let mut keys = vec![RecipientKey::X25519(
+            X25519PublicKey::from(&self.x25519).to_bytes(),
+        )];
+        #[cfg(feature = "pqc-kyber")]
+        if let Some((ref pk, _)) = self.kyber {
+            keys.push(RecipientKey::Kyber(pk.as_bytes().to_vec()));
+        }
+        keys
+    }
+
+    /// Encrypt a group key for storage (`nonce || ciphertext`)
+    pub fn seal_group_key(&self, group_key: &GroupKey) -> Result<Vec<u8>> {
+        let (nonce, ciphertext) = seal(&self.storage_key, &group_key.group_id, group_key.as_bytes())?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// Decrypt a group key stored with `seal_group_key`
+    pub fn open_group_key(&self, group_id: &[u8; 32], sealed: &[u8]) -> Result<GroupKey> {
+        if sealed.len() < NONCE_LEN {
+            return Err(anyhow!("Sealed group key too short"));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let key = Zeroizing::new(open(
+            &self.storage_key,
+            nonce.try_into()?,
+            group_id,
+            ciphertext,
+        )?);
+        let key: [u8; 32] = key[..]
+            .try_into()
+            .map_err(|_| anyhow!("Group key must be 32 bytes"))?;
+
+        let group_key = GroupKey::from_bytes(key);
+        if group_key.group_id() != *group_id {
+            return Err(anyhow!("Group key does not match its group id"));
+        }
+        Ok(group_key)
+    }
+
+    /// Unwrap a group key addressed to one of our keys
+    fn unwrap_key(&self, wrapped: &WrappedKey, group_id: &[u8; 32]) -> Option<Zeroizing<[u8; 32]>> {
+        let (kek, nonce, ciphertext) = match wrapped {
+            WrappedKey::X25519 {
+                ephemeral_pk,
+                nonce,
+                ciphertext,
+            } => {
+                let recipient_pk = X25519PublicKey::from(&self.x25519);
+                let shared = self
+                    .x25519
+                    .diffie_hellman(&X25519PublicKey::from(*ephemeral_pk));
+                if !shared.was_contributory() {
+                    return None;
+                }
+                let kek = x25519_kek(shared.as_bytes(), ephemeral_pk, recipient_pk.as_bytes());
+                (kek, nonce, ciphertext)
+            }
+            #[cfg(feature = "pqc-kyber")]
+            WrappedKey::Kyber {
+                kem_ciphertext,
+                nonce,
+                ciphertext,
+            } => {
+                let (_, ref sk) = self.kyber.as_ref()?;
+                let ct = kyber1024::Ciphertext::from_bytes(kem_ciphertext).ok()?;
+                let shared = kyber1024::decapsulate(&ct, sk);
+                (kyber_kek(shared.as_bytes()), nonce, ciphertext)
+            }
+            #[cfg(not(feature = "pqc-kyber"))]
+            WrappedKey::Kyber { .. } => return None,
+        };
+
+        let key = open(&kek, nonce, group_id, ciphertext).ok()?;
+        let key: [u8; 32] = key.as_slice().try_into().ok()?;
+        Some(Zeroizing::new(key))
+    }
+}
+
+/// Symmetric key shared by the members of a group
+///
+/// The group id is derived from the key, so a grant cannot pass off a
+/// different key under an existing group id.
+#[derive(Clone)]
+pub struct GroupKey {
+    group_id: [u8; 32],
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl GroupKey {
+    /// Create a key for a new group
+    pub fn generate() -> Self {
+        let mut key = Zeroizing::new([0u8; 32]);
+        OsRng.fill_bytes(key.as_mut());
+        Self::from_bytes(*key)
+    }
+
+    /// Restore a stored group key
+    pub fn from_bytes(key: [u8; 32]) -> Self {
+        Self {
+            group_id: blake3::derive_key(GROUP_ID_CONTEXT, &key),
+            key: Zeroizing::new(key),
+        }
+    }
+
+    pub fn group_id(&self) -> [u8; 32] {
+        self.group_id
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.key
+    }
+}
+
+impl fmt::Debug for GroupKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GroupKey")
+            .field("group_id", &hex::encode(self.group_id))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Group key encrypted to a single recipient
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WrappedKey {
+    X25519 {
+        ephemeral_pk: [u8; 32],
+        nonce: [u8; NONCE_LEN],
+        ciphertext: Vec<u8>,
+    },
+    Kyber {
+        kem_ciphertext: Vec<u8>,
+        nonce: [u8; NONCE_LEN],
+        ciphertext: Vec<u8>,
+    },
+}
+
+impl WrappedKey {
+    fn wrap(group_key: &GroupKey, recipient: &RecipientKey) -> Result<Self> {
+        match recipient {
+            RecipientKey::X25519(recipient_pk) => {
+                let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+                let ephemeral_pk = X25519PublicKey::from(&ephemeral).to_bytes();
+                let shared = ephemeral.diffie_hellman(&X25519PublicKey::from(*recipient_pk));
+                if !shared.was_contributory() {
+                    return Err(anyhow!("Invalid X25519 recipient key"));
+                }
+                let kek = x25519_kek(shared.as_bytes(), &ephemeral_pk, recipient_pk);
+                let (nonce, ciphertext) = seal(&kek, &group_key.group_id, group_key.as_bytes())?;
+                Ok(WrappedKey::X25519 {
+                    ephemeral_pk,
+                    nonce,
+                    ciphertext,
+                })
+            }
+            #[cfg(feature = "pqc-kyber")]
+            RecipientKey::Kyber(recipient_pk) => {
+                let pk = kyber1024::PublicKey::from_bytes(recipient_pk)
+                    .map_err(|_| anyhow!("Invalid Kyber recipient key"))?;
+                let (shared, kem_ciphertext) = kyber1024::encapsulate(&pk);
+                let kek = kyber_kek(shared.as_bytes());
+                let (nonce, ciphertext) = seal(&kek, &group_key.group_id, group_key.as_bytes())?;
+                Ok(WrappedKey::Kyber {
+                    kem_ciphertext: kem_ciphertext.as_bytes().to_vec(),
+                    nonce,
+                    ciphertext,
+                })
+            }
+            #[cfg(not(feature = "pqc-kyber"))]
+            RecipientKey::Kyber(_) => Err(anyhow!(
+                "Kyber recipients need the pqc-kyber feature"
+            )),
+        }
+    }
+
+    fn signing_bytes(&self, data: &mut Vec<u8>) {
+        match self {
+            WrappedKey::X25519 {
+                ephemeral_pk,
+                nonce,
+                ciphertext,
+            } => {
+                data.push(0);
+                data.extend_from_slice(ephemeral_pk);
+                data.extend_from_slice(nonce);
+                extend_with_len(data, ciphertext);
+            }
+            WrappedKey::Kyber {
+                kem_ciphertext,
+                nonce,
+                ciphertext,
+            } => {
+                data.push(1);
+                extend_with_len(data, kem_ciphertext);
+                data.extend_from_slice(nonce);
+                extend_with_len(data, ciphertext);
+            }
+        }
+    }
+}
+
+/// Signed hand-out of a group key to a set of recipients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupKeyGrant {
+    /// Group the key belongs to
+    pub group_id: [u8; 32],
+    /// Granting member public key (ed25519)
+    pub author_pk: [u8; 32],
+    /// Unix timestamp (milliseconds)
+    pub ts_unix_ms: i64,
+    /// Group key wrapped once per recipient
+    pub wrapped: Vec<WrappedKey>,
+    /// Author signature over all fields above
+    pub sig: Vec<u8>,
+}
+
+impl GroupKeyGrant {
+    /// Wrap `group_key` to every recipient and sign the grant
+    pub fn new(
+        group_key: &GroupKey,
+        recipients: &[RecipientKey],
+        signing_key: &SigningKey,
+    ) -> Result<Self> {
+        if recipients.is_empty() {
+            return Err(anyhow!("A grant needs at least one recipient"));
+        }
+
+        let mut grant = GroupKeyGrant {
+            group_id: group_key.group_id,
+            author_pk: signing_key.verifying_key().to_bytes(),
+            ts_unix_ms: chrono::Utc::now().timestamp_millis(),
+            wrapped: recipients
+                .iter()
+                .map(|recipient| WrappedKey::wrap(group_key, recipient))
+                .collect::<Result<_>>()?,
+            sig: Vec::new(),
+        };
+
+        grant.sig = signing_key.sign(&grant.signing_bytes()).to_bytes().to_vec();
+        Ok(grant)
+    }
+
+    /// Grant ID: blake3 of the signed fields
+    pub fn id(&self) -> [u8; 32] {
+        *blake3::hash(&self.signing_bytes()).as_bytes()
+    }
+
+    /// Verify author signature
+    pub fn verify(&self) -> Result<bool> {
+        verify_signature(&self.author_pk, &self.signing_bytes(), &self.sig)
+    }
+
+    /// Recover the group key if one of `secret`'s keys is a recipient
+    pub fn open(&self, secret: &RecipientSecret) -> Option<GroupKey> {
+        self.wrapped.iter().find_map(|wrapped| {
+            let key = secret.unwrap_key(wrapped, &self.group_id)?;
+            let group_key = GroupKey::from_bytes(*key);
+            (group_key.group_id == self.group_id).then_some(group_key)
+        })
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"synapsenet/group-key-grant/v1");
+        data.extend_from_slice(&self.group_id);
+        data.extend_from_slice(&self.author_pk);
+        data.extend_from_slice(&self.ts_unix_ms.to_le_bytes());
+        for wrapped in &self.wrapped {
+            wrapped.signing_bytes(&mut data);
+        }
+        data
+    }
+}
+
+/// Grain encrypted to the members of a group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrivateGrain {
+    /// Group whose key encrypts the grain
+    pub group_id: [u8; 32],
+    /// Author public key (ed25519), same as the inner grain's
+    pub author_pk: [u8; 32],
+    /// Unix timestamp (milliseconds)
+    pub ts_unix_ms: i64,
+    pub nonce: [u8; NONCE_LEN],
+    /// AES-256-GCM encrypted grain
+    pub ciphertext: Vec<u8>,
+    /// Author signature over all fields above
+    pub sig: Vec<u8>,
+}
+
+impl PrivateGrain {
+    /// Encrypt `grain` for the group and sign the envelope
+    pub fn seal(grain: &Grain, group_key: &GroupKey, signing_key: &SigningKey) -> Result<Self> {
+        let author_pk = signing_key.verifying_key().to_bytes();
+        if grain.meta.author_pk != author_pk {
+            return Err(anyhow!("Grain must be authored by the sealing key"));
+        }
+
+        let mut private = PrivateGrain {
+            group_id: group_key.group_id,
+            author_pk,
+            ts_unix_ms: grain.meta.ts_unix_ms,
+            nonce: [0u8; NONCE_LEN],
+            ciphertext: Vec::new(),
+            sig: Vec::new(),
+        };
+
+        let plaintext = Zeroizing::new(bincode::serialize(grain)?);
+        let (nonce, ciphertext) = seal(group_key.as_bytes(), &private.aad(), &plaintext)?;
+        private.nonce = nonce;
+        private.ciphertext = ciphertext;
+
+        private.sig = signing_key.sign(&private.signing_bytes()).to_bytes().to_vec();
+        Ok(private)
+    }
+
+    /// Private grain ID: blake3 of the signed fields
+    ///
+    /// Unrelated to the inner grain ID, which only members learn.
+    pub fn id(&self) -> [u8; 32] {
+        *blake3::hash(&self.signing_bytes()).as_bytes()
+    }
+
+    /// Verify author signature on the envelope
+    pub fn verify(&self) -> Result<bool> {
+        verify_signature(&self.author_pk, &self.signing_bytes(), &self.sig)
+    }
+
+    /// Decrypt with the group key and check the inner grain
+    pub fn open(&self, group_key: &GroupKey) -> Result<Grain> {
+        if group_key.group_id != self.group_id {
+            return Err(anyhow!("Grain belongs to another group"));
+        }
+
+        let plaintext = Zeroizing::new(open(
+            group_key.as_bytes(),
+            &self.nonce,
+            &self.aad(),
+            &self.ciphertext,
+        )?);
+        let grain: Grain = bincode::deserialize(&plaintext)?;
+
+        if grain.meta.author_pk != self.author_pk || grain.meta.ts_unix_ms != self.ts_unix_ms {
+            return Err(anyhow!("Inner grain does not match the envelope"));
+        }
+        if !grain.verify()? {
+            return Err(anyhow!("Invalid inner grain signature"));
+        }
+        Ok(grain)
+    }
+
+    fn aad(&self) -> Vec<u8> {
+        let mut aad = Vec::with_capacity(72);
+        aad.extend_from_slice(&self.group_id);
+        aad.extend_from_slice(&self.author_pk);
+        aad.extend_from_slice(&self.ts_unix_ms.to_le_bytes());
+        aad
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"synapsenet/private-grain/v1");
+        data.extend_from_slice(&self.aad());
+        data.extend_from_slice(&self.nonce);
+        data.extend_from_slice(&self.ciphertext);
+        data
+    }
+}
+
+fn x25519_kek(shared: &[u8; 32], ephemeral_pk: &[u8; 32], recipient_pk: &[u8; 32]) -> Zeroizing<[u8; 32]> {
+    let mut material = Zeroizing::new(Vec::with_capacity(96));
+    material.extend_from_slice(shared);
+    material.extend_from_slice(ephemeral_pk);
+    material.extend_from_slice(recipient_pk);
+    Zeroizing::new(blake3::derive_key(X25519_WRAP_CONTEXT, &material))
+}
+
+#[cfg(feature = "pqc-kyber")]
+fn kyber_kek(shared: &[u8]) -> Zeroizing<[u8; 32]> {
+    Zeroizing::new(blake3::derive_key(KYBER_WRAP_CONTEXT, shared))
+}
+
+fn seal(key: &[u8; 32], aad: &[u8], plaintext: &[u8]) -> Result<([u8; NONCE_LEN], Vec<u8>)> {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| anyhow!("Invalid key: {}", e))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad })
+        .map_err(|_| anyhow!("Encryption failed"))?;
+    Ok((nonce, ciphertext))
+}
+
+fn open(key: &[u8; 32], nonce: &[u8; NONCE_LEN], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| anyhow!("Invalid key: {}", e))?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| anyhow!("Decryption failed: wrong key or tampered ciphertext"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::CryptoBackend;
+    use crate::grain::GrainMeta;
+
+    fn signing_key() -> SigningKey {
+        let mut secret_bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut secret_bytes);
+        SigningKey::from_bytes(&secret_bytes)
+    }
+
+    fn recipient() -> RecipientSecret {
+        RecipientSecret::from_node_secret(&signing_key().to_bytes())
+    }
+
+    fn grain(signing_key: &SigningKey) -> Grain {
+        let meta = GrainMeta {
+            author_pk: signing_key.verifying_key().to_bytes().to_vec(),
+            crypto_backend: CryptoBackend::Classical,
+            ts_unix_ms: 1_700_000_000_000,
+            tags: vec!["team".to_string()],
+            mime: "text/plain".to_string(),
+            lang: "en".to_string(),
+            title: Some("Internal roadmap".to_string()),
+            summary: None,
+            embedding_model: None,
+            embedding_dimensions: Some(3),
+        };
+        Grain::new(vec![0.1, 0.2, 0.3], meta, signing_key).unwrap()
+    }
+
+    #[test]
+    fn test_group_key_grant() {
+        let author = signing_key();
+        let alice = recipient();
+        let bob = recipient();
+        let outsider = recipient();
+
+        let group_key = GroupKey::generate();
+        let recipients: Vec<_> = [&alice, &bob]
+            .iter()
+            .flat_map(|secret| secret.public_keys())
+            .collect();
+        let grant = GroupKeyGrant::new(&group_key, &recipients, &author).unwrap();
+        assert!(grant.verify().unwrap());
+
+        for member in [&alice, &bob] {
+            let opened = grant.open(member).unwrap();
+            assert_eq!(opened.group_id(), group_key.group_id());
+            assert_eq!(opened.as_bytes(), group_key.as_bytes());
+        }
+        assert!(grant.open(&outsider).is_none());
+
+        // Recipients cannot be swapped out after signing
+        let mut tampered = grant.clone();
+        tampered.wrapped.pop();
+        assert!(!tampered.verify().unwrap());
+
+        // The same node secret always yields the same recipient key
+        let secret = signing_key().to_bytes();
+        assert_eq!(
+            RecipientSecret::from_node_secret(&secret).public_keys(),
+            RecipientSecret::from_node_secret(&secret).public_keys()
+        );
+        assert!(GroupKeyGrant::new(&group_key, &[], &author).is_err());
+    }
+
+    #[test]
+    fn test_private_grain_round_trip() {
+        let author = signing_key();
+        let group_key = GroupKey::generate();
+        let grain = grain(&author);
+
+        let private = PrivateGrain::seal(&grain, &group_key, &author).unwrap();
+        assert!(private.verify().unwrap());
+        assert_ne!(private.id(), grain.id);
+
+        let opened = private.open(&group_key).unwrap();
+        assert_eq!(opened.id, grain.id);
+        assert_eq!(opened.vec, grain.vec);
+
+        // Non-members cannot decrypt
+        assert!(private.open(&GroupKey::generate()).is_err());
+
+        let mut tampered = private.clone();
+        tampered.ciphertext[0] ^= 0xff;
+        assert!(!tampered.verify().unwrap());
+        assert!(tampered.open(&group_key).is_err());
+
+        // Grains can only be sealed by their author
+        assert!(PrivateGrain::seal(&grain, &group_key, &signing_key()).is_err());
+    }
+
+    #[test]
+    fn test_group_key_storage() {
+        let secret = signing_key().to_bytes();
+        let owner = RecipientSecret::from_node_secret(&secret);
+        let group_key = GroupKey::generate();
+        let group_id = group_key.group_id();
+
+        let sealed = owner.seal_group_key(&group_key).unwrap();
+        assert!(!sealed
+            .windows(32)
+            .any(|window| window == group_key.as_bytes()));
+
+        let restored = RecipientSecret::from_node_secret(&secret)
+            .open_group_key(&group_id, &sealed)
+            .unwrap();
+        assert_eq!(restored.as_bytes(), group_key.as_bytes());
+
+        // Other nodes cannot open it, and it is bound to its group id
+        assert!(recipient().open_group_key(&group_id, &sealed).is_err());
+        assert!(owner.open_group_key(&[0u8; 32], &sealed).is_err());
+    }
+
+    #[test]
+    fn test_recipient_key_strings() {
+        let key = recipient().public_keys().remove(0);
+        let parsed: RecipientKey = key.to_string().parse().unwrap();
+        assert_eq!(parsed, key);
+
+        assert!("x25519:abcd".parse::<RecipientKey>().is_err());
+        assert!("rsa:abcd".parse::<RecipientKey>().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "pqc-kyber")]
+    fn test_kyber_recipient() {
+        let (pk, sk) = kyber1024::keypair();
+        let member = recipient()
+            .with_kyber(pk.as_bytes(), sk.as_bytes())
+            .unwrap();
+        let kyber_key = member.public_keys().pop().unwrap();
+        assert!(matches!(kyber_key, RecipientKey::Kyber(_)));
+
+        let group_key = GroupKey::generate();
+        let grant = GroupKeyGrant::new(&group_key, &[kyber_key], &signing_key()).unwrap();
+        assert_eq!(grant.open(&member).unwrap().group_id(), group_key.group_id());
+        assert!(grant.open(&recipient()).is_none());
+    }
+}
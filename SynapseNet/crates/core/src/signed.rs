@@ -0,0 +1,140 @@
+//! Records signed by a single ed25519 key
+//!
+//! Grain flags and curator decisions are both an action a key's owner takes
+//! on a subject (a grain, a review task), with an optional note. They share
+//! one signing layout through [`SignedRecord`], so a record type only says
+//! which fields fill it.
+
+use anyhow::{anyhow, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+/// An action signed by one key, with an optional note
+pub trait SignedRecord {
+    /// Domain separator; bump its version when the layout changes
+    const DOMAIN: &'static [u8];
+
+    /// What the action applies to
+    fn subject(&self) -> &[u8];
+
+    /// Signer public key (ed25519)
+    fn signer(&self) -> &[u8; 32];
+
+    /// Stable action name
+    fn action(&self) -> &str;
+
+    fn note(&self) -> Option<&str>;
+
+    /// Unix timestamp (milliseconds)
+    fn ts_unix_ms(&self) -> i64;
+
+    fn sig(&self) -> &[u8];
+
+    /// Bytes covered by the signature
+    ///
+    /// Clients signing records themselves must produce exactly these bytes.
+    /// The note is tagged and length-prefixed, so no note and an empty note
+    /// sign different bytes.
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(Self::DOMAIN);
+        extend_with_len(&mut data, self.subject());
+        data.extend_from_slice(self.signer());
+        extend_with_len(&mut data, self.action().as_bytes());
+        data.extend_from_slice(&self.ts_unix_ms().to_le_bytes());
+        match self.note() {
+            Some(note) => {
+                data.push(1);
+                extend_with_len(&mut data, note.as_bytes());
+            }
+            None => data.push(0),
+        }
+        data
+    }
+
+    /// Verify signer signature
+    fn verify(&self) -> Result<bool> {
+        verify_signature(self.signer(), &self.signing_bytes(), self.sig())
+    }
+}
+
+/// Signature of the key's owner over the record's signing bytes
+pub(crate) fn sign_record(record: &impl SignedRecord, signing_key: &SigningKey) -> Vec<u8> {
+    signing_key
+        .sign(&record.signing_bytes())
+        .to_bytes()
+        .to_vec()
+}
+
+pub(crate) fn verify_signature(pk: &[u8; 32], data: &[u8], sig: &[u8]) -> Result<bool> {
+    let verifying_key = VerifyingKey::from_bytes(pk)?;
+    let signature = Signature::from_bytes(
+        sig.try_into()
+            .map_err(|_| anyhow!("Invalid signature length"))?,
+    );
+    Ok(verifying_key.verify(data, &signature).is_ok())
+}
+
+pub(crate) fn extend_with_len(data: &mut Vec<u8>, bytes: &[u8]) {
+    data.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    data.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curation::{CuratorAction, CuratorDecision};
+    use crate::moderation::{FlagAction, FlagReason, GrainFlag};
+    use rand::rngs::OsRng;
+    use rand::RngCore;
+    use uuid::Uuid;
+
+    fn signing_key() -> SigningKey {
+        let mut secret_bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut secret_bytes);
+        SigningKey::from_bytes(&secret_bytes)
+    }
+
+    /// Signed with a note by `key`; `set_note` replaces the note
+    fn check_signature<T, F>(record: T, key: &SigningKey, set_note: F)
+    where
+        T: SignedRecord + Clone,
+        F: Fn(&mut T, Option<String>),
+    {
+        assert_eq!(record.signer(), &key.verifying_key().to_bytes());
+        assert!(record.verify().unwrap());
+
+        let mut tampered = record.clone();
+        set_note(&mut tampered, Some("edited".to_string()));
+        assert!(!tampered.verify().unwrap());
+
+        let mut missing = record.clone();
+        set_note(&mut missing, None);
+        let mut empty = record;
+        set_note(&mut empty, Some(String::new()));
+        assert_ne!(missing.signing_bytes(), empty.signing_bytes());
+    }
+
+    #[test]
+    fn test_flag_signature() {
+        let flagger = signing_key();
+        let flag = GrainFlag::new(
+            [9u8; 32],
+            FlagAction::Flag(FlagReason::Spam),
+            Some("link farm".to_string()),
+            &flagger,
+        );
+        check_signature(flag, &flagger, |flag, note| flag.note = note);
+    }
+
+    #[test]
+    fn test_decision_signature() {
+        let curator = signing_key();
+        let decision = CuratorDecision::new(
+            Uuid::new_v4(),
+            CuratorAction::Approve,
+            Some("historical context only".to_string()),
+            &curator,
+        );
+        check_signature(decision, &curator, |decision, note| decision.note = note);
+    }
+}
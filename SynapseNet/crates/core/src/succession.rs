@@ -0,0 +1,137 @@
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+/// Signed hand-over from an old node key to a new one
+///
+/// The old key signs the new key so authorship moves to it, and the new key
+/// countersigns to prove it accepted the identity. With `revoked_from` set,
+/// grains signed by the old key at or after that time are rejected, which
+/// covers a leak discovered some time after it happened.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeySuccession {
+    /// Key being replaced (ed25519)
+    pub old_pk: [u8; 32],
+    /// Replacement key (ed25519)
+    pub new_pk: [u8; 32],
+    /// Unix timestamp (milliseconds)
+    pub ts_unix_ms: i64,
+    /// Revoke the old key from this time (milliseconds)
+    pub revoked_from: Option<i64>,
+    /// Old key signature over all fields above
+    pub old_sig: Vec<u8>,
+    /// New key signature over all fields above
+    pub new_sig: Vec<u8>,
+}
+
+impl KeySuccession {
+    /// Create a succession from `old_key` to `new_key`
+    pub fn new(
+        old_key: &SigningKey,
+        new_key: &SigningKey,
+        revoked_from: Option<i64>,
+    ) -> Result<Self, anyhow::Error> {
+        let old_pk = old_key.verifying_key().to_bytes();
+        let new_pk = new_key.verifying_key().to_bytes();
+        if old_pk == new_pk {
+            return Err(anyhow::anyhow!("New key must differ from the old key"));
+        }
+
+        let mut succession = KeySuccession {
+            old_pk,
+            new_pk,
+            ts_unix_ms: chrono::Utc::now().timestamp_millis(),
+            revoked_from,
+            old_sig: Vec::new(),
+            new_sig: Vec::new(),
+        };
+
+        let data = succession.signing_bytes();
+        succession.old_sig = old_key.sign(&data).to_bytes().to_vec();
+        succession.new_sig = new_key.sign(&data).to_bytes().to_vec();
+        Ok(succession)
+    }
+
+    /// Succession ID: blake3 of the signed fields
+    pub fn id(&self) -> [u8; 32] {
+        *blake3::hash(&self.signing_bytes()).as_bytes()
+    }
+
+    /// Verify both key signatures
+    pub fn verify(&self) -> Result<bool, anyhow::Error> {
+        if self.old_pk == self.new_pk {
+            return Ok(false);
+        }
+
+        let data = self.signing_bytes();
+        for (pk, sig) in [(&self.old_pk, &self.old_sig), (&self.new_pk, &self.new_sig)] {
+            let verifying_key = VerifyingKey::from_bytes(pk)?;
+            let signature = Signature::from_bytes(
+                sig.as_slice()
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Invalid signature length"))?,
+            );
+            if verifying_key.verify(&data, &signature).is_err() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Whether the old key may no longer sign content created at `ts_unix_ms`
+    pub fn revokes_at(&self, ts_unix_ms: i64) -> bool {
+        self.revoked_from.is_some_and(|from| ts_unix_ms >= from)
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"synapsenet/key-succession/v1");
+        data.extend_from_slice(&self.old_pk);
+        data.extend_from_slice(&self.new_pk);
+        data.extend_from_slice(&self.ts_unix_ms.to_le_bytes());
+        match self.revoked_from {
+            Some(from) => {
+                data.push(1);
+                data.extend_from_slice(&from.to_le_bytes());
+            }
+            None => data.push(0),
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+    use rand::RngCore;
+
+    fn signing_key() -> SigningKey {
+        let mut secret_bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut secret_bytes);
+        SigningKey::from_bytes(&secret_bytes)
+    }
+
+    #[test]
+    fn test_succession_signatures() {
+        let old_key = signing_key();
+        let new_key = signing_key();
+        let succession = KeySuccession::new(&old_key, &new_key, Some(1_000)).unwrap();
+
+        assert_eq!(succession.new_pk, new_key.verifying_key().to_bytes());
+        assert!(succession.verify().unwrap());
+        assert!(succession.revokes_at(1_000));
+        assert!(!succession.revokes_at(999));
+
+        // Redirecting the succession to another key breaks both signatures
+        let mut hijacked = succession.clone();
+        hijacked.new_pk = signing_key().verifying_key().to_bytes();
+        assert!(!hijacked.verify().unwrap());
+
+        let mut unrevoked = succession.clone();
+        unrevoked.revoked_from = None;
+        assert!(!unrevoked.verify().unwrap());
+        assert_ne!(unrevoked.id(), succession.id());
+
+        assert!(KeySuccession::new(&old_key, &old_key, None).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::signed::extend_with_len;
+
+/// Signed NGT transfer between two nodes
+///
+/// Each sender numbers its transfers with a nonce it never reuses; peers
+/// accept them in any order. Two different transfers signed with the same
+/// sender and nonce are a double-spend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NgtTransfer {
+    /// Sender public key (ed25519)
+    pub from: [u8; 32],
+    /// Recipient public key (ed25519)
+    pub to: [u8; 32],
+    /// NGT amount
+    pub amount: f64,
+    /// Per-sender sequence number
+    pub nonce: u64,
+    /// Unix timestamp (milliseconds)
+    pub ts_unix_ms: i64,
+    /// Optional note for the recipient
+    pub memo: Option<String>,
+    /// Sender signature over all fields above
+    pub sig: Vec<u8>,
+}
+
+impl NgtTransfer {
+    /// Create and sign a transfer from the key's owner
+    pub fn new(
+        to: [u8; 32],
+        amount: f64,
+        nonce: u64,
+        memo: Option<String>,
+        signing_key: &SigningKey,
+    ) -> Result<Self, anyhow::Error> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(anyhow::anyhow!("Transfer amount must be positive"));
+        }
+
+        let mut transfer = NgtTransfer {
+            from: signing_key.verifying_key().to_bytes(),
+            to,
+            amount,
+            nonce,
+            ts_unix_ms: chrono::Utc::now().timestamp_millis(),
+            memo,
+            sig: Vec::new(),
+        };
+
+        transfer.sig = signing_key.sign(&transfer.signing_bytes()).to_bytes().to_vec();
+        Ok(transfer)
+    }
+
+    /// Transfer ID: blake3 of the signed fields
+    pub fn id(&self) -> [u8; 32] {
+        *blake3::hash(&self.signing_bytes()).as_bytes()
+    }
+
+    /// Verify sender signature
+    pub fn verify(&self) -> Result<bool, anyhow::Error> {
+        let verifying_key = VerifyingKey::from_bytes(&self.from)?;
+        let signature = Signature::from_bytes(
+            self.sig
+                .as_slice()
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Invalid signature length"))?,
+        );
+
+        Ok(verifying_key.verify(&self.signing_bytes(), &signature).is_ok())
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"synapsenet/ngt-transfer/v2");
+        data.extend_from_slice(&self.from);
+        data.extend_from_slice(&self.to);
+        data.extend_from_slice(&self.amount.to_le_bytes());
+        data.extend_from_slice(&self.nonce.to_le_bytes());
+        data.extend_from_slice(&self.ts_unix_ms.to_le_bytes());
+        match self.memo {
+            Some(ref memo) => {
+                data.push(1);
+                extend_with_len(&mut data, memo.as_bytes());
+            }
+            None => data.push(0),
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+    use rand::RngCore;
+
+    #[test]
+    fn test_transfer_signature() {
+        let mut secret_bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut secret_bytes);
+        let sender = SigningKey::from_bytes(&secret_bytes);
+        let transfer =
+            NgtTransfer::new([2u8; 32], 1.5, 1, Some("hosting".to_string()), &sender).unwrap();
+
+        assert_eq!(transfer.from, sender.verifying_key().to_bytes());
+        assert!(transfer.verify().unwrap());
+
+        let mut tampered = transfer.clone();
+        tampered.amount = 100.0;
+        assert!(!tampered.verify().unwrap());
+        assert_ne!(tampered.id(), transfer.id());
+
+        // A relay cannot swap a missing memo for an empty one
+        let mut emptied = NgtTransfer::new([2u8; 32], 1.5, 3, None, &sender).unwrap();
+        let id = emptied.id();
+        emptied.memo = Some(String::new());
+        assert!(!emptied.verify().unwrap());
+        assert_ne!(emptied.id(), id);
+
+        assert!(NgtTransfer::new([2u8; 32], 0.0, 2, None, &sender).is_err());
+    }
+}
@@ -119,3 +119,81 @@ fn test_pqc_deterministic_public_key() {
     
     assert_eq!(pk1, pk2, "Public key should be deterministic");
 }
+
+#[cfg(all(feature = "classical-crypto", feature = "pqc-dilithium"))]
+fn signed_grain(signing_key: &synapsenet_core::UnifiedSigningKey) -> synapsenet_core::Grain {
+    use synapsenet_core::{Grain, GrainMeta, SigningKeyTrait};
+    
+    let vec = vec![0.1, 0.2, 0.3, 0.4];
+    let meta = GrainMeta {
+        author_pk: signing_key.public_key(),
+        crypto_backend: signing_key.backend(),
+        ts_unix_ms: 1234567890,
+        tags: vec!["test".to_string()],
+        mime: "text/plain".to_string(),
+        lang: "en".to_string(),
+        title: None,
+        summary: None,
+        embedding_model: None,
+        embedding_dimensions: Some(vec.len()),
+    };
+    Grain::new_with_unified_key(vec, meta, signing_key).unwrap()
+}
+
+#[cfg(all(feature = "classical-crypto", feature = "pqc-dilithium"))]
+#[test]
+fn test_hybrid_grain_policies() {
+    use synapsenet_core::{CryptoBackend, HybridPolicy, UnifiedSigningKey};
+    
+    let signing_key = UnifiedSigningKey::generate(CryptoBackend::Hybrid);
+    let grain = signed_grain(&signing_key);
+    
+    assert!(grain.verify().unwrap());
+    assert!(grain.verify_with_policy(HybridPolicy::Both).unwrap());
+    assert!(grain.verify_with_policy(HybridPolicy::Either).unwrap());
+    
+    // Forged Dilithium half: only a node accepting either signature keeps it
+    let mut forged_pqc = grain.clone();
+    let last = forged_pqc.sig.len() - 1;
+    forged_pqc.sig[last] ^= 0xff;
+    assert!(!forged_pqc.verify_with_policy(HybridPolicy::Both).unwrap());
+    assert!(forged_pqc.verify_with_policy(HybridPolicy::Either).unwrap());
+    
+    // Forged ed25519 half
+    let mut forged_classical = grain.clone();
+    forged_classical.sig[0] ^= 0xff;
+    assert!(!forged_classical.verify_with_policy(HybridPolicy::Both).unwrap());
+    assert!(forged_classical.verify_with_policy(HybridPolicy::Either).unwrap());
+    
+    // Both halves forged
+    let mut forged_both = forged_pqc.clone();
+    forged_both.sig[0] ^= 0xff;
+    assert!(!forged_both.verify_with_policy(HybridPolicy::Either).unwrap());
+    
+    // Truncated to the classical half only
+    let mut truncated = grain.clone();
+    truncated.sig.truncate(64);
+    assert!(truncated.verify_with_policy(HybridPolicy::Either).is_err());
+}
+
+#[cfg(all(feature = "classical-crypto", feature = "pqc-dilithium"))]
+#[test]
+fn test_mixed_backend_peers() {
+    use synapsenet_core::{CryptoBackend, HybridPolicy, UnifiedSigningKey};
+    
+    // A hybrid node receives grains from classical, PQC and hybrid peers
+    for backend in [CryptoBackend::Classical, CryptoBackend::PostQuantum, CryptoBackend::Hybrid] {
+        let grain = signed_grain(&UnifiedSigningKey::generate(backend));
+        assert!(grain.verify().unwrap(), "{:?} grain should verify", backend);
+        assert!(grain.verify_with_policy(HybridPolicy::Both).unwrap());
+        
+        let mut tampered = grain.clone();
+        tampered.id[0] ^= 0xff;
+        assert!(!tampered.verify_with_policy(HybridPolicy::Either).unwrap());
+    }
+    
+    // A hybrid key cannot pass as a classical or PQC one
+    let grain = signed_grain(&UnifiedSigningKey::generate(CryptoBackend::Hybrid));
+    assert!(grain.verify_with_backend(CryptoBackend::Classical).is_err());
+    assert!(!grain.verify_with_backend(CryptoBackend::PostQuantum).unwrap_or(false));
+}
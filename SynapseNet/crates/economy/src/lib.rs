@@ -1,7 +1,9 @@
 // SynapseNet Economy - NGT credits and reputation
 
 pub mod ngt;
+pub mod poe_v2;
 pub mod reputation;
 
 pub use ngt::NgtLedger;
+pub use poe_v2::{AccessEvent, AccessType, PoEScore, PoEv2Engine, ReuseTracker, RewardWeights};
 pub use reputation::ReputationSystem;
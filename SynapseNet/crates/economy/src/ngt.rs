@@ -1,26 +1,40 @@
-use std::collections::HashMap;
-use synapsenet_core::poe::Credit;
-use synapsenet_core::ProofOfEmergence;
+use anyhow::{anyhow, Result};
+use ed25519_dalek::SigningKey;
+use std::sync::{Arc, Mutex, MutexGuard};
+use synapsenet_core::{NgtTransfer, ProofOfEmergence};
+use synapsenet_storage::ledger::{self, node_account, parse_node_account};
+use synapsenet_storage::{
+    LedgerAudit, LedgerEntry, LedgerQuery, NewLedgerEntry, Store, TransferConflict,
+    TransferOutcome, TransferRecord,
+};
+use tracing::warn;
 
 /// NGT (Neural Graph Token) ledger
-#[derive(Debug, Default)]
+///
+/// Balances live in the store's double-entry ledger, so they survive
+/// restarts and every change can be audited.
 pub struct NgtLedger {
-    /// Node balances
-    balances: HashMap<[u8; 32], f64>,
+    /// Backing store
+    store: Arc<Mutex<Store>>,
     /// PoE calculator
     pub poe: ProofOfEmergence,
 }
 
 impl NgtLedger {
-    pub fn new() -> Self {
-        Self::default()
+    /// Create ledger backed by an in-memory store
+    pub fn new() -> Result<Self> {
+        Self::with_poe(ProofOfEmergence::default())
     }
 
-    pub fn with_poe(poe: ProofOfEmergence) -> Self {
-        Self {
-            balances: HashMap::new(),
-            poe,
-        }
+    /// Create ledger with custom PoE weights, backed by an in-memory store
+    pub fn with_poe(poe: ProofOfEmergence) -> Result<Self> {
+        let store = Store::new(":memory:")?;
+        Ok(Self::with_store(Arc::new(Mutex::new(store)), poe))
+    }
+
+    /// Create ledger over a shared store
+    pub fn with_store(store: Arc<Mutex<Store>>, poe: ProofOfEmergence) -> Self {
+        Self { store, poe }
     }
 
     /// Award NGT for grain contribution
@@ -30,32 +44,178 @@ impl NgtLedger {
         novelty: f32,
         coherence: f32,
         reuse_count: u32,
-    ) -> f64 {
+    ) -> Result<f64> {
         let ngt = self.poe.calculate_ngt(novelty, coherence, reuse_count);
 
         if ngt > 0.0 {
-            *self.balances.entry(node_pk).or_insert(0.0) += ngt;
+            self.store()?.post_ledger_entry(&NewLedgerEntry::reward(
+                &node_account(&node_pk),
+                ngt,
+                "PoE award",
+            ))?;
+        }
+
+        Ok(ngt)
+    }
+
+    /// Reward a grain once per epoch, returns 0 if it was already rewarded
+    pub fn reward_grain(
+        &mut self,
+        node_pk: [u8; 32],
+        grain_id: [u8; 32],
+        epoch: u64,
+        novelty: f32,
+        coherence: f32,
+        reuse_count: u32,
+    ) -> Result<f64> {
+        let ngt = self.poe.calculate_ngt(novelty, coherence, reuse_count);
+        if ngt <= 0.0 {
+            return Ok(0.0);
+        }
+
+        let entry = NewLedgerEntry::reward(&node_account(&node_pk), ngt, "PoE reward")
+            .with_idempotency_key(ledger::reward_key(&grain_id, epoch));
+
+        let outcome = self.store()?.post_ledger_entry(&entry)?;
+        Ok(if outcome.is_posted() { ngt } else { 0.0 })
+    }
+
+    /// Mint NGT to a node
+    pub fn mint(&mut self, node_pk: [u8; 32], amount: f64, memo: &str) -> Result<i64> {
+        let entry = NewLedgerEntry::mint(&node_account(&node_pk), amount, memo);
+        Ok(self.store()?.post_ledger_entry(&entry)?.entry_id())
+    }
+
+    /// Transfer NGT between nodes without a signature (local bookkeeping)
+    pub fn transfer(&mut self, from: [u8; 32], to: [u8; 32], amount: f64) -> Result<i64> {
+        if from == to {
+            return Err(anyhow!("Cannot transfer to the same node"));
         }
 
-        ngt
+        let entry = NewLedgerEntry::transfer(
+            &node_account(&from),
+            &node_account(&to),
+            amount,
+            "transfer",
+        );
+        Ok(self.store()?.post_ledger_entry(&entry)?.entry_id())
+    }
+
+    /// Sign a transfer from the key's owner and apply it locally
+    ///
+    /// Applied transfers are queued for broadcast to peers.
+    pub fn send(
+        &mut self,
+        signing_key: &SigningKey,
+        to: [u8; 32],
+        amount: f64,
+        memo: Option<String>,
+    ) -> Result<(NgtTransfer, TransferOutcome)> {
+        let from = signing_key.verifying_key().to_bytes();
+        if from == to {
+            return Err(anyhow!("Cannot transfer to the same node"));
+        }
+
+        let store = self.store()?;
+        let nonce = store.next_transfer_nonce(&from)?;
+        let transfer = NgtTransfer::new(to, amount, nonce, memo, signing_key)?;
+        let outcome = store.apply_transfer(&transfer, true)?;
+        Ok((transfer, outcome))
+    }
+
+    /// Validate a transfer received from a peer and apply it to the local ledger
+    pub fn apply_transfer(&mut self, transfer: &NgtTransfer) -> Result<TransferOutcome> {
+        if !transfer.verify().unwrap_or(false) {
+            return Ok(TransferOutcome::Rejected("Invalid signature".to_string()));
+        }
+        if !transfer.amount.is_finite() || transfer.amount <= 0.0 {
+            return Ok(TransferOutcome::Rejected("Invalid amount".to_string()));
+        }
+        if transfer.from == transfer.to {
+            return Ok(TransferOutcome::Rejected("Sender is recipient".to_string()));
+        }
+
+        let outcome = self.store()?.apply_transfer(transfer, false)?;
+        if let TransferOutcome::Conflict { .. } = outcome {
+            warn!(
+                "Double-spend detected: {} reused nonce {}",
+                node_account(&transfer.from),
+                transfer.nonce
+            );
+        }
+        Ok(outcome)
+    }
+
+    /// Signed transfers sent or received by a node, newest first
+    pub fn transfers(&self, node_pk: &[u8; 32], limit: usize) -> Result<Vec<TransferRecord>> {
+        self.store()?.list_transfers(node_pk, limit)
+    }
+
+    /// Detected double-spend attempts
+    pub fn conflicts(&self, limit: usize) -> Result<Vec<TransferConflict>> {
+        self.store()?.get_transfer_conflicts(limit)
+    }
+
+    /// Take NGT from a node as a penalty
+    pub fn penalize(&mut self, node_pk: [u8; 32], amount: f64, reason: &str) -> Result<i64> {
+        let entry = NewLedgerEntry::penalty(&node_account(&node_pk), amount, reason);
+        Ok(self.store()?.post_ledger_entry(&entry)?.entry_id())
+    }
+
+    /// Burn NGT held by a node
+    pub fn burn(&mut self, node_pk: [u8; 32], amount: f64) -> Result<i64> {
+        let entry = NewLedgerEntry::burn(&node_account(&node_pk), amount, "burn");
+        Ok(self.store()?.post_ledger_entry(&entry)?.entry_id())
     }
 
     /// Get node balance
-    pub fn balance(&self, node_pk: &[u8; 32]) -> f64 {
-        self.balances.get(node_pk).copied().unwrap_or(0.0)
+    pub fn balance(&self, node_pk: &[u8; 32]) -> Result<f64> {
+        self.store()?.get_account_balance(&node_account(node_pk))
     }
 
-    /// Get total supply
-    pub fn total_supply(&self) -> f64 {
-        self.balances.values().sum()
+    /// Get total supply (NGT held by nodes)
+    pub fn total_supply(&self) -> Result<f64> {
+        Ok(self
+            .store()?
+            .get_account_balances("node:")?
+            .iter()
+            .map(|(_, balance)| balance)
+            .sum())
     }
 
     /// Get top holders
-    pub fn top_holders(&self, n: usize) -> Vec<([u8; 32], f64)> {
-        let mut holders: Vec<_> = self.balances.iter().map(|(k, v)| (*k, *v)).collect();
-        holders.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        holders.truncate(n);
-        holders
+    pub fn top_holders(&self, n: usize) -> Result<Vec<([u8; 32], f64)>> {
+        let balances = self.store()?.get_account_balances("node:")?;
+        Ok(balances
+            .into_iter()
+            .filter_map(|(account, balance)| Some((parse_node_account(&account)?, balance)))
+            .take(n)
+            .collect())
+    }
+
+    /// Ledger history of a node, newest first
+    pub fn history(&self, node_pk: &[u8; 32], limit: usize) -> Result<Vec<LedgerEntry>> {
+        self.store()?.query_ledger(&LedgerQuery {
+            account: Some(node_account(node_pk)),
+            limit,
+            ..LedgerQuery::default()
+        })
+    }
+
+    /// Snapshot all balances, returns the last entry included
+    pub fn snapshot(&self) -> Result<i64> {
+        self.store()?.create_balance_snapshot()
+    }
+
+    /// Verify ledger consistency
+    pub fn audit(&self) -> Result<LedgerAudit> {
+        self.store()?.audit_ledger()
+    }
+
+    fn store(&self) -> Result<MutexGuard<'_, Store>> {
+        self.store
+            .lock()
+            .map_err(|_| anyhow!("Store lock poisoned"))
     }
 }
 
@@ -65,14 +225,84 @@ mod tests {
 
     #[test]
     fn test_ngt_award() {
-        let mut ledger = NgtLedger::new();
+        let mut ledger = NgtLedger::new().unwrap();
         let node_pk = [1u8; 32];
 
         // High novelty, medium coherence
-        let ngt = ledger.award(node_pk, 0.8, 0.5, 0);
+        let ngt = ledger.award(node_pk, 0.8, 0.5, 0).unwrap();
         assert!(ngt > 0.0);
 
-        assert_eq!(ledger.balance(&node_pk), ngt);
-        assert_eq!(ledger.total_supply(), ngt);
+        assert_eq!(ledger.balance(&node_pk).unwrap(), ngt);
+        assert_eq!(ledger.total_supply().unwrap(), ngt);
+    }
+
+    #[test]
+    fn test_reward_once_per_epoch() {
+        let mut ledger = NgtLedger::new().unwrap();
+        let node_pk = [1u8; 32];
+        let grain_id = [2u8; 32];
+
+        let first = ledger.reward_grain(node_pk, grain_id, 1, 0.8, 0.5, 0).unwrap();
+        let again = ledger.reward_grain(node_pk, grain_id, 1, 0.8, 0.5, 0).unwrap();
+        let next_epoch = ledger.reward_grain(node_pk, grain_id, 2, 0.8, 0.5, 0).unwrap();
+
+        assert!(first > 0.0);
+        assert_eq!(again, 0.0);
+        assert_eq!(ledger.balance(&node_pk).unwrap(), first + next_epoch);
+    }
+
+    #[test]
+    fn test_transfer_penalty_burn() {
+        let mut ledger = NgtLedger::new().unwrap();
+        let (alice, bob) = ([1u8; 32], [2u8; 32]);
+
+        ledger.mint(alice, 10.0, "genesis").unwrap();
+        ledger.transfer(alice, bob, 3.0).unwrap();
+        ledger.penalize(bob, 1.0, "spam").unwrap();
+        ledger.burn(alice, 2.0).unwrap();
+        assert!(ledger.transfer(bob, alice, 5.0).is_err());
+
+        assert_eq!(ledger.balance(&alice).unwrap(), 5.0);
+        assert_eq!(ledger.balance(&bob).unwrap(), 2.0);
+        assert_eq!(ledger.total_supply().unwrap(), 7.0);
+        assert_eq!(ledger.top_holders(1).unwrap(), vec![(alice, 5.0)]);
+        assert_eq!(ledger.history(&bob, 10).unwrap().len(), 2);
+        assert!(ledger.audit().unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_signed_transfers() {
+        use rand::{rngs::OsRng, RngCore};
+
+        let mut ledger = NgtLedger::new().unwrap();
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut secret);
+        let key = SigningKey::from_bytes(&secret);
+        let alice = key.verifying_key().to_bytes();
+        let bob = [2u8; 32];
+
+        ledger.mint(alice, 3.0, "genesis").unwrap();
+        let (sent, outcome) = ledger.send(&key, bob, 1.0, None).unwrap();
+        assert!(matches!(outcome, TransferOutcome::Applied { .. }));
+
+        // Gossip echo of our own transfer is a no-op
+        assert_eq!(ledger.apply_transfer(&sent).unwrap(), TransferOutcome::Duplicate);
+
+        let mut forged = NgtTransfer::new(bob, 1.0, sent.nonce + 1, None, &key).unwrap();
+        forged.amount = 2.0;
+        assert!(matches!(
+            ledger.apply_transfer(&forged).unwrap(),
+            TransferOutcome::Rejected(_)
+        ));
+
+        let double_spend = NgtTransfer::new([3u8; 32], 1.0, sent.nonce, None, &key).unwrap();
+        assert!(matches!(
+            ledger.apply_transfer(&double_spend).unwrap(),
+            TransferOutcome::Conflict { .. }
+        ));
+
+        assert_eq!(ledger.balance(&alice).unwrap(), 2.0);
+        assert_eq!(ledger.transfers(&bob, 10).unwrap().len(), 1);
+        assert_eq!(ledger.conflicts(10).unwrap().len(), 1);
     }
 }
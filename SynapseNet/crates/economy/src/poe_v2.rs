@@ -1,13 +1,16 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::SystemTime;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use synapsenet_core::Grain;
-use synapsenet_storage::{HnswIndex, Store};
+use synapsenet_core::{EconomyConfig, Grain};
+use synapsenet_storage::{HnswIndex, PoeScoreRecord, Store};
+
+/// Grains rescored per pass
+const RESCORE_BATCH: usize = 100;
 
 /// PoE v2 score with three components
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,6 +68,15 @@ impl Default for RewardWeights {
 }
 
 impl RewardWeights {
+    /// Weights from economy configuration
+    pub fn from_config(config: &EconomyConfig) -> Self {
+        Self {
+            novelty: config.novelty_weight,
+            coherence: config.coherence_weight,
+            reuse: config.reuse_weight,
+        }
+    }
+
     /// Validate weights sum to 1.0
     pub fn validate(&self) -> Result<()> {
         let sum = self.novelty + self.coherence + self.reuse;
@@ -79,44 +91,169 @@ impl RewardWeights {
 }
 
 /// PoE v2 engine
+///
+/// Scores grains as they are ingested, tracks access for reuse and pays
+/// the author through the ledger whenever a grain's reward grows.
 pub struct PoEv2Engine {
-    store: Arc<tokio::sync::Mutex<Store>>,
+    store: Arc<Mutex<Store>>,
     index: Arc<RwLock<HnswIndex<'static>>>,
-    reuse_tracker: Arc<RwLock<ReuseTracker>>,
     weights: RewardWeights,
+    config: EconomyConfig,
 }
 
 impl PoEv2Engine {
     /// Create new PoE v2 engine
     pub fn new(
-        store: Arc<tokio::sync::Mutex<Store>>,
+        store: Arc<Mutex<Store>>,
         index: Arc<RwLock<HnswIndex<'static>>>,
-        reuse_tracker: Arc<RwLock<ReuseTracker>>,
-        weights: RewardWeights,
+        config: EconomyConfig,
     ) -> Result<Self> {
+        let weights = RewardWeights::from_config(&config);
         weights.validate()?;
-        
+
         Ok(Self {
             store,
             index,
-            reuse_tracker,
             weights,
+            config,
         })
     }
 
+    /// Whether PoE rewards are enabled
+    pub fn is_enabled(&self) -> bool {
+        self.config.poe_enabled
+    }
+
+    /// Store and index a new grain, scoring it and crediting its author
+    ///
+    /// Returns `None` if the grain was already known or PoE is disabled.
+    pub async fn ingest(&self, grain: &Grain) -> Result<Option<PoEScore>> {
+        if self.store()?.get_grain(&grain.id)?.is_some() {
+            debug!("Grain {:?} already ingested", hex_encode(&grain.id[..8]));
+            return Ok(None);
+        }
+
+        // Grain timestamps are author-claimed, so a revocation should start
+        // no later than the suspected leak
+        if self
+            .store()?
+            .is_key_revoked_at(&grain.meta.author_pk, grain.meta.ts_unix_ms)?
+        {
+            return Err(anyhow::anyhow!(
+                "Grain {} is signed by a revoked key",
+                hex_encode(&grain.id[..8])
+            ));
+        }
+
+        // Novelty is measured against what we knew before this grain
+        let score = if self.is_enabled() {
+            Some(self.calculate_score(grain).await?)
+        } else {
+            None
+        };
+
+        self.store()?.insert_grain(grain)?;
+        self.index.write().await.add(grain)?;
+
+        if let Some(ref score) = score {
+            let credited = self.save_score(grain, score)?;
+            info!(
+                "Grain {:?} scored {:.2}, credited {:.4} NGT",
+                hex_encode(&grain.id[..8]),
+                score.total,
+                credited
+            );
+        }
+
+        Ok(score)
+    }
+
+    /// Record that grains were accessed, e.g. returned by a query
+    pub fn record_access(
+        &self,
+        grain_ids: &[[u8; 32]],
+        peer_id: &str,
+        access_type: AccessType,
+    ) -> Result<()> {
+        if !self.config.track_access {
+            return Ok(());
+        }
+
+        let store = self.store()?;
+        for grain_id in grain_ids {
+            store.record_grain_access(grain_id, peer_id, access_type.as_str())?;
+        }
+        Ok(())
+    }
+
+    /// Rescore grains accessed since their last score, returns how many
+    pub async fn rescore(&self) -> Result<usize> {
+        if !self.is_enabled() {
+            return Ok(0);
+        }
+
+        let retention_secs = self.config.access_retention_days as i64 * 86_400;
+        let cutoff = chrono::Utc::now().timestamp() - retention_secs;
+        let grain_ids = {
+            let store = self.store()?;
+            store.cleanup_old_access_events(cutoff)?;
+            store.get_grains_to_rescore(RESCORE_BATCH)?
+        };
+
+        let mut rescored = 0;
+        for grain_id in grain_ids {
+            let (previous, grain) = {
+                let store = self.store()?;
+                (store.get_poe_score(&grain_id)?, store.get_grain(&grain_id)?)
+            };
+            let (Some(previous), Some(grain)) = (previous, grain) else {
+                continue;
+            };
+
+            // Novelty is fixed at ingestion, the grain is now in the index
+            let coherence = self.calculate_coherence(&grain).await?;
+            let reuse = self.calculate_reuse(&grain_id)?;
+            let score = PoEScore::new(previous.novelty, coherence, reuse, &self.weights);
+
+            let credited = self.save_score(&grain, &score)?;
+            if credited > 0.0 {
+                info!(
+                    "Grain {:?} reuse {:.2}, credited {:.4} NGT",
+                    hex_encode(&grain_id[..8]),
+                    reuse,
+                    credited
+                );
+            }
+            rescored += 1;
+        }
+
+        Ok(rescored)
+    }
+
+    /// Rescore periodically, as configured by `rescore_interval_secs`
+    pub async fn run(self: Arc<Self>) {
+        let interval = Duration::from_secs(self.config.rescore_interval_secs.max(1));
+        loop {
+            tokio::time::sleep(interval).await;
+            if let Err(e) = self.rescore().await {
+                warn!("PoE rescoring failed: {}", e);
+            }
+        }
+    }
+
     /// Calculate PoE score for a grain
     pub async fn calculate_score(&self, grain: &Grain) -> Result<PoEScore> {
         // Calculate novelty
-        let novelty = self.calculate_novelty(&grain.vec).await?;
-        
+        let novelty = self.calculate_novelty(grain).await?;
+
         // Calculate coherence
         let coherence = self.calculate_coherence(grain).await?;
-        
+
         // Calculate reuse
-        let reuse = self.calculate_reuse(&grain.id).await?;
-        
+        let reuse = self.calculate_reuse(&grain.id)?;
+
         let score = PoEScore::new(novelty, coherence, reuse, &self.weights);
-        
+
         debug!(
             "PoE score for grain {:?}: novelty={:.2}, coherence={:.2}, reuse={:.2}, total={:.2}",
             hex_encode(&grain.id[..8]),
@@ -125,50 +262,63 @@ impl PoEv2Engine {
             score.reuse,
             score.total
         );
-        
+
         Ok(score)
     }
 
+    /// NGT earned by a score, nothing below the novelty threshold
+    pub fn calculate_ngt_reward(&self, score: &PoEScore) -> f64 {
+        if score.novelty < self.config.min_novelty_threshold {
+            return 0.0;
+        }
+        score.to_ngt_reward() as f64
+    }
+
     /// Calculate novelty score
-    async fn calculate_novelty(&self, embedding: &[f32]) -> Result<f32> {
+    async fn calculate_novelty(&self, grain: &Grain) -> Result<f32> {
         let index = self.index.read().await;
-        
+
         // Search for K nearest neighbors
-        let neighbors = index.search(embedding, 10)?;
-        
+        let neighbors: Vec<_> = index
+            .search(&grain.vec, 10)?
+            .into_iter()
+            .filter(|n| n.grain_id != grain.id)
+            .collect();
+
         if neighbors.is_empty() {
             // First grain = maximum novelty
             return Ok(1.0);
         }
-        
+
         // Calculate average similarity
-        let avg_similarity: f32 = neighbors.iter().map(|n| n.similarity).sum::<f32>() / neighbors.len() as f32;
-        
+        let avg_similarity: f32 =
+            neighbors.iter().map(|n| n.similarity).sum::<f32>() / neighbors.len() as f32;
+
         // Novelty = 1 - similarity
-        let novelty = (1.0 - avg_similarity).max(0.0).min(1.0);
-        
-        Ok(novelty)
+        Ok((1.0 - avg_similarity).clamp(0.0, 1.0))
     }
 
     /// Calculate coherence score
     async fn calculate_coherence(&self, grain: &Grain) -> Result<f32> {
-        let index = self.index.read().await;
-        
         // Find related grains (similarity > 0.6)
-        let related = index.search(&grain.vec, 20)?
+        let related = self
+            .index
+            .read()
+            .await
+            .search(&grain.vec, 20)?
             .into_iter()
-            .filter(|r| r.similarity > 0.6)
+            .filter(|r| r.similarity > 0.6 && r.grain_id != grain.id)
             .collect::<Vec<_>>();
-        
+
         if related.len() < 2 {
             // No connections = no coherence
             return Ok(0.0);
         }
-        
+
         // Calculate topic diversity
-        let store = self.store.lock().await;
+        let store = self.store()?;
         let mut all_tags = HashSet::new();
-        
+
         for result in &related {
             if let Ok(Some(related_grain)) = store.get_grain(&result.grain_id) {
                 for tag in &related_grain.meta.tags {
@@ -176,33 +326,61 @@ impl PoEv2Engine {
                 }
             }
         }
-        
+
         // Topic diversity = unique tags / total connections
-        let diversity = if related.len() > 0 {
-            (all_tags.len() as f32 / related.len() as f32).min(1.0)
-        } else {
-            0.0
-        };
-        
+        let diversity = (all_tags.len() as f32 / related.len() as f32).min(1.0);
+
         // Connection score = number of connections (normalized)
         let connection_score = (related.len() as f32 / 20.0).min(1.0);
-        
+
         // Coherence = connections × diversity
-        let coherence = connection_score * diversity;
-        
-        Ok(coherence)
+        Ok(connection_score * diversity)
     }
 
-    /// Calculate reuse score
-    async fn calculate_reuse(&self, grain_id: &[u8; 32]) -> Result<f32> {
-        let tracker = self.reuse_tracker.read().await;
-        Ok(tracker.calculate_reuse_score(grain_id))
+    /// Calculate reuse score from recorded access events
+    fn calculate_reuse(&self, grain_id: &[u8; 32]) -> Result<f32> {
+        let (accesses, unique_peers) = self.store()?.get_grain_access_stats(grain_id)?;
+        Ok(reuse_score(accesses, unique_peers))
+    }
+
+    /// Persist a score and pay the author what it newly earned
+    fn save_score(&self, grain: &Grain, score: &PoEScore) -> Result<f64> {
+        self.store()?.save_poe_score(&PoeScoreRecord {
+            grain_id: grain.id,
+            author_pk: grain.meta.author_pk.clone(),
+            novelty: score.novelty,
+            coherence: score.coherence,
+            reuse: score.reuse,
+            total: score.total,
+            ngt_reward: self.calculate_ngt_reward(score),
+            credited: 0.0,
+            epoch: 0,
+            calculated_at: chrono::Utc::now().timestamp(),
+        })
     }
 
     /// Get reward weights
     pub fn weights(&self) -> &RewardWeights {
         &self.weights
     }
+
+    fn store(&self) -> Result<MutexGuard<'_, Store>> {
+        self.store
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Store lock poisoned"))
+    }
+}
+
+/// Reuse score from access frequency and peer diversity (log scale)
+pub fn reuse_score(accesses: usize, unique_peers: usize) -> f32 {
+    if accesses == 0 {
+        return 0.0;
+    }
+
+    let frequency_score = ((accesses as f32).log10() / 3.0).min(1.0);
+    let diversity_score = ((unique_peers as f32).log10() / 2.0).min(1.0);
+
+    (frequency_score + diversity_score).min(1.0)
 }
 
 /// Reuse tracker for monitoring grain access
@@ -221,12 +399,9 @@ impl ReuseTracker {
 
     /// Record grain access
     pub fn record_access(&mut self, grain_id: [u8; 32], event: AccessEvent) {
-        self.access_log
-            .entry(grain_id)
-            .or_insert_with(Vec::new)
-            .push(event);
-        
         debug!("Recorded {:?} access for grain {:?}", event.access_type, hex_encode(&grain_id[..8]));
+
+        self.access_log.entry(grain_id).or_default().push(event);
     }
 
     /// Calculate reuse score for a grain
@@ -236,21 +411,10 @@ impl ReuseTracker {
             None => return 0.0,
         };
         
-        if events.is_empty() {
-            return 0.0;
-        }
-        
         // Count unique peers
         let unique_peers: HashSet<_> = events.iter().map(|e| &e.peer_id).collect();
-        
-        // Frequency score (log scale)
-        let frequency_score = ((events.len() as f32).log10() / 3.0).min(1.0);
-        
-        // Diversity score (log scale)
-        let diversity_score = ((unique_peers.len() as f32).log10() / 2.0).min(1.0);
-        
-        // Combined score
-        (frequency_score + diversity_score).min(1.0)
+
+        reuse_score(events.len(), unique_peers.len())
     }
 
     /// Get access count for grain
@@ -305,13 +469,21 @@ pub enum AccessType {
     Reference,
 }
 
+impl AccessType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessType::Search => "search",
+            AccessType::Retrieve => "retrieve",
+            AccessType::Reference => "reference",
+        }
+    }
+}
+
 // Helper for hex encoding
 fn hex_encode(bytes: &[u8]) -> String {
     bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
-use std::collections::HashSet;
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,265 +1,231 @@
 //! Unit tests for PoE v2 economic model
 
-use synapsenet_economy::{PoEScore, PoEv2Engine, RewardWeights, ReuseTracker};
-use synapsenet_core::{Grain, GrainMeta, UnifiedSigningKey, CryptoBackend};
-use synapsenet_storage::{Store, HnswIndex};
-use std::sync::Arc;
-use tokio::sync::{Mutex, RwLock};
-use tempfile::TempDir;
-
-fn create_test_grain(text: &str, tags: Vec<String>) -> Grain {
-    let vec = vec![0.1f32; 384];
+use ed25519_dalek::SigningKey;
+use rand::{rngs::OsRng, RngCore};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use synapsenet_core::{CryptoBackend, EconomyConfig, Grain, GrainMeta, KeySuccession};
+use synapsenet_economy::{
+    AccessEvent, AccessType, PoEScore, PoEv2Engine, ReuseTracker, RewardWeights,
+};
+use synapsenet_storage::{HnswIndex, Store};
+use tokio::sync::RwLock;
+
+fn signing_key() -> SigningKey {
+    let mut secret_bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut secret_bytes);
+    SigningKey::from_bytes(&secret_bytes)
+}
+
+fn create_test_grain(vec: Vec<f32>, tags: Vec<String>, signing_key: &SigningKey) -> Grain {
     let meta = GrainMeta {
-        author_pk: [0u8; 32],
+        author_pk: signing_key.verifying_key().to_bytes().to_vec(),
         crypto_backend: CryptoBackend::Classical,
         ts_unix_ms: chrono::Utc::now().timestamp_millis(),
         tags,
         mime: "text/plain".to_string(),
         lang: "en".to_string(),
-        title: Some(text.to_string()),
+        title: None,
         summary: None,
         embedding_model: Some("test-model".to_string()),
-        embedding_dimensions: Some(384),
+        embedding_dimensions: Some(vec.len()),
     };
-    
-    let signing_key = UnifiedSigningKey::generate_classical();
-    Grain::new(vec, meta, &signing_key).unwrap()
+
+    Grain::new(vec, meta, signing_key).unwrap()
 }
 
-#[test]
-fn test_poe_score_creation() {
-    let score = PoEScore {
-        novelty: 0.8,
-        coherence: 0.6,
-        reuse: 0.4,
-        total: 0.6,
-    };
-    
-    assert_eq!(score.novelty, 0.8);
-    assert_eq!(score.coherence, 0.6);
-    assert_eq!(score.reuse, 0.4);
-    assert_eq!(score.total, 0.6);
+fn create_engine(config: EconomyConfig) -> (PoEv2Engine, Arc<Mutex<Store>>) {
+    let store = Arc::new(Mutex::new(Store::new(":memory:").unwrap()));
+    let index = Arc::new(RwLock::new(HnswIndex::new(1000, 3)));
+    let engine = PoEv2Engine::new(store.clone(), index, config).unwrap();
+    (engine, store)
 }
 
 #[test]
 fn test_reward_weights_default() {
     let weights = RewardWeights::default();
-    
+
     assert_eq!(weights.novelty, 0.4);
     assert_eq!(weights.coherence, 0.3);
     assert_eq!(weights.reuse, 0.3);
-    
-    // Weights should sum to 1.0
-    let sum = weights.novelty + weights.coherence + weights.reuse;
-    assert!((sum - 1.0).abs() < 0.001);
+    assert!(weights.validate().is_ok());
 }
 
 #[test]
-fn test_reward_weights_custom() {
-    let weights = RewardWeights {
-        novelty: 0.5,
-        coherence: 0.3,
-        reuse: 0.2,
+fn test_reward_weights_from_config() {
+    let config = EconomyConfig {
+        novelty_weight: 0.5,
+        coherence_weight: 0.3,
+        reuse_weight: 0.2,
+        ..EconomyConfig::default()
     };
-    
+
+    let weights = RewardWeights::from_config(&config);
     assert_eq!(weights.novelty, 0.5);
-    assert_eq!(weights.coherence, 0.3);
     assert_eq!(weights.reuse, 0.2);
-}
 
-#[test]
-fn test_reuse_tracker_creation() {
-    let tracker = ReuseTracker::new();
-    assert_eq!(tracker.get_access_count(&[0u8; 32]), 0);
-}
-
-#[test]
-fn test_reuse_tracker_record_access() {
-    let tracker = ReuseTracker::new();
-    let grain_id = [1u8; 32];
-    
-    // Record some accesses
-    tracker.record_access(&grain_id, "peer1", "search");
-    tracker.record_access(&grain_id, "peer2", "retrieve");
-    tracker.record_access(&grain_id, "peer1", "search");
-    
-    // Should have 3 accesses
-    assert_eq!(tracker.get_access_count(&grain_id), 3);
+    let invalid = EconomyConfig {
+        novelty_weight: 0.9,
+        ..EconomyConfig::default()
+    };
+    let store = Arc::new(Mutex::new(Store::new(":memory:").unwrap()));
+    let index = Arc::new(RwLock::new(HnswIndex::new(10, 3)));
+    assert!(PoEv2Engine::new(store, index, invalid).is_err());
 }
 
 #[test]
 fn test_reuse_tracker_unique_peers() {
-    let tracker = ReuseTracker::new();
+    let mut tracker = ReuseTracker::new();
     let grain_id = [1u8; 32];
-    
-    tracker.record_access(&grain_id, "peer1", "search");
-    tracker.record_access(&grain_id, "peer2", "search");
-    tracker.record_access(&grain_id, "peer1", "retrieve");
-    
-    let unique_peers = tracker.get_unique_peers(&grain_id);
-    assert_eq!(unique_peers, 2);
-}
 
-#[test]
-fn test_reuse_score_calculation() {
-    let tracker = ReuseTracker::new();
-    let grain_id = [1u8; 32];
-    
-    // No accesses = 0 score
     assert_eq!(tracker.calculate_reuse_score(&grain_id), 0.0);
-    
-    // Add some accesses
+
     for i in 0..10 {
-        tracker.record_access(&grain_id, &format!("peer{}", i), "search");
+        tracker.record_access(
+            grain_id,
+            AccessEvent {
+                timestamp: SystemTime::now(),
+                peer_id: format!("peer{}", i % 5),
+                access_type: AccessType::Search,
+            },
+        );
     }
-    
+
     let score = tracker.calculate_reuse_score(&grain_id);
+    assert_eq!(tracker.get_access_count(&grain_id), 10);
     assert!(score > 0.0 && score <= 1.0);
 }
 
 #[tokio::test]
-async fn test_poe_engine_creation() {
-    let temp_dir = TempDir::new().unwrap();
-    let db_path = temp_dir.path().join("test.db");
-    
-    let store = Arc::new(Mutex::new(Store::new(&db_path).unwrap()));
-    let index = Arc::new(RwLock::new(HnswIndex::new(1000, 384)));
-    let reuse_tracker = Arc::new(ReuseTracker::new());
-    let weights = RewardWeights::default();
-    
-    let engine = PoEv2Engine::new(
-        store,
-        index,
-        reuse_tracker,
-        weights,
-        0.1,
-    );
-    
-    assert!(engine.is_ok());
+async fn test_ingest_first_grain_credits_author() {
+    let (engine, store) = create_engine(EconomyConfig::default());
+    let key = signing_key();
+    let grain = create_test_grain(vec![1.0, 0.0, 0.0], vec!["rust".to_string()], &key);
+
+    let score = engine.ingest(&grain).await.unwrap().unwrap();
+    assert_eq!(score.novelty, 1.0);
+
+    let store = store.lock().unwrap();
+    assert!(store.get_grain(&grain.id).unwrap().is_some());
+    let saved = store.get_poe_score(&grain.id).unwrap().unwrap();
+    let balance = store
+        .get_node_ngt(&key.verifying_key().to_bytes())
+        .unwrap();
+    assert_eq!(saved.ngt_reward, score.to_ngt_reward() as f64);
+    assert!((balance - saved.ngt_reward).abs() < 1e-6);
 }
 
 #[tokio::test]
-async fn test_novelty_calculation_first_grain() {
-    let temp_dir = TempDir::new().unwrap();
-    let db_path = temp_dir.path().join("test.db");
-    
-    let store = Arc::new(Mutex::new(Store::new(&db_path).unwrap()));
-    let index = Arc::new(RwLock::new(HnswIndex::new(1000, 384)));
-    let reuse_tracker = Arc::new(ReuseTracker::new());
-    let weights = RewardWeights::default();
-    
-    let engine = PoEv2Engine::new(
-        store,
-        index,
-        reuse_tracker,
-        weights,
-        0.1,
-    ).unwrap();
-    
-    let grain = create_test_grain("First grain", vec!["test".to_string()]);
-    
-    // First grain should have maximum novelty
-    let score = engine.calculate_score(&grain).await.unwrap();
-    assert_eq!(score.novelty, 1.0);
+async fn test_ingest_duplicate_is_ignored() {
+    let (engine, store) = create_engine(EconomyConfig::default());
+    let key = signing_key();
+    let grain = create_test_grain(vec![1.0, 0.0, 0.0], vec![], &key);
+
+    assert!(engine.ingest(&grain).await.unwrap().is_some());
+    assert!(engine.ingest(&grain).await.unwrap().is_none());
+
+    let balance = store
+        .lock()
+        .unwrap()
+        .get_node_ngt(&key.verifying_key().to_bytes())
+        .unwrap();
+    assert!((balance - 5.0).abs() < 1e-6); // 1 + 0.4 * 10
 }
 
 #[tokio::test]
-async fn test_ngt_reward_calculation() {
-    let temp_dir = TempDir::new().unwrap();
-    let db_path = temp_dir.path().join("test.db");
-    
-    let store = Arc::new(Mutex::new(Store::new(&db_path).unwrap()));
-    let index = Arc::new(RwLock::new(HnswIndex::new(1000, 384)));
-    let reuse_tracker = Arc::new(ReuseTracker::new());
-    let weights = RewardWeights::default();
-    
-    let engine = PoEv2Engine::new(
-        store,
-        index,
-        reuse_tracker,
-        weights,
-        0.1,
-    ).unwrap();
-    
-    // Test various scores
-    let score1 = PoEScore {
-        novelty: 1.0,
-        coherence: 1.0,
-        reuse: 1.0,
-        total: 1.0,
-    };
-    let reward1 = engine.calculate_ngt_reward(&score1);
-    assert_eq!(reward1, 11.0); // 1 + 1.0 * 10
-    
-    let score2 = PoEScore {
-        novelty: 0.5,
-        coherence: 0.5,
-        reuse: 0.5,
-        total: 0.5,
-    };
-    let reward2 = engine.calculate_ngt_reward(&score2);
-    assert_eq!(reward2, 6.0); // 1 + 0.5 * 10
-    
-    let score3 = PoEScore {
-        novelty: 0.0,
-        coherence: 0.0,
-        reuse: 0.0,
-        total: 0.0,
-    };
-    let reward3 = engine.calculate_ngt_reward(&score3);
-    assert_eq!(reward3, 1.0); // 1 + 0.0 * 10
+async fn test_reuse_rescoring_pays_difference() {
+    let (engine, store) = create_engine(EconomyConfig::default());
+    let key = signing_key();
+    let grain = create_test_grain(vec![0.0, 1.0, 0.0], vec![], &key);
+    engine.ingest(&grain).await.unwrap();
+
+    let author = key.verifying_key().to_bytes();
+    let before = store.lock().unwrap().get_node_ngt(&author).unwrap();
+
+    for peer in ["peer1", "peer2", "peer3", "peer4"] {
+        engine
+            .record_access(&[grain.id], peer, AccessType::Search)
+            .unwrap();
+    }
+
+    // Access events are stored with second precision
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    for peer in ["peer5", "peer6"] {
+        engine
+            .record_access(&[grain.id], peer, AccessType::Search)
+            .unwrap();
+    }
+    assert_eq!(engine.rescore().await.unwrap(), 1);
+
+    let store = store.lock().unwrap();
+    let saved = store.get_poe_score(&grain.id).unwrap().unwrap();
+    let after = store.get_node_ngt(&author).unwrap();
+    assert!(saved.reuse > 0.0);
+    assert!(after > before);
+    assert!((after - saved.ngt_reward).abs() < 1e-6);
 }
 
-#[test]
-fn test_poe_score_bounds() {
-    // All scores should be between 0 and 1
-    let score = PoEScore {
-        novelty: 0.8,
-        coherence: 0.6,
-        reuse: 0.4,
-        total: 0.6,
+#[tokio::test]
+async fn test_config_disables_tracking_and_rewards() {
+    let config = EconomyConfig {
+        poe_enabled: false,
+        track_access: false,
+        ..EconomyConfig::default()
     };
-    
-    assert!(score.novelty >= 0.0 && score.novelty <= 1.0);
-    assert!(score.coherence >= 0.0 && score.coherence <= 1.0);
-    assert!(score.reuse >= 0.0 && score.reuse <= 1.0);
-    assert!(score.total >= 0.0 && score.total <= 1.0);
+    let (engine, store) = create_engine(config);
+    let key = signing_key();
+    let grain = create_test_grain(vec![0.0, 0.0, 1.0], vec![], &key);
+
+    assert!(engine.ingest(&grain).await.unwrap().is_none());
+    engine
+        .record_access(&[grain.id], "peer1", AccessType::Search)
+        .unwrap();
+
+    let store = store.lock().unwrap();
+    assert!(store.get_grain(&grain.id).unwrap().is_some());
+    assert!(store.get_poe_score(&grain.id).unwrap().is_none());
+    assert_eq!(store.get_grain_access_count(&grain.id).unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_ingest_rejects_grains_from_revoked_key() {
+    let (engine, store) = create_engine(EconomyConfig::default());
+    let old_key = signing_key();
+    let new_key = signing_key();
+
+    let before = create_test_grain(vec![1.0, 0.0, 0.0], vec![], &old_key);
+    let revoked_from = before.meta.ts_unix_ms + 1;
+    let succession = KeySuccession::new(&old_key, &new_key, Some(revoked_from)).unwrap();
+    store
+        .lock()
+        .unwrap()
+        .apply_key_succession(&succession, false)
+        .unwrap();
+
+    // Content signed before the revocation time is still accepted
+    assert!(engine.ingest(&before).await.is_ok());
+
+    let mut meta = before.meta.clone();
+    meta.ts_unix_ms = revoked_from;
+    let after = Grain::new(vec![0.0, 1.0, 0.0], meta, &old_key).unwrap();
+    assert!(engine.ingest(&after).await.is_err());
+    assert!(store.lock().unwrap().get_grain(&after.id).unwrap().is_none());
+
+    let rotated = create_test_grain(vec![0.0, 0.0, 1.0], vec![], &new_key);
+    assert!(engine.ingest(&rotated).await.is_ok());
 }
 
 #[test]
-fn test_ngt_reward_bounds() {
-    let temp_dir = TempDir::new().unwrap();
-    let db_path = temp_dir.path().join("test.db");
-    
-    let store = Arc::new(Mutex::new(Store::new(&db_path).unwrap()));
-    let index = Arc::new(RwLock::new(HnswIndex::new(1000, 384)));
-    let reuse_tracker = Arc::new(ReuseTracker::new());
-    let weights = RewardWeights::default();
-    
-    let engine = PoEv2Engine::new(
-        store,
-        index,
-        reuse_tracker,
-        weights,
-        0.1,
-    ).unwrap();
-    
-    // Test boundary conditions
-    let min_score = PoEScore {
-        novelty: 0.0,
-        coherence: 0.0,
-        reuse: 0.0,
-        total: 0.0,
+fn test_novelty_threshold() {
+    let config = EconomyConfig {
+        min_novelty_threshold: 0.5,
+        ..EconomyConfig::default()
     };
-    let min_reward = engine.calculate_ngt_reward(&min_score);
-    assert_eq!(min_reward, 1.0);
-    
-    let max_score = PoEScore {
-        novelty: 1.0,
-        coherence: 1.0,
-        reuse: 1.0,
-        total: 1.0,
-    };
-    let max_reward = engine.calculate_ngt_reward(&max_score);
-    assert_eq!(max_reward, 11.0);
+    let (engine, _store) = create_engine(config);
+    let weights = RewardWeights::default();
+
+    let stale = PoEScore::new(0.2, 1.0, 1.0, &weights);
+    let novel = PoEScore::new(1.0, 1.0, 1.0, &weights);
+    assert_eq!(engine.calculate_ngt_reward(&stale), 0.0);
+    assert_eq!(engine.calculate_ngt_reward(&novel), 11.0);
 }
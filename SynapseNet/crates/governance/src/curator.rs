@@ -1,51 +1,201 @@
-use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use anyhow::Result;
+use rand::{rngs::OsRng, RngCore};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use synapsenet_core::{CuratorDecision, SignedRecord};
+use synapsenet_storage::{
+    CuratorAuditEntry, CuratorTask, CuratorTaskStatus, DecisionOutcome, NewCuratorTask, Store,
+};
+use uuid::Uuid;
 
-/// Curator review queue
-#[derive(Debug, Default)]
+use crate::policy::{Classification, Route};
+
+/// Curator review queue, persisted in the node's store
+///
+/// Curators are identified by their public keys and act through signed
+/// `CuratorDecision`s, each of which lands in the audit log. Requesters see
+/// their outcome with the release token handed out when the task is filed.
 pub struct CuratorQueue {
-    queue: VecDeque<CuratorTask>,
+    store: Arc<Mutex<Store>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CuratorTask {
-    pub id: String,
-    pub query: String,
-    pub grain_ids: Vec<[u8; 32]>,
-    pub submitted_at: i64,
-    pub status: TaskStatus,
+/// A newly filed task and the token that releases its outcome
+#[derive(Debug, Clone)]
+pub struct SubmittedTask {
+    pub task: CuratorTask,
+    /// Secret for the requester only; the store keeps just its hash
+    pub release_token: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum TaskStatus {
-    Pending,
-    InReview,
-    Approved,
-    Rejected,
+/// What a requester can see of their task
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CuratorRelease {
+    pub task_id: Uuid,
+    pub status: &'static str,
+    /// Curator note, once decided
+    pub note: Option<String>,
+    /// Held result, only once approved
+    pub outcome: Option<serde_json::Value>,
 }
 
 impl CuratorQueue {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(store: Arc<Mutex<Store>>) -> Self {
+        Self { store }
+    }
+
+    /// File content held by `classification` for review
+    ///
+    /// `held` is what the holder of the returned release token gets if a
+    /// curator approves.
+    pub fn submit(
+        &self,
+        route: Route,
+        subject: &str,
+        requester: Option<&str>,
+        classification: &Classification,
+        held: Option<serde_json::Value>,
+    ) -> Result<SubmittedTask> {
+        let mut token = [0u8; 32];
+        OsRng.fill_bytes(&mut token);
+        let release_token = hex::encode(token);
+
+        let task = NewCuratorTask {
+            route: route.as_str().to_string(),
+            subject: subject.to_string(),
+            requester: requester.map(str::to_string),
+            classification: serde_json::to_value(classification)?,
+            outcome: held,
+            release_token_hash: Some(*blake3::hash(release_token.as_bytes()).as_bytes()),
+        };
+        let task = self.store.lock().unwrap().insert_curator_task(&task)?;
+        Ok(SubmittedTask {
+            task,
+            release_token,
+        })
+    }
+
+    /// Verify and apply a curator's decision
+    pub fn decide(&self, decision: &CuratorDecision) -> Result<DecisionOutcome> {
+        if !decision.verify()? {
+            anyhow::bail!("Invalid curator signature");
+        }
+        self.store.lock().unwrap().apply_curator_decision(decision)
+    }
+
+    /// The task as its requester may see it
+    ///
+    /// `None` unless `release_token` is the one `submit` returned for it.
+    pub fn release(
+        &self,
+        task_id: &Uuid,
+        release_token: &str,
+    ) -> Result<Option<CuratorRelease>> {
+        let Some(task) = self.get(task_id)? else {
+            return Ok(None);
+        };
+        // blake3::Hash compares in constant time
+        let presented = blake3::hash(release_token.as_bytes());
+        if task.release_token_hash.map(blake3::Hash::from) != Some(presented) {
+            return Ok(None);
+        }
+
+        let approved = task.status == CuratorTaskStatus::Approved;
+        Ok(Some(CuratorRelease {
+            task_id: task.id,
+            status: task.status.as_str(),
+            note: task.note,
+            outcome: task.outcome.filter(|_| approved),
+        }))
+    }
+
+    pub fn get(&self, task_id: &Uuid) -> Result<Option<CuratorTask>> {
+        self.store.lock().unwrap().get_curator_task(task_id)
     }
 
-    /// Add task to queue
-    pub fn submit(&mut self, task: CuratorTask) {
-        self.queue.push_back(task);
+    /// Tasks, oldest first
+    pub fn list(
+        &self,
+        status: Option<CuratorTaskStatus>,
+        limit: usize,
+    ) -> Result<Vec<CuratorTask>> {
+        self.store.lock().unwrap().list_curator_tasks(status, limit)
     }
 
-    /// Get next pending task
-    pub fn next(&mut self) -> Option<CuratorTask> {
-        self.queue.pop_front()
+    /// Applied decisions, oldest first
+    pub fn audit(&self, task_id: Option<&Uuid>, limit: usize) -> Result<Vec<CuratorAuditEntry>> {
+        self.store.lock().unwrap().curator_audit(task_id, limit)
     }
 
-    /// Queue length
-    pub fn len(&self) -> usize {
-        self.queue.len()
+    pub fn add_curator(&self, public_key: &[u8; 32], name: Option<&str>) -> Result<bool> {
+        self.store.lock().unwrap().add_curator(public_key, name)
     }
 
-    /// Check if queue is empty
-    pub fn is_empty(&self) -> bool {
-        self.queue.is_empty()
+    pub fn remove_curator(&self, public_key: &[u8; 32]) -> Result<bool> {
+        self.store.lock().unwrap().remove_curator(public_key)
+    }
+
+    pub fn curators(&self) -> Result<Vec<([u8; 32], Option<String>)>> {
+        self.store.lock().unwrap().list_curators()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{PolicyEngine, PolicyInput};
+    use ed25519_dalek::SigningKey;
+    use synapsenet_core::CuratorAction;
+
+    #[test]
+    fn test_release_after_approval() {
+        let queue = CuratorQueue::new(Arc::new(Mutex::new(Store::new(":memory:").unwrap())));
+        let curator = SigningKey::from_bytes(&[7u8; 32]);
+        queue
+            .add_curator(&curator.verifying_key().to_bytes(), Some("curator"))
+            .unwrap();
+
+        let classification = PolicyEngine::new(crate::Policy::default())
+            .evaluate(Route::Query, &PolicyInput::new("weapons history"));
+        let SubmittedTask {
+            task,
+            release_token,
+        } = queue
+            .submit(
+                Route::Query,
+                "weapons history",
+                Some("client-1"),
+                &classification,
+                Some(serde_json::json!(["result"])),
+            )
+            .unwrap();
+        assert_eq!(task.classification["class"], "analysis_only");
+
+        // Held until approved, and only for the token holder
+        let release = queue.release(&task.id, &release_token).unwrap().unwrap();
+        assert_eq!(release.status, "pending");
+        assert_eq!(release.outcome, None);
+        assert_eq!(queue.release(&task.id, "client-1").unwrap(), None);
+        assert_eq!(queue.release(&task.id, "").unwrap(), None);
+
+        let mut forged = CuratorDecision::new(task.id, CuratorAction::Approve, None, &curator);
+        forged.note = Some("edited".to_string());
+        assert!(queue.decide(&forged).is_err());
+
+        let approve = CuratorDecision::new(
+            task.id,
+            CuratorAction::Approve,
+            Some("educational".to_string()),
+            &curator,
+        );
+        assert!(matches!(
+            queue.decide(&approve).unwrap(),
+            DecisionOutcome::Applied(_)
+        ));
+
+        let release = queue.release(&task.id, &release_token).unwrap().unwrap();
+        assert_eq!(release.status, "approved");
+        assert_eq!(release.note.as_deref(), Some("educational"));
+        assert_eq!(release.outcome, Some(serde_json::json!(["result"])));
+        assert_eq!(queue.audit(Some(&task.id), 10).unwrap().len(), 1);
     }
 }
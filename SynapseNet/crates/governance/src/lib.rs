@@ -1,7 +1,12 @@
-// SynapseNet Governance - Policy engine and curation
+// SynapseNet Governance - Policy engine, curation and moderation
 
 pub mod curator;
+pub mod moderation;
 pub mod policy;
 
-pub use curator::CuratorQueue;
-pub use policy::{Policy, PolicyClass, PolicyEngine};
+pub use curator::{CuratorQueue, CuratorRelease, SubmittedTask};
+pub use moderation::Moderator;
+pub use policy::{
+    Classification, Policy, PolicyClass, PolicyEngine, PolicyInput, PolicyRule, PolicyViolation,
+    Route, RuleMatch, RuleMatcher,
+};
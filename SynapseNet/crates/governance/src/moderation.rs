@@ -0,0 +1,346 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use synapsenet_core::{GrainFlag, ModerationConfig, SignedRecord};
+use synapsenet_storage::ledger::node_account;
+use synapsenet_storage::{FlagOutcome, GrainModeration, ModerationStatus, Store};
+use tracing::info;
+
+/// Largest factor reputation can scale a node's trust by
+const MAX_REPUTATION_FACTOR: f64 = 3.0;
+
+/// Community moderation: aggregates signed flags into local quarantine
+///
+/// Each node weighs the flags it receives by how much it trusts the signer
+/// and quarantines a grain once the weight reaches the configured threshold.
+/// Quarantined grains stay stored but are hidden from search. Authors can
+/// appeal; the operator releases the grain or upholds the quarantine.
+pub struct Moderator {
+    store: Arc<Mutex<Store>>,
+    config: ModerationConfig,
+    /// This node's key; its own flags quarantine right away
+    local_pk: [u8; 32],
+}
+
+impl Moderator {
+    pub fn new(store: Arc<Mutex<Store>>, config: ModerationConfig, local_pk: [u8; 32]) -> Self {
+        Self {
+            store,
+            config,
+            local_pk,
+        }
+    }
+
+    /// Record a flag message signed on this node and queue it for broadcast
+    pub fn submit(&self, flag: &GrainFlag) -> Result<FlagOutcome> {
+        self.apply(flag, true)
+    }
+
+    /// Record a flag message received from the network
+    pub fn receive(&self, flag: &GrainFlag) -> Result<FlagOutcome> {
+        self.apply(flag, false)
+    }
+
+    /// Weight of a signer's flags
+    ///
+    /// Nodes listed in `trust` weigh their trust times `1 + ln(1 + balance)`
+    /// of their NGT balance. Other nodes weigh `default_trust` times
+    /// `ln(1 + balance)`, so keys without stake weigh nothing and flooding
+    /// flags from fresh identities does not pay. Both factors cap at 3.
+    pub fn flag_weight(&self, signer: &[u8; 32]) -> Result<f64> {
+        let store = self.store.lock().unwrap();
+        self.weigh(&store, signer)
+    }
+
+    /// Recompute this node's verdict on a grain from the flags it holds
+    pub fn reevaluate(&self, grain_id: &[u8; 32]) -> Result<Option<GrainModeration>> {
+        let store = self.store.lock().unwrap();
+        let current = store.get_grain_moderation(grain_id)?;
+        self.evaluate(&store, grain_id, current)
+    }
+
+    /// Operator release: show the grain again
+    ///
+    /// Flags received so far no longer count; new flags can quarantine the
+    /// grain again.
+    pub fn release(&self, grain_id: &[u8; 32]) -> Result<Option<GrainModeration>> {
+        let store = self.store.lock().unwrap();
+        let Some(mut current) = store.get_grain_moderation(grain_id)? else {
+            return Ok(None);
+        };
+
+        current.cleared_at = Some(unix_now_millis());
+        self.evaluate(&store, grain_id, Some(current))
+    }
+
+    /// Operator dismisses the author's appeal; the grain stays quarantined
+    pub fn uphold(&self, grain_id: &[u8; 32]) -> Result<Option<GrainModeration>> {
+        let store = self.store.lock().unwrap();
+        let Some(mut current) = store.get_grain_moderation(grain_id)? else {
+            return Ok(None);
+        };
+        if current.status != ModerationStatus::Appealed {
+            anyhow::bail!("Grain has no pending appeal");
+        }
+
+        current.appeal_dismissed_at = Some(unix_now_millis());
+        self.evaluate(&store, grain_id, Some(current))
+    }
+
+    pub fn status(&self, grain_id: &[u8; 32]) -> Result<Option<GrainModeration>> {
+        self.store.lock().unwrap().get_grain_moderation(grain_id)
+    }
+
+    /// Moderated grains, most recently updated first
+    pub fn list(
+        &self,
+        status: Option<ModerationStatus>,
+        limit: usize,
+    ) -> Result<Vec<GrainModeration>> {
+        self.store
+            .lock()
+            .unwrap()
+            .list_grain_moderation(status, limit)
+    }
+
+    /// Flag messages on a grain, oldest first
+    pub fn flags(&self, grain_id: &[u8; 32]) -> Result<Vec<GrainFlag>> {
+        self.store.lock().unwrap().grain_flags(grain_id)
+    }
+
+    /// Grains hidden from search
+    pub fn hidden_grain_ids(&self) -> Result<HashSet<[u8; 32]>> {
+        self.store.lock().unwrap().quarantined_grain_ids()
+    }
+
+    fn apply(&self, flag: &GrainFlag, pending_broadcast: bool) -> Result<FlagOutcome> {
+        if !flag.verify()? {
+            anyhow::bail!("Invalid flag signature");
+        }
+
+        let outcome = self
+            .store
+            .lock()
+            .unwrap()
+            .apply_grain_flag(flag, pending_broadcast)?;
+        if outcome == FlagOutcome::Recorded {
+            self.reevaluate(&flag.grain_id)?;
+        }
+        Ok(outcome)
+    }
+
+    fn weigh(&self, store: &Store, signer: &[u8; 32]) -> Result<f64> {
+        if *signer == self.local_pk {
+            return Ok(self.config.quarantine_threshold);
+        }
+
+        let stake = store
+            .get_account_balance(&node_account(signer))?
+            .max(0.0)
+            .ln_1p();
+        let weight = match self.config.trust.get(&hex::encode(signer)) {
+            Some(trust) => trust * (1.0 + stake).min(MAX_REPUTATION_FACTOR),
+            None => self.config.default_trust * stake.min(MAX_REPUTATION_FACTOR),
+        };
+
+        Ok(weight)
+    }
+
+    fn evaluate(
+        &self,
+        store: &Store,
+        grain_id: &[u8; 32],
+        current: Option<GrainModeration>,
+    ) -> Result<Option<GrainModeration>> {
+        let cleared_at = current.as_ref().and_then(|m| m.cleared_at);
+        let appeal_dismissed_at = current.as_ref().and_then(|m| m.appeal_dismissed_at);
+
+        let mut flag_weight = 0.0;
+        for flag in store.active_grain_flags(grain_id, cleared_at)? {
+            flag_weight += self.weigh(store, &flag.signer)?;
+        }
+        if current.is_none() && flag_weight == 0.0 {
+            return Ok(None);
+        }
+
+        let status = if flag_weight >= self.config.quarantine_threshold {
+            let appeal =
+                store.latest_grain_appeal(grain_id, cleared_at.max(appeal_dismissed_at))?;
+            if appeal.is_some() {
+                ModerationStatus::Appealed
+            } else {
+                ModerationStatus::Quarantined
+            }
+        } else if cleared_at.is_some() {
+            ModerationStatus::Cleared
+        } else {
+            ModerationStatus::Flagged
+        };
+
+        if status.is_hidden() && !current.as_ref().is_some_and(|m| m.status.is_hidden()) {
+            info!(
+                "Grain {} quarantined (flag weight {:.2})",
+                hex::encode(&grain_id[..8]),
+                flag_weight
+            );
+        }
+
+        let moderation = GrainModeration {
+            grain_id: *grain_id,
+            status,
+            flag_weight,
+            cleared_at,
+            appeal_dismissed_at,
+            updated_at: unix_now_millis(),
+        };
+        store.put_grain_moderation(&moderation)?;
+        Ok(Some(moderation))
+    }
+}
+
+fn unix_now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use synapsenet_core::{CryptoBackend, FlagAction, FlagReason, Grain, GrainMeta};
+
+    fn grain_by(author: &SigningKey) -> Grain {
+        let meta = GrainMeta {
+            author_pk: author.verifying_key().to_bytes().to_vec(),
+            crypto_backend: CryptoBackend::Classical,
+            ts_unix_ms: 1,
+            tags: vec![],
+            mime: "text/plain".to_string(),
+            lang: "en".to_string(),
+            title: Some("Buy followers".to_string()),
+            summary: None,
+            embedding_model: None,
+            embedding_dimensions: None,
+        };
+        Grain::new(vec![0.1, 0.2], meta, author).unwrap()
+    }
+
+    fn spam(grain: &Grain, key: &SigningKey) -> GrainFlag {
+        GrainFlag::new(grain.id, FlagAction::Flag(FlagReason::Spam), None, key)
+    }
+
+    #[test]
+    fn test_quarantine_appeal_and_release() {
+        let store = Arc::new(Mutex::new(Store::new(":memory:").unwrap()));
+        let author = SigningKey::from_bytes(&[1u8; 32]);
+        let peers: Vec<SigningKey> = (2..5).map(|i| SigningKey::from_bytes(&[i; 32])).collect();
+        let ignored = SigningKey::from_bytes(&[9u8; 32]);
+
+        let mut config = ModerationConfig::default();
+        config
+            .trust
+            .insert(hex::encode(ignored.verifying_key().to_bytes()), 0.0);
+        for peer in &peers {
+            config
+                .trust
+                .insert(hex::encode(peer.verifying_key().to_bytes()), 1.0);
+        }
+        let moderator = Moderator::new(store.clone(), config, [0u8; 32]);
+
+        let grain = grain_by(&author);
+        store.lock().unwrap().insert_grain(&grain).unwrap();
+
+        // Untrusted flags do not count
+        moderator.receive(&spam(&grain, &ignored)).unwrap();
+        assert_eq!(moderator.status(&grain.id).unwrap(), None);
+
+        moderator.receive(&spam(&grain, &peers[0])).unwrap();
+        moderator.receive(&spam(&grain, &peers[1])).unwrap();
+        let state = moderator.status(&grain.id).unwrap().unwrap();
+        assert_eq!(state.status, ModerationStatus::Flagged);
+        assert_eq!(state.flag_weight, 2.0);
+
+        moderator.receive(&spam(&grain, &peers[2])).unwrap();
+        assert!(moderator.hidden_grain_ids().unwrap().contains(&grain.id));
+
+        // Forged flags are refused
+        let mut forged = spam(&grain, &peers[0]);
+        forged.ts_unix_ms += 1;
+        assert!(moderator.receive(&forged).is_err());
+
+        let appeal = GrainFlag::new(grain.id, FlagAction::Appeal, None, &author);
+        moderator.receive(&appeal).unwrap();
+        assert_eq!(
+            moderator.status(&grain.id).unwrap().unwrap().status,
+            ModerationStatus::Appealed
+        );
+
+        let upheld = moderator.uphold(&grain.id).unwrap().unwrap();
+        assert_eq!(upheld.status, ModerationStatus::Quarantined);
+
+        let released = moderator.release(&grain.id).unwrap().unwrap();
+        assert_eq!(released.status, ModerationStatus::Cleared);
+        assert_eq!(released.flag_weight, 0.0);
+        assert!(moderator.hidden_grain_ids().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unflag_and_reputation() {
+        let store = Arc::new(Mutex::new(Store::new(":memory:").unwrap()));
+        let author = SigningKey::from_bytes(&[1u8; 32]);
+        let peer = SigningKey::from_bytes(&[2u8; 32]);
+        let peer_pk = peer.verifying_key().to_bytes();
+        let moderator = Moderator::new(store.clone(), ModerationConfig::default(), [0u8; 32]);
+
+        assert_eq!(moderator.flag_weight(&peer_pk).unwrap(), 0.0);
+        assert_eq!(
+            moderator.flag_weight(&[0u8; 32]).unwrap(),
+            ModerationConfig::default().quarantine_threshold
+        );
+
+        store
+            .lock()
+            .unwrap()
+            .post_ledger_entry(&synapsenet_storage::NewLedgerEntry::mint(
+                &node_account(&peer_pk),
+                1000.0,
+                "test",
+            ))
+            .unwrap();
+        assert_eq!(
+            moderator.flag_weight(&peer_pk).unwrap(),
+            MAX_REPUTATION_FACTOR
+        );
+
+        let grain = grain_by(&author);
+        store.lock().unwrap().insert_grain(&grain).unwrap();
+        moderator.submit(&spam(&grain, &peer)).unwrap();
+        assert!(moderator.hidden_grain_ids().unwrap().contains(&grain.id));
+
+        let unflag = GrainFlag::new(grain.id, FlagAction::Unflag, None, &peer);
+        moderator.submit(&unflag).unwrap();
+        let state = moderator.status(&grain.id).unwrap().unwrap();
+        assert_eq!(state.status, ModerationStatus::Flagged);
+        assert_eq!(state.flag_weight, 0.0);
+    }
+
+    #[test]
+    fn test_fresh_keys_cannot_quarantine() {
+        let store = Arc::new(Mutex::new(Store::new(":memory:").unwrap()));
+        let author = SigningKey::from_bytes(&[1u8; 32]);
+        let moderator = Moderator::new(store.clone(), ModerationConfig::default(), [0u8; 32]);
+
+        let grain = grain_by(&author);
+        store.lock().unwrap().insert_grain(&grain).unwrap();
+
+        for i in 2..5u8 {
+            let sybil = SigningKey::from_bytes(&[i; 32]);
+            moderator.receive(&spam(&grain, &sybil)).unwrap();
+        }
+
+        assert_eq!(moderator.status(&grain.id).unwrap(), None);
+        assert!(moderator.hidden_grain_ids().unwrap().is_empty());
+    }
+}
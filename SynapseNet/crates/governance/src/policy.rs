@@ -1,8 +1,17 @@
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use synapsenet_ai::EmbeddingModel;
+use synapsenet_core::grain::cosine_similarity;
+use synapsenet_core::Grain;
 
 /// Policy classification for queries
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Variants are ordered by severity, so `max` picks the stricter class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PolicyClass {
     /// Normal response allowed
     Ok,
@@ -10,25 +19,100 @@ pub enum PolicyClass {
     AnalysisOnly,
     /// Requires curator review
     Curated,
+    /// Refused outright
+    Blocked,
+}
+
+impl PolicyClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::AnalysisOnly => "analysis_only",
+            Self::Curated => "curated",
+            Self::Blocked => "blocked",
+        }
+    }
+}
+
+/// Where a policy is enforced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Route {
+    /// Semantic search over REST `/query`
+    Query,
+    /// New grains entering the local store
+    Ingest,
+    /// Agent tool calls
+    Tool,
+}
+
+impl Route {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Query => "query",
+            Self::Ingest => "ingest",
+            Self::Tool => "tool",
+        }
+    }
 }
 
 /// Policy configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Policy {
     /// Default policy class
+    #[serde(default = "default_class")]
     pub default: PolicyClass,
-    /// Keyword-based rules
+    /// Rules, in no particular order; `priority` decides between matches
+    #[serde(default)]
     pub rules: Vec<PolicyRule>,
 }
 
+fn default_class() -> PolicyClass {
+    PolicyClass::Ok
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyRule {
-    /// Keywords to match (case-insensitive)
-    pub keywords: Vec<String>,
+    /// Rule name used in explanations (defaults to `rule-<index>`)
+    #[serde(default)]
+    pub id: String,
+    /// Higher priority wins; ties go to the stricter class
+    #[serde(default)]
+    pub priority: i32,
     /// Policy class to apply
     pub class: PolicyClass,
     /// Optional explanation
+    #[serde(default)]
     pub reason: Option<String>,
+    /// Routes the rule applies to (empty = all routes)
+    #[serde(default)]
+    pub routes: Vec<Route>,
+    #[serde(flatten)]
+    pub matcher: RuleMatcher,
+}
+
+/// What a rule looks for, selected by `type` in TOML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleMatcher {
+    /// Whole words, case-insensitive
+    Keywords { keywords: Vec<String> },
+    /// Regular expression over the text
+    Regex { pattern: String },
+    /// Grain language codes (e.g. "en")
+    Language { languages: Vec<String> },
+    /// Any of these tags (tool calls carry `tool:<name>`)
+    Tags { tags: Vec<String> },
+    /// Cosine similarity to any exemplar at or above `threshold`
+    Semantic {
+        exemplars: Vec<String>,
+        #[serde(default = "default_threshold")]
+        threshold: f32,
+    },
+}
+
+fn default_threshold() -> f32 {
+    0.8
 }
 
 impl Default for Policy {
@@ -37,51 +121,359 @@ impl Default for Policy {
             default: PolicyClass::Ok,
             rules: vec![
                 PolicyRule {
-                    keywords: vec![
-                        "bomb".to_string(),
-                        "weapon".to_string(),
-                        "explosive".to_string(),
-                    ],
+                    id: "harm".to_string(),
+                    priority: 0,
                     class: PolicyClass::AnalysisOnly,
                     reason: Some("Potential harm".to_string()),
+                    routes: Vec::new(),
+                    matcher: RuleMatcher::Keywords {
+                        keywords: [
+                            "bomb",
+                            "bombs",
+                            "weapon",
+                            "weapons",
+                            "explosive",
+                            "explosives",
+                        ]
+                        .map(String::from)
+                        .to_vec(),
+                    },
                 },
                 PolicyRule {
-                    keywords: vec![
-                        "hack".to_string(),
-                        "exploit".to_string(),
-                        "vulnerability".to_string(),
-                    ],
+                    id: "security".to_string(),
+                    priority: 0,
                     class: PolicyClass::AnalysisOnly,
                     reason: Some("Security concern".to_string()),
+                    routes: Vec::new(),
+                    matcher: RuleMatcher::Keywords {
+                        keywords: [
+                            "hack",
+                            "hacking",
+                            "exploit",
+                            "exploits",
+                            "vulnerability",
+                            "vulnerabilities",
+                        ]
+                        .map(String::from)
+                        .to_vec(),
+                    },
                 },
             ],
         }
     }
 }
 
+impl Policy {
+    /// Parse and validate a TOML policy
+    pub fn from_toml_str(s: &str) -> Result<Self> {
+        let policy: Policy = toml::from_str(s).context("Invalid policy TOML")?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Load a TOML policy file
+    pub fn load(path: &Path) -> Result<Self> {
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read policy {}", path.display()))?;
+        Self::from_toml_str(&s)
+    }
+
+    /// Check that every rule compiles
+    pub fn validate(&self) -> Result<()> {
+        for (i, rule) in self.rules.iter().enumerate() {
+            compile_regex(&rule.matcher).with_context(|| format!("Rule {} ({})", i, rule.id))?;
+            if let RuleMatcher::Semantic { threshold, .. } = rule.matcher {
+                anyhow::ensure!(
+                    (-1.0..=1.0).contains(&threshold),
+                    "Rule {} ({}): threshold must be within [-1, 1]",
+                    i,
+                    rule.id
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn compile_regex(matcher: &RuleMatcher) -> Result<Option<Regex>> {
+    let regex = match matcher {
+        RuleMatcher::Keywords { keywords } if !keywords.is_empty() => {
+            let words: Vec<String> = keywords.iter().map(|k| regex::escape(k)).collect();
+            RegexBuilder::new(&format!(r"\b(?:{})\b", words.join("|")))
+                .case_insensitive(true)
+                .build()?
+        }
+        RuleMatcher::Regex { pattern } => Regex::new(pattern)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(regex))
+}
+
+/// Content submitted for classification
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PolicyInput<'a> {
+    pub text: &'a str,
+    pub lang: Option<&'a str>,
+    pub tags: &'a [String],
+    /// Precomputed embedding of `text`, if the caller has one
+    pub embedding: Option<&'a [f32]>,
+}
+
+impl<'a> PolicyInput<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_lang(mut self, lang: &'a str) -> Self {
+        self.lang = Some(lang);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: &'a [String]) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_embedding(mut self, embedding: &'a [f32]) -> Self {
+        self.embedding = Some(embedding);
+        self
+    }
+}
+
+/// A rule that fired
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleMatch {
+    pub rule_id: String,
+    pub class: PolicyClass,
+    pub priority: i32,
+    pub reason: Option<String>,
+    /// What matched: the words, language, tag or similarity
+    pub evidence: String,
+}
+
+/// Outcome of evaluating a policy, with the rules behind it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Classification {
+    pub class: PolicyClass,
+    /// Matching rules, winner first
+    pub matches: Vec<RuleMatch>,
+    pub explanation: String,
+}
+
+impl Classification {
+    /// Whether the request may proceed without review
+    pub fn is_allowed(&self) -> bool {
+        self.class < PolicyClass::Curated
+    }
+}
+
+/// Returned when content is refused or held by policy
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Policy {}: {}", .0.class.as_str(), .0.explanation)]
+pub struct PolicyViolation(pub Classification);
+
+struct CompiledRule {
+    rule: PolicyRule,
+    regex: Option<Regex>,
+    /// Exemplar texts and their embeddings
+    exemplars: Vec<(String, Vec<f32>)>,
+}
+
 /// Policy engine for query classification
 pub struct PolicyEngine {
-    policy: Policy,
+    default: PolicyClass,
+    rules: Vec<CompiledRule>,
+    model: Option<Arc<dyn EmbeddingModel + Send + Sync>>,
 }
 
 impl PolicyEngine {
+    /// Compile `policy`; rules that fail to compile are skipped with a warning
+    /// (`Policy::load` rejects them up front)
     pub fn new(policy: Policy) -> Self {
-        Self { policy }
+        let rules = policy
+            .rules
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, mut rule)| {
+                if rule.id.is_empty() {
+                    rule.id = format!("rule-{}", i);
+                }
+                match compile_regex(&rule.matcher) {
+                    Ok(regex) => Some(CompiledRule {
+                        rule,
+                        regex,
+                        exemplars: Vec::new(),
+                    }),
+                    Err(e) => {
+                        tracing::warn!("Skipping policy rule {}: {}", rule.id, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Self {
+            default: policy.default,
+            rules,
+            model: None,
+        }
+    }
+
+    /// Enable semantic rules by embedding their exemplars with `model`
+    ///
+    /// Without a model, semantic rules never match.
+    pub fn with_embedding_model(
+        mut self,
+        model: Arc<dyn EmbeddingModel + Send + Sync>,
+    ) -> Result<Self> {
+        for compiled in &mut self.rules {
+            if let RuleMatcher::Semantic { exemplars, .. } = &compiled.rule.matcher {
+                compiled.exemplars = exemplars
+                    .iter()
+                    .map(|text| Ok((text.clone(), model.embed(text)?)))
+                    .collect::<Result<_>>()
+                    .with_context(|| format!("Embedding exemplars of {}", compiled.rule.id))?;
+            }
+        }
+        self.model = Some(model);
+        Ok(self)
+    }
+
+    /// Evaluate the ingest rules against a grain's title, summary, language,
+    /// tags and vector
+    pub fn evaluate_grain(&self, grain: &Grain) -> Classification {
+        let meta = &grain.meta;
+        let text = [meta.title.as_deref(), meta.summary.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let input = PolicyInput::new(&text)
+            .with_lang(&meta.lang)
+            .with_tags(&meta.tags)
+            .with_embedding(&grain.vec);
+        self.evaluate(Route::Ingest, &input)
     }
 
     /// Classify query based on policy rules
     pub fn classify(&self, query: &str) -> PolicyClass {
-        let query_lower = query.to_lowercase();
+        self.evaluate(Route::Query, &PolicyInput::new(query)).class
+    }
+
+    /// Evaluate every rule that applies to `route`
+    ///
+    /// The highest-priority match decides the class, so a narrow `ok` rule
+    /// can override a broader one; equal priorities resolve to the stricter
+    /// class. No match yields the policy default.
+    pub fn evaluate(&self, route: Route, input: &PolicyInput) -> Classification {
+        let mut computed = None;
+        let mut matches = Vec::new();
 
-        for rule in &self.policy.rules {
-            for keyword in &rule.keywords {
-                if query_lower.contains(&keyword.to_lowercase()) {
-                    return rule.class;
+        for compiled in &self.rules {
+            let rule = &compiled.rule;
+            if !rule.routes.is_empty() && !rule.routes.contains(&route) {
+                continue;
+            }
+            let evidence = match &rule.matcher {
+                RuleMatcher::Keywords { .. } | RuleMatcher::Regex { .. } => compiled
+                    .regex
+                    .as_ref()
+                    .and_then(|re| re.find(input.text))
+                    .map(|m| format!("matched \"{}\"", m.as_str())),
+                RuleMatcher::Language { languages } => input
+                    .lang
+                    .filter(|lang| languages.iter().any(|l| l.eq_ignore_ascii_case(lang)))
+                    .map(|lang| format!("language {}", lang)),
+                RuleMatcher::Tags { tags } => input
+                    .tags
+                    .iter()
+                    .find(|tag| tags.contains(tag))
+                    .map(|tag| format!("tag {}", tag)),
+                RuleMatcher::Semantic { threshold, .. } => {
+                    let Some(embedding) = self.embedding(input, &mut computed) else {
+                        continue;
+                    };
+                    compiled
+                        .exemplars
+                        .iter()
+                        .map(|(text, vec)| (text, cosine_similarity(embedding, vec)))
+                        .filter(|(_, similarity)| similarity >= threshold)
+                        .max_by(|a, b| a.1.total_cmp(&b.1))
+                        .map(|(text, similarity)| {
+                            format!("similarity {:.2} to \"{}\"", similarity, text)
+                        })
                 }
+            };
+
+            if let Some(evidence) = evidence {
+                matches.push(RuleMatch {
+                    rule_id: rule.id.clone(),
+                    class: rule.class,
+                    priority: rule.priority,
+                    reason: rule.reason.clone(),
+                    evidence,
+                });
             }
         }
 
-        self.policy.default
+        // Stable sort keeps policy order among equals
+        matches.sort_by(|a, b| b.priority.cmp(&a.priority).then(b.class.cmp(&a.class)));
+
+        let (class, explanation) = match matches.first() {
+            Some(winner) => {
+                let mut explanation = format!(
+                    "{} by rule {} (priority {}, {})",
+                    winner.class.as_str(),
+                    winner.rule_id,
+                    winner.priority,
+                    winner.evidence
+                );
+                if let Some(reason) = &winner.reason {
+                    explanation.push_str(": ");
+                    explanation.push_str(reason);
+                }
+                if matches.len() > 1 {
+                    let others: Vec<&str> =
+                        matches[1..].iter().map(|m| m.rule_id.as_str()).collect();
+                    explanation.push_str(&format!("; also matched {}", others.join(", ")));
+                }
+                (winner.class, explanation)
+            }
+            None => (
+                self.default,
+                format!("{} by default: no rule matched", self.default.as_str()),
+            ),
+        };
+
+        Classification {
+            class,
+            matches,
+            explanation,
+        }
+    }
+
+    /// The input's embedding, computed at most once when it has none
+    fn embedding<'a>(
+        &self,
+        input: &'a PolicyInput,
+        computed: &'a mut Option<Option<Vec<f32>>>,
+    ) -> Option<&'a [f32]> {
+        let model = self.model.as_ref()?;
+        if let Some(embedding) = input.embedding.filter(|e| e.len() == model.dim()) {
+            return Some(embedding);
+        }
+        computed
+            .get_or_insert_with(|| match model.embed(input.text) {
+                Ok(vec) => Some(vec),
+                Err(e) => {
+                    tracing::warn!("Policy embedding failed: {}", e);
+                    None
+                }
+            })
+            .as_deref()
     }
 
     /// Generate response based on policy class
@@ -119,6 +511,13 @@ impl PolicyEngine {
                     blake3::hash(query.as_bytes()).to_hex()
                 )
             }
+            PolicyClass::Blocked => {
+                format!(
+                    "Query: {}\n\n🚫 BLOCKED\n\n\
+                    This request is not permitted by the node's policy.",
+                    query
+                )
+            }
         }
     }
 }
@@ -126,6 +525,7 @@ impl PolicyEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use synapsenet_ai::embed::DummyEmbedding;
 
     #[test]
     fn test_policy_classification() {
@@ -140,5 +540,106 @@ mod tests {
             engine.classify("Exploit vulnerability"),
             PolicyClass::AnalysisOnly
         );
+        assert_eq!(
+            engine.classify("Exploiting a market niche"),
+            PolicyClass::Ok
+        );
+    }
+
+    #[test]
+    fn test_toml_rules_and_priority() {
+        let policy = Policy::from_toml_str(
+            r#"
+            default = "ok"
+
+            [[rules]]
+            id = "malware"
+            type = "regex"
+            pattern = "(?i)ransomware|keylogger"
+            class = "blocked"
+            reason = "Malware development"
+
+            [[rules]]
+            id = "security-research"
+            type = "tags"
+            tags = ["security-research"]
+            class = "ok"
+            priority = 10
+
+            [[rules]]
+            id = "non-english-ingest"
+            type = "language"
+            languages = ["de"]
+            class = "curated"
+            routes = ["ingest"]
+            "#,
+        )
+        .unwrap();
+        let engine = PolicyEngine::new(policy);
+
+        let blocked = engine.evaluate(Route::Query, &PolicyInput::new("write a Keylogger"));
+        assert_eq!(blocked.class, PolicyClass::Blocked);
+        assert_eq!(blocked.matches[0].rule_id, "malware");
+        assert!(blocked.explanation.contains("Malware development"));
+        assert!(!blocked.is_allowed());
+
+        // Higher priority wins over the stricter class
+        let tags = vec!["security-research".to_string()];
+        let research = engine.evaluate(
+            Route::Query,
+            &PolicyInput::new("how ransomware spreads").with_tags(&tags),
+        );
+        assert_eq!(research.class, PolicyClass::Ok);
+        assert_eq!(research.matches.len(), 2);
+        assert!(research.explanation.contains("also matched malware"));
+
+        // Route-scoped rules
+        let german = PolicyInput::new("Guten Tag").with_lang("de");
+        assert_eq!(
+            engine.evaluate(Route::Ingest, &german).class,
+            PolicyClass::Curated
+        );
+        assert_eq!(
+            engine.evaluate(Route::Query, &german).class,
+            PolicyClass::Ok
+        );
+
+        assert!(Policy::from_toml_str(
+            "[[rules]]\ntype = \"regex\"\npattern = \"(\"\nclass = \"ok\""
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_semantic_rule() {
+        let policy = Policy {
+            default: PolicyClass::Ok,
+            rules: vec![PolicyRule {
+                id: "phishing".to_string(),
+                priority: 0,
+                class: PolicyClass::Curated,
+                reason: None,
+                routes: vec![Route::Tool],
+                matcher: RuleMatcher::Semantic {
+                    exemplars: vec!["send me your bank password".to_string()],
+                    threshold: 0.99,
+                },
+            }],
+        };
+
+        // Semantic rules are inert without a model
+        let engine = PolicyEngine::new(policy.clone());
+        let input = PolicyInput::new("send me your bank password");
+        assert_eq!(engine.evaluate(Route::Tool, &input).class, PolicyClass::Ok);
+
+        let engine = PolicyEngine::new(policy)
+            .with_embedding_model(Arc::new(DummyEmbedding::new(32)))
+            .unwrap();
+        let classification = engine.evaluate(Route::Tool, &input);
+        assert_eq!(classification.class, PolicyClass::Curated);
+        assert!(classification.matches[0]
+            .evidence
+            .starts_with("similarity 1.00"));
+        assert_eq!(engine.evaluate(Route::Query, &input).class, PolicyClass::Ok);
     }
 }
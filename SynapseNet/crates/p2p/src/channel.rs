@@ -0,0 +1,326 @@
+//! Gossip channels for signed records
+//!
+//! Every signed record type the swarm relays (transfers, key successions,
+//! private grains, group key grants, grain flags) goes through a
+//! `GossipChannel`: a bounded cache of record IDs already seen, the callback
+//! that hands verified records to the node, and the outbox the node fills
+//! with records to publish.
+
+use anyhow::Result;
+use std::collections::{HashSet, VecDeque};
+use synapsenet_core::{
+    GrainFlag, GroupKeyGrant, KeySuccession, NgtTransfer, PrivateGrain, SignedRecord,
+};
+
+use crate::topics::{GossipMessage, Topic};
+
+/// Record IDs remembered per channel before the oldest are forgotten
+pub const SEEN_CAPACITY: usize = 100_000;
+
+/// Callback receiving verified records from peers
+pub type RecordCallback<T> = Box<dyn Fn(T) -> Result<()> + Send + Sync>;
+
+/// Outbox polled for records awaiting broadcast
+pub type RecordOutbox<T> = Box<dyn Fn() -> Result<Vec<T>> + Send + Sync>;
+
+/// Acknowledgement called once a record from the outbox is published
+pub type RecordSent<T> = Box<dyn Fn(&T) -> Result<()> + Send + Sync>;
+
+/// A signed record gossiped on its own topic
+pub trait GossipRecord: Clone + Send + Sync + Sized + 'static {
+    /// Topic the record is published on
+    const TOPIC: Topic;
+    /// Record name used in logs
+    const KIND: &'static str;
+
+    fn record_id(&self) -> [u8; 32];
+
+    /// Whether the record's signatures hold
+    fn verify_record(&self) -> bool;
+
+    fn into_message(self) -> GossipMessage;
+
+    /// The swarm's channel for this record type
+    fn channel(channels: &mut Channels) -> &mut GossipChannel<Self>;
+
+    /// Signer and signing time, checked against revoked keys
+    fn signed_by(&self) -> Option<(&[u8], i64)> {
+        None
+    }
+
+    /// Key this record revokes and the time it is revoked from
+    fn revokes(&self) -> Option<([u8; 32], i64)> {
+        None
+    }
+}
+
+/// Bounded set of recently seen IDs; the oldest are evicted first
+pub struct SeenCache {
+    ids: HashSet<[u8; 32]>,
+    order: VecDeque<[u8; 32]>,
+    capacity: usize,
+}
+
+impl SeenCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            ids: HashSet::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Remember an ID; false if it was already known
+    pub fn insert(&mut self, id: [u8; 32]) -> bool {
+        if !self.ids.insert(id) {
+            return false;
+        }
+
+        self.order.push_back(id);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    pub fn contains(&self, id: &[u8; 32]) -> bool {
+        self.ids.contains(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+/// Seen cache, callback and outbox of one record type
+pub struct GossipChannel<T> {
+    seen: SeenCache,
+    callback: Option<RecordCallback<T>>,
+    outbox: Option<RecordOutbox<T>>,
+    on_sent: Option<RecordSent<T>>,
+}
+
+impl<T> Default for GossipChannel<T> {
+    fn default() -> Self {
+        Self::new(SEEN_CAPACITY)
+    }
+}
+
+impl<T> GossipChannel<T> {
+    pub fn new(seen_capacity: usize) -> Self {
+        Self {
+            seen: SeenCache::new(seen_capacity),
+            callback: None,
+            outbox: None,
+            on_sent: None,
+        }
+    }
+
+    /// Set the callback for records received from peers
+    ///
+    /// Records reach the callback after their signatures are verified, and
+    /// only until the callback accepts one: an error leaves the record to be
+    /// delivered again if a peer sends it again.
+    pub fn set_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(T) -> Result<()> + Send + Sync + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+    }
+
+    /// Set the outbox polled for local records to broadcast
+    ///
+    /// The outbox returns every record still awaiting broadcast, on each
+    /// poll; `on_sent` is called once a record is published, so a persistent
+    /// outbox only drops it then and nothing is lost to a failed publish or
+    /// a restart.
+    pub fn set_outbox<F, S>(&mut self, outbox: F, on_sent: S)
+    where
+        F: Fn() -> Result<Vec<T>> + Send + Sync + 'static,
+        S: Fn(&T) -> Result<()> + Send + Sync + 'static,
+    {
+        self.outbox = Some(Box::new(outbox));
+        self.on_sent = Some(Box::new(on_sent));
+    }
+
+    /// Remember a record ID; false if it was already seen
+    pub fn mark_seen(&mut self, id: [u8; 32]) -> bool {
+        self.seen.insert(id)
+    }
+
+    pub fn seen(&self) -> &SeenCache {
+        &self.seen
+    }
+
+    pub(crate) fn deliver(&self, record: T) -> Result<()> {
+        match self.callback {
+            Some(ref callback) => callback(record),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn poll_outbox(&self) -> Result<Vec<T>> {
+        match self.outbox {
+            Some(ref outbox) => outbox(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub(crate) fn sent(&self, record: &T) -> Result<()> {
+        match self.on_sent {
+            Some(ref on_sent) => on_sent(record),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The swarm's gossip channels, one per record type
+#[derive(Default)]
+pub struct Channels {
+    pub(crate) transfers: GossipChannel<NgtTransfer>,
+    pub(crate) successions: GossipChannel<KeySuccession>,
+    pub(crate) private_grains: GossipChannel<PrivateGrain>,
+    pub(crate) group_grants: GossipChannel<GroupKeyGrant>,
+    pub(crate) grain_flags: GossipChannel<GrainFlag>,
+}
+
+impl GossipRecord for NgtTransfer {
+    const TOPIC: Topic = Topic::NgtTransfer;
+    const KIND: &'static str = "transfer";
+
+    fn record_id(&self) -> [u8; 32] {
+        self.id()
+    }
+
+    fn verify_record(&self) -> bool {
+        self.verify().unwrap_or(false)
+    }
+
+    fn into_message(self) -> GossipMessage {
+        GossipMessage::NgtTransfer { transfer: self }
+    }
+
+    fn channel(channels: &mut Channels) -> &mut GossipChannel<Self> {
+        &mut channels.transfers
+    }
+}
+
+impl GossipRecord for KeySuccession {
+    const TOPIC: Topic = Topic::KeySuccession;
+    const KIND: &'static str = "key succession";
+
+    fn record_id(&self) -> [u8; 32] {
+        self.id()
+    }
+
+    fn verify_record(&self) -> bool {
+        self.verify().unwrap_or(false)
+    }
+
+    fn into_message(self) -> GossipMessage {
+        GossipMessage::KeySuccession { succession: self }
+    }
+
+    fn channel(channels: &mut Channels) -> &mut GossipChannel<Self> {
+        &mut channels.successions
+    }
+
+    fn revokes(&self) -> Option<([u8; 32], i64)> {
+        self.revoked_from.map(|from| (self.old_pk, from))
+    }
+}
+
+impl GossipRecord for PrivateGrain {
+    const TOPIC: Topic = Topic::PrivateGrains;
+    const KIND: &'static str = "private grain";
+
+    fn record_id(&self) -> [u8; 32] {
+        self.id()
+    }
+
+    fn verify_record(&self) -> bool {
+        self.verify().unwrap_or(false)
+    }
+
+    fn into_message(self) -> GossipMessage {
+        GossipMessage::PrivateGrainPut { grain: self }
+    }
+
+    fn channel(channels: &mut Channels) -> &mut GossipChannel<Self> {
+        &mut channels.private_grains
+    }
+
+    fn signed_by(&self) -> Option<(&[u8], i64)> {
+        Some((&self.author_pk, self.ts_unix_ms))
+    }
+}
+
+impl GossipRecord for GroupKeyGrant {
+    const TOPIC: Topic = Topic::GroupKeyGrant;
+    const KIND: &'static str = "group key grant";
+
+    fn record_id(&self) -> [u8; 32] {
+        self.id()
+    }
+
+    fn verify_record(&self) -> bool {
+        self.verify().unwrap_or(false)
+    }
+
+    fn into_message(self) -> GossipMessage {
+        GossipMessage::GroupKeyGrant { grant: self }
+    }
+
+    fn channel(channels: &mut Channels) -> &mut GossipChannel<Self> {
+        &mut channels.group_grants
+    }
+}
+
+impl GossipRecord for GrainFlag {
+    const TOPIC: Topic = Topic::GrainFlags;
+    const KIND: &'static str = "grain flag";
+
+    fn record_id(&self) -> [u8; 32] {
+        self.id()
+    }
+
+    fn verify_record(&self) -> bool {
+        self.verify().unwrap_or(false)
+    }
+
+    fn into_message(self) -> GossipMessage {
+        GossipMessage::GrainFlag { flag: self }
+    }
+
+    fn channel(channels: &mut Channels) -> &mut GossipChannel<Self> {
+        &mut channels.grain_flags
+    }
+
+    fn signed_by(&self) -> Option<(&[u8], i64)> {
+        Some((&self.signer, self.ts_unix_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seen_cache_is_bounded() {
+        let mut seen = SeenCache::new(2);
+        assert!(seen.insert([1u8; 32]));
+        assert!(!seen.insert([1u8; 32]));
+        assert!(seen.insert([2u8; 32]));
+        assert!(seen.insert([3u8; 32]));
+
+        assert_eq!(seen.len(), 2);
+        assert!(!seen.contains(&[1u8; 32]));
+        assert!(seen.contains(&[3u8; 32]));
+    }
+}
@@ -1,5 +1,6 @@
 // SynapseNet P2P - libp2p networking layer
 
+pub mod channel;
 pub mod clustering;
 pub mod dht;
 pub mod nat;
@@ -7,6 +8,7 @@ pub mod pqc_transport;
 pub mod swarm;
 pub mod topics;
 
+pub use channel::{GossipChannel, GossipRecord};
 pub use clustering::{ClusterStats, ClusteringManager, PeerCluster};
 pub use dht::{DhtDiscovery, TopicDiscovery};
 pub use nat::{ConnectionMethod, ConnectionStrategy, NatTraversal, NatType, RelayNode};
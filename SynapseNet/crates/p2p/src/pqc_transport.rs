@@ -45,6 +45,11 @@ impl KyberKem {
         self.secret_key.as_bytes().to_vec()
     }
     
+    /// Key other group members wrap private grain group keys to
+    pub fn recipient_key(&self) -> synapsenet_core::RecipientKey {
+        synapsenet_core::RecipientKey::Kyber(self.public_key_bytes())
+    }
+    
     /// Create from bytes
     pub fn from_bytes(pk_bytes: &[u8], sk_bytes: &[u8]) -> Result<Self> {
         let pk = kyber1024::PublicKey::from_bytes(pk_bytes)
@@ -5,14 +5,18 @@ use libp2p::{
     swarm::{NetworkBehaviour, SwarmEvent},
     tcp, yamux, Multiaddr, PeerId, Swarm, Transport,
 };
-use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
+use std::collections::{hash_map::DefaultHasher, HashMap};
 use std::hash::{Hash, Hasher};
 use std::time::Duration;
 use tokio::select;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
+use crate::channel::{Channels, GossipChannel, GossipRecord, SeenCache, SEEN_CAPACITY};
 use crate::topics::{GossipMessage, QueryResult, Topic};
+use synapsenet_core::{
+    GrainFlag, GroupKeyGrant, HybridPolicy, KeySuccession, NgtTransfer, PrivateGrain,
+};
 
 /// P2P configuration
 #[derive(Clone, Debug)]
@@ -20,6 +24,8 @@ pub struct P2pConfig {
     pub port: u16,
     pub enable_mdns: bool,
     pub bootstrap_peers: Vec<Multiaddr>,
+    /// Which halves of hybrid grain signatures must validate
+    pub hybrid_policy: HybridPolicy,
 }
 
 impl Default for P2pConfig {
@@ -28,6 +34,7 @@ impl Default for P2pConfig {
             port: 9000,
             enable_mdns: true,
             bootstrap_peers: Vec::new(),
+            hybrid_policy: HybridPolicy::default(),
         }
     }
 }
@@ -66,6 +73,12 @@ struct QueryState {
 /// Callback for handling received grains
 pub type GrainCallback = Box<dyn Fn(synapsenet_core::Grain) -> Result<()> + Send + Sync>;
 
+/// Callback for received PoE co-signing messages
+pub type PoeCosignCallback = Box<dyn Fn(Vec<u8>) -> Result<()> + Send + Sync>;
+
+/// Source of local PoE co-signing messages waiting to be broadcast
+pub type PoeCosignOutbox = Box<dyn Fn() -> Result<Vec<Vec<u8>>> + Send + Sync>;
+
 /// SynapseNet P2P swarm
 pub struct SynapseSwarm {
     swarm: Swarm<SynapseBehaviour>,
@@ -73,13 +86,22 @@ pub struct SynapseSwarm {
     connected_peers: HashMap<PeerId, PeerInfo>,
     config: P2pConfig,
     /// Track sent grains to avoid duplicates
-    sent_grains: HashSet<[u8; 32]>,
+    sent_grains: SeenCache,
     /// Track received grains to avoid duplicates
-    received_grains: HashSet<[u8; 32]>,
+    received_grains: SeenCache,
     /// Track active queries
     active_queries: HashMap<String, QueryState>,
     /// Callback for storing received grains
     grain_callback: Option<GrainCallback>,
+    /// Signed record channels (transfers, successions, private grains,
+    /// group key grants, grain flags)
+    channels: Channels,
+    /// Callback for received co-signing messages
+    poe_cosign_callback: Option<PoeCosignCallback>,
+    /// Outbox of local co-signing messages to broadcast
+    poe_cosign_outbox: Option<PoeCosignOutbox>,
+    /// Revocation time of old keys learned from successions
+    revoked_keys: HashMap<[u8; 32], i64>,
 }
 
 #[derive(NetworkBehaviour)]
@@ -132,6 +154,12 @@ impl SynapseSwarm {
             Topic::GrainsAck.as_str(),
             Topic::QueryKnn.as_str(),
             Topic::QueryResp.as_str(),
+            Topic::NgtTransfer.as_str(),
+            Topic::PoeCosign.as_str(),
+            Topic::KeySuccession.as_str(),
+            Topic::PrivateGrains.as_str(),
+            Topic::GroupKeyGrant.as_str(),
+            Topic::GrainFlags.as_str(),
         ];
 
         for topic_str in &topics {
@@ -170,10 +198,14 @@ impl SynapseSwarm {
             local_peer_id,
             connected_peers: HashMap::new(),
             config,
-            sent_grains: HashSet::new(),
-            received_grains: HashSet::new(),
+            sent_grains: SeenCache::new(SEEN_CAPACITY),
+            received_grains: SeenCache::new(SEEN_CAPACITY),
             active_queries: HashMap::new(),
             grain_callback: None,
+            channels: Channels::default(),
+            poe_cosign_callback: None,
+            poe_cosign_outbox: None,
+            revoked_keys: HashMap::new(),
         })
     }
 
@@ -195,6 +227,9 @@ impl SynapseSwarm {
         // Set up reputation check interval
         let mut reputation_check = tokio::time::interval(Duration::from_secs(60));
 
+        // Set up transfer outbox flush interval
+        let mut transfer_flush = tokio::time::interval(Duration::from_secs(5));
+
         loop {
             select! {
                 event = self.swarm.select_next_some() => {
@@ -212,6 +247,14 @@ impl SynapseSwarm {
                 _ = reputation_check.tick() => {
                     self.check_peer_reputation();
                 }
+                _ = transfer_flush.tick() => {
+                    self.flush_channel::<NgtTransfer>();
+                    self.flush_poe_cosign();
+                    self.flush_channel::<KeySuccession>();
+                    self.flush_channel::<PrivateGrain>();
+                    self.flush_channel::<GroupKeyGrant>();
+                    self.flush_channel::<GrainFlag>();
+                }
             }
         }
     }
@@ -362,8 +405,19 @@ impl SynapseSwarm {
                     }
                 }
 
+                // Reject content from revoked keys before anything else
+                if self.is_revoked(&grain.meta.author_pk, grain.meta.ts_unix_ms) {
+                    warn!("Grain {:?} signed by a revoked key", hex_encode(&grain.id[..8]));
+                    if let Some(source) = message.source {
+                        if let Some(peer_info) = self.connected_peers.get_mut(&source) {
+                            peer_info.reputation -= 1.0;
+                        }
+                    }
+                    return Ok(());
+                }
+
                 // Verify grain signature
-                match grain.verify() {
+                match grain.verify_with_policy(self.config.hybrid_policy) {
                     Ok(true) => {
                         info!("Grain signature verified: {:?}", hex_encode(&grain.id[..8]));
 
@@ -483,6 +537,35 @@ impl SynapseSwarm {
                     debug!("Received response for unknown query: {}", query_id);
                 }
             }
+            GossipMessage::NgtTransfer { transfer } => {
+                self.receive_record(transfer, message.source);
+            }
+            GossipMessage::KeySuccession { succession } => {
+                self.receive_record(succession, message.source);
+            }
+            GossipMessage::PrivateGrainPut { grain } => {
+                self.receive_record(grain, message.source);
+            }
+            GossipMessage::GroupKeyGrant { grant } => {
+                self.receive_record(grant, message.source);
+            }
+            GossipMessage::GrainFlag { flag } => {
+                self.receive_record(flag, message.source);
+            }
+            GossipMessage::PoeCosign { payload } => {
+                // Signatures are checked by the co-signer against the committee
+                if let Some(ref callback) = self.poe_cosign_callback {
+                    if let Err(e) = callback(payload) {
+                        warn!("Failed to handle co-signing message: {}", e);
+
+                        if let Some(source) = message.source {
+                            if let Some(peer_info) = self.connected_peers.get_mut(&source) {
+                                peer_info.reputation -= 1.0;
+                            }
+                        }
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -517,6 +600,146 @@ impl SynapseSwarm {
         Ok(())
     }
 
+    /// Broadcast a signed record on its channel's topic
+    pub fn broadcast<T: GossipRecord>(&mut self, record: &T) -> Result<()> {
+        let id = record.record_id();
+        let data = bincode::serialize(&record.clone().into_message())?;
+        let topic = gossipsub::IdentTopic::new(T::TOPIC.as_str());
+
+        self.swarm.behaviour_mut().gossipsub.publish(topic, data)?;
+        T::channel(&mut self.channels).mark_seen(id);
+        self.track_revocation(record);
+
+        info!("Broadcasted {}: {:?}", T::KIND, hex_encode(&id[..8]));
+
+        Ok(())
+    }
+
+    /// Publish records from a channel's outbox
+    ///
+    /// Published records are acknowledged to the outbox; the others are
+    /// returned again by the next poll.
+    fn flush_channel<T: GossipRecord>(&mut self) {
+        let records = match T::channel(&mut self.channels).poll_outbox() {
+            Ok(records) => records,
+            Err(e) => {
+                warn!("Failed to read {} outbox: {}", T::KIND, e);
+                return;
+            }
+        };
+
+        for record in records {
+            match self.broadcast(&record) {
+                Ok(()) => {
+                    if let Err(e) = T::channel(&mut self.channels).sent(&record) {
+                        warn!("Failed to acknowledge {} broadcast: {}", T::KIND, e);
+                    }
+                }
+                Err(e) => debug!("{} broadcast deferred: {}", T::KIND, e),
+            }
+        }
+    }
+
+    /// Check a record received from `source` and hand it to its channel
+    ///
+    /// Echoes are dropped. Records signed by a revoked key or with a bad
+    /// signature lower the sender's reputation.
+    fn receive_record<T: GossipRecord>(&mut self, record: T, source: Option<PeerId>) {
+        let id = record.record_id();
+        if T::channel(&mut self.channels).seen().contains(&id) {
+            debug!("{} {:?} already seen, skipping", T::KIND, hex_encode(&id[..8]));
+            return;
+        }
+
+        let revoked = record
+            .signed_by()
+            .is_some_and(|(signer, ts_unix_ms)| self.is_revoked(signer, ts_unix_ms));
+        if revoked || !record.verify_record() {
+            warn!("Invalid {}: {:?}", T::KIND, hex_encode(&id[..8]));
+            T::channel(&mut self.channels).mark_seen(id);
+
+            if let Some(source) = source {
+                if let Some(peer_info) = self.connected_peers.get_mut(&source) {
+                    peer_info.reputation -= 1.0;
+                }
+            }
+            return;
+        }
+
+        debug!("Received {} {:?}", T::KIND, hex_encode(&id[..8]));
+        self.track_revocation(&record);
+
+        // Only a record the node accepted is skipped from now on; one it
+        // could not apply yet is tried again when it arrives again
+        let channel = T::channel(&mut self.channels);
+        match channel.deliver(record) {
+            Ok(()) => {
+                channel.mark_seen(id);
+            }
+            Err(e) => warn!("Failed to record {}: {}", T::KIND, e),
+        }
+    }
+
+    /// Broadcast a PoE co-signing message to all peers
+    pub fn broadcast_poe_cosign(&mut self, payload: Vec<u8>) -> Result<()> {
+        let message = GossipMessage::PoeCosign { payload };
+
+        let data = bincode::serialize(&message)?;
+        let topic = gossipsub::IdentTopic::new(Topic::PoeCosign.as_str());
+
+        self.swarm.behaviour_mut().gossipsub.publish(topic, data)?;
+
+        Ok(())
+    }
+
+    /// Publish co-signing messages from the outbox
+    ///
+    /// Failures are dropped: the co-signer keeps re-queueing its messages
+    /// until its epoch reaches a quorum.
+    fn flush_poe_cosign(&mut self) {
+        let payloads = match self.poe_cosign_outbox {
+            Some(ref outbox) => match outbox() {
+                Ok(payloads) => payloads,
+                Err(e) => {
+                    warn!("Failed to read co-signing outbox: {}", e);
+                    return;
+                }
+            },
+            None => return,
+        };
+
+        for payload in payloads {
+            if let Err(e) = self.broadcast_poe_cosign(payload) {
+                debug!("Co-signing broadcast deferred: {}", e);
+            }
+        }
+    }
+
+    fn track_revocation<T: GossipRecord>(&mut self, record: &T) {
+        if let Some((pk, from)) = record.revokes() {
+            self.revoke_key(pk, from);
+        }
+    }
+
+    /// Reject content signed by `pk` from `from` on
+    ///
+    /// Successions seen on the network are tracked automatically; the node
+    /// seeds the ones it already holds at startup.
+    pub fn revoke_key(&mut self, pk: [u8; 32], from: i64) {
+        self.revoked_keys
+            .entry(pk)
+            .and_modify(|current| *current = (*current).min(from))
+            .or_insert(from);
+    }
+
+    /// Whether `author_pk` was revoked at `ts_unix_ms` by a known succession
+    fn is_revoked(&self, author_pk: &[u8], ts_unix_ms: i64) -> bool {
+        <[u8; 32]>::try_from(author_pk)
+            .ok()
+            .and_then(|pk| self.revoked_keys.get(&pk))
+            .is_some_and(|&from| ts_unix_ms >= from)
+    }
+
     /// Get connected peer count
     pub fn peer_count(&self) -> usize {
         self.connected_peers.len()
@@ -540,6 +763,30 @@ impl SynapseSwarm {
         self.grain_callback = Some(Box::new(callback));
     }
 
+    /// Set callback for received PoE co-signing messages
+    ///
+    /// An error from the callback (e.g. a forged signature) lowers the
+    /// sender's reputation.
+    pub fn set_poe_cosign_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(Vec<u8>) -> Result<()> + Send + Sync + 'static,
+    {
+        self.poe_cosign_callback = Some(Box::new(callback));
+    }
+
+    /// Set the outbox polled for local co-signing messages to broadcast
+    pub fn set_poe_cosign_outbox<F>(&mut self, outbox: F)
+    where
+        F: Fn() -> Result<Vec<Vec<u8>>> + Send + Sync + 'static,
+    {
+        self.poe_cosign_outbox = Some(Box::new(outbox));
+    }
+
+    /// Channel of a signed record type, to set its callback and outbox
+    pub fn channel<T: GossipRecord>(&mut self) -> &mut GossipChannel<T> {
+        T::channel(&mut self.channels)
+    }
+
     /// Query peers for similar grains (distributed KNN search)
     pub async fn query_peers(
         &mut self,
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
-use synapsenet_core::{Grain, Link};
+use synapsenet_core::{
+    Grain, GrainFlag, GroupKeyGrant, KeySuccession, Link, NgtTransfer, PrivateGrain,
+};
 
 /// P2P topic names
 pub enum Topic {
@@ -7,6 +9,12 @@ pub enum Topic {
     GrainsAck,
     QueryKnn,
     QueryResp,
+    NgtTransfer,
+    PoeCosign,
+    KeySuccession,
+    PrivateGrains,
+    GroupKeyGrant,
+    GrainFlags,
 }
 
 impl Topic {
@@ -16,6 +24,12 @@ impl Topic {
             Topic::GrainsAck => "grains.ack",
             Topic::QueryKnn => "query.knn",
             Topic::QueryResp => "query.resp",
+            Topic::NgtTransfer => "ngt.transfer",
+            Topic::PoeCosign => "poe.cosign",
+            Topic::KeySuccession => "key.succession",
+            Topic::PrivateGrains => "grains.private",
+            Topic::GroupKeyGrant => "group.grant",
+            Topic::GrainFlags => "grains.flag",
         }
     }
 }
@@ -41,6 +55,24 @@ pub enum GossipMessage {
         query_id: String,
         results: Vec<QueryResult>,
     },
+
+    /// Signed NGT transfer
+    NgtTransfer { transfer: NgtTransfer },
+
+    /// PoE batch co-signing message, encoded by the batch node
+    PoeCosign { payload: Vec<u8> },
+
+    /// Signed node key rotation
+    KeySuccession { succession: KeySuccession },
+
+    /// Grain encrypted to a group; relayed by everyone, readable by members
+    PrivateGrainPut { grain: PrivateGrain },
+
+    /// Group key wrapped to the group's members
+    GroupKeyGrant { grant: GroupKeyGrant },
+
+    /// Signed flag, unflag or appeal on a grain
+    GrainFlag { flag: GrainFlag },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,22 @@
+//! Approval requests of gated agent tools
+//!
+//! The request itself (proposed input, goal checkpoint) is owned by the agent
+//! crate and kept as JSON in `record`; `status` and `resumed` mirror it so
+//! every state change can be made conditional on the state it starts from.
+//! That keeps decisions and resumptions exactly-once across processes
+//! sharing the database.
+
+pub const CREATE_APPROVALS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS approvals (
+    id TEXT PRIMARY KEY,
+    status TEXT NOT NULL,
+    resumed INTEGER NOT NULL DEFAULT 0,
+    requested_at INTEGER NOT NULL,
+    record TEXT NOT NULL
+)
+"#;
+
+pub const CREATE_APPROVALS_INDEXES: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_approvals_status ON approvals(status)",
+    "CREATE INDEX IF NOT EXISTS idx_approvals_requested ON approvals(requested_at)",
+];
@@ -0,0 +1,150 @@
+//! Curator review tasks, curator keys and the decision audit log
+//!
+//! Content a policy classifies as `curated` is filed as a task. Whatever the
+//! requester asked for (query results, for instance) is held in `outcome`
+//! and only released once a registered curator approves the task, to whoever
+//! holds the task's release token. Every applied decision is kept with its
+//! signature in `curator_audit`.
+
+use synapsenet_core::CuratorAction;
+use uuid::Uuid;
+
+/// Review task state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CuratorTaskStatus {
+    Pending,
+    /// Claimed by a curator
+    InReview,
+    Approved,
+    Rejected,
+}
+
+impl CuratorTaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::InReview => "in_review",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(Self::Pending),
+            "in_review" => Some(Self::InReview),
+            "approved" => Some(Self::Approved),
+            "rejected" => Some(Self::Rejected),
+            _ => None,
+        }
+    }
+
+    pub fn is_decided(&self) -> bool {
+        matches!(self, Self::Approved | Self::Rejected)
+    }
+}
+
+/// Content waiting for (or past) curator review
+#[derive(Debug, Clone, PartialEq)]
+pub struct CuratorTask {
+    pub id: Uuid,
+    /// Where the content was held (query, ingest, ...)
+    pub route: String,
+    /// The query or text under review
+    pub subject: String,
+    /// Who to release the outcome to, if the request named itself
+    pub requester: Option<String>,
+    /// Policy classification that triggered the review (JSON)
+    pub classification: serde_json::Value,
+    /// Held result, released on approval
+    pub outcome: Option<serde_json::Value>,
+    pub status: CuratorTaskStatus,
+    pub curator: Option<[u8; 32]>,
+    pub note: Option<String>,
+    pub created_at: i64,
+    pub decided_at: Option<i64>,
+    /// BLAKE3 hash of the token the requester presents to see the outcome
+    pub release_token_hash: Option<[u8; 32]>,
+}
+
+/// Task to file for review
+#[derive(Debug, Clone)]
+pub struct NewCuratorTask {
+    pub route: String,
+    pub subject: String,
+    pub requester: Option<String>,
+    pub classification: serde_json::Value,
+    pub outcome: Option<serde_json::Value>,
+    pub release_token_hash: Option<[u8; 32]>,
+}
+
+/// Result of applying a curator decision
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecisionOutcome {
+    /// Decision recorded in the task and the audit log
+    Applied(Box<CuratorTask>),
+    /// No task with this ID
+    NotFound,
+    /// Key is not a registered curator
+    NotCurator,
+    /// Task is decided or claimed by another curator
+    Conflict(String),
+}
+
+/// Applied curator decision
+#[derive(Debug, Clone, PartialEq)]
+pub struct CuratorAuditEntry {
+    pub id: i64,
+    pub task_id: Uuid,
+    pub curator: [u8; 32],
+    pub action: CuratorAction,
+    pub note: Option<String>,
+    pub ts_unix_ms: i64,
+    pub sig: Vec<u8>,
+    pub recorded_at: i64,
+}
+
+pub const CREATE_CURATORS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS curators (
+    public_key BLOB PRIMARY KEY,
+    name TEXT,
+    added_at INTEGER NOT NULL
+)
+"#;
+
+pub const CREATE_CURATOR_TASKS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS curator_tasks (
+    id TEXT PRIMARY KEY,
+    route TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    requester TEXT,
+    classification TEXT NOT NULL,
+    outcome TEXT,
+    status TEXT NOT NULL,
+    curator BLOB,
+    note TEXT,
+    created_at INTEGER NOT NULL,
+    decided_at INTEGER
+)
+"#;
+
+pub const CREATE_CURATOR_AUDIT_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS curator_audit (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id TEXT NOT NULL,
+    curator BLOB NOT NULL,
+    action TEXT NOT NULL,
+    note TEXT,
+    ts_unix_ms INTEGER NOT NULL,
+    sig BLOB NOT NULL,
+    recorded_at INTEGER NOT NULL
+)
+"#;
+
+pub const ADD_CURATOR_RELEASE_TOKEN: &str =
+    "ALTER TABLE curator_tasks ADD COLUMN release_token_hash BLOB";
+
+pub const CREATE_CURATION_INDEXES: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_curator_tasks_status ON curator_tasks(status, created_at)",
+    "CREATE INDEX IF NOT EXISTS idx_curator_audit_task ON curator_audit(task_id)",
+];
@@ -0,0 +1,36 @@
+//! Author identities across key rotations
+//!
+//! A node identity is named by its first key. Every signed key succession
+//! moves the identity to a new key, so grains signed by any key in the chain
+//! belong to the same author. Successions may arrive out of order over
+//! gossip; a chain received later is attached to the identity it extends.
+
+/// Result of applying a key succession
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuccessionOutcome {
+    /// Succession recorded; the keys now belong to `identity`
+    Applied { identity: [u8; 32] },
+    /// Succession was already recorded
+    Duplicate,
+    /// Succession forks or loops an existing chain
+    Rejected(String),
+}
+
+pub const CREATE_KEY_SUCCESSIONS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS key_successions (
+    id BLOB PRIMARY KEY,
+    old_pk BLOB NOT NULL UNIQUE,
+    new_pk BLOB NOT NULL UNIQUE,
+    identity BLOB NOT NULL,
+    ts_unix_ms INTEGER NOT NULL,
+    revoked_from INTEGER,
+    record BLOB NOT NULL,
+    received_at INTEGER NOT NULL,
+    pending_broadcast INTEGER NOT NULL DEFAULT 0
+)
+"#;
+
+pub const CREATE_KEY_SUCCESSION_INDEXES: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_key_successions_identity ON key_successions(identity)",
+    "CREATE INDEX IF NOT EXISTS idx_key_successions_pending ON key_successions(pending_broadcast)",
+];
@@ -0,0 +1,418 @@
+//! Double-entry NGT ledger
+//!
+//! Every movement of NGT is a ledger entry made of postings that sum to zero:
+//! tokens are never created out of nothing, they are moved from a system
+//! account (issuance, penalties, burned) to a node account or back.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use synapsenet_core::NgtTransfer;
+
+/// Account debited when NGT is minted or rewarded
+pub const ISSUANCE_ACCOUNT: &str = "system:issuance";
+/// Account credited with penalties taken from nodes
+pub const PENALTY_ACCOUNT: &str = "system:penalties";
+/// Account credited with burned NGT
+pub const BURN_ACCOUNT: &str = "system:burned";
+
+/// Tolerance used when checking that postings balance
+const BALANCE_EPSILON: f64 = 1e-9;
+
+/// Ledger account name of a node
+pub fn node_account(node_pk: &[u8; 32]) -> String {
+    let hex: String = node_pk.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("node:{}", hex)
+}
+
+/// Parse node public key from an account name
+pub fn parse_node_account(account: &str) -> Option<[u8; 32]> {
+    let hex = account.strip_prefix("node:")?;
+    if hex.len() != 64 {
+        return None;
+    }
+
+    let mut pk = [0u8; 32];
+    for (i, byte) in pk.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(pk)
+}
+
+/// Check whether an account belongs to the system (may go negative)
+pub fn is_system_account(account: &str) -> bool {
+    account.starts_with("system:")
+}
+
+/// Idempotency key for a PoE reward of a grain in an epoch
+pub fn reward_key(grain_id: &[u8; 32], epoch: u64) -> String {
+    let hex: String = grain_id.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("reward:{}:{}", hex, epoch)
+}
+
+/// Idempotency key for an entry mirroring a row of the `credits` table
+pub fn credit_key(credit_id: i64) -> String {
+    format!("credit:{}", credit_id)
+}
+
+/// Idempotency key for the ledger entry of a signed transfer
+pub fn transfer_key(transfer_id: &[u8; 32]) -> String {
+    let hex: String = transfer_id.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("transfer:{}", hex)
+}
+
+/// Kind of ledger entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedgerEntryKind {
+    Mint,
+    Reward,
+    Transfer,
+    Penalty,
+    Burn,
+}
+
+impl LedgerEntryKind {
+    /// Kind name as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LedgerEntryKind::Mint => "mint",
+            LedgerEntryKind::Reward => "reward",
+            LedgerEntryKind::Transfer => "transfer",
+            LedgerEntryKind::Penalty => "penalty",
+            LedgerEntryKind::Burn => "burn",
+        }
+    }
+
+    /// Parse kind name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "mint" => Some(LedgerEntryKind::Mint),
+            "reward" => Some(LedgerEntryKind::Reward),
+            "transfer" => Some(LedgerEntryKind::Transfer),
+            "penalty" => Some(LedgerEntryKind::Penalty),
+            "burn" => Some(LedgerEntryKind::Burn),
+            _ => None,
+        }
+    }
+}
+
+/// Single posting: positive amounts credit the account, negative debit it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Posting {
+    pub account: String,
+    pub amount: f64,
+}
+
+/// Entry to be posted to the ledger
+#[derive(Debug, Clone)]
+pub struct NewLedgerEntry {
+    pub kind: LedgerEntryKind,
+    /// Postings, starting with the debited account
+    pub postings: Vec<Posting>,
+    /// Entries with an already used key are ignored
+    pub idempotency_key: Option<String>,
+    pub memo: String,
+}
+
+impl NewLedgerEntry {
+    fn between(kind: LedgerEntryKind, from: &str, to: &str, amount: f64, memo: &str) -> Self {
+        Self {
+            kind,
+            postings: vec![
+                Posting {
+                    account: from.to_string(),
+                    amount: -amount,
+                },
+                Posting {
+                    account: to.to_string(),
+                    amount,
+                },
+            ],
+            idempotency_key: None,
+            memo: memo.to_string(),
+        }
+    }
+
+    /// Mint new NGT into an account
+    pub fn mint(to: &str, amount: f64, memo: &str) -> Self {
+        Self::between(LedgerEntryKind::Mint, ISSUANCE_ACCOUNT, to, amount, memo)
+    }
+
+    /// Reward an account for a contribution
+    pub fn reward(to: &str, amount: f64, memo: &str) -> Self {
+        Self::between(LedgerEntryKind::Reward, ISSUANCE_ACCOUNT, to, amount, memo)
+    }
+
+    /// Move NGT between two accounts
+    pub fn transfer(from: &str, to: &str, amount: f64, memo: &str) -> Self {
+        Self::between(LedgerEntryKind::Transfer, from, to, amount, memo)
+    }
+
+    /// Take NGT from an account as a penalty
+    pub fn penalty(from: &str, amount: f64, memo: &str) -> Self {
+        Self::between(LedgerEntryKind::Penalty, from, PENALTY_ACCOUNT, amount, memo)
+    }
+
+    /// Destroy NGT held by an account
+    pub fn burn(from: &str, amount: f64, memo: &str) -> Self {
+        Self::between(LedgerEntryKind::Burn, from, BURN_ACCOUNT, amount, memo)
+    }
+
+    /// Set idempotency key
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Check the entry is well formed and balanced
+    pub fn validate(&self) -> Result<()> {
+        if self.postings.len() < 2 {
+            return Err(anyhow!("Ledger entry needs at least two postings"));
+        }
+
+        for posting in &self.postings {
+            if !posting.amount.is_finite() || posting.amount == 0.0 {
+                return Err(anyhow!(
+                    "Invalid amount {} for account {}",
+                    posting.amount,
+                    posting.account
+                ));
+            }
+        }
+
+        if self.postings[0].amount > 0.0 {
+            return Err(anyhow!("Ledger entry must start with a debit"));
+        }
+
+        let sum: f64 = self.postings.iter().map(|p| p.amount).sum();
+        if sum.abs() > BALANCE_EPSILON {
+            return Err(anyhow!("Ledger entry is unbalanced by {}", sum));
+        }
+
+        Ok(())
+    }
+}
+
+/// Entry stored in the ledger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub id: i64,
+    pub kind: LedgerEntryKind,
+    pub idempotency_key: Option<String>,
+    pub memo: String,
+    pub ts_unix_ms: i64,
+    pub postings: Vec<Posting>,
+}
+
+/// Result of posting an entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostOutcome {
+    /// Entry was recorded under this ID
+    Posted(i64),
+    /// Idempotency key was already used by this entry
+    Duplicate(i64),
+}
+
+impl PostOutcome {
+    /// ID of the entry holding the postings
+    pub fn entry_id(&self) -> i64 {
+        match self {
+            PostOutcome::Posted(id) | PostOutcome::Duplicate(id) => *id,
+        }
+    }
+
+    /// Check whether the entry was newly recorded
+    pub fn is_posted(&self) -> bool {
+        matches!(self, PostOutcome::Posted(_))
+    }
+}
+
+/// Account balance as of a ledger entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    pub account: String,
+    pub balance: f64,
+    /// Last entry included in the balance
+    pub entry_id: i64,
+    pub ts_unix_ms: i64,
+}
+
+/// Filter for ledger audit queries
+#[derive(Debug, Clone)]
+pub struct LedgerQuery {
+    pub account: Option<String>,
+    pub kind: Option<LedgerEntryKind>,
+    pub since_ms: Option<i64>,
+    pub limit: usize,
+}
+
+impl Default for LedgerQuery {
+    fn default() -> Self {
+        Self {
+            account: None,
+            kind: None,
+            since_ms: None,
+            limit: 100,
+        }
+    }
+}
+
+/// Result of a full ledger consistency check
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LedgerAudit {
+    pub entries_checked: usize,
+    /// Entries whose postings do not sum to zero
+    pub unbalanced_entries: Vec<i64>,
+    /// Accounts whose latest snapshot disagrees with their postings
+    pub snapshot_mismatches: Vec<String>,
+    /// Sum of all postings (zero for a consistent ledger)
+    pub net_total: f64,
+}
+
+impl LedgerAudit {
+    /// Check whether no problems were found
+    pub fn is_consistent(&self) -> bool {
+        self.unbalanced_entries.is_empty()
+            && self.snapshot_mismatches.is_empty()
+            && self.net_total.abs() <= BALANCE_EPSILON
+    }
+}
+
+/// Result of applying a signed transfer to the local ledger
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferOutcome {
+    /// Transfer was posted under this ledger entry
+    Applied { entry_id: i64 },
+    /// Transfer was already applied
+    Duplicate,
+    /// Sender already spent this nonce on another transfer
+    Conflict { existing_id: [u8; 32] },
+    /// Transfer is not valid against the local ledger view
+    Rejected(String),
+}
+
+/// Applied transfer as stored locally
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferRecord {
+    pub id: [u8; 32],
+    pub transfer: NgtTransfer,
+    pub entry_id: i64,
+    pub received_at: i64,
+}
+
+/// Two transfers signed by the same sender with the same nonce
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferConflict {
+    pub from: [u8; 32],
+    pub nonce: u64,
+    /// Transfer that was applied first
+    pub first_id: [u8; 32],
+    /// Transfer that tried to reuse the nonce
+    pub conflicting: NgtTransfer,
+    pub detected_at: i64,
+}
+
+pub(crate) fn amounts_match(a: f64, b: f64) -> bool {
+    (a - b).abs() <= BALANCE_EPSILON.max(b.abs() * 1e-12)
+}
+
+// SQL schema
+pub const CREATE_LEDGER_ENTRIES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS ledger_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    idempotency_key TEXT UNIQUE,
+    memo TEXT NOT NULL,
+    ts_unix_ms INTEGER NOT NULL
+);
+"#;
+
+pub const CREATE_LEDGER_POSTINGS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS ledger_postings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entry_id INTEGER NOT NULL,
+    account TEXT NOT NULL,
+    amount REAL NOT NULL,
+    FOREIGN KEY (entry_id) REFERENCES ledger_entries(id)
+);
+"#;
+
+pub const CREATE_LEDGER_SNAPSHOTS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS ledger_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account TEXT NOT NULL,
+    balance REAL NOT NULL,
+    entry_id INTEGER NOT NULL,
+    ts_unix_ms INTEGER NOT NULL
+);
+"#;
+
+pub const CREATE_TRANSFERS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS ngt_transfers (
+    id BLOB PRIMARY KEY,
+    from_pk BLOB NOT NULL,
+    to_pk BLOB NOT NULL,
+    amount REAL NOT NULL,
+    nonce INTEGER NOT NULL,
+    ts_unix_ms INTEGER NOT NULL,
+    memo TEXT,
+    sig BLOB NOT NULL,
+    entry_id INTEGER NOT NULL,
+    received_at INTEGER NOT NULL,
+    pending_broadcast INTEGER NOT NULL DEFAULT 0,
+    UNIQUE(from_pk, nonce),
+    FOREIGN KEY (entry_id) REFERENCES ledger_entries(id)
+);
+"#;
+
+pub const CREATE_TRANSFER_CONFLICTS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS ngt_transfer_conflicts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    from_pk BLOB NOT NULL,
+    nonce INTEGER NOT NULL,
+    first_id BLOB NOT NULL,
+    conflicting_id BLOB NOT NULL,
+    conflicting BLOB NOT NULL,
+    detected_at INTEGER NOT NULL,
+    UNIQUE(first_id, conflicting_id)
+);
+"#;
+
+pub const CREATE_TRANSFER_INDEXES: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_ngt_transfers_to ON ngt_transfers(to_pk);",
+    "CREATE INDEX IF NOT EXISTS idx_ngt_transfers_pending ON ngt_transfers(pending_broadcast);",
+];
+
+pub const CREATE_LEDGER_INDEXES: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_ledger_postings_account ON ledger_postings(account, entry_id);",
+    "CREATE INDEX IF NOT EXISTS idx_ledger_postings_entry ON ledger_postings(entry_id);",
+    "CREATE INDEX IF NOT EXISTS idx_ledger_entries_ts ON ledger_entries(ts_unix_ms);",
+    "CREATE INDEX IF NOT EXISTS idx_ledger_snapshots_account ON ledger_snapshots(account, entry_id);",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_validation() {
+        assert!(NewLedgerEntry::mint("node:a", 1.5, "genesis").validate().is_ok());
+        assert!(NewLedgerEntry::transfer("node:a", "node:b", -1.0, "")
+            .validate()
+            .is_err());
+        assert!(NewLedgerEntry::burn("node:a", f64::NAN, "").validate().is_err());
+
+        let mut unbalanced = NewLedgerEntry::mint("node:a", 1.0, "");
+        unbalanced.postings[1].amount = 2.0;
+        assert!(unbalanced.validate().is_err());
+    }
+
+    #[test]
+    fn test_node_account_round_trip() {
+        let pk = [7u8; 32];
+        let account = node_account(&pk);
+        assert_eq!(parse_node_account(&account), Some(pk));
+        assert!(parse_node_account(ISSUANCE_ACCOUNT).is_none());
+        assert!(is_system_account(BURN_ACCOUNT));
+    }
+}
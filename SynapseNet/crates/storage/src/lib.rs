@@ -1,13 +1,39 @@
 // SynapseNet Storage - SQLite + Vector Index + Parquet
 
+pub mod approvals;
+pub mod curation;
+pub mod identity;
 pub mod index_hnsw;
+pub mod ledger;
+pub mod manifest;
 pub mod migrations;
+pub mod moderation;
 pub mod parquet_io;
+pub mod poe;
+pub mod private;
+pub mod provenance;
+pub mod schema_v6;
 pub mod store;
 pub mod v03_migration;
+pub mod web_cache;
 
+pub use curation::{
+    CuratorAuditEntry, CuratorTask, CuratorTaskStatus, DecisionOutcome, NewCuratorTask,
+};
+pub use identity::SuccessionOutcome;
 pub use index_hnsw::HnswIndex;
+pub use ledger::{
+    BalanceSnapshot, LedgerAudit, LedgerEntry, LedgerEntryKind, LedgerQuery, NewLedgerEntry,
+    PostOutcome, Posting, TransferConflict, TransferOutcome, TransferRecord,
+};
+pub use manifest::{ExportManifest, ManifestPart, PartKind};
 pub use migrations::run_migrations;
+pub use moderation::{FlagOutcome, GrainModeration, ModerationStatus};
 pub use parquet_io::{ExportStats, ImportStats, ParquetExporter, ParquetImporter};
+pub use poe::PoeScoreRecord;
+pub use private::{GrantOutcome, PrivateGrainOutcome};
+pub use provenance::GrainProvenance;
+pub use schema_v6::{ActionRecord, EpisodeRecord, GoalRecord, PlanRecord};
 pub use store::Store;
 pub use v03_migration::{migrate_v03_to_v04, needs_migration};
+pub use web_cache::WebCacheEntry;
@@ -0,0 +1,144 @@
+//! Signed manifest of a Parquet export
+//!
+//! `manifest.json` lists every part of an export with its row count and
+//! blake3 hash, and is signed by the exporting node key. Importers check the
+//! signature and every part before reading any rows, so a truncated or
+//! edited export is rejected as a whole.
+
+use anyhow::{anyhow, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Manifest file name inside an export directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Current manifest format version
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Kind of records stored in a part
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PartKind {
+    Grains,
+    Links,
+    Credits,
+}
+
+/// One Parquet file of an export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestPart {
+    /// File name relative to the export directory
+    pub file: String,
+    pub kind: PartKind,
+    /// Number of rows in the file
+    pub rows: usize,
+    /// Parquet schema version of the part
+    pub schema_version: u32,
+    /// Embedding models of the grains in the part
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embedding_models: Vec<String>,
+    /// blake3 of the file (hex)
+    pub blake3: String,
+}
+
+/// Signed list of the parts of an export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub version: u32,
+    /// Unix timestamp (milliseconds)
+    pub created_at_ms: i64,
+    /// Exporting node public key (hex)
+    pub node_pk: String,
+    pub parts: Vec<ManifestPart>,
+    /// Node signature over all fields above (hex)
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl ExportManifest {
+    /// Create and sign a manifest for `parts`
+    pub fn new(parts: Vec<ManifestPart>, signing_key: &SigningKey) -> Result<Self> {
+        let mut manifest = ExportManifest {
+            version: MANIFEST_VERSION,
+            created_at_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_millis() as i64,
+            node_pk: hex::encode(signing_key.verifying_key().to_bytes()),
+            parts,
+            signature: String::new(),
+        };
+
+        manifest.signature = hex::encode(signing_key.sign(&manifest.signing_bytes()?).to_bytes());
+        Ok(manifest)
+    }
+
+    /// Public key of the exporting node
+    pub fn signer(&self) -> Result<[u8; 32]> {
+        hex::decode(&self.node_pk)?
+            .try_into()
+            .map_err(|_| anyhow!("Manifest node key must be 32 bytes"))
+    }
+
+    /// Verify the node signature
+    pub fn verify(&self) -> Result<bool> {
+        let verifying_key = VerifyingKey::from_bytes(&self.signer()?)?;
+        let signature = Signature::from_bytes(
+            hex::decode(&self.signature)?
+                .as_slice()
+                .try_into()
+                .map_err(|_| anyhow!("Invalid signature length"))?,
+        );
+        Ok(verifying_key.verify(&self.signing_bytes()?, &signature).is_ok())
+    }
+
+    /// Check a part's file against its recorded hash
+    pub fn check_part(&self, dir: &Path, part: &ManifestPart) -> Result<()> {
+        if part.file.contains(['/', '\\']) || part.file.starts_with('.') {
+            return Err(anyhow!("Invalid part file name: {}", part.file));
+        }
+
+        let path = dir.join(&part.file);
+        if !path.is_file() {
+            return Err(anyhow!("Export is missing {}", part.file));
+        }
+        if hash_file(&path)? != part.blake3 {
+            return Err(anyhow!("{} does not match the manifest hash", part.file));
+        }
+        Ok(())
+    }
+
+    /// Write `manifest.json` into `dir`
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        std::fs::write(dir.join(MANIFEST_FILE), serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Read `manifest.json` from `dir`, if the export has one
+    pub fn read(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&std::fs::read(path)?)?))
+    }
+
+    fn signing_bytes(&self) -> Result<Vec<u8>> {
+        let unsigned = ExportManifest {
+            signature: String::new(),
+            ..self.clone()
+        };
+
+        let mut data = Vec::new();
+        data.extend_from_slice(b"synapsenet/export-manifest/v1");
+        data.extend_from_slice(&serde_json::to_vec(&unsigned)?);
+        Ok(data)
+    }
+}
+
+/// blake3 of a file (hex)
+pub fn hash_file(path: &Path) -> Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(std::fs::File::open(path)?)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
@@ -3,7 +3,7 @@ use rusqlite::Connection;
 use tracing::info;
 
 /// Database schema version
-const CURRENT_VERSION: i32 = 4;
+const CURRENT_VERSION: i32 = 16;
 
 /// Run all pending migrations
 pub fn run_migrations(conn: &Connection) -> Result<()> {
@@ -29,6 +29,54 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
             migrate_to_v4(conn)?;
         }
 
+        if version < 5 {
+            migrate_to_v5(conn)?;
+        }
+
+        if version < 6 {
+            migrate_to_v6(conn)?;
+        }
+
+        if version < 7 {
+            migrate_to_v7(conn)?;
+        }
+
+        if version < 8 {
+            migrate_to_v8(conn)?;
+        }
+
+        if version < 9 {
+            migrate_to_v9(conn)?;
+        }
+
+        if version < 10 {
+            migrate_to_v10(conn)?;
+        }
+
+        if version < 11 {
+            migrate_to_v11(conn)?;
+        }
+
+        if version < 12 {
+            migrate_to_v12(conn)?;
+        }
+
+        if version < 13 {
+            migrate_to_v13(conn)?;
+        }
+
+        if version < 14 {
+            migrate_to_v14(conn)?;
+        }
+
+        if version < 15 {
+            migrate_to_v15(conn)?;
+        }
+
+        if version < 16 {
+            migrate_to_v16(conn)?;
+        }
+
         set_schema_version(conn, CURRENT_VERSION)?;
         info!("✓ Migrations complete");
     } else {
@@ -157,6 +205,251 @@ fn migrate_to_v4(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Migration to v5: Add reasoning goals, plans, episodes and action logs
+fn migrate_to_v5(conn: &Connection) -> Result<()> {
+    use crate::schema_v6::*;
+
+    info!("Migration v4 -> v5: Creating reasoning tables");
+
+    conn.execute_batch(CREATE_GOALS_TABLE)?;
+    conn.execute_batch(CREATE_PLANS_TABLE)?;
+    conn.execute_batch(CREATE_EPISODES_TABLE)?;
+    conn.execute_batch(CREATE_EPISODE_ACTIONS_TABLE)?;
+    conn.execute_batch(CREATE_REASON_STATS_TABLE)?;
+
+    for index in CREATE_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    info!("✓ Migration v4 -> v5 complete");
+    Ok(())
+}
+
+/// Migration to v6: Add double-entry NGT ledger and backfill existing credits
+fn migrate_to_v6(conn: &Connection) -> Result<()> {
+    use crate::ledger::*;
+
+    info!("Migration v5 -> v6: Creating NGT ledger tables");
+
+    conn.execute_batch(CREATE_LEDGER_ENTRIES_TABLE)?;
+    conn.execute_batch(CREATE_LEDGER_POSTINGS_TABLE)?;
+    conn.execute_batch(CREATE_LEDGER_SNAPSHOTS_TABLE)?;
+
+    for index in CREATE_LEDGER_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    // Credits recorded before the ledger existed become reward entries
+    let has_credits: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'credits'",
+        [],
+        |row| row.get(0),
+    )?;
+
+    if has_credits {
+        let mut stmt = conn.prepare(
+            "SELECT id, node_pk, ngt, reason, ts_unix_ms FROM credits WHERE ngt > 0 ORDER BY id",
+        )?;
+        let credits = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Vec<u8>>(1)?,
+                    row.get::<_, f64>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        for (id, node_pk, ngt, reason, ts) in &credits {
+            let mut pk = [0u8; 32];
+            if node_pk.len() != 32 {
+                continue;
+            }
+            pk.copy_from_slice(node_pk);
+
+            conn.execute(
+                "INSERT INTO ledger_entries (kind, idempotency_key, memo, ts_unix_ms)
+                 VALUES ('reward', ?1, ?2, ?3)",
+                rusqlite::params![credit_key(*id), reason, ts],
+            )?;
+            let entry_id = conn.last_insert_rowid();
+            conn.execute(
+                "INSERT INTO ledger_postings (entry_id, account, amount) VALUES (?1, ?2, ?3), (?1, ?4, ?5)",
+                rusqlite::params![entry_id, ISSUANCE_ACCOUNT, -ngt, node_account(&pk), ngt],
+            )?;
+        }
+
+        info!("Backfilled {} credits into the ledger", credits.len());
+    }
+
+    info!("✓ Migration v5 -> v6 complete");
+    Ok(())
+}
+
+/// Migration to v7: Add signed NGT transfers and double-spend conflicts
+fn migrate_to_v7(conn: &Connection) -> Result<()> {
+    use crate::ledger::*;
+
+    info!("Migration v6 -> v7: Creating NGT transfer tables");
+
+    conn.execute_batch(CREATE_TRANSFERS_TABLE)?;
+    conn.execute_batch(CREATE_TRANSFER_CONFLICTS_TABLE)?;
+
+    for index in CREATE_TRANSFER_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    info!("✓ Migration v6 -> v7 complete");
+    Ok(())
+}
+
+/// Migration to v8: Add persisted PoE v2 scores
+fn migrate_to_v8(conn: &Connection) -> Result<()> {
+    use crate::poe::*;
+
+    info!("Migration v7 -> v8: Creating poe_scores table");
+
+    conn.execute_batch(CREATE_POE_SCORES_TABLE)?;
+
+    for index in CREATE_POE_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    info!("✓ Migration v7 -> v8 complete");
+    Ok(())
+}
+
+/// Migration to v9: Add key successions for author identities
+fn migrate_to_v9(conn: &Connection) -> Result<()> {
+    use crate::identity::*;
+
+    info!("Migration v8 -> v9: Creating key_successions table");
+
+    conn.execute_batch(CREATE_KEY_SUCCESSIONS_TABLE)?;
+
+    for index in CREATE_KEY_SUCCESSION_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    info!("✓ Migration v8 -> v9 complete");
+    Ok(())
+}
+
+/// Migration to v10: Add private grain groups, grants and ciphertext
+fn migrate_to_v10(conn: &Connection) -> Result<()> {
+    use crate::private::*;
+
+    info!("Migration v9 -> v10: Creating private grain tables");
+
+    conn.execute_batch(CREATE_PRIVATE_GROUPS_TABLE)?;
+    conn.execute_batch(CREATE_GROUP_KEY_GRANTS_TABLE)?;
+    conn.execute_batch(CREATE_PRIVATE_GRAINS_TABLE)?;
+
+    for index in CREATE_PRIVATE_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    info!("✓ Migration v9 -> v10 complete");
+    Ok(())
+}
+
+/// Migration to v11: Add provenance of agent-written grains
+fn migrate_to_v11(conn: &Connection) -> Result<()> {
+    use crate::provenance::*;
+
+    info!("Migration v10 -> v11: Creating grain_provenance table");
+
+    conn.execute_batch(CREATE_GRAIN_PROVENANCE_TABLE)?;
+
+    for index in CREATE_GRAIN_PROVENANCE_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    info!("✓ Migration v10 -> v11 complete");
+    Ok(())
+}
+
+/// Migration to v12: Add the web_fetch page cache
+fn migrate_to_v12(conn: &Connection) -> Result<()> {
+    use crate::web_cache::*;
+
+    info!("Migration v11 -> v12: Creating web_cache table");
+
+    conn.execute_batch(CREATE_WEB_CACHE_TABLE)?;
+
+    for index in CREATE_WEB_CACHE_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    info!("✓ Migration v11 -> v12 complete");
+    Ok(())
+}
+
+/// Migration to v13: Add curators, review tasks and the curation audit log
+fn migrate_to_v13(conn: &Connection) -> Result<()> {
+    use crate::curation::*;
+
+    info!("Migration v12 -> v13: Creating curation tables");
+
+    conn.execute_batch(CREATE_CURATORS_TABLE)?;
+    conn.execute_batch(CREATE_CURATOR_TASKS_TABLE)?;
+    conn.execute_batch(CREATE_CURATOR_AUDIT_TABLE)?;
+
+    for index in CREATE_CURATION_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    info!("✓ Migration v12 -> v13 complete");
+    Ok(())
+}
+
+/// Migration to v14: Add grain flags and per-grain moderation state
+fn migrate_to_v14(conn: &Connection) -> Result<()> {
+    use crate::moderation::*;
+
+    info!("Migration v13 -> v14: Creating moderation tables");
+
+    conn.execute_batch(CREATE_GRAIN_FLAGS_TABLE)?;
+    conn.execute_batch(CREATE_GRAIN_MODERATION_TABLE)?;
+
+    for index in CREATE_MODERATION_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    info!("✓ Migration v13 -> v14 complete");
+    Ok(())
+}
+
+/// Migration to v15: Move approval requests into the database
+fn migrate_to_v15(conn: &Connection) -> Result<()> {
+    use crate::approvals::*;
+
+    info!("Migration v14 -> v15: Creating approvals table");
+
+    conn.execute_batch(CREATE_APPROVALS_TABLE)?;
+
+    for index in CREATE_APPROVALS_INDEXES {
+        conn.execute(index, [])?;
+    }
+
+    info!("✓ Migration v14 -> v15 complete");
+    Ok(())
+}
+
+/// Migration to v16: Add release token hashes to curator tasks
+fn migrate_to_v16(conn: &Connection) -> Result<()> {
+    use crate::curation::*;
+
+    info!("Migration v15 -> v16: Adding curator task release tokens");
+
+    conn.execute_batch(ADD_CURATOR_RELEASE_TOKEN)?;
+
+    info!("✓ Migration v15 -> v16 complete");
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
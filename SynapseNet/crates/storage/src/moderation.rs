@@ -0,0 +1,108 @@
+//! Grain flags and local quarantine state
+//!
+//! Every signed flag, unflag and appeal received is kept in `grain_flags`.
+//! A signer's latest flag or unflag on a grain decides whether their flag
+//! counts. `grain_moderation` holds this node's verdict per grain, which the
+//! node derives from the flags it trusts.
+
+/// How far ahead of local time a flag may be dated
+pub const MAX_FLAG_CLOCK_SKEW_MS: i64 = 5 * 60 * 1000;
+
+/// How long flags on grains this node does not hold are kept
+pub const ORPHAN_FLAG_TTL_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Most flags kept on grains this node does not hold
+pub const MAX_ORPHAN_FLAGS: usize = 10_000;
+
+/// Local moderation state of a grain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModerationStatus {
+    /// Flagged below the quarantine threshold; still visible
+    Flagged,
+    /// Hidden from search
+    Quarantined,
+    /// Quarantined, and the author has appealed
+    Appealed,
+    /// Released by the operator; earlier flags no longer count
+    Cleared,
+}
+
+impl ModerationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Flagged => "flagged",
+            Self::Quarantined => "quarantined",
+            Self::Appealed => "appealed",
+            Self::Cleared => "cleared",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "flagged" => Some(Self::Flagged),
+            "quarantined" => Some(Self::Quarantined),
+            "appealed" => Some(Self::Appealed),
+            "cleared" => Some(Self::Cleared),
+            _ => None,
+        }
+    }
+
+    /// Whether grains in this state are hidden from search
+    pub fn is_hidden(&self) -> bool {
+        matches!(self, Self::Quarantined | Self::Appealed)
+    }
+}
+
+/// This node's verdict on a grain
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrainModeration {
+    pub grain_id: [u8; 32],
+    pub status: ModerationStatus,
+    /// Trust-weighted sum of the flags that count
+    pub flag_weight: f64,
+    /// Flags received at or before this time were reviewed by the operator
+    pub cleared_at: Option<i64>,
+    /// Appeals received at or before this time were dismissed by the operator
+    pub appeal_dismissed_at: Option<i64>,
+    pub updated_at: i64,
+}
+
+/// Result of recording a flag message
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlagOutcome {
+    /// Stored; the grain should be re-evaluated
+    Recorded,
+    /// Already seen
+    Duplicate,
+    /// Not accepted, e.g. an appeal by someone other than the author
+    Rejected(String),
+}
+
+pub const CREATE_GRAIN_FLAGS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS grain_flags (
+    id BLOB PRIMARY KEY,
+    grain_id BLOB NOT NULL,
+    signer BLOB NOT NULL,
+    action TEXT NOT NULL,
+    ts_unix_ms INTEGER NOT NULL,
+    record BLOB NOT NULL,
+    received_at INTEGER NOT NULL,
+    pending_broadcast INTEGER NOT NULL DEFAULT 0
+)
+"#;
+
+pub const CREATE_GRAIN_MODERATION_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS grain_moderation (
+    grain_id BLOB PRIMARY KEY,
+    status TEXT NOT NULL,
+    flag_weight REAL NOT NULL,
+    cleared_at INTEGER,
+    appeal_dismissed_at INTEGER,
+    updated_at INTEGER NOT NULL
+)
+"#;
+
+pub const CREATE_MODERATION_INDEXES: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_grain_flags_grain ON grain_flags(grain_id, ts_unix_ms)",
+    "CREATE INDEX IF NOT EXISTS idx_grain_moderation_status ON grain_moderation(status)",
+];
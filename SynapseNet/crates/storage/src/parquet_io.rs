@@ -1,21 +1,34 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use arrow::array::{
-    Array, ArrayRef, BinaryArray, Float32Array, Int64Array, ListArray, StringArray,
+    Array, ArrayRef, BinaryArray, Float32Array, Float64Array, Int64Array, ListArray, StringArray,
 };
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
+use ed25519_dalek::SigningKey;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use parquet::arrow::arrow_writer::ArrowWriter;
 use parquet::file::properties::WriterProperties;
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::path::Path;
 use std::sync::Arc;
-use synapsenet_core::Grain;
+use synapsenet_core::poe::Credit;
+use synapsenet_core::{Grain, Link};
 use tracing::{info, warn};
 
+use crate::manifest::{hash_file, ExportManifest, ManifestPart, PartKind};
+use crate::Store;
+
 /// Batch size for Parquet export (10,000 grains per file)
 const BATCH_SIZE: usize = 10_000;
 
-/// Parquet exporter for grains
+/// Grain schema version; v2 adds the embedding model and dimensions
+const GRAIN_SCHEMA_VERSION: u32 = 2;
+
+/// Link and credit schema version
+const RECORD_SCHEMA_VERSION: u32 = 1;
+
+/// Parquet exporter for grains, links and credits
 pub struct ParquetExporter {
     output_dir: String,
     batch_size: usize,
@@ -30,35 +43,61 @@ impl ParquetExporter {
         }
     }
 
-    /// Export grains to Parquet files
-    pub fn export(&self, grains: Vec<Grain>) -> Result<ExportStats> {
-        info!("Starting Parquet export of {} grains", grains.len());
+    /// Export grains, links and credits to Parquet files
+    ///
+    /// Writes a `manifest.json` signed by `signing_key` that lists every
+    /// part with its hash and row count.
+    pub fn export(
+        &self,
+        grains: Vec<Grain>,
+        links: Vec<Link>,
+        credits: Vec<Credit>,
+        signing_key: &SigningKey,
+    ) -> Result<ExportStats> {
+        info!(
+            "Starting Parquet export of {} grains, {} links, {} credits",
+            grains.len(),
+            links.len(),
+            credits.len()
+        );
 
         // Create output directory if it doesn't exist
         std::fs::create_dir_all(&self.output_dir)?;
 
         let mut stats = ExportStats {
             total_grains: grains.len(),
+            total_links: links.len(),
+            total_credits: credits.len(),
             files_created: 0,
             bytes_written: 0,
         };
+        let mut parts = Vec::new();
 
         // Process grains in batches
         for (batch_idx, batch) in grains.chunks(self.batch_size).enumerate() {
-            let file_path = format!("{}/grains_{:04}.parquet", self.output_dir, batch_idx);
-            let bytes = self.write_batch(batch, &file_path)?;
-
-            stats.files_created += 1;
-            stats.bytes_written += bytes;
-
-            info!(
-                "Wrote batch {} ({} grains) to {}",
-                batch_idx,
-                batch.len(),
-                file_path
-            );
+            let batch_data = grains_to_record_batch(batch, create_grain_schema())?;
+            let mut part = self.write_part(PartKind::Grains, batch_idx, batch_data, &mut stats)?;
+            part.embedding_models = batch
+                .iter()
+                .filter_map(|grain| grain.meta.embedding_model.clone())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            parts.push(part);
+        }
+
+        for (batch_idx, batch) in links.chunks(self.batch_size).enumerate() {
+            let batch_data = links_to_record_batch(batch)?;
+            parts.push(self.write_part(PartKind::Links, batch_idx, batch_data, &mut stats)?);
+        }
+
+        for (batch_idx, batch) in credits.chunks(self.batch_size).enumerate() {
+            let batch_data = credits_to_record_batch(batch)?;
+            parts.push(self.write_part(PartKind::Credits, batch_idx, batch_data, &mut stats)?);
         }
 
+        ExportManifest::new(parts, signing_key)?.write(Path::new(&self.output_dir))?;
+
         info!(
             "Export complete: {} files, {} bytes",
             stats.files_created, stats.bytes_written
@@ -67,21 +106,46 @@ impl ParquetExporter {
         Ok(stats)
     }
 
-    /// Write a batch of grains to a Parquet file
-    fn write_batch(&self, grains: &[Grain], file_path: &str) -> Result<u64> {
-        // Create Arrow schema
-        let schema = create_grain_schema();
-
-        // Convert grains to Arrow arrays
-        let batch = grains_to_record_batch(grains, schema.clone())?;
+    /// Write one batch as a part file and describe it for the manifest
+    fn write_part(
+        &self,
+        kind: PartKind,
+        batch_idx: usize,
+        batch: RecordBatch,
+        stats: &mut ExportStats,
+    ) -> Result<ManifestPart> {
+        let file = format!("{}_{:04}.parquet", part_prefix(kind), batch_idx);
+        let file_path = Path::new(&self.output_dir).join(&file);
+        let rows = batch.num_rows();
+        let bytes = self.write_batch(batch, &file_path)?;
+
+        stats.files_created += 1;
+        stats.bytes_written += bytes;
+
+        info!("Wrote {} ({} rows)", file_path.display(), rows);
+
+        Ok(ManifestPart {
+            file,
+            kind,
+            rows,
+            schema_version: match kind {
+                PartKind::Grains => GRAIN_SCHEMA_VERSION,
+                PartKind::Links | PartKind::Credits => RECORD_SCHEMA_VERSION,
+            },
+            embedding_models: Vec::new(),
+            blake3: hash_file(&file_path)?,
+        })
+    }
 
+    /// Write a record batch to a Parquet file
+    fn write_batch(&self, batch: RecordBatch, file_path: &Path) -> Result<u64> {
         // Create Parquet writer with Snappy compression
         let file = File::create(file_path)?;
         let props = WriterProperties::builder()
             .set_compression(parquet::basic::Compression::SNAPPY)
             .build();
 
-        let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
 
         // Write batch
         writer.write(&batch)?;
@@ -93,128 +157,220 @@ impl ParquetExporter {
     }
 }
 
-/// Parquet importer for grains
+/// Parquet importer for grains, links and credits
 pub struct ParquetImporter {
     input_dir: String,
+    allow_unsigned: bool,
+    expected_signer: Option<[u8; 32]>,
 }
 
 impl ParquetImporter {
     /// Create new Parquet importer
     pub fn new(input_dir: String) -> Self {
-        Self { input_dir }
+        Self {
+            input_dir,
+            allow_unsigned: false,
+            expected_signer: None,
+        }
+    }
+
+    /// Accept exports without a manifest (written before manifests existed)
+    ///
+    /// Only the grain files are read and nothing guards against truncation.
+    pub fn allow_unsigned(mut self) -> Self {
+        self.allow_unsigned = true;
+        self
+    }
+
+    /// Only accept exports signed by `node_pk`
+    pub fn expect_signer(mut self, node_pk: [u8; 32]) -> Self {
+        self.expected_signer = Some(node_pk);
+        self
     }
 
-    /// Import grains from Parquet files
-    pub fn import(&self) -> Result<ImportStats> {
+    /// Import grains, links and credits from Parquet files into `store`
+    ///
+    /// The manifest signature and every part's hash are checked before any
+    /// rows are read; a mismatch fails the whole import. Grains with a valid
+    /// signature are stored together with the links and credits in one
+    /// transaction, so a failed import stores nothing.
+    pub fn import(&self, store: &Store) -> Result<ImportStats> {
         info!("Starting Parquet import from {}", self.input_dir);
 
-        let mut stats = ImportStats {
-            total_grains: 0,
-            imported: 0,
-            skipped: 0,
-            invalid: 0,
-        };
+        let dir = Path::new(&self.input_dir);
+        let mut stats = ImportStats::default();
 
-        // Find all Parquet files
-        let files = self.find_parquet_files()?;
+        let parts = match ExportManifest::read(dir)? {
+            Some(manifest) => {
+                let signer = self.check_manifest(dir, &manifest)?;
+                stats.signer = Some(signer);
+                manifest.parts
+            }
+            None if self.allow_unsigned => {
+                warn!("No manifest in {}, importing unsigned grains", self.input_dir);
+                self.legacy_parts()?
+            }
+            None => {
+                return Err(anyhow!(
+                    "No manifest in {}: the export is unsigned or incomplete",
+                    self.input_dir
+                ))
+            }
+        };
 
-        if files.is_empty() {
+        if parts.is_empty() {
             warn!("No Parquet files found in {}", self.input_dir);
             return Ok(stats);
         }
 
-        info!("Found {} Parquet files", files.len());
+        info!("Found {} Parquet files", parts.len());
+
+        let mut grains = Vec::new();
+        let mut links = Vec::new();
+        let mut credits = Vec::new();
 
         // Process each file
-        for file_path in files {
-            let batch_stats = self.import_file(&file_path)?;
-            stats.total_grains += batch_stats.total_grains;
-            stats.imported += batch_stats.imported;
-            stats.skipped += batch_stats.skipped;
-            stats.invalid += batch_stats.invalid;
-
-            info!(
-                "Imported {} from {} ({} imported, {} skipped, {} invalid)",
-                file_path.display(),
-                batch_stats.total_grains,
-                batch_stats.imported,
-                batch_stats.skipped,
-                batch_stats.invalid
-            );
+        for part in &parts {
+            let file_path = dir.join(&part.file);
+            let rows = match part.kind {
+                PartKind::Grains => {
+                    let (total, valid) = self.read_grains(&file_path)?;
+                    stats.total_grains += total;
+                    stats.invalid += total - valid.len();
+
+                    info!(
+                        "Read {} grains from {} ({} invalid)",
+                        total,
+                        file_path.display(),
+                        total - valid.len()
+                    );
+                    grains.extend(valid);
+                    total
+                }
+                PartKind::Links => {
+                    let batch = read_batches(&file_path, record_batch_to_links)?;
+                    let rows = batch.len();
+                    links.extend(batch);
+                    rows
+                }
+                PartKind::Credits => {
+                    let batch = read_batches(&file_path, record_batch_to_credits)?;
+                    let rows = batch.len();
+                    credits.extend(batch);
+                    rows
+                }
+            };
+
+            // Legacy parts have no recorded row count
+            if stats.signer.is_some() && rows != part.rows {
+                return Err(anyhow!(
+                    "{} has {} rows, manifest lists {}",
+                    part.file,
+                    rows,
+                    part.rows
+                ));
+            }
         }
 
+        stats.imported = store.import_records(&grains, &links, &credits)?;
+        stats.skipped = grains.len() - stats.imported;
+        stats.links = links.len();
+        stats.credits = credits.len();
+
         info!(
-            "Import complete: {} total, {} imported, {} skipped, {} invalid",
-            stats.total_grains, stats.imported, stats.skipped, stats.invalid
+            "Import complete: {} total, {} imported, {} skipped, {} invalid, {} links, {} credits",
+            stats.total_grains,
+            stats.imported,
+            stats.skipped,
+            stats.invalid,
+            stats.links,
+            stats.credits
         );
 
         Ok(stats)
     }
 
-    /// Import grains from a single Parquet file
-    fn import_file(&self, file_path: &Path) -> Result<ImportStats> {
-        let file = File::open(file_path)?;
-        let reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)?
-            .build()?;
-
-        let mut stats = ImportStats {
-            total_grains: 0,
-            imported: 0,
-            skipped: 0,
-            invalid: 0,
-        };
+    /// Verify the manifest signature and every part, returning the signer
+    fn check_manifest(&self, dir: &Path, manifest: &ExportManifest) -> Result<[u8; 32]> {
+        if !manifest.verify()? {
+            return Err(anyhow!("Manifest signature is invalid"));
+        }
 
-        for batch_result in reader {
-            let batch = batch_result?;
-            let grains = record_batch_to_grains(&batch)?;
-
-            stats.total_grains += grains.len();
-
-            for grain in grains {
-                // Verify signature
-                match grain.verify() {
-                    Ok(true) => {
-                        // TODO: Store grain in database
-                        // For now, just count as imported
-                        stats.imported += 1;
-                    }
-                    Ok(false) => {
-                        warn!(
-                            "Invalid signature for grain {:?}",
-                            hex_encode(&grain.id[..8])
-                        );
-                        stats.invalid += 1;
-                    }
-                    Err(e) => {
-                        warn!("Error verifying grain: {}", e);
-                        stats.invalid += 1;
-                    }
-                }
+        let signer = manifest.signer()?;
+        if let Some(expected) = self.expected_signer {
+            if signer != expected {
+                return Err(anyhow!(
+                    "Export signed by {}, expected {}",
+                    manifest.node_pk,
+                    hex::encode(expected)
+                ));
             }
         }
 
-        Ok(stats)
+        for part in &manifest.parts {
+            let supported = match part.kind {
+                PartKind::Grains => part.schema_version <= GRAIN_SCHEMA_VERSION,
+                PartKind::Links | PartKind::Credits => part.schema_version <= RECORD_SCHEMA_VERSION,
+            };
+            if !supported {
+                return Err(anyhow!(
+                    "{} uses unsupported schema version {}",
+                    part.file,
+                    part.schema_version
+                ));
+            }
+            manifest.check_part(dir, part)?;
+        }
+
+        Ok(signer)
+    }
+
+    /// Read a grain file, returning its row count and the correctly signed grains
+    fn read_grains(&self, file_path: &Path) -> Result<(usize, Vec<Grain>)> {
+        let grains = read_batches(file_path, record_batch_to_grains)?;
+        let total = grains.len();
+
+        let valid = grains
+            .into_iter()
+            .filter(|grain| match grain.verify_with_backend(grain.meta.crypto_backend) {
+                Ok(true) => true,
+                Ok(false) => {
+                    warn!("Invalid signature for grain {:?}", hex_encode(&grain.id[..8]));
+                    false
+                }
+                Err(e) => {
+                    warn!("Error verifying grain: {}", e);
+                    false
+                }
+            })
+            .collect();
+
+        Ok((total, valid))
     }
 
-    /// Find all Parquet files in the input directory
-    fn find_parquet_files(&self) -> Result<Vec<std::path::PathBuf>> {
-        let mut files = Vec::new();
+    /// Grain files of an export written without a manifest
+    fn legacy_parts(&self) -> Result<Vec<ManifestPart>> {
+        let mut parts = Vec::new();
 
         for entry in std::fs::read_dir(&self.input_dir)? {
             let entry = entry?;
             let path = entry.path();
-
-            if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    if ext == "parquet" {
-                        files.push(path);
-                    }
-                }
+            let file = entry.file_name().to_string_lossy().to_string();
+
+            if path.is_file() && file.starts_with("grains_") && file.ends_with(".parquet") {
+                parts.push(ManifestPart {
+                    file,
+                    kind: PartKind::Grains,
+                    rows: 0,
+                    schema_version: 1,
+                    embedding_models: Vec::new(),
+                    blake3: String::new(),
+                });
             }
         }
 
-        files.sort();
-        Ok(files)
+        parts.sort_by(|a, b| a.file.cmp(&b.file));
+        Ok(parts)
     }
 }
 
@@ -222,17 +378,49 @@ impl ParquetImporter {
 #[derive(Debug, Clone)]
 pub struct ExportStats {
     pub total_grains: usize,
+    pub total_links: usize,
+    pub total_credits: usize,
     pub files_created: usize,
     pub bytes_written: u64,
 }
 
 /// Import statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ImportStats {
     pub total_grains: usize,
+    /// Grains newly stored
     pub imported: usize,
+    /// Valid grains that were already stored
     pub skipped: usize,
+    /// Grains whose signature does not verify; not stored
     pub invalid: usize,
+    pub links: usize,
+    pub credits: usize,
+    /// Node that signed the manifest; `None` for unsigned exports
+    pub signer: Option<[u8; 32]>,
+}
+
+fn part_prefix(kind: PartKind) -> &'static str {
+    match kind {
+        PartKind::Grains => "grains",
+        PartKind::Links => "links",
+        PartKind::Credits => "credits",
+    }
+}
+
+/// Read every record batch of a Parquet file
+fn read_batches<T>(
+    file_path: &Path,
+    convert: fn(&RecordBatch) -> Result<Vec<T>>,
+) -> Result<Vec<T>> {
+    let file = File::open(file_path)?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+
+    let mut records = Vec::new();
+    for batch_result in reader {
+        records.extend(convert(&batch_result?)?);
+    }
+    Ok(records)
 }
 
 /// Create Arrow schema for grains
@@ -245,7 +433,7 @@ fn create_grain_schema() -> Arc<Schema> {
             false,
         ),
         Field::new("author_pk", DataType::Binary, false),
-        Field::new("crypto_backend", DataType::Utf8, false), // "Classical" or "PostQuantum"
+        Field::new("crypto_backend", DataType::Utf8, false), // "Classical", "PostQuantum" or "Hybrid"
         Field::new("ts_unix_ms", DataType::Int64, false),
         Field::new(
             "tags",
@@ -257,6 +445,8 @@ fn create_grain_schema() -> Arc<Schema> {
         Field::new("title", DataType::Utf8, true),
         Field::new("summary", DataType::Utf8, true),
         Field::new("sig", DataType::Binary, false),
+        Field::new("embedding_model", DataType::Utf8, true),
+        Field::new("embedding_dimensions", DataType::Int64, true),
     ]))
 }
 
@@ -273,6 +463,8 @@ fn grains_to_record_batch(grains: &[Grain], schema: Arc<Schema>) -> Result<Recor
     let mut titles = Vec::new();
     let mut summaries = Vec::new();
     let mut sigs: Vec<Vec<u8>> = Vec::new();
+    let mut embedding_models = Vec::new();
+    let mut embedding_dimensions = Vec::new();
 
     for grain in grains {
         ids.push(grain.id.to_vec());
@@ -286,6 +478,8 @@ fn grains_to_record_batch(grains: &[Grain], schema: Arc<Schema>) -> Result<Recor
         titles.push(grain.meta.title.clone());
         summaries.push(grain.meta.summary.clone());
         sigs.push(grain.sig.clone());
+        embedding_models.push(grain.meta.embedding_model.clone());
+        embedding_dimensions.push(grain.meta.embedding_dimensions.map(|dims| dims as i64));
     }
 
     // Create Arrow arrays
@@ -304,6 +498,8 @@ fn grains_to_record_batch(grains: &[Grain], schema: Arc<Schema>) -> Result<Recor
     let title_array = StringArray::from(titles);
     let summary_array = StringArray::from(summaries);
     let sig_array = BinaryArray::from_vec(sig_refs);
+    let embedding_model_array = StringArray::from(embedding_models);
+    let embedding_dimensions_array = Int64Array::from(embedding_dimensions);
 
     let batch = RecordBatch::try_new(
         schema,
@@ -319,6 +515,8 @@ fn grains_to_record_batch(grains: &[Grain], schema: Arc<Schema>) -> Result<Recor
             Arc::new(title_array) as ArrayRef,
             Arc::new(summary_array) as ArrayRef,
             Arc::new(sig_array) as ArrayRef,
+            Arc::new(embedding_model_array) as ArrayRef,
+            Arc::new(embedding_dimensions_array) as ArrayRef,
         ],
     )?;
 
@@ -387,6 +585,13 @@ fn record_batch_to_grains(batch: &RecordBatch) -> Result<Vec<Grain>> {
         .as_any()
         .downcast_ref::<BinaryArray>()
         .unwrap();
+    // Schema v2 columns, missing from older exports
+    let embedding_model_array = batch
+        .column_by_name("embedding_model")
+        .and_then(|column| column.as_any().downcast_ref::<StringArray>());
+    let embedding_dimensions_array = batch
+        .column_by_name("embedding_dimensions")
+        .and_then(|column| column.as_any().downcast_ref::<Int64Array>());
 
     for i in 0..num_rows {
         let id: [u8; 32] = id_array.value(i).try_into()?;
@@ -396,6 +601,7 @@ fn record_batch_to_grains(batch: &RecordBatch) -> Result<Vec<Grain>> {
         let crypto_backend = match crypto_backend_str {
             "Classical" => synapsenet_core::CryptoBackend::Classical,
             "PostQuantum" => synapsenet_core::CryptoBackend::PostQuantum,
+            "Hybrid" => synapsenet_core::CryptoBackend::Hybrid,
             _ => {
                 // Fallback: detect from public key length
                 if author_pk.len() == 32 {
@@ -420,6 +626,12 @@ fn record_batch_to_grains(batch: &RecordBatch) -> Result<Vec<Grain>> {
             Some(summary_array.value(i).to_string())
         };
         let sig = sig_array.value(i).to_vec();
+        let embedding_model = embedding_model_array
+            .filter(|array| !array.is_null(i))
+            .map(|array| array.value(i).to_string());
+        let embedding_dimensions = embedding_dimensions_array
+            .filter(|array| !array.is_null(i))
+            .map(|array| array.value(i) as usize);
 
         let grain = Grain {
             id,
@@ -433,8 +645,8 @@ fn record_batch_to_grains(batch: &RecordBatch) -> Result<Vec<Grain>> {
                 lang,
                 title,
                 summary,
-                embedding_model: None, // Legacy data doesn't have this
-                embedding_dimensions: None,
+                embedding_model,
+                embedding_dimensions,
             },
             sig,
         };
@@ -445,6 +657,111 @@ fn record_batch_to_grains(batch: &RecordBatch) -> Result<Vec<Grain>> {
     Ok(grains)
 }
 
+/// Convert links to an Arrow RecordBatch
+fn links_to_record_batch(links: &[Link]) -> Result<RecordBatch> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("from_id", DataType::Binary, false),
+        Field::new("to_id", DataType::Binary, false),
+        Field::new("weight", DataType::Float32, false),
+        Field::new("rationale", DataType::Utf8, true),
+        Field::new("sig", DataType::Binary, false),
+    ]));
+
+    let from_ids: Vec<&[u8]> = links.iter().map(|link| &link.from[..]).collect();
+    let to_ids: Vec<&[u8]> = links.iter().map(|link| &link.to[..]).collect();
+    let sigs: Vec<&[u8]> = links.iter().map(|link| link.sig.as_slice()).collect();
+
+    Ok(RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(BinaryArray::from_vec(from_ids)) as ArrayRef,
+            Arc::new(BinaryArray::from_vec(to_ids)) as ArrayRef,
+            Arc::new(Float32Array::from_iter_values(links.iter().map(|link| link.weight))) as ArrayRef,
+            Arc::new(StringArray::from_iter(links.iter().map(|link| link.rationale.clone()))) as ArrayRef,
+            Arc::new(BinaryArray::from_vec(sigs)) as ArrayRef,
+        ],
+    )?)
+}
+
+/// Convert an Arrow RecordBatch to links
+fn record_batch_to_links(batch: &RecordBatch) -> Result<Vec<Link>> {
+    let from_array = binary_column(batch, "from_id")?;
+    let to_array = binary_column(batch, "to_id")?;
+    let weight_array = typed_column::<Float32Array>(batch, "weight")?;
+    let rationale_array = typed_column::<StringArray>(batch, "rationale")?;
+    let sig_array = binary_column(batch, "sig")?;
+
+    (0..batch.num_rows())
+        .map(|i| {
+            Ok(Link {
+                from: from_array.value(i).try_into()?,
+                to: to_array.value(i).try_into()?,
+                weight: weight_array.value(i),
+                rationale: (!rationale_array.is_null(i))
+                    .then(|| rationale_array.value(i).to_string()),
+                sig: sig_array.value(i).to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// Convert credits to an Arrow RecordBatch
+fn credits_to_record_batch(credits: &[Credit]) -> Result<RecordBatch> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("grain_id", DataType::Binary, false),
+        Field::new("node_pk", DataType::Binary, false),
+        Field::new("ngt", DataType::Float64, false),
+        Field::new("reason", DataType::Utf8, false),
+        Field::new("ts_unix_ms", DataType::Int64, false),
+    ]));
+
+    let grain_ids: Vec<&[u8]> = credits.iter().map(|credit| &credit.grain_id[..]).collect();
+    let node_pks: Vec<&[u8]> = credits.iter().map(|credit| &credit.node_pk[..]).collect();
+
+    Ok(RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(BinaryArray::from_vec(grain_ids)) as ArrayRef,
+            Arc::new(BinaryArray::from_vec(node_pks)) as ArrayRef,
+            Arc::new(Float64Array::from_iter_values(credits.iter().map(|credit| credit.ngt))) as ArrayRef,
+            Arc::new(StringArray::from_iter_values(credits.iter().map(|credit| credit.reason.as_str()))) as ArrayRef,
+            Arc::new(Int64Array::from_iter_values(credits.iter().map(|credit| credit.ts_unix_ms))) as ArrayRef,
+        ],
+    )?)
+}
+
+/// Convert an Arrow RecordBatch to credits
+fn record_batch_to_credits(batch: &RecordBatch) -> Result<Vec<Credit>> {
+    let grain_id_array = binary_column(batch, "grain_id")?;
+    let node_pk_array = binary_column(batch, "node_pk")?;
+    let ngt_array = typed_column::<Float64Array>(batch, "ngt")?;
+    let reason_array = typed_column::<StringArray>(batch, "reason")?;
+    let ts_array = typed_column::<Int64Array>(batch, "ts_unix_ms")?;
+
+    (0..batch.num_rows())
+        .map(|i| {
+            Ok(Credit {
+                grain_id: grain_id_array.value(i).try_into()?,
+                node_pk: node_pk_array.value(i).try_into()?,
+                ngt: ngt_array.value(i),
+                reason: reason_array.value(i).to_string(),
+                ts_unix_ms: ts_array.value(i),
+            })
+        })
+        .collect()
+}
+
+fn binary_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a BinaryArray> {
+    typed_column::<BinaryArray>(batch, name)
+}
+
+fn typed_column<'a, T: Array + 'static>(batch: &'a RecordBatch, name: &str) -> Result<&'a T> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_any().downcast_ref::<T>())
+        .ok_or_else(|| anyhow!("Missing or mistyped column: {}", name))
+}
+
 /// Create Arrow ListArray from Vec<Vec<f32>>
 fn create_float_list_array(data: Vec<Vec<f32>>) -> ListArray {
     use arrow::array::Float32Builder;
@@ -509,3 +826,147 @@ fn extract_string_list(array: &ListArray, index: usize) -> Vec<String> {
 fn hex_encode(bytes: &[u8]) -> String {
     bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::OsRng, RngCore};
+    use synapsenet_core::GrainMeta;
+
+    fn generate_signing_key() -> SigningKey {
+        let mut secret_bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut secret_bytes);
+        SigningKey::from_bytes(&secret_bytes)
+    }
+
+    fn signed_grain(signing_key: &SigningKey, title: &str) -> Grain {
+        let meta = GrainMeta {
+            author_pk: signing_key.verifying_key().to_bytes().to_vec(),
+            crypto_backend: synapsenet_core::CryptoBackend::Classical,
+            ts_unix_ms: 1234567890,
+            tags: vec!["test".to_string()],
+            mime: "text/plain".to_string(),
+            lang: "en".to_string(),
+            title: Some(title.to_string()),
+            summary: None,
+            embedding_model: Some("test-model".to_string()),
+            embedding_dimensions: Some(3),
+        };
+        Grain::new(vec![0.1, 0.2, 0.3], meta, signing_key).unwrap()
+    }
+
+    fn test_store(dir: &tempfile::TempDir) -> Store {
+        Store::new(&dir.path().join("test.db").to_string_lossy()).unwrap()
+    }
+
+    fn export_fixture(dir: &Path, signing_key: &SigningKey) -> ExportStats {
+        let a = signed_grain(signing_key, "a");
+        let b = signed_grain(signing_key, "b");
+        let link = Link::new(a.id, b.id, 0.5, Some("related".to_string()), signing_key).unwrap();
+        let credit = Credit {
+            grain_id: a.id,
+            node_pk: signing_key.verifying_key().to_bytes(),
+            ngt: 1.5,
+            reason: "novelty".to_string(),
+            ts_unix_ms: 1234567890,
+        };
+
+        ParquetExporter::new(dir.to_string_lossy().to_string())
+            .export(vec![a, b], vec![link], vec![credit], signing_key)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_signed_export_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_dir = tempfile::tempdir().unwrap();
+        let store = test_store(&db_dir);
+        let signing_key = generate_signing_key();
+        let node_pk = signing_key.verifying_key().to_bytes();
+
+        let exported = export_fixture(dir.path(), &signing_key);
+        assert_eq!(exported.files_created, 3);
+
+        let manifest = ExportManifest::read(dir.path()).unwrap().unwrap();
+        assert!(manifest.verify().unwrap());
+        assert_eq!(manifest.parts[0].embedding_models, vec!["test-model"]);
+
+        let other = generate_signing_key().verifying_key().to_bytes();
+        assert!(ParquetImporter::new(dir.path().to_string_lossy().to_string())
+            .expect_signer(other)
+            .import(&store)
+            .is_err());
+        assert_eq!(store.count_grains().unwrap(), 0);
+
+        let importer =
+            ParquetImporter::new(dir.path().to_string_lossy().to_string()).expect_signer(node_pk);
+        let stats = importer.import(&store).unwrap();
+        assert_eq!(stats.total_grains, 2);
+        assert_eq!(stats.imported, 2);
+        assert_eq!(stats.invalid, 0);
+        assert_eq!(stats.links, 1);
+        assert_eq!(stats.credits, 1);
+        assert_eq!(stats.signer, Some(node_pk));
+
+        let mut titles: Vec<_> = store
+            .get_all_grains()
+            .unwrap()
+            .into_iter()
+            .map(|grain| grain.meta.title.unwrap())
+            .collect();
+        titles.sort();
+        assert_eq!(titles, vec!["a", "b"]);
+        assert_eq!(store.get_all_links().unwrap().len(), 1);
+        assert_eq!(store.get_all_credits().unwrap().len(), 1);
+        assert_eq!(store.get_node_ngt(&node_pk).unwrap(), 1.5);
+
+        // Importing the same export again stores nothing new
+        let stats = importer.import(&store).unwrap();
+        assert_eq!(stats.imported, 0);
+        assert_eq!(stats.skipped, 2);
+        assert_eq!(store.count_grains().unwrap(), 2);
+        assert_eq!(store.get_all_credits().unwrap().len(), 1);
+        assert_eq!(store.get_node_ngt(&node_pk).unwrap(), 1.5);
+    }
+
+    #[test]
+    fn test_tampered_or_incomplete_export_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_dir = tempfile::tempdir().unwrap();
+        let store = test_store(&db_dir);
+        let signing_key = generate_signing_key();
+        export_fixture(dir.path(), &signing_key);
+        let import =
+            || ParquetImporter::new(dir.path().to_string_lossy().to_string()).import(&store);
+
+        // Edited part
+        let links_path = dir.path().join("links_0000.parquet");
+        let mut bytes = std::fs::read(&links_path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        std::fs::write(&links_path, bytes).unwrap();
+        assert!(import().is_err());
+
+        // Missing part
+        std::fs::remove_file(&links_path).unwrap();
+        assert!(import().is_err());
+
+        // Edited manifest
+        let mut manifest = ExportManifest::read(dir.path()).unwrap().unwrap();
+        manifest.parts.retain(|part| part.kind != PartKind::Links);
+        manifest.write(dir.path()).unwrap();
+        assert!(import().is_err());
+
+        // Unsigned exports need an explicit opt-in
+        std::fs::remove_file(dir.path().join(crate::manifest::MANIFEST_FILE)).unwrap();
+        assert!(import().is_err());
+        assert_eq!(store.count_grains().unwrap(), 0);
+        let stats = ParquetImporter::new(dir.path().to_string_lossy().to_string())
+            .allow_unsigned()
+            .import(&store)
+            .unwrap();
+        assert_eq!(stats.imported, 2);
+        assert_eq!(stats.signer, None);
+        assert_eq!(store.count_grains().unwrap(), 2);
+    }
+}
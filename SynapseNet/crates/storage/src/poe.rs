@@ -0,0 +1,47 @@
+//! Persisted Proof of Emergence scores
+//!
+//! Each ingested grain keeps its latest PoE v2 score together with the NGT
+//! already credited for it, so rescoring only pays out the difference.
+
+use serde::{Deserialize, Serialize};
+
+/// Latest PoE score of a grain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoeScoreRecord {
+    pub grain_id: [u8; 32],
+    /// Grain author public key
+    pub author_pk: Vec<u8>,
+    pub novelty: f32,
+    pub coherence: f32,
+    pub reuse: f32,
+    /// Weighted total
+    pub total: f32,
+    /// NGT the grain has earned with this score
+    pub ngt_reward: f64,
+    /// NGT credited to the author so far
+    pub credited: f64,
+    /// Number of times the grain was rescored
+    pub epoch: u64,
+    /// Unix timestamp (seconds)
+    pub calculated_at: i64,
+}
+
+pub const CREATE_POE_SCORES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS poe_scores (
+    grain_id BLOB PRIMARY KEY,
+    author_pk BLOB NOT NULL,
+    novelty REAL NOT NULL,
+    coherence REAL NOT NULL,
+    reuse REAL NOT NULL,
+    total REAL NOT NULL,
+    ngt_reward REAL NOT NULL,
+    credited REAL NOT NULL DEFAULT 0,
+    epoch INTEGER NOT NULL DEFAULT 0,
+    calculated_at INTEGER NOT NULL
+)
+"#;
+
+pub const CREATE_POE_INDEXES: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_poe_scores_total ON poe_scores(total)",
+    "CREATE INDEX IF NOT EXISTS idx_poe_scores_calculated_at ON poe_scores(calculated_at)",
+];
@@ -0,0 +1,70 @@
+//! Private grain groups and relayed ciphertext
+//!
+//! Group keys this node holds live in `private_groups`, sealed with the
+//! node's `RecipientSecret`. Every grant and private grain seen is kept,
+//! readable or not, so the node can relay it and open it later once a grant
+//! for its group arrives. Opened grains go into the `grains` table, marked by
+//! `private_grains.grain_id`; `Store::get_grain` and `get_all_grains` skip
+//! them, and only `get_private_grains` returns them.
+
+/// Result of applying a group key grant
+#[derive(Debug, Clone, PartialEq)]
+pub enum GrantOutcome {
+    /// Grant was addressed to us; the group key is stored and `opened`
+    /// private grains received earlier were decrypted
+    Joined { group_id: [u8; 32], opened: usize },
+    /// Grant recorded for relaying only
+    Relayed,
+    /// Grant was already recorded
+    Duplicate,
+}
+
+/// Result of applying a private grain
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrivateGrainOutcome {
+    /// Grain decrypted with a held group key and stored for local search
+    Opened { grain_id: [u8; 32] },
+    /// Ciphertext recorded for relaying only
+    Relayed,
+    /// Private grain was already recorded
+    Duplicate,
+    /// Group key is held but the content does not check out
+    Rejected(String),
+}
+
+pub const CREATE_PRIVATE_GROUPS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS private_groups (
+    group_id BLOB PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    group_key BLOB NOT NULL,
+    created_at INTEGER NOT NULL
+)
+"#;
+
+pub const CREATE_GROUP_KEY_GRANTS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS group_key_grants (
+    id BLOB PRIMARY KEY,
+    group_id BLOB NOT NULL,
+    record BLOB NOT NULL,
+    received_at INTEGER NOT NULL,
+    pending_broadcast INTEGER NOT NULL DEFAULT 0
+)
+"#;
+
+pub const CREATE_PRIVATE_GRAINS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS private_grains (
+    id BLOB PRIMARY KEY,
+    group_id BLOB NOT NULL,
+    grain_id BLOB,
+    record BLOB NOT NULL,
+    received_at INTEGER NOT NULL,
+    pending_broadcast INTEGER NOT NULL DEFAULT 0
+)
+"#;
+
+pub const CREATE_PRIVATE_INDEXES: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_group_key_grants_pending ON group_key_grants(pending_broadcast)",
+    "CREATE INDEX IF NOT EXISTS idx_private_grains_group ON private_grains(group_id)",
+    "CREATE INDEX IF NOT EXISTS idx_private_grains_grain ON private_grains(grain_id)",
+    "CREATE INDEX IF NOT EXISTS idx_private_grains_pending ON private_grains(pending_broadcast)",
+];
@@ -0,0 +1,33 @@
+//! Provenance of grains written by agents
+//!
+//! Grains added through agent tools record the goal and episode that
+//! created them, so a goal's output can be traced and reviewed later.
+
+use uuid::Uuid;
+
+/// Goal and episode that created a grain
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrainProvenance {
+    pub grain_id: [u8; 32],
+    pub goal_id: Uuid,
+    pub episode_id: Option<Uuid>,
+    /// Tool that wrote the grain
+    pub tool: String,
+    /// User the goal ran for
+    pub user_id: String,
+    pub created_at: i64,
+}
+
+pub const CREATE_GRAIN_PROVENANCE_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS grain_provenance (
+    grain_id BLOB PRIMARY KEY,
+    goal_id TEXT NOT NULL,
+    episode_id TEXT,
+    tool TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+)
+"#;
+
+pub const CREATE_GRAIN_PROVENANCE_INDEXES: &[&str] =
+    &["CREATE INDEX IF NOT EXISTS idx_grain_provenance_goal ON grain_provenance(goal_id)"];
@@ -1,6 +1,8 @@
 //! Database schema v6 for reasoning system
 
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use synapsenet_core::{Episode, EpisodeAction, Goal, GoalStatus, Priority};
 use uuid::Uuid;
 
 /// Goal record in database
@@ -40,6 +42,21 @@ pub struct EpisodeRecord {
     pub timestamp: i64,
 }
 
+/// Episode action record in database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionRecord {
+    pub id: String,
+    pub episode_id: String,
+    pub goal_id: String,
+    pub tool_name: String,
+    pub input: String, // JSON
+    pub output: Option<String>, // JSON
+    pub error: Option<String>,
+    pub timestamp: i64,
+    pub execution_time_ms: i64,
+    pub success: bool,
+}
+
 /// Reasoning statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReasonStats {
@@ -88,6 +105,22 @@ CREATE TABLE IF NOT EXISTS episodes (
 );
 "#;
 
+pub const CREATE_EPISODE_ACTIONS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS episode_actions (
+    id TEXT PRIMARY KEY,
+    episode_id TEXT NOT NULL,
+    goal_id TEXT NOT NULL,
+    tool_name TEXT NOT NULL,
+    input TEXT NOT NULL,
+    output TEXT,
+    error TEXT,
+    timestamp INTEGER NOT NULL,
+    execution_time_ms INTEGER NOT NULL,
+    success INTEGER NOT NULL,
+    FOREIGN KEY (episode_id) REFERENCES episodes(id)
+);
+"#;
+
 pub const CREATE_REASON_STATS_TABLE: &str = r#"
 CREATE TABLE IF NOT EXISTS reason_stats (
     key TEXT PRIMARY KEY,
@@ -104,60 +137,128 @@ pub const CREATE_INDEXES: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS idx_episodes_goal_id ON episodes(goal_id);",
     "CREATE INDEX IF NOT EXISTS idx_episodes_step ON episodes(step);",
     "CREATE INDEX IF NOT EXISTS idx_episodes_timestamp ON episodes(timestamp);",
+    "CREATE INDEX IF NOT EXISTS idx_episode_actions_episode ON episode_actions(episode_id);",
+    "CREATE INDEX IF NOT EXISTS idx_episode_actions_goal ON episode_actions(goal_id);",
 ];
 
-/// Database operations
-pub struct ReasoningDb {
-    // TODO: Add actual database connection
+impl From<&Goal> for GoalRecord {
+    fn from(goal: &Goal) -> Self {
+        Self {
+            id: goal.id.to_string(),
+            text: goal.text.clone(),
+            status: goal.status.as_str().to_string(),
+            priority: goal.priority as i32,
+            created_by: goal.created_by.clone(),
+            created_at: goal.created_at,
+            updated_at: goal.updated_at,
+            parent_id: goal.parent_id.map(|id| id.to_string()),
+            metadata: goal.metadata.to_string(),
+        }
+    }
 }
 
-impl ReasoningDb {
-    /// Initialize database with schema
-    pub async fn init() -> Result<Self, String> {
-        // TODO: Implement SQLite connection
-        Ok(Self {})
-    }
+impl TryFrom<GoalRecord> for Goal {
+    type Error = anyhow::Error;
 
-    /// Create all tables
-    pub async fn create_tables(&self) -> Result<(), String> {
-        // TODO: Execute CREATE TABLE statements
-        Ok(())
+    fn try_from(record: GoalRecord) -> Result<Self> {
+        Ok(Goal {
+            id: Uuid::parse_str(&record.id)?,
+            text: record.text,
+            status: GoalStatus::parse(&record.status)
+                .ok_or_else(|| anyhow!("Unknown goal status: {}", record.status))?,
+            priority: Priority::from_i32(record.priority),
+            created_by: record.created_by,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+            parent_id: record.parent_id.as_deref().map(Uuid::parse_str).transpose()?,
+            metadata: serde_json::from_str(&record.metadata)?,
+        })
     }
+}
 
-    /// Insert goal
-    pub async fn insert_goal(&self, _goal: &GoalRecord) -> Result<(), String> {
-        // TODO: INSERT INTO goals
-        Ok(())
-    }
+impl EpisodeRecord {
+    /// Build record from an episode
+    pub fn from_episode(episode: &Episode) -> Result<Self> {
+        // Everything except the columns stored separately goes into meta
+        let meta = serde_json::json!({
+            "retrieved_grains": episode.retrieved_grains,
+            "signatures": episode.signatures,
+            "metadata": episode.metadata,
+        });
 
-    /// Get goal by ID
-    pub async fn get_goal(&self, _id: &str) -> Result<Option<GoalRecord>, String> {
-        // TODO: SELECT FROM goals WHERE id = ?
-        Ok(None)
+        Ok(Self {
+            id: episode.id.to_string(),
+            goal_id: episode.goal_id.to_string(),
+            step: episode.step as i32,
+            query: episode.query.clone(),
+            synthesis: episode.synthesis.clone(),
+            confidence: episode.confidence,
+            vec: match &episode.query_vec {
+                Some(vec) => bincode::serialize(vec)?,
+                None => Vec::new(),
+            },
+            meta: meta.to_string(),
+            timestamp: episode.timestamp,
+        })
     }
 
-    /// Insert plan
-    pub async fn insert_plan(&self, _plan: &PlanRecord) -> Result<(), String> {
-        // TODO: INSERT INTO plans
-        Ok(())
-    }
+    /// Convert record back into an episode
+    pub fn into_episode(self) -> Result<Episode> {
+        let mut meta: serde_json::Value = serde_json::from_str(&self.meta)?;
 
-    /// Insert episode
-    pub async fn insert_episode(&self, _episode: &EpisodeRecord) -> Result<(), String> {
-        // TODO: INSERT INTO episodes
-        Ok(())
+        Ok(Episode {
+            id: Uuid::parse_str(&self.id)?,
+            goal_id: Uuid::parse_str(&self.goal_id)?,
+            step: self.step as u32,
+            query: self.query,
+            query_vec: if self.vec.is_empty() {
+                None
+            } else {
+                Some(bincode::deserialize(&self.vec)?)
+            },
+            retrieved_grains: serde_json::from_value(meta["retrieved_grains"].take())
+                .unwrap_or_default(),
+            synthesis: self.synthesis,
+            confidence: self.confidence,
+            signatures: serde_json::from_value(meta["signatures"].take()).unwrap_or_default(),
+            timestamp: self.timestamp,
+            metadata: match meta["metadata"].take() {
+                serde_json::Value::Null => serde_json::json!({}),
+                value => value,
+            },
+        })
     }
+}
 
-    /// Get episodes for goal
-    pub async fn get_episodes(&self, _goal_id: &str) -> Result<Vec<EpisodeRecord>, String> {
-        // TODO: SELECT FROM episodes WHERE goal_id = ?
-        Ok(Vec::new())
+impl ActionRecord {
+    /// Build record from an action performed in an episode
+    pub fn from_action(episode: &Episode, action: &EpisodeAction) -> Self {
+        Self {
+            id: action.id.to_string(),
+            episode_id: episode.id.to_string(),
+            goal_id: episode.goal_id.to_string(),
+            tool_name: action.tool_name.clone(),
+            input: action.input.to_string(),
+            output: action.output.as_ref().map(|o| o.to_string()),
+            error: action.error.clone(),
+            timestamp: action.timestamp,
+            execution_time_ms: action.execution_time_ms as i64,
+            success: action.success,
+        }
     }
 
-    /// Update stats
-    pub async fn update_stat(&self, _key: &str, _value: &str) -> Result<(), String> {
-        // TODO: INSERT OR REPLACE INTO reason_stats
-        Ok(())
+    /// Convert record back into an action
+    pub fn into_action(self) -> Result<EpisodeAction> {
+        Ok(EpisodeAction {
+            id: Uuid::parse_str(&self.id)?,
+            tool_name: self.tool_name,
+            input: serde_json::from_str(&self.input)?,
+            output: self.output.map(|o| serde_json::from_str(&o)).transpose()?,
+            error: self.error,
+            timestamp: self.timestamp,
+            execution_time_ms: self.execution_time_ms as u64,
+            success: self.success,
+        })
     }
 }
 
@@ -165,11 +266,6 @@ impl ReasoningDb {
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_db_init() {
-        let _db = ReasoningDb::init().await.unwrap();
-    }
-
     #[test]
     fn test_goal_record() {
         let goal = GoalRecord {
@@ -183,7 +279,27 @@ mod tests {
             parent_id: None,
             metadata: "{}".to_string(),
         };
-        
+
         assert_eq!(goal.text, "Test goal");
+
+        let converted = Goal::try_from(goal).unwrap();
+        assert_eq!(converted.status, GoalStatus::Pending);
+        assert_eq!(converted.priority, Priority::Normal);
+    }
+
+    #[test]
+    fn test_episode_record_round_trip() {
+        let mut episode = Episode::new(Uuid::new_v4(), 2, "What is PoE?");
+        episode.query_vec = Some(vec![0.1, 0.2]);
+        episode.add_grain(synapsenet_core::RetrievedGrain::new("g1", 0.9, "local"));
+        episode.set_synthesis("Proof of Emergence", 0.7);
+
+        let record = EpisodeRecord::from_episode(&episode).unwrap();
+        let restored = record.into_episode().unwrap();
+
+        assert_eq!(restored.id, episode.id);
+        assert_eq!(restored.query_vec, Some(vec![0.1, 0.2]));
+        assert_eq!(restored.retrieved_grains.len(), 1);
+        assert_eq!(restored.synthesis, "Proof of Emergence");
     }
 }
@@ -1,9 +1,33 @@
+use crate::ledger::{
+    self, BalanceSnapshot, LedgerAudit, LedgerEntry, LedgerEntryKind, LedgerQuery,
+    NewLedgerEntry, PostOutcome, Posting, TransferConflict, TransferOutcome, TransferRecord,
+};
+use crate::curation::{
+    CuratorAuditEntry, CuratorTask, CuratorTaskStatus, DecisionOutcome, NewCuratorTask,
+};
+use crate::identity::SuccessionOutcome;
+use crate::moderation::{
+    FlagOutcome, GrainModeration, ModerationStatus, MAX_FLAG_CLOCK_SKEW_MS, MAX_ORPHAN_FLAGS,
+    ORPHAN_FLAG_TTL_MS,
+};
+use crate::poe::PoeScoreRecord;
+use crate::private::{GrantOutcome, PrivateGrainOutcome};
+use crate::provenance::GrainProvenance;
+use crate::schema_v6::{ActionRecord, EpisodeRecord, GoalRecord};
+use crate::web_cache::WebCacheEntry;
 use anyhow::Result;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use synapsenet_core::poe::Credit;
-use synapsenet_core::{Grain, Link};
+use std::collections::{HashMap, HashSet};
+use synapsenet_core::{
+    CuratorAction, CuratorDecision, Episode, EpisodeAction, EpisodeV2, FlagAction, Goal,
+    GoalStatus, Grain, GrainFlag, GroupKey, GroupKeyGrant, KeySuccession, Link, NgtTransfer,
+    PrivateGrain, RecipientSecret,
+};
+use tracing::warn;
+use uuid::Uuid;
 
-/// SQLite storage for grains, links, credits, and peers
+/// SQLite storage for grains, links, credits, ledger, and peers
 pub struct Store {
     conn: Connection,
 }
@@ -83,43 +107,29 @@ impl Store {
     }
 
     /// Get grain by ID
+    ///
+    /// Decrypted private grains are not returned; see `get_private_grains`.
     pub fn get_grain(&self, id: &[u8; 32]) -> Result<Option<Grain>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT id, vec, meta, sig FROM grains WHERE id = ?1")?;
-
-        let mut rows = stmt.query(params![&id[..]])?;
-
-        if let Some(row) = rows.next()? {
-            let id_bytes: Vec<u8> = row.get(0)?;
-            let vec_bytes: Vec<u8> = row.get(1)?;
-            let meta_bytes: Vec<u8> = row.get(2)?;
-            let sig: Vec<u8> = row.get(3)?;
-
-            let mut id_arr = [0u8; 32];
-            id_arr.copy_from_slice(&id_bytes);
-
-            let vec: Vec<f32> = bincode::deserialize(&vec_bytes)?;
-            let meta = bincode::deserialize(&meta_bytes)?;
-
-            Ok(Some(Grain {
-                id: id_arr,
-                vec,
-                meta,
-                sig,
-            }))
-        } else {
-            Ok(None)
-        }
+        let sql = format!("SELECT id, vec, meta, sig FROM grains WHERE id = ?1 AND {}", PUBLIC_GRAIN);
+        Ok(self.query_grains(&sql, params![&id[..]])?.pop())
     }
 
     /// Get all grains
+    ///
+    /// Decrypted private grains are left out, so nothing that lists grains
+    /// can leak them outside their group.
     pub fn get_all_grains(&self) -> Result<Vec<Grain>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT id, vec, meta, sig FROM grains ORDER BY created_at DESC")?;
+        let sql = format!(
+            "SELECT id, vec, meta, sig FROM grains WHERE {} ORDER BY created_at DESC",
+            PUBLIC_GRAIN
+        );
+        self.query_grains(&sql, [])
+    }
 
-        let rows = stmt.query_map([], |row| {
+    fn query_grains(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<Grain>> {
+        let mut stmt = self.conn.prepare(sql)?;
+
+        let rows = stmt.query_map(params, |row| {
             let id_bytes: Vec<u8> = row.get(0)?;
             let vec_bytes: Vec<u8> = row.get(1)?;
             let meta_bytes: Vec<u8> = row.get(2)?;
@@ -164,36 +174,154 @@ impl Store {
         Ok(())
     }
 
-    /// Insert credit
-    pub fn insert_credit(&self, credit: &Credit) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO credits (grain_id, node_pk, ngt, reason, ts_unix_ms) VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![
-                &credit.grain_id[..],
-                &credit.node_pk[..],
-                credit.ngt,
-                &credit.reason,
-                credit.ts_unix_ms
-            ],
+    /// Get all links
+    pub fn get_all_links(&self) -> Result<Vec<Link>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT from_id, to_id, weight, rationale, sig FROM links ORDER BY created_at",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let from: Vec<u8> = row.get(0)?;
+            let to: Vec<u8> = row.get(1)?;
+            let weight: f64 = row.get(2)?;
+            let rationale: Option<String> = row.get(3)?;
+            let sig: Vec<u8> = row.get(4)?;
+
+            Ok((from, to, weight, rationale, sig))
+        })?;
+
+        let mut links = Vec::new();
+        for row in rows {
+            let (from, to, weight, rationale, sig) = row?;
+            links.push(Link {
+                from: from
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Invalid link source id"))?,
+                to: to
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Invalid link target id"))?,
+                weight: weight as f32,
+                rationale,
+                sig,
+            });
+        }
+
+        Ok(links)
+    }
+
+    /// Get all credits
+    pub fn get_all_credits(&self) -> Result<Vec<Credit>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT grain_id, node_pk, ngt, reason, ts_unix_ms FROM credits ORDER BY id",
         )?;
+
+        let rows = stmt.query_map([], |row| {
+            let grain_id: Vec<u8> = row.get(0)?;
+            let node_pk: Vec<u8> = row.get(1)?;
+            let ngt: f64 = row.get(2)?;
+            let reason: String = row.get(3)?;
+            let ts_unix_ms: i64 = row.get(4)?;
+
+            Ok((grain_id, node_pk, ngt, reason, ts_unix_ms))
+        })?;
+
+        let mut credits = Vec::new();
+        for row in rows {
+            let (grain_id, node_pk, ngt, reason, ts_unix_ms) = row?;
+            credits.push(Credit {
+                grain_id: grain_id
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Invalid credit grain id"))?,
+                node_pk: node_pk
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Invalid credit node key"))?,
+                ngt,
+                reason,
+                ts_unix_ms,
+            });
+        }
+
+        Ok(credits)
+    }
+
+    /// Insert credit and post the matching reward to the NGT ledger
+    pub fn insert_credit(&self, credit: &Credit) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        insert_credit(&tx, credit)?;
+        tx.commit()?;
         Ok(())
     }
 
-    /// Get total NGT for a node
+    /// Insert the grains, links and credits of an export in one transaction
+    ///
+    /// Grains and links already stored are kept, and credits already recorded
+    /// are not posted again, so importing an export twice changes nothing.
+    /// Returns the number of grains that were new.
+    pub fn import_records(
+        &self,
+        grains: &[Grain],
+        links: &[Link],
+        credits: &[Credit],
+    ) -> Result<usize> {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis() as i64;
+        let tx = self.conn.unchecked_transaction()?;
+
+        let mut inserted = 0;
+        for grain in grains {
+            inserted += tx.execute(
+                "INSERT OR IGNORE INTO grains (id, vec, meta, sig, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    &grain.id[..],
+                    bincode::serialize(&grain.vec)?,
+                    bincode::serialize(&grain.meta)?,
+                    &grain.sig,
+                    ts
+                ],
+            )?;
+        }
+
+        for link in links {
+            tx.execute(
+                "INSERT OR IGNORE INTO links (from_id, to_id, weight, rationale, sig, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![&link.from[..], &link.to[..], link.weight, &link.rationale, &link.sig, ts],
+            )?;
+        }
+
+        for credit in credits {
+            let known: bool = tx.query_row(
+                "SELECT COUNT(*) > 0 FROM credits
+                 WHERE grain_id = ?1 AND node_pk = ?2 AND reason = ?3 AND ts_unix_ms = ?4",
+                params![
+                    &credit.grain_id[..],
+                    &credit.node_pk[..],
+                    &credit.reason,
+                    credit.ts_unix_ms
+                ],
+                |row| row.get(0),
+            )?;
+            if !known {
+                insert_credit(&tx, credit)?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// Get NGT balance of a node from the ledger
     pub fn get_node_ngt(&self, node_pk: &[u8; 32]) -> Result<f64> {
-        let ngt: f64 = self.conn.query_row(
-            "SELECT COALESCE(SUM(ngt), 0.0) FROM credits WHERE node_pk = ?1",
-            params![&node_pk[..]],
-            |row| row.get(0),
-        )?;
-        Ok(ngt)
+        self.get_account_balance(&ledger::node_account(node_pk))
     }
 
-    /// Count grains
+    /// Count grains, decrypted private grains excluded
     pub fn count_grains(&self) -> Result<usize> {
-        let count: usize = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM grains", [], |row| row.get(0))?;
+        let count: usize = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM grains WHERE {}", PUBLIC_GRAIN),
+            [],
+            |row| row.get(0),
+        )?;
         Ok(count)
     }
 
@@ -258,6 +386,115 @@ impl Store {
         Ok(deleted)
     }
 
+    /// Get total access count and number of distinct peers for a grain
+    pub fn get_grain_access_stats(&self, grain_id: &[u8; 32]) -> Result<(usize, usize)> {
+        let stats = self.conn.query_row(
+            "SELECT COUNT(*), COUNT(DISTINCT peer_id) FROM grain_access WHERE grain_id = ?1",
+            params![&grain_id[..]],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(stats)
+    }
+
+    // ===== PoE Scores =====
+
+    /// Save a grain's latest PoE score and credit its author
+    ///
+    /// Only the part of `ngt_reward` not yet credited is paid out, so a
+    /// rescore never pays twice. `credited` and `epoch` of the record are
+    /// managed here and ignored. Returns the NGT credited by this call.
+    pub fn save_poe_score(&self, record: &PoeScoreRecord) -> Result<f64> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        let previous: Option<(f64, i64)> = tx
+            .query_row(
+                "SELECT credited, epoch FROM poe_scores WHERE grain_id = ?1",
+                params![&record.grain_id[..]],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let (mut credited, epoch) = match previous {
+            Some((credited, epoch)) => (credited, epoch as u64 + 1),
+            None => (0.0, 0),
+        };
+
+        let mut paid = 0.0;
+        let delta = record.ngt_reward - credited;
+        if delta > 1e-9 {
+            if let Ok(author) = <[u8; 32]>::try_from(record.author_pk.as_slice()) {
+                let entry = NewLedgerEntry::reward(&ledger::node_account(&author), delta, "PoE reward")
+                    .with_idempotency_key(ledger::reward_key(&record.grain_id, epoch));
+                if post_entry(&tx, &entry, unix_now_millis()?)?.is_posted() {
+                    credited += delta;
+                    paid = delta;
+                }
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO poe_scores
+                (grain_id, author_pk, novelty, coherence, reuse, total, ngt_reward, credited, epoch, calculated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(grain_id) DO UPDATE SET
+                novelty = excluded.novelty, coherence = excluded.coherence, reuse = excluded.reuse,
+                total = excluded.total, ngt_reward = excluded.ngt_reward, credited = excluded.credited,
+                epoch = excluded.epoch, calculated_at = excluded.calculated_at",
+            params![
+                &record.grain_id[..],
+                record.author_pk,
+                record.novelty,
+                record.coherence,
+                record.reuse,
+                record.total,
+                record.ngt_reward,
+                credited,
+                epoch as i64,
+                record.calculated_at,
+            ],
+        )?;
+        tx.commit()?;
+
+        Ok(paid)
+    }
+
+    /// Get the latest PoE score of a grain
+    pub fn get_poe_score(&self, grain_id: &[u8; 32]) -> Result<Option<PoeScoreRecord>> {
+        let record = self
+            .conn
+            .query_row(
+                "SELECT grain_id, author_pk, novelty, coherence, reuse, total, ngt_reward, credited, epoch, calculated_at
+                 FROM poe_scores WHERE grain_id = ?1",
+                params![&grain_id[..]],
+                poe_score_from_row,
+            )
+            .optional()?;
+        Ok(record)
+    }
+
+    /// List PoE scores, best first
+    pub fn list_poe_scores(&self, limit: usize, min_total: f32) -> Result<Vec<PoeScoreRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT grain_id, author_pk, novelty, coherence, reuse, total, ngt_reward, credited, epoch, calculated_at
+             FROM poe_scores WHERE total >= ?1 ORDER BY total DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![min_total, limit as i64], poe_score_from_row)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Grains accessed since they were last scored
+    pub fn get_grains_to_rescore(&self, limit: usize) -> Result<Vec<[u8; 32]>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.grain_id FROM poe_scores s
+             WHERE EXISTS (
+                SELECT 1 FROM grain_access a
+                WHERE a.grain_id = s.grain_id AND a.timestamp > s.calculated_at
+             )
+             ORDER BY s.calculated_at LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], |row| row_id(row, 0))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
     // ===== Embedding Models Metadata =====
 
     /// Register embedding model
@@ -399,48 +636,2668 @@ impl Store {
 
         Ok((topic_count, peer_count))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use ed25519_dalek::SigningKey;
-    use rand::{rngs::OsRng, RngCore};
-    use synapsenet_core::GrainMeta;
+    // ===== Reasoning Goals =====
 
-    fn generate_signing_key() -> SigningKey {
-        let mut secret_bytes = [0u8; 32];
-        OsRng.fill_bytes(&mut secret_bytes);
-        SigningKey::from_bytes(&secret_bytes)
+    /// Insert or update goal
+    pub fn upsert_goal(&self, goal: &Goal) -> Result<()> {
+        let record = GoalRecord::from(goal);
+
+        self.conn.execute(
+            "INSERT INTO goals (id, text, status, priority, created_by, created_at, updated_at, parent_id, metadata)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(id) DO UPDATE SET
+             text = excluded.text, status = excluded.status, priority = excluded.priority,
+             updated_at = excluded.updated_at, metadata = excluded.metadata",
+            params![
+                record.id,
+                record.text,
+                record.status,
+                record.priority,
+                record.created_by,
+                record.created_at,
+                record.updated_at,
+                record.parent_id,
+                record.metadata
+            ],
+        )?;
+        Ok(())
     }
 
-    #[test]
-    fn test_store_grain() {
-        let store = Store::new(":memory:").unwrap();
+    /// Get goal by ID
+    pub fn get_goal(&self, id: &Uuid) -> Result<Option<Goal>> {
+        let record = self
+            .conn
+            .query_row(
+                "SELECT id, text, status, priority, created_by, created_at, updated_at, parent_id, metadata
+                 FROM goals WHERE id = ?1",
+                params![id.to_string()],
+                goal_record_from_row,
+            )
+            .optional()?;
 
-        let signing_key = generate_signing_key();
-        let author_pk = signing_key.verifying_key().to_bytes();
+        record.map(Goal::try_from).transpose()
+    }
 
-        let meta = GrainMeta {
-            author_pk: author_pk.to_vec(),
-            crypto_backend: synapsenet_core::CryptoBackend::Classical,
-            ts_unix_ms: 1234567890,
-            tags: vec!["test".to_string()],
-            mime: "text/plain".to_string(),
-            lang: "en".to_string(),
-            title: Some("Test".to_string()),
-            summary: None,
-            embedding_model: Some("test-model".to_string()),
-            embedding_dimensions: Some(384),
-        };
+    /// List goals, newest first, optionally filtered by status
+    pub fn list_goals(&self, status: Option<GoalStatus>, limit: usize) -> Result<Vec<Goal>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, text, status, priority, created_by, created_at, updated_at, parent_id, metadata
+             FROM goals WHERE ?1 IS NULL OR status = ?1
+             ORDER BY created_at DESC LIMIT ?2",
+        )?;
 
-        let vec = vec![0.1, 0.2, 0.3];
-        let grain = Grain::new(vec, meta, &signing_key).unwrap();
+        let rows = stmt.query_map(
+            params![status.map(|s| s.as_str()), limit],
+            goal_record_from_row,
+        )?;
 
-        store.insert_grain(&grain).unwrap();
+        let mut goals = Vec::new();
+        for row in rows {
+            goals.push(Goal::try_from(row?)?);
+        }
+        Ok(goals)
+    }
 
-        let retrieved = store.get_grain(&grain.id).unwrap().unwrap();
-        assert_eq!(retrieved.id, grain.id);
-        assert_eq!(retrieved.vec, grain.vec);
+    /// Get goals that have not reached a terminal state, in scheduling order
+    pub fn get_unfinished_goals(&self) -> Result<Vec<Goal>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, text, status, priority, created_by, created_at, updated_at, parent_id, metadata
+             FROM goals WHERE status NOT IN ('completed', 'failed', 'cancelled')
+             ORDER BY priority DESC, created_at ASC",
+        )?;
+
+        let rows = stmt.query_map([], goal_record_from_row)?;
+
+        let mut goals = Vec::new();
+        for row in rows {
+            goals.push(Goal::try_from(row?)?);
+        }
+        Ok(goals)
+    }
+
+    /// Update goal status, returns false if the goal does not exist
+    pub fn update_goal_status(&self, id: &Uuid, status: GoalStatus) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE goals SET status = ?1, updated_at = ?2 WHERE id = ?3",
+            params![status.as_str(), unix_now_secs()?, id.to_string()],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Save (or replace) the task graph for a goal
+    pub fn save_plan(&self, goal_id: &Uuid, dag_json: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO plans (id, goal_id, dag_json, created_at) VALUES (?1, ?1, ?2, ?3)
+             ON CONFLICT(id) DO UPDATE SET dag_json = excluded.dag_json",
+            params![goal_id.to_string(), dag_json, unix_now_secs()?],
+        )?;
+        Ok(())
+    }
+
+    /// Get the task graph JSON for a goal
+    pub fn get_plan(&self, goal_id: &Uuid) -> Result<Option<String>> {
+        let plan = self
+            .conn
+            .query_row(
+                "SELECT dag_json FROM plans WHERE goal_id = ?1",
+                params![goal_id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(plan)
+    }
+
+    /// Insert or replace episode
+    pub fn insert_episode(&self, episode: &Episode) -> Result<()> {
+        let record = EpisodeRecord::from_episode(episode)?;
+
+        self.conn.execute(
+            "INSERT OR REPLACE INTO episodes (id, goal_id, step, query, synthesis, confidence, vec, meta, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                record.id,
+                record.goal_id,
+                record.step,
+                record.query,
+                record.synthesis,
+                record.confidence,
+                record.vec,
+                record.meta,
+                record.timestamp
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Insert episode together with its action log
+    pub fn insert_episode_v2(&self, episode: &EpisodeV2) -> Result<()> {
+        self.insert_episode(&episode.base)?;
+
+        for action in &episode.actions {
+            let record = ActionRecord::from_action(&episode.base, action);
+            self.conn.execute(
+                "INSERT OR REPLACE INTO episode_actions
+                 (id, episode_id, goal_id, tool_name, input, output, error, timestamp, execution_time_ms, success)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    record.id,
+                    record.episode_id,
+                    record.goal_id,
+                    record.tool_name,
+                    record.input,
+                    record.output,
+                    record.error,
+                    record.timestamp,
+                    record.execution_time_ms,
+                    record.success
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Get episodes for a goal in step order
+    pub fn get_episodes(&self, goal_id: &Uuid) -> Result<Vec<Episode>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, goal_id, step, query, synthesis, confidence, vec, meta, timestamp
+             FROM episodes WHERE goal_id = ?1 ORDER BY step ASC, timestamp ASC",
+        )?;
+
+        let rows = stmt.query_map(params![goal_id.to_string()], |row| {
+            Ok(EpisodeRecord {
+                id: row.get(0)?,
+                goal_id: row.get(1)?,
+                step: row.get(2)?,
+                query: row.get(3)?,
+                synthesis: row.get(4)?,
+                confidence: row.get(5)?,
+                vec: row.get::<_, Option<Vec<u8>>>(6)?.unwrap_or_default(),
+                meta: row.get(7)?,
+                timestamp: row.get(8)?,
+            })
+        })?;
+
+        let mut episodes = Vec::new();
+        for row in rows {
+            episodes.push(row?.into_episode()?);
+        }
+        Ok(episodes)
+    }
+
+    /// Get actions recorded for an episode
+    pub fn get_episode_actions(&self, episode_id: &Uuid) -> Result<Vec<EpisodeAction>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, episode_id, goal_id, tool_name, input, output, error, timestamp, execution_time_ms, success
+             FROM episode_actions WHERE episode_id = ?1 ORDER BY timestamp ASC",
+        )?;
+
+        let rows = stmt.query_map(params![episode_id.to_string()], |row| {
+            Ok(ActionRecord {
+                id: row.get(0)?,
+                episode_id: row.get(1)?,
+                goal_id: row.get(2)?,
+                tool_name: row.get(3)?,
+                input: row.get(4)?,
+                output: row.get(5)?,
+                error: row.get(6)?,
+                timestamp: row.get(7)?,
+                execution_time_ms: row.get(8)?,
+                success: row.get(9)?,
+            })
+        })?;
+
+        let mut actions = Vec::new();
+        for row in rows {
+            actions.push(row?.into_action()?);
+        }
+        Ok(actions)
+    }
+
+    /// Get episodes for a goal with their action logs
+    pub fn get_episodes_v2(&self, goal_id: &Uuid) -> Result<Vec<EpisodeV2>> {
+        let mut result = Vec::new();
+        for episode in self.get_episodes(goal_id)? {
+            let actions = self.get_episode_actions(&episode.id)?;
+            let mut episode = EpisodeV2::from_episode(episode);
+            for action in actions {
+                episode.add_action(action);
+            }
+            result.push(episode);
+        }
+        Ok(result)
+    }
+
+    /// Delete plan, episodes and actions of a goal (used before a re-run)
+    pub fn clear_goal_progress(&self, goal_id: &Uuid) -> Result<()> {
+        let id = goal_id.to_string();
+        self.conn
+            .execute("DELETE FROM episode_actions WHERE goal_id = ?1", params![id])?;
+        self.conn
+            .execute("DELETE FROM episodes WHERE goal_id = ?1", params![id])?;
+        self.conn
+            .execute("DELETE FROM plans WHERE goal_id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ===== NGT Ledger =====
+
+    /// Post a balanced entry; entries reusing an idempotency key are not applied twice
+    pub fn post_ledger_entry(&self, entry: &NewLedgerEntry) -> Result<PostOutcome> {
+        let tx = self.conn.unchecked_transaction()?;
+        let outcome = post_entry(&tx, entry, unix_now_millis()?)?;
+        tx.commit()?;
+        Ok(outcome)
+    }
+
+    /// Get the current balance of a ledger account
+    pub fn get_account_balance(&self, account: &str) -> Result<f64> {
+        account_balance(&self.conn, account)
+    }
+
+    /// Get balances of all accounts with the given prefix (e.g. "node:")
+    pub fn get_account_balances(&self, prefix: &str) -> Result<Vec<(String, f64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT account, SUM(amount) FROM ledger_postings
+             WHERE substr(account, 1, length(?1)) = ?1
+             GROUP BY account ORDER BY SUM(amount) DESC",
+        )?;
+
+        let rows = stmt.query_map(params![prefix], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Get a ledger entry by ID
+    pub fn get_ledger_entry(&self, id: i64) -> Result<Option<LedgerEntry>> {
+        let entry = self
+            .conn
+            .query_row(
+                "SELECT id, kind, idempotency_key, memo, ts_unix_ms FROM ledger_entries WHERE id = ?1",
+                params![id],
+                ledger_entry_from_row,
+            )
+            .optional()?;
+
+        match entry {
+            Some(mut entry) => {
+                entry.postings = self.get_postings(entry.id)?;
+                Ok(Some(entry))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Query ledger entries for auditing, newest first
+    pub fn query_ledger(&self, query: &LedgerQuery) -> Result<Vec<LedgerEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, kind, idempotency_key, memo, ts_unix_ms FROM ledger_entries e
+             WHERE (?1 IS NULL OR EXISTS (
+                 SELECT 1 FROM ledger_postings p WHERE p.entry_id = e.id AND p.account = ?1))
+             AND (?2 IS NULL OR kind = ?2)
+             AND (?3 IS NULL OR ts_unix_ms >= ?3)
+             ORDER BY id DESC LIMIT ?4",
+        )?;
+
+        let rows = stmt.query_map(
+            params![
+                query.account,
+                query.kind.map(|k| k.as_str()),
+                query.since_ms,
+                query.limit
+            ],
+            ledger_entry_from_row,
+        )?;
+
+        let mut entries = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+        for entry in &mut entries {
+            entry.postings = self.get_postings(entry.id)?;
+        }
+        Ok(entries)
+    }
+
+    /// Snapshot every account balance as of the latest entry, returns that entry ID
+    pub fn create_balance_snapshot(&self) -> Result<i64> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        let entry_id: i64 = tx.query_row(
+            "SELECT COALESCE(MAX(id), 0) FROM ledger_entries",
+            [],
+            |row| row.get(0),
+        )?;
+
+        tx.execute(
+            "INSERT INTO ledger_snapshots (account, balance, entry_id, ts_unix_ms)
+             SELECT account, SUM(amount), ?1, ?2 FROM ledger_postings
+             WHERE entry_id <= ?1 GROUP BY account",
+            params![entry_id, unix_now_millis()?],
+        )?;
+
+        tx.commit()?;
+        Ok(entry_id)
+    }
+
+    /// Get snapshots of an account, newest first
+    pub fn get_balance_snapshots(&self, account: &str, limit: usize) -> Result<Vec<BalanceSnapshot>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT account, balance, entry_id, ts_unix_ms FROM ledger_snapshots
+             WHERE account = ?1 ORDER BY entry_id DESC LIMIT ?2",
+        )?;
+
+        let rows = stmt.query_map(params![account, limit], |row| {
+            Ok(BalanceSnapshot {
+                account: row.get(0)?,
+                balance: row.get(1)?,
+                entry_id: row.get(2)?,
+                ts_unix_ms: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Check that every entry balances and snapshots agree with the postings
+    pub fn audit_ledger(&self) -> Result<LedgerAudit> {
+        let entries_checked: usize =
+            self.conn
+                .query_row("SELECT COUNT(*) FROM ledger_entries", [], |row| row.get(0))?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT e.id FROM ledger_entries e LEFT JOIN ledger_postings p ON p.entry_id = e.id
+             GROUP BY e.id HAVING COUNT(p.id) < 2 OR ABS(COALESCE(SUM(p.amount), 0)) > 1e-9
+             ORDER BY e.id",
+        )?;
+        let unbalanced_entries = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<i64>>>()?;
+
+        let net_total: f64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(amount), 0.0) FROM ledger_postings",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT s.account, s.balance,
+                    (SELECT COALESCE(SUM(amount), 0.0) FROM ledger_postings p
+                     WHERE p.account = s.account AND p.entry_id <= s.entry_id)
+             FROM ledger_snapshots s
+             WHERE s.id = (SELECT MAX(id) FROM ledger_snapshots WHERE account = s.account)",
+        )?;
+        let snapshot_mismatches = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?, row.get::<_, f64>(2)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|(_, snapshot, actual)| !ledger::amounts_match(*snapshot, *actual))
+            .map(|(account, _, _)| account)
+            .collect();
+
+        Ok(LedgerAudit {
+            entries_checked,
+            unbalanced_entries,
+            snapshot_mismatches,
+            net_total,
+        })
+    }
+
+    // ===== NGT Transfers =====
+
+    /// Apply a signed transfer (signature must already be verified)
+    ///
+    /// `pending_broadcast` marks transfers created locally that still have to
+    /// be published to peers. Any nonce the sender has not used is accepted,
+    /// since gossip may deliver transfers out of order; reusing one is a
+    /// conflict.
+    pub fn apply_transfer(
+        &self,
+        transfer: &NgtTransfer,
+        pending_broadcast: bool,
+    ) -> Result<TransferOutcome> {
+        let id = transfer.id();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let already_applied: bool = tx.query_row(
+            "SELECT COUNT(*) > 0 FROM ngt_transfers WHERE id = ?1",
+            params![&id[..]],
+            |row| row.get(0),
+        )?;
+        if already_applied {
+            return Ok(TransferOutcome::Duplicate);
+        }
+
+        // Same sender and nonce but different content: double-spend
+        let existing: Option<Vec<u8>> = tx
+            .query_row(
+                "SELECT id FROM ngt_transfers WHERE from_pk = ?1 AND nonce = ?2",
+                params![&transfer.from[..], transfer.nonce as i64],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(existing) = existing {
+            let existing_id = blob_to_id(&existing)?;
+            tx.execute(
+                "INSERT OR IGNORE INTO ngt_transfer_conflicts
+                 (from_pk, nonce, first_id, conflicting_id, conflicting, detected_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    &transfer.from[..],
+                    transfer.nonce as i64,
+                    &existing_id[..],
+                    &id[..],
+                    bincode::serialize(transfer)?,
+                    unix_now_millis()?
+                ],
+            )?;
+            tx.commit()?;
+            return Ok(TransferOutcome::Conflict { existing_id });
+        }
+
+        let from = ledger::node_account(&transfer.from);
+        let balance = account_balance(&tx, &from)?;
+        if balance < transfer.amount && !ledger::amounts_match(balance, transfer.amount) {
+            return Ok(TransferOutcome::Rejected(format!(
+                "Insufficient balance: {} < {}",
+                balance, transfer.amount
+            )));
+        }
+
+        let entry = NewLedgerEntry::transfer(
+            &from,
+            &ledger::node_account(&transfer.to),
+            transfer.amount,
+            transfer.memo.as_deref().unwrap_or("transfer"),
+        )
+        .with_idempotency_key(ledger::transfer_key(&id));
+        let entry_id = post_entry(&tx, &entry, transfer.ts_unix_ms)?.entry_id();
+
+        tx.execute(
+            "INSERT INTO ngt_transfers
+             (id, from_pk, to_pk, amount, nonce, ts_unix_ms, memo, sig, entry_id, received_at, pending_broadcast)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                &id[..],
+                &transfer.from[..],
+                &transfer.to[..],
+                transfer.amount,
+                transfer.nonce as i64,
+                transfer.ts_unix_ms,
+                transfer.memo,
+                transfer.sig,
+                entry_id,
+                unix_now_millis()?,
+                pending_broadcast
+            ],
+        )?;
+
+        tx.commit()?;
+        Ok(TransferOutcome::Applied { entry_id })
+    }
+
+    /// Next unused nonce for a sender
+    pub fn next_transfer_nonce(&self, from: &[u8; 32]) -> Result<u64> {
+        let last: Option<i64> = self.conn.query_row(
+            "SELECT MAX(nonce) FROM ngt_transfers WHERE from_pk = ?1",
+            params![&from[..]],
+            |row| row.get(0),
+        )?;
+        Ok(last.map(|n| n as u64 + 1).unwrap_or(1))
+    }
+
+    /// Get applied transfer by ID
+    pub fn get_transfer(&self, id: &[u8; 32]) -> Result<Option<TransferRecord>> {
+        let record = self
+            .conn
+            .query_row(
+                "SELECT id, from_pk, to_pk, amount, nonce, ts_unix_ms, memo, sig, entry_id, received_at
+                 FROM ngt_transfers WHERE id = ?1",
+                params![&id[..]],
+                transfer_record_from_row,
+            )
+            .optional()?;
+        Ok(record)
+    }
+
+    /// Transfers sent or received by a node, newest first
+    pub fn list_transfers(&self, node_pk: &[u8; 32], limit: usize) -> Result<Vec<TransferRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, from_pk, to_pk, amount, nonce, ts_unix_ms, memo, sig, entry_id, received_at
+             FROM ngt_transfers WHERE from_pk = ?1 OR to_pk = ?1
+             ORDER BY received_at DESC LIMIT ?2",
+        )?;
+
+        let rows = stmt.query_map(params![&node_pk[..], limit], transfer_record_from_row)?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+        Ok(records)
+    }
+
+    /// Detected double-spend attempts, newest first
+    pub fn get_transfer_conflicts(&self, limit: usize) -> Result<Vec<TransferConflict>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT from_pk, nonce, first_id, conflicting, detected_at
+             FROM ngt_transfer_conflicts ORDER BY id DESC LIMIT ?1",
+        )?;
+
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok((
+                row.get::<_, Vec<u8>>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Vec<u8>>(2)?,
+                row.get::<_, Vec<u8>>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })?;
+
+        let mut conflicts = Vec::new();
+        for row in rows {
+            let (from, nonce, first_id, conflicting, detected_at) = row?;
+            conflicts.push(TransferConflict {
+                from: blob_to_id(&from)?,
+                nonce: nonce as u64,
+                first_id: blob_to_id(&first_id)?,
+                conflicting: bincode::deserialize(&conflicting)?,
+                detected_at,
+            });
+        }
+        Ok(conflicts)
+    }
+
+    /// Locally created transfers that still need to be published
+    ///
+    /// They stay pending until `mark_transfer_broadcast`.
+    pub fn pending_transfers(&self) -> Result<Vec<NgtTransfer>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, from_pk, to_pk, amount, nonce, ts_unix_ms, memo, sig, entry_id, received_at
+             FROM ngt_transfers WHERE pending_broadcast = 1 ORDER BY nonce ASC",
+        )?;
+        let rows = stmt.query_map([], transfer_record_from_row)?;
+
+        let mut transfers = Vec::new();
+        for row in rows {
+            transfers.push(row?.transfer);
+        }
+        Ok(transfers)
+    }
+
+    /// Record that a pending transfer was published
+    pub fn mark_transfer_broadcast(&self, id: &[u8; 32]) -> Result<()> {
+        self.mark_broadcast("ngt_transfers", id)
+    }
+
+    // ===== Key Successions =====
+
+    /// Record a key succession (signatures must already be verified)
+    ///
+    /// `pending_broadcast` marks successions created locally that still have
+    /// to be published to peers.
+    pub fn apply_key_succession(
+        &self,
+        succession: &KeySuccession,
+        pending_broadcast: bool,
+    ) -> Result<SuccessionOutcome> {
+        let id = succession.id();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let already_applied: bool = tx.query_row(
+            "SELECT COUNT(*) > 0 FROM key_successions WHERE id = ?1",
+            params![&id[..]],
+            |row| row.get(0),
+        )?;
+        if already_applied {
+            return Ok(SuccessionOutcome::Duplicate);
+        }
+
+        let old_succeeded: bool = tx.query_row(
+            "SELECT COUNT(*) > 0 FROM key_successions WHERE old_pk = ?1",
+            params![&succession.old_pk[..]],
+            |row| row.get(0),
+        )?;
+        if old_succeeded {
+            return Ok(SuccessionOutcome::Rejected(
+                "Old key was already succeeded by another key".to_string(),
+            ));
+        }
+
+        let new_is_successor: bool = tx.query_row(
+            "SELECT COUNT(*) > 0 FROM key_successions WHERE new_pk = ?1",
+            params![&succession.new_pk[..]],
+            |row| row.get(0),
+        )?;
+        if new_is_successor {
+            return Ok(SuccessionOutcome::Rejected(
+                "New key already belongs to another identity".to_string(),
+            ));
+        }
+
+        let identity = key_identity(&tx, &succession.old_pk)?;
+        if identity == succession.new_pk {
+            return Ok(SuccessionOutcome::Rejected(
+                "Succession would create a key cycle".to_string(),
+            ));
+        }
+
+        // A chain that starts at the new key was received first; attach it
+        tx.execute(
+            "UPDATE key_successions SET identity = ?1 WHERE identity = ?2",
+            params![&identity[..], &succession.new_pk[..]],
+        )?;
+
+        tx.execute(
+            "INSERT INTO key_successions
+             (id, old_pk, new_pk, identity, ts_unix_ms, revoked_from, record, received_at, pending_broadcast)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                &id[..],
+                &succession.old_pk[..],
+                &succession.new_pk[..],
+                &identity[..],
+                succession.ts_unix_ms,
+                succession.revoked_from,
+                bincode::serialize(succession)?,
+                unix_now_millis()?,
+                pending_broadcast
+            ],
+        )?;
+
+        tx.commit()?;
+        Ok(SuccessionOutcome::Applied { identity })
+    }
+
+    /// Identity (first key) a grain author key belongs to
+    ///
+    /// Keys that were never rotated are their own identity.
+    pub fn author_identity(&self, author_pk: &[u8]) -> Result<Vec<u8>> {
+        let identity: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "SELECT identity FROM key_successions WHERE old_pk = ?1 OR new_pk = ?1 LIMIT 1",
+                params![author_pk],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(identity.unwrap_or_else(|| author_pk.to_vec()))
+    }
+
+    /// Key successions of an identity, oldest key first
+    pub fn get_key_successions(&self, identity: &[u8]) -> Result<Vec<KeySuccession>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT record FROM key_successions WHERE identity = ?1")?;
+        let rows = stmt.query_map(params![identity], |row| row.get::<_, Vec<u8>>(0))?;
+
+        let mut by_old = std::collections::HashMap::new();
+        for row in rows {
+            let succession: KeySuccession = bincode::deserialize(&row?)?;
+            by_old.insert(succession.old_pk, succession);
+        }
+
+        // Walk the chain from the identity key
+        let mut chain = Vec::with_capacity(by_old.len());
+        let mut key = blob_to_id(identity).ok();
+        while let Some(succession) = key.and_then(|k| by_old.remove(&k)) {
+            key = Some(succession.new_pk);
+            chain.push(succession);
+        }
+        Ok(chain)
+    }
+
+    /// Time from which a key is revoked, if it was
+    pub fn key_revoked_from(&self, pk: &[u8]) -> Result<Option<i64>> {
+        let revoked_from: Option<Option<i64>> = self
+            .conn
+            .query_row(
+                "SELECT revoked_from FROM key_successions WHERE old_pk = ?1",
+                params![pk],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(revoked_from.flatten())
+    }
+
+    /// Whether content signed by `pk` at `ts_unix_ms` must be rejected
+    pub fn is_key_revoked_at(&self, pk: &[u8], ts_unix_ms: i64) -> Result<bool> {
+        Ok(self
+            .key_revoked_from(pk)?
+            .is_some_and(|from| ts_unix_ms >= from))
+    }
+
+    /// Revoked keys and the time each is revoked from
+    pub fn revoked_keys(&self) -> Result<Vec<([u8; 32], i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT old_pk, revoked_from FROM key_successions WHERE revoked_from IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, Vec<u8>>(0)?, row.get(1)?)))?;
+
+        let mut revoked = Vec::new();
+        for row in rows {
+            let (pk, from) = row?;
+            revoked.push((blob_to_id(&pk)?, from));
+        }
+        Ok(revoked)
+    }
+
+    /// Locally created successions that still need to be published
+    ///
+    /// They stay pending until `mark_succession_broadcast`.
+    pub fn pending_successions(&self) -> Result<Vec<KeySuccession>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT record FROM key_successions WHERE pending_broadcast = 1 ORDER BY ts_unix_ms ASC",
+        )?;
+        let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0))?;
+
+        let mut successions = Vec::new();
+        for row in rows {
+            successions.push(bincode::deserialize(&row?)?);
+        }
+        Ok(successions)
+    }
+
+    /// Record that a pending succession was published
+    pub fn mark_succession_broadcast(&self, id: &[u8; 32]) -> Result<()> {
+        self.mark_broadcast("key_successions", id)
+    }
+
+    // ===== Private Grains =====
+
+    /// Store a group key under a local name, sealed with `secret`
+    ///
+    /// Returns false if the group is already known.
+    pub fn insert_group_key(
+        &self,
+        name: &str,
+        group_key: &GroupKey,
+        secret: &RecipientSecret,
+    ) -> Result<bool> {
+        let known: bool = self.conn.query_row(
+            "SELECT COUNT(*) > 0 FROM private_groups WHERE group_id = ?1",
+            params![&group_key.group_id()[..]],
+            |row| row.get(0),
+        )?;
+        if known {
+            return Ok(false);
+        }
+
+        self.conn.execute(
+            "INSERT INTO private_groups (group_id, name, group_key, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                &group_key.group_id()[..],
+                name,
+                secret.seal_group_key(group_key)?,
+                unix_now_millis()?
+            ],
+        )?;
+        Ok(true)
+    }
+
+    /// Get a held group key by group ID
+    pub fn get_group_key(
+        &self,
+        group_id: &[u8; 32],
+        secret: &RecipientSecret,
+    ) -> Result<Option<GroupKey>> {
+        self.load_group_key("group_id = ?1", params![&group_id[..]], secret)
+    }
+
+    /// Get a held group key by its local name
+    pub fn find_group_key(&self, name: &str, secret: &RecipientSecret) -> Result<Option<GroupKey>> {
+        self.load_group_key("name = ?1", params![name], secret)
+    }
+
+    fn load_group_key(
+        &self,
+        condition: &str,
+        params: impl rusqlite::Params,
+        secret: &RecipientSecret,
+    ) -> Result<Option<GroupKey>> {
+        let row: Option<(Vec<u8>, Vec<u8>)> = self
+            .conn
+            .query_row(
+                &format!("SELECT group_id, group_key FROM private_groups WHERE {}", condition),
+                params,
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((group_id, stored)) = row else {
+            return Ok(None);
+        };
+        let group_id = blob_to_id(&group_id)?;
+
+        // Keys stored before they were sealed are sealed on first use
+        if stored.len() == 32 {
+            let group_key = GroupKey::from_bytes(blob_to_id(&stored)?);
+            self.conn.execute(
+                "UPDATE private_groups SET group_key = ?1 WHERE group_id = ?2",
+                params![secret.seal_group_key(&group_key)?, &group_id[..]],
+            )?;
+            return Ok(Some(group_key));
+        }
+
+        secret.open_group_key(&group_id, &stored).map(Some)
+    }
+
+    /// Names and IDs of the groups this node belongs to
+    pub fn list_groups(&self) -> Result<Vec<(String, [u8; 32])>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, group_id FROM private_groups ORDER BY created_at ASC")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+
+        let mut groups = Vec::new();
+        for row in rows {
+            let (name, group_id) = row?;
+            groups.push((name, blob_to_id(&group_id)?));
+        }
+        Ok(groups)
+    }
+
+    /// Record a group key grant (signature must already be verified)
+    ///
+    /// A grant that `secret` can open adds the group and decrypts the private
+    /// grains of that group received so far.
+    pub fn apply_group_grant(
+        &self,
+        grant: &GroupKeyGrant,
+        secret: &RecipientSecret,
+        pending_broadcast: bool,
+    ) -> Result<GrantOutcome> {
+        let id = grant.id();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let already_applied: bool = tx.query_row(
+            "SELECT COUNT(*) > 0 FROM group_key_grants WHERE id = ?1",
+            params![&id[..]],
+            |row| row.get(0),
+        )?;
+        if already_applied {
+            return Ok(GrantOutcome::Duplicate);
+        }
+
+        tx.execute(
+            "INSERT INTO group_key_grants (id, group_id, record, received_at, pending_broadcast)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                &id[..],
+                &grant.group_id[..],
+                bincode::serialize(grant)?,
+                unix_now_millis()?,
+                pending_broadcast
+            ],
+        )?;
+
+        let outcome = match grant.open(secret) {
+            Some(group_key) => {
+                let name = format!("group-{}", short_hex(&grant.group_id));
+                if self.insert_group_key(&name, &group_key, secret)? {
+                    GrantOutcome::Joined {
+                        group_id: grant.group_id,
+                        opened: self.open_private_grains(&group_key)?,
+                    }
+                } else {
+                    GrantOutcome::Relayed
+                }
+            }
+            None => GrantOutcome::Relayed,
+        };
+
+        tx.commit()?;
+        Ok(outcome)
+    }
+
+    /// Record a private grain (envelope signature must already be verified)
+    ///
+    /// Grains of groups we hold a key for are decrypted and stored with the
+    /// other grains; the rest are kept as ciphertext.
+    pub fn apply_private_grain(
+        &self,
+        private: &PrivateGrain,
+        secret: &RecipientSecret,
+        pending_broadcast: bool,
+    ) -> Result<PrivateGrainOutcome> {
+        let id = private.id();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let already_applied: bool = tx.query_row(
+            "SELECT COUNT(*) > 0 FROM private_grains WHERE id = ?1",
+            params![&id[..]],
+            |row| row.get(0),
+        )?;
+        if already_applied {
+            return Ok(PrivateGrainOutcome::Duplicate);
+        }
+
+        let grain = match self.get_group_key(&private.group_id, secret)? {
+            Some(group_key) => match private.open(&group_key) {
+                Ok(grain) => Some(grain),
+                Err(e) => return Ok(PrivateGrainOutcome::Rejected(e.to_string())),
+            },
+            None => None,
+        };
+        if let Some(ref grain) = grain {
+            self.insert_grain(grain)?;
+        }
+
+        tx.execute(
+            "INSERT INTO private_grains (id, group_id, grain_id, record, received_at, pending_broadcast)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                &id[..],
+                &private.group_id[..],
+                grain.as_ref().map(|grain| grain.id.to_vec()),
+                bincode::serialize(private)?,
+                unix_now_millis()?,
+                pending_broadcast
+            ],
+        )?;
+
+        tx.commit()?;
+        Ok(match grain {
+            Some(grain) => PrivateGrainOutcome::Opened { grain_id: grain.id },
+            None => PrivateGrainOutcome::Relayed,
+        })
+    }
+
+    /// IDs of decrypted private grains, which must not leave the group
+    pub fn private_grain_ids(&self) -> Result<HashSet<[u8; 32]>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT grain_id FROM private_grains WHERE grain_id IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0))?;
+
+        let mut ids = HashSet::new();
+        for row in rows {
+            ids.insert(blob_to_id(&row?)?);
+        }
+        Ok(ids)
+    }
+
+    /// Decrypted grains of a group this node belongs to
+    pub fn get_private_grains(&self, group_id: &[u8; 32]) -> Result<Vec<Grain>> {
+        self.query_grains(
+            "SELECT id, vec, meta, sig FROM grains
+             WHERE id IN (SELECT grain_id FROM private_grains WHERE group_id = ?1)
+             ORDER BY created_at DESC",
+            params![&group_id[..]],
+        )
+    }
+
+    /// Locally created grants that still need to be published
+    pub fn pending_group_grants(&self) -> Result<Vec<GroupKeyGrant>> {
+        self.pending_records("group_key_grants")
+    }
+
+    /// Record that a pending grant was published
+    pub fn mark_group_grant_broadcast(&self, id: &[u8; 32]) -> Result<()> {
+        self.mark_broadcast("group_key_grants", id)
+    }
+
+    /// Locally created private grains that still need to be published
+    pub fn pending_private_grains(&self) -> Result<Vec<PrivateGrain>> {
+        self.pending_records("private_grains")
+    }
+
+    /// Record that a pending private grain was published
+    pub fn mark_private_grain_broadcast(&self, id: &[u8; 32]) -> Result<()> {
+        self.mark_broadcast("private_grains", id)
+    }
+
+    /// Decrypt stored private grains of a group we just joined
+    fn open_private_grains(&self, group_key: &GroupKey) -> Result<usize> {
+        let records = {
+            let mut stmt = self.conn.prepare(
+                "SELECT id, record FROM private_grains WHERE group_id = ?1 AND grain_id IS NULL",
+            )?;
+            let rows = stmt.query_map(params![&group_key.group_id()[..]], |row| {
+                Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        let mut opened = 0;
+        for (id, record) in records {
+            let private: PrivateGrain = bincode::deserialize(&record)?;
+            match private.open(group_key) {
+                Ok(grain) => {
+                    self.insert_grain(&grain)?;
+                    self.conn.execute(
+                        "UPDATE private_grains SET grain_id = ?1 WHERE id = ?2",
+                        params![&grain.id[..], id],
+                    )?;
+                    opened += 1;
+                }
+                Err(e) => warn!("Failed to open private grain {}: {}", short_hex(&id), e),
+            }
+        }
+        Ok(opened)
+    }
+
+    // ===== Grain Provenance =====
+
+    /// Record the goal and episode that created a grain
+    pub fn record_provenance(&self, provenance: &GrainProvenance) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO grain_provenance (grain_id, goal_id, episode_id, tool, user_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                &provenance.grain_id[..],
+                provenance.goal_id.to_string(),
+                provenance.episode_id.map(|id| id.to_string()),
+                provenance.tool,
+                provenance.user_id,
+                provenance.created_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the provenance of an agent-written grain
+    pub fn get_provenance(&self, grain_id: &[u8; 32]) -> Result<Option<GrainProvenance>> {
+        let row = self
+            .conn
+            .query_row(
+                "SELECT grain_id, goal_id, episode_id, tool, user_id, created_at
+                 FROM grain_provenance WHERE grain_id = ?1",
+                params![&grain_id[..]],
+                |row| {
+                    Ok((
+                        row_id(row, 0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                        row.get(5)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        row.map(|(grain_id, goal_id, episode_id, tool, user_id, created_at)| {
+            Ok(GrainProvenance {
+                grain_id,
+                goal_id: Uuid::parse_str(&goal_id)?,
+                episode_id: episode_id.as_deref().map(Uuid::parse_str).transpose()?,
+                tool,
+                user_id,
+                created_at,
+            })
+        })
+        .transpose()
+    }
+
+    /// Grains created by a goal, oldest first
+    pub fn grains_created_by_goal(&self, goal_id: &Uuid) -> Result<Vec<[u8; 32]>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT grain_id FROM grain_provenance WHERE goal_id = ?1 ORDER BY created_at ASC",
+        )?;
+        let rows = stmt.query_map(params![goal_id.to_string()], |row| row_id(row, 0))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    // ===== Web Cache =====
+
+    /// Store the latest fetch of a URL, replacing the previous one
+    pub fn put_web_cache(&self, entry: &WebCacheEntry) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO web_cache
+             (url, etag, last_modified, content_hash, content_type, body, grain_ids, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.url,
+                entry.etag,
+                entry.last_modified,
+                entry.content_hash,
+                entry.content_type,
+                entry.body,
+                entry.grain_ids.concat(),
+                entry.fetched_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the cached fetch of a URL
+    pub fn get_web_cache(&self, url: &str) -> Result<Option<WebCacheEntry>> {
+        let entry = self
+            .conn
+            .query_row(
+                "SELECT url, etag, last_modified, content_hash, content_type, body, grain_ids, fetched_at
+                 FROM web_cache WHERE url = ?1",
+                params![url],
+                web_cache_from_row,
+            )
+            .optional()?;
+        Ok(entry)
+    }
+
+    /// Grains already holding content with this hash, from any URL
+    pub fn grains_for_content(&self, content_hash: &str) -> Result<Vec<[u8; 32]>> {
+        let ids: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "SELECT grain_ids FROM web_cache
+                 WHERE content_hash = ?1 AND length(grain_ids) > 0
+                 ORDER BY fetched_at ASC LIMIT 1",
+                params![content_hash],
+                |row| row.get(0),
+            )
+            .optional()?;
+        ids.unwrap_or_default()
+            .chunks(32)
+            .map(blob_to_id)
+            .collect()
+    }
+
+    // ===== Approvals =====
+
+    /// File a new approval request (`record` is the request as JSON)
+    pub fn insert_approval(
+        &self,
+        id: &Uuid,
+        status: &str,
+        requested_at: i64,
+        record: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO approvals (id, status, resumed, requested_at, record)
+             VALUES (?1, ?2, 0, ?3, ?4)",
+            params![id.to_string(), status, requested_at, record],
+        )?;
+        Ok(())
+    }
+
+    /// Get the JSON record of an approval request
+    pub fn get_approval(&self, id: &Uuid) -> Result<Option<String>> {
+        let record = self
+            .conn
+            .query_row(
+                "SELECT record FROM approvals WHERE id = ?1",
+                params![id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(record)
+    }
+
+    /// JSON records of all approval requests, oldest first
+    pub fn list_approvals(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT record FROM approvals ORDER BY requested_at ASC")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<String>>>()?)
+    }
+
+    /// Replace an approval request still in `expected_status` and not resumed
+    ///
+    /// Returns false if another writer changed the request first.
+    pub fn update_approval(
+        &self,
+        id: &Uuid,
+        expected_status: &str,
+        status: &str,
+        resumed: bool,
+        record: &str,
+    ) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE approvals SET status = ?3, resumed = ?4, record = ?5
+             WHERE id = ?1 AND status = ?2 AND resumed = 0",
+            params![id.to_string(), expected_status, status, resumed, record],
+        )?;
+        Ok(updated > 0)
+    }
+
+    // ===== Curation =====
+
+    /// Register a curator key; returns false if it was already registered
+    pub fn add_curator(&self, public_key: &[u8; 32], name: Option<&str>) -> Result<bool> {
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO curators (public_key, name, added_at) VALUES (?1, ?2, ?3)",
+            params![&public_key[..], name, unix_now_millis()?],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Unregister a curator key; past decisions stay in the audit log
+    pub fn remove_curator(&self, public_key: &[u8; 32]) -> Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM curators WHERE public_key = ?1",
+            params![&public_key[..]],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Registered curators with their names, oldest first
+    pub fn list_curators(&self) -> Result<Vec<([u8; 32], Option<String>)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT public_key, name FROM curators ORDER BY added_at ASC")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
+
+        let mut curators = Vec::new();
+        for row in rows {
+            let (key, name) = row?;
+            curators.push((blob_to_id(&key)?, name));
+        }
+        Ok(curators)
+    }
+
+    /// Whether `public_key` is a registered curator
+    pub fn is_curator(&self, public_key: &[u8; 32]) -> Result<bool> {
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*) > 0 FROM curators WHERE public_key = ?1",
+            params![&public_key[..]],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// File a task for review
+    pub fn insert_curator_task(&self, task: &NewCuratorTask) -> Result<CuratorTask> {
+        let task = CuratorTask {
+            id: Uuid::new_v4(),
+            route: task.route.clone(),
+            subject: task.subject.clone(),
+            requester: task.requester.clone(),
+            classification: task.classification.clone(),
+            outcome: task.outcome.clone(),
+            status: CuratorTaskStatus::Pending,
+            curator: None,
+            note: None,
+            created_at: unix_now_millis()?,
+            decided_at: None,
+            release_token_hash: task.release_token_hash,
+        };
+
+        self.conn.execute(
+            "INSERT INTO curator_tasks
+             (id, route, subject, requester, classification, outcome, status, created_at,
+              release_token_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                task.id.to_string(),
+                task.route,
+                task.subject,
+                task.requester,
+                task.classification.to_string(),
+                task.outcome.as_ref().map(|o| o.to_string()),
+                task.status.as_str(),
+                task.created_at,
+                task.release_token_hash.as_ref().map(|hash| &hash[..])
+            ],
+        )?;
+        Ok(task)
+    }
+
+    /// Get a review task
+    pub fn get_curator_task(&self, id: &Uuid) -> Result<Option<CuratorTask>> {
+        get_curator_task(&self.conn, id)
+    }
+
+    /// Review tasks, oldest first
+    pub fn list_curator_tasks(
+        &self,
+        status: Option<CuratorTaskStatus>,
+        limit: usize,
+    ) -> Result<Vec<CuratorTask>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM curator_tasks
+             WHERE ?1 IS NULL OR status = ?1
+             ORDER BY created_at ASC LIMIT ?2",
+            CURATOR_TASK_COLUMNS
+        ))?;
+        let rows = stmt.query_map(
+            params![status.map(|s| s.as_str()), limit as i64],
+            curator_task_from_row,
+        )?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Apply a curator decision (signature must already be verified)
+    ///
+    /// A claimed task can only be decided by the curator holding it. Applied
+    /// decisions are appended to the audit log in the same transaction.
+    pub fn apply_curator_decision(&self, decision: &CuratorDecision) -> Result<DecisionOutcome> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        let Some(mut task) = get_curator_task(&tx, &decision.task_id)? else {
+            return Ok(DecisionOutcome::NotFound);
+        };
+        let is_curator: bool = tx.query_row(
+            "SELECT COUNT(*) > 0 FROM curators WHERE public_key = ?1",
+            params![&decision.curator[..]],
+            |row| row.get(0),
+        )?;
+        if !is_curator {
+            return Ok(DecisionOutcome::NotCurator);
+        }
+        if task.status.is_decided() {
+            return Ok(DecisionOutcome::Conflict(format!(
+                "Task already {}",
+                task.status.as_str()
+            )));
+        }
+        if let Some(holder) = task.curator.filter(|holder| *holder != decision.curator) {
+            return Ok(DecisionOutcome::Conflict(format!(
+                "Task claimed by {}",
+                hex::encode(&holder[..8])
+            )));
+        }
+
+        let now = unix_now_millis()?;
+        task.curator = Some(decision.curator);
+        match decision.action {
+            CuratorAction::Claim => task.status = CuratorTaskStatus::InReview,
+            CuratorAction::Approve | CuratorAction::Reject => {
+                task.status = if decision.action == CuratorAction::Approve {
+                    CuratorTaskStatus::Approved
+                } else {
+                    CuratorTaskStatus::Rejected
+                };
+                task.note = decision.note.clone();
+                task.decided_at = Some(now);
+            }
+        }
+
+        tx.execute(
+            "UPDATE curator_tasks SET status = ?2, curator = ?3, note = ?4, decided_at = ?5
+             WHERE id = ?1",
+            params![
+                task.id.to_string(),
+                task.status.as_str(),
+                &decision.curator[..],
+                task.note,
+                task.decided_at
+            ],
+        )?;
+        tx.execute(
+            "INSERT INTO curator_audit (task_id, curator, action, note, ts_unix_ms, sig, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                task.id.to_string(),
+                &decision.curator[..],
+                decision.action.as_str(),
+                decision.note,
+                decision.ts_unix_ms,
+                decision.sig,
+                now
+            ],
+        )?;
+
+        tx.commit()?;
+        Ok(DecisionOutcome::Applied(Box::new(task)))
+    }
+
+    /// Applied curator decisions, oldest first, optionally for one task
+    pub fn curator_audit(
+        &self,
+        task_id: Option<&Uuid>,
+        limit: usize,
+    ) -> Result<Vec<CuratorAuditEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, task_id, curator, action, note, ts_unix_ms, sig, recorded_at
+             FROM curator_audit
+             WHERE ?1 IS NULL OR task_id = ?1
+             ORDER BY id ASC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(
+            params![task_id.map(|id| id.to_string()), limit as i64],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Vec<u8>>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, i64>(5)?,
+                    row.get::<_, Vec<u8>>(6)?,
+                    row.get::<_, i64>(7)?,
+                ))
+            },
+        )?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let (id, task_id, curator, action, note, ts_unix_ms, sig, recorded_at) = row?;
+            entries.push(CuratorAuditEntry {
+                id,
+                task_id: Uuid::parse_str(&task_id)?,
+                curator: blob_to_id(&curator)?,
+                action: CuratorAction::parse(&action)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown curator action: {}", action))?,
+                note,
+                ts_unix_ms,
+                sig,
+                recorded_at,
+            });
+        }
+        Ok(entries)
+    }
+
+    // ===== Moderation =====
+
+    /// Record a flag, unflag or appeal (signature must already be verified)
+    ///
+    /// Flags for grains we do not hold are kept for `ORPHAN_FLAG_TTL_MS`,
+    /// since the grain may still arrive, up to `MAX_ORPHAN_FLAGS`. Appeals
+    /// are only accepted from the grain's author. Messages dated more than
+    /// `MAX_FLAG_CLOCK_SKEW_MS` ahead of local time are rejected.
+    pub fn apply_grain_flag(
+        &self,
+        flag: &GrainFlag,
+        pending_broadcast: bool,
+    ) -> Result<FlagOutcome> {
+        let id = flag.id();
+        let now = unix_now_millis()?;
+        if flag.ts_unix_ms > now + MAX_FLAG_CLOCK_SKEW_MS {
+            return Ok(FlagOutcome::Rejected(
+                "Flag is dated in the future".to_string(),
+            ));
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+
+        let already_applied: bool = tx.query_row(
+            "SELECT COUNT(*) > 0 FROM grain_flags WHERE id = ?1",
+            params![&id[..]],
+            |row| row.get(0),
+        )?;
+        if already_applied {
+            return Ok(FlagOutcome::Duplicate);
+        }
+
+        let grain = self.get_grain(&flag.grain_id)?;
+        if flag.action == FlagAction::Appeal
+            && grain
+                .as_ref()
+                .is_none_or(|grain| grain.meta.author_pk != flag.signer)
+        {
+            return Ok(FlagOutcome::Rejected(
+                "Only the grain's author can appeal".to_string(),
+            ));
+        }
+        if grain.is_none() {
+            self.prune_orphan_grain_flags(now - ORPHAN_FLAG_TTL_MS)?;
+            let orphans: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM grain_flags f
+                 WHERE NOT EXISTS (SELECT 1 FROM grains g WHERE g.id = f.grain_id)",
+                [],
+                |row| row.get(0),
+            )?;
+            if orphans as usize >= MAX_ORPHAN_FLAGS {
+                return Ok(FlagOutcome::Rejected(
+                    "Too many flags on unknown grains".to_string(),
+                ));
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO grain_flags
+             (id, grain_id, signer, action, ts_unix_ms, record, received_at, pending_broadcast)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                &id[..],
+                &flag.grain_id[..],
+                &flag.signer[..],
+                flag.action.as_str(),
+                flag.ts_unix_ms,
+                bincode::serialize(flag)?,
+                now,
+                pending_broadcast
+            ],
+        )?;
+
+        tx.commit()?;
+        Ok(FlagOutcome::Recorded)
+    }
+
+    /// Drop flags received before `before` on grains this node does not hold
+    pub fn prune_orphan_grain_flags(&self, before: i64) -> Result<usize> {
+        Ok(self.conn.execute(
+            "DELETE FROM grain_flags
+             WHERE received_at < ?1
+               AND NOT EXISTS (SELECT 1 FROM grains g WHERE g.id = grain_flags.grain_id)",
+            params![before],
+        )?)
+    }
+
+    /// All flag messages on a grain, oldest first
+    pub fn grain_flags(&self, grain_id: &[u8; 32]) -> Result<Vec<GrainFlag>> {
+        Ok(self
+            .received_grain_flags(grain_id)?
+            .into_iter()
+            .map(|(flag, _)| flag)
+            .collect())
+    }
+
+    /// Flags that currently count against a grain, one per signer
+    ///
+    /// A signer's latest flag or unflag wins. Flags received at or before
+    /// `after` (local time) are ignored.
+    pub fn active_grain_flags(
+        &self,
+        grain_id: &[u8; 32],
+        after: Option<i64>,
+    ) -> Result<Vec<GrainFlag>> {
+        let mut latest: HashMap<[u8; 32], (GrainFlag, i64)> = HashMap::new();
+        for (flag, received_at) in self.received_grain_flags(grain_id)? {
+            if flag.action != FlagAction::Appeal {
+                latest.insert(flag.signer, (flag, received_at));
+            }
+        }
+
+        Ok(latest
+            .into_values()
+            .filter(|(flag, _)| matches!(flag.action, FlagAction::Flag(_)))
+            .filter(|(_, received_at)| after.is_none_or(|after| *received_at > after))
+            .map(|(flag, _)| flag)
+            .collect())
+    }
+
+    /// The author's latest appeal on a grain, if received after `after`
+    pub fn latest_grain_appeal(
+        &self,
+        grain_id: &[u8; 32],
+        after: Option<i64>,
+    ) -> Result<Option<GrainFlag>> {
+        Ok(self
+            .received_grain_flags(grain_id)?
+            .into_iter()
+            .rev()
+            .filter(|(flag, _)| flag.action == FlagAction::Appeal)
+            .find(|(_, received_at)| after.is_none_or(|after| *received_at > after))
+            .map(|(flag, _)| flag))
+    }
+
+    /// Flag messages on a grain with their local receive time, oldest first
+    fn received_grain_flags(&self, grain_id: &[u8; 32]) -> Result<Vec<(GrainFlag, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT record, received_at FROM grain_flags WHERE grain_id = ?1
+             ORDER BY ts_unix_ms ASC, rowid ASC",
+        )?;
+        let rows = stmt.query_map(params![&grain_id[..]], |row| {
+            Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut flags = Vec::new();
+        for row in rows {
+            let (record, received_at) = row?;
+            flags.push((bincode::deserialize(&record)?, received_at));
+        }
+        Ok(flags)
+    }
+
+    /// Get this node's verdict on a grain
+    pub fn get_grain_moderation(&self, grain_id: &[u8; 32]) -> Result<Option<GrainModeration>> {
+        let moderation = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM grain_moderation WHERE grain_id = ?1",
+                    GRAIN_MODERATION_COLUMNS
+                ),
+                params![&grain_id[..]],
+                grain_moderation_from_row,
+            )
+            .optional()?;
+        Ok(moderation)
+    }
+
+    /// Insert or replace this node's verdict on a grain
+    pub fn put_grain_moderation(&self, moderation: &GrainModeration) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO grain_moderation
+             (grain_id, status, flag_weight, cleared_at, appeal_dismissed_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                &moderation.grain_id[..],
+                moderation.status.as_str(),
+                moderation.flag_weight,
+                moderation.cleared_at,
+                moderation.appeal_dismissed_at,
+                moderation.updated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Moderated grains, most recently updated first
+    pub fn list_grain_moderation(
+        &self,
+        status: Option<ModerationStatus>,
+        limit: usize,
+    ) -> Result<Vec<GrainModeration>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM grain_moderation
+             WHERE ?1 IS NULL OR status = ?1
+             ORDER BY updated_at DESC LIMIT ?2",
+            GRAIN_MODERATION_COLUMNS
+        ))?;
+        let rows = stmt.query_map(
+            params![status.map(|s| s.as_str()), limit as i64],
+            grain_moderation_from_row,
+        )?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// IDs of quarantined grains, which must not show up in search
+    pub fn quarantined_grain_ids(&self) -> Result<HashSet<[u8; 32]>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT grain_id FROM grain_moderation WHERE status IN ('quarantined', 'appealed')",
+        )?;
+        let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0))?;
+
+        let mut ids = HashSet::new();
+        for row in rows {
+            ids.insert(blob_to_id(&row?)?);
+        }
+        Ok(ids)
+    }
+
+    /// Locally signed flag messages that still need to be published
+    pub fn pending_grain_flags(&self) -> Result<Vec<GrainFlag>> {
+        self.pending_records("grain_flags")
+    }
+
+    /// Record that a pending flag message was published
+    pub fn mark_grain_flag_broadcast(&self, id: &[u8; 32]) -> Result<()> {
+        self.mark_broadcast("grain_flags", id)
+    }
+
+    /// Records still to be published; they stay pending until `mark_broadcast`
+    fn pending_records<T: serde::de::DeserializeOwned>(&self, table: &str) -> Result<Vec<T>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT record FROM {} WHERE pending_broadcast = 1 ORDER BY received_at ASC",
+            table
+        ))?;
+        let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0))?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(bincode::deserialize(&row?)?);
+        }
+        Ok(records)
+    }
+
+    fn mark_broadcast(&self, table: &str, id: &[u8; 32]) -> Result<()> {
+        self.conn.execute(
+            &format!("UPDATE {} SET pending_broadcast = 0 WHERE id = ?1", table),
+            params![&id[..]],
+        )?;
+        Ok(())
+    }
+
+    fn get_postings(&self, entry_id: i64) -> Result<Vec<Posting>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT account, amount FROM ledger_postings WHERE entry_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![entry_id], |row| {
+            Ok(Posting {
+                account: row.get(0)?,
+                amount: row.get(1)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+}
+
+/// Insert a credit and post its reward, inside the caller's transaction
+fn insert_credit(conn: &Connection, credit: &Credit) -> Result<()> {
+    conn.execute(
+        "INSERT INTO credits (grain_id, node_pk, ngt, reason, ts_unix_ms) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            &credit.grain_id[..],
+            &credit.node_pk[..],
+            credit.ngt,
+            &credit.reason,
+            credit.ts_unix_ms
+        ],
+    )?;
+
+    if credit.ngt > 0.0 {
+        let entry = NewLedgerEntry::reward(
+            &ledger::node_account(&credit.node_pk),
+            credit.ngt,
+            &credit.reason,
+        )
+        .with_idempotency_key(ledger::credit_key(conn.last_insert_rowid()));
+        post_entry(conn, &entry, credit.ts_unix_ms)?;
+    }
+    Ok(())
+}
+
+/// Post an entry inside an open transaction
+fn post_entry(conn: &Connection, entry: &NewLedgerEntry, ts_unix_ms: i64) -> Result<PostOutcome> {
+    entry.validate()?;
+
+    if let Some(key) = &entry.idempotency_key {
+        let existing: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM ledger_entries WHERE idempotency_key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(id) = existing {
+            return Ok(PostOutcome::Duplicate(id));
+        }
+    }
+
+    // Only system accounts may go negative
+    for posting in entry.postings.iter().filter(|p| p.amount < 0.0) {
+        if ledger::is_system_account(&posting.account) {
+            continue;
+        }
+        let balance = account_balance(conn, &posting.account)?;
+        if balance + posting.amount < 0.0 && !ledger::amounts_match(balance, -posting.amount) {
+            return Err(anyhow::anyhow!(
+                "Insufficient balance in {}: {} < {}",
+                posting.account,
+                balance,
+                -posting.amount
+            ));
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO ledger_entries (kind, idempotency_key, memo, ts_unix_ms) VALUES (?1, ?2, ?3, ?4)",
+        params![entry.kind.as_str(), entry.idempotency_key, entry.memo, ts_unix_ms],
+    )?;
+    let entry_id = conn.last_insert_rowid();
+
+    for posting in &entry.postings {
+        conn.execute(
+            "INSERT INTO ledger_postings (entry_id, account, amount) VALUES (?1, ?2, ?3)",
+            params![entry_id, posting.account, posting.amount],
+        )?;
+    }
+
+    Ok(PostOutcome::Posted(entry_id))
+}
+
+/// Identity of a key inside an open transaction
+fn key_identity(conn: &Connection, pk: &[u8; 32]) -> Result<[u8; 32]> {
+    let identity: Option<Vec<u8>> = conn
+        .query_row(
+            "SELECT identity FROM key_successions WHERE new_pk = ?1",
+            params![&pk[..]],
+            |row| row.get(0),
+        )
+        .optional()?;
+    identity.map_or(Ok(*pk), |identity| blob_to_id(&identity))
+}
+
+/// Balance from the latest snapshot plus the postings made after it
+fn account_balance(conn: &Connection, account: &str) -> Result<f64> {
+    let (snapshot, since_entry): (f64, i64) = conn
+        .query_row(
+            "SELECT balance, entry_id FROM ledger_snapshots
+             WHERE account = ?1 ORDER BY entry_id DESC LIMIT 1",
+            params![account],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .unwrap_or((0.0, 0));
+
+    let delta: f64 = conn.query_row(
+        "SELECT COALESCE(SUM(amount), 0.0) FROM ledger_postings WHERE account = ?1 AND entry_id > ?2",
+        params![account, since_entry],
+        |row| row.get(0),
+    )?;
+
+    Ok(snapshot + delta)
+}
+
+/// Map a ledger_entries row to an entry without postings
+fn ledger_entry_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<LedgerEntry> {
+    let kind: String = row.get(1)?;
+    Ok(LedgerEntry {
+        id: row.get(0)?,
+        kind: LedgerEntryKind::parse(&kind).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(
+                1,
+                rusqlite::types::Type::Text,
+                format!("Unknown ledger entry kind: {}", kind).into(),
+            )
+        })?,
+        idempotency_key: row.get(2)?,
+        memo: row.get(3)?,
+        ts_unix_ms: row.get(4)?,
+        postings: Vec::new(),
+    })
+}
+
+/// Map an ngt_transfers row to a record
+fn transfer_record_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<TransferRecord> {
+    Ok(TransferRecord {
+        id: row_id(row, 0)?,
+        transfer: NgtTransfer {
+            from: row_id(row, 1)?,
+            to: row_id(row, 2)?,
+            amount: row.get(3)?,
+            nonce: row.get::<_, i64>(4)? as u64,
+            ts_unix_ms: row.get(5)?,
+            memo: row.get(6)?,
+            sig: row.get(7)?,
+        },
+        entry_id: row.get(8)?,
+        received_at: row.get(9)?,
+    })
+}
+
+/// Read a 32-byte ID column
+fn row_id(row: &rusqlite::Row<'_>, idx: usize) -> rusqlite::Result<[u8; 32]> {
+    let bytes: Vec<u8> = row.get(idx)?;
+    blob_to_id(&bytes).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Blob, e.into())
+    })
+}
+
+fn short_hex(bytes: &[u8]) -> String {
+    bytes.iter().take(4).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn blob_to_id(bytes: &[u8]) -> Result<[u8; 32]> {
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Expected 32 bytes, got {}", bytes.len()))
+}
+
+/// Map a web_cache row
+fn web_cache_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<WebCacheEntry> {
+    let ids: Vec<u8> = row.get(6)?;
+    let grain_ids = ids
+        .chunks(32)
+        .map(blob_to_id)
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(6, rusqlite::types::Type::Blob, e.into())
+        })?;
+
+    Ok(WebCacheEntry {
+        url: row.get(0)?,
+        etag: row.get(1)?,
+        last_modified: row.get(2)?,
+        content_hash: row.get(3)?,
+        content_type: row.get(4)?,
+        body: row.get(5)?,
+        grain_ids,
+        fetched_at: row.get(7)?,
+    })
+}
+
+/// Map a poe_scores row
+fn poe_score_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<PoeScoreRecord> {
+    Ok(PoeScoreRecord {
+        grain_id: row_id(row, 0)?,
+        author_pk: row.get(1)?,
+        novelty: row.get(2)?,
+        coherence: row.get(3)?,
+        reuse: row.get(4)?,
+        total: row.get(5)?,
+        ngt_reward: row.get(6)?,
+        credited: row.get(7)?,
+        epoch: row.get::<_, i64>(8)? as u64,
+        calculated_at: row.get(9)?,
+    })
+}
+
+/// Map a goals row to a record
+fn goal_record_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<GoalRecord> {
+    Ok(GoalRecord {
+        id: row.get(0)?,
+        text: row.get(1)?,
+        status: row.get(2)?,
+        priority: row.get(3)?,
+        created_by: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+        parent_id: row.get(7)?,
+        metadata: row.get(8)?,
+    })
+}
+
+/// Condition on `grains` rows that are not decrypted private grains
+const PUBLIC_GRAIN: &str =
+    "NOT EXISTS (SELECT 1 FROM private_grains p WHERE p.grain_id = grains.id)";
+
+const CURATOR_TASK_COLUMNS: &str = "id, route, subject, requester, classification, outcome, \
+     status, curator, note, created_at, decided_at, release_token_hash";
+
+/// Get a review task inside an open transaction
+fn get_curator_task(conn: &Connection, id: &Uuid) -> Result<Option<CuratorTask>> {
+    let task = conn
+        .query_row(
+            &format!("SELECT {} FROM curator_tasks WHERE id = ?1", CURATOR_TASK_COLUMNS),
+            params![id.to_string()],
+            curator_task_from_row,
+        )
+        .optional()?;
+    Ok(task)
+}
+
+const GRAIN_MODERATION_COLUMNS: &str =
+    "grain_id, status, flag_weight, cleared_at, appeal_dismissed_at, updated_at";
+
+/// Map a grain_moderation row
+fn grain_moderation_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<GrainModeration> {
+    let grain_id: Vec<u8> = row.get(0)?;
+    let status: String = row.get(1)?;
+    let invalid = |index: usize, e: String| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, e.into())
+    };
+
+    Ok(GrainModeration {
+        grain_id: blob_to_id(&grain_id).map_err(|e| invalid(0, e.to_string()))?,
+        status: ModerationStatus::parse(&status)
+            .ok_or_else(|| invalid(1, format!("Unknown moderation status: {}", status)))?,
+        flag_weight: row.get(2)?,
+        cleared_at: row.get(3)?,
+        appeal_dismissed_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+/// Map a curator_tasks row
+fn curator_task_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CuratorTask> {
+    fn invalid(
+        index: usize,
+        e: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> rusqlite::Error {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, e.into())
+    }
+
+    let id: String = row.get(0)?;
+    let classification: String = row.get(4)?;
+    let outcome: Option<String> = row.get(5)?;
+    let status: String = row.get(6)?;
+    let curator: Option<Vec<u8>> = row.get(7)?;
+    let release_token_hash: Option<Vec<u8>> = row.get(11)?;
+
+    Ok(CuratorTask {
+        id: Uuid::parse_str(&id).map_err(|e| invalid(0, e))?,
+        route: row.get(1)?,
+        subject: row.get(2)?,
+        requester: row.get(3)?,
+        classification: serde_json::from_str(&classification).map_err(|e| invalid(4, e))?,
+        outcome: outcome
+            .map(|o| serde_json::from_str(&o))
+            .transpose()
+            .map_err(|e| invalid(5, e))?,
+        status: CuratorTaskStatus::parse(&status)
+            .ok_or_else(|| invalid(6, format!("Unknown task status: {}", status)))?,
+        curator: curator
+            .map(|key| blob_to_id(&key))
+            .transpose()
+            .map_err(|e| invalid(7, e))?,
+        note: row.get(8)?,
+        created_at: row.get(9)?,
+        decided_at: row.get(10)?,
+        release_token_hash: release_token_hash
+            .map(|hash| blob_to_id(&hash))
+            .transpose()
+            .map_err(|e| invalid(11, e))?,
+    })
+}
+
+fn unix_now_millis() -> Result<i64> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_millis() as i64)
+}
+
+fn unix_now_secs() -> Result<i64> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use rand::{rngs::OsRng, RngCore};
+    use synapsenet_core::GrainMeta;
+
+    fn generate_signing_key() -> SigningKey {
+        let mut secret_bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut secret_bytes);
+        SigningKey::from_bytes(&secret_bytes)
+    }
+
+    #[test]
+    fn test_store_grain() {
+        let store = Store::new(":memory:").unwrap();
+
+        let signing_key = generate_signing_key();
+        let author_pk = signing_key.verifying_key().to_bytes();
+
+        let meta = GrainMeta {
+            author_pk: author_pk.to_vec(),
+            crypto_backend: synapsenet_core::CryptoBackend::Classical,
+            ts_unix_ms: 1234567890,
+            tags: vec!["test".to_string()],
+            mime: "text/plain".to_string(),
+            lang: "en".to_string(),
+            title: Some("Test".to_string()),
+            summary: None,
+            embedding_model: Some("test-model".to_string()),
+            embedding_dimensions: Some(384),
+        };
+
+        let vec = vec![0.1, 0.2, 0.3];
+        let grain = Grain::new(vec, meta, &signing_key).unwrap();
+
+        store.insert_grain(&grain).unwrap();
+
+        let retrieved = store.get_grain(&grain.id).unwrap().unwrap();
+        assert_eq!(retrieved.id, grain.id);
+        assert_eq!(retrieved.vec, grain.vec);
+    }
+
+    #[test]
+    fn test_goal_persistence() {
+        let store = Store::new(":memory:").unwrap();
+
+        let mut goal = Goal::new("Explain PoE", "cli");
+        store.upsert_goal(&goal).unwrap();
+
+        goal.set_status(GoalStatus::Reasoning);
+        store.upsert_goal(&goal).unwrap();
+
+        let loaded = store.get_goal(&goal.id).unwrap().unwrap();
+        assert_eq!(loaded.text, "Explain PoE");
+        assert_eq!(loaded.status, GoalStatus::Reasoning);
+        assert_eq!(store.get_unfinished_goals().unwrap().len(), 1);
+
+        assert!(store.update_goal_status(&goal.id, GoalStatus::Cancelled).unwrap());
+        assert!(store.get_unfinished_goals().unwrap().is_empty());
+        assert_eq!(
+            store.list_goals(Some(GoalStatus::Cancelled), 10).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_episode_persistence() {
+        let store = Store::new(":memory:").unwrap();
+        let goal = Goal::new("Explain PoE", "cli");
+        store.upsert_goal(&goal).unwrap();
+        store.save_plan(&goal.id, "{}").unwrap();
+
+        let mut episode = EpisodeV2::from_episode(Episode::new(goal.id, 1, "Step 1"));
+        episode.add_action(EpisodeAction {
+            id: Uuid::new_v4(),
+            tool_name: "web_fetch".to_string(),
+            input: serde_json::json!({"url": "http://localhost"}),
+            output: None,
+            error: Some("timeout".to_string()),
+            timestamp: 1,
+            execution_time_ms: 5,
+            success: false,
+        });
+        store.insert_episode_v2(&episode).unwrap();
+
+        let episodes = store.get_episodes_v2(&goal.id).unwrap();
+        assert_eq!(episodes.len(), 1);
+        assert_eq!(episodes[0].actions[0].error.as_deref(), Some("timeout"));
+
+        store.clear_goal_progress(&goal.id).unwrap();
+        assert!(store.get_episodes(&goal.id).unwrap().is_empty());
+        assert!(store.get_plan(&goal.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_ledger_double_entry() {
+        let store = Store::new(":memory:").unwrap();
+        let alice = ledger::node_account(&[1u8; 32]);
+        let bob = ledger::node_account(&[2u8; 32]);
+
+        store
+            .post_ledger_entry(&NewLedgerEntry::mint(&alice, 10.0, "genesis"))
+            .unwrap();
+        store
+            .post_ledger_entry(&NewLedgerEntry::transfer(&alice, &bob, 4.0, "payment"))
+            .unwrap();
+        store
+            .post_ledger_entry(&NewLedgerEntry::burn(&bob, 1.0, "fee"))
+            .unwrap();
+
+        assert_eq!(store.get_account_balance(&alice).unwrap(), 6.0);
+        assert_eq!(store.get_account_balance(&bob).unwrap(), 3.0);
+
+        // Overdrafts are rejected
+        assert!(store
+            .post_ledger_entry(&NewLedgerEntry::penalty(&bob, 5.0, "spam"))
+            .is_err());
+
+        let history = store
+            .query_ledger(&LedgerQuery {
+                account: Some(bob.clone()),
+                ..LedgerQuery::default()
+            })
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].kind, LedgerEntryKind::Burn);
+
+        assert!(store.audit_ledger().unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_ledger_idempotency_and_snapshots() {
+        let store = Store::new(":memory:").unwrap();
+        let node = ledger::node_account(&[3u8; 32]);
+        let key = ledger::reward_key(&[9u8; 32], 7);
+
+        let reward = NewLedgerEntry::reward(&node, 2.5, "grain").with_idempotency_key(&key);
+        let first = store.post_ledger_entry(&reward).unwrap();
+        let second = store.post_ledger_entry(&reward).unwrap();
+        assert!(first.is_posted());
+        assert_eq!(second, PostOutcome::Duplicate(first.entry_id()));
+
+        let snapshot_at = store.create_balance_snapshot().unwrap();
+        store
+            .post_ledger_entry(&NewLedgerEntry::mint(&node, 1.0, "bonus"))
+            .unwrap();
+
+        let snapshots = store.get_balance_snapshots(&node, 10).unwrap();
+        assert_eq!(snapshots[0].entry_id, snapshot_at);
+        assert_eq!(snapshots[0].balance, 2.5);
+        assert_eq!(store.get_account_balance(&node).unwrap(), 3.5);
+        assert!(store.audit_ledger().unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_credit_posts_reward() {
+        let store = Store::new(":memory:").unwrap();
+        let node_pk = [4u8; 32];
+
+        store
+            .insert_credit(&Credit {
+                grain_id: [5u8; 32],
+                node_pk,
+                ngt: 1.25,
+                reason: "novel grain".to_string(),
+                ts_unix_ms: 1,
+            })
+            .unwrap();
+
+        assert_eq!(store.get_node_ngt(&node_pk).unwrap(), 1.25);
+        assert_eq!(
+            store.get_account_balance(ledger::ISSUANCE_ACCOUNT).unwrap(),
+            -1.25
+        );
+    }
+
+    #[test]
+    fn test_apply_transfer() {
+        let store = Store::new(":memory:").unwrap();
+        let sender = generate_signing_key();
+        let sender_pk = sender.verifying_key().to_bytes();
+        let recipient = [8u8; 32];
+
+        store
+            .post_ledger_entry(&NewLedgerEntry::mint(
+                &ledger::node_account(&sender_pk),
+                5.0,
+                "genesis",
+            ))
+            .unwrap();
+
+        let nonce = store.next_transfer_nonce(&sender_pk).unwrap();
+        let transfer = NgtTransfer::new(recipient, 2.0, nonce, None, &sender).unwrap();
+        assert!(matches!(
+            store.apply_transfer(&transfer, true).unwrap(),
+            TransferOutcome::Applied { .. }
+        ));
+        assert_eq!(
+            store.apply_transfer(&transfer, false).unwrap(),
+            TransferOutcome::Duplicate
+        );
+        assert_eq!(store.get_node_ngt(&recipient).unwrap(), 2.0);
+
+        // Reusing the nonce for another recipient is a double-spend
+        let double_spend = NgtTransfer::new([9u8; 32], 2.0, nonce, None, &sender).unwrap();
+        assert_eq!(
+            store.apply_transfer(&double_spend, false).unwrap(),
+            TransferOutcome::Conflict {
+                existing_id: transfer.id()
+            }
+        );
+        assert_eq!(store.get_transfer_conflicts(10).unwrap().len(), 1);
+
+        // Gossip may deliver a sender's transfers out of order
+        let later = NgtTransfer::new(recipient, 1.0, nonce + 2, None, &sender).unwrap();
+        let earlier = NgtTransfer::new(recipient, 1.0, nonce + 1, None, &sender).unwrap();
+        for transfer in [&later, &earlier] {
+            assert!(matches!(
+                store.apply_transfer(transfer, false).unwrap(),
+                TransferOutcome::Applied { .. }
+            ));
+        }
+        assert_eq!(store.get_node_ngt(&recipient).unwrap(), 4.0);
+
+        let overdraft = NgtTransfer::new(recipient, 10.0, nonce + 3, None, &sender).unwrap();
+        assert!(matches!(
+            store.apply_transfer(&overdraft, false).unwrap(),
+            TransferOutcome::Rejected(_)
+        ));
+
+        // Pending until published
+        assert_eq!(store.pending_transfers().unwrap(), vec![transfer.clone()]);
+        assert_eq!(store.pending_transfers().unwrap(), vec![transfer.clone()]);
+        store.mark_transfer_broadcast(&transfer.id()).unwrap();
+        assert!(store.pending_transfers().unwrap().is_empty());
+        assert_eq!(store.list_transfers(&recipient, 10).unwrap().len(), 3);
+        assert_eq!(store.next_transfer_nonce(&sender_pk).unwrap(), nonce + 3);
+        assert!(store.audit_ledger().unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_key_successions_track_identity() {
+        let store = Store::new(":memory:").unwrap();
+        let keys: Vec<SigningKey> = (0..4).map(|_| generate_signing_key()).collect();
+        let pks: Vec<[u8; 32]> = keys.iter().map(|k| k.verifying_key().to_bytes()).collect();
+
+        let first = KeySuccession::new(&keys[0], &keys[1], None).unwrap();
+        let second = KeySuccession::new(&keys[1], &keys[2], Some(5_000)).unwrap();
+
+        // Out of order: the later rotation is attached once the first arrives
+        assert_eq!(
+            store.apply_key_succession(&second, true).unwrap(),
+            SuccessionOutcome::Applied { identity: pks[1] }
+        );
+        assert_eq!(
+            store.apply_key_succession(&first, false).unwrap(),
+            SuccessionOutcome::Applied { identity: pks[0] }
+        );
+        assert_eq!(
+            store.apply_key_succession(&first, false).unwrap(),
+            SuccessionOutcome::Duplicate
+        );
+
+        for pk in &pks[..3] {
+            assert_eq!(store.author_identity(pk).unwrap(), pks[0].to_vec());
+        }
+        assert_eq!(store.author_identity(&pks[3]).unwrap(), pks[3].to_vec());
+        assert_eq!(
+            store.get_key_successions(&pks[0]).unwrap(),
+            vec![first.clone(), second.clone()]
+        );
+
+        // Forks and loops are rejected
+        let fork = KeySuccession::new(&keys[1], &keys[3], None).unwrap();
+        let cycle = KeySuccession::new(&keys[2], &keys[0], None).unwrap();
+        for succession in [&fork, &cycle] {
+            assert!(matches!(
+                store.apply_key_succession(succession, false).unwrap(),
+                SuccessionOutcome::Rejected(_)
+            ));
+        }
+
+        assert!(!store.is_key_revoked_at(&pks[0], i64::MAX).unwrap());
+        assert!(!store.is_key_revoked_at(&pks[1], 4_999).unwrap());
+        assert!(store.is_key_revoked_at(&pks[1], 5_000).unwrap());
+        assert_eq!(store.revoked_keys().unwrap(), vec![(pks[1], 5_000)]);
+        assert_eq!(store.pending_successions().unwrap(), vec![second.clone()]);
+        store.mark_succession_broadcast(&second.id()).unwrap();
+        assert!(store.pending_successions().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_poe_score_credits_difference() {
+        let store = Store::new(":memory:").unwrap();
+        let (grain_id, author) = ([1u8; 32], [2u8; 32]);
+        let mut record = PoeScoreRecord {
+            grain_id,
+            author_pk: author.to_vec(),
+            novelty: 0.8,
+            coherence: 0.2,
+            reuse: 0.0,
+            total: 0.38,
+            ngt_reward: 4.8,
+            credited: 0.0,
+            epoch: 0,
+            calculated_at: 0,
+        };
+
+        assert_eq!(store.save_poe_score(&record).unwrap(), 4.8);
+
+        // Reuse raised the reward: only the difference is paid
+        record.reuse = 0.5;
+        record.ngt_reward = 6.3;
+        let paid = store.save_poe_score(&record).unwrap();
+        assert!((paid - 1.5).abs() < 1e-9);
+        assert_eq!(store.save_poe_score(&record).unwrap(), 0.0);
+
+        let saved = store.get_poe_score(&grain_id).unwrap().unwrap();
+        assert_eq!(saved.epoch, 2);
+        assert!((saved.credited - 6.3).abs() < 1e-9);
+        assert!((store.get_node_ngt(&author).unwrap() - 6.3).abs() < 1e-9);
+        assert_eq!(store.list_poe_scores(10, 0.5).unwrap().len(), 0);
+
+        store.record_grain_access(&grain_id, "peer1", "search").unwrap();
+        assert_eq!(store.get_grains_to_rescore(10).unwrap(), vec![grain_id]);
+        assert_eq!(store.get_grain_access_stats(&grain_id).unwrap(), (1, 1));
+    }
+
+    #[test]
+    fn test_private_grains_open_after_grant() {
+        let store = Store::new(":memory:").unwrap();
+        let author = generate_signing_key();
+        let member = RecipientSecret::from_node_secret(&generate_signing_key().to_bytes());
+        let outsider = RecipientSecret::from_node_secret(&generate_signing_key().to_bytes());
+
+        let meta = GrainMeta {
+            author_pk: author.verifying_key().to_bytes().to_vec(),
+            crypto_backend: synapsenet_core::CryptoBackend::Classical,
+            ts_unix_ms: 1234567890,
+            tags: vec![],
+            mime: "text/plain".to_string(),
+            lang: "en".to_string(),
+            title: Some("Team notes".to_string()),
+            summary: None,
+            embedding_model: None,
+            embedding_dimensions: Some(3),
+        };
+        let grain = Grain::new(vec![0.1, 0.2, 0.3], meta, &author).unwrap();
+        let group_key = GroupKey::generate();
+        let private = PrivateGrain::seal(&grain, &group_key, &author).unwrap();
+        let grant = GroupKeyGrant::new(&group_key, &member.public_keys(), &author).unwrap();
+
+        // Ciphertext arrives before the grant: kept for relaying only
+        assert_eq!(
+            store.apply_private_grain(&private, &member, false).unwrap(),
+            PrivateGrainOutcome::Relayed
+        );
+        assert_eq!(
+            store.apply_private_grain(&private, &member, false).unwrap(),
+            PrivateGrainOutcome::Duplicate
+        );
+        assert!(store.get_grain(&grain.id).unwrap().is_none());
+
+        // A grant for someone else does not join the group
+        let other = GroupKeyGrant::new(&group_key, &outsider.public_keys(), &author).unwrap();
+        assert_eq!(
+            store.apply_group_grant(&other, &member, false).unwrap(),
+            GrantOutcome::Relayed
+        );
+
+        assert_eq!(
+            store.apply_group_grant(&grant, &member, true).unwrap(),
+            GrantOutcome::Joined {
+                group_id: group_key.group_id(),
+                opened: 1
+            }
+        );
+        assert!(store.private_grain_ids().unwrap().contains(&grain.id));
+        let opened = store.get_private_grains(&group_key.group_id()).unwrap();
+        assert_eq!(opened.len(), 1);
+        assert_eq!(opened[0].vec, grain.vec);
+
+        // Decrypted grains stay out of every public read
+        assert!(store.get_grain(&grain.id).unwrap().is_none());
+        assert!(store.get_all_grains().unwrap().is_empty());
+        assert_eq!(store.count_grains().unwrap(), 0);
+        assert_eq!(store.list_groups().unwrap().len(), 1);
+        assert_eq!(store.pending_group_grants().unwrap(), vec![grant.clone()]);
+        store.mark_group_grant_broadcast(&grant.id()).unwrap();
+        assert!(store.pending_group_grants().unwrap().is_empty());
+
+        // Group members open new grains as they arrive
+        let named = GroupKey::generate();
+        assert!(store.insert_group_key("team", &named, &member).unwrap());
+        assert!(!store.insert_group_key("team", &named, &member).unwrap());
+        let sealed = PrivateGrain::seal(&grain, &named, &author).unwrap();
+        assert_eq!(
+            store.apply_private_grain(&sealed, &member, true).unwrap(),
+            PrivateGrainOutcome::Opened { grain_id: grain.id }
+        );
+        assert_eq!(store.pending_private_grains().unwrap(), vec![sealed]);
+        assert_eq!(
+            store.find_group_key("team", &member).unwrap().unwrap().group_id(),
+            named.group_id()
+        );
+
+        // Group keys are sealed at rest and only the node secret opens them
+        let stored: Vec<u8> = store
+            .conn
+            .query_row(
+                "SELECT group_key FROM private_groups WHERE name = 'team'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_ne!(&stored[..], &named.as_bytes()[..]);
+        assert!(store.find_group_key("team", &outsider).is_err());
+    }
+
+    #[test]
+    fn test_grain_provenance() {
+        let store = Store::new(":memory:").unwrap();
+        let goal_id = Uuid::new_v4();
+
+        let provenance = GrainProvenance {
+            grain_id: [7u8; 32],
+            goal_id,
+            episode_id: Some(Uuid::new_v4()),
+            tool: "grain_add".to_string(),
+            user_id: "agent".to_string(),
+            created_at: 1234,
+        };
+        store.record_provenance(&provenance).unwrap();
+
+        assert_eq!(store.get_provenance(&[7u8; 32]).unwrap(), Some(provenance));
+        assert_eq!(store.get_provenance(&[8u8; 32]).unwrap(), None);
+        assert_eq!(store.grains_created_by_goal(&goal_id).unwrap(), vec![[7u8; 32]]);
+    }
+
+    #[test]
+    fn test_web_cache() {
+        let store = Store::new(":memory:").unwrap();
+
+        let mut entry = WebCacheEntry {
+            url: "https://example.com/a".to_string(),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            content_hash: "abc".to_string(),
+            content_type: Some("text/html".to_string()),
+            body: "<p>hello</p>".to_string(),
+            grain_ids: vec![],
+            fetched_at: 1,
+        };
+        store.put_web_cache(&entry).unwrap();
+        assert_eq!(store.get_web_cache(&entry.url).unwrap(), Some(entry.clone()));
+        assert!(store.grains_for_content("abc").unwrap().is_empty());
+
+        // Ingesting the same content under another URL is found by hash
+        entry.url = "https://mirror.example.com/a".to_string();
+        entry.grain_ids = vec![[1u8; 32], [2u8; 32]];
+        entry.fetched_at = 2;
+        store.put_web_cache(&entry).unwrap();
+        assert_eq!(
+            store.grains_for_content("abc").unwrap(),
+            vec![[1u8; 32], [2u8; 32]]
+        );
+        assert_eq!(store.get_web_cache("https://example.com/b").unwrap(), None);
+    }
+
+    #[test]
+    fn test_curator_workflow() {
+        let store = Store::new(":memory:").unwrap();
+        let alice = generate_signing_key();
+        let bob = generate_signing_key();
+        let alice_pk = alice.verifying_key().to_bytes();
+
+        assert!(store.add_curator(&alice_pk, Some("alice")).unwrap());
+        assert!(!store.add_curator(&alice_pk, None).unwrap());
+        assert!(store.is_curator(&alice_pk).unwrap());
+
+        let task = store
+            .insert_curator_task(&NewCuratorTask {
+                route: "query".to_string(),
+                subject: "sensitive question".to_string(),
+                requester: Some("client-1".to_string()),
+                classification: serde_json::json!({ "class": "curated" }),
+                outcome: Some(serde_json::json!([{ "grain_id": "ab" }])),
+                release_token_hash: Some([9u8; 32]),
+            })
+            .unwrap();
+        assert_eq!(store.get_curator_task(&task.id).unwrap(), Some(task.clone()));
+
+        // Unregistered keys cannot act
+        let claim = CuratorDecision::new(task.id, CuratorAction::Claim, None, &bob);
+        assert_eq!(store.apply_curator_decision(&claim).unwrap(), DecisionOutcome::NotCurator);
+
+        let claim = CuratorDecision::new(task.id, CuratorAction::Claim, None, &alice);
+        let DecisionOutcome::Applied(claimed) = store.apply_curator_decision(&claim).unwrap() else {
+            panic!("claim not applied");
+        };
+        assert_eq!(claimed.status, CuratorTaskStatus::InReview);
+        assert_eq!(
+            store.list_curator_tasks(Some(CuratorTaskStatus::InReview), 10).unwrap().len(),
+            1
+        );
+
+        // Another curator cannot decide a claimed task
+        store.add_curator(&bob.verifying_key().to_bytes(), None).unwrap();
+        let reject = CuratorDecision::new(task.id, CuratorAction::Reject, None, &bob);
+        assert!(matches!(
+            store.apply_curator_decision(&reject).unwrap(),
+            DecisionOutcome::Conflict(_)
+        ));
+
+        let approve = CuratorDecision::new(
+            task.id,
+            CuratorAction::Approve,
+            Some("fine in context".to_string()),
+            &alice,
+        );
+        store.apply_curator_decision(&approve).unwrap();
+        let decided = store.get_curator_task(&task.id).unwrap().unwrap();
+        assert_eq!(decided.status, CuratorTaskStatus::Approved);
+        assert_eq!(decided.note.as_deref(), Some("fine in context"));
+        assert!(decided.decided_at.is_some());
+        assert!(matches!(
+            store.apply_curator_decision(&approve).unwrap(),
+            DecisionOutcome::Conflict(_)
+        ));
+
+        let audit = store.curator_audit(Some(&task.id), 10).unwrap();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[1].action, CuratorAction::Approve);
+        assert_eq!(audit[1].sig, approve.sig);
+        assert_eq!(
+            store.apply_curator_decision(&CuratorDecision::new(
+                Uuid::new_v4(),
+                CuratorAction::Claim,
+                None,
+                &alice
+            ))
+            .unwrap(),
+            DecisionOutcome::NotFound
+        );
+    }
+
+    #[test]
+    fn test_grain_flags_and_appeals() {
+        use synapsenet_core::FlagReason;
+
+        let store = Store::new(":memory:").unwrap();
+        let author = generate_signing_key();
+        let flagger = generate_signing_key();
+
+        let meta = GrainMeta {
+            author_pk: author.verifying_key().to_bytes().to_vec(),
+            crypto_backend: synapsenet_core::CryptoBackend::Classical,
+            ts_unix_ms: 1234567890,
+            tags: vec![],
+            mime: "text/plain".to_string(),
+            lang: "en".to_string(),
+            title: Some("Cheap pills".to_string()),
+            summary: None,
+            embedding_model: None,
+            embedding_dimensions: None,
+        };
+        let grain = Grain::new(vec![0.1, 0.2, 0.3], meta, &author).unwrap();
+        store.insert_grain(&grain).unwrap();
+
+        let mut flag = GrainFlag::new(
+            grain.id,
+            FlagAction::Flag(FlagReason::Spam),
+            None,
+            &flagger,
+        );
+        flag.ts_unix_ms -= 10;
+        flag.sig = Vec::new(); // signatures are checked before the store
+        assert_eq!(store.apply_grain_flag(&flag, true).unwrap(), FlagOutcome::Recorded);
+        assert_eq!(store.apply_grain_flag(&flag, true).unwrap(), FlagOutcome::Duplicate);
+        assert_eq!(store.active_grain_flags(&grain.id, None).unwrap(), vec![flag.clone()]);
+        // A release covers flags received before it, whatever their date
+        assert!(store
+            .active_grain_flags(&grain.id, Some(unix_now_millis().unwrap()))
+            .unwrap()
+            .is_empty());
+        assert_eq!(store.pending_grain_flags().unwrap(), vec![flag.clone()]);
+        store.mark_grain_flag_broadcast(&flag.id()).unwrap();
+        assert!(store.pending_grain_flags().unwrap().is_empty());
+
+        // Flags dated in the future are refused
+        let mut future = GrainFlag::new(grain.id, FlagAction::Unflag, None, &flagger);
+        future.ts_unix_ms += 2 * MAX_FLAG_CLOCK_SKEW_MS;
+        assert!(matches!(
+            store.apply_grain_flag(&future, false).unwrap(),
+            FlagOutcome::Rejected(_)
+        ));
+
+        // Flags on grains we do not hold expire
+        let orphan = GrainFlag::new(
+            [7u8; 32],
+            FlagAction::Flag(FlagReason::Spam),
+            None,
+            &flagger,
+        );
+        assert_eq!(store.apply_grain_flag(&orphan, false).unwrap(), FlagOutcome::Recorded);
+        assert_eq!(store.prune_orphan_grain_flags(i64::MAX).unwrap(), 1);
+        assert!(store.grain_flags(&[7u8; 32]).unwrap().is_empty());
+        assert_eq!(store.grain_flags(&grain.id).unwrap().len(), 1);
+
+        // Only the author can appeal
+        let appeal = GrainFlag::new(grain.id, FlagAction::Appeal, None, &flagger);
+        assert!(matches!(
+            store.apply_grain_flag(&appeal, false).unwrap(),
+            FlagOutcome::Rejected(_)
+        ));
+        let appeal = GrainFlag::new(grain.id, FlagAction::Appeal, None, &author);
+        store.apply_grain_flag(&appeal, false).unwrap();
+        assert_eq!(store.latest_grain_appeal(&grain.id, None).unwrap(), Some(appeal));
+
+        // The flagger's later unflag withdraws the flag
+        let unflag = GrainFlag::new(grain.id, FlagAction::Unflag, None, &flagger);
+        store.apply_grain_flag(&unflag, false).unwrap();
+        assert!(store.active_grain_flags(&grain.id, None).unwrap().is_empty());
+
+        let moderation = GrainModeration {
+            grain_id: grain.id,
+            status: ModerationStatus::Quarantined,
+            flag_weight: 3.0,
+            cleared_at: None,
+            appeal_dismissed_at: None,
+            updated_at: 1,
+        };
+        store.put_grain_moderation(&moderation).unwrap();
+        assert_eq!(store.get_grain_moderation(&grain.id).unwrap(), Some(moderation));
+        assert!(store.quarantined_grain_ids().unwrap().contains(&grain.id));
+        assert_eq!(
+            store
+                .list_grain_moderation(Some(ModerationStatus::Cleared), 10)
+                .unwrap()
+                .len(),
+            0
+        );
     }
 }
@@ -0,0 +1,37 @@
+//! Cache of pages fetched by the `web_fetch` tool
+//!
+//! Each URL keeps its HTTP validators (ETag / Last-Modified) and last body,
+//! so an unchanged page is revalidated with a conditional GET instead of
+//! downloaded again. Pages ingested into memory also record their grains,
+//! keyed by content hash, so the same content is never stored twice.
+
+/// Last fetch of a URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebCacheEntry {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Hash of the page's cleaned text (hex)
+    pub content_hash: String,
+    pub content_type: Option<String>,
+    pub body: String,
+    /// Grains the content was ingested as, in chunk order
+    pub grain_ids: Vec<[u8; 32]>,
+    pub fetched_at: i64,
+}
+
+pub const CREATE_WEB_CACHE_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS web_cache (
+    url TEXT PRIMARY KEY,
+    etag TEXT,
+    last_modified TEXT,
+    content_hash TEXT NOT NULL,
+    content_type TEXT,
+    body TEXT NOT NULL,
+    grain_ids BLOB NOT NULL,
+    fetched_at INTEGER NOT NULL
+)
+"#;
+
+pub const CREATE_WEB_CACHE_INDEXES: &[&str] =
+    &["CREATE INDEX IF NOT EXISTS idx_web_cache_content ON web_cache(content_hash)"];
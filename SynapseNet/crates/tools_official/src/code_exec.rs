@@ -3,9 +3,6 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use synapsenet_agent::*;
-use std::process::Stdio;
-use tokio::io::AsyncWriteExt;
-use tokio::process::Command;
 
 /// Supported programming languages
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +29,14 @@ impl Language {
             Self::Rust => "rustc",
         }
     }
+
+    /// Flag that makes the interpreter evaluate its next argument
+    fn eval_flag(&self) -> &str {
+        match self {
+            Self::JavaScript => "-e",
+            Self::Python | Self::Rust => "-c",
+        }
+    }
 }
 
 /// Code execution configuration
@@ -40,6 +45,9 @@ pub struct CodeExecConfig {
     pub timeout_secs: u64,
     pub max_output_bytes: usize,
     pub allowed_languages: Vec<String>,
+    /// OS confinement for the interpreter process
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
 }
 
 impl Default for CodeExecConfig {
@@ -48,6 +56,11 @@ impl Default for CodeExecConfig {
             timeout_secs: 10,
             max_output_bytes: 1024 * 1024, // 1MB
             allowed_languages: vec!["python".to_string(), "javascript".to_string()],
+            sandbox: SandboxConfig {
+                cpu_limit_ms: 5_000,
+                memory_limit_mb: 256,
+                ..SandboxConfig::default()
+            },
         }
     }
 }
@@ -55,47 +68,42 @@ impl Default for CodeExecConfig {
 /// Code execution tool
 pub struct CodeExecTool {
     config: CodeExecConfig,
+    sandbox: Sandbox,
 }
 
 impl CodeExecTool {
     pub fn new(config: CodeExecConfig) -> Self {
-        Self { config }
+        let sandbox = Sandbox::new(config.sandbox.clone());
+        Self { config, sandbox }
     }
 
     pub fn with_defaults() -> Self {
         Self::new(CodeExecConfig::default())
     }
 
-    async fn execute_code(&self, language: Language, code: &str) -> Result<String, ToolError> {
-        let mut child = Command::new(language.command())
-            .arg("-c")
+    async fn execute_code(
+        &self,
+        language: Language,
+        code: &str,
+    ) -> Result<SandboxResult<CommandOutput>, ToolError> {
+        let command = SandboxCommand::new(language.command())
+            .arg(language.eval_flag())
             .arg(code)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to spawn process: {}", e)))?;
-
-        // Wait with timeout
-        let timeout = tokio::time::Duration::from_secs(self.config.timeout_secs);
-        let output = tokio::time::timeout(timeout, child.wait_with_output())
-            .await
-            .map_err(|_| ToolError::Timeout)?
-            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+            .timeout(std::time::Duration::from_secs(self.config.timeout_secs))
+            .max_output_bytes(self.config.max_output_bytes);
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let result = self.sandbox.run_command(command).await?;
 
-        if !output.status.success() {
-            return Err(ToolError::ExecutionFailed(stderr.to_string()));
+        if !result.value.success() {
+            return Err(ToolError::ExecutionFailed(result.value.stderr));
         }
 
         // Check output size
-        if stdout.len() > self.config.max_output_bytes {
+        if result.value.truncated {
             return Err(ToolError::ResourceLimitExceeded);
         }
 
-        Ok(stdout.to_string())
+        Ok(result)
     }
 }
 
@@ -106,7 +114,7 @@ impl Tool for CodeExecTool {
     }
 
     fn description(&self) -> &'static str {
-        "Execute code in isolated sandbox (Python, JavaScript)"
+        "Execute code in an OS-isolated sandbox (Python, JavaScript)"
     }
 
     fn schema(&self) -> ToolSchema {
@@ -156,21 +164,21 @@ impl Tool for CodeExecTool {
             return Err(ToolError::PermissionDenied);
         }
 
-        let output = self.execute_code(language, code).await?;
+        let result = self.execute_code(language, code).await?;
 
         let execution_time_ms = start.elapsed().as_millis() as u64;
 
         Ok(ToolOutput {
             result: serde_json::json!({
                 "language": language_str,
-                "output": output,
+                "output": result.value.stdout,
                 "code_length": code.len(),
             }),
             metadata: ToolMetadata {
                 execution_time_ms,
                 resources_used: ResourceUsage {
-                    cpu_ms: execution_time_ms,
-                    memory_mb: 10,
+                    cpu_ms: result.cpu_time_ms,
+                    memory_mb: result.memory_used_mb,
                     network_bytes: 0,
                 },
                 success: true,
@@ -180,7 +188,7 @@ impl Tool for CodeExecTool {
     }
 
     fn validate_input(&self, input: &ToolInput) -> Result<(), ToolError> {
-        let language = input.params.get("language")
+        input.params.get("language")
             .and_then(|v| v.as_str())
             .ok_or_else(|| ToolError::InvalidInput("Missing 'language' parameter".to_string()))?;
 
@@ -256,31 +264,83 @@ mod tests {
         assert!(tool.validate_input(&input).is_err());
     }
 
-    #[tokio::test]
-    async fn test_python_execution() {
-        let tool = CodeExecTool::with_defaults();
-        
-        let context = ExecutionContext {
-            goal_id: Uuid::new_v4(),
-            episode_id: None,
-            user_id: "test".to_string(),
-            timestamp: chrono::Utc::now().timestamp(),
-            metadata: HashMap::new(),
-        };
-        
-        let input = ToolInput {
+    fn sandboxed_tool(work: &std::path::Path) -> CodeExecTool {
+        let mut config = CodeExecConfig::default();
+        config.sandbox.file_access_path = work.to_path_buf();
+        CodeExecTool::new(config)
+    }
+
+    fn python_input(code: &str) -> ToolInput {
+        ToolInput {
             params: serde_json::json!({
                 "language": "python",
-                "code": "print(2 + 2)"
+                "code": code
             }),
-            context,
-        };
-        
-        let result = tool.execute(input).await;
-        // May fail if python3 not installed - that's ok for test
-        if result.is_ok() {
-            let output = result.unwrap();
-            assert!(output.metadata.success);
+            context: ExecutionContext {
+                goal_id: Uuid::new_v4(),
+                episode_id: None,
+                user_id: "test".to_string(),
+                timestamp: chrono::Utc::now().timestamp(),
+                metadata: HashMap::new(),
+            },
         }
     }
+
+    /// Fail instead of passing vacuously without Landlock or python3
+    fn require_isolated_python() {
+        assert!(
+            Sandbox::isolation_available(),
+            "Landlock is unavailable; code execution needs Linux 5.13+ with Landlock enabled"
+        );
+        assert!(
+            std::process::Command::new("python3").arg("--version").output().is_ok(),
+            "python3 must be on PATH for this test"
+        );
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(target_os = "linux"), ignore = "process isolation needs Linux")]
+    async fn test_python_execution() {
+        require_isolated_python();
+        let work = tempfile::tempdir().unwrap();
+        let tool = sandboxed_tool(work.path());
+
+        let output = tool.execute(python_input("print(2 + 2)")).await.unwrap();
+        assert!(output.metadata.success);
+        assert_eq!(output.result["output"], "4\n");
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(target_os = "linux"), ignore = "process isolation needs Linux")]
+    async fn test_python_escape_attempts() {
+        require_isolated_python();
+        let work = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("secret.txt");
+        std::fs::write(&secret, "secret").unwrap();
+        let tool = sandboxed_tool(work.path());
+
+        let attempts = [
+            format!("print(open({:?}).read())", secret.display().to_string()),
+            format!("open({:?}, 'w').write('x')", outside.path().join("x").display().to_string()),
+            "import socket; socket.create_connection(('127.0.0.1', 80))".to_string(),
+            "print(open('/etc/hostname').read())".to_string(),
+        ];
+        for code in attempts {
+            match tool.execute(python_input(&code)).await {
+                Err(ToolError::ExecutionFailed(stderr)) => {
+                    assert!(stderr.contains("PermissionError"), "{}: {}", code, stderr)
+                }
+                other => panic!("{} was not denied: {:?}", code, other),
+            }
+        }
+        assert!(!outside.path().join("x").exists());
+
+        // Work dir stays usable
+        let output = tool
+            .execute(python_input("open('notes.txt', 'w').write('ok'); print(open('notes.txt').read())"))
+            .await
+            .unwrap();
+        assert_eq!(output.result["output"], "ok\n");
+    }
 }
@@ -266,10 +266,8 @@ impl Tool for FileOpsTool {
             return Err(ToolError::InvalidInput("Path cannot be empty".to_string()));
         }
 
-        if operation == "write" {
-            if input.params.get("content").is_none() {
-                return Err(ToolError::InvalidInput("Missing 'content' for write operation".to_string()));
-            }
+        if operation == "write" && input.params.get("content").is_none() {
+            return Err(ToolError::InvalidInput("Missing 'content' for write operation".to_string()));
         }
 
         Ok(())
@@ -0,0 +1,894 @@
+//! Grain Memory Tools - Search, read, add and link grains in the node's memory
+//!
+//! All four tools share one `GrainMemory` over the node's `Store`, HNSW index
+//! and signing key. Grains and links are signed by the node; every grain an
+//! agent adds records the goal and episode that created it, and must pass
+//! the ingest policy. Private grains are never visible to agents.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use synapsenet_agent::*;
+use synapsenet_ai::EmbeddingModel;
+use synapsenet_core::{Grain, GrainMeta, Link, SigningKeyTrait, UnifiedSigningKey};
+use synapsenet_governance::PolicyEngine;
+use synapsenet_storage::{GrainProvenance, HnswIndex, Store};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Per-goal limits on memory access
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrainQuota {
+    /// Results returned by one search
+    pub max_results: usize,
+    pub max_searches_per_goal: u32,
+    pub max_grains_per_goal: u32,
+    pub max_links_per_goal: u32,
+    /// Text accepted for a single grain
+    pub max_text_bytes: usize,
+    /// Text a goal may write in total
+    pub max_bytes_per_goal: u64,
+}
+
+impl GrainQuota {
+    /// Quota that lets a goal write no more than its sandbox file budget
+    pub fn from_sandbox(config: &SandboxConfig) -> Self {
+        Self {
+            max_bytes_per_goal: config.max_file_size_mb * 1024 * 1024,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for GrainQuota {
+    fn default() -> Self {
+        Self {
+            max_results: 20,
+            max_searches_per_goal: 100,
+            max_grains_per_goal: 50,
+            max_links_per_goal: 100,
+            max_text_bytes: 8 * 1024,
+            max_bytes_per_goal: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// Memory use of one goal, or the cost of one call
+#[derive(Debug, Clone, Copy, Default)]
+struct GoalUsage {
+    searches: u32,
+    grains: u32,
+    links: u32,
+    bytes: u64,
+}
+
+impl GoalUsage {
+    fn plus(self, cost: Self) -> Self {
+        Self {
+            searches: self.searches.saturating_add(cost.searches),
+            grains: self.grains.saturating_add(cost.grains),
+            links: self.links.saturating_add(cost.links),
+            bytes: self.bytes.saturating_add(cost.bytes),
+        }
+    }
+
+    fn minus(self, cost: Self) -> Self {
+        Self {
+            searches: self.searches.saturating_sub(cost.searches),
+            grains: self.grains.saturating_sub(cost.grains),
+            links: self.links.saturating_sub(cost.links),
+            bytes: self.bytes.saturating_sub(cost.bytes),
+        }
+    }
+
+    fn within(&self, quota: &GrainQuota) -> bool {
+        self.searches <= quota.max_searches_per_goal
+            && self.grains <= quota.max_grains_per_goal
+            && self.links <= quota.max_links_per_goal
+            && self.bytes <= quota.max_bytes_per_goal
+    }
+}
+
+/// Shared backend of the grain tools
+pub struct GrainMemory {
+    store: Arc<Mutex<Store>>,
+    index: Arc<RwLock<HnswIndex<'static>>>,
+    embedding: Arc<dyn EmbeddingModel + Send + Sync>,
+    embedding_model: String,
+    signing_key: Arc<UnifiedSigningKey>,
+    quota: GrainQuota,
+    /// Ingest policy new grains must pass
+    policy: Option<Arc<PolicyEngine>>,
+    usage: Mutex<HashMap<Uuid, GoalUsage>>,
+}
+
+impl GrainMemory {
+    pub fn new(
+        store: Arc<Mutex<Store>>,
+        index: Arc<RwLock<HnswIndex<'static>>>,
+        embedding: Arc<dyn EmbeddingModel + Send + Sync>,
+        signing_key: Arc<UnifiedSigningKey>,
+    ) -> Self {
+        Self {
+            store,
+            index,
+            embedding,
+            embedding_model: "all-MiniLM-L6-v2".to_string(),
+            signing_key,
+            quota: GrainQuota::default(),
+            policy: None,
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set per-goal limits
+    pub fn with_quota(mut self, quota: GrainQuota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Refuse new grains the policy's ingest rules hold or block
+    pub fn with_policy(mut self, policy: Arc<PolicyEngine>) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Name of the embedding model recorded in new grains
+    pub fn with_embedding_model(mut self, name: &str) -> Self {
+        self.embedding_model = name.to_string();
+        self
+    }
+
+    /// `grain_search`, `grain_get`, `grain_add` and `grain_link` over this memory
+    pub fn tools(self: &Arc<Self>) -> Vec<Box<dyn Tool>> {
+        vec![
+            Box::new(GrainSearchTool {
+                memory: self.clone(),
+            }),
+            Box::new(GrainGetTool {
+                memory: self.clone(),
+            }),
+            Box::new(GrainAddTool {
+                memory: self.clone(),
+            }),
+            Box::new(GrainLinkTool {
+                memory: self.clone(),
+            }),
+        ]
+    }
+
+    /// Register all grain tools
+    pub fn register(self: &Arc<Self>, registry: &ToolRegistry) -> Result<(), String> {
+        for tool in self.tools() {
+            registry.register(tool)?;
+        }
+        Ok(())
+    }
+
+    /// Drop the usage counters of a finished goal
+    pub fn forget_goal(&self, goal_id: &Uuid) {
+        self.usage.lock().unwrap().remove(goal_id);
+    }
+
+    /// Count `cost` against the goal's quota, failing if it would be exceeded
+    ///
+    /// A call that fails is not counted.
+    fn charge(&self, goal_id: Uuid, cost: GoalUsage) -> Result<(), ToolError> {
+        let mut usage = self.usage.lock().unwrap();
+        let total = usage.get(&goal_id).copied().unwrap_or_default().plus(cost);
+        if !total.within(&self.quota) {
+            return Err(ToolError::ResourceLimitExceeded);
+        }
+        usage.insert(goal_id, total);
+        Ok(())
+    }
+
+    /// Give back a charge whose write failed
+    fn refund(&self, goal_id: Uuid, cost: GoalUsage) {
+        if let Some(usage) = self.usage.lock().unwrap().get_mut(&goal_id) {
+            *usage = usage.minus(cost);
+        }
+    }
+
+    /// Count `grains` new grains holding `bytes` of text against the goal's quota
+    pub(crate) fn charge_grains(
+        &self,
+        goal_id: Uuid,
+        grains: u32,
+        bytes: u64,
+    ) -> Result<(), ToolError> {
+        self.charge(goal_id, GoalUsage { grains, bytes, ..GoalUsage::default() })
+    }
+
+    /// Give back grains charged by `charge_grains` that were never stored
+    pub(crate) fn refund_grains(&self, goal_id: Uuid, grains: u32, bytes: u64) {
+        self.refund(goal_id, GoalUsage { grains, bytes, ..GoalUsage::default() });
+    }
+
+    /// Embed, sign, store and index a text grain written by `tool`
+    pub(crate) async fn write_grain(
+        &self,
+        context: &ExecutionContext,
+        tool: &str,
+        text: &str,
+        title: String,
+        tags: Vec<String>,
+    ) -> Result<Grain, ToolError> {
+        let grain = self.new_grain(text, title, tags)?;
+        self.store_grain(context, tool, &grain).await?;
+        Ok(grain)
+    }
+
+    /// Embed and sign a text grain without storing it
+    ///
+    /// Grains the ingest policy does not allow are refused with
+    /// `ToolError::PolicyDenied`.
+    pub(crate) fn new_grain(
+        &self,
+        text: &str,
+        title: String,
+        tags: Vec<String>,
+    ) -> Result<Grain, ToolError> {
+        let vec = self.embedding.embed(text).map_err(failed)?;
+        let signing_key = &self.signing_key;
+        let meta = GrainMeta {
+            author_pk: signing_key.public_key(),
+            crypto_backend: signing_key.backend(),
+            ts_unix_ms: chrono::Utc::now().timestamp_millis(),
+            tags,
+            mime: "text/plain".to_string(),
+            lang: "en".to_string(),
+            title: Some(title),
+            summary: Some(text.to_string()),
+            embedding_model: Some(self.embedding_model.clone()),
+            embedding_dimensions: Some(vec.len()),
+        };
+        let grain = Grain::new_with_unified_key(vec, meta, signing_key).map_err(failed)?;
+
+        if let Some(policy) = &self.policy {
+            let classification = policy.evaluate_grain(&grain);
+            if !classification.is_allowed() {
+                return Err(ToolError::PolicyDenied(classification.explanation));
+            }
+        }
+        Ok(grain)
+    }
+
+    /// Store and index a grain from `new_grain`, recording its provenance
+    pub(crate) async fn store_grain(
+        &self,
+        context: &ExecutionContext,
+        tool: &str,
+        grain: &Grain,
+    ) -> Result<(), ToolError> {
+        {
+            let store = self.store.lock().unwrap();
+            store.insert_grain(grain).map_err(failed)?;
+            store
+                .record_provenance(&GrainProvenance {
+                    grain_id: grain.id,
+                    goal_id: context.goal_id,
+                    episode_id: context.episode_id,
+                    tool: tool.to_string(),
+                    user_id: context.user_id.clone(),
+                    created_at: chrono::Utc::now().timestamp(),
+                })
+                .map_err(failed)?;
+        }
+        self.index.write().await.add(grain).map_err(failed)?;
+
+        tracing::info!(
+            "Agent goal {} added grain {}",
+            context.goal_id,
+            hex::encode(grain.id)
+        );
+        Ok(())
+    }
+
+    /// Node store the grain tools write to
+    pub(crate) fn store(&self) -> &Arc<Mutex<Store>> {
+        &self.store
+    }
+}
+
+/// Search memory for grains similar to a query
+pub struct GrainSearchTool {
+    memory: Arc<GrainMemory>,
+}
+
+/// Read one grain with its provenance
+pub struct GrainGetTool {
+    memory: Arc<GrainMemory>,
+}
+
+/// Add a signed grain to memory
+pub struct GrainAddTool {
+    memory: Arc<GrainMemory>,
+}
+
+/// Link two grains with a signed link
+pub struct GrainLinkTool {
+    memory: Arc<GrainMemory>,
+}
+
+#[async_trait]
+impl Tool for GrainSearchTool {
+    fn name(&self) -> &'static str {
+        "grain_search"
+    }
+
+    fn description(&self) -> &'static str {
+        "Semantic search over the node's grain memory"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema(
+            "grain_search",
+            "Search grains",
+            vec![
+                param("query", "Text to search for", "string", true, None),
+                param(
+                    "k",
+                    "Number of results",
+                    "number",
+                    false,
+                    Some(serde_json::json!(5)),
+                ),
+            ],
+            "Matching grains with similarity",
+        )
+    }
+
+    async fn execute(&self, input: ToolInput) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+        let query = required_str(&input, "query")?;
+        let k = input.params.get("k").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
+        let k = k.clamp(1, self.memory.quota.max_results);
+
+        let cost = GoalUsage { searches: 1, ..GoalUsage::default() };
+        self.memory.charge(input.context.goal_id, cost)?;
+
+        let query_vec = self.memory.embedding.embed(query).map_err(failed)?;
+        let hits = self
+            .memory
+            .index
+            .read()
+            .await
+            .search(&query_vec, k)
+            .map_err(failed)?;
+
+        let results = {
+            let store = self.memory.store.lock().unwrap();
+            let quarantined = store.quarantined_grain_ids().map_err(failed)?;
+            let mut results = Vec::new();
+            for hit in hits {
+                if let Ok(grain) = visible_grain(&store, &quarantined, &hit.grain_id) {
+                    let mut entry = grain_summary(&grain);
+                    entry["similarity"] = serde_json::json!(hit.similarity);
+                    results.push(entry);
+                }
+            }
+            results
+        };
+
+        Ok(output(serde_json::json!({ "results": results }), start))
+    }
+
+    fn validate_input(&self, input: &ToolInput) -> Result<(), ToolError> {
+        required_str(input, "query").map(|_| ())
+    }
+
+    fn end_goal(&self, goal_id: &Uuid) {
+        self.memory.forget_goal(goal_id);
+    }
+}
+
+#[async_trait]
+impl Tool for GrainGetTool {
+    fn name(&self) -> &'static str {
+        "grain_get"
+    }
+
+    fn description(&self) -> &'static str {
+        "Read a grain from the node's memory"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema(
+            "grain_get",
+            "Get grain",
+            vec![param("grain_id", "Grain ID (hex)", "string", true, None)],
+            "Grain metadata and provenance",
+        )
+    }
+
+    async fn execute(&self, input: ToolInput) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+        let grain_id = parse_grain_id(required_str(&input, "grain_id")?)?;
+
+        let store = self.memory.store.lock().unwrap();
+        let quarantined = store.quarantined_grain_ids().map_err(failed)?;
+        let grain = visible_grain(&store, &quarantined, &grain_id)?;
+
+        let mut result = grain_summary(&grain);
+        result["author_pk"] = serde_json::json!(hex::encode(&grain.meta.author_pk));
+        result["ts_unix_ms"] = serde_json::json!(grain.meta.ts_unix_ms);
+        result["embedding_model"] = serde_json::json!(grain.meta.embedding_model);
+        result["provenance"] = match store.get_provenance(&grain_id).map_err(failed)? {
+            Some(p) => serde_json::json!({
+                "goal_id": p.goal_id,
+                "episode_id": p.episode_id,
+                "tool": p.tool,
+            }),
+            None => serde_json::Value::Null,
+        };
+
+        Ok(output(result, start))
+    }
+
+    fn validate_input(&self, input: &ToolInput) -> Result<(), ToolError> {
+        parse_grain_id(required_str(input, "grain_id")?).map(|_| ())
+    }
+}
+
+#[async_trait]
+impl Tool for GrainAddTool {
+    fn name(&self) -> &'static str {
+        "grain_add"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add a grain signed by this node to its memory"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema(
+            "grain_add",
+            "Add grain",
+            vec![
+                param("text", "Grain content", "string", true, None),
+                param("title", "Short title", "string", false, None),
+                param("tags", "Tags", "array", false, None),
+            ],
+            "ID of the new grain",
+        )
+    }
+
+    async fn execute(&self, input: ToolInput) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+        self.validate_input(&input)?;
+        let text = required_str(&input, "text")?;
+        let context = &input.context;
+
+        self.memory
+            .charge_grains(context.goal_id, 1, text.len() as u64)?;
+
+        let tags = input
+            .params
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let title = match input.params.get("title").and_then(|v| v.as_str()) {
+            Some(title) => title.to_string(),
+            None => text.chars().take(50).collect(),
+        };
+
+        let grain = match self.memory.write_grain(context, self.name(), text, title, tags).await {
+            Ok(grain) => grain,
+            Err(e) => {
+                self.memory.refund_grains(context.goal_id, 1, text.len() as u64);
+                return Err(e);
+            }
+        };
+
+        Ok(output(
+            serde_json::json!({ "grain_id": hex::encode(grain.id) }),
+            start,
+        ))
+    }
+
+    fn validate_input(&self, input: &ToolInput) -> Result<(), ToolError> {
+        let text = required_str(input, "text")?;
+        if text.trim().is_empty() {
+            return Err(ToolError::InvalidInput("Text cannot be empty".to_string()));
+        }
+        if text.len() > self.memory.quota.max_text_bytes {
+            return Err(ToolError::InvalidInput(format!(
+                "Text exceeds {} bytes",
+                self.memory.quota.max_text_bytes
+            )));
+        }
+        Ok(())
+    }
+
+    fn end_goal(&self, goal_id: &Uuid) {
+        self.memory.forget_goal(goal_id);
+    }
+}
+
+#[async_trait]
+impl Tool for GrainLinkTool {
+    fn name(&self) -> &'static str {
+        "grain_link"
+    }
+
+    fn description(&self) -> &'static str {
+        "Link two grains with a link signed by this node"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema(
+            "grain_link",
+            "Link grains",
+            vec![
+                param("from", "Source grain ID (hex)", "string", true, None),
+                param("to", "Target grain ID (hex)", "string", true, None),
+                param(
+                    "weight",
+                    "Link strength (0.0..1.0)",
+                    "number",
+                    false,
+                    Some(serde_json::json!(0.5)),
+                ),
+                param(
+                    "rationale",
+                    "Why the grains are related",
+                    "string",
+                    false,
+                    None,
+                ),
+            ],
+            "The created link",
+        )
+    }
+
+    async fn execute(&self, input: ToolInput) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+        self.validate_input(&input)?;
+        let from = parse_grain_id(required_str(&input, "from")?)?;
+        let to = parse_grain_id(required_str(&input, "to")?)?;
+        let weight = input
+            .params
+            .get("weight")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.5) as f32;
+        let rationale = input
+            .params
+            .get("rationale")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        let store = self.memory.store.lock().unwrap();
+        let quarantined = store.quarantined_grain_ids().map_err(failed)?;
+        visible_grain(&store, &quarantined, &from)?;
+        visible_grain(&store, &quarantined, &to)?;
+
+        let goal_id = input.context.goal_id;
+        let cost = GoalUsage { links: 1, ..GoalUsage::default() };
+        self.memory.charge(goal_id, cost)?;
+
+        let written =
+            Link::new_with_unified_key(from, to, weight, rationale, &self.memory.signing_key)
+                .map_err(failed)
+                .and_then(|link| {
+                    store.insert_link(&link).map_err(failed)?;
+                    Ok(link)
+                });
+        let link = match written {
+            Ok(link) => link,
+            Err(e) => {
+                self.memory.refund(goal_id, cost);
+                return Err(e);
+            }
+        };
+
+        Ok(output(
+            serde_json::json!({
+                "from": hex::encode(link.from),
+                "to": hex::encode(link.to),
+                "weight": link.weight,
+                "rationale": link.rationale,
+            }),
+            start,
+        ))
+    }
+
+    fn validate_input(&self, input: &ToolInput) -> Result<(), ToolError> {
+        let from = parse_grain_id(required_str(input, "from")?)?;
+        let to = parse_grain_id(required_str(input, "to")?)?;
+        if from == to {
+            return Err(ToolError::InvalidInput(
+                "Cannot link a grain to itself".to_string(),
+            ));
+        }
+        if let Some(weight) = input.params.get("weight") {
+            match weight.as_f64() {
+                Some(w) if (0.0..=1.0).contains(&w) => {}
+                _ => {
+                    return Err(ToolError::InvalidInput(
+                        "Weight must be between 0 and 1".to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn end_goal(&self, goal_id: &Uuid) {
+        self.memory.forget_goal(goal_id);
+    }
+}
+
+fn param(
+    name: &str,
+    description: &str,
+    param_type: &str,
+    required: bool,
+    default: Option<serde_json::Value>,
+) -> ToolParameter {
+    ToolParameter {
+        name: name.to_string(),
+        description: description.to_string(),
+        param_type: param_type.to_string(),
+        required,
+        default,
+    }
+}
+
+fn schema(
+    name: &str,
+    description: &str,
+    parameters: Vec<ToolParameter>,
+    returns: &str,
+) -> ToolSchema {
+    ToolSchema {
+        name: name.to_string(),
+        description: description.to_string(),
+        version: "1.0.0".to_string(),
+        parameters,
+        returns: ToolReturn {
+            return_type: "object".to_string(),
+            description: returns.to_string(),
+        },
+    }
+}
+
+fn required_str<'a>(input: &'a ToolInput, name: &str) -> Result<&'a str, ToolError> {
+    input
+        .params
+        .get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidInput(format!("Missing '{}' parameter", name)))
+}
+
+fn parse_grain_id(hex_id: &str) -> Result<[u8; 32], ToolError> {
+    hex::decode(hex_id)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| ToolError::InvalidInput(format!("Invalid grain id: {}", hex_id)))
+}
+
+fn failed(e: impl std::fmt::Display) -> ToolError {
+    ToolError::ExecutionFailed(e.to_string())
+}
+
+/// Get a grain agents may see: not private (the store never returns those),
+/// not among the `quarantined` IDs loaded once per tool call
+fn visible_grain(
+    store: &Store,
+    quarantined: &HashSet<[u8; 32]>,
+    id: &[u8; 32],
+) -> Result<Grain, ToolError> {
+    match store.get_grain(id).map_err(failed)? {
+        Some(grain) if !quarantined.contains(id) => Ok(grain),
+        _ => Err(ToolError::ExecutionFailed(format!(
+            "Grain not found: {}",
+            hex::encode(id)
+        ))),
+    }
+}
+
+fn grain_summary(grain: &Grain) -> serde_json::Value {
+    serde_json::json!({
+        "grain_id": hex::encode(grain.id),
+        "title": grain.meta.title,
+        "text": grain.meta.summary,
+        "tags": grain.meta.tags,
+    })
+}
+
+fn output(result: serde_json::Value, start: std::time::Instant) -> ToolOutput {
+    let execution_time_ms = start.elapsed().as_millis() as u64;
+    ToolOutput {
+        result,
+        metadata: ToolMetadata {
+            execution_time_ms,
+            resources_used: ResourceUsage {
+                cpu_ms: execution_time_ms,
+                memory_mb: 0,
+                network_bytes: 0,
+            },
+            success: true,
+            error: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use synapsenet_ai::embed::DummyEmbedding;
+    use synapsenet_core::CryptoBackend;
+
+    fn memory(quota: GrainQuota) -> Arc<GrainMemory> {
+        Arc::new(
+            GrainMemory::new(
+                Arc::new(Mutex::new(Store::new(":memory:").unwrap())),
+                Arc::new(RwLock::new(HnswIndex::new(1000, 16))),
+                Arc::new(DummyEmbedding::new(16)),
+                Arc::new(UnifiedSigningKey::generate(CryptoBackend::Classical)),
+            )
+            .with_quota(quota),
+        )
+    }
+
+    fn input(goal_id: Uuid, params: serde_json::Value) -> ToolInput {
+        ToolInput {
+            params,
+            context: ExecutionContext {
+                goal_id,
+                episode_id: Some(Uuid::nil()),
+                user_id: "test".to_string(),
+                timestamp: chrono::Utc::now().timestamp(),
+                metadata: HashMap::new(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_search_get_link() {
+        let memory = memory(GrainQuota::default());
+        let registry = ToolRegistry::new();
+        memory.register(&registry).unwrap();
+        let tool = |name: &str| registry.get(name).unwrap();
+        let goal_id = Uuid::new_v4();
+
+        let mut ids = Vec::new();
+        for text in [
+            "Rust has no garbage collector",
+            "Grains are signed by their author",
+        ] {
+            let added = tool("grain_add")
+                .execute(input(
+                    goal_id,
+                    serde_json::json!({ "text": text, "tags": ["test"] }),
+                ))
+                .await
+                .unwrap();
+            ids.push(added.result["grain_id"].as_str().unwrap().to_string());
+        }
+
+        let found = tool("grain_search")
+            .execute(input(
+                goal_id,
+                serde_json::json!({ "query": "Rust has no garbage collector", "k": 2 }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(found.result["results"][0]["grain_id"], ids[0]);
+
+        let grain = tool("grain_get")
+            .execute(input(goal_id, serde_json::json!({ "grain_id": ids[1] })))
+            .await
+            .unwrap();
+        assert_eq!(grain.result["text"], "Grains are signed by their author");
+        assert_eq!(grain.result["provenance"]["goal_id"], goal_id.to_string());
+        assert_eq!(grain.result["provenance"]["tool"], "grain_add");
+
+        tool("grain_link")
+            .execute(input(
+                goal_id,
+                serde_json::json!({ "from": ids[0], "to": ids[1], "rationale": "both facts" }),
+            ))
+            .await
+            .unwrap();
+        let store = memory.store.lock().unwrap();
+        assert_eq!(store.grains_created_by_goal(&goal_id).unwrap().len(), 2);
+        let links = store.get_all_links().unwrap();
+        assert_eq!(links.len(), 1);
+        assert!(links[0]
+            .verify(&memory.signing_key.public_key().try_into().unwrap())
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_quota_per_goal() {
+        let memory = memory(GrainQuota {
+            max_grains_per_goal: 1,
+            ..GrainQuota::default()
+        });
+        let add = GrainAddTool {
+            memory: memory.clone(),
+        };
+        let goal_id = Uuid::new_v4();
+
+        add.execute(input(goal_id, serde_json::json!({ "text": "first" })))
+            .await
+            .unwrap();
+        let second = add
+            .execute(input(goal_id, serde_json::json!({ "text": "second" })))
+            .await;
+        assert!(matches!(second, Err(ToolError::ResourceLimitExceeded)));
+
+        // Other goals have their own budget
+        assert!(add
+            .execute(input(
+                Uuid::new_v4(),
+                serde_json::json!({ "text": "other" })
+            ))
+            .await
+            .is_ok());
+
+        let too_long = "x".repeat(GrainQuota::default().max_text_bytes + 1);
+        assert!(add
+            .validate_input(&input(goal_id, serde_json::json!({ "text": too_long })))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_writes_are_not_charged() {
+        use synapsenet_governance::{Policy, PolicyClass, PolicyRule, Route, RuleMatcher};
+
+        let policy = PolicyEngine::new(Policy {
+            default: PolicyClass::Ok,
+            rules: vec![PolicyRule {
+                id: "no-secrets".to_string(),
+                priority: 0,
+                class: PolicyClass::Blocked,
+                reason: None,
+                routes: vec![Route::Ingest],
+                matcher: RuleMatcher::Keywords {
+                    keywords: vec!["secret".to_string()],
+                },
+            }],
+        });
+        let memory = Arc::new(
+            GrainMemory::new(
+                Arc::new(Mutex::new(Store::new(":memory:").unwrap())),
+                Arc::new(RwLock::new(HnswIndex::new(1000, 16))),
+                Arc::new(DummyEmbedding::new(16)),
+                Arc::new(UnifiedSigningKey::generate(CryptoBackend::Classical)),
+            )
+            .with_quota(GrainQuota {
+                max_grains_per_goal: 1,
+                max_bytes_per_goal: 16,
+                ..GrainQuota::default()
+            })
+            .with_policy(Arc::new(policy)),
+        );
+        let add = GrainAddTool {
+            memory: memory.clone(),
+        };
+        let goal_id = Uuid::new_v4();
+        let text = |text: &str| input(goal_id, serde_json::json!({ "text": text }));
+
+        let over = add.execute(text("more than sixteen bytes")).await;
+        assert!(matches!(over, Err(ToolError::ResourceLimitExceeded)));
+        let denied = add.execute(text("the secret")).await;
+        assert!(matches!(denied, Err(ToolError::PolicyDenied(_))));
+
+        add.execute(text("kept")).await.unwrap();
+        let full = add.execute(text("again")).await;
+        assert!(matches!(full, Err(ToolError::ResourceLimitExceeded)));
+
+        // A finished goal's usage is dropped
+        add.end_goal(&goal_id);
+        assert!(memory.usage.lock().unwrap().is_empty());
+    }
+}
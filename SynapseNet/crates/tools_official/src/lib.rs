@@ -2,10 +2,14 @@
 
 pub mod code_exec;
 pub mod file_ops;
+pub mod grain_tools;
 pub mod math_eval;
 pub mod web_fetch;
 
 pub use code_exec::{CodeExecConfig, CodeExecTool, Language};
 pub use file_ops::{FileOpsConfig, FileOpsTool};
+pub use grain_tools::{
+    GrainAddTool, GrainGetTool, GrainLinkTool, GrainMemory, GrainQuota, GrainSearchTool,
+};
 pub use math_eval::MathEvalTool;
 pub use web_fetch::{WebFetchConfig, WebFetchTool};
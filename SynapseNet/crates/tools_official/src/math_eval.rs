@@ -1,7 +1,6 @@
 //! Math Evaluation Tool - Safe mathematical computations
 
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize};
 use synapsenet_agent::*;
 
 /// Math evaluation tool
@@ -1,9 +1,22 @@
 //! Web Fetch Tool - Safe HTTP requests with restrictions
+//!
+//! With a `GrainMemory` attached, GET responses are cached in the store and
+//! revalidated with ETag / Last-Modified, and pages can be ingested: the
+//! HTML is reduced to text, chunked and stored as grains tagged with their
+//! source URL and content hash. Content already ingested, from any URL,
+//! reuses its grains.
 
 use async_trait::async_trait;
+use reqwest::header::{CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use synapsenet_agent::*;
+use synapsenet_storage::WebCacheEntry;
+use std::sync::Arc;
 use std::time::Duration;
+use uuid::Uuid;
+
+use crate::grain_tools::GrainMemory;
 
 /// Web fetch tool configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +26,16 @@ pub struct WebFetchConfig {
     pub max_size_mb: u64,
     pub timeout_secs: u64,
     pub user_agent: String,
+    /// Ingest fetched pages as grains unless a call sets `ingest: false`
+    #[serde(default)]
+    pub ingest: bool,
+    /// Largest chunk of page text stored as one grain, in bytes
+    #[serde(default = "default_chunk_bytes")]
+    pub chunk_bytes: usize,
+}
+
+fn default_chunk_bytes() -> usize {
+    2000
 }
 
 impl Default for WebFetchConfig {
@@ -23,6 +46,8 @@ impl Default for WebFetchConfig {
             max_size_mb: 10,
             timeout_secs: 30,
             user_agent: "SynapseNet/0.7".to_string(),
+            ingest: false,
+            chunk_bytes: default_chunk_bytes(),
         }
     }
 }
@@ -31,6 +56,19 @@ impl Default for WebFetchConfig {
 pub struct WebFetchTool {
     config: WebFetchConfig,
     client: reqwest::Client,
+    memory: Option<Arc<GrainMemory>>,
+}
+
+/// Fetched page after the cache has been consulted
+struct Page {
+    status: u16,
+    headers: std::collections::HashMap<String, String>,
+    body: String,
+    content_type: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Served from the cache after a 304
+    cached: Option<WebCacheEntry>,
 }
 
 impl WebFetchTool {
@@ -42,7 +80,13 @@ impl WebFetchTool {
             .build()
             .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
-        Ok(Self { config, client })
+        Ok(Self { config, client, memory: None })
+    }
+
+    /// Cache GET responses in `memory`'s store and allow ingesting pages
+    pub fn with_memory(mut self, memory: Arc<GrainMemory>) -> Self {
+        self.memory = Some(memory);
+        self
     }
 
     /// Create with default configuration
@@ -75,6 +119,366 @@ impl WebFetchTool {
         // Check whitelist
         self.config.allowed_domains.iter().any(|d| host.contains(d))
     }
+
+    /// GET `url`, revalidating a cached copy when there is one
+    async fn get(&self, url: &str) -> Result<Page, ToolError> {
+        let cached = match &self.memory {
+            Some(memory) => memory.store().lock().unwrap().get_web_cache(url)
+                .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?,
+            None => None,
+        };
+
+        let mut request = self.client.get(url);
+        if let Some(entry) = &cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request.send().await
+            .map_err(|e| ToolError::NetworkError(e.to_string()))?;
+        self.read_response(response, cached).await
+    }
+
+    /// Read a response within the size limit; a 304 is answered from `cached`
+    async fn read_response(
+        &self,
+        response: reqwest::Response,
+        cached: Option<WebCacheEntry>,
+    ) -> Result<Page, ToolError> {
+        let status = response.status();
+        let headers: std::collections::HashMap<String, String> = response
+            .headers()
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+            .collect();
+        let header = |name: reqwest::header::HeaderName| {
+            response.headers().get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let content_type = header(CONTENT_TYPE);
+
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached {
+                return Ok(Page {
+                    status: status.as_u16(),
+                    headers,
+                    body: entry.body.clone(),
+                    content_type: entry.content_type.clone(),
+                    etag: etag.or_else(|| entry.etag.clone()),
+                    last_modified: last_modified.or_else(|| entry.last_modified.clone()),
+                    cached: Some(entry),
+                });
+            }
+        }
+
+        // Check content length
+        let content_length = response.content_length().unwrap_or(0);
+        if content_length > self.config.max_size_mb * 1024 * 1024 {
+            return Err(ToolError::ResourceLimitExceeded);
+        }
+
+        let body = response.text().await
+            .map_err(|e| ToolError::NetworkError(e.to_string()))?;
+
+        // Check actual body size
+        if body.len() > (self.config.max_size_mb * 1024 * 1024) as usize {
+            return Err(ToolError::ResourceLimitExceeded);
+        }
+
+        Ok(Page {
+            status: status.as_u16(),
+            headers,
+            body,
+            content_type,
+            etag,
+            last_modified,
+            cached: None,
+        })
+    }
+
+    /// Cache the page and, if asked, ingest its text as grains
+    async fn store_page(
+        &self,
+        memory: &GrainMemory,
+        url: &str,
+        page: &Page,
+        ingest: bool,
+        context: &ExecutionContext,
+    ) -> Result<StoredPage, ToolError> {
+        let failed = |e: anyhow::Error| ToolError::ExecutionFailed(e.to_string());
+        let is_html = page.content_type.as_deref().is_some_and(|t| t.contains("html"))
+            || looks_like_html(&page.body);
+        let text = if is_html { html_to_text(&page.body) } else { page.body.trim().to_string() };
+        let content_hash = blake3::hash(text.as_bytes()).to_hex().to_string();
+
+        // Grains this content was already ingested as, under this URL or another
+        let mut grain_ids = match &page.cached {
+            Some(entry) if entry.content_hash == content_hash => entry.grain_ids.clone(),
+            _ => Vec::new(),
+        };
+        let mut deduplicated = false;
+        if ingest && !text.is_empty() {
+            if grain_ids.is_empty() {
+                grain_ids = memory.store().lock().unwrap().grains_for_content(&content_hash)
+                    .map_err(failed)?;
+            }
+            if grain_ids.is_empty() {
+                grain_ids = self.ingest(memory, url, &page.body, &text, is_html, &content_hash, context).await?;
+            } else {
+                deduplicated = true;
+            }
+        }
+
+        memory.store().lock().unwrap().put_web_cache(&WebCacheEntry {
+            url: url.to_string(),
+            etag: page.etag.clone(),
+            last_modified: page.last_modified.clone(),
+            content_hash: content_hash.clone(),
+            content_type: page.content_type.clone(),
+            body: page.body.clone(),
+            grain_ids: grain_ids.clone(),
+            fetched_at: chrono::Utc::now().timestamp(),
+        }).map_err(failed)?;
+
+        Ok(StoredPage { content_hash, grain_ids, deduplicated })
+    }
+
+    /// Store page text as one grain per chunk
+    #[allow(clippy::too_many_arguments)]
+    async fn ingest(
+        &self,
+        memory: &GrainMemory,
+        url: &str,
+        body: &str,
+        text: &str,
+        is_html: bool,
+        content_hash: &str,
+        context: &ExecutionContext,
+    ) -> Result<Vec<[u8; 32]>, ToolError> {
+        let chunks = chunk_text(text, self.config.chunk_bytes.max(1));
+        let goal_id = context.goal_id;
+        let chunk_bytes =
+            |chunks: &[String]| -> u64 { chunks.iter().map(|c| c.len() as u64).sum() };
+        memory.charge_grains(goal_id, chunks.len() as u32, chunk_bytes(&chunks))?;
+
+        let page_title = is_html
+            .then(|| html_title(body))
+            .flatten()
+            .unwrap_or_else(|| url.to_string());
+        let tags = vec![
+            "web".to_string(),
+            format!("source:{}", url),
+            format!("content_hash:{}", content_hash),
+        ];
+
+        // Every chunk must pass the ingest policy before any is stored
+        let mut grains = Vec::with_capacity(chunks.len());
+        for (i, chunk) in chunks.iter().enumerate() {
+            let title = if chunks.len() == 1 {
+                page_title.clone()
+            } else {
+                format!("{} ({}/{})", page_title, i + 1, chunks.len())
+            };
+            match memory.new_grain(chunk, title, tags.clone()) {
+                Ok(grain) => grains.push(grain),
+                Err(e) => {
+                    memory.refund_grains(goal_id, chunks.len() as u32, chunk_bytes(&chunks));
+                    return Err(e);
+                }
+            }
+        }
+
+        // Chunks already stored stay charged
+        let mut grain_ids = Vec::with_capacity(grains.len());
+        for grain in &grains {
+            if let Err(e) = memory.store_grain(context, "web_fetch", grain).await {
+                let unstored = &chunks[grain_ids.len()..];
+                memory.refund_grains(goal_id, unstored.len() as u32, chunk_bytes(unstored));
+                return Err(e);
+            }
+            grain_ids.push(grain.id);
+        }
+
+        tracing::info!("Ingested {} as {} grains", url, grain_ids.len());
+        Ok(grain_ids)
+    }
+}
+
+/// Cache outcome of a fetched page
+struct StoredPage {
+    content_hash: String,
+    grain_ids: Vec<[u8; 32]>,
+    deduplicated: bool,
+}
+
+fn looks_like_html(body: &str) -> bool {
+    let start = body.trim_start().get(..15).unwrap_or("").to_ascii_lowercase();
+    start.starts_with("<!doctype html") || start.starts_with("<html")
+}
+
+/// Text of the `<title>` element
+fn html_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = collapse_spaces(&decode_entities(&html[start..end]));
+    (!title.is_empty()).then_some(title)
+}
+
+/// Readable text of an HTML page, one block element per line
+fn html_to_text(html: &str) -> String {
+    const SKIPPED: &[&str] = &["script", "style", "noscript", "template", "svg", "head"];
+    const BLOCKS: &[&str] = &[
+        "p", "div", "br", "li", "ul", "ol", "tr", "table", "section", "article", "header",
+        "footer", "nav", "aside", "main", "blockquote", "pre", "h1", "h2", "h3", "h4", "h5",
+        "h6", "hr", "dt", "dd", "figcaption",
+    ];
+
+    let lower = html.to_ascii_lowercase();
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut pos = 0;
+
+    while let Some(offset) = html[pos..].find('<') {
+        let tag_start = pos + offset;
+        text.push_str(&html[pos..tag_start]);
+
+        if lower[tag_start..].starts_with("<!--") {
+            pos = lower[tag_start..].find("-->").map_or(html.len(), |end| tag_start + end + 3);
+            continue;
+        }
+
+        let Some(tag_len) = html[tag_start..].find('>') else {
+            pos = html.len();
+            break;
+        };
+        let tag_end = tag_start + tag_len + 1;
+        let name: String = lower[tag_start + 1..tag_end - 1]
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        let closing = lower[tag_start + 1..].starts_with('/');
+
+        pos = tag_end;
+        if !closing && SKIPPED.contains(&name.as_str()) {
+            let close = format!("</{}", name);
+            pos = lower[tag_end..].find(&close)
+                .and_then(|end| lower[tag_end + end..].find('>').map(|gt| tag_end + end + gt + 1))
+                .unwrap_or(html.len());
+        } else if BLOCKS.contains(&name.as_str()) {
+            text.push('\n');
+        } else {
+            text.push(' ');
+        }
+    }
+    if pos < html.len() {
+        text.push_str(&html[pos..]);
+    }
+
+    decode_entities(&text)
+        .lines()
+        .map(collapse_spaces)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn collapse_spaces(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decode the common named entities and numeric references
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let entity = rest[1..].find(';').filter(|end| *end <= 10).map(|end| &rest[1..=end]);
+        let value = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" | "#39" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity.strip_prefix('#').and_then(|code| {
+                match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => code.parse().ok(),
+                }
+                .and_then(char::from_u32)
+            }),
+        });
+
+        match (entity, value) {
+            (Some(entity), Some(c)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Split text into chunks of at most `max_bytes`, preferring line breaks,
+/// then spaces
+fn chunk_text(text: &str, max_bytes: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines() {
+        let mut line = line;
+        while !line.is_empty() {
+            let room = if current.is_empty() { max_bytes } else { max_bytes.saturating_sub(current.len() + 1) };
+            if line.len() <= room {
+                if !current.is_empty() {
+                    current.push('\n');
+                }
+                current.push_str(line);
+                break;
+            }
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+                continue;
+            }
+
+            // A single line longer than a chunk: cut at the last space that fits
+            let mut cut = max_bytes;
+            while !line.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            if !line[cut..].starts_with(' ') {
+                if let Some(space) = line[..cut].rfind(' ').filter(|s| *s > 0) {
+                    cut = space;
+                }
+            }
+            if cut == 0 {
+                cut = line.chars().next().map_or(line.len(), char::len_utf8);
+            }
+            chunks.push(line[..cut].trim_end().to_string());
+            line = line[cut..].trim_start();
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
 }
 
 #[async_trait]
@@ -107,6 +511,13 @@ impl Tool for WebFetchTool {
                     required: false,
                     default: Some(serde_json::json!("GET")),
                 },
+                ToolParameter {
+                    name: "ingest".to_string(),
+                    description: "Store the page text as grains".to_string(),
+                    param_type: "boolean".to_string(),
+                    required: false,
+                    default: Some(serde_json::json!(self.config.ingest)),
+                },
             ],
             returns: ToolReturn {
                 return_type: "object".to_string(),
@@ -132,50 +543,50 @@ impl Tool for WebFetchTool {
             return Err(ToolError::PermissionDenied);
         }
 
-        // Make request
-        let response = match method.to_uppercase().as_str() {
-            "GET" => self.client.get(url).send().await,
+        let page = match method.to_uppercase().as_str() {
+            "GET" => self.get(url).await?,
             "POST" => {
                 let body = input.params.get("body")
                     .cloned()
                     .unwrap_or(serde_json::Value::Null);
-                self.client.post(url).json(&body).send().await
+                let response = self.client.post(url).json(&body).send().await
+                    .map_err(|e| ToolError::NetworkError(e.to_string()))?;
+                self.read_response(response, None).await?
             }
             _ => return Err(ToolError::InvalidInput(format!("Unsupported method: {}", method))),
         };
 
-        let response = response
-            .map_err(|e| ToolError::NetworkError(e.to_string()))?;
-
-        let status = response.status().as_u16();
-        let headers: std::collections::HashMap<String, String> = response
-            .headers()
-            .iter()
-            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
-            .collect();
-
-        // Check content length
-        let content_length = response.content_length().unwrap_or(0);
-        if content_length > self.config.max_size_mb * 1024 * 1024 {
-            return Err(ToolError::ResourceLimitExceeded);
-        }
-
-        let body = response.text().await
-            .map_err(|e| ToolError::NetworkError(e.to_string()))?;
-
-        // Check actual body size
-        if body.len() > (self.config.max_size_mb * 1024 * 1024) as usize {
-            return Err(ToolError::ResourceLimitExceeded);
-        }
+        let ingest = input.params.get("ingest")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(self.config.ingest);
+        let fetched = (200..300).contains(&page.status) || page.cached.is_some();
+        let stored = match &self.memory {
+            Some(memory) if method.eq_ignore_ascii_case("GET") && fetched => {
+                Some(self.store_page(memory, url, &page, ingest, &input.context).await?)
+            }
+            _ => None,
+        };
 
         let execution_time_ms = start.elapsed().as_millis() as u64;
 
-        let result = serde_json::json!({
-            "status": status,
-            "headers": headers,
-            "body": body,
+        let body_len = page.body.len();
+        let network_bytes = if page.cached.is_some() { 0 } else { body_len as u64 };
+        let mut result = serde_json::json!({
+            "status": page.status,
+            "headers": page.headers,
+            "body": page.body,
             "url": url,
         });
+        if let Some(stored) = stored {
+            result["cached"] = serde_json::json!(page.cached.is_some());
+            result["content_hash"] = serde_json::json!(stored.content_hash);
+            result["grain_ids"] = serde_json::json!(stored
+                .grain_ids
+                .iter()
+                .map(hex::encode)
+                .collect::<Vec<_>>());
+            result["deduplicated"] = serde_json::json!(stored.deduplicated);
+        }
 
         Ok(ToolOutput {
             result,
@@ -183,8 +594,8 @@ impl Tool for WebFetchTool {
                 execution_time_ms,
                 resources_used: ResourceUsage {
                     cpu_ms: execution_time_ms,
-                    memory_mb: (body.len() / 1024 / 1024) as u64,
-                    network_bytes: body.len() as u64,
+                    memory_mb: (body_len / 1024 / 1024) as u64,
+                    network_bytes,
                 },
                 success: true,
                 error: None,
@@ -209,8 +620,18 @@ impl Tool for WebFetchTool {
         url::Url::parse(url)
             .map_err(|e| ToolError::InvalidInput(format!("Invalid URL: {}", e)))?;
 
+        if self.memory.is_none() && input.params.get("ingest").and_then(|v| v.as_bool()) == Some(true) {
+            return Err(ToolError::InvalidInput("No grain memory to ingest into".to_string()));
+        }
+
         Ok(())
     }
+
+    fn end_goal(&self, goal_id: &Uuid) {
+        if let Some(memory) = &self.memory {
+            memory.forget_goal(goal_id);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -274,4 +695,197 @@ mod tests {
         };
         assert!(tool.validate_input(&input).is_ok());
     }
+
+    #[test]
+    fn test_html_to_text() {
+        let html = r#"<!DOCTYPE html><html><head><title>Tea &amp; Biscuits</title>
+            <style>p { color: red }</style></head>
+            <body><!-- nav --><h1>Tea</h1><p>Green&nbsp;tea is <b>steamed</b>.</p>
+            <script>alert("x")</script><ul><li>Sencha</li><li>Matcha &#x2014; powdered</li></ul>
+            </body></html>"#;
+
+        assert!(looks_like_html(html));
+        assert_eq!(html_title(html).as_deref(), Some("Tea & Biscuits"));
+        assert_eq!(
+            html_to_text(html),
+            "Tea\nGreen tea is steamed .\nSencha\nMatcha \u{2014} powdered"
+        );
+    }
+
+    #[test]
+    fn test_chunk_text() {
+        let chunks = chunk_text("one two three\nfour\nfive six seven eight nine", 14);
+        assert_eq!(chunks, vec!["one two three", "four", "five six seven", "eight nine"]);
+        assert!(chunks.iter().all(|c| c.len() <= 14));
+    }
+
+    mod ingest {
+        use super::*;
+        use crate::grain_tools::GrainMemory;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Mutex;
+        use synapsenet_ai::embed::DummyEmbedding;
+        use synapsenet_core::{CryptoBackend, UnifiedSigningKey};
+        use synapsenet_storage::{HnswIndex, Store};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        const PAGE: &str = "<html><head><title>Caching</title></head><body>\
+            <p>An ETag identifies one version of a resource.</p>\
+            <p>Clients send it back in If-None-Match.</p></body></html>";
+        const ETAG: &str = "\"v1\"";
+
+        /// Serve `PAGE` on every path, answering a matching If-None-Match
+        /// with 304; returns the base URL and the count of full responses
+        async fn serve_page() -> (String, Arc<AtomicUsize>) {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base = format!("http://{}", listener.local_addr().unwrap());
+            let full = Arc::new(AtomicUsize::new(0));
+            let served = full.clone();
+
+            tokio::spawn(async move {
+                loop {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    let served = served.clone();
+                    tokio::spawn(async move {
+                        let mut request = Vec::new();
+                        let mut buf = [0u8; 1024];
+                        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                            let n = socket.read(&mut buf).await.unwrap();
+                            if n == 0 {
+                                return;
+                            }
+                            request.extend_from_slice(&buf[..n]);
+                        }
+                        let request = String::from_utf8_lossy(&request).to_ascii_lowercase();
+                        let response = if request.contains(&format!("if-none-match: {}", ETAG)) {
+                            format!(
+                                "HTTP/1.1 304 Not Modified\r\nETag: {}\r\nConnection: close\r\n\r\n",
+                                ETAG
+                            )
+                        } else {
+                            served.fetch_add(1, Ordering::SeqCst);
+                            format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nETag: {}\r\n\
+                                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                                ETAG,
+                                PAGE.len(),
+                                PAGE
+                            )
+                        };
+                        socket.write_all(response.as_bytes()).await.unwrap();
+                        socket.shutdown().await.ok();
+                    });
+                }
+            });
+
+            (base, full)
+        }
+
+        fn input(url: &str) -> ToolInput {
+            ToolInput {
+                params: serde_json::json!({ "url": url }),
+                context: ExecutionContext {
+                    goal_id: Uuid::new_v4(),
+                    episode_id: None,
+                    user_id: "test".to_string(),
+                    timestamp: chrono::Utc::now().timestamp(),
+                    metadata: HashMap::new(),
+                },
+            }
+        }
+
+        #[tokio::test]
+        async fn test_conditional_get_and_dedup() {
+            let (base, full) = serve_page().await;
+            let store = Arc::new(Mutex::new(Store::new(":memory:").unwrap()));
+            let memory = Arc::new(GrainMemory::new(
+                store.clone(),
+                Arc::new(tokio::sync::RwLock::new(HnswIndex::new(1000, 16))),
+                Arc::new(DummyEmbedding::new(16)),
+                Arc::new(UnifiedSigningKey::generate(CryptoBackend::Classical)),
+            ));
+            let config = WebFetchConfig {
+                blocked_domains: vec![],
+                ingest: true,
+                chunk_bytes: 50,
+                ..Default::default()
+            };
+            let tool = WebFetchTool::new(config).unwrap().with_memory(memory);
+            let url = format!("{}/page", base);
+
+            let first = tool.execute(input(&url)).await.unwrap().result;
+            assert_eq!(first["status"], 200);
+            assert_eq!(first["cached"], false);
+            assert_eq!(first["deduplicated"], false);
+            let grain_ids = first["grain_ids"].as_array().unwrap().clone();
+            assert_eq!(grain_ids.len(), 2);
+
+            // Unchanged page: revalidated, served from the cache, nothing new stored
+            let second = tool.execute(input(&url)).await.unwrap().result;
+            assert_eq!(second["status"], 304);
+            assert_eq!(second["cached"], true);
+            assert_eq!(second["body"], PAGE);
+            assert_eq!(second["grain_ids"], first["grain_ids"]);
+            assert_eq!(full.load(Ordering::SeqCst), 1);
+
+            // Same content under another URL reuses the grains
+            let mirror = format!("{}/mirror", base);
+            let third = tool.execute(input(&mirror)).await.unwrap().result;
+            assert_eq!(third["status"], 200);
+            assert_eq!(third["deduplicated"], true);
+            assert_eq!(third["grain_ids"], first["grain_ids"]);
+
+            let store = store.lock().unwrap();
+            let grains = store.get_all_grains().unwrap();
+            assert_eq!(grains.len(), 2);
+            let meta = &grains[0].meta;
+            assert!(meta.tags.contains(&format!("source:{}", url)));
+            assert!(meta.tags.contains(&format!("content_hash:{}", first["content_hash"].as_str().unwrap())));
+            assert!(meta.title.as_deref().unwrap().starts_with("Caching ("));
+            assert_eq!(store.get_web_cache(&mirror).unwrap().unwrap().grain_ids.len(), 2);
+        }
+
+        #[tokio::test]
+        async fn test_ingest_policy_blocks_page() {
+            use synapsenet_governance::{Policy, PolicyClass, PolicyEngine, PolicyRule, Route, RuleMatcher};
+
+            let (base, _) = serve_page().await;
+            let store = Arc::new(Mutex::new(Store::new(":memory:").unwrap()));
+            let policy = PolicyEngine::new(Policy {
+                default: PolicyClass::Ok,
+                rules: vec![PolicyRule {
+                    id: "no-clients".to_string(),
+                    priority: 0,
+                    class: PolicyClass::Blocked,
+                    reason: None,
+                    routes: vec![Route::Ingest],
+                    matcher: RuleMatcher::Keywords {
+                        keywords: vec!["clients".to_string()],
+                    },
+                }],
+            });
+            let memory = GrainMemory::new(
+                store.clone(),
+                Arc::new(tokio::sync::RwLock::new(HnswIndex::new(1000, 16))),
+                Arc::new(DummyEmbedding::new(16)),
+                Arc::new(UnifiedSigningKey::generate(CryptoBackend::Classical)),
+            )
+            .with_policy(Arc::new(policy));
+            let config = WebFetchConfig {
+                blocked_domains: vec![],
+                ingest: true,
+                chunk_bytes: 50,
+                ..Default::default()
+            };
+            let tool = WebFetchTool::new(config).unwrap().with_memory(Arc::new(memory));
+            let url = format!("{}/page", base);
+
+            // Only the second chunk matches, and nothing of the page is stored
+            let result = tool.execute(input(&url)).await;
+            assert!(matches!(result, Err(ToolError::PolicyDenied(_))));
+            let store = store.lock().unwrap();
+            assert!(store.get_all_grains().unwrap().is_empty());
+            assert!(store.get_web_cache(&url).unwrap().is_none());
+        }
+    }
 }
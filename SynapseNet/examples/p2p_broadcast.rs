@@ -50,6 +50,7 @@ async fn async_main() -> Result<()> {
         port,
         enable_mdns: true,
         bootstrap_peers: Vec::new(),
+        ..P2pConfig::default()
     };
 
     // Create swarm
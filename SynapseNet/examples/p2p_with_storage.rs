@@ -2,7 +2,7 @@
 //
 // This example demonstrates:
 // - P2P swarm with grain storage
-// - Automatic storage of received grains
+// - Automatic storage and PoE scoring of received grains
 // - Database and index integration
 //
 // Run multiple instances to see grain propagation and storage:
@@ -16,9 +16,10 @@ use ed25519_dalek::SigningKey;
 use rand::{RngCore, rngs::OsRng};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use synapsenet_core::{Grain, GrainMeta};
+use synapsenet_core::{CryptoBackend, EconomyConfig, Grain, GrainMeta};
+use synapsenet_economy::PoEv2Engine;
 use synapsenet_p2p::{P2pConfig, SynapseSwarm};
-use synapsenet_storage::Store;
+use synapsenet_storage::{HnswIndex, Store};
 use tracing::{info, Level};
 
 fn main() -> Result<()> {
@@ -58,11 +59,24 @@ async fn async_main() -> Result<()> {
     
     info!("Database opened: {:?}", db_path);
 
+    // PoE engine stores, indexes and scores every ingested grain
+    let mut index = HnswIndex::new(1000, 384);
+    for grain in store.lock().unwrap().get_all_grains()? {
+        index.add(&grain)?;
+    }
+    let index = Arc::new(tokio::sync::RwLock::new(index));
+    let poe = Arc::new(PoEv2Engine::new(
+        Arc::clone(&store),
+        index,
+        EconomyConfig::default(),
+    )?);
+
     // Create P2P configuration
     let config = P2pConfig {
         port,
         enable_mdns: true,
         bootstrap_peers: Vec::new(),
+        ..P2pConfig::default()
     };
 
     // Create swarm
@@ -72,10 +86,14 @@ async fn async_main() -> Result<()> {
     info!("Local peer ID: {}", swarm.local_peer_id());
 
     // Set up grain storage callback
-    let store_clone = Arc::clone(&store);
+    let poe_clone = Arc::clone(&poe);
     swarm.set_grain_callback(move |grain| {
-        let store = store_clone.lock().unwrap();
-        store.insert_grain(&grain)?;
+        let poe = Arc::clone(&poe_clone);
+        tokio::spawn(async move {
+            if let Err(e) = poe.ingest(&grain).await {
+                tracing::warn!("Failed to ingest grain: {}", e);
+            }
+        });
         Ok(())
     });
     
@@ -91,13 +109,16 @@ async fn async_main() -> Result<()> {
     let author_pk = signing_key.verifying_key().to_bytes();
 
     let meta = GrainMeta {
-        author_pk,
+        author_pk: author_pk.to_vec(),
+        crypto_backend: CryptoBackend::Classical,
         ts_unix_ms: chrono::Utc::now().timestamp_millis(),
         tags: vec!["p2p".to_string(), "storage".to_string()],
         mime: "text/plain".to_string(),
         lang: "en".to_string(),
         title: Some(format!("Test grain from port {}", port)),
         summary: Some("P2P storage integration test".to_string()),
+        embedding_model: None,
+        embedding_dimensions: Some(384),
     };
 
     let vec = vec![0.1 * port as f32; 384];
@@ -109,8 +130,7 @@ async fn async_main() -> Result<()> {
             info!("✓ Grain broadcasted: {:?}", hex_encode(&grain.id[..8]));
             
             // Also store locally
-            let store = store.lock().unwrap();
-            store.insert_grain(&grain)?;
+            poe.ingest(&grain).await?;
             info!("✓ Grain stored locally");
         }
         Err(e) => {
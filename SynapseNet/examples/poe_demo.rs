@@ -3,11 +3,11 @@
 use synapsenet_core::ProofOfEmergence;
 use synapsenet_economy::NgtLedger;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     println!("=== Proof of Emergence Demo ===\n");
 
     let poe = ProofOfEmergence::default();
-    let mut ledger = NgtLedger::with_poe(poe);
+    let mut ledger = NgtLedger::with_poe(poe)?;
 
     println!("PoE Formula: NGT(g) = α * N(g) + β * C(g) + γ * log(1 + R(g))");
     println!(
@@ -30,20 +30,20 @@ fn main() {
     for (i, (desc, novelty, coherence, reuse)) in scenarios.iter().enumerate() {
         let node_pk = [i as u8; 32];
 
-        let ngt = ledger.award(node_pk, *novelty, *coherence, *reuse);
+        let ngt = ledger.award(node_pk, *novelty, *coherence, *reuse)?;
 
         println!("{}. {}", i + 1, desc);
         println!("   Novelty: {:.2}", novelty);
         println!("   Coherence: {:.2}", coherence);
         println!("   Reuse: {}", reuse);
         println!("   NGT Reward: {:.4}", ngt);
-        println!("   Balance: {:.4}\n", ledger.balance(&node_pk));
+        println!("   Balance: {:.4}\n", ledger.balance(&node_pk)?);
     }
 
-    println!("Total NGT Supply: {:.4}\n", ledger.total_supply());
+    println!("Total NGT Supply: {:.4}\n", ledger.total_supply()?);
 
     println!("Top holders:");
-    for (i, (pk, balance)) in ledger.top_holders(3).iter().enumerate() {
+    for (i, (pk, balance)) in ledger.top_holders(3)?.iter().enumerate() {
         println!("{}. Node {:?}... - {:.4} NGT", i + 1, &pk[..4], balance);
     }
 
@@ -53,4 +53,6 @@ fn main() {
     println!("- Spam (low both) = zero reward (anti-spam)");
     println!("- Reuse increases reward logarithmically");
     println!("- Balance = cumulative contributions");
+
+    Ok(())
 }
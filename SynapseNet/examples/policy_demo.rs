@@ -45,6 +45,9 @@ fn main() {
             PolicyClass::Curated => {
                 println!("⏳ Curator review required\n");
             }
+            PolicyClass::Blocked => {
+                println!("🚫 Blocked by policy\n");
+            }
         }
 
         println!("---\n");
@@ -55,4 +58,5 @@ fn main() {
     println!("- OK: Normal queries get full responses");
     println!("- AnalysisOnly: Harmful queries get consequences, not instructions");
     println!("- Curated: Sensitive queries require human review");
+    println!("- Blocked: Queries the node refuses outright");
 }
@@ -1,7 +1,7 @@
 //! PoE batch aggregation
 
+use poe_merkle::WEIGHT_SCALE;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 pub type NodeId = String;
@@ -13,6 +13,8 @@ pub struct PoeBatch {
     pub epoch: u64,
     pub chain_id: String,
     pub root: [u8; 32],
+    /// Sum of item weights in `WEIGHT_SCALE` units
+    pub total_weight: u128,
     pub items: Vec<PoeItem>,
     pub participants: Vec<NodeId>,
     pub signers: Vec<PubKey>,
@@ -21,6 +23,7 @@ pub struct PoeBatch {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PoeItem {
+    /// Payout address of the node
     pub node: NodeId,
     pub goal: Uuid,
     pub novelty: f32,
@@ -39,34 +42,44 @@ impl PoeItem {
         reuse: f32,
         weight: f32,
     ) -> Self {
-        let leaf_hash = Self::compute_leaf_hash(&node, &goal, novelty, coherence, reuse, weight);
-        Self {
+        let mut item = Self {
             node,
             goal,
             novelty,
             coherence,
             reuse,
             weight,
-            leaf_hash,
-        }
+            leaf_hash: [0u8; 32],
+        };
+        item.leaf_hash = poe_merkle::leaf_hash(
+            &item.node,
+            item.goal.as_bytes(),
+            &item.scores_bytes(),
+            item.weight_units(),
+        );
+        item
     }
 
-    fn compute_leaf_hash(
-        node: &str,
-        goal: &Uuid,
-        novelty: f32,
-        coherence: f32,
-        reuse: f32,
-        weight: f32,
-    ) -> [u8; 32] {
-        let mut hasher = Sha256::new();
-        hasher.update(node.as_bytes());
-        hasher.update(goal.as_bytes());
-        hasher.update(&novelty.to_le_bytes());
-        hasher.update(&coherence.to_le_bytes());
-        hasher.update(&reuse.to_le_bytes());
-        hasher.update(&weight.to_le_bytes());
-        hasher.finalize().into()
+    /// Score encoding committed in the leaf
+    pub fn scores_bytes(&self) -> [u8; 12] {
+        let mut scores = [0u8; 12];
+        scores[..4].copy_from_slice(&self.novelty.to_le_bytes());
+        scores[4..8].copy_from_slice(&self.coherence.to_le_bytes());
+        scores[8..].copy_from_slice(&self.reuse.to_le_bytes());
+        scores
+    }
+
+    /// Weight as the fixed-point integer the contract divides rewards by
+    pub fn weight_units(&self) -> u64 {
+        (self.weight.max(0.0) as f64 * WEIGHT_SCALE as f64).round() as u64
+    }
+}
+
+impl PoeBatch {
+    /// Inclusion proof of the item at `index` against `root`
+    pub fn proof(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        let leaves: Vec<_> = self.items.iter().map(|item| item.leaf_hash).collect();
+        poe_merkle::merkle_proof(&leaves, index)
     }
 }
 
@@ -81,12 +94,14 @@ impl BatchAggregator {
 
     pub fn create_batch(&self, epoch: u64, items: Vec<PoeItem>) -> PoeBatch {
         let root = self.compute_merkle_root(&items);
+        let total_weight = items.iter().map(|i| i.weight_units() as u128).sum();
         let participants: Vec<NodeId> = items.iter().map(|i| i.node.clone()).collect();
 
         PoeBatch {
             epoch,
             chain_id: self.chain_id.clone(),
             root,
+            total_weight,
             items,
             participants,
             signers: Vec::new(),
@@ -95,30 +110,8 @@ impl BatchAggregator {
     }
 
     fn compute_merkle_root(&self, items: &[PoeItem]) -> [u8; 32] {
-        if items.is_empty() {
-            return [0u8; 32];
-        }
-
-        let mut hashes: Vec<[u8; 32]> = items.iter().map(|item| item.leaf_hash).collect();
-
-        while hashes.len() > 1 {
-            let mut next_level = Vec::new();
-
-            for chunk in hashes.chunks(2) {
-                let mut hasher = Sha256::new();
-                hasher.update(&chunk[0]);
-                if chunk.len() > 1 {
-                    hasher.update(&chunk[1]);
-                } else {
-                    hasher.update(&chunk[0]);
-                }
-                next_level.push(hasher.finalize().into());
-            }
-
-            hashes = next_level;
-        }
-
-        hashes[0]
+        let leaves: Vec<_> = items.iter().map(|item| item.leaf_hash).collect();
+        poe_merkle::merkle_root(&leaves)
     }
 }
 
@@ -154,5 +147,25 @@ mod tests {
         let batch = aggregator.create_batch(1, items);
         assert_ne!(batch.root, [0u8; 32]);
         assert_eq!(batch.participants.len(), 2);
+        assert_eq!(batch.total_weight, 1_600_000);
+    }
+
+    #[test]
+    fn test_batch_proofs_verify() {
+        let aggregator = BatchAggregator::new("test-chain".to_string());
+        let items = (0..5)
+            .map(|i| PoeItem::new(format!("node{}", i), Uuid::new_v4(), 0.5, 0.5, 0.5, 1.0))
+            .collect();
+        let batch = aggregator.create_batch(1, items);
+
+        for (index, item) in batch.items.iter().enumerate() {
+            let proof = batch.proof(index).unwrap();
+            assert!(poe_merkle::verify_proof(
+                &item.leaf_hash,
+                index as u64,
+                &proof,
+                &batch.root
+            ));
+        }
     }
 }
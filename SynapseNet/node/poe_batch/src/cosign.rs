@@ -0,0 +1,486 @@
+//! Threshold co-signing of PoE batches
+//!
+//! Committee members announce their `PoeItem`s for an epoch. Once every
+//! member has announced (or the caller seals the epoch on a timeout), each
+//! member builds the batch from the union of announced items, ordered by
+//! leaf hash so everyone arrives at the same root, and signs only the root it
+//! computed itself. Signatures are gossiped until `min_signers` members agree,
+//! which yields a `PoeBatch` ready for `ChainSubmitter`.
+//!
+//! The protocol is transport agnostic: `handle` takes a received message and
+//! returns the messages to publish in response.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::aggregate::{BatchAggregator, PoeBatch, PoeItem};
+
+/// Domain separator for item announcements
+const ITEMS_SIGN_DOMAIN: &[u8] = b"synapsenet/poe-items/v1";
+
+/// Co-signing gossip message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CosignMessage {
+    /// A member's items for an epoch
+    Items {
+        epoch: u64,
+        signer: String,
+        items: Vec<PoeItem>,
+        /// Base64 ed25519 signature over the item leaf hashes
+        sig: String,
+    },
+    /// A member's signature over the batch it built
+    Signature {
+        epoch: u64,
+        signer: String,
+        root: [u8; 32],
+        total_weight: u128,
+        /// Base64 ed25519 signature over `poe_merkle::batch_sign_bytes`
+        sig: String,
+    },
+}
+
+impl CosignMessage {
+    pub fn epoch(&self) -> u64 {
+        match self {
+            CosignMessage::Items { epoch, .. } | CosignMessage::Signature { epoch, .. } => *epoch,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(self).map_err(|e| format!("Failed to encode cosign message: {}", e))
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(data).map_err(|e| format!("Invalid cosign message: {}", e))
+    }
+}
+
+/// Batch signers and the quorum they must reach
+///
+/// Member ids are the signer ids registered with the contract.
+#[derive(Debug, Clone)]
+pub struct Committee {
+    members: BTreeMap<String, VerifyingKey>,
+    min_signers: usize,
+}
+
+impl Committee {
+    pub fn new(members: BTreeMap<String, VerifyingKey>, min_signers: usize) -> Result<Self, String> {
+        if min_signers == 0 || min_signers > members.len() {
+            return Err(format!(
+                "min_signers {} must be between 1 and the committee size {}",
+                min_signers,
+                members.len()
+            ));
+        }
+        Ok(Self {
+            members,
+            min_signers,
+        })
+    }
+
+    fn verify(&self, signer: &str, message: &[u8], sig: &str) -> Result<(), String> {
+        let key = self
+            .members
+            .get(signer)
+            .ok_or_else(|| format!("{} is not a committee member", signer))?;
+        let sig = BASE64
+            .decode(sig)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or_else(|| format!("Malformed signature from {}", signer))?;
+        key.verify(message, &sig)
+            .map_err(|_| format!("Invalid signature from {}", signer))
+    }
+}
+
+#[derive(Default)]
+struct EpochSession {
+    /// Announced items keyed by leaf hash
+    items: BTreeMap<[u8; 32], PoeItem>,
+    announced: HashSet<String>,
+    /// Our own announcement, kept for rebroadcasts
+    own_items: Option<CosignMessage>,
+    /// Batch we built and signed
+    batch: Option<PoeBatch>,
+    /// Signatures by (root, total weight), then signer
+    sigs: HashMap<([u8; 32], u128), BTreeMap<String, String>>,
+}
+
+/// One committee member's view of the co-signing protocol
+pub struct Cosigner {
+    signer_id: String,
+    key: SigningKey,
+    chain_id: String,
+    aggregator: BatchAggregator,
+    committee: Committee,
+    epochs: BTreeMap<u64, EpochSession>,
+}
+
+impl Cosigner {
+    pub fn new(
+        signer_id: String,
+        key: SigningKey,
+        chain_id: String,
+        committee: Committee,
+    ) -> Result<Self, String> {
+        match committee.members.get(&signer_id) {
+            Some(member) if *member == key.verifying_key() => {}
+            _ => return Err(format!("{} is not in the committee with this key", signer_id)),
+        }
+
+        Ok(Self {
+            signer_id,
+            key,
+            aggregator: BatchAggregator::new(chain_id.clone()),
+            chain_id,
+            committee,
+            epochs: BTreeMap::new(),
+        })
+    }
+
+    /// Announce our items for `epoch`
+    pub fn announce(&mut self, epoch: u64, items: Vec<PoeItem>) -> Result<Vec<CosignMessage>, String> {
+        let leaves: Vec<_> = items.iter().map(|item| item.leaf_hash).collect();
+        let sig = self.sign(&self.items_sign_bytes(epoch, &leaves));
+        let message = CosignMessage::Items {
+            epoch,
+            signer: self.signer_id.clone(),
+            items,
+            sig,
+        };
+        self.session(epoch).own_items = Some(message.clone());
+
+        let mut out = vec![message.clone()];
+        out.extend(self.handle(message)?);
+        Ok(out)
+    }
+
+    /// Process a received message, returning messages to publish
+    pub fn handle(&mut self, message: CosignMessage) -> Result<Vec<CosignMessage>, String> {
+        match message {
+            CosignMessage::Items {
+                epoch,
+                signer,
+                items,
+                sig,
+            } => self.handle_items(epoch, signer, items, sig),
+            CosignMessage::Signature {
+                epoch,
+                signer,
+                root,
+                total_weight,
+                sig,
+            } => {
+                let message =
+                    poe_merkle::batch_sign_bytes(&self.chain_id, epoch, &root, total_weight);
+                self.committee.verify(&signer, &message, &sig)?;
+
+                self.session(epoch)
+                    .sigs
+                    .entry((root, total_weight))
+                    .or_default()
+                    .insert(signer, sig);
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    fn handle_items(
+        &mut self,
+        epoch: u64,
+        signer: String,
+        items: Vec<PoeItem>,
+        sig: String,
+    ) -> Result<Vec<CosignMessage>, String> {
+        // Recompute leaves rather than trusting the announced hashes
+        let items: Vec<_> = items
+            .into_iter()
+            .map(|i| PoeItem::new(i.node, i.goal, i.novelty, i.coherence, i.reuse, i.weight))
+            .collect();
+        let leaves: Vec<_> = items.iter().map(|item| item.leaf_hash).collect();
+        self.committee
+            .verify(&signer, &self.items_sign_bytes(epoch, &leaves), &sig)?;
+
+        let members = self.committee.members.len();
+        let session = self.session(epoch);
+        if session.batch.is_some() || !session.announced.insert(signer) {
+            return Ok(Vec::new());
+        }
+        for item in items {
+            session.items.insert(item.leaf_hash, item);
+        }
+
+        if session.announced.len() == members {
+            return Ok(self.seal(epoch).into_iter().collect());
+        }
+        Ok(Vec::new())
+    }
+
+    /// Build and sign the batch from the items announced so far
+    ///
+    /// Called automatically once every member has announced; call it on a
+    /// timeout to proceed without silent members. Later items are ignored.
+    pub fn seal(&mut self, epoch: u64) -> Option<CosignMessage> {
+        let session = self.epochs.get(&epoch)?;
+        if session.batch.is_some() || session.items.is_empty() {
+            return None;
+        }
+
+        let batch = self
+            .aggregator
+            .create_batch(epoch, session.items.values().cloned().collect());
+        let sig = self.sign(&poe_merkle::batch_sign_bytes(
+            &self.chain_id,
+            epoch,
+            &batch.root,
+            batch.total_weight,
+        ));
+        let message = CosignMessage::Signature {
+            epoch,
+            signer: self.signer_id.clone(),
+            root: batch.root,
+            total_weight: batch.total_weight,
+            sig: sig.clone(),
+        };
+
+        let signer_id = self.signer_id.clone();
+        let session = self.session(epoch);
+        session
+            .sigs
+            .entry((batch.root, batch.total_weight))
+            .or_default()
+            .insert(signer_id, sig);
+        session.batch = Some(batch);
+        Some(message)
+    }
+
+    /// Our batch for `epoch` with its signatures once a quorum agrees on it
+    pub fn signed_batch(&self, epoch: u64) -> Option<PoeBatch> {
+        let session = self.epochs.get(&epoch)?;
+        let batch = session.batch.as_ref()?;
+        let sigs = session.sigs.get(&(batch.root, batch.total_weight))?;
+        if sigs.len() < self.committee.min_signers {
+            return None;
+        }
+
+        let mut batch = batch.clone();
+        batch.signers = sigs.keys().cloned().collect();
+        batch.sigs = sigs.values().cloned().collect();
+        Some(batch)
+    }
+
+    /// Our messages for epochs still short of a quorum
+    ///
+    /// Publish these periodically so members that missed them catch up.
+    pub fn rebroadcast(&self) -> Vec<CosignMessage> {
+        let mut out = Vec::new();
+        for (epoch, session) in &self.epochs {
+            if self.signed_batch(*epoch).is_some() {
+                continue;
+            }
+            out.extend(session.own_items.clone());
+            if let Some(batch) = &session.batch {
+                let sig = &session.sigs[&(batch.root, batch.total_weight)][&self.signer_id];
+                out.push(CosignMessage::Signature {
+                    epoch: *epoch,
+                    signer: self.signer_id.clone(),
+                    root: batch.root,
+                    total_weight: batch.total_weight,
+                    sig: sig.clone(),
+                });
+            }
+        }
+        out
+    }
+
+    /// Drop state for epochs before `epoch`
+    pub fn prune(&mut self, epoch: u64) {
+        self.epochs = self.epochs.split_off(&epoch);
+    }
+
+    fn session(&mut self, epoch: u64) -> &mut EpochSession {
+        self.epochs.entry(epoch).or_default()
+    }
+
+    fn sign(&self, message: &[u8]) -> String {
+        BASE64.encode(self.key.sign(message).to_bytes())
+    }
+
+    fn items_sign_bytes(&self, epoch: u64, leaves: &[[u8; 32]]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(ITEMS_SIGN_DOMAIN);
+        hasher.update(self.chain_id.as_bytes());
+        hasher.update([0]);
+        hasher.update(epoch.to_be_bytes());
+        for leaf in leaves {
+            hasher.update(leaf);
+        }
+        hasher.finalize().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    const CHAIN_ID: &str = "test-chain";
+
+    fn committee(n: u8, min_signers: usize) -> (Vec<SigningKey>, Committee) {
+        let keys: Vec<_> = (1..=n).map(|i| SigningKey::from_bytes(&[i; 32])).collect();
+        let members = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (format!("s{}", i), key.verifying_key()))
+            .collect();
+        (keys, Committee::new(members, min_signers).unwrap())
+    }
+
+    fn cosigners(n: u8, min_signers: usize) -> Vec<Cosigner> {
+        let (keys, committee) = committee(n, min_signers);
+        keys.into_iter()
+            .enumerate()
+            .map(|(i, key)| {
+                Cosigner::new(format!("s{}", i), key, CHAIN_ID.to_string(), committee.clone())
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    fn item(node: &str, weight: f32) -> PoeItem {
+        PoeItem::new(node.to_string(), Uuid::new_v4(), 0.5, 0.5, 0.5, weight)
+    }
+
+    /// Deliver every message to every other node until nothing is left
+    fn gossip(nodes: &mut [Cosigner], mut queue: Vec<(usize, CosignMessage)>) {
+        while let Some((from, message)) = queue.pop() {
+            for (i, node) in nodes.iter_mut().enumerate() {
+                if i != from {
+                    for reply in node.handle(message.clone()).unwrap() {
+                        queue.push((i, reply));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_committee_reaches_quorum() {
+        let mut nodes = cosigners(4, 3);
+        let mut queue = Vec::new();
+        for (i, node) in nodes.iter_mut().enumerate() {
+            let items = vec![item(&format!("node{}", i), 1.0 + i as f32)];
+            for message in node.announce(1, items).unwrap() {
+                queue.push((i, message));
+            }
+        }
+        gossip(&mut nodes, queue);
+
+        let batches: Vec<_> = nodes.iter().map(|n| n.signed_batch(1).unwrap()).collect();
+        for batch in &batches {
+            assert_eq!(batch.root, batches[0].root);
+            assert_eq!(batch.items.len(), 4);
+            assert_eq!(batch.signers.len(), 4);
+        }
+
+        // Signatures are the ones the contract checks
+        let batch = &batches[0];
+        let message =
+            poe_merkle::batch_sign_bytes(CHAIN_ID, 1, &batch.root, batch.total_weight);
+        let (keys, _) = committee(4, 3);
+        for (signer, sig) in batch.signers.iter().zip(&batch.sigs) {
+            let index: usize = signer[1..].parse().unwrap();
+            let sig = Signature::from_slice(&BASE64.decode(sig).unwrap()).unwrap();
+            assert!(keys[index].verifying_key().verify(&message, &sig).is_ok());
+        }
+        assert!(nodes.iter().all(|n| n.rebroadcast().is_empty()));
+    }
+
+    #[test]
+    fn test_seal_without_silent_member() {
+        let mut nodes = cosigners(4, 3);
+        let mut queue = Vec::new();
+        for (i, node) in nodes.iter_mut().take(3).enumerate() {
+            for message in node.announce(7, vec![item("node", 1.0)]).unwrap() {
+                queue.push((i, message));
+            }
+        }
+        gossip(&mut nodes, queue);
+        assert!(nodes[0].signed_batch(7).is_none());
+
+        let queue = (0..3)
+            .map(|i| (i, nodes[i].seal(7).unwrap()))
+            .collect();
+        gossip(&mut nodes, queue);
+
+        let batch = nodes[0].signed_batch(7).unwrap();
+        assert_eq!(batch.signers, vec!["s0", "s1", "s2"]);
+        assert!(nodes[3].signed_batch(7).is_none());
+    }
+
+    #[test]
+    fn test_divergent_root_does_not_count() {
+        let mut nodes = cosigners(3, 2);
+        let shared = item("node1", 1.0);
+        nodes[0].announce(1, vec![shared.clone()]).unwrap();
+        nodes[0].seal(1).unwrap();
+
+        // Node 1 saw an extra item and signs a different root
+        nodes[1].announce(1, vec![shared, item("node2", 1.0)]).unwrap();
+        let other = nodes[1].seal(1).unwrap();
+        nodes[0].handle(other).unwrap();
+
+        assert!(nodes[0].signed_batch(1).is_none());
+        assert_eq!(nodes[0].rebroadcast().len(), 2);
+    }
+
+    #[test]
+    fn test_rejects_forged_messages() {
+        let mut nodes = cosigners(3, 2);
+        let outsider = SigningKey::from_bytes(&[9; 32]);
+
+        let items = vec![item("node1", 1.0)];
+        let leaves: Vec<_> = items.iter().map(|i| i.leaf_hash).collect();
+        let forged = CosignMessage::Items {
+            epoch: 1,
+            signer: "s1".to_string(),
+            items,
+            sig: BASE64.encode(
+                outsider
+                    .sign(&nodes[0].items_sign_bytes(1, &leaves))
+                    .to_bytes(),
+            ),
+        };
+        assert!(nodes[0].handle(forged).is_err());
+
+        let mut tampered = nodes[1].announce(1, vec![item("node1", 1.0)]).unwrap();
+        if let CosignMessage::Items { items, .. } = &mut tampered[0] {
+            items[0].weight = 100.0;
+        }
+        assert!(nodes[0].handle(tampered.remove(0)).is_err());
+
+        let unknown = CosignMessage::Signature {
+            epoch: 1,
+            signer: "s9".to_string(),
+            root: [0; 32],
+            total_weight: 1,
+            sig: String::new(),
+        };
+        assert!(nodes[0].handle(unknown).is_err());
+    }
+
+    #[test]
+    fn test_message_roundtrip() {
+        let mut nodes = cosigners(2, 1);
+        let message = nodes[0].announce(3, vec![item("node1", 0.5)]).unwrap().remove(0);
+        let decoded = CosignMessage::from_bytes(&message.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.epoch(), 3);
+        nodes[1].handle(decoded).unwrap();
+    }
+}
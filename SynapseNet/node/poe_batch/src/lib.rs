@@ -1,7 +1,9 @@
 //! PoE batch module
 
 pub mod aggregate;
+pub mod cosign;
 pub mod submit;
 
 pub use aggregate::{BatchAggregator, PoeBatch, PoeItem};
-pub use submit::ChainSubmitter;
+pub use cosign::{Committee, CosignMessage, Cosigner};
+pub use submit::{ChainSubmitter, SubmitterConfig};
@@ -1,57 +1,145 @@
 //! Submit PoE batch to chain
+//!
+//! Contract calls are wrapped in Cosmos SDK `MsgExecuteContract`
+//! transactions, signed in direct mode with the submitter key and broadcast
+//! over the CometBFT JSON-RPC endpoint at `rpc_url`.
+
+use std::time::{Duration, Instant};
 
 use crate::aggregate::PoeBatch;
-use serde_json::json;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use cosmrs::cosmwasm::MsgExecuteContract;
+use cosmrs::crypto::secp256k1::SigningKey;
+use cosmrs::proto::cosmos::auth::v1beta1::{BaseAccount, QueryAccountRequest, QueryAccountResponse};
+use cosmrs::proto::cosmos::tx::v1beta1::{SimulateRequest, SimulateResponse};
+use cosmrs::proto::cosmwasm::wasm::v1::{
+    QuerySmartContractStateRequest, QuerySmartContractStateResponse,
+};
+use cosmrs::tx::{Body, Fee, Msg, SignDoc, SignerInfo};
+use cosmrs::{AccountId, Any, Coin};
+use prost::Message;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
 
-pub struct ChainSubmitter {
-    rpc_url: String,
-    contract_address: String,
+const BASE_ACCOUNT_TYPE: &str = "/cosmos.auth.v1beta1.BaseAccount";
+
+/// Chain connection and fee settings
+#[derive(Debug, Clone)]
+pub struct SubmitterConfig {
+    /// CometBFT JSON-RPC endpoint
+    pub rpc_url: String,
+    pub contract_address: String,
+    pub chain_id: String,
+    /// Bech32 prefix of account addresses
+    pub account_prefix: String,
+    pub fee_denom: String,
+    /// Fee paid per unit of gas
+    pub gas_price: f64,
+    /// Multiplier applied to simulated gas
+    pub gas_adjustment: f64,
+    /// How long to wait for a broadcast tx to land in a block
+    pub confirm_timeout: Duration,
+    pub poll_interval: Duration,
 }
 
-impl ChainSubmitter {
-    pub fn new(rpc_url: String, contract_address: String) -> Self {
+impl SubmitterConfig {
+    pub fn new(rpc_url: String, contract_address: String, chain_id: String) -> Self {
         Self {
             rpc_url,
             contract_address,
+            chain_id,
+            account_prefix: "wasm".to_string(),
+            fee_denom: "ustake".to_string(),
+            gas_price: 0.025,
+            gas_adjustment: 1.3,
+            confirm_timeout: Duration::from_secs(30),
+            poll_interval: Duration::from_secs(1),
         }
     }
+}
+
+pub struct ChainSubmitter {
+    config: SubmitterConfig,
+    key: SigningKey,
+    sender: AccountId,
+    client: reqwest::Client,
+}
+
+impl ChainSubmitter {
+    pub fn new(config: SubmitterConfig, key: SigningKey) -> Result<Self, String> {
+        let sender = key
+            .public_key()
+            .account_id(&config.account_prefix)
+            .map_err(|e| format!("Failed to derive sender address: {}", e))?;
+
+        Ok(Self {
+            config,
+            key,
+            sender,
+            client: reqwest::Client::new(),
+        })
+    }
 
+    /// Address paying for and signing submitted transactions
+    pub fn sender(&self) -> &AccountId {
+        &self.sender
+    }
+
+    /// Post a fully signed batch, returning the confirmed tx hash
     pub async fn submit_batch(&self, batch: &PoeBatch) -> Result<String, String> {
-        // Convert root to base64
-        let root_b64 = base64::encode(&batch.root);
+        if batch.chain_id != self.config.chain_id {
+            return Err(format!(
+                "Batch was signed for chain {}, submitter targets {}",
+                batch.chain_id, self.config.chain_id
+            ));
+        }
 
         // Build execute message
         let execute_msg = json!({
             "submit_batch": {
                 "epoch": batch.epoch,
-                "root": root_b64,
-                "participants": batch.participants,
+                "root": BASE64.encode(batch.root),
+                "total_weight": batch.total_weight.to_string(),
                 "signers": batch.signers,
                 "sigs": batch.sigs,
             }
         });
 
-        // TODO: Sign and broadcast transaction
         tracing::info!("Submitting batch for epoch {}", batch.epoch);
         tracing::debug!("Execute msg: {}", execute_msg);
 
-        // Placeholder response
-        Ok("tx_hash_placeholder".to_string())
+        self.execute(&execute_msg).await
     }
 
-    pub async fn claim_reward(&self, node: &str) -> Result<String, String> {
+    /// Claim the reward of `batch.items[index]` with its Merkle proof
+    pub async fn claim_reward(&self, batch: &PoeBatch, index: usize) -> Result<String, String> {
+        let item = batch
+            .items
+            .get(index)
+            .ok_or_else(|| format!("No item {} in batch {}", index, batch.epoch))?;
+        let proof = batch
+            .proof(index)
+            .ok_or_else(|| format!("No proof for item {}", index))?;
+
         let execute_msg = json!({
             "claim_reward": {
-                "node": node
+                "epoch": batch.epoch,
+                "node": item.node,
+                "goal": BASE64.encode(item.goal.as_bytes()),
+                "scores": BASE64.encode(item.scores_bytes()),
+                "weight": item.weight_units().to_string(),
+                "index": index,
+                "proof": proof.iter().map(|hash| BASE64.encode(hash)).collect::<Vec<_>>(),
             }
         });
 
-        tracing::info!("Claiming reward for node: {}", node);
+        tracing::info!("Claiming reward for node: {}", item.node);
         tracing::debug!("Execute msg: {}", execute_msg);
 
-        Ok("tx_hash_placeholder".to_string())
+        self.execute(&execute_msg).await
     }
 
+    /// Total reward the contract has paid to `node`
     pub async fn query_accrual(&self, node: &str) -> Result<u128, String> {
         let query_msg = json!({
             "get_accrual": {
@@ -61,20 +149,221 @@ impl ChainSubmitter {
 
         tracing::debug!("Querying accrual for node: {}", node);
 
-        // TODO: Query contract
-        Ok(0)
+        let request = QuerySmartContractStateRequest {
+            address: self.config.contract_address.clone(),
+            query_data: query_msg.to_string().into_bytes(),
+        };
+        let value = self
+            .abci_query(
+                "/cosmwasm.wasm.v1.Query/SmartContractState",
+                request.encode_to_vec(),
+            )
+            .await?;
+        let response = QuerySmartContractStateResponse::decode(value.as_slice())
+            .map_err(|e| format!("Invalid contract query response: {}", e))?;
+
+        // Uint128 is serialized as a JSON string
+        let amount: String = serde_json::from_slice(&response.data)
+            .map_err(|e| format!("Invalid accrual response: {}", e))?;
+        amount
+            .parse()
+            .map_err(|e| format!("Invalid accrual amount {}: {}", amount, e))
     }
-}
 
-// Base64 encoding helper
-mod base64 {
-    pub fn encode(data: &[u8]) -> String {
-        use std::fmt::Write;
-        let mut result = String::new();
-        for byte in data {
-            write!(&mut result, "{:02x}", byte).unwrap();
+    /// Sign, broadcast and confirm a contract execution
+    async fn execute(&self, execute_msg: &Value) -> Result<String, String> {
+        let contract = self
+            .config
+            .contract_address
+            .parse()
+            .map_err(|e| format!("Invalid contract address: {}", e))?;
+        let msg = MsgExecuteContract {
+            sender: self.sender.clone(),
+            contract,
+            msg: execute_msg.to_string().into_bytes(),
+            funds: vec![],
+        }
+        .to_any()
+        .map_err(|e| format!("Failed to encode message: {}", e))?;
+
+        let (account_number, sequence) = self.account().await?;
+
+        let simulated = self
+            .simulate(self.sign_tx(msg.clone(), account_number, sequence, 0)?)
+            .await?;
+        let gas = (simulated as f64 * self.config.gas_adjustment).ceil() as u64;
+
+        let tx = self.sign_tx(msg, account_number, sequence, gas)?;
+        let hash = self.broadcast(&tx).await?;
+        self.wait_for_tx(&hash).await?;
+
+        tracing::info!("Transaction {} confirmed (gas {})", hash, gas);
+        Ok(hash)
+    }
+
+    fn sign_tx(
+        &self,
+        msg: Any,
+        account_number: u64,
+        sequence: u64,
+        gas: u64,
+    ) -> Result<Vec<u8>, String> {
+        let denom = self
+            .config
+            .fee_denom
+            .parse()
+            .map_err(|e| format!("Invalid fee denom: {}", e))?;
+        let amount = (gas as f64 * self.config.gas_price).ceil() as u128;
+        let fee = Fee::from_amount_and_gas(Coin { denom, amount }, gas);
+
+        let body = Body::new(vec![msg], "", 0u32);
+        let auth_info = SignerInfo::single_direct(Some(self.key.public_key()), sequence).auth_info(fee);
+        let chain_id = self
+            .config
+            .chain_id
+            .parse()
+            .map_err(|e| format!("Invalid chain id: {}", e))?;
+
+        SignDoc::new(&body, &auth_info, &chain_id, account_number)
+            .and_then(|doc| doc.sign(&self.key))
+            .and_then(|raw| raw.to_bytes())
+            .map_err(|e| format!("Failed to sign transaction: {}", e))
+    }
+
+    /// Account number and next sequence of the sender
+    async fn account(&self) -> Result<(u64, u64), String> {
+        let request = QueryAccountRequest {
+            address: self.sender.to_string(),
+        };
+        let value = self
+            .abci_query("/cosmos.auth.v1beta1.Query/Account", request.encode_to_vec())
+            .await?;
+        let account = QueryAccountResponse::decode(value.as_slice())
+            .map_err(|e| format!("Invalid account response: {}", e))?
+            .account
+            .ok_or_else(|| format!("Account {} not found", self.sender))?;
+
+        if account.type_url != BASE_ACCOUNT_TYPE {
+            return Err(format!("Unsupported account type {}", account.type_url));
+        }
+        let account = BaseAccount::decode(account.value.as_slice())
+            .map_err(|e| format!("Invalid account: {}", e))?;
+        Ok((account.account_number, account.sequence))
+    }
+
+    /// Gas used by `tx` in simulation
+    async fn simulate(&self, tx: Vec<u8>) -> Result<u64, String> {
+        #[allow(deprecated)]
+        let request = SimulateRequest {
+            tx: None,
+            tx_bytes: tx,
+        };
+        let value = self
+            .abci_query("/cosmos.tx.v1beta1.Service/Simulate", request.encode_to_vec())
+            .await?;
+        let response = SimulateResponse::decode(value.as_slice())
+            .map_err(|e| format!("Invalid simulate response: {}", e))?;
+
+        response
+            .gas_info
+            .map(|info| info.gas_used)
+            .ok_or_else(|| "Simulation returned no gas info".to_string())
+    }
+
+    /// Broadcast and return the tx hash once it passed CheckTx
+    async fn broadcast(&self, tx: &[u8]) -> Result<String, String> {
+        let result = self
+            .rpc("broadcast_tx_sync", json!({ "tx": BASE64.encode(tx) }))
+            .await?;
+
+        let code = result["code"].as_u64().unwrap_or(0);
+        if code != 0 {
+            return Err(format!("Transaction rejected ({}): {}", code, result["log"]));
+        }
+
+        Ok(hex::encode_upper(Sha256::digest(tx)))
+    }
+
+    /// Poll until the tx is included in a block
+    async fn wait_for_tx(&self, hash: &str) -> Result<(), String> {
+        let hash_bytes = hex::decode(hash).map_err(|e| format!("Invalid tx hash: {}", e))?;
+        let deadline = Instant::now() + self.config.confirm_timeout;
+
+        loop {
+            let params = json!({ "hash": BASE64.encode(&hash_bytes), "prove": false });
+            match self.rpc("tx", params).await {
+                Ok(result) => {
+                    let code = result["tx_result"]["code"].as_u64().unwrap_or(0);
+                    if code != 0 {
+                        return Err(format!(
+                            "Transaction {} failed ({}): {}",
+                            hash, code, result["tx_result"]["log"]
+                        ));
+                    }
+                    return Ok(());
+                }
+                Err(e) => tracing::debug!("Transaction {} not confirmed yet: {}", hash, e),
+            }
+
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "Transaction {} not confirmed after {:?}",
+                    hash, self.config.confirm_timeout
+                ));
+            }
+            tokio::time::sleep(self.config.poll_interval).await;
+        }
+    }
+
+    async fn abci_query(&self, path: &str, data: Vec<u8>) -> Result<Vec<u8>, String> {
+        let params = json!({
+            "path": path,
+            "data": hex::encode(data),
+            "height": "0",
+            "prove": false,
+        });
+        let result = self.rpc("abci_query", params).await?;
+        let response = &result["response"];
+
+        let code = response["code"].as_u64().unwrap_or(0);
+        if code != 0 {
+            return Err(format!("Query {} failed ({}): {}", path, code, response["log"]));
+        }
+
+        match response["value"].as_str() {
+            Some(value) => BASE64
+                .decode(value)
+                .map_err(|e| format!("Invalid query value: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn rpc(&self, method: &str, params: Value) -> Result<Value, String> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response: Value = self
+            .client
+            .post(&self.config.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| format!("RPC {} failed: {}", method, e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid RPC {} response: {}", method, e))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(format!("RPC {} error: {}", method, error));
         }
-        result
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| format!("RPC {} returned no result", method))
     }
 }
 
@@ -82,16 +371,119 @@ mod base64 {
 mod tests {
     use super::*;
     use crate::aggregate::{BatchAggregator, PoeItem};
+    use axum::{extract::State, routing::post, Json, Router};
+    use cosmrs::proto::cosmos::base::abci::v1beta1::GasInfo;
+    use cosmrs::proto::cosmos::tx::v1beta1::{SignDoc as ProtoSignDoc, TxRaw};
+    use k256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+    use std::sync::{Arc, Mutex};
     use uuid::Uuid;
 
-    #[tokio::test]
-    async fn test_submit_batch() {
-        let submitter = ChainSubmitter::new(
-            "http://localhost:26657".to_string(),
-            "contract_addr".to_string(),
-        );
+    const CHAIN_ID: &str = "test-chain";
+    const CONTRACT: &str = "wasm14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9s0phg4d";
+    const ACCOUNT_NUMBER: u64 = 7;
+    const SEQUENCE: u64 = 3;
+
+    /// Mock CometBFT node recording broadcast transactions
+    #[derive(Default)]
+    struct MockNode {
+        txs: Vec<Vec<u8>>,
+        tx_polls: usize,
+        reject: bool,
+    }
+
+    type Shared = Arc<Mutex<MockNode>>;
+
+    fn abci_value(value: Vec<u8>) -> Value {
+        json!({ "response": { "code": 0, "value": BASE64.encode(value) } })
+    }
+
+    async fn handle(State(node): State<Shared>, Json(request): Json<Value>) -> Json<Value> {
+        let params = &request["params"];
+        let mut node = node.lock().unwrap();
+
+        let result = match request["method"].as_str().unwrap() {
+            "abci_query" => match params["path"].as_str().unwrap() {
+                "/cosmos.auth.v1beta1.Query/Account" => {
+                    let account = BaseAccount {
+                        address: String::new(),
+                        pub_key: None,
+                        account_number: ACCOUNT_NUMBER,
+                        sequence: SEQUENCE,
+                    };
+                    abci_value(
+                        QueryAccountResponse {
+                            account: Some(cosmrs::proto::Any {
+                                type_url: BASE_ACCOUNT_TYPE.to_string(),
+                                value: account.encode_to_vec(),
+                            }),
+                        }
+                        .encode_to_vec(),
+                    )
+                }
+                "/cosmos.tx.v1beta1.Service/Simulate" => abci_value(
+                    SimulateResponse {
+                        gas_info: Some(GasInfo {
+                            gas_wanted: 0,
+                            gas_used: 100_000,
+                        }),
+                        result: None,
+                    }
+                    .encode_to_vec(),
+                ),
+                "/cosmwasm.wasm.v1.Query/SmartContractState" => abci_value(
+                    QuerySmartContractStateResponse {
+                        data: b"\"1500\"".to_vec(),
+                    }
+                    .encode_to_vec(),
+                ),
+                path => panic!("unexpected query {}", path),
+            },
+            "broadcast_tx_sync" => {
+                if node.reject {
+                    json!({ "code": 5, "log": "insufficient funds" })
+                } else {
+                    let tx = BASE64.decode(params["tx"].as_str().unwrap()).unwrap();
+                    node.txs.push(tx);
+                    json!({ "code": 0, "log": "" })
+                }
+            }
+            "tx" => {
+                node.tx_polls += 1;
+                // Not in a block on the first poll
+                if node.tx_polls == 1 {
+                    return Json(json!({
+                        "jsonrpc": "2.0",
+                        "id": 1,
+                        "error": { "code": -32603, "message": "tx not found" },
+                    }));
+                }
+                json!({ "height": "5", "tx_result": { "code": 0 } })
+            }
+            method => panic!("unexpected method {}", method),
+        };
+
+        Json(json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+    }
+
+    async fn mock_node() -> (String, Shared) {
+        let node = Shared::default();
+        let app = Router::new().route("/", post(handle)).with_state(node.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, node)
+    }
 
-        let aggregator = BatchAggregator::new("test-chain".to_string());
+    fn submitter(rpc_url: String) -> ChainSubmitter {
+        let mut config =
+            SubmitterConfig::new(rpc_url, CONTRACT.to_string(), CHAIN_ID.to_string());
+        config.poll_interval = Duration::from_millis(10);
+        config.confirm_timeout = Duration::from_secs(5);
+        ChainSubmitter::new(config, SigningKey::from_slice(&[5u8; 32]).unwrap()).unwrap()
+    }
+
+    fn signed_batch() -> PoeBatch {
+        let aggregator = BatchAggregator::new(CHAIN_ID.to_string());
         let items = vec![PoeItem::new(
             "node1".to_string(),
             Uuid::new_v4(),
@@ -101,9 +493,81 @@ mod tests {
             0.85,
         )];
 
-        let batch = aggregator.create_batch(1, items);
-        let result = submitter.submit_batch(&batch).await;
+        let mut batch = aggregator.create_batch(1, items);
+        batch.signers = vec!["s0".to_string()];
+        batch.sigs = vec![BASE64.encode([1u8; 64])];
+        batch
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch() {
+        let (url, node) = mock_node().await;
+        let submitter = submitter(url);
+        let batch = signed_batch();
+
+        let hash = submitter.submit_batch(&batch).await.unwrap();
+
+        let node = node.lock().unwrap();
+        assert_eq!(node.txs.len(), 1);
+        assert_eq!(node.tx_polls, 2);
+        assert_eq!(hash, hex::encode_upper(Sha256::digest(&node.txs[0])));
+
+        let tx = cosmrs::Tx::from_bytes(&node.txs[0]).unwrap();
+        let msg = MsgExecuteContract::from_any(&tx.body.messages[0]).unwrap();
+        assert_eq!(msg.contract.to_string(), CONTRACT);
+        assert_eq!(msg.sender, *submitter.sender());
+        let execute: Value = serde_json::from_slice(&msg.msg).unwrap();
+        assert_eq!(execute["submit_batch"]["epoch"], 1);
+        assert_eq!(execute["submit_batch"]["total_weight"], "850000");
+
+        // Simulated gas times the adjustment, priced at gas_price
+        assert_eq!(tx.auth_info.fee.gas_limit, 130_000);
+        assert_eq!(tx.auth_info.fee.amount[0].amount, 3250);
+        assert_eq!(tx.auth_info.signer_infos[0].sequence, SEQUENCE);
+
+        // Signed over the direct-mode sign doc for this chain and account
+        let raw = TxRaw::decode(node.txs[0].as_slice()).unwrap();
+        let sign_doc = ProtoSignDoc {
+            body_bytes: raw.body_bytes.clone(),
+            auth_info_bytes: raw.auth_info_bytes.clone(),
+            chain_id: CHAIN_ID.to_string(),
+            account_number: ACCOUNT_NUMBER,
+        };
+        let key = VerifyingKey::from_sec1_bytes(&submitter.key.public_key().to_bytes()).unwrap();
+        let sig = Signature::from_slice(&raw.signatures[0]).unwrap();
+        assert!(key.verify(&sign_doc.encode_to_vec(), &sig).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_claim_and_query() {
+        let (url, node) = mock_node().await;
+        let submitter = submitter(url);
+        let batch = signed_batch();
+
+        submitter.claim_reward(&batch, 0).await.unwrap();
+        assert!(submitter.claim_reward(&batch, 1).await.is_err());
+
+        let tx = cosmrs::Tx::from_bytes(&node.lock().unwrap().txs[0]).unwrap();
+        let msg = MsgExecuteContract::from_any(&tx.body.messages[0]).unwrap();
+        let execute: Value = serde_json::from_slice(&msg.msg).unwrap();
+        assert_eq!(execute["claim_reward"]["node"], "node1");
+        assert_eq!(execute["claim_reward"]["weight"], "850000");
+
+        assert_eq!(submitter.query_accrual("node1").await.unwrap(), 1500);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_and_mismatched_batches() {
+        let (url, node) = mock_node().await;
+        let submitter = submitter(url);
+
+        let mut batch = signed_batch();
+        batch.chain_id = "other-chain".to_string();
+        assert!(submitter.submit_batch(&batch).await.is_err());
 
-        assert!(result.is_ok());
+        node.lock().unwrap().reject = true;
+        let err = submitter.submit_batch(&signed_batch()).await.unwrap_err();
+        assert!(err.contains("insufficient funds"));
+        assert!(node.lock().unwrap().txs.is_empty());
     }
 }